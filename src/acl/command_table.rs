@@ -0,0 +1,338 @@
+/// 命令涉及的键在参数中的位置
+#[derive(Clone, Copy)]
+pub enum KeySpec {
+    /// 不涉及键
+    None,
+    /// 从 first 到 last（负数表示从末尾倒数）每隔 step 个参数为一个键
+    Range(usize, isize, usize),
+    /// numkeys 位于指定位置，其后紧跟 numkeys 个键（EVAL / EVALSHA）
+    NumKeys(usize),
+    /// 第一个参数为目标键，numkeys 位于指定位置，其后紧跟 numkeys 个键（ZUNIONSTORE / ZINTERSTORE / ZDIFFSTORE）
+    StoreNumKeys(usize),
+    /// STREAMS 之后前一半参数为键（XREAD / XREADGROUP）
+    Streams,
+}
+
+/// 命令涉及的频道在参数中的位置
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChannelSpec {
+    None,
+    /// 第一个参数为频道（PUBLISH）
+    First,
+    /// 所有参数均为频道（SUBSCRIBE）
+    All,
+    /// 所有参数均为频道模式（PSUBSCRIBE），只有与授权模式完全相同时才允许
+    Patterns,
+}
+
+/// 命令表项：命令名（子命令以 | 分隔）、所属类别、键位置、频道位置
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub categories: &'static [&'static str],
+    pub keys: KeySpec,
+    pub channels: ChannelSpec,
+}
+
+/// 所有命令类别
+pub const CATEGORIES: &[&str] = &[
+    "keyspace", "read", "write", "set", "sortedset", "list", "hash", "string", "bitmap", "hyperloglog",
+    "stream", "pubsub", "admin", "fast", "slow", "blocking", "dangerous", "connection", "transaction", "scripting",
+];
+
+const fn spec(name: &'static str, categories: &'static [&'static str], keys: KeySpec) -> CommandSpec {
+    CommandSpec { name, categories, keys, channels: ChannelSpec::None }
+}
+
+const fn pubsub(name: &'static str, categories: &'static [&'static str], channels: ChannelSpec) -> CommandSpec {
+    CommandSpec { name, categories, keys: KeySpec::None, channels }
+}
+
+const NONE: KeySpec = KeySpec::None;
+const FIRST: KeySpec = KeySpec::Range(1, 1, 1);
+const ALL: KeySpec = KeySpec::Range(1, -1, 1);
+
+/// 命令表，新增命令时需要同时在此登记，否则只有拥有 +@all 权限的用户可以执行
+pub const COMMANDS: &[CommandSpec] = &[
+    // 连接
+    spec("auth", &["fast", "connection"], NONE),
+    spec("hello", &["fast", "connection"], NONE),
+    spec("ping", &["fast", "connection"], NONE),
+    spec("echo", &["fast", "connection"], NONE),
+    spec("select", &["fast", "connection"], NONE),
+    spec("client", &["slow", "connection"], NONE),
+    // 键空间
+    spec("del", &["keyspace", "write", "slow"], ALL),
+    spec("exists", &["keyspace", "read", "fast"], ALL),
+    spec("expire", &["keyspace", "write", "fast"], FIRST),
+    spec("expireat", &["keyspace", "write", "fast"], FIRST),
+    spec("pexpire", &["keyspace", "write", "fast"], FIRST),
+    spec("pexpireat", &["keyspace", "write", "fast"], FIRST),
+    spec("dump", &["keyspace", "read", "slow"], FIRST),
+    spec("restore", &["keyspace", "write", "slow", "dangerous"], FIRST),
+    spec("persist", &["keyspace", "write", "fast"], FIRST),
+    spec("ttl", &["keyspace", "read", "fast"], FIRST),
+    spec("pttl", &["keyspace", "read", "fast"], FIRST),
+    spec("type", &["keyspace", "read", "fast"], FIRST),
+    spec("rename", &["keyspace", "write", "slow"], KeySpec::Range(1, 2, 1)),
+    spec("renamenx", &["keyspace", "write", "fast"], KeySpec::Range(1, 2, 1)),
+    spec("move", &["keyspace", "write", "fast"], FIRST),
+    spec("keys", &["keyspace", "read", "slow", "dangerous"], NONE),
+    spec("scan", &["keyspace", "read", "slow"], NONE),
+    spec("randomkey", &["keyspace", "read", "slow"], NONE),
+    spec("dbsize", &["keyspace", "read", "fast"], NONE),
+    spec("flushdb", &["keyspace", "write", "slow", "dangerous"], NONE),
+    spec("flushall", &["keyspace", "write", "slow", "dangerous"], NONE),
+    // 字符串
+    spec("get", &["read", "string", "fast"], FIRST),
+    spec("set", &["write", "string", "slow"], FIRST),
+    spec("setnx", &["write", "string", "fast"], FIRST),
+    spec("setex", &["write", "string", "slow"], FIRST),
+    spec("psetex", &["write", "string", "slow"], FIRST),
+    spec("getset", &["write", "string", "fast"], FIRST),
+    spec("getrange", &["read", "string", "slow"], FIRST),
+    spec("setrange", &["write", "string", "slow"], FIRST),
+    spec("strlen", &["read", "string", "fast"], FIRST),
+    spec("append", &["write", "string", "fast"], FIRST),
+    spec("mget", &["read", "string", "fast"], ALL),
+    spec("mset", &["write", "string", "slow"], KeySpec::Range(1, -1, 2)),
+    spec("msetnx", &["write", "string", "slow"], KeySpec::Range(1, -1, 2)),
+    spec("incr", &["write", "string", "fast"], FIRST),
+    spec("incrby", &["write", "string", "fast"], FIRST),
+    spec("incrbyfloat", &["write", "string", "fast"], FIRST),
+    spec("decr", &["write", "string", "fast"], FIRST),
+    spec("decrby", &["write", "string", "fast"], FIRST),
+    // 位图
+    spec("setbit", &["write", "bitmap", "slow"], FIRST),
+    spec("getbit", &["read", "bitmap", "fast"], FIRST),
+    spec("bitcount", &["read", "bitmap", "slow"], FIRST),
+    spec("bitop", &["write", "bitmap", "slow"], KeySpec::Range(2, -1, 1)),
+    // 哈希
+    spec("hset", &["write", "hash", "fast"], FIRST),
+    spec("hsetnx", &["write", "hash", "fast"], FIRST),
+    spec("hmset", &["write", "hash", "fast"], FIRST),
+    spec("hget", &["read", "hash", "fast"], FIRST),
+    spec("hmget", &["read", "hash", "fast"], FIRST),
+    spec("hdel", &["write", "hash", "fast"], FIRST),
+    spec("hexists", &["read", "hash", "fast"], FIRST),
+    spec("hstrlen", &["read", "hash", "fast"], FIRST),
+    spec("hlen", &["read", "hash", "fast"], FIRST),
+    spec("hgetall", &["read", "hash", "slow"], FIRST),
+    spec("hkeys", &["read", "hash", "slow"], FIRST),
+    spec("hvals", &["read", "hash", "slow"], FIRST),
+    spec("hscan", &["read", "hash", "slow"], FIRST),
+    spec("hincrby", &["write", "hash", "fast"], FIRST),
+    spec("hincrbyfloat", &["write", "hash", "fast"], FIRST),
+    // 列表
+    spec("lpush", &["write", "list", "fast"], FIRST),
+    spec("rpush", &["write", "list", "fast"], FIRST),
+    spec("lpushx", &["write", "list", "fast"], FIRST),
+    spec("rpushx", &["write", "list", "fast"], FIRST),
+    spec("lpop", &["write", "list", "fast"], FIRST),
+    spec("rpop", &["write", "list", "fast"], FIRST),
+    spec("llen", &["read", "list", "fast"], FIRST),
+    spec("lindex", &["read", "list", "slow"], FIRST),
+    spec("lrange", &["read", "list", "slow"], FIRST),
+    spec("lset", &["write", "list", "slow"], FIRST),
+    spec("ltrim", &["write", "list", "slow"], FIRST),
+    spec("lrem", &["write", "list", "slow"], FIRST),
+    spec("linsert", &["write", "list", "slow"], FIRST),
+    spec("lpos", &["read", "list", "slow"], FIRST),
+    spec("lmove", &["write", "list", "slow"], KeySpec::Range(1, 2, 1)),
+    spec("rpoplpush", &["write", "list", "slow"], KeySpec::Range(1, 2, 1)),
+    spec("lmpop", &["write", "list", "slow"], KeySpec::NumKeys(1)),
+    spec("blpop", &["write", "list", "slow", "blocking"], KeySpec::Range(1, -2, 1)),
+    spec("brpop", &["write", "list", "slow", "blocking"], KeySpec::Range(1, -2, 1)),
+    spec("blmove", &["write", "list", "slow", "blocking"], KeySpec::Range(1, 2, 1)),
+    spec("brpoplpush", &["write", "list", "slow", "blocking"], KeySpec::Range(1, 2, 1)),
+    spec("blmpop", &["write", "list", "slow", "blocking"], KeySpec::NumKeys(2)),
+    // 集合
+    spec("sadd", &["write", "set", "fast"], FIRST),
+    spec("srem", &["write", "set", "fast"], FIRST),
+    spec("scard", &["read", "set", "fast"], FIRST),
+    spec("sismember", &["read", "set", "fast"], FIRST),
+    spec("smembers", &["read", "set", "slow"], FIRST),
+    spec("srandmember", &["read", "set", "slow"], FIRST),
+    spec("spop", &["write", "set", "fast"], FIRST),
+    spec("smove", &["write", "set", "fast"], KeySpec::Range(1, 2, 1)),
+    spec("sscan", &["read", "set", "slow"], FIRST),
+    spec("sunion", &["read", "set", "slow"], ALL),
+    spec("sinter", &["read", "set", "slow"], ALL),
+    spec("sdiff", &["read", "set", "slow"], ALL),
+    spec("sunionstore", &["write", "set", "slow"], ALL),
+    spec("sinterstore", &["write", "set", "slow"], ALL),
+    spec("sdiffstore", &["write", "set", "slow"], ALL),
+    // 有序集合
+    spec("zadd", &["write", "sortedset", "fast"], FIRST),
+    spec("zincrby", &["write", "sortedset", "fast"], FIRST),
+    spec("zrem", &["write", "sortedset", "fast"], FIRST),
+    spec("zcard", &["read", "sortedset", "fast"], FIRST),
+    spec("zcount", &["read", "sortedset", "fast"], FIRST),
+    spec("zscore", &["read", "sortedset", "fast"], FIRST),
+    spec("zrank", &["read", "sortedset", "fast"], FIRST),
+    spec("zlexcount", &["read", "sortedset", "fast"], FIRST),
+    spec("zrange", &["read", "sortedset", "slow"], FIRST),
+    spec("zrevrange", &["read", "sortedset", "slow"], FIRST),
+    spec("zrangebyscore", &["read", "sortedset", "slow"], FIRST),
+    spec("zrevrangebyscore", &["read", "sortedset", "slow"], FIRST),
+    spec("zrangebylex", &["read", "sortedset", "slow"], FIRST),
+    spec("zrevrangebylex", &["read", "sortedset", "slow"], FIRST),
+    spec("zrangestore", &["write", "sortedset", "slow"], KeySpec::Range(1, 2, 1)),
+    spec("zrevrank", &["read", "sortedset", "fast"], FIRST),
+    spec("zremrangebyrank", &["write", "sortedset", "slow"], FIRST),
+    spec("zremrangebyscore", &["write", "sortedset", "slow"], FIRST),
+    spec("zremrangebylex", &["write", "sortedset", "slow"], FIRST),
+    spec("zmscore", &["read", "sortedset", "fast"], FIRST),
+    spec("zrandmember", &["read", "sortedset", "slow"], FIRST),
+    spec("zscan", &["read", "sortedset", "slow"], FIRST),
+    spec("zunion", &["read", "sortedset", "slow"], KeySpec::NumKeys(1)),
+    spec("zunionstore", &["write", "sortedset", "slow"], KeySpec::StoreNumKeys(2)),
+    spec("zinter", &["read", "sortedset", "slow"], KeySpec::NumKeys(1)),
+    spec("zinterstore", &["write", "sortedset", "slow"], KeySpec::StoreNumKeys(2)),
+    spec("zintercard", &["read", "sortedset", "slow"], KeySpec::NumKeys(1)),
+    spec("zdiff", &["read", "sortedset", "slow"], KeySpec::NumKeys(1)),
+    spec("zdiffstore", &["write", "sortedset", "slow"], KeySpec::StoreNumKeys(2)),
+    spec("zpopmin", &["write", "sortedset", "fast"], FIRST),
+    spec("zpopmax", &["write", "sortedset", "fast"], FIRST),
+    spec("zmpop", &["write", "sortedset", "slow"], KeySpec::NumKeys(1)),
+    spec("bzpopmin", &["write", "sortedset", "fast", "blocking"], KeySpec::Range(1, -2, 1)),
+    spec("bzpopmax", &["write", "sortedset", "fast", "blocking"], KeySpec::Range(1, -2, 1)),
+    spec("bzmpop", &["write", "sortedset", "slow", "blocking"], KeySpec::NumKeys(2)),
+    // HyperLogLog
+    spec("pfadd", &["write", "hyperloglog", "fast"], FIRST),
+    spec("pfcount", &["read", "hyperloglog", "slow"], ALL),
+    spec("pfmerge", &["write", "hyperloglog", "slow"], ALL),
+    // 流
+    spec("xadd", &["write", "stream", "fast"], FIRST),
+    spec("xlen", &["read", "stream", "fast"], FIRST),
+    spec("xrange", &["read", "stream", "slow"], FIRST),
+    spec("xrevrange", &["read", "stream", "slow"], FIRST),
+    spec("xdel", &["write", "stream", "fast"], FIRST),
+    spec("xtrim", &["write", "stream", "slow"], FIRST),
+    spec("xread", &["read", "stream", "slow", "blocking"], KeySpec::Streams),
+    spec("xreadgroup", &["write", "stream", "slow", "blocking"], KeySpec::Streams),
+    spec("xgroup", &["write", "stream", "slow"], KeySpec::Range(2, 2, 1)),
+    spec("xack", &["write", "stream", "fast"], FIRST),
+    spec("xpending", &["read", "stream", "slow"], FIRST),
+    spec("xclaim", &["write", "stream", "fast"], FIRST),
+    spec("xautoclaim", &["write", "stream", "fast"], FIRST),
+    // 发布订阅
+    pubsub("subscribe", &["pubsub", "slow"], ChannelSpec::All),
+    pubsub("unsubscribe", &["pubsub", "slow"], ChannelSpec::None),
+    pubsub("psubscribe", &["pubsub", "slow"], ChannelSpec::Patterns),
+    pubsub("punsubscribe", &["pubsub", "slow"], ChannelSpec::None),
+    pubsub("publish", &["pubsub", "fast"], ChannelSpec::First),
+    pubsub("pubsub", &["pubsub", "slow"], ChannelSpec::None),
+    // 事务
+    spec("multi", &["fast", "transaction"], NONE),
+    spec("exec", &["slow", "transaction"], NONE),
+    spec("discard", &["fast", "transaction"], NONE),
+    spec("watch", &["fast", "transaction"], ALL),
+    spec("unwatch", &["fast", "transaction"], NONE),
+    // 脚本
+    spec("eval", &["slow", "scripting"], KeySpec::NumKeys(2)),
+    spec("evalsha", &["slow", "scripting"], KeySpec::NumKeys(2)),
+    spec("script", &["slow", "scripting"], NONE),
+    // 服务器管理
+    spec("info", &["slow", "dangerous"], NONE),
+    spec("save", &["admin", "slow", "dangerous"], NONE),
+    spec("bgsave", &["admin", "slow", "dangerous"], NONE),
+    spec("lastsave", &["admin", "fast", "dangerous"], NONE),
+    spec("bgrewriteaof", &["admin", "slow", "dangerous"], NONE),
+    spec("replconf", &["admin", "slow", "dangerous"], NONE),
+    spec("psync", &["admin", "slow", "dangerous"], NONE),
+    spec("replicaof", &["admin", "slow", "dangerous"], NONE),
+    spec("slaveof", &["admin", "slow", "dangerous"], NONE),
+    spec("wait", &["slow", "connection"], NONE),
+    spec("config", &["admin", "slow", "dangerous"], NONE),
+    spec("debug", &["admin", "slow", "dangerous"], NONE),
+    spec("acl", &["admin", "slow", "dangerous"], NONE),
+    spec("acl|whoami", &["slow"], NONE),
+    spec("acl|cat", &["slow"], NONE),
+];
+
+/**
+ * 查找命令表项，优先匹配子命令
+ *
+ * @param name 命令名（小写）
+ * @param subcommand 第一个参数（小写），用于匹配子命令
+ */
+pub fn lookup(name: &str, subcommand: Option<&str>) -> Option<&'static CommandSpec> {
+    if let Some(subcommand) = subcommand {
+        let full_name = format!("{}|{}", name, subcommand);
+        if let Some(spec) = COMMANDS.iter().find(|spec| spec.name == full_name) {
+            return Some(spec);
+        }
+    }
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
+
+/// 命令是否存在（用于校验 ACL 规则）
+pub fn exists(name: &str) -> bool {
+    let command = name.split('|').next().unwrap_or_default();
+    COMMANDS.iter().any(|spec| spec.name == command)
+}
+
+/// 类别下的所有命令（不含子命令）
+pub fn commands_in_category(category: &str) -> Vec<&'static str> {
+    COMMANDS
+        .iter()
+        .filter(|spec| !spec.name.contains('|') && spec.categories.contains(&category))
+        .map(|spec| spec.name)
+        .collect()
+}
+
+/**
+ * 提取命令涉及的键
+ *
+ * @param spec 键位置
+ * @param args 完整的命令参数（含命令名）
+ */
+pub fn extract_keys<T: AsRef<[u8]>>(spec: KeySpec, args: &[T]) -> Vec<&T> {
+    match spec {
+        KeySpec::None => Vec::new(),
+        KeySpec::Range(first, last, step) => {
+            let last = if last < 0 { args.len() as isize + last } else { last };
+            if last < first as isize {
+                return Vec::new();
+            }
+            let last = (last as usize).min(args.len().saturating_sub(1));
+            (first..=last).step_by(step).filter_map(|index| args.get(index)).collect()
+        },
+        KeySpec::NumKeys(index) => {
+            let numkeys = args.get(index).and_then(parse_numkeys).unwrap_or(0);
+            args.iter().skip(index + 1).take(numkeys).collect()
+        },
+        KeySpec::StoreNumKeys(index) => {
+            let numkeys = args.get(index).and_then(parse_numkeys).unwrap_or(0);
+            args.get(1).into_iter().chain(args.iter().skip(index + 1).take(numkeys)).collect()
+        },
+        KeySpec::Streams => {
+            match args.iter().position(|arg| arg.as_ref().eq_ignore_ascii_case(b"STREAMS")) {
+                Some(position) => {
+                    let rest = &args[position + 1..];
+                    rest.iter().take(rest.len() / 2).collect()
+                },
+                None => Vec::new(),
+            }
+        },
+    }
+}
+
+/// 解析 numkeys 参数
+fn parse_numkeys(arg: &impl AsRef<[u8]>) -> Option<usize> {
+    std::str::from_utf8(arg.as_ref()).ok()?.parse().ok()
+}
+
+/**
+ * 提取命令涉及的频道
+ *
+ * @param spec 频道位置
+ * @param args 完整的命令参数（含命令名）
+ */
+pub fn extract_channels<T>(spec: ChannelSpec, args: &[T]) -> Vec<&T> {
+    match spec {
+        ChannelSpec::None => Vec::new(),
+        ChannelSpec::First => args.get(1).into_iter().collect(),
+        ChannelSpec::All | ChannelSpec::Patterns => args.iter().skip(1).collect(),
+    }
+}
@@ -0,0 +1,183 @@
+pub mod command_table;
+pub mod user;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Error;
+
+use crate::args::Args;
+
+pub use user::{Denied, User};
+
+/// 默认用户名，未指定用户名的 AUTH 与新连接都使用该用户
+pub const DEFAULT_USER: &str = "default";
+
+const NO_ACL_FILE: &str = "ERR This Redis instance is not configured to use an ACL file. You may want to specify users via the ACL SETUSER command and then issue a CONFIG REWRITE (assuming you have a Redis configuration file set) in order to store users in the Redis configuration.";
+
+/**
+ * ACL 用户管理
+ *
+ * 默认用户拥有全部权限，未配置 requirepass 时无需密码；
+ * 配置了 aclfile 时，用户列表可以通过 ACL SAVE / ACL LOAD 持久化到 ACL 文件
+ */
+pub struct Acl {
+    users: BTreeMap<String, User>,
+    file: Option<PathBuf>,
+    requirepass: Option<String>,
+}
+
+impl Acl {
+
+    pub fn new(args: &Args) -> Self {
+        let file = args.aclfile.as_ref().map(|aclfile| PathBuf::from(&args.dir).join(aclfile));
+        let mut acl = Acl {
+            users: BTreeMap::new(),
+            file,
+            requirepass: args.requirepass.clone(),
+        };
+        acl.users.insert(DEFAULT_USER.to_string(), acl.default_user());
+        acl
+    }
+
+    /// 默认用户：on ~* &* +@all，配置了 requirepass 时以其作为密码，否则为 nopass
+    fn default_user(&self) -> User {
+        let mut user = User::new(DEFAULT_USER);
+        let password = match &self.requirepass {
+            Some(requirepass) => format!(">{}", requirepass),
+            None => "nopass".to_string(),
+        };
+        for rule in ["on", password.as_str(), "allkeys", "allchannels", "allcommands"] {
+            let _ = user.apply_rule(rule);
+        }
+        user
+    }
+
+    /**
+     * 修改默认用户的密码（CONFIG SET requirepass）
+     *
+     * @param requirepass 新密码，为 None 时默认用户无需密码
+     */
+    pub fn set_requirepass(&mut self, requirepass: Option<String>) {
+        let rule = match &requirepass {
+            Some(requirepass) => format!(">{}", requirepass),
+            None => "nopass".to_string(),
+        };
+        self.requirepass = requirepass;
+        if let Some(user) = self.users.get_mut(DEFAULT_USER) {
+            let _ = user.apply_rule("resetpass");
+            let _ = user.apply_rule(&rule);
+        }
+    }
+
+    pub fn get_user(&self, name: &str) -> Option<&User> {
+        self.users.get(name)
+    }
+
+    /// 所有用户（按用户名排序）
+    pub fn users(&self) -> impl Iterator<Item = &User> {
+        self.users.values()
+    }
+
+    /**
+     * 创建或修改用户（ACL SETUSER）
+     *
+     * 规则全部合法时才会生效，任意一条规则出错则用户保持不变
+     *
+     * @param name 用户名
+     * @param rules 规则列表
+     */
+    pub fn set_user(&mut self, name: &str, rules: &[String]) -> Result<(), Error> {
+        let mut user = self.users.get(name).cloned().unwrap_or_else(|| User::new(name));
+        for rule in rules {
+            if let Err(reason) = user.apply_rule(rule) {
+                return Err(Error::msg(format!("ERR Error in ACL SETUSER modifier '{}': {}", rule, reason)));
+            }
+        }
+        self.users.insert(name.to_string(), user);
+        Ok(())
+    }
+
+    /**
+     * 删除用户（ACL DELUSER），默认用户不能被删除
+     *
+     * @return 实际删除的用户数量
+     */
+    pub fn del_users(&mut self, names: &[String]) -> Result<usize, Error> {
+        if names.iter().any(|name| name == DEFAULT_USER) {
+            return Err(Error::msg("ERR The 'default' user cannot be removed"));
+        }
+        Ok(names.iter().filter(|name| self.users.remove(name.as_str()).is_some()).count())
+    }
+
+    /// 校验用户名与密码，用户被禁用时校验失败
+    pub fn authenticate(&self, name: &str, password: &str) -> bool {
+        self.users.get(name).is_some_and(|user| user.check_password(password))
+    }
+
+    /// 新连接是否无需认证（默认用户启用且为 nopass）
+    pub fn default_user_nopass(&self) -> bool {
+        self.users.get(DEFAULT_USER).is_some_and(|user| user.check_password(""))
+    }
+
+    /// ACL 文件路径，未配置 aclfile 时为 None
+    pub fn file(&self) -> Option<&PathBuf> {
+        self.file.as_ref()
+    }
+
+    /**
+     * 从 ACL 文件加载用户（ACL LOAD 与服务启动）
+     *
+     * 文件中任意一行出错时不做任何修改；文件中没有定义默认用户时使用默认配置的默认用户
+     */
+    pub fn load(&mut self) -> Result<(), Error> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Err(Error::msg(NO_ACL_FILE)),
+        };
+        let content = std::fs::read_to_string(file)
+            .map_err(|e| Error::msg(format!("ERR Error loading ACL file {}: {}", file.display(), e)))?;
+
+        let mut users = BTreeMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 2 || parts[0] != "user" {
+                return Err(Error::msg(format!("ERR {}:{}: should start with user keyword", file.display(), index + 1)));
+            }
+            let mut user = User::new(parts[1]);
+            for rule in &parts[2..] {
+                if let Err(reason) = user.apply_rule(rule) {
+                    return Err(Error::msg(format!("ERR {}:{}: {}. ", file.display(), index + 1, reason)));
+                }
+            }
+            users.insert(parts[1].to_string(), user);
+        }
+        if !users.contains_key(DEFAULT_USER) {
+            users.insert(DEFAULT_USER.to_string(), self.default_user());
+        }
+        self.users = users;
+        Ok(())
+    }
+
+    /// 将所有用户写入 ACL 文件（ACL SAVE）
+    pub fn save(&self) -> Result<(), Error> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Err(Error::msg(NO_ACL_FILE)),
+        };
+        let mut content = String::new();
+        for user in self.users.values() {
+            content.push_str(&user.describe());
+            content.push('\n');
+        }
+        // 先写临时文件再重命名，避免写入过程中崩溃导致 ACL 文件损坏
+        let temp = file.with_extension("tmp");
+        std::fs::write(&temp, content)
+            .and_then(|_| std::fs::rename(&temp, file))
+            .map_err(|e| Error::msg(format!("ERR There was an error trying to save the ACLs. Please check the server logs for more information: {}", e)))
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::BTreeSet;
+
+use sha2::{Digest, Sha256};
+
+use crate::tools::pattern;
+
+use super::command_table::{self, ChannelSpec, CATEGORIES};
+
+/// 命令规则的作用对象
+#[derive(Clone, PartialEq)]
+enum RuleTarget {
+    All,
+    Category(String),
+    Command(String),
+}
+
+/// 命令规则，按添加顺序匹配，最后一条匹配的规则生效
+#[derive(Clone, PartialEq)]
+struct CommandRule {
+    allow: bool,
+    target: RuleTarget,
+}
+
+impl CommandRule {
+
+    fn matches(&self, name: &str, subcommand: Option<&str>, categories: &[&str]) -> bool {
+        match &self.target {
+            RuleTarget::All => true,
+            RuleTarget::Category(category) => categories.contains(&category.as_str()),
+            RuleTarget::Command(command) => match command.split_once('|') {
+                Some((command, sub)) => command == name && subcommand == Some(sub),
+                None => command == name,
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let sign = if self.allow { '+' } else { '-' };
+        match &self.target {
+            RuleTarget::All => format!("{}@all", sign),
+            RuleTarget::Category(category) => format!("{}@{}", sign, category),
+            RuleTarget::Command(command) => format!("{}{}", sign, command),
+        }
+    }
+}
+
+/// 权限检查失败的原因
+pub enum Denied {
+    Command(String),
+    Key,
+    Channel,
+}
+
+impl Denied {
+
+    /// 拒绝执行时回复给客户端的错误
+    pub fn to_error(&self, username: &str) -> String {
+        match self {
+            Denied::Command(command) => format!("NOPERM User {} has no permissions to run the '{}' command", username, command),
+            Denied::Key => "NOPERM No permissions to access a key".to_string(),
+            Denied::Channel => "NOPERM No permissions to access a channel".to_string(),
+        }
+    }
+}
+
+/**
+ * ACL 用户
+ *
+ * 新建的用户默认处于禁用状态，没有密码，也没有任何命令、键与频道的权限
+ */
+#[derive(Clone)]
+pub struct User {
+    name: String,
+    enabled: bool,
+    nopass: bool,
+    passwords: BTreeSet<String>,
+    commands: Vec<CommandRule>,
+    keys: Vec<String>,
+    channels: Vec<String>,
+}
+
+impl User {
+
+    pub fn new(name: &str) -> Self {
+        User {
+            name: name.to_string(),
+            enabled: false,
+            nopass: false,
+            passwords: BTreeSet::new(),
+            commands: Vec::new(),
+            keys: Vec::new(),
+            channels: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 密码的 SHA256 摘要
+    pub fn hash_password(password: &str) -> String {
+        format!("{:x}", Sha256::digest(password.as_bytes()))
+    }
+
+    /// 校验密码，nopass 用户接受任意密码
+    pub fn check_password(&self, password: &str) -> bool {
+        self.enabled && (self.nopass || self.passwords.contains(&Self::hash_password(password)))
+    }
+
+    /// 是否没有设置密码且允许任意密码登录
+    pub fn is_nopass(&self) -> bool {
+        self.nopass
+    }
+
+    /**
+     * 应用一条 ACL 规则（与 ACL SETUSER 的规则语法一致）
+     *
+     * @param rule 规则，如 on、>password、~pattern、+@read、-flushall
+     */
+    pub fn apply_rule(&mut self, rule: &str) -> Result<(), String> {
+        let lower = rule.to_lowercase();
+        match lower.as_str() {
+            "on" => self.enabled = true,
+            "off" => self.enabled = false,
+            "nopass" => {
+                self.nopass = true;
+                self.passwords.clear();
+            },
+            "resetpass" => {
+                self.nopass = false;
+                self.passwords.clear();
+            },
+            "allkeys" => self.keys = vec!["*".to_string()],
+            "resetkeys" => self.keys.clear(),
+            "allchannels" => self.channels = vec!["*".to_string()],
+            "resetchannels" => self.channels.clear(),
+            "allcommands" => self.apply_command_rule(true, RuleTarget::All),
+            "nocommands" => self.apply_command_rule(false, RuleTarget::All),
+            "reset" => {
+                self.enabled = false;
+                self.nopass = false;
+                self.passwords.clear();
+                self.commands.clear();
+                self.keys.clear();
+                self.channels.clear();
+            },
+            _ => return self.apply_prefixed_rule(rule),
+        }
+        Ok(())
+    }
+
+    fn apply_prefixed_rule(&mut self, rule: &str) -> Result<(), String> {
+        let mut chars = rule.chars();
+        let (prefix, value) = match chars.next() {
+            Some(prefix) => (prefix, chars.as_str()),
+            None => return Err("Syntax error".to_string()),
+        };
+        match prefix {
+            '>' => {
+                self.passwords.insert(Self::hash_password(value));
+                self.nopass = false;
+            },
+            '<' => {
+                if !self.passwords.remove(&Self::hash_password(value)) {
+                    return Err("no such password".to_string());
+                }
+            },
+            '#' => {
+                if value.len() != 64 || !value.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)) {
+                    return Err("The password hash must be exactly 64 characters and contain only lowercase hexadecimal characters".to_string());
+                }
+                self.passwords.insert(value.to_string());
+                self.nopass = false;
+            },
+            '!' => {
+                if !self.passwords.remove(value) {
+                    return Err("no such password".to_string());
+                }
+            },
+            '~' if value == "*" => self.keys = vec![value.to_string()],
+            '~' => {
+                if !self.keys.iter().any(|key| key == "*" || key == value) {
+                    self.keys.push(value.to_string());
+                }
+            },
+            '&' if value == "*" => self.channels = vec![value.to_string()],
+            '&' => {
+                if !self.channels.iter().any(|channel| channel == "*" || channel == value) {
+                    self.channels.push(value.to_string());
+                }
+            },
+            '+' | '-' => {
+                let allow = prefix == '+';
+                let value = value.to_lowercase();
+                let target = match value.strip_prefix('@') {
+                    Some("all") => RuleTarget::All,
+                    Some(category) if CATEGORIES.contains(&category) => RuleTarget::Category(category.to_string()),
+                    None if command_table::exists(&value) => RuleTarget::Command(value),
+                    _ => return Err("Unknown command or category name in ACL".to_string()),
+                };
+                self.apply_command_rule(allow, target);
+            },
+            _ => return Err("Syntax error".to_string()),
+        }
+        Ok(())
+    }
+
+    fn apply_command_rule(&mut self, allow: bool, target: RuleTarget) {
+        // +@all / -@all 覆盖之前的所有命令规则
+        if target == RuleTarget::All {
+            self.commands.clear();
+        }
+        let rule = CommandRule { allow, target };
+        self.commands.retain(|existing| existing.target != rule.target);
+        self.commands.push(rule);
+    }
+
+    /**
+     * 检查用户能否执行命令
+     *
+     * @param args 完整的命令参数（含命令名）
+     */
+    pub fn check<T: AsRef<[u8]>>(&self, args: &[T]) -> Result<(), Denied> {
+        let name = match args.first() {
+            Some(name) => String::from_utf8_lossy(name.as_ref()).to_lowercase(),
+            None => return Ok(()),
+        };
+        let subcommand = args.get(1).map(|sub| String::from_utf8_lossy(sub.as_ref()).to_lowercase());
+        let spec = command_table::lookup(&name, subcommand.as_deref());
+        let categories = spec.map(|spec| spec.categories).unwrap_or(&[]);
+
+        let allowed = self.commands.iter().fold(false, |allowed, rule| {
+            if rule.matches(&name, subcommand.as_deref(), categories) { rule.allow } else { allowed }
+        });
+        if !allowed {
+            let command = match spec {
+                Some(spec) if spec.name.contains('|') => spec.name.to_string(),
+                _ => name,
+            };
+            return Err(Denied::Command(command));
+        }
+
+        let spec = match spec {
+            Some(spec) => spec,
+            None => return Ok(()),
+        };
+        if !self.keys.iter().any(|key| key == "*") {
+            let keys = command_table::extract_keys(spec.keys, args);
+            if keys.iter().any(|key| !self.keys.iter().any(|pattern| pattern::is_match(key, pattern))) {
+                return Err(Denied::Key);
+            }
+        }
+        if !self.channels.iter().any(|channel| channel == "*") {
+            let channels = command_table::extract_channels(spec.channels, args);
+            let permitted = |channel: &T| {
+                if spec.channels == ChannelSpec::Patterns {
+                    self.channels.iter().any(|pattern| pattern.as_bytes() == channel.as_ref())
+                } else {
+                    self.channels.iter().any(|pattern| pattern::is_match(channel, pattern))
+                }
+            };
+            if !channels.into_iter().all(permitted) {
+                return Err(Denied::Channel);
+            }
+        }
+        Ok(())
+    }
+
+    /// 用户的标志位（ACL GETUSER 的 flags 字段）
+    pub fn flags(&self) -> Vec<&'static str> {
+        let mut flags = vec![if self.enabled { "on" } else { "off" }];
+        if self.nopass {
+            flags.push("nopass");
+        }
+        flags
+    }
+
+    /// 密码摘要列表
+    pub fn passwords(&self) -> Vec<String> {
+        self.passwords.iter().cloned().collect()
+    }
+
+    /// 命令规则的文本形式
+    pub fn describe_commands(&self) -> String {
+        if self.commands.is_empty() {
+            return "-@all".to_string();
+        }
+        self.commands.iter().map(CommandRule::describe).collect::<Vec<_>>().join(" ")
+    }
+
+    /// 键模式的文本形式
+    pub fn describe_keys(&self) -> String {
+        self.keys.iter().map(|key| format!("~{}", key)).collect::<Vec<_>>().join(" ")
+    }
+
+    /// 频道模式的文本形式
+    pub fn describe_channels(&self) -> String {
+        self.channels.iter().map(|channel| format!("&{}", channel)).collect::<Vec<_>>().join(" ")
+    }
+
+    /**
+     * 用户的完整描述（ACL LIST 与 ACL 文件的格式）
+     *
+     * 例如：user default on nopass ~* &* +@all
+     */
+    pub fn describe(&self) -> String {
+        let mut parts = vec!["user".to_string(), self.name.clone()];
+        parts.extend(self.flags().into_iter().map(str::to_string));
+        parts.extend(self.passwords.iter().map(|hash| format!("#{}", hash)));
+        if !self.keys.is_empty() {
+            parts.push(self.describe_keys());
+        }
+        if self.channels.is_empty() {
+            parts.push("resetchannels".to_string());
+        } else {
+            parts.push(self.describe_channels());
+        }
+        parts.push(self.describe_commands());
+        parts.join(" ")
+    }
+}
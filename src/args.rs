@@ -2,8 +2,11 @@ use std::str::FromStr;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use clap::Parser;
 
+use crate::store::memory::EvictionPolicy;
+
 #[derive(Parser)]
 #[command(version, author, about, long_about = None)]
 pub struct Args {
@@ -16,6 +19,10 @@ pub struct Args {
     #[arg(long)] 
     pub requirepass: Option<String>,
 
+    /// ACL 文件路径（相对于 dir），用于 ACL SAVE / ACL LOAD 持久化用户
+    #[arg(long)]
+    pub aclfile: Option<String>,
+
     /// 绑定地址
     #[arg(short, long, default_value = "0.0.0.0")]
     pub bind: String,
@@ -24,6 +31,10 @@ pub struct Args {
     #[arg(default_value = "data/dump.rdb")] 
     pub dbfilename: String,
 
+    /// 启动时从该 Redis RDB 文件加载数据（代替 dbfilename），用于从 Redis 迁移
+    #[arg(long = "import-rdb")]
+    pub import_rdb: Option<String>,
+
     /// 安装路径
     #[arg(default_value = "./")] 
     pub dir: String,
@@ -66,6 +77,18 @@ pub struct Args {
     #[arg(long, default_value = "always")] 
     pub appendfsync: String,
 
+    /// AOF 重写时是否以 RDB 快照作为文件开头（混合格式）
+    #[arg(long = "aof-use-rdb-preamble", default_value = "yes")]
+    pub aof_use_rdb_preamble: String,
+
+    /// AOF 自动重写 - 文件相对上次重写后增长的百分比，0 表示关闭自动重写
+    #[arg(long = "auto-aof-rewrite-percentage", default_value = "100")]
+    pub auto_aof_rewrite_percentage: u64,
+
+    /// AOF 自动重写 - 触发自动重写的最小文件大小
+    #[arg(long = "auto-aof-rewrite-min-size", default_value = "64mb", value_parser = parse_memory)]
+    pub auto_aof_rewrite_min_size: usize,
+
     /// 最大客户端连接数
     #[arg(long, default_value = "0")]
     pub maxclients: usize,
@@ -74,13 +97,73 @@ pub struct Args {
     #[arg(long, default_value = "8080")]
     pub webport: u16,
 
-    /// Web 管理界面用户名
+    /// Web 管理界面用户名，配置了 ACL 用户时改用 ACL 用户登录
     #[arg(long, default_value = "admin")]
     pub webuser: String,
 
     /// Web 管理界面密码
     #[arg(long, default_value = "admin")]
     pub webpass: String,
+
+    /// 单个批量字符串的最大长度（字节），超过时视为协议错误并断开连接
+    #[arg(long = "proto-max-bulk-len", default_value = "536870912", value_parser = parse_memory)]
+    pub proto_max_bulk_len: usize,
+
+    /// 最大内存（字节），0 表示不限制
+    #[arg(long, default_value = "0", value_parser = parse_memory)]
+    pub maxmemory: usize,
+
+    /// 内存淘汰策略
+    #[arg(long = "maxmemory-policy", default_value = "noeviction", value_parser = EvictionPolicy::from_str)]
+    pub maxmemory_policy: EvictionPolicy,
+
+    /// 内存淘汰时每个数据库的采样数量
+    #[arg(long = "maxmemory-samples", default_value = "5")]
+    pub maxmemory_samples: usize,
+
+    /// 从节点是否拒绝客户端的写命令
+    #[arg(long = "replica-read-only", default_value = "yes")]
+    pub replica_read_only: String,
+
+    /// 主节点至少需要的正常从节点数量，不足时拒绝写命令，0 表示关闭
+    #[arg(long = "min-replicas-to-write", default_value = "0")]
+    pub min_replicas_to_write: usize,
+
+    /// 正常从节点的最大延迟（秒）：超过该时间没有确认偏移量的从节点不计入 min-replicas-to-write
+    #[arg(long = "min-replicas-max-lag", default_value = "10")]
+    pub min_replicas_max_lag: u64,
+
+    /// 复制积压缓冲区大小，从节点断线重连时缺失的数据在缓冲区内则只补发缺失部分
+    #[arg(long = "repl-backlog-size", default_value = "1mb", value_parser = parse_memory)]
+    pub repl_backlog_size: usize,
+
+    /// 脚本的最长执行时间（毫秒）：超过后其他客户端收到 BUSY 错误，只读脚本可以通过 SCRIPT KILL 终止，0 表示不限制
+    #[arg(long = "lua-time-limit", default_value = "5000")]
+    pub lua_time_limit: i64,
+
+    /// 以哨兵模式运行：只监控主从节点并在主节点下线时自动故障转移，不提供数据服务
+    #[arg(long)]
+    pub sentinel: bool,
+
+    /// 哨兵监控的主节点，格式为 "<name> <host> <port> <quorum>"，可以指定多次
+    #[arg(long = "sentinel-monitor")]
+    pub sentinel_monitor: Vec<String>,
+
+    /// 哨兵判定实例主观下线（没有有效回复）的时间，单位毫秒
+    #[arg(long = "sentinel-down-after-milliseconds", default_value = "30000")]
+    pub sentinel_down_after_milliseconds: u64,
+
+    /// 哨兵故障转移超时，单位毫秒；同一主节点两次故障转移之间至少间隔两倍该时间
+    #[arg(long = "sentinel-failover-timeout", default_value = "180000")]
+    pub sentinel_failover_timeout: u64,
+
+    /// 哨兵连接主从节点时使用的 ACL 用户名，不指定时以 sentinel-auth-pass 认证默认用户
+    #[arg(long = "sentinel-auth-user")]
+    pub sentinel_auth_user: Option<String>,
+
+    /// 哨兵连接主从节点时发送 AUTH 使用的密码，主从节点配置了 requirepass 或 ACL 用户时需要
+    #[arg(long = "sentinel-auth-pass")]
+    pub sentinel_auth_pass: Option<String>,
 }
 
 impl Args {
@@ -93,12 +176,46 @@ impl Args {
         self.replicaof.is_some()
     }
 
+    /**
+     * 主节点地址
+     *
+     * 同时支持 "host port"（配置文件中与 Redis 相同的写法）与 "host:port"
+     *
+     * @return 没有配置 replicaof 时返回 None，格式错误时返回错误
+     */
+    pub fn master_addr(&self) -> Result<Option<(String, u16)>, String> {
+        let replicaof = match &self.replicaof {
+            Some(replicaof) => replicaof.trim(),
+            None => return Ok(None),
+        };
+        let (host, port) = match replicaof.split_once(char::is_whitespace) {
+            Some((host, port)) => (host, port.trim()),
+            None => replicaof.rsplit_once(':').ok_or_else(|| format!("Invalid replicaof address: {}", replicaof))?,
+        };
+        let port = port.parse().map_err(|_| format!("Invalid replicaof port: {}", port))?;
+        Ok(Some((host.to_string(), port)))
+    }
+
+    /// AOF 文件路径
+    pub fn aof_path(&self) -> PathBuf {
+        PathBuf::from(&self.dir).join(&self.appendfilename)
+    }
+
+    /// 不带任何命令行参数时的默认配置
+    pub fn default_args() -> Self {
+        Args::parse_from(["rudis-server"])
+    }
+
     /// 从配置文件中加载配置
     pub fn load() -> Self {
         let mut args = Args::parse();
         if let Ok(config_map) = parse_config_file(&args.config) {
             args.merge_config(config_map);
         }
+        // 与 Redis 相同，哨兵默认监听 26379，避免与同机的数据节点冲突
+        if args.sentinel && args.port == "6379" {
+            args.port = "26379".to_string();
+        }
         args
     }
     
@@ -126,6 +243,13 @@ impl Args {
             }
         }
         
+        // import-rdb
+        if self.import_rdb.is_none() {
+            if let Some(import_rdb) = config_map.get("import-rdb") {
+                self.import_rdb = Some(import_rdb.clone());
+            }
+        }
+
         // dir
         if self.dir == "./" { 
             if let Some(dir) = config_map.get("dir") {
@@ -136,10 +260,9 @@ impl Args {
         // save - 只有在命令行未设置时才使用配置文件的值
         if self.save.is_empty() {
             if let Some(save_rules) = config_map.get("save") {
-                self.save = save_rules
-                    .split_whitespace()
-                    .filter_map(|s| SaveRule::from_str(s).ok())
-                    .collect();
+                if let Ok(rules) = parse_save_rules(save_rules) {
+                    self.save = rules;
+                }
             }
         }
         
@@ -203,6 +326,31 @@ impl Args {
             }
         }
         
+        // aof-use-rdb-preamble
+        if self.aof_use_rdb_preamble == "yes" {
+            if let Some(preamble) = config_map.get("aof-use-rdb-preamble") {
+                self.aof_use_rdb_preamble = preamble.clone();
+            }
+        }
+
+        // auto-aof-rewrite-percentage
+        if self.auto_aof_rewrite_percentage == 100 {
+            if let Some(percentage) = config_map.get("auto-aof-rewrite-percentage") {
+                if let Ok(percentage) = percentage.parse() {
+                    self.auto_aof_rewrite_percentage = percentage;
+                }
+            }
+        }
+
+        // auto-aof-rewrite-min-size
+        if self.auto_aof_rewrite_min_size == 64 * 1024 * 1024 {
+            if let Some(min_size) = config_map.get("auto-aof-rewrite-min-size") {
+                if let Ok(min_size) = parse_memory(min_size) {
+                    self.auto_aof_rewrite_min_size = min_size;
+                }
+            }
+        }
+
         // maxclients
         if self.maxclients == 0 { 
             if let Some(maxclients) = config_map.get("maxclients") {
@@ -221,6 +369,13 @@ impl Args {
             }
         }
 
+        // aclfile
+        if self.aclfile.is_none() {
+            if let Some(aclfile) = config_map.get("aclfile") {
+                self.aclfile = Some(aclfile.clone());
+            }
+        }
+
         // webuser
         if self.webuser == "admin" {
             if let Some(user) = config_map.get("webuser") {
@@ -234,6 +389,125 @@ impl Args {
                 self.webpass = pass.clone();
             }
         }
+
+        // proto-max-bulk-len
+        if self.proto_max_bulk_len == crate::frame::DEFAULT_MAX_BULK_LEN {
+            if let Some(len) = config_map.get("proto-max-bulk-len") {
+                if let Ok(len) = parse_memory(len) {
+                    self.proto_max_bulk_len = len;
+                }
+            }
+        }
+
+        // maxmemory
+        if self.maxmemory == 0 {
+            if let Some(maxmemory) = config_map.get("maxmemory") {
+                if let Ok(maxmemory) = parse_memory(maxmemory) {
+                    self.maxmemory = maxmemory;
+                }
+            }
+        }
+
+        // maxmemory-policy
+        if self.maxmemory_policy == EvictionPolicy::NoEviction {
+            if let Some(policy) = config_map.get("maxmemory-policy") {
+                if let Ok(policy) = policy.parse() {
+                    self.maxmemory_policy = policy;
+                }
+            }
+        }
+
+        // maxmemory-samples
+        if self.maxmemory_samples == 5 {
+            if let Some(samples) = config_map.get("maxmemory-samples") {
+                if let Ok(samples) = samples.parse() {
+                    self.maxmemory_samples = samples;
+                }
+            }
+        }
+
+        // replica-read-only
+        if self.replica_read_only == "yes" {
+            if let Some(read_only) = config_map.get("replica-read-only") {
+                self.replica_read_only = read_only.clone();
+            }
+        }
+
+        // min-replicas-to-write
+        if self.min_replicas_to_write == 0 {
+            if let Some(count) = config_map.get("min-replicas-to-write") {
+                if let Ok(count) = count.parse() {
+                    self.min_replicas_to_write = count;
+                }
+            }
+        }
+
+        // min-replicas-max-lag
+        if self.min_replicas_max_lag == 10 {
+            if let Some(lag) = config_map.get("min-replicas-max-lag") {
+                if let Ok(lag) = lag.parse() {
+                    self.min_replicas_max_lag = lag;
+                }
+            }
+        }
+
+        // repl-backlog-size
+        if self.repl_backlog_size == 1024 * 1024 {
+            if let Some(size) = config_map.get("repl-backlog-size") {
+                if let Ok(size) = parse_memory(size) {
+                    self.repl_backlog_size = size;
+                }
+            }
+        }
+
+        // lua-time-limit
+        if self.lua_time_limit == 5000 {
+            if let Some(limit) = config_map.get("lua-time-limit") {
+                if let Ok(limit) = limit.parse() {
+                    self.lua_time_limit = limit;
+                }
+            }
+        }
+
+        // sentinel
+        if !self.sentinel {
+            self.sentinel = config_map.get("sentinel").is_some_and(|sentinel| sentinel == "yes");
+        }
+
+        // sentinel-monitor（配置文件中只能配置一个主节点，多个主节点通过命令行指定）
+        if self.sentinel_monitor.is_empty() {
+            if let Some(monitor) = config_map.get("sentinel-monitor") {
+                self.sentinel_monitor.push(monitor.clone());
+            }
+        }
+
+        // sentinel-down-after-milliseconds
+        if self.sentinel_down_after_milliseconds == 30000 {
+            if let Some(millis) = config_map.get("sentinel-down-after-milliseconds") {
+                if let Ok(millis) = millis.parse() {
+                    self.sentinel_down_after_milliseconds = millis;
+                }
+            }
+        }
+
+        // sentinel-failover-timeout
+        if self.sentinel_failover_timeout == 180000 {
+            if let Some(millis) = config_map.get("sentinel-failover-timeout") {
+                if let Ok(millis) = millis.parse() {
+                    self.sentinel_failover_timeout = millis;
+                }
+            }
+        }
+
+        // sentinel-auth-user
+        if self.sentinel_auth_user.is_none() {
+            self.sentinel_auth_user = config_map.get("sentinel-auth-user").cloned();
+        }
+
+        // sentinel-auth-pass
+        if self.sentinel_auth_pass.is_none() {
+            self.sentinel_auth_pass = config_map.get("sentinel-auth-pass").cloned();
+        }
     }
 }
 
@@ -257,6 +531,45 @@ impl FromStr for SaveRule {
     }
 }
 
+/**
+ * 解析保存规则
+ *
+ * 支持 Redis 配置文件的格式 "900 1 300 10"，也支持命令行的格式 "900,1 300,10"；
+ * 空字符串（或 ""）表示关闭 RDB 自动保存
+ */
+pub fn parse_save_rules(value: &str) -> Result<Vec<SaveRule>, String> {
+    let value = value.trim().trim_matches('"');
+    if value.contains(',') {
+        return value.split_whitespace().map(SaveRule::from_str).collect();
+    }
+    let numbers = value
+        .split_whitespace()
+        .map(|number| number.parse::<u64>().map_err(|_| "Invalid save parameters".to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !numbers.len().is_multiple_of(2) {
+        return Err("Invalid save parameters".to_string());
+    }
+    Ok(numbers.chunks(2).map(|pair| SaveRule { seconds: pair[0], changes: pair[1] }).collect())
+}
+
+/// 解析内存大小，支持 Redis 配置文件中的单位（如 512mb、1gb、100k）
+pub fn parse_memory(value: &str) -> Result<usize, String> {
+    let value = value.trim().to_lowercase();
+    let units: [(&str, usize); 8] = [
+        ("kb", 1024), ("mb", 1024 * 1024), ("gb", 1024 * 1024 * 1024),
+        ("k", 1000), ("m", 1000 * 1000), ("g", 1000 * 1000 * 1000),
+        ("b", 1), ("", 1),
+    ];
+    for (suffix, multiplier) in units {
+        if let Some(number) = value.strip_suffix(suffix) {
+            if let Ok(number) = number.parse::<usize>() {
+                return Ok(number * multiplier);
+            }
+        }
+    }
+    Err(format!("Invalid memory value: {}", value))
+}
+
 fn parse_config_file(filename: &str) -> Result<HashMap<String, String>, std::io::Error> {
     let file = File::open(filename)?;
     let reader = BufReader::new(file);
@@ -1,29 +1,44 @@
 use anyhow::Error;
 
-use crate::{frame::Frame, server::Handler};
+use crate::{acl::DEFAULT_USER, frame::Frame, server::Handler};
 
+/// AUTH [username] password
 pub struct Auth {
-    requirepass: String,
+    username: Option<String>,
+    password: String,
 }
 
 impl Auth {
     
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let requirepass = frame.get_arg(1);
+        let args = frame.get_args_from_index(1);
 
-        if requirepass.is_none() {
-            return Err(Error::msg(
+        let (username, password) = match args.as_slice() {
+            [password] => (None, password.clone()),
+            [username, password] => (Some(username.clone()), password.clone()),
+            _ => return Err(Error::msg(
                 "ERR wrong number of arguments for 'auth' command",
-            ));
-        }
+            )),
+        };
 
-        Ok(Auth {
-            requirepass: requirepass.unwrap().to_string(),
-        })
+        Ok(Auth { username, password })
     }
 
     pub fn apply(self, handler:&mut Handler) -> Result<Frame, Error> {
-        match handler.login(&self.requirepass) {
+        // 只提供密码时以默认用户登录，默认用户无需密码时与 Redis 一样提示配置错误
+        let username = match self.username {
+            Some(username) => username,
+            None => {
+                let nopass = handler.get_state().acl.read().unwrap()
+                    .get_user(DEFAULT_USER)
+                    .is_some_and(|user| user.is_nopass());
+                if nopass {
+                    return Ok(Frame::Error("ERR AUTH <password> called without any password configured for the default user. Are you sure your configuration is correct?".to_string()));
+                }
+                DEFAULT_USER.to_string()
+            }
+        };
+        match handler.login(&username, &self.password) {
             Ok(_) => Ok(Frame::Ok),
             Err(e) => {
                 Ok(Frame::Error(e.to_string()))
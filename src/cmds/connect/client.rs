@@ -1,37 +1,54 @@
 use anyhow::Error;
 
-use crate::frame::Frame;
+use crate::{frame::Frame, server::Handler};
 
 pub struct Client {
-    subcommand: String
+    subcommand: String,
+    args: Vec<String>,
 }
 
 impl Client {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
         let args = frame.get_args();
-        
+
         if args.len() < 2 {
             return Err(Error::msg("ERR wrong number of arguments for 'client' command"));
         }
 
         let subcommand = args[1].to_uppercase();
-        // let args: Vec<String> = args.iter().skip(2).map(|s| s.to_string()).collect();
+        let args: Vec<String> = args.iter().skip(2).map(|s| s.to_string()).collect();
         Ok(Client {
             subcommand,
-            // args,
+            args,
         })
     }
 
-    pub fn apply(self) -> Result<Frame, Error> {
+    pub fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
         match self.subcommand.as_str() {
             "SETINFO" => {
                 // For SETINFO, we just acknowledge the command without doing anything
                 // In a production Redis server, this would track client library info
                 Ok(Frame::Ok)
             },
+            "SETNAME" => {
+                if self.args.len() != 1 {
+                    return Ok(Frame::Error("ERR wrong number of arguments for 'client|setname' command".to_string()));
+                }
+                match handler.set_client_name(self.args[0].clone()) {
+                    Ok(_) => Ok(Frame::Ok),
+                    Err(e) => Ok(Frame::Error(e.to_string())),
+                }
+            },
+            "GETNAME" => {
+                match handler.get_session().get_name() {
+                    Some(name) => Ok(Frame::BulkString(name.clone().into())),
+                    None => Ok(Frame::Null),
+                }
+            },
+            "ID" => Ok(Frame::Integer(handler.get_session().get_id() as i64)),
             _ => {
                 Ok(Frame::Error(format!("ERR unknown subcommand '{}'", self.subcommand)))
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -19,6 +19,6 @@ impl Echo {
     }
 
     pub fn apply(self) -> Result<Frame, Error> {
-        Ok(Frame::BulkString(self.str))
+        Ok(Frame::BulkString(self.str.into()))
     }
 }
\ No newline at end of file
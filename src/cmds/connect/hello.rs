@@ -0,0 +1,94 @@
+use anyhow::Error;
+
+use crate::{frame::{Frame, Protocol}, server::Handler};
+
+/// HELLO 命令：协商协议版本，可同时完成认证与设置连接名称
+///
+/// HELLO [protover [AUTH username password] [SETNAME clientname]]
+pub struct Hello {
+    protocol: Option<Protocol>,
+    auth: Option<(String, String)>,
+    client_name: Option<String>,
+}
+
+impl Hello {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        let mut hello = Hello {
+            protocol: None,
+            auth: None,
+            client_name: None,
+        };
+
+        if args.len() < 2 {
+            return Ok(hello);
+        }
+
+        let version = match args[1].parse::<i64>() {
+            Ok(version) => version,
+            Err(_) => return Err(Error::msg("ERR Protocol version is not an integer or out of range")),
+        };
+
+        hello.protocol = match Protocol::from_version(version) {
+            Some(protocol) => Some(protocol),
+            None => return Err(Error::msg("NOPROTO unsupported protocol version")),
+        };
+
+        let mut index = 2;
+        while index < args.len() {
+            let remaining = args.len() - index - 1;
+            match args[index].to_uppercase().as_str() {
+                "AUTH" if remaining >= 2 => {
+                    hello.auth = Some((args[index + 1].clone(), args[index + 2].clone()));
+                    index += 3;
+                },
+                "SETNAME" if remaining >= 1 => {
+                    hello.client_name = Some(args[index + 1].clone());
+                    index += 2;
+                },
+                _ => {
+                    return Err(Error::msg(format!("ERR Syntax error in HELLO option '{}'", args[index])));
+                }
+            }
+        }
+
+        Ok(hello)
+    }
+
+    pub fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+
+        if let Some((username, password)) = &self.auth {
+            if let Err(e) = handler.login(username, password) {
+                return Ok(Frame::Error(e.to_string()));
+            }
+        }
+
+        if !handler.get_session().get_certification() {
+            return Ok(Frame::Error("NOAUTH HELLO must be called with the client already authenticated, otherwise the HELLO <proto> AUTH <user> <pass> option can be used to authenticate the client and select the RESP protocol version at the same time".to_string()));
+        }
+
+        if let Some(name) = self.client_name {
+            if let Err(e) = handler.set_client_name(name) {
+                return Ok(Frame::Error(e.to_string()));
+            }
+        }
+
+        let session = handler.get_session();
+        if let Some(protocol) = self.protocol {
+            session.connection.set_protocol(protocol);
+        }
+
+        let role = if handler.get_state().is_replica() { "replica" } else { "master" };
+        let field = |name: &str| Frame::BulkString(name.to_string().into());
+        Ok(Frame::Map(vec![
+            (field("server"), field("redis")),
+            (field("version"), field(env!("CARGO_PKG_VERSION"))),
+            (field("proto"), Frame::Integer(session.connection.get_protocol().version() as i64)),
+            (field("id"), Frame::Integer(session.get_id() as i64)),
+            (field("mode"), field("standalone")),
+            (field("role"), field(role)),
+            (field("modules"), Frame::Array(Vec::new())),
+        ]))
+    }
+}
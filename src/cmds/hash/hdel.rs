@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Hdel {
-    key: String,
-    fields: Vec<String>,
+    key: Vec<u8>,
+    fields: Vec<Vec<u8>>,
 }
 
 impl Hdel {
@@ -15,8 +15,8 @@ impl Hdel {
             return Err(Error::msg("ERR wrong number of arguments for 'hdel' command"));
         }
 
-        let key = args[1].to_string();
-        let fields = args[2..].iter().map(|arg| arg.to_string()).collect();
+        let key = frame.get_arg_bytes(1).unwrap();
+        let fields = frame.get_args_bytes_from_index(2);
 
         Ok(Hdel {
             key,
@@ -25,6 +25,12 @@ impl Hdel {
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 字段都不存在时直接返回，不修改键
+        if let Some(Structure::Hash(hash)) = db.get(&self.key) {
+            if !self.fields.iter().any(|field| hash.contains_key(field)) {
+                return Ok(Frame::Integer(0));
+            }
+        }
         match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
@@ -2,23 +2,23 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Hget {
-    key: String,
-    field: String,
+    key: Vec<u8>,
+    field: Vec<u8>,
 }
 
 impl Hget {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
-        let field = frame.get_arg(2);
+        let key = frame.get_arg_bytes(1);
+        let field = frame.get_arg_bytes(2);
 
         if key.is_none() || field.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'hget' command"));
         }
 
-        let final_key = key.unwrap().to_string(); // 键
-        let final_field = field.unwrap().to_string(); // 字段
+        let final_key = key.unwrap(); // 键
+        let final_field = field.unwrap(); // 字段
 
         Ok(Hget {
             key: final_key,
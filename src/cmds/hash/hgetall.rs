@@ -2,20 +2,20 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Hgetall {
-    key: String,
+    key: Vec<u8>,
 }
 
 impl Hgetall {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
 
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'hgetall' command"));
         }
 
-        let final_key = key.unwrap().to_string(); // 键
+        let final_key = key.unwrap(); // 键
 
         Ok(Hgetall {
             key: final_key,
@@ -29,10 +29,9 @@ impl Hgetall {
                     Structure::Hash(hash) => {
                         let mut result = Vec::new();
                         for (field, value) in hash.iter() {
-                            result.push(Frame::BulkString(field.clone()));
-                            result.push(Frame::BulkString(value.clone()));
+                            result.push((Frame::BulkString(field.clone()), Frame::BulkString(value.clone())));
                         }
-                        Ok(Frame::Array(result))
+                        Ok(Frame::Map(result))
                     },
                     _ => {
                         let f = "ERR Operation against a key holding the wrong kind of value";
@@ -40,7 +39,7 @@ impl Hgetall {
                     }
                 }
             },
-            None => Ok(Frame::Null),
+            None => Ok(Frame::Map(Vec::new())),
         }
     }
 }
\ No newline at end of file
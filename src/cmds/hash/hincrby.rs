@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Hincrby {
-    key: String,
-    field: String,
+    key: Vec<u8>,
+    field: Vec<u8>,
     increment: i64,
 }
 
@@ -14,8 +14,8 @@ impl Hincrby {
             return Err(Error::msg("ERR wrong number of arguments for 'hincrby' command"));
         }
         
-        let key = args[1].to_string();
-        let field = args[2].to_string();
+        let key = frame.get_arg_bytes(1).unwrap();
+        let field = frame.get_arg_bytes(2).unwrap();
         let increment = args[3].parse::<i64>().map_err(|_| {
             Error::msg("ERR value is not an integer or out of range")
         })?;
@@ -29,7 +29,7 @@ impl Hincrby {
                 match structure {
                     Structure::Hash(hash) => {
                         // 获取字段的当前值，如果字段不存在则默认为"0"
-                        let current_value = hash.get(&self.field).cloned().unwrap_or_else(|| "0".to_string());
+                        let current_value = hash.get(&self.field).map(|value| String::from_utf8_lossy(value).into_owned()).unwrap_or_else(|| "0".to_string());
                         
                         // 尝试将当前值解析为整数
                         match current_value.parse::<i64>() {
@@ -38,7 +38,7 @@ impl Hincrby {
                                 let new_value = num + self.increment;
                                 
                                 // 更新哈希表中的值
-                                hash.insert(self.field, new_value.to_string());
+                                hash.insert(self.field, new_value.to_string().into_bytes());
                                 
                                 // 返回新值
                                 Ok(Frame::Integer(new_value))
@@ -61,7 +61,7 @@ impl Hincrby {
                 // 键不存在，创建新的哈希表
                 let mut hash = std::collections::HashMap::new();
                 let new_value = self.increment;
-                hash.insert(self.field, new_value.to_string());
+                hash.insert(self.field, new_value.to_string().into_bytes());
                 db.insert(self.key.clone(), Structure::Hash(hash));
                 
                 // 返回新值
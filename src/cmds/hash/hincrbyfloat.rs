@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct HincrbyFloat {
-    key: String,
-    field: String,
+    key: Vec<u8>,
+    field: Vec<u8>,
     increment: f64,
 }
 
@@ -14,8 +14,8 @@ impl HincrbyFloat {
             return Err(Error::msg("ERR wrong number of arguments for 'hincrbyfloat' command"));
         }
         
-        let key = args[1].to_string();
-        let field = args[2].to_string();
+        let key = frame.get_arg_bytes(1).unwrap();
+        let field = frame.get_arg_bytes(2).unwrap();
         let increment = args[3].parse::<f64>().map_err(|_| {
             Error::msg("ERR value is not a valid float")
         })?;
@@ -29,7 +29,7 @@ impl HincrbyFloat {
                 match structure {
                     Structure::Hash(hash) => {
                         // 获取字段的当前值，如果字段不存在则默认为"0"
-                        let current_value = hash.get(&self.field).cloned().unwrap_or_else(|| "0".to_string());
+                        let current_value = hash.get(&self.field).map(|value| String::from_utf8_lossy(value).into_owned()).unwrap_or_else(|| "0".to_string());
                         
                         // 尝试将当前值解析为浮点数
                         match current_value.parse::<f64>() {
@@ -38,10 +38,10 @@ impl HincrbyFloat {
                                 let new_value = num + self.increment;
                                 
                                 // 更新哈希表中的值
-                                hash.insert(self.field, new_value.to_string());
+                                hash.insert(self.field, new_value.to_string().into_bytes());
                                 
                                 // 返回新值
-                                Ok(Frame::BulkString(new_value.to_string()))
+                                Ok(Frame::BulkString(new_value.to_string().into()))
                             },
                             Err(_) => {
                                 // 如果当前值不是浮点数，返回错误
@@ -61,11 +61,11 @@ impl HincrbyFloat {
                 // 键不存在，创建新的哈希表
                 let mut hash = std::collections::HashMap::new();
                 let new_value = self.increment;
-                hash.insert(self.field, new_value.to_string());
+                hash.insert(self.field, new_value.to_string().into_bytes());
                 db.insert(self.key.clone(), Structure::Hash(hash));
                 
                 // 返回新值
-                Ok(Frame::BulkString(new_value.to_string()))
+                Ok(Frame::BulkString(new_value.to_string().into()))
             }
         }
     }
@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Hmget {
-    key: String,
-    fields: Vec<String>,
+    key: Vec<u8>,
+    fields: Vec<Vec<u8>>,
 }
 
 impl Hmget {
@@ -14,8 +14,8 @@ impl Hmget {
             return Err(Error::msg("ERR wrong number of arguments for 'hmget' command"));
         }
 
-        let key = args[1].to_string();
-        let fields = args[2..].iter().map(|arg| arg.to_string()).collect();
+        let key = frame.get_arg_bytes(1).unwrap();
+        let fields = frame.get_args_bytes_from_index(2);
 
         Ok(Hmget {
             key,
@@ -4,21 +4,21 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Hmset {
-    key: String,
-    fields: HashMap<String, String>,
+    key: Vec<u8>,
+    fields: HashMap<Vec<u8>, Vec<u8>>,
 }
 
 impl Hmset {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'hmset' command"));
         }
 
-        let args = frame.get_args();
+        let args = frame.get_args_bytes_from_index(0);
 
         if args.len() % 2 != 0 {
             return Err(Error::msg("ERR wrong number of arguments for 'hmset' command"));
@@ -27,13 +27,13 @@ impl Hmset {
         let mut fields = HashMap::new();
 
         for i in (2..args.len()).step_by(2) {
-            let field = args[i].to_string();
-            let value = args[i + 1].to_string();
+            let field = args[i].clone();
+            let value = args[i + 1].clone();
             fields.insert(field, value);
         }
 
         Ok(Hmset {
-            key: key.unwrap().to_string(),
+            key: key.unwrap(),
             fields,
         })
     }
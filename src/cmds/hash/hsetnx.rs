@@ -4,26 +4,26 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Hsetnx {
-    key: String,
-    field: String,
-    value: String,
+    key: Vec<u8>,
+    field: Vec<u8>,
+    value: Vec<u8>,
 }
 
 impl Hsetnx {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
-        let field = frame.get_arg(2);
-        let value = frame.get_arg(3);
+        let key = frame.get_arg_bytes(1);
+        let field = frame.get_arg_bytes(2);
+        let value = frame.get_arg_bytes(3);
 
         if key.is_none() || field.is_none() || value.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'hsetnx' command"));
         }
 
-        let final_key = key.unwrap().to_string(); 
-        let final_field = field.unwrap().to_string(); 
-        let final_value = value.unwrap().to_string();
+        let final_key = key.unwrap(); 
+        let final_field = field.unwrap(); 
+        let final_value = value.unwrap();
 
         Ok(Hsetnx {
             key: final_key,
@@ -33,6 +33,12 @@ impl Hsetnx {
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 字段已经存在时不修改键
+        if let Some(Structure::Hash(hash)) = db.get(&self.key) {
+            if hash.contains_key(&self.field) {
+                return Ok(Frame::Integer(0));
+            }
+        }
         match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
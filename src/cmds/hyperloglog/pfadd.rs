@@ -14,8 +14,8 @@ use crate::store::hyperloglog::HyperLogLog;
  * - 0: 如果没有寄存器被更新
  */
 pub struct Pfadd {
-    key: String,
-    elements: Vec<String>,
+    key: Vec<u8>,
+    elements: Vec<Vec<u8>>,
 }
 
 impl Pfadd {
@@ -33,8 +33,8 @@ impl Pfadd {
             return Err(Error::msg("ERR wrong number of arguments for 'pfadd' command"));
         }
 
-        let key = args[1].to_string();
-        let elements = args.iter().skip(2).map(|s| s.to_string()).collect();
+        let key = frame.get_arg_bytes(1).unwrap();
+        let elements = frame.get_args_bytes_from_index(2);
 
         Ok(Pfadd { key, elements })
     }
@@ -49,9 +49,16 @@ impl Pfadd {
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
         // 检查 key 是否存在
         let key_existed = db.records.contains_key(&self.key);
+
+        // 没有寄存器需要更新时不修改已有的 HyperLogLog
+        if let Some(Structure::HyperLogLog(hll)) = db.get(&self.key) {
+            if !self.elements.iter().any(|element| hll.would_update(element)) {
+                return Ok(Frame::Integer(0));
+            }
+        }
         
         // 获取或创建 HyperLogLog
-        let hll = match db.records.get_mut(&self.key) {
+        let hll = match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::HyperLogLog(hll) => hll,
@@ -65,7 +72,7 @@ impl Pfadd {
                 // key 不存在，创建新的 HyperLogLog
                 let new_hll = HyperLogLog::new();
                 db.insert(self.key.clone(), Structure::HyperLogLog(new_hll));
-                match db.records.get_mut(&self.key) {
+                match db.get_mut(&self.key) {
                     Some(Structure::HyperLogLog(hll)) => hll,
                     _ => {
                         return Ok(Frame::Error("ERR Failed to create HyperLogLog".to_string()));
@@ -15,7 +15,7 @@ use crate::store::hyperloglog::HyperLogLog;
  * - key 不存在: 返回 0
  */
 pub struct Pfcount {
-    keys: Vec<String>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Pfcount {
@@ -33,7 +33,7 @@ impl Pfcount {
             return Err(Error::msg("ERR wrong number of arguments for 'pfcount' command"));
         }
 
-        let keys = args.iter().skip(1).map(|s| s.to_string()).collect();
+        let keys = frame.get_args_bytes_from_index(1);
 
         Ok(Pfcount { keys })
     }
@@ -71,14 +71,14 @@ impl Pfcount {
             let mut has_valid_hll = false;
 
             for key in &self.keys {
-                match db.records.get(key.as_str()) {
+                match db.records.get(key) {
                     Some(Structure::HyperLogLog(hll)) => {
                         merged_hll.merge(hll);
                         has_valid_hll = true;
                     },
                     Some(_) => {
                         // 如果某个 key 不是 HyperLogLog 类型，返回错误
-                        let f = format!("ERR Operation against a key holding the wrong kind of value: {}", key);
+                        let f = format!("ERR Operation against a key holding the wrong kind of value: {}", String::from_utf8_lossy(key));
                         return Ok(Frame::Error(f));
                     },
                     None => {
@@ -19,8 +19,8 @@ use crate::store::hyperloglog::HyperLogLog;
  * - 如果源 key 不是 HyperLogLog 类型，返回错误
  */
 pub struct Pfmerge {
-    destination: String,
-    source_keys: Vec<String>,
+    destination: Vec<u8>,
+    source_keys: Vec<Vec<u8>>,
 }
 
 impl Pfmerge {
@@ -38,8 +38,8 @@ impl Pfmerge {
             return Err(Error::msg("ERR wrong number of arguments for 'pfmerge' command"));
         }
 
-        let destination = args[1].to_string();
-        let source_keys = args.iter().skip(2).map(|s| s.to_string()).collect();
+        let destination = frame.get_arg_bytes(1).unwrap();
+        let source_keys = frame.get_args_bytes_from_index(2);
 
         Ok(Pfmerge { destination, source_keys })
     }
@@ -56,13 +56,13 @@ impl Pfmerge {
         let mut source_hlls = Vec::new();
         
         for key in &self.source_keys {
-            match db.records.get(key.as_str()) {
+            match db.records.get(key) {
                 Some(Structure::HyperLogLog(source_hll)) => {
                     source_hlls.push(source_hll.clone());
                 },
                 Some(_) => {
                     // 如果源 key 不是 HyperLogLog 类型，返回错误
-                    let f = format!("ERR Operation against a key holding the wrong kind of value: {}", key);
+                    let f = format!("ERR Operation against a key holding the wrong kind of value: {}", String::from_utf8_lossy(key));
                     return Ok(Frame::Error(f));
                 },
                 None => {
@@ -72,7 +72,7 @@ impl Pfmerge {
         }
 
         // 获取或创建目标 HyperLogLog
-        let dest_hll = match db.records.get_mut(&self.destination) {
+        let dest_hll = match db.get_mut(&self.destination) {
             Some(Structure::HyperLogLog(hll)) => hll,
             Some(_) => {
                 let f = "ERR Operation against a key holding the wrong kind of value";
@@ -82,7 +82,7 @@ impl Pfmerge {
                 // 目标 key 不存在，创建新的 HyperLogLog
                 let new_hll = HyperLogLog::new();
                 db.insert(self.destination.clone(), Structure::HyperLogLog(new_hll));
-                match db.records.get_mut(&self.destination) {
+                match db.get_mut(&self.destination) {
                     Some(Structure::HyperLogLog(hll)) => hll,
                     _ => {
                         return Ok(Frame::Error("ERR Failed to create HyperLogLog".to_string()));
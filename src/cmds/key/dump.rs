@@ -0,0 +1,30 @@
+use anyhow::Error;
+
+use crate::{frame::Frame, persistence::dump::dump_value, store::db::Db};
+
+/**
+ * DUMP 命令：将键的值序列化，可通过 RESTORE 还原
+ *
+ * 语法: DUMP key
+ */
+pub struct Dump {
+    key: Vec<u8>,
+}
+
+impl Dump {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 2 {
+            return Err(Error::msg("ERR wrong number of arguments for 'dump' command"));
+        }
+        Ok(Dump { key: frame.get_arg_bytes(1).unwrap_or_default() })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        match db.get(&self.key) {
+            Some(value) => Ok(Frame::BulkString(dump_value(value)?)),
+            None => Ok(Frame::Null),
+        }
+    }
+}
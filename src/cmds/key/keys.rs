@@ -2,13 +2,13 @@ use anyhow::Error;
 use crate::{store::db::Db, frame::Frame};
 
 pub struct Keys {
-    pattern: String,
+    pattern: Vec<u8>,
 }
 
 impl Keys {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let args = frame.get_args_from_index(1);
+        let args = frame.get_args_bytes_from_index(1);
         if args.len() != 1 {
             return Err(Error::msg("KEYS command requires exactly one argument"));
         }
@@ -17,7 +17,7 @@ impl Keys {
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
         let keys = db.keys(&self.pattern);
-        let results: Vec<Frame> = keys.into_iter().map(|key| Frame::BulkString(key)).collect();
+        let results: Vec<Frame> = keys.into_iter().map(Frame::BulkString).collect();
         Ok(Frame::Array(results))
     }
 }
\ No newline at end of file
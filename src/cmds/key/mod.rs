@@ -13,4 +13,6 @@ pub mod keys;
 pub mod pexpireat;
 pub mod pexpire;
 pub mod r#move;
-pub mod scan;
\ No newline at end of file
+pub mod scan;
+pub mod dump;
+pub mod restore;
\ No newline at end of file
@@ -1,8 +1,10 @@
 use anyhow::Error;
-use crate::{frame::Frame, server::Handler};
+use tokio::sync::mpsc::Sender;
+
+use crate::{command::Command, frame::Frame, server::Handler, store::db::DatabaseMessage};
 
 pub struct Move {
-    key: String,
+    key: Vec<u8>,
     db_index: usize,
 }
 
@@ -14,7 +16,7 @@ impl Move {
             return Err(Error::msg("ERR wrong number of arguments for 'move' command"));
         }
         
-        let key = args[1].to_string();
+        let key = frame.get_arg_bytes(1).unwrap();
         let db_index = match args[2].parse::<usize>() {
             Ok(num) => num,
             Err(_) => {
@@ -25,7 +27,7 @@ impl Move {
         Ok(Move { key, db_index })
     }
 
-    pub fn get_key(&self) -> &String {
+    pub fn get_key(&self) -> &Vec<u8> {
         &self.key
     }
 
@@ -102,80 +104,24 @@ impl Move {
             return Ok(Frame::Integer(0));
         }
         
-        // 从当前数据库获取键值
-        let (get_tx, get_rx) = tokio::sync::oneshot::channel();
-        let get_message = crate::store::db::DatabaseMessage::Command { 
-            sender: get_tx, 
-            command: crate::command::Command::Get(crate::cmds::string::get::Get { key: key.clone() })
-        };
-        
-        if current_db_sender.send(get_message).await.is_err() {
-            return Ok(Frame::Error("Failed to communicate with current database".to_string()));
-        }
-        
-        let get_result = match get_rx.await {
-            Ok(frame) => frame,
-            Err(_) => return Ok(Frame::Error("Failed to get value from current database".to_string())),
-        };
-        
-        // 获取键值和类型
-        let (value, structure_type) = match get_result {
-            Frame::BulkString(value) => {
-                // 获取键的类型以确定其结构
-                let (type_tx, type_rx) = tokio::sync::oneshot::channel();
-                let type_message = crate::store::db::DatabaseMessage::Command { 
-                    sender: type_tx, 
-                    command: crate::command::Command::Type(crate::cmds::key::r#type::Type { key: key.clone() })
-                };
-                
-                if current_db_sender.send(type_message).await.is_err() {
-                    return Ok(Frame::Error("Failed to communicate with current database".to_string()));
-                }
-                
-                let type_result = match type_rx.await {
-                    Ok(frame) => frame,
-                    Err(_) => return Ok(Frame::Error("Failed to get type from current database".to_string())),
-                };
-                
-                let structure_type = match type_result {
-                    Frame::SimpleString(type_str) => type_str,
-                    _ => return Ok(Frame::Error("Unexpected type response from current database".to_string())),
-                };
-                
-                (value, structure_type)
-            },
-            Frame::Null => return Ok(Frame::Integer(0)), // 键不存在
-            _ => return Ok(Frame::Error("Unexpected value type".to_string())),
-        };
-        
-        // 在目标数据库设置键值
-        let structure = match structure_type.as_str() {
-            "string" => crate::store::db::Structure::String(value),
-            _ => return Ok(Frame::Error("Unsupported value type for MOVE command".to_string())),
+        // 用 DUMP 与 PTTL 取出任意类型的值及其过期时间，在目标数据库中 RESTORE
+        let payload = match Self::query(&current_db_sender, &[b"DUMP", &key]).await {
+            Some(Frame::BulkString(payload)) => payload,
+            Some(Frame::Null) => return Ok(Frame::Integer(0)), // 键不存在
+            _ => return Ok(Frame::Error("Failed to get value from current database".to_string())),
         };
-        
-        let (set_tx, set_rx) = tokio::sync::oneshot::channel();
-        let set_message = crate::store::db::DatabaseMessage::Command { 
-            sender: set_tx, 
-            command: crate::command::Command::Set(crate::cmds::string::set::Set { 
-                key: key.clone(), 
-                val: match &structure {
-                    crate::store::db::Structure::String(s) => s.clone(),
-                    _ => return Ok(Frame::Error("Unsupported value type for MOVE command".to_string())),
-                },
-                ttl: None,
-            })
+        let ttl = match Self::query(&current_db_sender, &[b"PTTL", &key]).await {
+            Some(Frame::Integer(ttl)) if ttl > 0 => ttl,
+            Some(Frame::Integer(-1)) => 0,
+            Some(Frame::Integer(_)) => return Ok(Frame::Integer(0)), // 键已经过期
+            _ => return Ok(Frame::Error("Failed to get ttl from current database".to_string())),
         };
-        
-        if target_db_sender.send(set_message).await.is_err() {
-            return Ok(Frame::Error("Failed to communicate with target database".to_string()));
+        match Self::query(&target_db_sender, &[b"RESTORE", &key, ttl.to_string().as_bytes(), &payload]).await {
+            Some(Frame::Ok) => {},
+            Some(Frame::Error(e)) if e.starts_with("BUSYKEY") => return Ok(Frame::Integer(0)),
+            _ => return Ok(Frame::Error("Failed to set value in target database".to_string())),
         }
-        
-        let _set_result = match set_rx.await {
-            Ok(frame) => frame,
-            Err(_) => return Ok(Frame::Error("Failed to set value in target database".to_string())),
-        };
-        
+
         // 从当前数据库删除键
         let (del_tx, del_rx) = tokio::sync::oneshot::channel();
         let del_message = crate::store::db::DatabaseMessage::Command { 
@@ -197,4 +143,19 @@ impl Move {
             _ => Ok(Frame::Integer(0)),
         }
     }
-}
\ No newline at end of file
+
+    /**
+     * 在指定数据库中执行命令
+     *
+     * @param sender 数据库的发送者
+     * @param args 命令的完整参数
+     * @return 数据库不可用时返回 None
+     */
+    async fn query(sender: &Sender<DatabaseMessage>, args: &[&[u8]]) -> Option<Frame> {
+        let frame = Frame::Array(args.iter().map(|arg| Frame::BulkString(arg.to_vec())).collect());
+        let command = Command::parse_from_frame(frame).ok()?;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        sender.send(DatabaseMessage::Command { sender: tx, command }).await.ok()?;
+        rx.await.ok()
+    }
+}
@@ -1,21 +1,21 @@
 use anyhow::Error;
 use crate::{store::db::Db, frame::Frame};
 pub struct Renamenx {
-    old_key: String,
-    new_key: String,
+    old_key: Vec<u8>,
+    new_key: Vec<u8>,
 }
 
 impl Renamenx {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let old_key = frame.get_arg(1);
-        let new_key = frame.get_arg(2);
+        let old_key = frame.get_arg_bytes(1);
+        let new_key = frame.get_arg_bytes(2);
 
         if old_key.is_none() || new_key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'renamenx' command"));
         }
 
-        let old_key_str = old_key.unwrap().to_string();
-        let new_key_str = new_key.unwrap().to_string();
+        let old_key_str = old_key.unwrap();
+        let new_key_str = new_key.unwrap();
 
         Ok(Renamenx {
             old_key: old_key_str,
@@ -0,0 +1,88 @@
+use anyhow::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{frame::Frame, persistence::dump::restore_value, store::db::Db};
+
+/**
+ * RESTORE 命令：使用 DUMP 生成的序列化值创建键
+ *
+ * 语法: RESTORE key ttl serialized-value [REPLACE] [ABSTTL] [IDLETIME seconds] [FREQ frequency]
+ *
+ * ttl 为 0 时不设置过期时间；指定 ABSTTL 时 ttl 为毫秒级的 Unix 时间戳。
+ * IDLETIME 与 FREQ 只做参数校验，键的访问信息在创建时重新记录
+ */
+pub struct Restore {
+    key: Vec<u8>,
+    ttl: u64,
+    payload: Vec<u8>,
+    replace: bool,
+    absttl: bool,
+}
+
+impl Restore {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'restore' command"));
+        }
+        let ttl = match args[2].parse::<i64>() {
+            Ok(ttl) if ttl >= 0 => ttl as u64,
+            Ok(_) => return Err(Error::msg("ERR Invalid TTL value, must be >= 0")),
+            Err(_) => return Err(Error::msg("ERR value is not an integer or out of range")),
+        };
+        let mut restore = Restore {
+            key: frame.get_arg_bytes(1).unwrap_or_default(),
+            ttl,
+            payload: frame.get_arg_bytes(3).unwrap_or_default(),
+            replace: false,
+            absttl: false,
+        };
+
+        let mut index = 4;
+        while index < args.len() {
+            match args[index].to_uppercase().as_str() {
+                "REPLACE" => restore.replace = true,
+                "ABSTTL" => restore.absttl = true,
+                "IDLETIME" | "FREQ" if index + 1 < args.len() => {
+                    if args[index + 1].parse::<u64>().is_err() {
+                        return Err(Error::msg(format!("ERR Invalid {} value, must be >= 0", args[index].to_uppercase())));
+                    }
+                    index += 1;
+                },
+                _ => return Err(Error::msg("ERR syntax error")),
+            }
+            index += 1;
+        }
+        Ok(restore)
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        if !self.replace && db.exists(&self.key) {
+            return Ok(Frame::Error("BUSYKEY Target key name already exists.".to_string()));
+        }
+        let value = match restore_value(&self.payload) {
+            Ok(value) => value,
+            Err(e) => return Ok(Frame::Error(e.to_string())),
+        };
+
+        let ttl = if self.absttl && self.ttl > 0 {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_millis() as u64;
+            // 已经过期的键不再创建
+            if self.ttl <= now {
+                db.remove(&self.key);
+                return Ok(Frame::Ok);
+            }
+            self.ttl - now
+        } else {
+            self.ttl
+        };
+
+        db.remove(&self.key);
+        db.insert(self.key.clone(), value);
+        if ttl > 0 {
+            db.expire(self.key, ttl);
+        }
+        Ok(Frame::Ok)
+    }
+}
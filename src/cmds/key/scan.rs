@@ -3,13 +3,14 @@ use crate::{store::db::Db, frame::Frame, tools::pattern};
 
 pub struct Scan {
     cursor: u64,
-    pattern: Option<String>,
+    pattern: Option<Vec<u8>>,
     count: Option<u64>,
 }
 
 impl Scan {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
         let args = frame.get_args_from_index(1);
+        let raw_args = frame.get_args_bytes_from_index(1);
         if args.is_empty() {
             return Err(Error::msg("SCAN command requires at least one argument"));
         }
@@ -26,7 +27,7 @@ impl Scan {
                 if i + 1 >= args.len() {
                     return Err(Error::msg("MATCH option requires an argument"));
                 }
-                pattern = Some(args[i + 1].clone());
+                pattern = Some(raw_args[i + 1].clone());
                 i += 2;
             } else if arg == "COUNT" {
                 if i + 1 >= args.len() {
@@ -44,12 +45,12 @@ impl Scan {
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
         // 默认匹配模式为 "*"
-        let pattern = self.pattern.unwrap_or_else(|| "*".to_string());
+        let pattern = self.pattern.unwrap_or_else(|| b"*".to_vec());
         // 默认返回数量为 10
         let count = self.count.unwrap_or(10) as usize;
 
         // 获取所有匹配的键
-        let matched_keys: Vec<String> = db.records.keys()
+        let matched_keys: Vec<Vec<u8>> = db.records.keys()
             .filter(|key| pattern::is_match(key, &pattern))
             .cloned()
             .collect();
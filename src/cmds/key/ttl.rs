@@ -3,17 +3,17 @@ use anyhow::Error;
 use crate::{store::db::Db, frame::Frame};
 
 pub struct Ttl {
-    key: String,
+    key: Vec<u8>,
 }
 
 impl Ttl {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {      
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'ttl' command"));
         }
-        let fianl_key = key.unwrap().to_string();
+        let fianl_key = key.unwrap();
         Ok(Ttl {
             key: fianl_key
         })
@@ -3,23 +3,23 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Type {
-    pub key: String,
+    pub key: Vec<u8>,
 }
 
 impl Type {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'type' command"));
         }
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
         Ok(Type { 
             key: final_key 
         })
     }
 
-    pub fn new(key: String) -> Self {
+    pub fn new(key: Vec<u8>) -> Self {
         Type { key }
     }
 
@@ -50,6 +50,9 @@ impl Type {
                     },
                     Structure::HyperLogLog(_) => {
                         Ok(Frame::SimpleString("string".to_string()))
+                    },
+                    Structure::Stream(_) => {
+                        Ok(Frame::SimpleString("stream".to_string()))
                     }
                 }
             },
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+use crate::{command::Command, store::{blocking::parse_timeout, db::Db}, frame::Frame, server::Handler};
+use super::Direction;
+
+/// BLMOVE source destination LEFT|RIGHT LEFT|RIGHT timeout
+#[derive(Clone)]
+pub struct Blmove {
+    source: Vec<u8>,
+    destination: Vec<u8>,
+    from: Direction,
+    to: Direction,
+    timeout: Option<Duration>,
+}
+
+impl Blmove {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 6 {
+            return Err(Error::msg("ERR wrong number of arguments for 'blmove' command"));
+        }
+
+        let source = frame.get_arg_bytes(1).unwrap(); // 源列表
+        let destination = frame.get_arg_bytes(2).unwrap(); // 目标列表
+        let from = Direction::parse(&args[3])?;
+        let to = Direction::parse(&args[4])?;
+        let timeout = parse_timeout(&args[5])?;
+
+        Ok(Blmove { source, destination, from, to, timeout })
+    }
+
+    /// 源列表为空时阻塞，直到源列表被写入或超时
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let keys = [self.source.clone()];
+        handler.block_on_keys(&keys, self.timeout, self.from.block_direction(), || Command::Blmove(self.clone())).await
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(super::move_element(db, &self.source, &self.destination, self.from, self.to))
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 改写为 LMOVE，从节点重放时不会阻塞；没有移动元素时不传播
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        if !matches!(reply, Frame::BulkString(_)) {
+            return None;
+        }
+        let blmove = Blmove::parse_from_frame(frame).ok()?;
+        Some(Frame::Array(vec![
+            Frame::BulkString("LMOVE".to_string().into()),
+            Frame::BulkString(blmove.source),
+            Frame::BulkString(blmove.destination),
+            Frame::BulkString(blmove.from.as_str().to_string().into()),
+            Frame::BulkString(blmove.to.as_str().to_string().into()),
+        ]))
+    }
+}
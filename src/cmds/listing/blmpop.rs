@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+use crate::{command::Command, store::{blocking::parse_timeout, db::Db}, frame::Frame, server::Handler};
+use super::lmpop::Lmpop;
+
+/// BLMPOP timeout numkeys key [key ...] LEFT|RIGHT [COUNT count]
+#[derive(Clone)]
+pub struct Blmpop {
+    timeout: Option<Duration>,
+    lmpop: Lmpop,
+}
+
+impl Blmpop {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 5 {
+            return Err(Error::msg("ERR wrong number of arguments for 'blmpop' command"));
+        }
+        let timeout = parse_timeout(&args[1])?;
+        let lmpop = Lmpop::parse(&frame.get_args_bytes()[2..], "blmpop")?;
+        Ok(Blmpop { timeout, lmpop })
+    }
+
+    /// 所有列表都为空时阻塞，直到其中一个被写入或超时
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let direction = self.lmpop.direction.block_direction();
+        let keys = self.lmpop.keys.clone();
+        handler.block_on_keys(&keys, self.timeout, direction, || Command::Blmpop(self.clone())).await
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        self.lmpop.apply(db)
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 改写为弹出实际键与数量的 LMPOP，从节点重放时不会阻塞；没有弹出元素时不传播
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let Frame::Array(items) = reply else {
+            return None;
+        };
+        let (Some(key), Some(Frame::Array(values))) = (items.first(), items.get(1)) else {
+            return None;
+        };
+        let blmpop = Blmpop::parse_from_frame(frame).ok()?;
+        Some(Frame::Array(vec![
+            Frame::BulkString("LMPOP".to_string().into()),
+            Frame::BulkString("1".to_string().into()),
+            key.clone(),
+            Frame::BulkString(blmpop.lmpop.direction.as_str().to_string().into()),
+            Frame::BulkString("COUNT".to_string().into()),
+            Frame::BulkString(values.len().to_string().into()),
+        ]))
+    }
+}
@@ -1,129 +1,85 @@
+use std::time::Duration;
+
 use anyhow::Error;
-use crate::{frame::Frame, server::Handler};
-use crate::store::blocking::BlockDirection;
 
-/// BLPOP 命令：阻塞式从列表左端弹出元素
-///
-/// 这个命令需要在 Handler 中处理，因为：
-/// 1. 需要异步等待（tokio::select!）
-/// 2. 需要访问 blocking_manager 和 session_manager
-/// 3. 需要跨任务通信
-///
-/// 所有逻辑都在 `apply` 方法中，不依赖 server.rs
+use crate::{command::Command, store::{blocking::{parse_timeout, BlockDirection}, db::Db}, frame::Frame, server::Handler};
+
+/// BLPOP / BRPOP 共用的实现：key [key ...] timeout
 #[derive(Clone)]
-pub struct Blpop {
-    keys: Vec<String>,
-    timeout: u64,  // 秒，0 表示永久阻塞（实际限制为 3600 秒）
+pub struct Bpop {
+    keys: Vec<Vec<u8>>,
+    timeout: Option<Duration>,
+    left: bool,
 }
 
-impl Blpop {
-    /// 从 Frame 解析 BLPOP 命令
-    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let args = frame.get_args();
-        
+impl Bpop {
+    pub fn parse(frame: &Frame, command: &str, left: bool) -> Result<Self, Error> {
+        let args = frame.get_args_bytes();
         if args.len() < 3 {
-            return Err(Error::msg("ERR wrong number of arguments for 'blpop' command"));
-        }
-
-        // 安全限制：防止恶意客户端发送过多的 key
-        if args.len() - 2 > 1000 {
-            return Err(Error::msg("ERR too many keys for 'blpop' command"));
+            return Err(Error::msg(format!("ERR wrong number of arguments for '{}' command", command)));
         }
-        
-        let keys: Vec<String> = args[1..args.len()-1]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        
-        let timeout = args.last()
-            .ok_or_else(|| Error::msg("ERR timeout required"))?
-            .parse::<u64>()
-            .map_err(|_| Error::msg("ERR timeout is not an integer"))?;
-        
-        Ok(Blpop { keys, timeout })
+        let keys = args[1..args.len() - 1].to_vec();
+        let timeout = parse_timeout(&String::from_utf8_lossy(&args[args.len() - 1]))?;
+        Ok(Bpop { keys, timeout, left })
     }
 
-    /// 在 Handler 中执行 BLPOP 命令
-    /// 
-    /// 流程：
-    /// 1. 遍历所有 keys 非阻塞检查，如果某个列表非空，立即返回
-    /// 2. 如果所有列表都为空，注册阻塞请求
-    /// 3. 使用 tokio::select! 等待结果或超时
-    pub async fn apply(&mut self, handler: &mut Handler) -> Result<Frame, Error> {
-        use tokio::time::{sleep, Duration};
-        
-        // 1. 遍历所有 keys，非阻塞检查是否非空
-        // Redis BLPOP 语义：按顺序检查所有 key，返回第一个非空列表的结果
-        for key in &self.keys {
-            let pop_result = self.non_blocking_pop(handler, key).await?;
-            
-            if let Some(value) = pop_result {
-                return Ok(Frame::Array(vec![
-                    Frame::BulkString(key.clone()),
-                    Frame::BulkString(value),
-                ]));
-            }
-            // 当前 key 对应的列表为空，继续检查下一个 key
-        }
-        
-        // 2. 所有列表都为空，注册阻塞请求
-        let timeout = if self.timeout == 0 {
-            Some(Duration::from_secs(3600)) // 设置合理上限
-        } else {
-            Some(Duration::from_secs(self.timeout))
-        };
-        
-        let mut blocking_manager = handler.get_state().blocking_list.lock().await;
-        let session_id = handler.get_session().get_id();
-        let receiver = blocking_manager.register_blocking_request(
-            self.keys.clone(),
-            session_id,
-            BlockDirection::Left,
-            timeout,
-        );
-        drop(blocking_manager); // 释放锁，避免在等待时持有锁
-        
-        // 3. 使用 tokio::select! 处理超时
-        let timeout_duration = timeout.unwrap_or(Duration::from_secs(3600));
-        let blocking_manager_clone = handler.get_state().blocking_list.clone();
-        
-        tokio::select! {
-            result = receiver => {
-                match result {
-                    Ok(frame) => Ok(frame),
-                    Err(_) => Ok(Frame::Null),
-                }
-            }
-            _ = sleep(timeout_duration) => {
-                // 超时，清理请求
-                let mut manager = blocking_manager_clone.lock().await;
-                manager.cleanup_session(session_id);
-                Ok(Frame::Null)
+    /// 非阻塞弹出：从第一个非空的列表弹出一个元素，回复 [键, 元素]，所有列表都为空时回复 nil
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        for key in self.keys {
+            match super::pop(db, &key, 1, self.left) {
+                Ok(Some(mut values)) => {
+                    if let Some(value) = values.pop() {
+                        return Ok(Frame::Array(vec![Frame::BulkString(key), Frame::BulkString(value)]));
+                    }
+                },
+                Ok(None) => continue,
+                Err(reply) => return Ok(reply),
             }
         }
+        Ok(Frame::NullArray)
     }
 
-    /// 非阻塞弹出：尝试从指定列表弹出一个元素
-    /// 
-    /// 返回 Some(value) 如果列表非空，返回 None 如果列表为空
-    async fn non_blocking_pop(&self, handler: &mut Handler, key: &str) -> Result<Option<String>, Error> {
-        let pop_frame = Frame::Array(vec![
-            Frame::BulkString("LPOP".to_string()),
-            Frame::BulkString(key.to_string()),
-        ]);
-        
-        let pop_cmd = match crate::command::Command::parse_from_frame(pop_frame) {
-            Ok(cmd) => cmd,
-            Err(_) => return Ok(None),
+    /// 阻塞等待的键、超时时间与方向
+    pub fn blocking_args(&self) -> (Vec<Vec<u8>>, Option<Duration>, BlockDirection) {
+        let direction = if self.left { BlockDirection::Left } else { BlockDirection::Right };
+        (self.keys.clone(), self.timeout, direction)
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 改写为弹出实际键的 LPOP / RPOP，从节点重放时不会阻塞；没有弹出元素时不传播
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let Frame::Array(items) = reply else {
+            return None;
         };
-        
-        // 非阻塞执行 LPOP
-        let result = handler.apply_db_command(pop_cmd).await?;
-        
-        if let Frame::BulkString(value) = result {
-            Ok(Some(value))
-        } else {
-            Ok(None)
-        }
+        let key = items.first()?.clone();
+        let command = frame.get_arg(0)?.to_uppercase();
+        let pop = if command == "BRPOP" { "RPOP" } else { "LPOP" };
+        Some(Frame::Array(vec![Frame::BulkString(pop.to_string().into()), key]))
+    }
+}
+
+/// BLPOP 命令：阻塞式从列表左端弹出元素
+#[derive(Clone)]
+pub struct Blpop {
+    pop: Bpop,
+}
+
+impl Blpop {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let pop = Bpop::parse(&frame, "blpop", true)?;
+        Ok(Blpop { pop })
+    }
+
+    /// 所有列表都为空时阻塞，直到其中一个被写入或超时
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let (keys, timeout, direction) = self.pop.blocking_args();
+        handler.block_on_keys(&keys, timeout, direction, || Command::Blpop(self.clone())).await
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        self.pop.apply(db)
     }
 }
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+use crate::{command::Command, store::{blocking::parse_timeout, db::Db}, frame::Frame, server::Handler};
+use super::Direction;
+
+/// 等同于 BLMOVE source destination RIGHT LEFT timeout
+#[derive(Clone)]
+pub struct Brpoplpush {
+    source: Vec<u8>,
+    destination: Vec<u8>,
+    timeout: Option<Duration>,
+}
+
+impl Brpoplpush {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'brpoplpush' command"));
+        }
+
+        let source = frame.get_arg_bytes(1).unwrap(); // 源列表
+        let destination = frame.get_arg_bytes(2).unwrap(); // 目标列表
+        let timeout = parse_timeout(&args[3])?;
+
+        Ok(Brpoplpush { source, destination, timeout })
+    }
+
+    /// 源列表为空时阻塞，直到源列表被写入或超时
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let keys = [self.source.clone()];
+        handler.block_on_keys(&keys, self.timeout, Direction::Right.block_direction(), || Command::Brpoplpush(self.clone())).await
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(super::move_element(db, &self.source, &self.destination, Direction::Right, Direction::Left))
+    }
+
+    /// 改写为 RPOPLPUSH 写入 AOF 与传播给从节点；没有移动元素时不传播
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        if !matches!(reply, Frame::BulkString(_)) {
+            return None;
+        }
+        let brpoplpush = Brpoplpush::parse_from_frame(frame).ok()?;
+        Some(Frame::Array(vec![
+            Frame::BulkString("RPOPLPUSH".to_string().into()),
+            Frame::BulkString(brpoplpush.source),
+            Frame::BulkString(brpoplpush.destination),
+        ]))
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Lindex {
-    key: String,
+    key: Vec<u8>,
     index: i64,
 }
 
@@ -10,14 +10,14 @@ impl Lindex {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         let index = frame.get_arg(2);
 
         if key.is_none() || index.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'lindex' command"));
         }
 
-        let final_key = key.unwrap().to_string(); // 键
+        let final_key = key.unwrap(); // 键
         let final_index = index.unwrap().parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
 
         Ok(Lindex {
@@ -0,0 +1,54 @@
+use anyhow::Error;
+use crate::{store::db::{Db, Structure}, frame::Frame};
+
+pub struct Linsert {
+    key: Vec<u8>,
+    before: bool,
+    pivot: Vec<u8>,
+    element: Vec<u8>,
+}
+
+impl Linsert {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 5 {
+            return Err(Error::msg("ERR wrong number of arguments for 'linsert' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let before = match args[2].to_uppercase().as_str() {
+            "BEFORE" => true,
+            "AFTER" => false,
+            _ => return Err(Error::msg("ERR syntax error")),
+        };
+        let pivot = frame.get_arg_bytes(3).unwrap_or_default(); // 基准元素
+        let element = frame.get_arg_bytes(4).unwrap_or_default(); // 插入的元素
+
+        Ok(Linsert { key, before, pivot, element })
+    }
+
+    /// 返回插入后列表的长度，找不到基准元素时返回 -1，键不存在时返回 0
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 找不到 pivot 时不修改键
+        if let Some(Structure::List(list)) = db.get(&self.key) {
+            if !list.contains(&self.pivot) {
+                return Ok(Frame::Integer(-1));
+            }
+        }
+        match db.get_mut(&self.key) {
+            Some(Structure::List(list)) => {
+                match list.iter().position(|value| *value == self.pivot) {
+                    Some(index) => {
+                        let index = if self.before { index } else { index + 1 };
+                        list.insert(index, self.element);
+                        Ok(Frame::Integer(list.len() as i64))
+                    },
+                    None => Ok(Frame::Integer(-1)),
+                }
+            },
+            Some(_) => Ok(super::wrong_type()),
+            None => Ok(Frame::Integer(0)),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::Error;
+use crate::{store::db::Db, frame::Frame};
+use super::Direction;
+
+pub struct Lmove {
+    source: Vec<u8>,
+    destination: Vec<u8>,
+    from: Direction,
+    to: Direction,
+}
+
+impl Lmove {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 5 {
+            return Err(Error::msg("ERR wrong number of arguments for 'lmove' command"));
+        }
+
+        let source = frame.get_arg_bytes(1).unwrap(); // 源列表
+        let destination = frame.get_arg_bytes(2).unwrap(); // 目标列表
+        let from = Direction::parse(&args[3])?;
+        let to = Direction::parse(&args[4])?;
+
+        Ok(Lmove { source, destination, from, to })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(super::move_element(db, &self.source, &self.destination, self.from, self.to))
+    }
+}
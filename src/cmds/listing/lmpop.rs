@@ -0,0 +1,67 @@
+use anyhow::Error;
+use crate::{store::db::Db, frame::Frame};
+use super::Direction;
+
+/// LMPOP numkeys key [key ...] LEFT|RIGHT [COUNT count]
+#[derive(Clone)]
+pub struct Lmpop {
+    pub keys: Vec<Vec<u8>>,
+    pub direction: Direction,
+    count: usize,
+}
+
+impl Lmpop {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        Lmpop::parse(&frame.get_args_bytes()[1..], "lmpop")
+    }
+
+    /**
+     * 解析 numkeys 及之后的参数（LMPOP 与 BLMPOP 共用）
+     *
+     * @param args numkeys key [key ...] LEFT|RIGHT [COUNT count]
+     * @param command 命令名，用于错误信息
+     */
+    pub fn parse(args: &[Vec<u8>], command: &str) -> Result<Self, Error> {
+        if args.len() < 3 {
+            return Err(Error::msg(format!("ERR wrong number of arguments for '{}' command", command)));
+        }
+
+        let numkeys = String::from_utf8_lossy(&args[0]).parse::<i64>().map_err(|_| Error::msg("ERR numkeys should be greater than 0"))?;
+        if numkeys < 1 {
+            return Err(Error::msg("ERR numkeys should be greater than 0"));
+        }
+        let numkeys = numkeys as usize;
+        if numkeys + 1 >= args.len() {
+            return Err(Error::msg("ERR syntax error"));
+        }
+        let keys = args[1..1 + numkeys].to_vec();
+        let direction = Direction::parse(&String::from_utf8_lossy(&args[1 + numkeys]))?;
+        let count = match &args[2 + numkeys..] {
+            [] => 1,
+            [option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+                String::from_utf8_lossy(count).parse::<i64>().ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| Error::msg("ERR count should be greater than 0"))? as usize
+            },
+            _ => return Err(Error::msg("ERR syntax error")),
+        };
+
+        Ok(Lmpop { keys, direction, count })
+    }
+
+    /// 从第一个非空的列表弹出，回复 [键, [元素, ...]]，所有列表都为空时回复 nil
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        for key in self.keys {
+            match super::pop(db, &key, self.count, self.direction == Direction::Left) {
+                Ok(Some(values)) if !values.is_empty() => {
+                    let values = values.into_iter().map(Frame::BulkString).collect();
+                    return Ok(Frame::Array(vec![Frame::BulkString(key), Frame::Array(values)]));
+                },
+                Ok(_) => continue,
+                Err(reply) => return Ok(reply),
+            }
+        }
+        Ok(Frame::NullArray)
+    }
+}
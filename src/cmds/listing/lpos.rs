@@ -0,0 +1,87 @@
+use anyhow::Error;
+use crate::{store::db::{Db, Structure}, frame::Frame};
+
+pub struct Lpos {
+    key: Vec<u8>,
+    element: Vec<u8>,
+    rank: i64, // 从第几个匹配开始返回，负数表示从列表末尾开始查找
+    count: Option<usize>, // 指定时返回数组，0 表示返回所有匹配
+    maxlen: usize, // 最多比较的元素数量，0 表示不限制
+}
+
+impl Lpos {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'lpos' command"));
+        }
+        if args.len().is_multiple_of(2) {
+            return Err(Error::msg("ERR syntax error"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let element = frame.get_arg_bytes(2).unwrap_or_default(); // 查找的元素
+        let mut rank = 1;
+        let mut count = None;
+        let mut maxlen = 0;
+
+        for option in args[3..].chunks(2) {
+            let value = option[1].parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+            match option[0].to_uppercase().as_str() {
+                "RANK" => {
+                    if value == 0 || value == i64::MIN {
+                        return Err(Error::msg("ERR RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list"));
+                    }
+                    rank = value;
+                },
+                "COUNT" => {
+                    if value < 0 {
+                        return Err(Error::msg("ERR COUNT can't be negative"));
+                    }
+                    count = Some(value as usize);
+                },
+                "MAXLEN" => {
+                    if value < 0 {
+                        return Err(Error::msg("ERR MAXLEN can't be negative"));
+                    }
+                    maxlen = value as usize;
+                },
+                _ => return Err(Error::msg("ERR syntax error")),
+            }
+        }
+
+        Ok(Lpos { key, element, rank, count, maxlen })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let list = match db.get(&self.key) {
+            Some(Structure::List(list)) => list,
+            Some(_) => return Ok(super::wrong_type()),
+            None => return Ok(if self.count.is_some() { Frame::Array(vec![]) } else { Frame::Null }),
+        };
+
+        let maxlen = if self.maxlen == 0 { list.len() } else { self.maxlen.min(list.len()) };
+        let limit = match self.count {
+            Some(0) => usize::MAX,
+            Some(count) => count,
+            None => 1,
+        };
+        let indexes: Box<dyn Iterator<Item = usize>> = if self.rank > 0 {
+            Box::new(0..maxlen)
+        } else {
+            Box::new((list.len() - maxlen..list.len()).rev())
+        };
+        let positions: Vec<Frame> = indexes
+            .filter(|index| list[*index] == self.element)
+            .skip(self.rank.unsigned_abs() as usize - 1)
+            .take(limit)
+            .map(|index| Frame::Integer(index as i64))
+            .collect();
+
+        match self.count {
+            Some(_) => Ok(Frame::Array(positions)),
+            None => Ok(positions.into_iter().next().unwrap_or(Frame::Null)),
+        }
+    }
+}
@@ -3,8 +3,8 @@ use crate::{store::db::{Db, Structure}, frame::Frame};
 
 #[derive(Clone)]
 pub struct Lpush {
-    key: String,
-    values: Vec<String>,
+    key: Vec<u8>,
+    values: Vec<Vec<u8>>,
 }
 
 impl Lpush {
@@ -17,8 +17,8 @@ impl Lpush {
             return Err(Error::msg("ERR wrong number of arguments for 'lpush' command"));
         }
 
-        let key = args[1].to_string(); // 键
-        let values: Vec<String> = args.iter().skip(2).map(|v| v.to_string()).collect(); // 值
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let values = frame.get_args_bytes_from_index(2); // 值
 
         Ok(Lpush { key, values })
     }
@@ -51,12 +51,12 @@ impl Lpush {
     }
 
     /// 获取键名
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &[u8] {
         &self.key
     }
 
     /// 获取值列表
-    pub fn values(&self) -> &[String] {
+    pub fn values(&self) -> &[Vec<u8>] {
         &self.values
     }
 }
\ No newline at end of file
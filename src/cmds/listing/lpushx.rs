@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Lpushx {
-    key: String,
-    values: Vec<String>,
+    key: Vec<u8>,
+    values: Vec<Vec<u8>>,
 }
 
 impl Lpushx {
@@ -16,8 +16,8 @@ impl Lpushx {
             return Err(Error::msg("ERR wrong number of arguments for 'lpushx' command"));
         }
 
-        let key = args[1].to_string(); // 键
-        let values: Vec<String> = args.iter().skip(2).map(|v| v.to_string()).collect(); // 值
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let values = frame.get_args_bytes_from_index(2); // 值
 
         Ok(Lpushx { key, values })
     }
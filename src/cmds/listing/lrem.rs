@@ -2,9 +2,9 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Lrem {
-    key: String,
+    key: Vec<u8>,
     count: i64,
-    value: String,
+    value: Vec<u8>,
 }
 
 impl Lrem {
@@ -17,18 +17,24 @@ impl Lrem {
             return Err(Error::msg("ERR wrong number of arguments for 'lrem' command"));
         }
 
-        let key = args[1].to_string();
+        let key = frame.get_arg_bytes(1).unwrap();
         
         let count = args[2].parse::<i64>().map_err(|_| {
             Error::msg("ERR value is not an integer or out of range")
         })?;
         
-        let value = args[3].to_string();
+        let value = frame.get_arg_bytes(3).unwrap_or_default();
 
         Ok(Lrem { key, count, value })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 列表中没有该元素时不修改键
+        if let Some(Structure::List(list)) = db.get(&self.key) {
+            if !list.contains(&self.value) {
+                return Ok(Frame::Integer(0));
+            }
+        }
         match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
@@ -50,7 +56,7 @@ impl Lrem {
     /// count > 0: 从表头开始向表尾搜索，移除 count 个
     /// count < 0: 从表尾开始向表头搜索，移除 |count| 个
     /// count = 0: 移除所有与 value 相等的元素
-    fn remove_elements(&self, list: &mut Vec<String>) -> i64 {
+    fn remove_elements(&self, list: &mut Vec<Vec<u8>>) -> i64 {
         let mut removed_count: i64 = 0;
 
         if self.count > 0 {
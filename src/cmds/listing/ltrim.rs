@@ -2,14 +2,14 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Ltrim {
-    key: String,
+    key: Vec<u8>,
     start: i64,
     stop: i64,
 }
 
 impl Ltrim {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         let start = frame.get_arg(2);
         let stop = frame.get_arg(3);
 
@@ -17,7 +17,7 @@ impl Ltrim {
             return Err(Error::msg("ERR wrong number of arguments for 'ltrim' command"));
         }
 
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
 
         let start = match start.unwrap().parse::<i64>() {
             Ok(n) => n,
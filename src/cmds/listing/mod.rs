@@ -11,4 +11,117 @@ pub mod lrem;
 pub mod lset;
 pub mod ltrim;
 pub mod blpop;
-pub mod brpop;
\ No newline at end of file
+pub mod brpop;
+pub mod linsert;
+pub mod lpos;
+pub mod lmove;
+pub mod rpoplpush;
+pub mod lmpop;
+pub mod blmove;
+pub mod brpoplpush;
+pub mod blmpop;
+
+use anyhow::Error;
+
+use crate::{frame::Frame, store::{blocking::BlockDirection, db::{Db, Structure}}};
+
+/// 列表的一端
+#[derive(Clone, Copy, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        match text.to_uppercase().as_str() {
+            "LEFT" => Ok(Direction::Left),
+            "RIGHT" => Ok(Direction::Right),
+            _ => Err(Error::msg("ERR syntax error")),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Left => "LEFT",
+            Direction::Right => "RIGHT",
+        }
+    }
+
+    /// 阻塞等待时的方向
+    pub fn block_direction(&self) -> BlockDirection {
+        match self {
+            Direction::Left => BlockDirection::Left,
+            Direction::Right => BlockDirection::Right,
+        }
+    }
+}
+
+fn wrong_type() -> Frame {
+    Frame::Error("ERR Operation against a key holding the wrong kind of value".to_string())
+}
+
+/// 解析 COUNT 参数，必须为非负整数
+fn parse_count(text: &str) -> Result<usize, Error> {
+    let count = text.parse::<i64>().map_err(|_| Error::msg("ERR value is out of range, must be positive"))?;
+    if count < 0 {
+        return Err(Error::msg("ERR value is out of range, must be positive"));
+    }
+    Ok(count as usize)
+}
+
+/**
+ * 从列表的一端弹出最多 count 个元素，弹出后列表为空时删除键
+ *
+ * @param left 为 true 时从左端弹出
+ * @return 键不存在时返回 None，类型错误时返回错误回复
+ */
+fn pop(db: &mut Db, key: &[u8], count: usize, left: bool) -> Result<Option<Vec<Vec<u8>>>, Frame> {
+    let (popped, empty) = match db.get_mut(key) {
+        Some(Structure::List(list)) => {
+            let count = count.min(list.len());
+            let popped = if left {
+                list.drain(..count).collect()
+            } else {
+                list.drain(list.len() - count..).rev().collect()
+            };
+            (popped, list.is_empty())
+        },
+        Some(_) => return Err(wrong_type()),
+        None => return Ok(None),
+    };
+    if empty {
+        db.remove(key);
+    }
+    Ok(Some(popped))
+}
+
+/**
+ * 从源列表的一端弹出一个元素，推入目标列表的一端（LMOVE、RPOPLPUSH）
+ *
+ * 源列表与目标列表可以相同，此时相当于旋转列表
+ *
+ * @return 被移动的元素，源列表不存在时回复 nil
+ */
+pub fn move_element(db: &mut Db, source: &[u8], destination: &[u8], from: Direction, to: Direction) -> Frame {
+    // 先检查目标键的类型，避免弹出后无法推入
+    if !matches!(db.get(destination), Some(Structure::List(_)) | None) {
+        return wrong_type();
+    }
+    let value = match pop(db, source, 1, from == Direction::Left) {
+        Ok(Some(mut values)) => match values.pop() {
+            Some(value) => value,
+            None => return Frame::Null,
+        },
+        Ok(None) => return Frame::Null,
+        Err(reply) => return reply,
+    };
+    match db.get_mut(destination) {
+        Some(Structure::List(list)) => match to {
+            Direction::Left => list.insert(0, value.clone()),
+            Direction::Right => list.push(value.clone()),
+        },
+        _ => db.insert(destination.to_vec(), Structure::List(vec![value.clone()])),
+    }
+    Frame::BulkString(value)
+}
@@ -1,9 +1,9 @@
 use anyhow::Error;
-
-use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::{store::db::Db, frame::Frame};
 
 pub struct Rpop {
-    key: String,
+    key: Vec<u8>,
+    count: Option<usize>, // 指定时返回数组，最多弹出 count 个元素
 }
 
 impl Rpop {
@@ -12,39 +12,30 @@ impl Rpop {
 
         let args = frame.get_args();
 
-        if args.len() != 2 {
+        if args.len() != 2 && args.len() != 3 {
             return Err(Error::msg("ERR wrong number of arguments for 'rpop' command"));
         }
 
-        let key = args[1].to_string(); // 键
-
-        Ok(Rpop { key })
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let count = match args.get(2) {
+            Some(count) => Some(super::parse_count(count)?),
+            None => None,
+        };
+        
+        Ok(Rpop { key, count })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.get_mut(&self.key) {
-            Some(structure) => {
-                match structure {
-                    Structure::List(list) => {
-                        if list.is_empty() {
-                            Ok(Frame::Null)
-                        } else {
-                            let value = list.pop(); // 移除列表的最后一个元素
-                            match value {
-                                Some(val) => Ok(Frame::BulkString(val)),
-                                None => Ok(Frame::Null), // 理论上不会执行到这，因为前面已经判断过列表不为空
-                            }
-                        }
-                    },
-                    _ => {
-                        let f = "ERR Operation against a key holding the wrong kind of value";
-                        Ok(Frame::Error(f.to_string()))
-                    }
-                }
-            },
-            None => {
-                Ok(Frame::Null)
-            }
-        }
+        let popped = match super::pop(db, &self.key, self.count.unwrap_or(1), false) {
+            Ok(popped) => popped,
+            Err(reply) => return Ok(reply),
+        };
+        Ok(match (self.count, popped) {
+            (Some(_), Some(values)) => Frame::Array(values.into_iter().map(Frame::BulkString).collect()),
+            (None, Some(mut values)) => values.pop().map_or(Frame::Null, Frame::BulkString),
+            // 键不存在时返回 nil
+            (Some(_), None) => Frame::NullArray,
+            (None, None) => Frame::Null,
+        })
     }
 }
\ No newline at end of file
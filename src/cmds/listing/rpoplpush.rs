@@ -0,0 +1,28 @@
+use anyhow::Error;
+use crate::{store::db::Db, frame::Frame};
+use super::Direction;
+
+/// 等同于 LMOVE source destination RIGHT LEFT
+pub struct Rpoplpush {
+    source: Vec<u8>,
+    destination: Vec<u8>,
+}
+
+impl Rpoplpush {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'rpoplpush' command"));
+        }
+
+        let source = frame.get_arg_bytes(1).unwrap(); // 源列表
+        let destination = frame.get_arg_bytes(2).unwrap(); // 目标列表
+
+        Ok(Rpoplpush { source, destination })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(super::move_element(db, &self.source, &self.destination, Direction::Right, Direction::Left))
+    }
+}
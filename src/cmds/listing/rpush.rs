@@ -3,8 +3,8 @@ use crate::{store::db::{Db, Structure}, frame::Frame};
 
 #[derive(Clone)]
 pub struct Rpush {
-    key: String,
-    values: Vec<String>,
+    key: Vec<u8>,
+    values: Vec<Vec<u8>>,
 }
 
 impl Rpush {
@@ -14,8 +14,8 @@ impl Rpush {
         if args.len() < 3 {
             return Err(Error::msg("ERR wrong number of arguments for 'rpush' command"));
         }
-        let key = args[1].to_string(); // 键
-        let values: Vec<String> = args.iter().skip(2).map(|v| v.to_string()).collect(); // 值
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let values = frame.get_args_bytes_from_index(2); // 值
         Ok(Rpush { key, values })
     }
 
@@ -47,12 +47,12 @@ impl Rpush {
     }
 
     /// 获取键名
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &[u8] {
         &self.key
     }
 
     /// 获取值列表
-    pub fn values(&self) -> &[String] {
+    pub fn values(&self) -> &[Vec<u8>] {
         &self.values
     }
 }
\ No newline at end of file
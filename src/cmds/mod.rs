@@ -9,4 +9,7 @@ pub mod server_sync;
 pub mod string;
 pub mod set;
 pub mod transaction;
-pub mod hyperloglog;
\ No newline at end of file
+pub mod hyperloglog;
+pub mod pubsub;
+pub mod stream;
+pub mod scripting;
\ No newline at end of file
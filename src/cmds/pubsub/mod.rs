@@ -0,0 +1,34 @@
+pub mod subscribe;
+pub mod unsubscribe;
+pub mod psubscribe;
+pub mod punsubscribe;
+pub mod publish;
+#[allow(clippy::module_inception)]
+pub mod pubsub;
+
+use crate::{frame::Frame, server::Handler};
+
+/// 构建订阅类命令的确认消息：[kind, channel, 订阅总数]
+///
+/// channel 为 None 时（无订阅时执行 UNSUBSCRIBE）回复 nil。
+/// RESP3 连接以推送消息的形式接收，RESP2 连接接收普通数组
+fn confirmation(kind: &str, channel: Option<String>, count: usize) -> Frame {
+    Frame::Push(vec![
+        Frame::BulkString(kind.to_string().into()),
+        channel.map_or(Frame::Null, |channel| Frame::BulkString(channel.into())),
+        Frame::Integer(count as i64),
+    ])
+}
+
+/// 回复订阅类命令的确认消息，并同步会话的订阅状态
+///
+/// (P)SUBSCRIBE / (P)UNSUBSCRIBE 每个频道都有一条确认消息，
+/// 除最后一条外均直接写入连接，最后一条作为命令结果返回
+async fn reply_confirmations(handler: &mut Handler, mut replies: Vec<Frame>, count: usize) -> Frame {
+    handler.set_subscriptions(count);
+    let last = replies.pop().unwrap_or(Frame::Array(vec![]));
+    for reply in replies {
+        handler.get_session().connection.write_frame(&reply).await;
+    }
+    last
+}
@@ -0,0 +1,37 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+use super::{confirmation, reply_confirmations};
+
+/// PSUBSCRIBE 命令：按 glob 模式订阅频道
+///
+/// 匹配规则与 KEYS 相同（tools::pattern）
+#[derive(Clone)]
+pub struct Psubscribe {
+    patterns: Vec<String>,
+}
+
+impl Psubscribe {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 2 {
+            return Err(Error::msg("ERR wrong number of arguments for 'psubscribe' command"));
+        }
+        let patterns = args[1..].to_vec();
+        Ok(Psubscribe { patterns })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let session_id = handler.get_session().get_id();
+        let mut replies = Vec::new();
+        let mut count = 0;
+        {
+            let mut pubsub = handler.get_state().pubsub.lock().await;
+            for pattern in self.patterns {
+                count = pubsub.psubscribe(session_id, &pattern);
+                replies.push(confirmation("psubscribe", Some(pattern), count));
+            }
+        }
+        Ok(reply_confirmations(handler, replies, count).await)
+    }
+}
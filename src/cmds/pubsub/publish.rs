@@ -0,0 +1,40 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+/// PUBLISH 命令：向频道发布消息
+///
+/// 返回收到消息的客户端数量（频道订阅者 + 匹配的模式订阅者）
+#[derive(Clone)]
+pub struct Publish {
+    channel: String,
+    message: Vec<u8>,
+}
+
+impl Publish {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'publish' command"));
+        }
+        let channel = args[1].to_string();
+        let message = frame.get_arg_bytes(2).unwrap_or_default();
+        Ok(Publish { channel, message })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        // 先在锁内计算投递目标，释放锁后再写入各订阅者的连接
+        let messages = {
+            let pubsub = handler.get_state().pubsub.lock().await;
+            pubsub.messages_for(&self.channel, &self.message)
+        };
+
+        let mut receivers = 0;
+        for (session_id, frame) in messages {
+            if let Some(session) = handler.get_session_manager().get_session(session_id) {
+                session.connection.write_frame(&frame).await;
+                receivers += 1;
+            }
+        }
+        Ok(Frame::Integer(receivers))
+    }
+}
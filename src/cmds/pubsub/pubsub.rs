@@ -0,0 +1,54 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+/// PUBSUB 命令：查看发布订阅系统的状态
+///
+/// - PUBSUB CHANNELS [pattern]: 活跃频道列表
+/// - PUBSUB NUMSUB [channel ...]: 各频道的订阅者数量
+/// - PUBSUB NUMPAT: 被订阅的模式数量
+#[derive(Clone)]
+pub struct Pubsub {
+    subcommand: String,
+    args: Vec<String>,
+}
+
+impl Pubsub {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 2 {
+            return Err(Error::msg("ERR wrong number of arguments for 'pubsub' command"));
+        }
+        let subcommand = args[1].to_uppercase();
+        let args = args[2..].to_vec();
+        Ok(Pubsub { subcommand, args })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let pubsub = handler.get_state().pubsub.lock().await;
+        match self.subcommand.as_str() {
+            "CHANNELS" => {
+                if self.args.len() > 1 {
+                    return Ok(Frame::Error("ERR wrong number of arguments for 'pubsub|channels' command".to_string()));
+                }
+                let channels = pubsub.active_channels(self.args.first().map(|s| s.as_str()));
+                Ok(Frame::Array(channels.into_iter().map(|item| Frame::BulkString(item.into())).collect()))
+            },
+            "NUMSUB" => {
+                let mut result = Vec::with_capacity(self.args.len() * 2);
+                for channel in self.args {
+                    let count = pubsub.numsub(&channel);
+                    result.push(Frame::BulkString(channel.into()));
+                    result.push(Frame::Integer(count as i64));
+                }
+                Ok(Frame::Array(result))
+            },
+            "NUMPAT" => {
+                if !self.args.is_empty() {
+                    return Ok(Frame::Error("ERR wrong number of arguments for 'pubsub|numpat' command".to_string()));
+                }
+                Ok(Frame::Integer(pubsub.numpat() as i64))
+            },
+            _ => Ok(Frame::Error(format!("ERR unknown subcommand '{}'", self.subcommand))),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+use super::{confirmation, reply_confirmations};
+
+/// PUNSUBSCRIBE 命令：退订模式
+///
+/// 不带参数时退订该会话的所有模式
+#[derive(Clone)]
+pub struct Punsubscribe {
+    patterns: Vec<String>,
+}
+
+impl Punsubscribe {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let patterns = frame.get_args_from_index(1);
+        Ok(Punsubscribe { patterns })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let session_id = handler.get_session().get_id();
+        let mut replies = Vec::new();
+        let count;
+        {
+            let mut pubsub = handler.get_state().pubsub.lock().await;
+            let patterns = if self.patterns.is_empty() {
+                pubsub.session_patterns(session_id)
+            } else {
+                self.patterns
+            };
+            for pattern in patterns {
+                let remaining = pubsub.punsubscribe(session_id, &pattern);
+                replies.push(confirmation("punsubscribe", Some(pattern), remaining));
+            }
+            count = pubsub.subscription_count(session_id);
+        }
+        if replies.is_empty() {
+            replies.push(confirmation("punsubscribe", None, count));
+        }
+        Ok(reply_confirmations(handler, replies, count).await)
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+use super::{confirmation, reply_confirmations};
+
+/// SUBSCRIBE 命令：订阅一个或多个频道
+///
+/// 订阅后会话进入订阅模式，只允许执行订阅相关命令与 PING
+#[derive(Clone)]
+pub struct Subscribe {
+    channels: Vec<String>,
+}
+
+impl Subscribe {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 2 {
+            return Err(Error::msg("ERR wrong number of arguments for 'subscribe' command"));
+        }
+        let channels = args[1..].to_vec();
+        Ok(Subscribe { channels })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let session_id = handler.get_session().get_id();
+        let mut replies = Vec::new();
+        let mut count = 0;
+        {
+            let mut pubsub = handler.get_state().pubsub.lock().await;
+            for channel in self.channels {
+                count = pubsub.subscribe(session_id, &channel);
+                replies.push(confirmation("subscribe", Some(channel), count));
+            }
+        }
+        Ok(reply_confirmations(handler, replies, count).await)
+    }
+}
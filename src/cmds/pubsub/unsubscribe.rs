@@ -0,0 +1,42 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+use super::{confirmation, reply_confirmations};
+
+/// UNSUBSCRIBE 命令：退订频道
+///
+/// 不带参数时退订该会话的所有频道
+#[derive(Clone)]
+pub struct Unsubscribe {
+    channels: Vec<String>,
+}
+
+impl Unsubscribe {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let channels = frame.get_args_from_index(1);
+        Ok(Unsubscribe { channels })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let session_id = handler.get_session().get_id();
+        let mut replies = Vec::new();
+        let count;
+        {
+            let mut pubsub = handler.get_state().pubsub.lock().await;
+            let channels = if self.channels.is_empty() {
+                pubsub.session_channels(session_id)
+            } else {
+                self.channels
+            };
+            for channel in channels {
+                let remaining = pubsub.unsubscribe(session_id, &channel);
+                replies.push(confirmation("unsubscribe", Some(channel), remaining));
+            }
+            count = pubsub.subscription_count(session_id);
+        }
+        if replies.is_empty() {
+            replies.push(confirmation("unsubscribe", None, count));
+        }
+        Ok(reply_confirmations(handler, replies, count).await)
+    }
+}
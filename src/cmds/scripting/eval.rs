@@ -0,0 +1,59 @@
+use anyhow::Error;
+use mlua::Lua;
+
+use crate::{acl::User, frame::Frame, server::Handler, store::db::Db};
+use crate::store::script_cache::ScriptCache;
+
+/**
+ * EVAL 命令：执行 Lua 脚本
+ *
+ * EVAL script numkeys [key [key ...]] [arg [arg ...]]
+ *
+ * 脚本执行期间不会穿插其他客户端的命令；执行前脚本会被缓存，之后可以通过 EVALSHA 按摘要调用
+ */
+#[derive(Clone)]
+pub struct Eval {
+    script: String,
+    keys: Vec<Vec<u8>>,
+    args: Vec<Vec<u8>>,
+}
+
+impl Eval {
+
+    pub fn new(script: String, keys: Vec<Vec<u8>>, args: Vec<Vec<u8>>) -> Self {
+        Eval { script, keys, args }
+    }
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let script = match frame.get_arg(1) {
+            Some(script) => script,
+            None => return Err(Error::msg("ERR wrong number of arguments for 'eval' command")),
+        };
+        let (keys, args) = super::parse_keys_and_args(&frame, "eval")?;
+        Ok(Eval::new(script, keys, args))
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        {
+            let mut scripts = handler.get_state().scripts.lock().await;
+            if !scripts.exists(&ScriptCache::sha1hex(self.script.as_bytes())) {
+                if let Err(frame) = super::compile(&self.script) {
+                    return Ok(frame);
+                }
+                scripts.load(&self.script);
+            }
+        }
+        super::run(handler, self).await
+    }
+
+    /**
+     * 在 Db 线程中执行脚本
+     *
+     * @param lua 数据库的 Lua 虚拟机
+     * @param user 执行脚本的用户，为 None 时不检查权限
+     * @return (脚本回复, 需要传播的写命令)
+     */
+    pub fn execute(self, db: &mut Db, lua: &Lua, user: Option<&User>) -> (Frame, Vec<Frame>) {
+        super::execute(db, lua, user, &self.script, self.keys, self.args)
+    }
+}
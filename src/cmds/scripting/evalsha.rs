@@ -0,0 +1,37 @@
+use anyhow::Error;
+
+use crate::{frame::Frame, server::Handler};
+
+use super::eval::Eval;
+
+/**
+ * EVALSHA 命令：按 SHA1 摘要执行已缓存的脚本
+ *
+ * EVALSHA sha1 numkeys [key [key ...]] [arg [arg ...]]
+ */
+#[derive(Clone)]
+pub struct Evalsha {
+    sha: String,
+    keys: Vec<Vec<u8>>,
+    args: Vec<Vec<u8>>,
+}
+
+impl Evalsha {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let sha = match frame.get_arg(1) {
+            Some(sha) => sha,
+            None => return Err(Error::msg("ERR wrong number of arguments for 'evalsha' command")),
+        };
+        let (keys, args) = super::parse_keys_and_args(&frame, "evalsha")?;
+        Ok(Evalsha { sha, keys, args })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let script = handler.get_state().scripts.lock().await.get(&self.sha);
+        match script {
+            Some(script) => super::run(handler, Eval::new(script, self.keys, self.args)).await,
+            None => Ok(Frame::Error("NOSCRIPT No matching script. Please use EVAL.".to_string())),
+        }
+    }
+}
@@ -0,0 +1,387 @@
+pub mod eval;
+pub mod evalsha;
+pub mod script;
+
+use std::sync::Arc;
+
+use anyhow::Error;
+use mlua::{HookTriggers, Lua, LuaOptions, StdLib, Table, Value, Variadic};
+use tokio::sync::oneshot;
+
+use crate::acl::User;
+use crate::command::Command;
+use crate::config::Config;
+use crate::frame::Frame;
+use crate::server::Handler;
+use crate::store::db::{DatabaseMessage, Db, Mailbox};
+use crate::store::script_cache::ScriptCache;
+use crate::store::script_status::{ScriptStatus, KILLED_MESSAGE};
+
+use self::eval::Eval;
+
+/**
+ * 在 redis 表上定义 redis.call，并禁止脚本读写未定义的全局变量
+ *
+ * 脚本被 SCRIPT KILL 终止后，pcall 与 xpcall 不再捕获错误，保证脚本能够退出；
+ * 参数为判断脚本是否已被终止的函数
+ */
+const PRELUDE: &str = r#"
+local script_killed = ...
+local lua_pcall, lua_xpcall, lua_error = pcall, xpcall, error
+local function rethrow_if_killed(ok, ...)
+    if not ok and script_killed() then
+        lua_error((...), 0)
+    end
+    return ok, ...
+end
+pcall = function(...)
+    return rethrow_if_killed(lua_pcall(...))
+end
+xpcall = function(f, handler)
+    return rethrow_if_killed(lua_xpcall(f, handler))
+end
+redis.call = function(...)
+    local reply = redis.pcall(...)
+    if type(reply) == 'table' and reply.err ~= nil then
+        error(reply)
+    end
+    return reply
+end
+setmetatable(_G, {
+    __newindex = function(_, name)
+        error("Script attempted to create global variable '" .. tostring(name) .. "'", 2)
+    end,
+    __index = function(_, name)
+        error("Script attempted to access nonexistent global variable '" .. tostring(name) .. "'", 2)
+    end,
+})
+"#;
+
+/// 每执行多少条 Lua 指令检查一次脚本是否超时或被终止
+const HOOK_INSTRUCTIONS: u32 = 100_000;
+
+/// 传入脚本的 KEYS 与 ARGV
+type KeysAndArgs = (Vec<Vec<u8>>, Vec<Vec<u8>>);
+
+/**
+ * 解析 EVAL / EVALSHA 的 numkeys、键与参数
+ *
+ * @param frame 命令
+ * @param name 命令名，用于错误信息
+ * @return (键列表, 参数列表)
+ */
+fn parse_keys_and_args(frame: &Frame, name: &str) -> Result<KeysAndArgs, Error> {
+    let numkeys = match frame.get_arg(2) {
+        Some(numkeys) => numkeys,
+        None => return Err(Error::msg(format!("ERR wrong number of arguments for '{}' command", name))),
+    };
+    let numkeys = numkeys.parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+    if numkeys < 0 {
+        return Err(Error::msg("ERR Number of keys can't be negative"));
+    }
+
+    let mut keys = frame.get_args_bytes_from_index(3);
+    if numkeys as usize > keys.len() {
+        return Err(Error::msg("ERR Number of keys can't be greater than number of args"));
+    }
+    let args = keys.split_off(numkeys as usize);
+    Ok((keys, args))
+}
+
+/**
+ * 检查脚本能否编译
+ *
+ * @param script 脚本内容
+ * @return 编译失败时返回错误回复
+ */
+pub fn compile(script: &str) -> Result<(), Frame> {
+    let lua = Lua::new_with(StdLib::NONE, LuaOptions::default()).map_err(|e| Frame::Error(format!("ERR {}", e)))?;
+    lua.load(script)
+        .set_name("@user_script")
+        .into_function()
+        .map(|_| ())
+        .map_err(|e| match e {
+            mlua::Error::SyntaxError { message, .. } => Frame::Error(format!("ERR Error compiling script (new function): {}", message)),
+            e => Frame::Error(format!("ERR {}", e)),
+        })
+}
+
+/**
+ * 在会话当前数据库中执行脚本，并传播脚本执行过的写命令
+ *
+ * 脚本在 Db 线程中一次性执行完毕，期间不会穿插其他客户端的命令；
+ * 传播的是脚本产生的写命令而不是脚本本身，AOF 重放与从节点无需依赖脚本缓存，结果也与主节点一致
+ *
+ * @param handler 当前会话
+ * @param eval 待执行的脚本
+ */
+pub async fn run(handler: &mut Handler, eval: Eval) -> Result<Frame, Error> {
+    let (sender, receiver) = oneshot::channel();
+    let message = DatabaseMessage::Eval { sender, eval, user: handler.current_user() };
+    if let Err(e) = handler.get_session().get_sender().send(message).await {
+        return Ok(Frame::Error(format!("Channel closed: {:?}", e)));
+    }
+    let (reply, effects) = match receiver.await {
+        Ok(result) => result,
+        Err(e) => return Ok(Frame::Error(format!("{:?}", e))),
+    };
+    for effect in effects {
+        handler.propagate(effect).await;
+    }
+    Ok(reply)
+}
+
+/**
+ * 创建数据库的 Lua 虚拟机
+ *
+ * 虚拟机在数据库的整个生命周期内重复使用：redis 库与全局变量保护只初始化一次，
+ * KEYS、ARGV 与 redis.pcall 在每次执行脚本时重新设置。
+ * 指令钩子定期检查脚本执行时间，超过 lua-time-limit 后对其他客户端回复 BUSY，被 SCRIPT KILL 终止时抛出错误
+ *
+ * @param status 数据库的脚本状态
+ * @param config 运行时配置
+ * @param mailbox 数据库的消息队列
+ */
+pub fn create_vm(status: Arc<ScriptStatus>, config: Arc<Config>, mailbox: Mailbox) -> mlua::Result<Lua> {
+    let lua = Lua::new_with(StdLib::TABLE | StdLib::STRING | StdLib::MATH, LuaOptions::default())?;
+    load_redis_lib(&lua, status.clone())?;
+    lua.set_hook(HookTriggers::new().every_nth_instruction(HOOK_INSTRUCTIONS), move |_, _| {
+        if status.is_killed() {
+            return Err(mlua::Error::runtime(KILLED_MESSAGE));
+        }
+        if status.check_time(config.values().lua_time_limit) {
+            mailbox.reject_busy();
+        }
+        Ok(())
+    });
+    Ok(lua)
+}
+
+/// 设置 redis 库，并禁止访问文件系统与创建全局变量
+fn load_redis_lib(lua: &Lua, status: Arc<ScriptStatus>) -> mlua::Result<()> {
+    let globals = lua.globals();
+    // 禁止访问文件系统
+    globals.set("dofile", Value::Nil)?;
+    globals.set("loadfile", Value::Nil)?;
+    globals.set("KEYS", lua.create_table()?)?;
+    globals.set("ARGV", lua.create_table()?)?;
+
+    let redis = lua.create_table()?;
+    redis.set("sha1hex", lua.create_function(|_, data: mlua::String| Ok(ScriptCache::sha1hex(data.as_bytes())))?)?;
+    redis.set("error_reply", lua.create_function(|lua, message: String| reply_table(lua, "err", message))?)?;
+    redis.set("status_reply", lua.create_function(|lua, message: String| reply_table(lua, "ok", message))?)?;
+    redis.set("replicate_commands", lua.create_function(|_, ()| Ok(true))?)?;
+    redis.set("log", lua.create_function(|_, (level, message): (i64, Variadic<String>)| {
+        let message = message.join(" ");
+        match level {
+            0 => log::debug!("{}", message),
+            1 | 2 => log::info!("{}", message),
+            _ => log::warn!("{}", message),
+        }
+        Ok(())
+    })?)?;
+    redis.set("LOG_DEBUG", 0)?;
+    redis.set("LOG_VERBOSE", 1)?;
+    redis.set("LOG_NOTICE", 2)?;
+    redis.set("LOG_WARNING", 3)?;
+    globals.set("redis", redis)?;
+    let script_killed = lua.create_function(move |_, ()| Ok(status.is_killed()))?;
+    lua.load(PRELUDE).call(script_killed)
+}
+
+/**
+ * 执行脚本
+ *
+ * @param db 数据库
+ * @param lua 数据库的 Lua 虚拟机
+ * @param user 执行脚本的用户，为 None 时不检查权限
+ * @param script 脚本内容
+ * @param keys 传入脚本的 KEYS
+ * @param args 传入脚本的 ARGV
+ * @return (脚本回复, 需要传播的写命令)
+ */
+pub fn execute(db: &mut Db, lua: &Lua, user: Option<&User>, script: &str, keys: Vec<Vec<u8>>, args: Vec<Vec<u8>>) -> (Frame, Vec<Frame>) {
+    let mut effects = Vec::new();
+    let status = db.script.clone();
+    status.start();
+    let result = execute_lua(db, lua, user, script, keys, args, &mut effects);
+    if status.finish() {
+        return (running_error(script, KILLED_MESSAGE), effects);
+    }
+    let reply = match result {
+        Ok(reply) => reply,
+        Err(mlua::Error::SyntaxError { message, .. }) => {
+            Frame::Error(format!("ERR Error compiling script (new function): {}", message))
+        },
+        Err(e) => running_error(script, &e.to_string()),
+    };
+    (reply, effects)
+}
+
+/// 脚本运行期错误的回复
+fn running_error(script: &str, message: &str) -> Frame {
+    let sha = ScriptCache::sha1hex(script.as_bytes());
+    Frame::Error(format!("ERR Error running script (call to f_{}): {}", sha, message))
+}
+
+fn execute_lua(db: &mut Db, lua: &Lua, user: Option<&User>, script: &str, keys: Vec<Vec<u8>>, args: Vec<Vec<u8>>, effects: &mut Vec<Frame>) -> mlua::Result<Frame> {
+    let globals = lua.globals();
+    // _G 的元表禁止创建全局变量，KEYS 与 ARGV 需要绕过元表设置
+    globals.raw_set("KEYS", lua.create_sequence_from(keys.iter().map(|key| lua.create_string(key)).collect::<mlua::Result<Vec<_>>>()?)?)?;
+    globals.raw_set("ARGV", lua.create_sequence_from(args.iter().map(|arg| lua.create_string(arg)).collect::<mlua::Result<Vec<_>>>()?)?)?;
+    let redis: Table = globals.raw_get("redis")?;
+
+    lua.scope(|scope| {
+        redis.set("pcall", scope.create_function_mut(|lua, args: Variadic<Value>| call(lua, db, user, effects, args))?)?;
+
+        let function = lua.load(script).set_name("@user_script").into_function()?;
+        let (ok, result): (bool, Value) = lua.load("return pcall(...)").call(function)?;
+        if ok {
+            return Ok(lua_to_frame(result));
+        }
+        match result {
+            // redis.call 返回的错误原样回复给客户端
+            Value::Table(table) => match table.raw_get::<_, Option<String>>("err")? {
+                Some(err) => Ok(Frame::Error(err)),
+                None => Ok(running_error(script, "unknown error")),
+            },
+            other => {
+                let message = lua.coerce_string(other)?.map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+                Ok(running_error(script, &message))
+            },
+        }
+    })
+}
+
+/**
+ * redis.pcall：执行命令并将回复转换为 Lua 值，错误以 {err = ...} 表的形式返回
+ */
+fn call<'lua>(lua: &'lua Lua, db: &mut Db, user: Option<&User>, effects: &mut Vec<Frame>, args: Variadic<Value<'lua>>) -> mlua::Result<Value<'lua>> {
+    if args.is_empty() {
+        return reply_table(lua, "err", "ERR Please specify at least one argument for this redis lib call".to_string()).map(Value::Table);
+    }
+
+    let mut items = Vec::with_capacity(args.len());
+    for arg in args.iter() {
+        let bytes = match arg {
+            Value::String(s) => s.as_bytes().to_vec(),
+            Value::Integer(i) => i.to_string().into_bytes(),
+            Value::Number(n) => n.to_string().into_bytes(),
+            _ => {
+                let f = "ERR Lua redis lib command arguments must be strings or integers";
+                return reply_table(lua, "err", f.to_string()).map(Value::Table);
+            },
+        };
+        items.push(Frame::BulkString(bytes));
+    }
+    let frame = Frame::Array(items);
+
+    let command = match Command::parse_from_frame(frame.clone()) {
+        Ok(Command::Unknown(_)) => {
+            return reply_table(lua, "err", "ERR Unknown Redis command called from script".to_string()).map(Value::Table);
+        },
+        Ok(command) => command,
+        Err(e) => return reply_table(lua, "err", e.to_string()).map(Value::Table),
+    };
+    // 脚本中的命令与客户端直接执行的命令遵循相同的 ACL 权限
+    if let Some(user) = user {
+        if let Err(denied) = user.check(&frame.get_args_bytes()) {
+            return reply_table(lua, "err", denied.to_error(user.name())).map(Value::Table);
+        }
+    }
+
+    let should_propagate = command.propagate_aof_if_needed();
+    // 执行过写命令的脚本不能再被 SCRIPT KILL 终止；已经被终止的脚本不能再写入
+    if should_propagate && !db.script.record_write() {
+        return Err(mlua::Error::runtime(KILLED_MESSAGE));
+    }
+
+    // 只允许直接作用于数据库的命令，阻塞、订阅、切换数据库等依赖会话的命令不能在脚本中执行
+    let reply = match db.handle_command(command) {
+        Ok(reply) => reply,
+        Err(_) => {
+            return reply_table(lua, "err", "ERR This Redis command is not allowed from script".to_string()).map(Value::Table);
+        },
+    };
+    if should_propagate && !matches!(reply, Frame::Error(_)) {
+        if let Some(propagated) = Command::propagation_frame(frame, &reply) {
+            effects.push(propagated);
+        }
+    }
+    frame_to_lua(lua, reply)
+}
+
+/// 构造 {ok = ...} 或 {err = ...} 形式的回复表
+fn reply_table<'lua>(lua: &'lua Lua, field: &str, message: String) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set(field, message)?;
+    Ok(table)
+}
+
+/**
+ * 将命令回复转换为 Lua 值（与 Redis 的 RESP2 转换规则一致）
+ *
+ * 整数 -> number，批量字符串 -> string，数组 -> table，状态回复 -> {ok = ...}，
+ * 错误回复 -> {err = ...}，空回复 -> false
+ */
+fn frame_to_lua(lua: &Lua, frame: Frame) -> mlua::Result<Value<'_>> {
+    let value = match frame {
+        Frame::Ok => Value::Table(reply_table(lua, "ok", "OK".to_string())?),
+        Frame::SimpleString(s) => Value::Table(reply_table(lua, "ok", s)?),
+        Frame::Error(e) => Value::Table(reply_table(lua, "err", e)?),
+        Frame::Integer(i) => Value::Integer(i),
+        Frame::BulkString(bytes) | Frame::Verbatim(_, bytes) => Value::String(lua.create_string(bytes)?),
+        Frame::BigNumber(s) => Value::String(lua.create_string(s)?),
+        Frame::Double(d) => Value::String(lua.create_string(Frame::format_double(d))?),
+        Frame::Boolean(true) => Value::Integer(1),
+        Frame::Boolean(false) | Frame::Null | Frame::NullArray | Frame::RDBFile(_) => Value::Boolean(false),
+        Frame::Array(items) | Frame::Set(items) | Frame::Push(items) => {
+            let table = lua.create_table()?;
+            for item in items {
+                table.raw_push(frame_to_lua(lua, item)?)?;
+            }
+            Value::Table(table)
+        },
+        Frame::Map(entries) => {
+            let table = lua.create_table()?;
+            for (field, value) in entries {
+                table.raw_push(frame_to_lua(lua, field)?)?;
+                table.raw_push(frame_to_lua(lua, value)?)?;
+            }
+            Value::Table(table)
+        },
+    };
+    Ok(value)
+}
+
+/**
+ * 将脚本返回值转换为命令回复
+ *
+ * number -> 整数（小数部分被截断），string -> 批量字符串，true -> 1，false/nil -> 空回复，
+ * {ok = ...} -> 状态回复，{err = ...} -> 错误回复，其他 table 按数组转换直到第一个 nil
+ */
+fn lua_to_frame(value: Value) -> Frame {
+    match value {
+        Value::Integer(i) => Frame::Integer(i),
+        Value::Number(n) => Frame::Integer(n as i64),
+        Value::String(s) => Frame::BulkString(s.as_bytes().to_vec()),
+        Value::Boolean(true) => Frame::Integer(1),
+        Value::Table(table) => {
+            if let Ok(Some(err)) = table.raw_get::<_, Option<String>>("err") {
+                return Frame::Error(err);
+            }
+            if let Ok(Some(ok)) = table.raw_get::<_, Option<String>>("ok") {
+                return Frame::SimpleString(ok);
+            }
+            let mut items = Vec::new();
+            for index in 1.. {
+                match table.raw_get::<_, Value>(index) {
+                    Ok(Value::Nil) | Err(_) => break,
+                    Ok(value) => items.push(lua_to_frame(value)),
+                }
+            }
+            Frame::Array(items)
+        },
+        _ => Frame::Null,
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Error;
+
+use crate::{frame::Frame, server::Handler, store::script_status::KillResult};
+
+#[derive(Clone)]
+enum Subcommand {
+    Load(String),
+    Exists(Vec<String>),
+    Flush,
+    Kill,
+}
+
+/**
+ * SCRIPT 命令：管理脚本缓存
+ *
+ * SCRIPT LOAD script：编译并缓存脚本，返回 SHA1 摘要
+ * SCRIPT EXISTS sha1 [sha1 ...]：返回各摘要对应的脚本是否已缓存
+ * SCRIPT FLUSH [ASYNC | SYNC]：清空脚本缓存
+ * SCRIPT KILL：终止超时且没有执行过写命令的脚本
+ */
+#[derive(Clone)]
+pub struct Script {
+    subcommand: Subcommand,
+}
+
+impl Script {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        let name = match args.get(1) {
+            Some(name) => name.to_uppercase(),
+            None => return Err(Error::msg("ERR wrong number of arguments for 'script' command")),
+        };
+        let subcommand = match (name.as_str(), args.len()) {
+            ("LOAD", 3) => Subcommand::Load(args[2].clone()),
+            ("EXISTS", len) if len > 2 => Subcommand::Exists(args[2..].to_vec()),
+            ("FLUSH", 2) => Subcommand::Flush,
+            ("FLUSH", 3) if args[2].eq_ignore_ascii_case("ASYNC") || args[2].eq_ignore_ascii_case("SYNC") => Subcommand::Flush,
+            ("FLUSH", _) => return Err(Error::msg("ERR SCRIPT FLUSH only support SYNC|ASYNC option")),
+            ("KILL", 2) => Subcommand::Kill,
+            ("LOAD", _) | ("EXISTS", _) | ("KILL", _) => {
+                return Err(Error::msg(format!("ERR wrong number of arguments for 'script|{}' command", name.to_lowercase())));
+            },
+            _ => return Err(Error::msg(format!("ERR unknown subcommand '{}'. Try SCRIPT HELP.", args[1]))),
+        };
+        Ok(Script { subcommand })
+    }
+
+    /// 是否为 SCRIPT KILL（脚本超时期间仍然可以执行）
+    pub fn is_kill(&self) -> bool {
+        matches!(self.subcommand, Subcommand::Kill)
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        if self.is_kill() {
+            let reply = match handler.get_db_manager().kill_script() {
+                KillResult::Killed => Frame::Ok,
+                KillResult::NotBusy => Frame::Error("NOTBUSY No scripts in execution right now.".to_string()),
+                KillResult::Unkillable => Frame::Error("UNKILLABLE Sorry the script already executed write commands against the dataset. You can either wait the script termination or kill the server in a hard way using the SHUTDOWN NOSAVE command.".to_string()),
+            };
+            return Ok(reply);
+        }
+        let mut scripts = handler.get_state().scripts.lock().await;
+        match self.subcommand {
+            Subcommand::Load(script) => {
+                if let Err(frame) = super::compile(&script) {
+                    return Ok(frame);
+                }
+                Ok(Frame::BulkString(scripts.load(&script).into()))
+            },
+            Subcommand::Exists(shas) => {
+                let exists = shas.iter().map(|sha| Frame::Integer(scripts.exists(sha) as i64)).collect();
+                Ok(Frame::Array(exists))
+            },
+            Subcommand::Flush => {
+                scripts.flush();
+                Ok(Frame::Ok)
+            },
+            Subcommand::Kill => unreachable!(),
+        }
+    }
+}
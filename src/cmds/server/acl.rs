@@ -0,0 +1,147 @@
+use anyhow::Error;
+
+use crate::{acl::command_table::{self, CATEGORIES}, frame::Frame, server::Handler};
+
+enum Subcommand {
+    Setuser(String, Vec<String>),
+    Deluser(Vec<String>),
+    Getuser(String),
+    List,
+    Users,
+    Whoami,
+    Cat(Option<String>),
+    Dryrun(String, Vec<String>),
+    Save,
+    Load,
+}
+
+/**
+ * ACL 命令：管理 ACL 用户
+ *
+ * ACL SETUSER username [rule ...]：创建或修改用户
+ * ACL DELUSER username [username ...]：删除用户，返回删除的数量
+ * ACL GETUSER username：获取用户的规则
+ * ACL LIST：以 ACL 文件格式列出所有用户
+ * ACL USERS：列出所有用户名
+ * ACL WHOAMI：当前连接的用户名
+ * ACL CAT [category]：列出所有类别，或类别下的所有命令
+ * ACL DRYRUN username command [arg ...]：检查用户能否执行命令
+ * ACL SAVE / ACL LOAD：将用户保存到 ACL 文件 / 从 ACL 文件重新加载用户
+ */
+pub struct Acl {
+    subcommand: Subcommand,
+}
+
+impl Acl {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        let name = match args.get(1) {
+            Some(name) => name.to_uppercase(),
+            None => return Err(Error::msg("ERR wrong number of arguments for 'acl' command")),
+        };
+        let subcommand = match (name.as_str(), args.len()) {
+            ("SETUSER", len) if len >= 3 => Subcommand::Setuser(args[2].clone(), args[3..].to_vec()),
+            ("DELUSER", len) if len >= 3 => Subcommand::Deluser(args[2..].to_vec()),
+            ("GETUSER", 3) => Subcommand::Getuser(args[2].clone()),
+            ("LIST", 2) => Subcommand::List,
+            ("USERS", 2) => Subcommand::Users,
+            ("WHOAMI", 2) => Subcommand::Whoami,
+            ("CAT", 2) => Subcommand::Cat(None),
+            ("CAT", 3) => Subcommand::Cat(Some(args[2].to_lowercase())),
+            ("DRYRUN", len) if len >= 4 => Subcommand::Dryrun(args[2].clone(), args[3..].to_vec()),
+            ("SAVE", 2) => Subcommand::Save,
+            ("LOAD", 2) => Subcommand::Load,
+            ("SETUSER", _) | ("DELUSER", _) | ("GETUSER", _) | ("LIST", _) | ("USERS", _) | ("WHOAMI", _)
+            | ("CAT", _) | ("DRYRUN", _) | ("SAVE", _) | ("LOAD", _) => {
+                return Err(Error::msg(format!("ERR wrong number of arguments for 'acl|{}' command", name.to_lowercase())));
+            },
+            _ => return Err(Error::msg(format!("ERR unknown subcommand '{}'. Try ACL HELP.", args[1]))),
+        };
+        Ok(Acl { subcommand })
+    }
+
+    pub fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let state = handler.get_state().clone();
+        match self.subcommand {
+            Subcommand::Setuser(name, rules) => {
+                match state.acl.write().unwrap().set_user(&name, &rules) {
+                    Ok(_) => Ok(Frame::Ok),
+                    Err(e) => Ok(Frame::Error(e.to_string())),
+                }
+            },
+            Subcommand::Deluser(names) => {
+                match state.acl.write().unwrap().del_users(&names) {
+                    Ok(count) => Ok(Frame::Integer(count as i64)),
+                    Err(e) => Ok(Frame::Error(e.to_string())),
+                }
+            },
+            Subcommand::Getuser(name) => {
+                let acl = state.acl.read().unwrap();
+                let user = match acl.get_user(&name) {
+                    Some(user) => user,
+                    None => return Ok(Frame::Null),
+                };
+                let bulk = |value: String| Frame::BulkString(value.into());
+                Ok(Frame::Map(vec![
+                    (bulk("flags".to_string()), Frame::Array(user.flags().into_iter().map(|flag| bulk(flag.to_string())).collect())),
+                    (bulk("passwords".to_string()), Frame::Array(user.passwords().into_iter().map(bulk).collect())),
+                    (bulk("commands".to_string()), bulk(user.describe_commands())),
+                    (bulk("keys".to_string()), bulk(user.describe_keys())),
+                    (bulk("channels".to_string()), bulk(user.describe_channels())),
+                    (bulk("selectors".to_string()), Frame::Array(Vec::new())),
+                ]))
+            },
+            Subcommand::List => {
+                let acl = state.acl.read().unwrap();
+                Ok(Frame::Array(acl.users().map(|user| Frame::BulkString(user.describe().into())).collect()))
+            },
+            Subcommand::Users => {
+                let acl = state.acl.read().unwrap();
+                Ok(Frame::Array(acl.users().map(|user| Frame::BulkString(user.name().to_string().into())).collect()))
+            },
+            Subcommand::Whoami => {
+                Ok(Frame::BulkString(handler.get_session().get_user().clone().into()))
+            },
+            Subcommand::Cat(None) => {
+                Ok(Frame::Array(CATEGORIES.iter().map(|category| Frame::BulkString(category.to_string().into())).collect()))
+            },
+            Subcommand::Cat(Some(category)) => {
+                if !CATEGORIES.contains(&category.as_str()) {
+                    return Ok(Frame::Error(format!("ERR Unknown category '{}'", category)));
+                }
+                let commands = command_table::commands_in_category(&category);
+                Ok(Frame::Array(commands.into_iter().map(|command| Frame::BulkString(command.to_string().into())).collect()))
+            },
+            Subcommand::Dryrun(name, args) => {
+                let acl = state.acl.read().unwrap();
+                let user = match acl.get_user(&name) {
+                    Some(user) => user,
+                    None => return Ok(Frame::Error(format!("ERR User '{}' not found", name))),
+                };
+                if !command_table::exists(&args[0].to_lowercase()) {
+                    return Ok(Frame::Error(format!("ERR Command '{}' not found", args[0])));
+                }
+                match user.check(&args) {
+                    Ok(_) => Ok(Frame::Ok),
+                    Err(denied) => {
+                        let message = denied.to_error(&name);
+                        Ok(Frame::BulkString(message.trim_start_matches("NOPERM ").to_string().into()))
+                    },
+                }
+            },
+            Subcommand::Save => {
+                match state.acl.read().unwrap().save() {
+                    Ok(_) => Ok(Frame::Ok),
+                    Err(e) => Ok(Frame::Error(e.to_string())),
+                }
+            },
+            Subcommand::Load => {
+                match state.acl.write().unwrap().load() {
+                    Ok(_) => Ok(Frame::Ok),
+                    Err(e) => Ok(Frame::Error(e.to_string())),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Error;
+
+use crate::{frame::Frame, server::Handler};
+
+/**
+ * BGREWRITEAOF 命令：在后台重写 AOF 文件
+ *
+ * 根据当前数据生成新文件（RDB 快照或最少的命令），重写期间的写命令追加到新文件末尾，完成后替换旧文件
+ */
+pub struct Bgrewriteaof {}
+
+impl Bgrewriteaof {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        if frame.get_args().len() != 1 {
+            return Err(Error::msg("ERR wrong number of arguments for 'bgrewriteaof' command"));
+        }
+        Ok(Bgrewriteaof {})
+    }
+
+    pub fn apply(self, handler: &Handler) -> Result<Frame, Error> {
+        let aof_file = match &handler.get_state().aof {
+            Some(aof_file) => aof_file,
+            None => return Ok(Frame::Error("ERR Background append only file rewriting is not available when appendonly is no".to_string())),
+        };
+        let use_preamble = handler.get_state().config.values().aof_use_rdb_preamble;
+        match aof_file.rewrite(handler.get_db_manager().get_senders(), use_preamble) {
+            Ok(_) => Ok(Frame::SimpleString("Background append only file rewriting started".to_string())),
+            Err(e) => Ok(Frame::Error(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::Error;
+
+use crate::{frame::Frame, server::Handler};
+
+enum Subcommand {
+    Get(Vec<String>),
+    Set(Vec<(String, String)>),
+    Resetstat,
+    Rewrite,
+}
+
+/**
+ * CONFIG 命令：在运行时查看与修改配置
+ *
+ * CONFIG GET parameter [parameter ...]：获取匹配 glob 模式的配置项
+ * CONFIG SET parameter value [parameter value ...]：修改配置，立即生效
+ * CONFIG RESETSTAT：清零 INFO 中的统计信息
+ * CONFIG REWRITE：将当前配置写回启动时读取的配置文件
+ */
+pub struct Config {
+    subcommand: Subcommand,
+}
+
+impl Config {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        let name = match args.get(1) {
+            Some(name) => name.to_uppercase(),
+            None => return Err(Error::msg("ERR wrong number of arguments for 'config' command")),
+        };
+        let subcommand = match (name.as_str(), args.len()) {
+            ("GET", len) if len >= 3 => Subcommand::Get(args[2..].to_vec()),
+            ("SET", len) if len >= 4 && len % 2 == 0 => {
+                Subcommand::Set(args[2..].chunks(2).map(|pair| (pair[0].clone(), pair[1].clone())).collect())
+            },
+            ("RESETSTAT", 2) => Subcommand::Resetstat,
+            ("REWRITE", 2) => Subcommand::Rewrite,
+            ("GET", _) | ("SET", _) | ("RESETSTAT", _) | ("REWRITE", _) => {
+                return Err(Error::msg(format!("ERR wrong number of arguments for 'config|{}' command", name.to_lowercase())));
+            },
+            _ => return Err(Error::msg(format!("ERR unknown subcommand '{}'. Try CONFIG HELP.", args[1]))),
+        };
+        Ok(Config { subcommand })
+    }
+
+    pub fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let state = handler.get_state().clone();
+        match self.subcommand {
+            Subcommand::Get(patterns) => {
+                let parameters = state.config.get(&patterns)
+                    .into_iter()
+                    .map(|(name, value)| (Frame::BulkString(name.into()), Frame::BulkString(value.into())))
+                    .collect();
+                Ok(Frame::Map(parameters))
+            },
+            Subcommand::Set(pairs) => {
+                if let Err(e) = state.config.set(&pairs) {
+                    return Ok(Frame::Error(e.to_string()));
+                }
+                // requirepass 即默认用户的密码
+                if pairs.iter().any(|(name, _)| name.eq_ignore_ascii_case("requirepass")) {
+                    let requirepass = state.config.values().requirepass.clone();
+                    state.acl.write().unwrap().set_requirepass(requirepass);
+                }
+                Ok(Frame::Ok)
+            },
+            Subcommand::Resetstat => {
+                state.stats.reset();
+                handler.get_db_manager().get_memory().reset_stats();
+                Ok(Frame::Ok)
+            },
+            Subcommand::Rewrite => {
+                match state.config.rewrite() {
+                    Ok(_) => Ok(Frame::Ok),
+                    Err(e) => Ok(Frame::Error(e.to_string())),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use tokio::sync::oneshot;
+
+use crate::{frame::Frame, persistence::{rdb_file::write_atomically, redis_rdb}, server::Handler, store::db::DatabaseMessage};
+
+enum Subcommand {
+    RdbExport(String),
+    RdbImport(String),
+}
+
+/**
+ * DEBUG 命令：管理与诊断
+ *
+ * DEBUG RDB-EXPORT path：将当前数据导出为 Redis RDB 文件，供 Redis 加载
+ * DEBUG RDB-IMPORT path：从 Redis RDB 文件加载数据，替换所有数据库的内容
+ */
+pub struct Debug {
+    subcommand: Subcommand,
+}
+
+impl Debug {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        let name = match args.get(1) {
+            Some(name) => name.to_uppercase(),
+            None => return Err(Error::msg("ERR wrong number of arguments for 'debug' command")),
+        };
+        let subcommand = match (name.as_str(), args.len()) {
+            ("RDB-EXPORT", 3) => Subcommand::RdbExport(args[2].clone()),
+            ("RDB-IMPORT", 3) => Subcommand::RdbImport(args[2].clone()),
+            ("RDB-EXPORT", _) | ("RDB-IMPORT", _) => {
+                return Err(Error::msg(format!("ERR wrong number of arguments for 'debug|{}' command", name.to_lowercase())));
+            },
+            _ => return Err(Error::msg(format!("ERR unknown subcommand '{}'. Try DEBUG HELP.", args[1]))),
+        };
+        Ok(Debug { subcommand })
+    }
+
+    pub async fn apply(self, handler: &Handler) -> Result<Frame, Error> {
+        match self.subcommand {
+            Subcommand::RdbExport(path) => Self::export(handler, path).await,
+            Subcommand::RdbImport(path) => Self::import(handler, path).await,
+        }
+    }
+
+    async fn export(handler: &Handler, path: String) -> Result<Frame, Error> {
+        let mut databases = HashMap::new();
+        for (index, sender) in handler.get_db_manager().get_senders().iter().enumerate() {
+            let (tx, rx) = oneshot::channel();
+            sender.send(DatabaseMessage::Snapshot(tx)).await?;
+            databases.insert(index, rx.await?);
+        }
+        let result = tokio::task::spawn_blocking(move || write_atomically(path.as_ref(), &redis_rdb::encode(&databases))).await?;
+        match result {
+            Ok(()) => Ok(Frame::Ok),
+            Err(e) => Ok(Frame::Error(format!("ERR Error exporting the RDB dump: {}", e))),
+        }
+    }
+
+    /// 导入的数据不经过写命令，开启 AOF 时导入后立即重写 AOF，保证重启后数据一致
+    async fn import(handler: &Handler, path: String) -> Result<Frame, Error> {
+        let aof_file = handler.get_state().aof.clone();
+        if aof_file.as_ref().is_some_and(|aof_file| aof_file.status().rewrite_in_progress()) {
+            return Ok(Frame::Error("ERR Background append only file rewriting in progress".to_string()));
+        }
+        let result = tokio::task::spawn_blocking(move || {
+            let bytes = std::fs::read(&path)?;
+            redis_rdb::decode(&bytes)
+        }).await?;
+        let mut databases = match result {
+            Ok(databases) => databases,
+            Err(e) => return Ok(Frame::Error(format!("ERR Error trying to load the RDB dump: {}", e))),
+        };
+
+        let senders = handler.get_db_manager().get_senders();
+        if let Some(index) = databases.keys().find(|index| **index >= senders.len()) {
+            return Ok(Frame::Error(format!("ERR Error trying to load the RDB dump: DB index {} is out of range", index)));
+        }
+        for (index, sender) in senders.iter().enumerate() {
+            sender.send(DatabaseMessage::Restore(databases.remove(&index).unwrap_or_default())).await?;
+        }
+
+        if let Some(aof_file) = aof_file {
+            let use_preamble = handler.get_state().config.values().aof_use_rdb_preamble;
+            if let Err(e) = aof_file.rewrite(senders, use_preamble) {
+                log::warn!("Failed to rewrite the append only file after RDB import: {}", e);
+            }
+        }
+        Ok(Frame::Ok)
+    }
+}
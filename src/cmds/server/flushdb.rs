@@ -15,8 +15,7 @@ impl Flushdb {
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        db.expire_records.clear();
-        db.records.clear();
+        db.clear();
         Ok(Frame::Ok)
     }
 }
\ No newline at end of file
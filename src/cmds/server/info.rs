@@ -1,9 +1,11 @@
 use anyhow::Error;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot;
 
 use crate::{
     frame::Frame,
-    store::db::Db,
+    server::Handler,
+    store::{db::DatabaseMessage, memory},
 };
 
 pub struct Info {
@@ -23,12 +25,27 @@ impl Info {
         Ok(Info { section })
     }
 
-    pub fn apply(self, db: &Db) -> Result<Frame, Error> {
-        let info = self.generate_info(db);
-        Ok(Frame::BulkString(info))
+    pub async fn apply(self, handler: &Handler) -> Result<Frame, Error> {
+        // 收集所有数据库的键数量：(数据库索引, 键数量, 设置了过期时间的键数量)
+        let mut keyspace = Vec::new();
+        for (index, sender) in handler.get_db_manager().get_senders().iter().enumerate() {
+            let (tx, rx) = oneshot::channel();
+            if sender.send(DatabaseMessage::Keyspace(tx)).await.is_ok() {
+                if let Ok((keys, expires)) = rx.await {
+                    if keys > 0 {
+                        keyspace.push((index, keys, expires));
+                    }
+                }
+            }
+        }
+        let changes = handler.get_db_manager().get_rdb().changes().await;
+        let info = self.generate_info(handler, &keyspace, changes);
+        Ok(Frame::BulkString(info.into()))
     }
 
-    fn generate_info(&self, db: &Db) -> String {
+    fn generate_info(&self, handler: &Handler, keyspace: &[(usize, usize, usize)], changes: u64) -> String {
+        let args = handler.get_args();
+        let memory = handler.get_db_manager().get_memory();
         let mut info = String::new();
         
         // Default sections to show
@@ -69,13 +86,13 @@ impl Info {
             info.push_str("configured_hz:10\r\n");
             info.push_str("lru_clock:0\r\n");
             info.push_str("executable:/rudis-server\r\n");
-            info.push_str("config_file:rudis.conf\r\n\r\n");
+            info.push_str(&format!("config_file:{}\r\n\r\n", args.config));
         }
 
         // Clients section
         if show_clients {
             info.push_str("# Clients\r\n");
-            info.push_str("connected_clients:1\r\n");
+            info.push_str(&format!("connected_clients:{}\r\n", handler.get_session_manager().get_connection_count()));
             info.push_str("client_recent_max_input_buffer:0\r\n");
             info.push_str("client_recent_max_output_buffer:0\r\n");
             info.push_str("blocked_clients:0\r\n");
@@ -86,41 +103,70 @@ impl Info {
         // Memory section
         if show_memory {
             info.push_str("# Memory\r\n");
-            // Estimate memory usage based on the number of records
-            let memory_used = db.records.len() * 100; // Rough estimate
-            info.push_str(&format!("used_memory:{}\r\n", memory_used));
-            info.push_str(&format!("used_memory_human:{}B\r\n", memory_used));
-            info.push_str("used_memory_rss:0\r\n");
-            info.push_str("used_memory_peak:0\r\n");
-            info.push_str("used_memory_peak_human:0B\r\n");
+            // 数据集的内存用量按每个键的估算值累计
+            let used_memory = memory.used_memory();
+            let peak_memory = memory.peak_memory();
+            let rss = memory::process_rss();
+            info.push_str(&format!("used_memory:{}\r\n", used_memory));
+            info.push_str(&format!("used_memory_human:{}\r\n", memory::format_human(used_memory)));
+            info.push_str(&format!("used_memory_rss:{}\r\n", rss));
+            info.push_str(&format!("used_memory_rss_human:{}\r\n", memory::format_human(rss)));
+            info.push_str(&format!("used_memory_peak:{}\r\n", peak_memory));
+            info.push_str(&format!("used_memory_peak_human:{}\r\n", memory::format_human(peak_memory)));
+            info.push_str(&format!("used_memory_dataset:{}\r\n", used_memory));
             info.push_str("used_memory_lua:0\r\n");
             info.push_str("used_memory_lua_human:0B\r\n");
-            info.push_str("maxmemory:0\r\n");
-            info.push_str("maxmemory_human:0B\r\n");
-            info.push_str("maxmemory_policy:noeviction\r\n");
-            info.push_str("mem_fragmentation_ratio:0.00\r\n");
-            info.push_str("mem_allocator:jemalloc-0.0.0\r\n\r\n");
+            let (maxmemory, policy) = {
+                let values = handler.get_state().config.values();
+                (values.maxmemory, values.maxmemory_policy)
+            };
+            info.push_str(&format!("maxmemory:{}\r\n", maxmemory));
+            info.push_str(&format!("maxmemory_human:{}\r\n", memory::format_human(maxmemory)));
+            info.push_str(&format!("maxmemory_policy:{}\r\n", policy.as_str()));
+            let fragmentation = if used_memory > 0 { rss as f64 / used_memory as f64 } else { 0.0 };
+            info.push_str(&format!("mem_fragmentation_ratio:{:.2}\r\n", fragmentation));
+            info.push_str("mem_allocator:libc\r\n\r\n");
         }
 
         // Persistence section
         if show_persistence {
             info.push_str("# Persistence\r\n");
             info.push_str("loading:0\r\n");
-            info.push_str("rdb_changes_since_last_save:0\r\n");
-            info.push_str("rdb_bgsave_in_progress:0\r\n");
-            info.push_str("rdb_last_save_time:0\r\n");
-            info.push_str("rdb_last_bgsave_status:ok\r\n");
-            info.push_str("rdb_last_bgsave_time_sec:-1\r\n");
-            info.push_str("rdb_current_bgsave_time_sec:-1\r\n");
+            let rdb = handler.get_db_manager().get_rdb();
+            info.push_str(&format!("rdb_changes_since_last_save:{}\r\n", changes));
+            info.push_str(&format!("rdb_bgsave_in_progress:{}\r\n", rdb.bgsave_in_progress() as u8));
+            info.push_str(&format!("rdb_last_save_time:{}\r\n", rdb.last_save_time()));
+            info.push_str(&format!("rdb_last_bgsave_status:{}\r\n", if rdb.last_bgsave_ok() { "ok" } else { "err" }));
+            info.push_str(&format!("rdb_last_bgsave_time_sec:{}\r\n", rdb.last_bgsave_time_sec()));
+            info.push_str(&format!("rdb_current_bgsave_time_sec:{}\r\n", rdb.current_bgsave_time_sec()));
             info.push_str("rdb_last_cow_size:0\r\n");
-            info.push_str("aof_enabled:0\r\n");
-            info.push_str("aof_rewrite_in_progress:0\r\n");
-            info.push_str("aof_rewrite_scheduled:0\r\n");
-            info.push_str("aof_last_rewrite_time_sec:-1\r\n");
-            info.push_str("aof_current_rewrite_time_sec:-1\r\n");
-            info.push_str("aof_last_bgrewrite_status:ok\r\n");
-            info.push_str("aof_last_write_status:ok\r\n");
-            info.push_str("aof_last_cow_size:0\r\n");
+            match &handler.get_state().aof {
+                Some(aof_file) => {
+                    let status = aof_file.status();
+                    info.push_str("aof_enabled:1\r\n");
+                    info.push_str(&format!("aof_rewrite_in_progress:{}\r\n", status.rewrite_in_progress() as u8));
+                    info.push_str("aof_rewrite_scheduled:0\r\n");
+                    info.push_str(&format!("aof_last_rewrite_time_sec:{}\r\n", status.last_rewrite_time_sec()));
+                    info.push_str(&format!("aof_current_rewrite_time_sec:{}\r\n", status.current_rewrite_time_sec()));
+                    info.push_str(&format!("aof_last_bgrewrite_status:{}\r\n", if status.last_rewrite_ok() { "ok" } else { "err" }));
+                    info.push_str(&format!("aof_rewrites:{}\r\n", status.rewrites()));
+                    info.push_str("aof_last_write_status:ok\r\n");
+                    info.push_str("aof_last_cow_size:0\r\n");
+                    info.push_str(&format!("aof_current_size:{}\r\n", status.current_size()));
+                    info.push_str(&format!("aof_base_size:{}\r\n", status.base_size()));
+                },
+                None => {
+                    info.push_str("aof_enabled:0\r\n");
+                    info.push_str("aof_rewrite_in_progress:0\r\n");
+                    info.push_str("aof_rewrite_scheduled:0\r\n");
+                    info.push_str("aof_last_rewrite_time_sec:-1\r\n");
+                    info.push_str("aof_current_rewrite_time_sec:-1\r\n");
+                    info.push_str("aof_last_bgrewrite_status:ok\r\n");
+                    info.push_str("aof_rewrites:0\r\n");
+                    info.push_str("aof_last_write_status:ok\r\n");
+                    info.push_str("aof_last_cow_size:0\r\n");
+                },
+            }
             info.push_str("module_fork_in_progress:0\r\n");
             info.push_str("module_fork_last_cow_size:0\r\n\r\n");
         }
@@ -128,14 +174,15 @@ impl Info {
         // Stats section
         if show_stats {
             info.push_str("# Stats\r\n");
-            info.push_str(&format!("total_connections_received:{}\r\n", 1));
-            info.push_str(&format!("total_commands_processed:{}\r\n", 1));
+            let stats = &handler.get_state().stats;
+            info.push_str(&format!("total_connections_received:{}\r\n", stats.total_connections_received()));
+            info.push_str(&format!("total_commands_processed:{}\r\n", stats.total_commands_processed()));
             info.push_str("instantaneous_ops_per_sec:0\r\n");
             info.push_str("total_net_input_bytes:0\r\n");
             info.push_str("total_net_output_bytes:0\r\n");
             info.push_str("instantaneous_input_kbps:0.00\r\n");
             info.push_str("instantaneous_output_kbps:0.00\r\n");
-            info.push_str("rejected_connections:0\r\n");
+            info.push_str(&format!("rejected_connections:{}\r\n", stats.rejected_connections()));
             info.push_str("sync_full:0\r\n");
             info.push_str("sync_partial_ok:0\r\n");
             info.push_str("sync_partial_err:0\r\n");
@@ -143,7 +190,7 @@ impl Info {
             info.push_str("expired_stale_perc:0.00\r\n");
             info.push_str("expired_time_cap_reached_count:0\r\n");
             info.push_str("expire_cycle_cpu_milliseconds:0\r\n");
-            info.push_str("evicted_keys:0\r\n");
+            info.push_str(&format!("evicted_keys:{}\r\n", memory.evicted_keys()));
             info.push_str("keyspace_hits:0\r\n");
             info.push_str("keyspace_misses:0\r\n");
             info.push_str("pubsub_channels:0\r\n");
@@ -169,16 +216,60 @@ impl Info {
         // Replication section
         if show_replication {
             info.push_str("# Replication\r\n");
-            info.push_str("role:master\r\n");
-            info.push_str("connected_slaves:0\r\n");
-            info.push_str("master_replid:0000000000000000000000000000000000000000\r\n");
+            let replication = &handler.get_state().replication;
+            let replica = handler.get_state().replica_status();
+            match &replica {
+                Some(status) => {
+                    info.push_str("role:slave\r\n");
+                    info.push_str(&format!("master_host:{}\r\n", status.master_host()));
+                    info.push_str(&format!("master_port:{}\r\n", status.master_port()));
+                    info.push_str(&format!("master_link_status:{}\r\n", if status.link_up() { "up" } else { "down" }));
+                    info.push_str(&format!("master_last_io_seconds_ago:{}\r\n", status.last_io_seconds_ago()));
+                    info.push_str(&format!("master_sync_in_progress:{}\r\n", status.sync_in_progress() as u8));
+                    info.push_str(&format!("slave_read_repl_offset:{}\r\n", status.offset()));
+                    info.push_str(&format!("slave_repl_offset:{}\r\n", status.offset()));
+                    if let Some(seconds) = status.link_down_seconds() {
+                        info.push_str(&format!("master_link_down_since_seconds:{}\r\n", seconds));
+                    }
+                    info.push_str("slave_priority:100\r\n");
+                    info.push_str(&format!("slave_read_only:{}\r\n", handler.get_state().config.values().replica_read_only as u8));
+                },
+                None => info.push_str("role:master\r\n"),
+            }
+            info.push_str(&format!("connected_slaves:{}\r\n", replication.replica_count()));
+            let mut slaves = handler.get_session_manager().get_slave_sessions();
+            slaves.sort_by_key(|session| session.get_id());
+            for (index, session) in slaves.iter().enumerate() {
+                let replica_info = session.get_replica_info();
+                let ip = replica_info.ip_address.clone()
+                    .or_else(|| session.connection.peer_addr().map(|addr| addr.ip().to_string()))
+                    .unwrap_or_default();
+                info.push_str(&format!(
+                    "slave{}:ip={},port={},state=online,offset={},lag={}\r\n",
+                    index, ip, replica_info.listening_port.unwrap_or(0), replica_info.ack_offset, replica_info.lag().unwrap_or(0),
+                ));
+            }
+            let (min_replicas_to_write, min_replicas_max_lag) = {
+                let values = handler.get_state().config.values();
+                (values.min_replicas_to_write, values.min_replicas_max_lag)
+            };
+            if min_replicas_to_write > 0 {
+                info.push_str(&format!("min_slaves_good_slaves:{}\r\n", handler.get_session_manager().count_good_replicas(min_replicas_max_lag)));
+            }
+            // 从节点报告主节点的复制 ID 与已处理的偏移量
+            let (replid, offset) = match &replica {
+                Some(status) if !status.master_replid().is_empty() => (status.master_replid(), status.offset()),
+                _ => (replication.replid().to_string(), replication.offset()),
+            };
+            info.push_str(&format!("master_replid:{}\r\n", replid));
             info.push_str("master_replid2:0000000000000000000000000000000000000000\r\n");
-            info.push_str("master_repl_offset:0\r\n");
+            info.push_str(&format!("master_repl_offset:{}\r\n", offset));
             info.push_str("second_repl_offset:-1\r\n");
-            info.push_str("repl_backlog_active:0\r\n");
-            info.push_str("repl_backlog_size:1048576\r\n");
-            info.push_str("repl_backlog_first_byte_offset:0\r\n");
-            info.push_str("repl_backlog_histlen:0\r\n\r\n");
+            let backlog = replication.backlog_info();
+            info.push_str(&format!("repl_backlog_active:{}\r\n", backlog.is_some() as u8));
+            info.push_str(&format!("repl_backlog_size:{}\r\n", handler.get_state().config.values().repl_backlog_size));
+            info.push_str(&format!("repl_backlog_first_byte_offset:{}\r\n", backlog.as_ref().map_or(0, |backlog| backlog.first_byte_offset)));
+            info.push_str(&format!("repl_backlog_histlen:{}\r\n\r\n", backlog.as_ref().map_or(0, |backlog| backlog.histlen)));
         }
 
         // CPU section
@@ -202,8 +293,9 @@ impl Info {
         // Keyspace section
         if show_keyspace {
             info.push_str("# Keyspace\r\n");
-            let db_size = db.records.len();
-            info.push_str(&format!("db0:keys={},expires=0,avg_ttl=0\r\n", db_size));
+            for (index, keys, expires) in keyspace {
+                info.push_str(&format!("db{}:keys={},expires={},avg_ttl=0\r\n", index, keys, expires));
+            }
         }
 
         info
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use crate::{store::db_manager::DatabaseManager, frame::Frame};
+
+/**
+ * LASTSAVE 命令：返回上次成功保存 RDB 文件的 Unix 时间（秒）
+ */
+pub struct Lastsave {}
+
+impl Lastsave {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        if frame.get_args().len() != 1 {
+            return Err(Error::msg("ERR wrong number of arguments for 'lastsave' command"));
+        }
+        Ok(Lastsave { })
+    }
+
+    pub fn apply(self, db_manager: Arc<DatabaseManager>) -> Result<Frame, Error> {
+        Ok(Frame::Integer(db_manager.get_rdb().last_save_time() as i64))
+    }
+}
@@ -1,6 +1,11 @@
+pub mod acl;
+pub mod bgrewriteaof;
 pub mod bgsave;
+pub mod config;
+pub mod debug;
 pub mod save;
 pub mod dbsize;
 pub mod flushall;
 pub mod flushdb;
-pub mod info;
\ No newline at end of file
+pub mod info;
+pub mod lastsave;
\ No newline at end of file
@@ -1,2 +1,4 @@
 pub mod psync;
-pub mod replconf;
\ No newline at end of file
+pub mod replconf;
+pub mod replicaof;
+pub mod wait;
\ No newline at end of file
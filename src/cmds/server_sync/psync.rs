@@ -1,30 +1,115 @@
-use std::sync::Arc;
+use std::{collections::HashMap, time::Instant};
 
-use crate::{args::Args, store::db::DatabaseMessage, store::db_manager::DatabaseManager, frame::Frame, persistence::rdb_file::RdbFile};
 use anyhow::Error;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc::UnboundedReceiver, oneshot};
 
-pub struct Psync {}
+use crate::{frame::Frame, network::session_role::SessionRole, persistence::{rdb_file::RdbFile, redis_rdb}, server::Handler, store::db::DatabaseMessage};
+
+/**
+ * PSYNC 命令：从节点请求同步
+ *
+ * 复制 ID 匹配且缺失的数据仍在积压缓冲区内时回复 +CONTINUE <replid>，只补发缺失的部分；
+ * 否则回复 +FULLRESYNC <replid> <offset>，随后以 $<len>\r\n<rdb> 发送快照（末尾没有 CRLF）。
+ * 之后连接转为复制流。从节点通过 REPLCONF capa rudis 声明时发送原生格式的快照（保留所有数据类型），
+ * 否则发送 Redis RDB 格式
+ *
+ * @param replid 从节点已知的复制 ID，首次同步为 ?
+ * @param offset 从节点需要的第一个字节的偏移量（已处理的偏移量 + 1），首次同步为 -1
+ */
+pub struct Psync {
+    replid: String,
+    offset: i64,
+}
 
 impl Psync {
-    
-    pub fn parse_from_frame(_frame: Frame) -> Result<Self, Error> {
-        Ok(Psync {})
-    }
 
-    pub async fn apply(self, db_manager: Arc<DatabaseManager>, _args: Arc<Args>) -> Result<Frame, Error> {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'psync' command"));
+        }
+        let offset = args[2].parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+        Ok(Psync { replid: args[1].clone(), offset })
+    }
 
-        // 获取 RDB 快照
-        let mut snapshots = Vec::new();
-        for sender in db_manager.get_senders() {
-            let (tx, rx) = oneshot::channel();
-            sender.send(DatabaseMessage::Snapshot(tx)).await?;
-            snapshots.push(rx.await?);
+    pub async fn apply(self, handler: &mut Handler) -> Result<(), Error> {
+        let state = handler.get_state().clone();
+        let session_id = handler.get_session().get_id();
+        log::info!("Replica {} asks for synchronization (replid {}, offset {})", session_id, self.replid, self.offset);
+        if let Some((missing, stream)) = state.replication.try_continue(session_id, &self.replid, self.offset) {
+            log::info!("Partial resynchronization accepted for replica {}, sending {} bytes of backlog", session_id, missing.len());
+            let mut bytes = format!("+CONTINUE {}\r\n", state.replication.replid()).into_bytes();
+            bytes.extend(missing);
+            Self::start_streaming(handler, bytes, stream).await;
+            return Ok(());
         }
+        Self::full_resync(handler).await
+    }
+
+    /**
+     * 执行全量同步
+     *
+     * 持有写屏障期间请求快照并注册从节点，快照包含之前的所有写命令，之后的写命令进入复制流
+     */
+    async fn full_resync(handler: &mut Handler) -> Result<(), Error> {
+        let state = handler.get_state().clone();
+        let session_id = handler.get_session().get_id();
+        let (offset, stream, receivers) = {
+            let _barrier = state.write_barrier.write().await;
+            let mut receivers = Vec::new();
+            for sender in handler.get_db_manager().get_senders() {
+                let (tx, rx) = oneshot::channel();
+                sender.send(DatabaseMessage::Snapshot(tx)).await?;
+                receivers.push(rx);
+            }
+            let (offset, stream) = state.replication.add_replica(session_id);
+            (offset, stream, receivers)
+        };
+
+        let result = async {
+            let mut snapshots = Vec::new();
+            for receiver in receivers {
+                snapshots.push(receiver.await?);
+            }
+            let native = handler.get_session().get_replica_info().has_capability("rudis");
+            tokio::task::spawn_blocking(move || {
+                if native {
+                    RdbFile::from_snapshots(snapshots).serialize()
+                } else {
+                    let databases: HashMap<_, _> = snapshots.into_iter().enumerate().collect();
+                    Ok(redis_rdb::encode(&databases))
+                }
+            }).await?
+        }.await;
+        let payload = match result {
+            Ok(payload) => payload,
+            Err(e) => {
+                state.replication.remove_replica(session_id);
+                return Err(e);
+            }
+        };
+
+        log::info!("Starting full synchronization with replica {}, {} bytes, offset {}", session_id, payload.len(), offset);
+        let mut bytes = format!("+FULLRESYNC {} {}\r\n${}\r\n", state.replication.replid(), offset, payload.len()).into_bytes();
+        bytes.extend(payload);
+        Self::start_streaming(handler, bytes, stream).await;
+        Ok(())
+    }
+
+    /// 发送同步的回复，之后将复制流转发到从节点连接
+    async fn start_streaming(handler: &mut Handler, reply: Vec<u8>, mut stream: UnboundedReceiver<Vec<u8>>) {
+        let connection = handler.get_session().connection.clone();
+        connection.write_bytes(reply).await;
+        let mut info = handler.get_session().get_replica_info().clone();
+        info.ack_time = Some(Instant::now());
+        handler.set_replica_info(info);
+        handler.set_session_role(SessionRole::Slave);
 
-        // 构建 RDB 文件
-        let rdb = RdbFile::from_snapshots(snapshots);
-        let rdb_data = rdb.serialize()?;
-        Ok(Frame::RDBFile(rdb_data))
+        // 复制流在快照之后按顺序发送，从节点断开后发送端被移除，任务随之结束
+        tokio::spawn(async move {
+            while let Some(bytes) = stream.recv().await {
+                connection.write_bytes(bytes).await;
+            }
+        });
     }
 }
@@ -1,56 +1,70 @@
 use anyhow::Error;
 
-use crate::{frame::Frame, network::session_role::SessionRole, server::Handler};
+use crate::{frame::Frame, server::Handler};
 
+/**
+ * REPLCONF 命令：从节点在复制握手与复制过程中上报信息
+ *
+ * REPLCONF listening-port <port>：从节点的监听端口
+ * REPLCONF ip-address <ip>：从节点的地址
+ * REPLCONF capa <capability>：从节点支持的能力（可以多次出现），capa rudis 表示可以接收原生格式的快照
+ * REPLCONF ack <offset>：从节点已经处理的复制偏移量，主节点不回复
+ * REPLCONF getack *：主节点要求从节点立即上报偏移量
+ */
 pub struct Replconf {
-    port: Option<String>,
-    addr: Option<String>,
+    options: Vec<(String, String)>,
 }
 
 impl Replconf {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let mut port = None;
-        let mut addr = None;
-        if let Frame::Array(frames) = frame {
-            if frames.len() < 5 {
-                return Err(Error::msg("REPLCONF command requires at least 5 arguments"));
-            }
-            let cmd = frames[0].to_string().to_uppercase();
-            if cmd != "REPLCONF" {
-                return Err(Error::msg("Invalid command, expected REPLCONF"));
-            }
-            let mut index = 1;
-            while index < frames.len() {
-                let arg = frames[index].to_string().to_uppercase();
-                match arg.as_str() {
-                    "LISTENING-PORT" if index + 1 < frames.len() => {
-                        port = Some(frames[index + 1].to_string());
-                        index += 2; 
-                    }
-                    "IP-ADDRESS" if index + 1 < frames.len() => {
-                        addr = Some(frames[index + 1].to_string());
-                        index += 2; 
+        let args = frame.get_args();
+        if args.len().is_multiple_of(2) {
+            return Err(Error::msg("ERR syntax error"));
+        }
+        let mut options = Vec::new();
+        for pair in args[1..].chunks(2) {
+            let option = pair[0].to_lowercase();
+            match option.as_str() {
+                "listening-port" => {
+                    if pair[1].parse::<u16>().is_err() {
+                        return Err(Error::msg("ERR value is not an integer or out of range"));
                     }
-                    _ => {
-                        index += 1;
+                },
+                "ack" => {
+                    if pair[1].parse::<u64>().is_err() {
+                        return Err(Error::msg("ERR value is not an integer or out of range"));
                     }
-                }
+                },
+                "ip-address" | "capa" | "getack" | "rdb-only" | "rdb-filter-only" | "rdb-channel" => {},
+                _ => return Err(Error::msg(format!("ERR Unrecognized REPLCONF option: {}", pair[0]))),
             }
-        } else {
-            return Err(Error::msg("REPLCONF command must be an array frame"));
+            options.push((option, pair[1].clone()));
         }
+        Ok(Replconf { options })
+    }
 
-        if port.is_none() || addr.is_none() {
-            return Err(Error::msg("Missing required parameters for REPLCONF"));
-        }
+    /// REPLCONF ACK 上报的偏移量
+    pub fn ack_offset(&self) -> Option<u64> {
+        self.options.iter().find(|(option, _)| option == "ack").and_then(|(_, value)| value.parse().ok())
+    }
 
-        Ok(Replconf { port, addr })
+    /// 是否为 REPLCONF GETACK
+    pub fn is_getack(&self) -> bool {
+        self.options.iter().any(|(option, _)| option == "getack")
     }
 
     pub fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
-        log::info!("Slave 节点信息 - {}:{}", self.addr.unwrap(), self.port.unwrap());
-        handler.set_session_role(SessionRole::Slave);
+        let mut info = handler.get_session().get_replica_info().clone();
+        for (option, value) in self.options {
+            match option.as_str() {
+                "listening-port" => info.listening_port = value.parse().ok(),
+                "ip-address" => info.ip_address = Some(value),
+                "capa" => info.capabilities.push(value),
+                _ => {},
+            }
+        }
+        handler.set_replica_info(info);
         Ok(Frame::Ok)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,56 @@
+use anyhow::Error;
+
+use crate::{frame::Frame, server::Handler};
+
+/**
+ * REPLICAOF 命令（SLAVEOF）：在运行时切换复制角色
+ *
+ * REPLICAOF host port：复制指定的主节点，全量同步时替换本地数据
+ * REPLICAOF NO ONE：停止复制，成为主节点，保留已有数据
+ *
+ * @param master 主节点地址，None 表示 NO ONE
+ */
+pub struct Replicaof {
+    master: Option<(String, u16)>,
+}
+
+impl Replicaof {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 3 {
+            return Err(Error::msg(format!("ERR wrong number of arguments for '{}' command", args[0].to_lowercase())));
+        }
+        if args[1].eq_ignore_ascii_case("no") && args[2].eq_ignore_ascii_case("one") {
+            return Ok(Replicaof { master: None });
+        }
+        let port = match args[2].parse::<u16>() {
+            Ok(port) if port > 0 => port,
+            _ => return Err(Error::msg("ERR Invalid master port")),
+        };
+        Ok(Replicaof { master: Some((args[1].clone(), port)) })
+    }
+
+    pub fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let state = handler.get_state().clone();
+        match self.master {
+            None => {
+                if state.is_replica() {
+                    state.replicaof(None, handler.get_args().clone(), handler.get_db_manager().clone());
+                    log::info!("MASTER MODE enabled (user request from id={})", handler.get_session().get_id());
+                }
+                Ok(Frame::Ok)
+            },
+            Some((host, port)) => {
+                if let Some(status) = state.replica_status() {
+                    if status.master_host() == host && status.master_port() == port {
+                        return Ok(Frame::SimpleString("OK Already connected to specified master".to_string()));
+                    }
+                }
+                log::info!("REPLICAOF {}:{} enabled (user request from id={})", host, port, handler.get_session().get_id());
+                state.replicaof(Some((host, port)), handler.get_args().clone(), handler.get_db_manager().clone());
+                Ok(Frame::Ok)
+            },
+        }
+    }
+}
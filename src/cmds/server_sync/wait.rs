@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+use crate::{frame::Frame, server::Handler};
+
+/**
+ * WAIT 命令：等待之前的写命令被指定数量的从节点确认
+ *
+ * 已经有足够的从节点确认时立即返回，否则要求从节点立即上报偏移量，直到满足条件或超时
+ *
+ * @param numreplicas 需要确认的从节点数量
+ * @param timeout 超时时间（毫秒），0 表示一直等待
+ * @return 确认了写命令的从节点数量
+ */
+pub struct Wait {
+    numreplicas: usize,
+    timeout: u64,
+}
+
+impl Wait {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'wait' command"));
+        }
+        let numreplicas = args[1].parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+        let timeout = args[2].parse::<i64>().map_err(|_| Error::msg("ERR timeout is not an integer or out of range"))?;
+        if timeout < 0 {
+            return Err(Error::msg("ERR timeout is negative"));
+        }
+        Ok(Wait { numreplicas: numreplicas.max(0) as usize, timeout: timeout as u64 })
+    }
+
+    pub async fn apply(self, handler: &Handler) -> Result<Frame, Error> {
+        let state = handler.get_state();
+        if state.is_replica() {
+            return Ok(Frame::Error("ERR WAIT cannot be used with replica instances. Please also note that writes to replicas are just local and are not propagated.".to_string()));
+        }
+        let offset = state.replication.offset();
+        let session_manager = handler.get_session_manager();
+        let acked = session_manager.count_replicas_acked(offset);
+        if acked >= self.numreplicas {
+            return Ok(Frame::Integer(acked as i64));
+        }
+
+        state.replication.request_ack();
+        let deadline = match self.timeout {
+            0 => None,
+            timeout => Some(tokio::time::Instant::now() + Duration::from_millis(timeout)),
+        };
+        let acked = session_manager.wait_replica_acks(offset, self.numreplicas, deadline).await;
+        Ok(Frame::Integer(acked as i64))
+    }
+}
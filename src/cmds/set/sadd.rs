@@ -4,8 +4,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Sadd {
-    key: String,
-    members: Vec<String>,
+    key: Vec<u8>,
+    members: Vec<Vec<u8>>,
 }
 
 impl Sadd {
@@ -18,14 +18,20 @@ impl Sadd {
             return Err(Error::msg("ERR wrong number of arguments for 'sadd' command"));
         }
 
-        let key = args[1].to_string(); // 键
-        let members: Vec<String> = args.iter().skip(2).map(|v| v.to_string()).collect(); // 成员
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let members: Vec<Vec<u8>> = frame.get_args_bytes_from_index(2); // 成员
 
         Ok(Sadd { key, members })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get_mut(&self.key) {
+        // 成员都已经在集合中时不修改键
+        if let Some(Structure::Set(set)) = db.get(&self.key) {
+            if self.members.iter().all(|member| set.contains(member)) {
+                return Ok(Frame::Integer(0));
+            }
+        }
+        match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
@@ -51,7 +57,7 @@ impl Sadd {
                         added_count += 1;
                     }
                 }
-                db.records.insert(self.key.clone(), Structure::Set(set));
+                db.insert(self.key.clone(), Structure::Set(set));
                 Ok(Frame::Integer(added_count as i64))
             }
         }
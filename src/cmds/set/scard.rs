@@ -2,7 +2,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Scard {
-    key: String,
+    key: Vec<u8>,
 }
 
 impl Scard {
@@ -15,13 +15,13 @@ impl Scard {
             return Err(Error::msg("ERR wrong number of arguments for 'scard' command"));
         }
 
-        let key = args[1].to_string(); // 键
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
 
         Ok(Scard { key })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
@@ -4,7 +4,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Sdiff {
-    keys: Vec<String>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Sdiff {
@@ -18,7 +18,7 @@ impl Sdiff {
         }
 
         // 提取所有键
-        let keys = args[1..].iter().map(|arg| arg.to_string()).collect();
+        let keys = frame.get_args_bytes_from_index(1);
 
         Ok(Sdiff { keys })
     }
@@ -46,7 +46,7 @@ impl Sdiff {
         };
 
         // 计算差集
-        let mut difference: HashSet<String> = first_set;
+        let mut difference: HashSet<Vec<u8>> = first_set;
         for key in iter {
             match db.records.get(key) {
                 Some(structure) => {
@@ -70,11 +70,11 @@ impl Sdiff {
             }
         }
 
-        // 将结果转换为 Frame::Array
+        // 将结果转换为 Frame::Set
         let members: Vec<Frame> = difference.into_iter()
-            .map(|member| Frame::BulkString(member))
+            .map(Frame::BulkString)
             .collect();
 
-        Ok(Frame::Array(members))
+        Ok(Frame::Set(members))
     }
 }
\ No newline at end of file
@@ -4,8 +4,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Sdiffstore {
-    destination: String,
-    keys: Vec<String>,
+    destination: Vec<u8>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Sdiffstore {
@@ -18,8 +18,8 @@ impl Sdiffstore {
             return Err(Error::msg("ERR wrong number of arguments for 'sdiffstore' command"));
         }
 
-        let destination = args[1].to_string();
-        let keys = args[2..].iter().map(|arg| arg.to_string()).collect();
+        let destination = frame.get_arg_bytes(1).unwrap();
+        let keys = frame.get_args_bytes_from_index(2);
 
         Ok(Sdiffstore { destination, keys })
     }
@@ -47,7 +47,7 @@ impl Sdiffstore {
         };
 
         // calculate the difference set
-        let mut difference: HashSet<String> = first_set;
+        let mut difference: HashSet<Vec<u8>> = first_set;
         for key in iter {
             match db.records.get(key) {
                 Some(structure) => {
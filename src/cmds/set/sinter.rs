@@ -3,7 +3,7 @@ use crate::{store::db::{Db, Structure}, frame::Frame};
 use std::collections::HashSet;
 
 pub struct Sinter {
-    keys: Vec<String>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Sinter {
@@ -13,7 +13,7 @@ impl Sinter {
         if args.len() < 2 {
             return Err(Error::msg("ERR wrong number of arguments for 'sinter' command"));
         }
-        let keys: Vec<String> = args[1..].iter().map(|arg| arg.to_string()).collect();
+        let keys: Vec<Vec<u8>> = frame.get_args_bytes_from_index(1);
         Ok(Sinter { keys })
     }
 
@@ -26,7 +26,7 @@ impl Sinter {
             Some(structure) => {
                 match structure {
                     Structure::Set(first_set) => {       
-                        let mut intersection: HashSet<String> = first_set.clone();
+                        let mut intersection: HashSet<Vec<u8>> = first_set.clone();
                         for key in iter {
                             match db.records.get(key) {
                                 Some(structure) => {
@@ -50,7 +50,7 @@ impl Sinter {
                         for member in intersection.iter() {
                             result.push(Frame::BulkString(member.clone()));
                         }
-                        Ok(Frame::Array(result))
+                        Ok(Frame::Set(result))
                     },
                     _ => {
                         let f = "ERR Operation against a key holding the wrong kind of value";
@@ -59,7 +59,7 @@ impl Sinter {
                 }
             },
             None => {
-                Ok(Frame::Array(Vec::new()))
+                Ok(Frame::Set(Vec::new()))
             }
         }
     }
@@ -4,8 +4,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Sinterstore {
-    destination: String,
-    keys: Vec<String>,
+    destination: Vec<u8>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Sinterstore {
@@ -18,8 +18,8 @@ impl Sinterstore {
             return Err(Error::msg("ERR wrong number of arguments for 'sinterstore' command"));
         }
 
-        let destination = args[1].to_string();
-        let keys = args[2..].iter().map(|arg| arg.to_string()).collect();
+        let destination = frame.get_arg_bytes(1).unwrap();
+        let keys = frame.get_args_bytes_from_index(2);
 
         Ok(Sinterstore { destination, keys })
     }
@@ -32,7 +32,7 @@ impl Sinterstore {
             Some(structure) => {
                 match structure {
                     Structure::Set(first_set) => {       
-                        let mut intersection: HashSet<String> = first_set.clone();
+                        let mut intersection: HashSet<Vec<u8>> = first_set.clone();
                         for key in iter {
                             match db.records.get(key) {
                                 Some(structure) => {
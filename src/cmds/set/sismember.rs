@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Sismember {
-    key: String,
-    member: String,
+    key: Vec<u8>,
+    member: Vec<u8>,
 }
 
 impl Sismember {
@@ -13,13 +13,13 @@ impl Sismember {
         if args.len() != 3 {
             return Err(Error::msg("ERR wrong number of arguments for 'sismember' command"));
         }
-        let key = args[1].to_string(); // 键
-        let member = args[2].to_string(); // 成员
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let member = frame.get_arg_bytes(2).unwrap(); // 成员
         Ok(Sismember { key, member })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
@@ -2,7 +2,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Smembers {
-    key: String,
+    key: Vec<u8>,
 }
 
 impl Smembers {
@@ -13,12 +13,12 @@ impl Smembers {
             return Err(Error::msg("ERR wrong number of arguments for 'smembers' command"));
         }
 
-        let key = args[1].to_string(); // 键
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
         Ok(Smembers { key })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
@@ -26,7 +26,7 @@ impl Smembers {
                         for member in set.iter() {
                             members.push(Frame::BulkString(member.clone()));
                         }
-                        Ok(Frame::Array(members))
+                        Ok(Frame::Set(members))
                     },
                     _ => {
                         let f = "ERR Operation against a key holding the wrong kind of value";
@@ -35,7 +35,7 @@ impl Smembers {
                 }
             },
             None => {
-                Ok(Frame::Array(Vec::new()))
+                Ok(Frame::Set(Vec::new()))
             }
         }
     }
@@ -2,9 +2,9 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Smove {
-    source: String,
-    destination: String,
-    member: String,
+    source: Vec<u8>,
+    destination: Vec<u8>,
+    member: Vec<u8>,
 }
 
 impl Smove {
@@ -17,16 +17,23 @@ impl Smove {
             return Err(Error::msg("ERR wrong number of arguments for 'smove' command"));
         }
 
-        let source = args[1].to_string();
-        let destination = args[2].to_string();
-        let member = args[3].to_string();
+        let source = frame.get_arg_bytes(1).unwrap();
+        let destination = frame.get_arg_bytes(2).unwrap();
+        let member = frame.get_arg_bytes(3).unwrap();
 
         Ok(Smove { source, destination, member })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // the source collection does not contain the member, nothing changes and WATCHed keys stay untouched
+        if let Some(Structure::Set(set)) = db.get(&self.source) {
+            if !set.contains(&self.member) {
+                return Ok(Frame::Integer(0));
+            }
+        }
+
         // check if the source collection exists and contains the member
-        let member_exists = match db.records.get_mut(&self.source) {
+        let member_exists = match db.get_mut(&self.source) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
@@ -48,7 +55,7 @@ impl Smove {
         }
 
         // add members to the target collection
-        match db.records.get_mut(&self.destination) {
+        match db.get_mut(&self.destination) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
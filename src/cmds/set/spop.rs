@@ -2,7 +2,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Spop {
-    key: String,
+    key: Vec<u8>,
     count: Option<usize>,
 }
 
@@ -16,7 +16,7 @@ impl Spop {
             return Err(Error::msg("ERR wrong number of arguments for 'spop' command"));
         }
 
-        let key = args[1].to_string(); // 键
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
         let count = if args.len() == 3 {
             match args[2].parse::<usize>() {
                 Ok(c) => Some(c),
@@ -30,7 +30,7 @@ impl Spop {
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get_mut(&self.key) {
+        match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
@@ -3,7 +3,7 @@ use crate::{store::db::{Db, Structure}, frame::Frame};
 use std::collections::HashSet;
 
 pub struct Srandmember {
-    key: String,
+    key: Vec<u8>,
     count: Option<i64>,
 }
 
@@ -11,13 +11,13 @@ impl Srandmember {
     
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
 
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'srandmember' command"));
         }
 
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
         
         // count is an optional parameter
         let count = if let Some(count_str) = frame.get_arg(2) {
@@ -44,7 +44,7 @@ impl Srandmember {
                             return Ok(Frame::Null);
                         }
 
-                        let vec: Vec<String> = set.iter().cloned().collect();
+                        let vec: Vec<Vec<u8>> = set.iter().cloned().collect();
                         let vec_len = vec.len();
 
                         match self.count {
@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Srem {
-    key: String,
-    members: Vec<String>,
+    key: Vec<u8>,
+    members: Vec<Vec<u8>>,
 }
 
 impl Srem {
@@ -16,14 +16,20 @@ impl Srem {
             return Err(Error::msg("ERR wrong number of arguments for 'srem' command"));
         }
 
-        let key = args[1].to_string(); // 键
-        let members = args[2..].iter().map(|arg| arg.to_string()).collect(); // 要移除的成员
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let members = frame.get_args_bytes_from_index(2); // 要移除的成员
 
         Ok(Srem { key, members })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get_mut(&self.key) {
+        // 成员都不在集合中时不修改键
+        if let Some(Structure::Set(set)) = db.get(&self.key) {
+            if !self.members.iter().any(|member| set.contains(member)) {
+                return Ok(Frame::Integer(0));
+            }
+        }
+        match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
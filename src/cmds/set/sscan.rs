@@ -2,20 +2,21 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame, tools::pattern};
 
 pub struct Sscan {
-    key: String,
+    key: Vec<u8>,
     cursor: u64,
-    pattern: Option<String>,
+    pattern: Option<Vec<u8>>,
     count: Option<u64>,
 }
 
 impl Sscan {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
         let args = frame.get_args_from_index(1);
+        let raw_args = frame.get_args_bytes_from_index(1);
         if args.len() < 2 {
             return Err(Error::msg("SSCAN command requires at least two arguments"));
         }
 
-        let key = args[0].clone();
+        let key = raw_args[0].clone();
         let cursor = args[1].parse::<u64>()?;
 
         let mut pattern = None;
@@ -28,7 +29,7 @@ impl Sscan {
                 if i + 1 >= args.len() {
                     return Err(Error::msg("MATCH option requires an argument"));
                 }
-                pattern = Some(args[i + 1].clone());
+                pattern = Some(raw_args[i + 1].clone());
                 i += 2;
             } else if arg == "COUNT" {
                 if i + 1 >= args.len() {
@@ -46,16 +47,16 @@ impl Sscan {
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
         // 默认匹配模式为 "*"
-        let pattern = self.pattern.unwrap_or_else(|| "*".to_string());
+        let pattern = self.pattern.unwrap_or_else(|| b"*".to_vec());
         // 默认返回数量为 10
         let count = self.count.unwrap_or(10) as usize;
 
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::Set(set) => {
                         // 获取所有匹配的成员
-                        let matched_members: Vec<String> = set.iter()
+                        let matched_members: Vec<Vec<u8>> = set.iter()
                             .filter(|member| pattern::is_match(member, &pattern))
                             .cloned()
                             .collect();
@@ -4,7 +4,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Sunion {
-    keys: Vec<String>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Sunion {
@@ -18,7 +18,7 @@ impl Sunion {
         }
 
         // 提取所有键
-        let keys = args[1..].iter().map(|arg| arg.to_string()).collect();
+        let keys = frame.get_args_bytes_from_index(1);
 
         Ok(Sunion { keys })
     }
@@ -41,11 +41,11 @@ impl Sunion {
             }
         }
 
-        // 将结果转换为 Frame::Array
+        // 将结果转换为 Frame::Set
         let members: Vec<Frame> = result_set.into_iter()
-            .map(|member| Frame::BulkString(member))
+            .map(Frame::BulkString)
             .collect();
 
-        Ok(Frame::Array(members))
+        Ok(Frame::Set(members))
     }
 }
\ No newline at end of file
@@ -4,8 +4,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Sunionstore {
-    destination: String,
-    keys: Vec<String>,
+    destination: Vec<u8>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Sunionstore {
@@ -18,8 +18,8 @@ impl Sunionstore {
             return Err(Error::msg("ERR wrong number of arguments for 'sunionstore' command"));
         }
 
-        let destination = args[1].to_string();
-        let keys = args[2..].iter().map(|arg| arg.to_string()).collect();
+        let destination = frame.get_arg_bytes(1).unwrap();
+        let keys = frame.get_args_bytes_from_index(2);
 
         Ok(Sunionstore { destination, keys })
     }
@@ -46,7 +46,7 @@ impl Sunionstore {
                 }
             }
         }
-        db.records.insert(destination, Structure::Set(result_set.clone()));
+        db.insert(destination, Structure::Set(result_set.clone()));
         Ok(Frame::Integer(result_set.len() as i64))
     }
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+use crate::{command::Command, store::{blocking::{parse_timeout, BlockDirection}, db::Db}, frame::Frame, server::Handler};
+use super::zmpop::MpopArgs;
+
+/// BZMPOP timeout numkeys key [key ...] MIN|MAX [COUNT count]
+#[derive(Clone)]
+pub struct Bzmpop {
+    timeout: Option<Duration>,
+    args: MpopArgs,
+}
+
+impl Bzmpop {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args_bytes();
+        if args.len() < 5 {
+            return Err(Error::msg("ERR wrong number of arguments for 'bzmpop' command"));
+        }
+        let timeout = parse_timeout(&String::from_utf8_lossy(&args[1]))?;
+        let args = MpopArgs::parse(&args[2..], "bzmpop")?;
+        Ok(Bzmpop { timeout, args })
+    }
+
+    /// 所有有序集合都为空时阻塞，直到其中一个被写入或超时
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let direction = if self.args.max { BlockDirection::Max } else { BlockDirection::Min };
+        let keys = self.args.keys.clone();
+        handler.block_on_keys(&keys, self.timeout, direction, || Command::Bzmpop(self.clone())).await
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(self.args.apply(db))
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 改写为弹出实际键与数量的 ZPOPMIN / ZPOPMAX，从节点重放时不会阻塞；没有弹出成员时不传播
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let Frame::Array(items) = reply else {
+            return None;
+        };
+        let (Some(key), Some(Frame::Array(members))) = (items.first(), items.get(1)) else {
+            return None;
+        };
+        let bzmpop = Bzmpop::parse_from_frame(frame).ok()?;
+        let pop = if bzmpop.args.max { "ZPOPMAX" } else { "ZPOPMIN" };
+        Some(Frame::Array(vec![
+            Frame::BulkString(pop.to_string().into()),
+            key.clone(),
+            Frame::BulkString(members.len().to_string().into()),
+        ]))
+    }
+}
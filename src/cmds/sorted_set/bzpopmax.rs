@@ -0,0 +1,26 @@
+use anyhow::Error;
+
+use crate::{command::Command, store::db::Db, frame::Frame, server::Handler};
+use super::bzpopmin::Bzpop;
+
+#[derive(Clone)]
+pub struct Bzpopmax {
+    pop: Bzpop,
+}
+
+impl Bzpopmax {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let pop = Bzpop::parse(&frame.get_args_bytes(), "bzpopmax", true)?;
+        Ok(Bzpopmax { pop })
+    }
+
+    /// 所有有序集合都为空时阻塞，直到其中一个被写入或超时
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let (keys, timeout, direction) = self.pop.blocking_args();
+        handler.block_on_keys(&keys, timeout, direction, || Command::Bzpopmax(self.clone())).await
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        self.pop.apply(db)
+    }
+}
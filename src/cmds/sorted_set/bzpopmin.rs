@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+use crate::{command::Command, store::{blocking::{parse_timeout, BlockDirection}, db::Db}, frame::Frame, server::Handler};
+
+/// BZPOPMIN / BZPOPMAX 共用的实现：key [key ...] timeout
+#[derive(Clone)]
+pub struct Bzpop {
+    keys: Vec<Vec<u8>>,
+    timeout: Option<Duration>,
+    max: bool,
+}
+
+impl Bzpop {
+    pub fn parse(args: &[Vec<u8>], command: &str, max: bool) -> Result<Self, Error> {
+        if args.len() < 3 {
+            return Err(Error::msg(format!("ERR wrong number of arguments for '{}' command", command)));
+        }
+        let keys = args[1..args.len() - 1].to_vec();
+        let timeout = parse_timeout(&String::from_utf8_lossy(&args[args.len() - 1]))?;
+        Ok(Bzpop { keys, timeout, max })
+    }
+
+    /// 非阻塞弹出：从第一个非空的有序集合弹出一个成员，回复 [键, 成员, 分数]，所有键都为空时回复 nil
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        for key in self.keys {
+            let Some(mut popped) = super::pop(db, &key, 1, self.max) else {
+                return Ok(super::wrong_type());
+            };
+            if let Some((member, score)) = popped.pop() {
+                return Ok(Frame::Array(vec![
+                    Frame::BulkString(key),
+                    Frame::BulkString(member),
+                    Frame::Double(score),
+                ]));
+            }
+        }
+        Ok(Frame::NullArray)
+    }
+
+    /// 阻塞等待的键、超时时间与方向
+    pub fn blocking_args(&self) -> (Vec<Vec<u8>>, Option<Duration>, BlockDirection) {
+        let direction = if self.max { BlockDirection::Max } else { BlockDirection::Min };
+        (self.keys.clone(), self.timeout, direction)
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 改写为弹出实际键的 ZPOPMIN / ZPOPMAX，从节点重放时不会阻塞；没有弹出成员时不传播
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let Frame::Array(items) = reply else {
+            return None;
+        };
+        let key = items.first()?.clone();
+        let command = frame.get_arg(0)?.to_uppercase();
+        let pop = if command == "BZPOPMAX" { "ZPOPMAX" } else { "ZPOPMIN" };
+        Some(Frame::Array(vec![Frame::BulkString(pop.to_string().into()), key]))
+    }
+}
+
+#[derive(Clone)]
+pub struct Bzpopmin {
+    pop: Bzpop,
+}
+
+impl Bzpopmin {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let pop = Bzpop::parse(&frame.get_args_bytes(), "bzpopmin", false)?;
+        Ok(Bzpopmin { pop })
+    }
+
+    /// 所有有序集合都为空时阻塞，直到其中一个被写入或超时
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let (keys, timeout, direction) = self.pop.blocking_args();
+        handler.block_on_keys(&keys, timeout, direction, || Command::Bzpopmin(self.clone())).await
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        self.pop.apply(db)
+    }
+}
@@ -6,4 +6,54 @@ pub mod zscore;
 pub mod zcard;
 pub mod zrank;
 pub mod zlexcount;
-pub mod zrange;
\ No newline at end of file
+pub mod zrange;pub mod zrevrange;
+pub mod zrangebyscore;
+pub mod zrevrangebyscore;
+pub mod zrangebylex;
+pub mod zrevrangebylex;
+pub mod zrangestore;
+pub mod zrevrank;
+pub mod zremrangebyrank;
+pub mod zremrangebyscore;
+pub mod zremrangebylex;
+pub mod zmscore;
+pub mod zrandmember;
+pub mod zscan;
+pub mod zunion;
+pub mod zunionstore;
+pub mod zinter;
+pub mod zinterstore;
+pub mod zintercard;
+pub mod zdiff;
+pub mod zdiffstore;
+pub mod zpopmin;
+pub mod zpopmax;
+pub mod zmpop;
+pub mod bzpopmin;
+pub mod bzpopmax;
+pub mod bzmpop;
+
+use crate::frame::Frame;
+use crate::store::db::{Db, Structure};
+
+/**
+ * 从有序集合弹出成员，弹出后集合为空时删除键
+ *
+ * @param max 为 true 时弹出分数最大的成员
+ * @return 键不存在时为空，类型错误时返回 None
+ */
+fn pop(db: &mut Db, key: &[u8], count: usize, max: bool) -> Option<Vec<(Vec<u8>, f64)>> {
+    let (popped, empty) = match db.get_mut(key) {
+        Some(Structure::SortedSet(set)) => (set.pop(count, max), set.is_empty()),
+        Some(_) => return None,
+        None => return Some(Vec::new()),
+    };
+    if empty {
+        db.remove(key);
+    }
+    Some(popped)
+}
+
+fn wrong_type() -> Frame {
+    Frame::Error("ERR Operation against a key holding the wrong kind of value".to_string())
+}
@@ -1,65 +1,172 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
 
 use crate::{store::db::{Db, Structure}, frame::Frame};
 use crate::store::sorted_set::SortedSet;
 
+/// ZADD 的条件：NX 只添加新成员，XX 只更新已有成员
+#[derive(Clone, Copy, PartialEq)]
+enum Condition {
+    Always,
+    Nx,
+    Xx,
+}
+
+/// ZADD 的分数比较：GT 只在新分数更大时更新，LT 只在新分数更小时更新（不影响新成员的添加）
+#[derive(Clone, Copy, PartialEq)]
+enum Comparison {
+    Always,
+    Gt,
+    Lt,
+}
+
+#[derive(Clone)]
 pub struct Zadd {
-    key: String,
-    members: Vec<(f64, String)>, // 成员及其分数
+    key: Vec<u8>,
+    condition: Condition,
+    comparison: Comparison,
+    ch: bool, // 返回新增与分数被修改的成员数量，而不只是新增的数量
+    incr: bool, // 与 ZINCRBY 相同，为成员的分数增加 score
+    members: Vec<(f64, Vec<u8>)>, // 成员及其分数
 }
 
 impl Zadd {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
         let args = frame.get_args();
-        if args.len() < 4 || args.len() % 2 != 0 {
+        if args.len() < 4 {
             return Err(Error::msg("ERR wrong number of arguments for 'zadd' command"));
         }
         
-        let key = args[1].to_string(); // 键
-        let mut members = Vec::new();
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let mut nx = false;
+        let mut xx = false;
+        let mut gt = false;
+        let mut lt = false;
+        let mut ch = false;
+        let mut incr = false;
 
-        for chunk in args[2..].chunks(2) {
-            if chunk.len() != 2 {
-                return Err(Error::msg("ERR wrong number of arguments for 'zadd' command"));
+        // 选项位于 score member 对之前
+        let mut index = 2;
+        while index < args.len() {
+            match args[index].to_uppercase().as_str() {
+                "NX" => nx = true,
+                "XX" => xx = true,
+                "GT" => gt = true,
+                "LT" => lt = true,
+                "CH" => ch = true,
+                "INCR" => incr = true,
+                _ => break,
             }
-            let score = chunk[0].parse::<f64>().map_err(|_| Error::msg("ERR score is not a valid float"))?;
-            let member = chunk[1].to_string();
+            index += 1;
+        }
+
+        let pairs = &args[index..];
+        if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+            return Err(Error::msg("ERR syntax error"));
+        }
+        if nx && xx {
+            return Err(Error::msg("ERR XX and NX options at the same time are not compatible"));
+        }
+        if (gt && lt) || (nx && (gt || lt)) {
+            return Err(Error::msg("ERR GT, LT, and/or NX options at the same time are not compatible"));
+        }
+        if incr && pairs.len() > 2 {
+            return Err(Error::msg("ERR INCR option supports a single increment-element pair"));
+        }
+
+        let raw_pairs = &frame.get_args_bytes()[index..];
+        let mut members = Vec::new();
+        for (chunk, raw_chunk) in pairs.chunks(2).zip(raw_pairs.chunks(2)) {
+            let score = chunk[0].parse::<f64>().ok()
+                .filter(|score| !score.is_nan())
+                .ok_or_else(|| Error::msg("ERR value is not a valid float"))?;
+            let member = raw_chunk[1].clone();
             members.push((score, member));
         }
 
-        Ok(Zadd { key, members })
+        let condition = if nx { Condition::Nx } else if xx { Condition::Xx } else { Condition::Always };
+        let comparison = if gt { Comparison::Gt } else if lt { Comparison::Lt } else { Comparison::Always };
+        Ok(Zadd { key, condition, comparison, ch, incr, members })
     }
 
+    /**
+     * 添加或更新成员
+     *
+     * 默认返回新增成员的数量，指定 CH 时返回新增与分数被修改的成员数量；
+     * 指定 INCR 时返回成员的新分数，因 NX / XX / GT / LT 未执行时返回 nil
+     *
+     * 先只读地计算需要写入的成员，没有成员变化时不修改键，被 WATCH 的键不会被误判为已修改
+     */
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let set = match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => Some(set),
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => None,
+        };
+
         let mut added_count = 0;
+        let mut changed_count = 0;
+        let mut incr_result = None;
+        // 同一命令中重复出现的成员以前面写入的分数为准
+        let mut updates: HashMap<&[u8], f64> = HashMap::new();
+        let mut order = Vec::new();
+        for (score, member) in &self.members {
+            let current = updates.get(member.as_slice()).copied()
+                .or_else(|| set.and_then(|set| set.get_score(member)));
+            let score = match current {
+                Some(current) if self.incr => current + score,
+                _ => *score,
+            };
+            if score.is_nan() {
+                return Ok(Frame::Error("ERR resulting score is not a number (NaN)".to_string()));
+            }
+            // XX 只更新已有成员，键不存在时不会创建
+            let allowed = match (current, self.condition, self.comparison) {
+                (Some(_), Condition::Nx, _) | (None, Condition::Xx, _) => false,
+                (Some(current), _, Comparison::Gt) => score > current,
+                (Some(current), _, Comparison::Lt) => score < current,
+                _ => true,
+            };
+            if !allowed {
+                continue;
+            }
+            incr_result = Some(score);
+            if current.is_none() {
+                added_count += 1; // 成员新增成功
+            } else if current != Some(score) {
+                changed_count += 1;
+            } else {
+                continue;
+            }
+            if updates.insert(member, score).is_none() {
+                order.push(member);
+            }
+        }
 
-        match db.records.get_mut(&self.key) {
-            Some(structure) => {
-                match structure {
-                    Structure::SortedSet(set) => {
-                        for (score, member) in self.members {
-                            if set.add(member, score) {
-                                added_count += 1; // 成员新增成功
-                            }
-                        }
-                    },
-                    _ => {
-                        let f = "ERR Operation against a key holding the wrong kind of value";
-                        return Ok(Frame::Error(f.to_string()));
-                    }
-                }
-            },
-            None => { // 键不存在，创建一个新的有序集合并插入所有成员
-                let mut set = SortedSet::new();
-                for (score, member) in self.members {
-                    if set.add(member, score) {
-                        added_count += 1; // 成员新增成功
-                    }
-                }
-                db.records.insert(self.key, Structure::SortedSet(set));
+        if !order.is_empty() {
+            let members = order.into_iter().map(|member| (member.clone(), updates[member.as_slice()]));
+            match db.get_mut(&self.key) {
+                Some(Structure::SortedSet(set)) => members.for_each(|(member, score)| {
+                    set.add(member, score);
+                }),
+                _ => {
+                    let mut set = SortedSet::new();
+                    members.for_each(|(member, score)| {
+                        set.add(member, score);
+                    });
+                    db.insert(self.key.clone(), Structure::SortedSet(set));
+                },
             }
         }
 
-        Ok(Frame::Integer(added_count as i64))
+        if self.incr {
+            return Ok(incr_result.map_or(Frame::Null, Frame::Double));
+        }
+        let count = if self.ch { added_count + changed_count } else { added_count };
+        Ok(Frame::Integer(count as i64))
     }
-}
\ No newline at end of file
+}
@@ -3,7 +3,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Zcard {
-    key: String,
+    key: Vec<u8>,
 }
 
 impl Zcard {
@@ -12,12 +12,12 @@ impl Zcard {
         if args.len() != 2 {
             return Err(Error::msg("ERR wrong number of arguments for 'zcard' command"));
         }
-        let key = args[1].to_string(); // 键
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
         Ok(Zcard { key })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::SortedSet(set) => {
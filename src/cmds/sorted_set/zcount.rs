@@ -1,11 +1,11 @@
 use anyhow::Error;
 
-use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::{store::{db::{Db, Structure}, sorted_set::ScoreBound}, frame::Frame};
 
 pub struct Zcount {
-    key: String,
-    min: f64,
-    max: f64,
+    key: Vec<u8>,
+    min: ScoreBound,
+    max: ScoreBound,
 }
 
 impl Zcount {
@@ -15,20 +15,20 @@ impl Zcount {
         if args.len() != 4 {
             return Err(Error::msg("ERR wrong number of arguments for 'zcount' command"));
         }
-        let key = args[1].to_string(); // 键
-        let min = args[2].parse::<f64>().map_err(|_| Error::msg("ERR min is not a valid float"))?;
-        let max = args[3].parse::<f64>().map_err(|_| Error::msg("ERR max is not a valid float"))?;
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let min = ScoreBound::parse(&args[2]).ok_or_else(|| Error::msg("ERR min or max is not a float"))?;
+        let max = ScoreBound::parse(&args[3]).ok_or_else(|| Error::msg("ERR min or max is not a float"))?;
         Ok(Zcount { key, min, max })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::SortedSet(set) => {
-                        // 使用跳表的高效范围查询
-                        let count = set.count_by_score(self.min, self.max);
-                        Ok(Frame::Integer(count as i64))
+                        // 跳表中区间两端的索引之差即为数量，O(log n)
+                        let (start, end) = set.score_range(self.min, self.max);
+                        Ok(Frame::Integer((end - start) as i64))
                     },
                     _ => {
                         let f = "ERR Operation against a key holding the wrong kind of value";
@@ -3,10 +3,11 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 use crate::store::sorted_set::SortedSet;
 
+#[derive(Clone)]
 pub struct Zincrby {
-    key: String,
+    key: Vec<u8>,
     increment: f64,
-    member: String,
+    member: Vec<u8>,
 }
 
 impl Zincrby {
@@ -16,16 +17,16 @@ impl Zincrby {
             return Err(Error::msg("ERR wrong number of arguments for 'zincrby' command"));
         }
         
-        let key = args[1].to_string(); // 键
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
         let increment = args[2].parse::<f64>().map_err(|_| Error::msg("ERR value is not a valid float"))?;
-        let member = args[3].to_string(); // 成员
+        let member = frame.get_arg_bytes(3).unwrap(); // 成员
         
         Ok(Zincrby { key, increment, member })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
         // 获取当前成员的分数，如果不存在则默认为0.0
-        let current_score = match db.records.get_mut(&self.key) {
+        let current_score = match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::SortedSet(set) => {
@@ -43,7 +44,7 @@ impl Zincrby {
                 // 键不存在，创建新的有序集合
                 let mut set = SortedSet::new();
                 set.add(self.member.clone(), 0.0);
-                db.records.insert(self.key.clone(), Structure::SortedSet(set));
+                db.insert(self.key.clone(), Structure::SortedSet(set));
                 0.0
             }
         };
@@ -52,7 +53,7 @@ impl Zincrby {
         let new_score = current_score + self.increment;
 
         // 更新分数（add 方法会自动处理已存在的成员）
-        match db.records.get_mut(&self.key) {
+        match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::SortedSet(set) => {
@@ -65,6 +66,6 @@ impl Zincrby {
         }
 
         // 返回新分数
-        Ok(Frame::BulkString(new_score.to_string()))
+        Ok(Frame::Double(new_score))
     }
 }
\ No newline at end of file
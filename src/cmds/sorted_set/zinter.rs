@@ -0,0 +1,23 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zunion::{SetOpArgs, SetOperation};
+
+pub struct Zinter {
+    args: SetOpArgs,
+}
+
+impl Zinter {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args_bytes();
+        if args.len() < 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zinter' command"));
+        }
+        let args = SetOpArgs::parse(&args[1..], "zinter", SetOperation::Inter, true)?;
+        Ok(Zinter { args })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(self.args.reply(db))
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zunion::{members, parse_keys};
+
+pub struct Zintercard {
+    keys: Vec<Vec<u8>>,
+    limit: usize,
+}
+
+impl Zintercard {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args_bytes();
+        if args.len() < 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zintercard' command"));
+        }
+        let (keys, options) = parse_keys(&args[1..], "zintercard")?;
+        // LIMIT 为 0 表示不限制
+        let limit = match options.as_slice() {
+            [] => 0,
+            [option, limit] if option.eq_ignore_ascii_case("LIMIT") => {
+                let limit = limit.parse::<i64>().map_err(|_| Error::msg("ERR LIMIT can't be negative"))?;
+                if limit < 0 {
+                    return Err(Error::msg("ERR LIMIT can't be negative"));
+                }
+                limit as usize
+            },
+            _ => return Err(Error::msg("ERR syntax error")),
+        };
+        Ok(Zintercard { keys, limit })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let mut inputs = Vec::with_capacity(self.keys.len());
+        for key in &self.keys {
+            match members(db, key) {
+                Some(input) => inputs.push(input.into_iter().map(|(member, _)| member).collect::<HashSet<Vec<u8>>>()),
+                None => {
+                    let f = "ERR Operation against a key holding the wrong kind of value";
+                    return Ok(Frame::Error(f.to_string()));
+                },
+            }
+        }
+
+        // 从最小的集合开始检查，达到 LIMIT 后立即返回
+        inputs.sort_by_key(|input| input.len());
+        let (smallest, others) = inputs.split_first().unwrap();
+        let mut count = 0;
+        for member in smallest {
+            if others.iter().all(|input| input.contains(member)) {
+                count += 1;
+                if count == self.limit {
+                    break;
+                }
+            }
+        }
+        Ok(Frame::Integer(count as i64))
+    }
+}
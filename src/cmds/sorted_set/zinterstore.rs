@@ -0,0 +1,26 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zunion::{SetOpArgs, SetOperation};
+
+#[derive(Clone)]
+pub struct Zinterstore {
+    destination: Vec<u8>,
+    args: SetOpArgs,
+}
+
+impl Zinterstore {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args_bytes();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zinterstore' command"));
+        }
+        let destination = args[1].clone();
+        let args = SetOpArgs::parse(&args[2..], "zinterstore", SetOperation::Inter, false)?;
+        Ok(Zinterstore { destination, args })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(self.args.store(db, self.destination))
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zunion::parse_keys;
+
+/// ZMPOP / BZMPOP 共用的参数：numkeys key [key ...] MIN|MAX [COUNT count]
+#[derive(Clone)]
+pub struct MpopArgs {
+    pub keys: Vec<Vec<u8>>,
+    pub max: bool,
+    pub count: usize,
+}
+
+impl MpopArgs {
+    /**
+     * @param args 从 numkeys 开始的参数
+     * @param command 命令名称（用于错误信息）
+     */
+    pub fn parse(args: &[Vec<u8>], command: &str) -> Result<Self, Error> {
+        if args.first().and_then(|numkeys| String::from_utf8_lossy(numkeys).parse::<i64>().ok()).is_some_and(|numkeys| numkeys < 1) {
+            return Err(Error::msg("ERR numkeys should be greater than 0"));
+        }
+        let (keys, options) = parse_keys(args, command)?;
+        let max = match options.first().map(|direction| direction.to_uppercase()).as_deref() {
+            Some("MIN") => false,
+            Some("MAX") => true,
+            _ => return Err(Error::msg("ERR syntax error")),
+        };
+        let count = match &options[1..] {
+            [] => 1,
+            [option, count] if option.eq_ignore_ascii_case("COUNT") => {
+                count.parse::<i64>().ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| Error::msg("ERR count should be greater than 0"))? as usize
+            },
+            _ => return Err(Error::msg("ERR syntax error")),
+        };
+        Ok(MpopArgs { keys, max, count })
+    }
+
+    /// 从第一个非空的有序集合弹出，回复 [键, [[成员, 分数], ...]]，所有键都为空时回复 nil
+    pub fn apply(&self, db: &mut Db) -> Frame {
+        for key in &self.keys {
+            let Some(popped) = super::pop(db, key, self.count, self.max) else {
+                return super::wrong_type();
+            };
+            if !popped.is_empty() {
+                let members = popped.into_iter()
+                    .map(|(member, score)| Frame::Array(vec![Frame::BulkString(member), Frame::Double(score)]))
+                    .collect();
+                return Frame::Array(vec![Frame::BulkString(key.clone()), Frame::Array(members)]);
+            }
+        }
+        Frame::NullArray
+    }
+}
+
+pub struct Zmpop {
+    args: MpopArgs,
+}
+
+impl Zmpop {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args_bytes();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zmpop' command"));
+        }
+        let args = MpopArgs::parse(&args[1..], "zmpop")?;
+        Ok(Zmpop { args })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(self.args.apply(db))
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+
+pub struct Zmscore {
+    key: Vec<u8>,
+    members: Vec<Vec<u8>>,
+}
+
+impl Zmscore {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zmscore' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let members = frame.get_args_bytes_from_index(2); // 成员
+        Ok(Zmscore { key, members })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => {
+                let scores = self.members.iter()
+                    .map(|member| set.get_score(member).map_or(Frame::Null, Frame::Double))
+                    .collect();
+                Ok(Frame::Array(scores))
+            },
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                Ok(Frame::Error(f.to_string()))
+            },
+            // 键不存在时每个成员都返回 nil
+            None => Ok(Frame::Array(vec![Frame::Null; self.members.len()])),
+        }
+    }
+}
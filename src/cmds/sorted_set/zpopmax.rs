@@ -0,0 +1,19 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zpopmin::Zpop;
+
+pub struct Zpopmax {
+    pop: Zpop,
+}
+
+impl Zpopmax {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let pop = Zpop::parse(&frame.get_args_bytes(), "zpopmax", true)?;
+        Ok(Zpopmax { pop })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        self.pop.apply(db)
+    }
+}
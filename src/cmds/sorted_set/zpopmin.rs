@@ -0,0 +1,58 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+
+/// ZPOPMIN / ZPOPMAX 共用的实现
+pub struct Zpop {
+    key: Vec<u8>,
+    count: usize,
+    max: bool,
+}
+
+impl Zpop {
+    pub fn parse(args: &[Vec<u8>], command: &str, max: bool) -> Result<Self, Error> {
+        if args.len() != 2 && args.len() != 3 {
+            return Err(Error::msg(format!("ERR wrong number of arguments for '{}' command", command)));
+        }
+        let key = args[1].clone(); // 键
+        let count = match args.get(2) {
+            Some(count) => {
+                let count = String::from_utf8_lossy(count).parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+                if count < 0 {
+                    return Err(Error::msg("ERR value is out of range, must be positive"));
+                }
+                count as usize
+            },
+            None => 1,
+        };
+        Ok(Zpop { key, count, max })
+    }
+
+    /// 回复 [成员, 分数, 成员, 分数, ...]，键不存在时为空数组
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let Some(popped) = super::pop(db, &self.key, self.count, self.max) else {
+            return Ok(super::wrong_type());
+        };
+        let mut result = Vec::with_capacity(popped.len() * 2);
+        for (member, score) in popped {
+            result.push(Frame::BulkString(member));
+            result.push(Frame::Double(score));
+        }
+        Ok(Frame::Array(result))
+    }
+}
+
+pub struct Zpopmin {
+    pop: Zpop,
+}
+
+impl Zpopmin {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let pop = Zpop::parse(&frame.get_args_bytes(), "zpopmin", false)?;
+        Ok(Zpopmin { pop })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        self.pop.apply(db)
+    }
+}
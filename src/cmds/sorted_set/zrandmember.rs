@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use anyhow::Error;
+
+use crate::{store::{db::{Db, Structure}, memory::random_u64, sorted_set::SortedSet}, frame::Frame};
+
+pub struct Zrandmember {
+    key: Vec<u8>,
+    count: Option<i64>,
+    with_scores: bool,
+}
+
+impl Zrandmember {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 2 || args.len() > 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zrandmember' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let count = match args.get(2) {
+            Some(count) => Some(count.parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?),
+            None => None,
+        };
+        let with_scores = match args.get(3) {
+            Some(option) if option.eq_ignore_ascii_case("WITHSCORES") => true,
+            Some(_) => return Err(Error::msg("ERR syntax error")),
+            None => false,
+        };
+        Ok(Zrandmember { key, count, with_scores })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let set = match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => set,
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => return Ok(if self.count.is_some() { Frame::Array(vec![]) } else { Frame::Null }),
+        };
+
+        let Some(count) = self.count else {
+            // 未指定 count 时返回单个成员
+            return Ok(match random_index(set.len()).and_then(|index| set.get_by_index(index)) {
+                Some((member, _)) => Frame::BulkString(member.clone()),
+                None => Frame::Null,
+            });
+        };
+
+        let indexes = if count >= 0 {
+            distinct_indexes(set.len(), count as usize)
+        } else {
+            // count 为负数时允许重复，返回 |count| 个成员
+            (0..count.unsigned_abs()).filter_map(|_| random_index(set.len())).collect()
+        };
+        Ok(Frame::Array(reply(set, indexes, self.with_scores)))
+    }
+}
+
+fn random_index(len: usize) -> Option<usize> {
+    (len > 0).then(|| (random_u64() % len as u64) as usize)
+}
+
+/**
+ * 随机选取 count 个不重复的索引（Floyd 抽样），只需 O(count) 次随机数
+ *
+ * @param len 有序集合的成员数量
+ * @param count 需要的数量，不少于 len 时返回全部索引
+ */
+fn distinct_indexes(len: usize, count: usize) -> Vec<usize> {
+    if count >= len {
+        return (0..len).collect();
+    }
+    let mut selected = HashSet::with_capacity(count);
+    let mut indexes = Vec::with_capacity(count);
+    for upper in (len - count)..len {
+        let index = (random_u64() % (upper as u64 + 1)) as usize;
+        let index = if selected.insert(index) { index } else { selected.insert(upper); upper };
+        indexes.push(index);
+    }
+    indexes
+}
+
+fn reply(set: &SortedSet, indexes: Vec<usize>, with_scores: bool) -> Vec<Frame> {
+    let mut result = Vec::new();
+    for (member, score) in indexes.into_iter().filter_map(|index| set.get_by_index(index)) {
+        result.push(Frame::BulkString(member.clone()));
+        if with_scores {
+            result.push(Frame::Double(score));
+        }
+    }
+    result
+}
@@ -1,11 +1,213 @@
 use anyhow::Error;
 
-use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::{store::{db::{Db, Structure}, sorted_set::{LexBound, ScoreBound, SortedSet}}, frame::Frame};
+
+/// ZRANGE 系列命令的区间类型
+#[derive(Clone)]
+pub enum RangeBy {
+    /// 按排名：start stop，负数表示从末尾倒数
+    Index(i64, i64),
+    /// 按分数：min max
+    Score(ScoreBound, ScoreBound),
+    /// 按字典序：min max
+    Lex(LexBound, LexBound),
+}
+
+/**
+ * ZRANGE 系列命令共用的查询：区间、方向与 LIMIT
+ *
+ * 所有查询先在跳表上换算为索引区间（O(log n)），再只读取需要返回的成员（O(m)）
+ *
+ * @param rev 按分数从大到小；按排名时 start stop 从最大的成员算起
+ * @param limit LIMIT offset count，count 为负数表示不限数量
+ */
+#[derive(Clone)]
+pub struct RangeQuery {
+    pub by: RangeBy,
+    pub rev: bool,
+    pub limit: Option<(i64, i64)>,
+}
+
+impl RangeQuery {
+
+    /**
+     * 解析 ZRANGE / ZRANGESTORE 的区间参数
+     *
+     * 格式：start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]，
+     * REV 与 BYSCORE / BYLEX 同时使用时区间参数为 max min
+     *
+     * @param raw_args key 之后的参数（字典序区间按字节解析）
+     * @return 查询与是否指定了 WITHSCORES
+     */
+    pub fn parse(raw_args: &[Vec<u8>]) -> Result<(Self, bool), Error> {
+        let args: Vec<String> = raw_args.iter().map(|arg| String::from_utf8_lossy(arg).into_owned()).collect();
+        let mut by_score = false;
+        let mut by_lex = false;
+        let mut rev = false;
+        let mut limit = None;
+        let mut with_scores = false;
+        let mut i = 2;
+        while i < args.len() {
+            match args[i].to_uppercase().as_str() {
+                "BYSCORE" => by_score = true,
+                "BYLEX" => by_lex = true,
+                "REV" => rev = true,
+                "WITHSCORES" => with_scores = true,
+                "LIMIT" if i + 2 < args.len() => {
+                    limit = Some(parse_limit(&args[i + 1], &args[i + 2])?);
+                    i += 2;
+                },
+                _ => return Err(Error::msg("ERR syntax error")),
+            }
+            i += 1;
+        }
+        if by_score && by_lex {
+            return Err(Error::msg("ERR syntax error"));
+        }
+        if limit.is_some() && !by_score && !by_lex {
+            return Err(Error::msg("ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"));
+        }
+        if with_scores && by_lex {
+            return Err(Error::msg("ERR syntax error, WITHSCORES not supported in combination with BYLEX"));
+        }
+        let (min, max) = if rev && (by_score || by_lex) { (1, 0) } else { (0, 1) };
+        let by = if by_score {
+            RangeBy::Score(parse_score_bound(&args[min])?, parse_score_bound(&args[max])?)
+        } else if by_lex {
+            RangeBy::Lex(parse_lex_bound(&raw_args[min])?, parse_lex_bound(&raw_args[max])?)
+        } else {
+            RangeBy::Index(parse_index(&args[0])?, parse_index(&args[1])?)
+        };
+        Ok((RangeQuery { by, rev, limit }, with_scores))
+    }
+
+    /// 查询结果（成员, 分数），按查询方向排列
+    pub fn select(&self, set: &SortedSet) -> Vec<(Vec<u8>, f64)> {
+        let (start, end) = self.window(set);
+        set.slice(start, end, self.rev)
+    }
+
+    /// 查询结果在跳表中的索引区间 [start, end)
+    pub fn window(&self, set: &SortedSet) -> (usize, usize) {
+        let (start, end) = match &self.by {
+            RangeBy::Index(start, stop) => {
+                let Some((start, end)) = index_window(set.len(), *start, *stop) else {
+                    return (0, 0);
+                };
+                // 倒序时排名从最大的成员算起
+                if self.rev { (set.len() - end, set.len() - start) } else { (start, end) }
+            },
+            RangeBy::Score(min, max) => set.score_range(*min, *max),
+            RangeBy::Lex(min, max) => set.lex_range(min, max),
+        };
+        match self.limit {
+            None => (start, end),
+            Some((offset, _)) if offset < 0 => (0, 0),
+            Some((offset, count)) => {
+                let offset = offset as usize;
+                let available = (end - start).saturating_sub(offset);
+                if available == 0 {
+                    return (0, 0);
+                }
+                let count = if count < 0 { available } else { (count as usize).min(available) };
+                // 倒序时从区间的末尾跳过 offset 个成员
+                if self.rev { (end - offset - count, end - offset) } else { (start + offset, start + offset + count) }
+            },
+        }
+    }
+}
+
+/**
+ * 按排名的区间换算为索引区间 [start, end)
+ *
+ * @param len 有序集合的成员数量
+ * @param start 起始排名，负数表示从末尾倒数
+ * @param stop 结束排名（包含），负数表示从末尾倒数
+ * @return 区间为空时返回 None
+ */
+pub fn index_window(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 { (len + start).max(0) } else { start };
+    let stop = if stop < 0 { len + stop } else { stop.min(len - 1) };
+    if start > stop || start >= len {
+        return None;
+    }
+    Some((start as usize, stop as usize + 1))
+}
+
+pub fn parse_index(value: &str) -> Result<i64, Error> {
+    value.parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))
+}
+
+pub fn parse_score_bound(value: &str) -> Result<ScoreBound, Error> {
+    ScoreBound::parse(value).ok_or_else(|| Error::msg("ERR min or max is not a float"))
+}
+
+pub fn parse_lex_bound(value: &[u8]) -> Result<LexBound, Error> {
+    LexBound::parse(value).ok_or_else(|| Error::msg("ERR min or max not valid string range item"))
+}
+
+pub fn parse_limit(offset: &str, count: &str) -> Result<(i64, i64), Error> {
+    Ok((parse_index(offset)?, parse_index(count)?))
+}
+
+/**
+ * 解析 ZRANGEBYSCORE / ZRANGEBYLEX 等命令的可选参数：[WITHSCORES] [LIMIT offset count]
+ *
+ * @param args 区间之后的参数
+ * @param allow_scores 是否支持 WITHSCORES（按字典序的命令不支持）
+ * @return 是否指定了 WITHSCORES，以及 LIMIT
+ */
+pub fn parse_options(args: &[String], allow_scores: bool) -> Result<(bool, Option<(i64, i64)>), Error> {
+    let mut with_scores = false;
+    let mut limit = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].to_uppercase().as_str() {
+            "WITHSCORES" if allow_scores => with_scores = true,
+            "LIMIT" if i + 2 < args.len() => {
+                limit = Some(parse_limit(&args[i + 1], &args[i + 2])?);
+                i += 2;
+            },
+            _ => return Err(Error::msg("ERR syntax error")),
+        }
+        i += 1;
+    }
+    Ok((with_scores, limit))
+}
+
+/**
+ * 执行查询并生成回复
+ *
+ * @param db 数据库
+ * @param key 键
+ * @param query 查询
+ * @param with_scores 是否在每个成员之后返回分数
+ */
+pub fn range(db: &mut Db, key: &[u8], query: &RangeQuery, with_scores: bool) -> Result<Frame, Error> {
+    match db.get(key) {
+        Some(Structure::SortedSet(set)) => {
+            let mut result = Vec::new();
+            for (member, score) in query.select(set) {
+                result.push(Frame::BulkString(member));
+                if with_scores {
+                    result.push(Frame::Double(score));
+                }
+            }
+            Ok(Frame::Array(result))
+        },
+        Some(_) => {
+            let f = "ERR Operation against a key holding the wrong kind of value";
+            Ok(Frame::Error(f.to_string()))
+        },
+        // 键不存在，返回空数组
+        None => Ok(Frame::Array(vec![])),
+    }
+}
 
 pub struct Zrange {
-    key: String,
-    start: i64,
-    stop: i64,
+    key: Vec<u8>,
+    query: RangeQuery,
     with_scores: bool,
 }
 
@@ -15,75 +217,12 @@ impl Zrange {
         if args.len() < 4 {
             return Err(Error::msg("ERR wrong number of arguments for 'zrange' command"));
         }
-        
-        let key = args[1].to_string();
-        let start = args[2].to_string().parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
-        let stop = args[3].to_string().parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
-        
-        // 检查是否有WITHSCORES参数
-        let mut with_scores = false;
-        for arg in args.iter().skip(4) {
-            if arg.to_uppercase() == "WITHSCORES" {
-                with_scores = true;
-                break;
-            }
-        }
-        
-        Ok(Zrange { key, start, stop, with_scores })
+        let key = frame.get_arg_bytes(1).unwrap();
+        let (query, with_scores) = RangeQuery::parse(&frame.get_args_bytes()[2..])?;
+        Ok(Zrange { key, query, with_scores })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
-            Some(structure) => {
-                match structure {
-                    Structure::SortedSet(set) => {
-                        // 跳表已经按 (score, member) 排序，直接使用
-                        let len = set.len() as i64;
-                        
-                        // 处理负数索引
-                        let start_idx = if self.start < 0 {
-                            (len + self.start).max(0)
-                        } else {
-                            self.start.min(len)
-                        };
-                        
-                        let stop_idx = if self.stop < 0 {
-                            (len + self.stop).max(-1)
-                        } else {
-                            self.stop.min(len - 1)
-                        };
-                        
-                        // 确保索引有效
-                        if start_idx > stop_idx || start_idx >= len {
-                            return Ok(Frame::Array(vec![]));
-                        }
-                        
-                        // 使用跳表的 range 方法，O(log n + m) 时间复杂度
-                        let start_idx = start_idx as usize;
-                        let stop_idx = stop_idx as usize;
-                        let selected_members = set.range(start_idx, stop_idx);
-                        
-                        // 构建返回结果
-                        let mut result = Vec::new();
-                        for (member, score) in selected_members {
-                            result.push(Frame::BulkString(member));
-                            if self.with_scores {
-                                result.push(Frame::BulkString(score.to_string()));
-                            }
-                        }
-                        
-                        Ok(Frame::Array(result))
-                    },
-                    _ => {
-                        let f = "ERR Operation against a key holding the wrong kind of value";
-                        Ok(Frame::Error(f.to_string()))
-                    }
-                }
-            },
-            None => {
-                // 键不存在，返回空数组
-                Ok(Frame::Array(vec![]))
-            }
-        }
+        range(db, &self.key, &self.query, self.with_scores)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,28 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zrange::{parse_lex_bound, parse_options, range, RangeBy, RangeQuery};
+
+pub struct Zrangebylex {
+    key: Vec<u8>,
+    query: RangeQuery,
+}
+
+impl Zrangebylex {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zrangebylex' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap();
+        let min = parse_lex_bound(&frame.get_arg_bytes(2).unwrap_or_default())?;
+        let max = parse_lex_bound(&frame.get_arg_bytes(3).unwrap_or_default())?;
+        let (_, limit) = parse_options(&args[4..], false)?;
+        let query = RangeQuery { by: RangeBy::Lex(min, max), rev: false, limit };
+        Ok(Zrangebylex { key, query })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        range(db, &self.key, &self.query, false)
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zrange::{parse_score_bound, parse_options, range, RangeBy, RangeQuery};
+
+pub struct Zrangebyscore {
+    key: Vec<u8>,
+    query: RangeQuery,
+    with_scores: bool,
+}
+
+impl Zrangebyscore {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zrangebyscore' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap();
+        let min = parse_score_bound(&args[2])?;
+        let max = parse_score_bound(&args[3])?;
+        let (with_scores, limit) = parse_options(&args[4..], true)?;
+        let query = RangeQuery { by: RangeBy::Score(min, max), rev: false, limit };
+        Ok(Zrangebyscore { key, query, with_scores })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        range(db, &self.key, &self.query, self.with_scores)
+    }
+}
@@ -0,0 +1,51 @@
+use anyhow::Error;
+
+use crate::{store::{db::{Db, Structure}, sorted_set::SortedSet}, frame::Frame};
+use super::zrange::RangeQuery;
+
+#[derive(Clone)]
+pub struct Zrangestore {
+    destination: Vec<u8>,
+    source: Vec<u8>,
+    query: RangeQuery,
+}
+
+impl Zrangestore {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 5 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zrangestore' command"));
+        }
+        let destination = frame.get_arg_bytes(1).unwrap();
+        let source = frame.get_arg_bytes(2).unwrap();
+        let (query, with_scores) = RangeQuery::parse(&frame.get_args_bytes()[3..])?;
+        if with_scores {
+            return Err(Error::msg("ERR syntax error"));
+        }
+        Ok(Zrangestore { destination, source, query })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let selected = match db.get(&self.source) {
+            Some(Structure::SortedSet(set)) => self.query.select(set),
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => Vec::new(),
+        };
+
+        // 结果为空时删除目标键，与 Redis 一致
+        let count = selected.len();
+        if selected.is_empty() {
+            db.remove(&self.destination);
+        } else {
+            let mut set = SortedSet::new();
+            for (member, score) in selected {
+                set.add(member, score);
+            }
+            db.insert(self.destination, Structure::SortedSet(set));
+        }
+        Ok(Frame::Integer(count as i64))
+    }
+}
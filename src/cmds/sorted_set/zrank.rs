@@ -1,35 +1,37 @@
 use anyhow::Error;
 
-use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::{store::{db::{Db, Structure}, sorted_set::SortedSet}, frame::Frame};
 
 pub struct Zrank {
-    key: String,
-    member: String,
+    key: Vec<u8>,
+    member: Vec<u8>,
+    with_score: bool,
 }
 
 impl Zrank {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
         let args = frame.get_args();
-        if args.len() != 3 {
+        if args.len() != 3 && args.len() != 4 {
             return Err(Error::msg("ERR wrong number of arguments for 'zrank' command"));
         }
-        let key = args[1].to_string(); // 键
-        let member = args[2].to_string(); // 成员
-        Ok(Zrank { key, member })
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let member = frame.get_arg_bytes(2).unwrap(); // 成员
+        let with_score = match args.get(3) {
+            Some(option) if option.eq_ignore_ascii_case("WITHSCORE") => true,
+            Some(_) => return Err(Error::msg("ERR syntax error")),
+            None => false,
+        };
+        Ok(Zrank { key, member, with_score })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::SortedSet(set) => {
                         // 使用跳表计算排名，O(log n) 时间复杂度
-                        if let Some(rank) = set.rank(&self.member) {
-                            Ok(Frame::Integer(rank as i64))
-                        } else {
-                            // 如果成员不存在，返回 nil
-                            Ok(Frame::Null)
-                        }
+                        let rank = set.rank(&self.member);
+                        Ok(rank_reply(set, &self.member, rank, self.with_score))
                     },
                     _ => {
                         let f = "ERR Operation against a key holding the wrong kind of value";
@@ -39,8 +41,23 @@ impl Zrank {
             },
             None => {
                 // 如果键不存在，返回 nil
-                Ok(Frame::Null)
+                Ok(if self.with_score { Frame::NullArray } else { Frame::Null })
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/**
+ * ZRANK / ZREVRANK 的回复
+ *
+ * @param rank 成员的排名，成员不存在时为 None（回复 nil）
+ * @param with_score 指定 WITHSCORE 时回复 [排名, 分数]
+ */
+pub fn rank_reply(set: &SortedSet, member: &[u8], rank: Option<usize>, with_score: bool) -> Frame {
+    match (rank, set.get_score(member)) {
+        (Some(rank), Some(score)) if with_score => Frame::Array(vec![Frame::Integer(rank as i64), Frame::Double(score)]),
+        (Some(rank), _) => Frame::Integer(rank as i64),
+        (None, _) if with_score => Frame::NullArray,
+        (None, _) => Frame::Null,
+    }
+}
@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Zrem {
-    key: String,
-    members: Vec<String>,
+    key: Vec<u8>,
+    members: Vec<Vec<u8>>,
 }
 
 impl Zrem {
@@ -12,13 +12,19 @@ impl Zrem {
         if args.len() < 3 {
             return Err(Error::msg("ERR wrong number of arguments for 'zrem' command"));
         }
-        let key = args[1].to_string(); // 键
-        let members = args[2..].iter().map(|arg| arg.to_string()).collect(); // 要移除的成员
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let members = frame.get_args_bytes_from_index(2); // 要移除的成员
         Ok(Zrem { key, members })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get_mut(&self.key) {
+        // 成员都不在有序集合中时不修改键
+        if let Some(Structure::SortedSet(set)) = db.get(&self.key) {
+            if !self.members.iter().any(|member| set.get_score(member).is_some()) {
+                return Ok(Frame::Integer(0));
+            }
+        }
+        match db.get_mut(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::SortedSet(set) => {
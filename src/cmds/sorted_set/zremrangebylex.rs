@@ -0,0 +1,46 @@
+use anyhow::Error;
+
+use crate::{store::{db::{Db, Structure}, sorted_set::LexBound}, frame::Frame};
+use super::zrange::parse_lex_bound;
+
+pub struct Zremrangebylex {
+    key: Vec<u8>,
+    min: LexBound,
+    max: LexBound,
+}
+
+impl Zremrangebylex {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zremrangebylex' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let min = parse_lex_bound(&frame.get_arg_bytes(2).unwrap_or_default())?;
+        let max = parse_lex_bound(&frame.get_arg_bytes(3).unwrap_or_default())?;
+        Ok(Zremrangebylex { key, min, max })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 字典序范围内没有成员时不修改键
+        let (start, end) = match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => set.lex_range(&self.min, &self.max),
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => return Ok(Frame::Integer(0)),
+        };
+        if start >= end {
+            return Ok(Frame::Integer(0));
+        }
+        let removed = match db.get_mut(&self.key) {
+            Some(Structure::SortedSet(set)) => set.remove_slice(start, end),
+            _ => 0,
+        };
+        if matches!(db.get(&self.key), Some(Structure::SortedSet(set)) if set.is_empty()) {
+            db.remove(&self.key);
+        }
+        Ok(Frame::Integer(removed as i64))
+    }
+}
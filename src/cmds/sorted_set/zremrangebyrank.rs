@@ -0,0 +1,46 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use super::zrange::{index_window, parse_index};
+
+pub struct Zremrangebyrank {
+    key: Vec<u8>,
+    start: i64,
+    stop: i64,
+}
+
+impl Zremrangebyrank {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zremrangebyrank' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let start = parse_index(&args[2])?;
+        let stop = parse_index(&args[3])?;
+        Ok(Zremrangebyrank { key, start, stop })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 下标范围为空时不修改键
+        let window = match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => index_window(set.len(), self.start, self.stop),
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => return Ok(Frame::Integer(0)),
+        };
+        let Some((start, end)) = window else {
+            return Ok(Frame::Integer(0));
+        };
+        let removed = match db.get_mut(&self.key) {
+            Some(Structure::SortedSet(set)) => set.remove_slice(start, end),
+            _ => 0,
+        };
+        if matches!(db.get(&self.key), Some(Structure::SortedSet(set)) if set.is_empty()) {
+            db.remove(&self.key);
+        }
+        Ok(Frame::Integer(removed as i64))
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::Error;
+
+use crate::{store::{db::{Db, Structure}, sorted_set::ScoreBound}, frame::Frame};
+use super::zrange::parse_score_bound;
+
+pub struct Zremrangebyscore {
+    key: Vec<u8>,
+    min: ScoreBound,
+    max: ScoreBound,
+}
+
+impl Zremrangebyscore {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zremrangebyscore' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let min = parse_score_bound(&args[2])?;
+        let max = parse_score_bound(&args[3])?;
+        Ok(Zremrangebyscore { key, min, max })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 分数范围内没有成员时不修改键
+        let (start, end) = match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => set.score_range(self.min, self.max),
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => return Ok(Frame::Integer(0)),
+        };
+        if start >= end {
+            return Ok(Frame::Integer(0));
+        }
+        let removed = match db.get_mut(&self.key) {
+            Some(Structure::SortedSet(set)) => set.remove_slice(start, end),
+            _ => 0,
+        };
+        if matches!(db.get(&self.key), Some(Structure::SortedSet(set)) if set.is_empty()) {
+            db.remove(&self.key);
+        }
+        Ok(Frame::Integer(removed as i64))
+    }
+}
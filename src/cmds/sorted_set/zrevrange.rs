@@ -0,0 +1,33 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use super::zrange::{parse_index, range, RangeBy, RangeQuery};
+
+pub struct Zrevrange {
+    key: Vec<u8>,
+    query: RangeQuery,
+    with_scores: bool,
+}
+
+impl Zrevrange {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 4 && args.len() != 5 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zrevrange' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap();
+        let start = parse_index(&args[2])?;
+        let stop = parse_index(&args[3])?;
+        let with_scores = match args.get(4) {
+            Some(option) if option.eq_ignore_ascii_case("WITHSCORES") => true,
+            Some(_) => return Err(Error::msg("ERR syntax error")),
+            None => false,
+        };
+        let query = RangeQuery { by: RangeBy::Index(start, stop), rev: true, limit: None };
+        Ok(Zrevrange { key, query, with_scores })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        range(db, &self.key, &self.query, self.with_scores)
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use super::zrank::rank_reply;
+
+pub struct Zrevrank {
+    key: Vec<u8>,
+    member: Vec<u8>,
+    with_score: bool,
+}
+
+impl Zrevrank {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 3 && args.len() != 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zrevrank' command"));
+        }
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let member = frame.get_arg_bytes(2).unwrap(); // 成员
+        let with_score = match args.get(3) {
+            Some(option) if option.eq_ignore_ascii_case("WITHSCORE") => true,
+            Some(_) => return Err(Error::msg("ERR syntax error")),
+            None => false,
+        };
+        Ok(Zrevrank { key, member, with_score })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => {
+                // 倒序排名 = 成员数量 - 1 - 正序排名，O(log n)
+                let rank = set.rank(&self.member).map(|rank| set.len() - 1 - rank);
+                Ok(rank_reply(set, &self.member, rank, self.with_score))
+            },
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                Ok(Frame::Error(f.to_string()))
+            },
+            None => Ok(if self.with_score { Frame::NullArray } else { Frame::Null }),
+        }
+    }
+}
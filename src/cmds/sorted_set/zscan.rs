@@ -0,0 +1,77 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame, tools::pattern};
+
+pub struct Zscan {
+    key: Vec<u8>,
+    cursor: u64,
+    pattern: Option<Vec<u8>>,
+    count: Option<u64>,
+}
+
+impl Zscan {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args_from_index(1);
+        let raw_args = frame.get_args_bytes_from_index(1);
+        if args.len() < 2 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zscan' command"));
+        }
+
+        let key = raw_args[0].clone();
+        let cursor = args[1].parse::<u64>().map_err(|_| Error::msg("ERR invalid cursor"))?;
+
+        let mut pattern = None;
+        let mut count = None;
+
+        let mut i = 2;
+        while i < args.len() {
+            let arg = args[i].to_uppercase();
+            if i + 1 >= args.len() {
+                return Err(Error::msg("ERR syntax error"));
+            }
+            if arg == "MATCH" {
+                pattern = Some(raw_args[i + 1].clone());
+            } else if arg == "COUNT" {
+                let value = args[i + 1].parse::<u64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+                if value == 0 {
+                    return Err(Error::msg("ERR syntax error"));
+                }
+                count = Some(value);
+            } else {
+                return Err(Error::msg("ERR syntax error"));
+            }
+            i += 2;
+        }
+
+        Ok(Zscan { key, cursor, pattern, count })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 默认返回数量为 10
+        let count = self.count.unwrap_or(10) as usize;
+
+        match db.get(&self.key) {
+            Some(Structure::SortedSet(set)) => {
+                // 游标即跳表中的索引，只读取本次需要的成员
+                let start = self.cursor as usize;
+                let end = start.saturating_add(count).min(set.len());
+                let next_cursor = if end >= set.len() { 0 } else { end as i64 };
+
+                let mut items = Vec::new();
+                for (member, score) in set.slice(start, end, false) {
+                    if self.pattern.as_ref().is_none_or(|pattern| pattern::is_match(&member, pattern)) {
+                        items.push(Frame::BulkString(member));
+                        items.push(Frame::Double(score));
+                    }
+                }
+                Ok(Frame::Array(vec![Frame::Integer(next_cursor), Frame::Array(items)]))
+            },
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                Ok(Frame::Error(f.to_string()))
+            },
+            // 键不存在，返回游标 0 和空数组
+            None => Ok(Frame::Array(vec![Frame::Integer(0), Frame::Array(vec![])])),
+        }
+    }
+}
@@ -3,8 +3,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Zscore {
-    key: String,
-    member: String,
+    key: Vec<u8>,
+    member: Vec<u8>,
 }
 
 impl Zscore {
@@ -13,18 +13,18 @@ impl Zscore {
         if args.len() != 3 {
             return Err(Error::msg("ERR wrong number of arguments for 'zscore' command"));
         }
-        let key = args[1].to_string(); // 键
-        let member = args[2].to_string(); // 成员
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
+        let member = frame.get_arg_bytes(2).unwrap(); // 成员
         Ok(Zscore { key, member })
     }
 
     pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
-        match db.records.get(&self.key) {
+        match db.get(&self.key) {
             Some(structure) => {
                 match structure {
                     Structure::SortedSet(set) => {
                         if let Some(score) = set.get_score(&self.member) {
-                            Ok(Frame::BulkString(score.to_string()))
+                            Ok(Frame::Double(score))
                         } else {
                             Ok(Frame::Null)
                         }
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+
+use crate::{store::{db::{Db, Structure}, sorted_set::SortedSet}, frame::Frame};
+
+/// 集合运算的类型
+#[derive(Clone, Copy, PartialEq)]
+pub enum SetOperation {
+    Union,
+    Inter,
+    Diff,
+}
+
+/// 同一成员在多个集合中的分数的合并方式
+#[derive(Clone, Copy, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            // +inf 与 -inf 相加得到 NaN，与 Redis 相同视为 0
+            Aggregate::Sum => zero_if_nan(a + b),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+fn zero_if_nan(value: f64) -> f64 {
+    if value.is_nan() { 0.0 } else { value }
+}
+
+/**
+ * ZUNION / ZINTER / ZDIFF 系列命令共用的参数
+ *
+ * @param weights 每个输入集合的权重，默认为 1
+ * @param aggregate 分数的合并方式，默认为 SUM
+ */
+#[derive(Clone)]
+pub struct SetOpArgs {
+    pub operation: SetOperation,
+    pub keys: Vec<Vec<u8>>,
+    pub weights: Vec<f64>,
+    pub aggregate: Aggregate,
+    pub with_scores: bool,
+}
+
+impl SetOpArgs {
+
+    /**
+     * 解析 numkeys key [key ...] [WEIGHTS weight ...] [AGGREGATE SUM|MIN|MAX] [WITHSCORES]
+     *
+     * ZDIFF 系列不支持 WEIGHTS 与 AGGREGATE，*STORE 命令不支持 WITHSCORES
+     *
+     * @param args 从 numkeys 开始的参数
+     * @param command 命令名称（用于错误信息）
+     * @param allow_scores 是否支持 WITHSCORES
+     */
+    pub fn parse(args: &[Vec<u8>], command: &str, operation: SetOperation, allow_scores: bool) -> Result<Self, Error> {
+        let (keys, options) = parse_keys(args, command)?;
+        let mut weights = vec![1.0; keys.len()];
+        let mut aggregate = Aggregate::Sum;
+        let mut with_scores = false;
+        let mut i = 0;
+        while i < options.len() {
+            match options[i].to_uppercase().as_str() {
+                "WEIGHTS" if operation != SetOperation::Diff && i + keys.len() < options.len() => {
+                    for (weight, value) in weights.iter_mut().zip(&options[i + 1..]) {
+                        *weight = value.parse::<f64>().ok()
+                            .filter(|weight| !weight.is_nan())
+                            .ok_or_else(|| Error::msg("ERR weight value is not a float"))?;
+                    }
+                    i += keys.len();
+                },
+                "AGGREGATE" if operation != SetOperation::Diff && i + 1 < options.len() => {
+                    aggregate = match options[i + 1].to_uppercase().as_str() {
+                        "SUM" => Aggregate::Sum,
+                        "MIN" => Aggregate::Min,
+                        "MAX" => Aggregate::Max,
+                        _ => return Err(Error::msg("ERR syntax error")),
+                    };
+                    i += 1;
+                },
+                "WITHSCORES" if allow_scores => with_scores = true,
+                _ => return Err(Error::msg("ERR syntax error")),
+            }
+            i += 1;
+        }
+        Ok(SetOpArgs { operation, keys, weights, aggregate, with_scores })
+    }
+
+    /**
+     * 执行集合运算
+     *
+     * 普通集合（SET）也可以参与运算，成员的分数视为 1
+     *
+     * @return 任意一个键的类型错误时返回 None
+     */
+    pub fn combine(&self, db: &mut Db) -> Option<SortedSet> {
+        let mut inputs = Vec::with_capacity(self.keys.len());
+        for key in &self.keys {
+            inputs.push(members(db, key)?);
+        }
+
+        let mut inputs = inputs.into_iter().zip(&self.weights);
+        let mut result: HashMap<Vec<u8>, f64> = HashMap::new();
+        if let Some((first, weight)) = inputs.next() {
+            result = weighted(first, *weight, self.operation);
+        }
+        for (input, weight) in inputs {
+            if result.is_empty() && self.operation != SetOperation::Union {
+                break;
+            }
+            match self.operation {
+                SetOperation::Union => {
+                    for (member, score) in weighted(input, *weight, self.operation) {
+                        result.entry(member)
+                            .and_modify(|current| *current = self.aggregate.apply(*current, score))
+                            .or_insert(score);
+                    }
+                },
+                SetOperation::Inter => {
+                    let input = weighted(input, *weight, self.operation);
+                    result.retain(|member, current| match input.get(member) {
+                        Some(score) => {
+                            *current = self.aggregate.apply(*current, *score);
+                            true
+                        },
+                        None => false,
+                    });
+                },
+                SetOperation::Diff => {
+                    for (member, _) in input {
+                        result.remove(&member);
+                    }
+                },
+            }
+        }
+
+        let mut set = SortedSet::new();
+        for (member, score) in result {
+            set.add(member, score);
+        }
+        Some(set)
+    }
+
+    /// 执行运算并回复结果（ZUNION / ZINTER / ZDIFF）
+    pub fn reply(&self, db: &mut Db) -> Frame {
+        let Some(set) = self.combine(db) else {
+            return wrong_type();
+        };
+        let mut result = Vec::new();
+        for (member, score) in set.members_with_scores() {
+            result.push(Frame::BulkString(member));
+            if self.with_scores {
+                result.push(Frame::Double(score));
+            }
+        }
+        Frame::Array(result)
+    }
+
+    /// 执行运算并保存到目标键（ZUNIONSTORE / ZINTERSTORE / ZDIFFSTORE），结果为空时删除目标键
+    pub fn store(&self, db: &mut Db, destination: Vec<u8>) -> Frame {
+        let Some(set) = self.combine(db) else {
+            return wrong_type();
+        };
+        let len = set.len();
+        if set.is_empty() {
+            db.remove(&destination);
+        } else {
+            db.insert(destination, Structure::SortedSet(set));
+        }
+        Frame::Integer(len as i64)
+    }
+}
+
+/**
+ * 解析 numkeys key [key ...]
+ *
+ * @param args 从 numkeys 开始的参数
+ * @param command 命令名称（用于错误信息）
+ * @return 键，以及键之后的参数（选项均为 ASCII，按字符串处理）
+ */
+pub fn parse_keys(args: &[Vec<u8>], command: &str) -> Result<(Vec<Vec<u8>>, Vec<String>), Error> {
+    let numkeys = args.first()
+        .and_then(|numkeys| String::from_utf8_lossy(numkeys).parse::<i64>().ok())
+        .ok_or_else(|| Error::msg("ERR value is not an integer or out of range"))?;
+    if numkeys < 1 {
+        return Err(Error::msg(format!("ERR at least 1 input key is needed for '{}' command", command)));
+    }
+    let numkeys = numkeys as usize;
+    if numkeys > args.len() - 1 {
+        return Err(Error::msg("ERR syntax error"));
+    }
+    let options = args[numkeys + 1..].iter()
+        .map(|option| String::from_utf8_lossy(option).to_string())
+        .collect();
+    Ok((args[1..=numkeys].to_vec(), options))
+}
+
+/**
+ * 读取参与运算的集合
+ *
+ * @return 键不存在时为空，类型错误时返回 None
+ */
+pub fn members(db: &mut Db, key: &[u8]) -> Option<Vec<(Vec<u8>, f64)>> {
+    match db.get(key) {
+        Some(Structure::SortedSet(set)) => Some(set.members_with_scores()),
+        Some(Structure::Set(set)) => Some(set.iter().map(|member| (member.clone(), 1.0)).collect()),
+        Some(_) => None,
+        None => Some(Vec::new()),
+    }
+}
+
+/// 乘以权重，ZDIFF 不使用权重
+fn weighted(input: Vec<(Vec<u8>, f64)>, weight: f64, operation: SetOperation) -> HashMap<Vec<u8>, f64> {
+    if operation == SetOperation::Diff {
+        return input.into_iter().collect();
+    }
+    // 0 * inf 得到 NaN，与 Redis 相同视为 0
+    input.into_iter().map(|(member, score)| (member, zero_if_nan(score * weight))).collect()
+}
+
+fn wrong_type() -> Frame {
+    Frame::Error("ERR Operation against a key holding the wrong kind of value".to_string())
+}
+
+pub struct Zunion {
+    args: SetOpArgs,
+}
+
+impl Zunion {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args_bytes();
+        if args.len() < 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'zunion' command"));
+        }
+        let args = SetOpArgs::parse(&args[1..], "zunion", SetOperation::Union, true)?;
+        Ok(Zunion { args })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        Ok(self.args.reply(db))
+    }
+}
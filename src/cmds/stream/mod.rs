@@ -0,0 +1,199 @@
+pub mod xadd;
+pub mod xlen;
+pub mod xrange;
+pub mod xrevrange;
+pub mod xdel;
+pub mod xtrim;
+pub mod xread;
+pub mod xgroup;
+pub mod xreadgroup;
+pub mod xack;
+pub mod xpending;
+pub mod xclaim;
+pub mod xautoclaim;
+
+use std::time::Duration;
+
+use anyhow::Error;
+use tokio::time::Instant;
+
+use crate::{command::Command, frame::Frame, server::Handler};
+use crate::store::stream::{StreamFields, StreamId, TrimStrategy};
+
+/// 将消息编码为 [id, [field, value, ...]]
+fn entry_to_frame(id: StreamId, fields: &StreamFields) -> Frame {
+    let mut items = Vec::with_capacity(fields.len() * 2);
+    for (field, value) in fields {
+        items.push(Frame::BulkString(field.clone()));
+        items.push(Frame::BulkString(value.clone()));
+    }
+    Frame::Array(vec![
+        Frame::BulkString(id.to_string().into()),
+        Frame::Array(items),
+    ])
+}
+
+/// 将已被删除的消息编码为 [id, nil]
+fn deleted_entry_to_frame(id: StreamId) -> Frame {
+    Frame::Array(vec![
+        Frame::BulkString(id.to_string().into()),
+        Frame::NullArray,
+    ])
+}
+
+fn id_to_frame(id: StreamId) -> Frame {
+    Frame::BulkString(id.to_string().into())
+}
+
+/**
+ * 解析 COUNT 参数，负数视为 0
+ *
+ * @param text 参数文本
+ */
+fn parse_count(text: &str) -> Result<usize, Error> {
+    let count = text.parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+    Ok(count.max(0) as usize)
+}
+
+/**
+ * 解析 BLOCK 参数（毫秒，0 表示永久阻塞）
+ *
+ * @param text 参数文本
+ */
+fn parse_block(text: &str) -> Result<u64, Error> {
+    match text.parse::<i64>() {
+        Ok(timeout) if timeout >= 0 => Ok(timeout as u64),
+        Ok(_) => Err(Error::msg("ERR timeout is negative")),
+        Err(_) => Err(Error::msg("ERR timeout is not an integer or out of range")),
+    }
+}
+
+/**
+ * 解析裁剪参数：MAXLEN|MINID [=|~] threshold [LIMIT count]
+ *
+ * 近似裁剪（~）按精确裁剪处理，结果总是满足阈值
+ *
+ * @param args 命令参数
+ * @param index MAXLEN 或 MINID 所在的位置
+ * @return 裁剪策略、LIMIT 以及下一个参数的位置
+ */
+fn parse_trim(args: &[String], mut index: usize) -> Result<(TrimStrategy, Option<usize>, usize), Error> {
+    let syntax_error = || Error::msg("ERR syntax error");
+    let kind = args[index].to_uppercase();
+    index += 1;
+
+    let mut approximate = false;
+    match args.get(index).map(|arg| arg.as_str()) {
+        Some("~") => {
+            approximate = true;
+            index += 1;
+        },
+        Some("=") => index += 1,
+        _ => {}
+    }
+
+    let threshold = args.get(index).ok_or_else(syntax_error)?;
+    index += 1;
+    let strategy = match kind.as_str() {
+        "MAXLEN" => {
+            let max_len = threshold.parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+            if max_len < 0 {
+                return Err(Error::msg("ERR The MAXLEN argument must be >= 0."));
+            }
+            TrimStrategy::MaxLen(max_len as usize)
+        },
+        _ => TrimStrategy::MinId(StreamId::parse(threshold, 0)?),
+    };
+
+    let mut limit = None;
+    if args.get(index).is_some_and(|arg| arg.eq_ignore_ascii_case("LIMIT")) {
+        let count = args.get(index + 1).ok_or_else(syntax_error)?;
+        let count = count.parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+        if count < 0 {
+            return Err(Error::msg("ERR The LIMIT argument must be >= 0."));
+        }
+        if !approximate {
+            return Err(Error::msg("ERR syntax error, LIMIT cannot be used without the special ~ option"));
+        }
+        // LIMIT 0 表示不限制
+        limit = if count == 0 { None } else { Some(count as usize) };
+        index += 2;
+    }
+
+    Ok((strategy, limit, index))
+}
+
+/**
+ * 拆分 STREAMS 之后的键与 ID
+ *
+ * @param args STREAMS 之后的参数
+ * @param command 命令名称（用于错误信息）
+ */
+fn split_streams(args: &[Vec<u8>], command: &str) -> Result<(Vec<Vec<u8>>, Vec<String>), Error> {
+    if args.is_empty() || !args.len().is_multiple_of(2) {
+        return Err(Error::msg(format!("ERR Unbalanced '{}' list of streams: for each stream key an ID or '$' must be specified.", command)));
+    }
+    let half = args.len() / 2;
+    let ids = args[half..].iter().map(|id| String::from_utf8_lossy(id).to_string()).collect();
+    Ok((args[..half].to_vec(), ids))
+}
+
+/**
+ * 阻塞执行流读取命令（XREAD BLOCK、XREADGROUP BLOCK）
+ *
+ * 先注册等待请求再执行读取，避免在两者之间写入的消息被遗漏；
+ * 读到消息、读取出错或超时后返回，否则等待键被写入后重新读取
+ *
+ * @param handler 当前连接
+ * @param keys 等待的键
+ * @param timeout 阻塞的毫秒数，0 表示永久阻塞
+ * @param read 构建一次非阻塞读取的命令
+ */
+async fn block_on_streams<F>(handler: &mut Handler, keys: &[Vec<u8>], timeout: u64, read: F) -> Result<Frame, Error>
+where
+    F: Fn() -> Command,
+{
+    let session_id = handler.get_session().get_id();
+    let db_index = handler.get_session().get_current_db();
+    let deadline = if timeout == 0 { None } else { Some(Instant::now() + Duration::from_millis(timeout)) };
+    let manager = handler.get_state().blocking_stream.clone();
+
+    loop {
+        let receiver = manager.lock().await.register(db_index, keys, session_id);
+
+        // XREADGROUP 会修改消费者组，与弹出相同需要持有写屏障
+        let result = match read() {
+            command @ Command::Xreadgroup(_) => handler.apply_with_barrier(command).await?,
+            command => handler.apply_db_command(command).await?,
+        };
+        if !matches!(result, Frame::NullArray) {
+            manager.lock().await.cleanup_session(session_id);
+            return Ok(result);
+        }
+
+        let woken = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, receiver).await.is_ok(),
+            None => receiver.await.is_ok(),
+        };
+        if !woken {
+            manager.lock().await.cleanup_session(session_id);
+            return Ok(Frame::NullArray);
+        }
+    }
+}
+
+/**
+ * 执行流写入命令，成功后唤醒等待该键的客户端（XADD、XGROUP）
+ *
+ * @param handler 当前连接
+ * @param key 被写入的键
+ * @param command 写入命令
+ */
+pub async fn apply_and_notify(handler: &mut Handler, key: Vec<u8>, command: Command) -> Result<Frame, Error> {
+    let result = handler.apply_db_command(command).await?;
+    if !matches!(result, Frame::Error(_) | Frame::Null) {
+        let db_index = handler.get_session().get_current_db();
+        handler.get_state().blocking_stream.lock().await.notify(db_index, &key);
+    }
+    Ok(result)
+}
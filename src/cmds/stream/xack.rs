@@ -0,0 +1,54 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::stream::StreamId;
+
+/// XACK key group id [id ...]
+pub struct Xack {
+    key: Vec<u8>,
+    group: String,
+    ids: Vec<StreamId>,
+}
+
+impl Xack {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xack' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap();
+        let group = args[2].to_string();
+        let ids = args[3..]
+            .iter()
+            .map(|id| StreamId::parse(id, 0))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Xack { key, group, ids })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 消息都不在 PEL 中（或消费者组不存在）时不修改键
+        if let Some(Structure::Stream(stream)) = db.get(&self.key) {
+            let pending = stream.group(&self.group).is_some_and(|group| self.ids.iter().any(|id| group.pending.contains_key(id)));
+            if !pending {
+                return Ok(Frame::Integer(0));
+            }
+        }
+        match db.get_mut(&self.key) {
+            Some(Structure::Stream(stream)) => {
+                let acknowledged = match stream.group_mut(&self.group) {
+                    Some(group) => self.ids.iter().filter(|id| group.ack(id)).count(),
+                    None => 0,
+                };
+                Ok(Frame::Integer(acknowledged as i64))
+            },
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                Ok(Frame::Error(f.to_string()))
+            },
+            None => Ok(Frame::Integer(0)),
+        }
+    }
+}
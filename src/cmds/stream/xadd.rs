@@ -0,0 +1,180 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::memory;
+use crate::store::stream::{Stream, StreamFields, StreamId, TrimStrategy};
+
+/// 消息 ID 的指定方式
+#[derive(Clone, Copy)]
+enum IdSpec {
+    // *：自动生成
+    Auto,
+    // ms-*：指定时间戳，自动生成序号
+    AutoSeq(u64),
+    // ms-seq：完全指定
+    Explicit(StreamId),
+}
+
+/// XADD key [NOMKSTREAM] [MAXLEN|MINID [=|~] threshold [LIMIT count]] *|id field value [field value ...]
+#[derive(Clone)]
+pub struct Xadd {
+    key: Vec<u8>,
+    nomkstream: bool,
+    trim: Option<(TrimStrategy, Option<usize>)>,
+    id: IdSpec,
+    id_index: usize,
+    fields: StreamFields,
+}
+
+impl Xadd {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 5 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xadd' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap();
+        let mut nomkstream = false;
+        let mut trim = None;
+        let mut index = 2;
+        loop {
+            match args[index].to_uppercase().as_str() {
+                "NOMKSTREAM" => {
+                    nomkstream = true;
+                    index += 1;
+                },
+                "MAXLEN" | "MINID" => {
+                    let (strategy, limit, next) = super::parse_trim(&args, index)?;
+                    trim = Some((strategy, limit));
+                    index = next;
+                },
+                _ => break,
+            }
+            if index >= args.len() {
+                return Err(Error::msg("ERR wrong number of arguments for 'xadd' command"));
+            }
+        }
+
+        let id_index = index;
+        let id = match args[id_index].as_str() {
+            "*" => IdSpec::Auto,
+            text => match text.strip_suffix("-*") {
+                Some(ms) => IdSpec::AutoSeq(ms.parse::<u64>().map_err(|_| Error::msg("ERR Invalid stream ID specified as stream command argument"))?),
+                None => {
+                    let id = StreamId::parse(text, 0)?;
+                    if id == StreamId::MIN {
+                        return Err(Error::msg("ERR The ID specified in XADD must be greater than 0-0"));
+                    }
+                    IdSpec::Explicit(id)
+                }
+            }
+        };
+
+        let values = frame.get_args_bytes_from_index(id_index + 1);
+        if values.is_empty() || !values.len().is_multiple_of(2) {
+            return Err(Error::msg("ERR wrong number of arguments for 'xadd' command"));
+        }
+        let fields = values
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect();
+
+        Ok(Xadd { key, nomkstream, trim, id, id_index, fields })
+    }
+
+    /// 获取键名
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 自动生成的 ID 替换为实际写入的 ID，保证重放后 ID 一致
+     *
+     * @param frame 原始命令
+     * @param reply 命令的执行结果
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let id = match reply {
+            Frame::BulkString(id) => id.clone(),
+            _ => return None,
+        };
+        let xadd = Xadd::parse_from_frame(frame.clone()).ok()?;
+        match (xadd.id, frame) {
+            (IdSpec::Explicit(_), frame) => Some(frame),
+            (_, Frame::Array(mut items)) => {
+                items[xadd.id_index] = Frame::BulkString(id);
+                Some(Frame::Array(items))
+            },
+            (_, frame) => Some(frame),
+        }
+    }
+
+    /**
+     * 根据指定方式生成新消息的 ID，必须大于流的最后一个 ID
+     *
+     * @param last_id 流的最后一个 ID
+     */
+    fn next_id(&self, last_id: StreamId) -> Result<StreamId, Error> {
+        let too_small = || Error::msg("ERR The ID specified in XADD is equal or smaller than the target stream top item");
+        match self.id {
+            IdSpec::Auto => {
+                let now = memory::now_millis();
+                if now > last_id.ms {
+                    Ok(StreamId::new(now, 0))
+                } else {
+                    last_id.next().ok_or_else(|| Error::msg("ERR The stream has exhausted the last possible ID, unable to add more items"))
+                }
+            },
+            IdSpec::AutoSeq(ms) => {
+                if ms > last_id.ms {
+                    Ok(StreamId::new(ms, 0))
+                } else if ms == last_id.ms && last_id.seq < u64::MAX {
+                    Ok(StreamId::new(ms, last_id.seq + 1))
+                } else {
+                    Err(too_small())
+                }
+            },
+            IdSpec::Explicit(id) => {
+                if id > last_id {
+                    Ok(id)
+                } else {
+                    Err(too_small())
+                }
+            },
+        }
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        if db.get(&self.key).is_none() {
+            if self.nomkstream {
+                return Ok(Frame::Null);
+            }
+            // 先校验 ID，避免创建空的流
+            if let Err(e) = self.next_id(StreamId::MIN) {
+                return Ok(Frame::Error(e.to_string()));
+            }
+            db.insert(self.key.clone(), Structure::Stream(Stream::new()));
+        }
+
+        match db.get_mut(&self.key) {
+            Some(Structure::Stream(stream)) => {
+                let id = match self.next_id(stream.last_id()) {
+                    Ok(id) => id,
+                    Err(e) => return Ok(Frame::Error(e.to_string())),
+                };
+                stream.add(id, self.fields);
+                if let Some((strategy, limit)) = self.trim {
+                    stream.trim(strategy, limit);
+                }
+                Ok(Frame::BulkString(id.to_string().into()))
+            },
+            _ => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                Ok(Frame::Error(f.to_string()))
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::memory;
+use crate::store::stream::StreamId;
+
+/**
+ * XAUTOCLAIM 命令：从指定位置开始扫描 PEL，认领空闲时间足够长的消息
+ *
+ * XAUTOCLAIM key group consumer min-idle-time start [COUNT count] [JUSTID]
+ *
+ * 返回 [下一次扫描的起始 ID, 被认领的消息, 已从流中删除的消息 ID]，扫描完成时起始 ID 为 0-0
+ */
+pub struct Xautoclaim {
+    key: Vec<u8>,
+    group: String,
+    consumer: String,
+    min_idle: u64,
+    start: StreamId,
+    count: usize,
+    justid: bool,
+}
+
+impl Xautoclaim {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 6 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xautoclaim' command"));
+        }
+
+        let min_idle = args[4]
+            .parse::<i64>()
+            .map(|value| value.max(0) as u64)
+            .map_err(|_| Error::msg("ERR Invalid min-idle-time argument for XAUTOCLAIM"))?;
+        let start = StreamId::parse_range_bound(&args[5], true)?.unwrap_or(StreamId::MAX);
+
+        let mut count = 100;
+        let mut justid = false;
+        let mut index = 6;
+        while index < args.len() {
+            match args[index].to_uppercase().as_str() {
+                "COUNT" if index + 1 < args.len() => {
+                    count = match args[index + 1].parse::<i64>() {
+                        Ok(count) if count >= 1 => count as usize,
+                        _ => return Err(Error::msg("ERR COUNT must be > 0")),
+                    };
+                    index += 2;
+                },
+                "JUSTID" => {
+                    justid = true;
+                    index += 1;
+                },
+                _ => return Err(Error::msg("ERR syntax error")),
+            }
+        }
+
+        Ok(Xautoclaim {
+            key: frame.get_arg_bytes(1).unwrap_or_default(),
+            group: args[2].to_string(),
+            consumer: args[3].to_string(),
+            min_idle,
+            start,
+            count,
+            justid,
+        })
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 改写为认领具体消息的 XCLAIM，避免重放时因空闲时间不同而结果不一致；
+     * 已删除的消息同样传入，重放时会从 PEL 中移除
+     *
+     * @param frame 原始命令
+     * @param reply 命令的执行结果
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let (claimed, deleted) = match reply {
+            Frame::Array(items) if items.len() == 3 => (&items[1], &items[2]),
+            _ => return None,
+        };
+        let mut ids = Vec::new();
+        for list in [claimed, deleted] {
+            if let Frame::Array(items) = list {
+                ids.extend(super::xclaim::claimed_ids(items));
+            }
+        }
+        if ids.is_empty() {
+            return None;
+        }
+
+        let xautoclaim = Xautoclaim::parse_from_frame(frame.clone()).ok()?;
+        let mut args = vec!["XCLAIM".to_string().into_bytes(), xautoclaim.key, xautoclaim.group.into_bytes(), xautoclaim.consumer.into_bytes(), b"0".to_vec()];
+        args.extend(ids.into_iter().map(String::into_bytes));
+        if xautoclaim.justid {
+            args.push(b"JUSTID".to_vec());
+        }
+        Some(Frame::Array(args.into_iter().map(Frame::BulkString).collect()))
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let no_group = Frame::Error(format!("NOGROUP No such key '{}' or consumer group '{}'", String::from_utf8_lossy(&self.key), self.group));
+        let stream = match db.get_mut(&self.key) {
+            Some(Structure::Stream(stream)) => stream,
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => return Ok(no_group),
+        };
+        let (group, entries) = match stream.group_with_entries(&self.group) {
+            Some(result) => result,
+            None => return Ok(no_group),
+        };
+
+        let now = memory::now_millis();
+        group.consumer(&self.consumer, now);
+
+        // 最多检查 count * 10 条待确认消息，避免单次调用耗时过长
+        let mut attempts = self.count * 10;
+        let mut claimed = Vec::new();
+        let mut deleted = Vec::new();
+        let mut next = StreamId::MIN;
+        let mut to_assign = Vec::new();
+        for (id, pending) in group.pending.range(self.start..) {
+            let (id, delivery_time, delivery_count) = (*id, pending.delivery_time, pending.delivery_count);
+            if claimed.len() >= self.count || attempts == 0 {
+                next = id;
+                break;
+            }
+            attempts -= 1;
+            if !entries.contains_key(&id) {
+                deleted.push(id);
+                continue;
+            }
+            if now.saturating_sub(delivery_time) < self.min_idle {
+                continue;
+            }
+            let delivery_count = if self.justid { delivery_count } else { delivery_count + 1 };
+            to_assign.push((id, delivery_count));
+            claimed.push(if self.justid {
+                super::id_to_frame(id)
+            } else {
+                super::entry_to_frame(id, &entries[&id])
+            });
+        }
+
+        for (id, delivery_count) in to_assign {
+            group.assign(id, &self.consumer, now, delivery_count);
+        }
+        for id in &deleted {
+            group.ack(id);
+        }
+
+        Ok(Frame::Array(vec![
+            super::id_to_frame(next),
+            Frame::Array(claimed),
+            Frame::Array(deleted.into_iter().map(super::id_to_frame).collect()),
+        ]))
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::memory;
+use crate::store::stream::StreamId;
+
+/**
+ * XCLAIM 命令：将空闲时间足够长的待确认消息转移给指定的消费者
+ *
+ * XCLAIM key group consumer min-idle-time id [id ...] [IDLE ms] [TIME unix-time-milliseconds]
+ *        [RETRYCOUNT count] [FORCE] [JUSTID] [LASTID lastid]
+ */
+pub struct Xclaim {
+    key: Vec<u8>,
+    group: String,
+    consumer: String,
+    min_idle: u64,
+    ids: Vec<StreamId>,
+    idle: Option<u64>,
+    time: Option<u64>,
+    retry_count: Option<u64>,
+    force: bool,
+    justid: bool,
+    last_id: Option<StreamId>,
+}
+
+impl Xclaim {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 6 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xclaim' command"));
+        }
+
+        let parse_integer = |text: &str| {
+            text.parse::<i64>().map(|value| value.max(0) as u64).map_err(|_| Error::msg("ERR Invalid min-idle-time argument for XCLAIM"))
+        };
+        let min_idle = parse_integer(&args[4])?;
+
+        // ID 之后是可选参数，第一个无法解析为 ID 的参数开始
+        let mut index = 5;
+        let mut ids = Vec::new();
+        while index < args.len() {
+            match StreamId::parse(&args[index], 0) {
+                Ok(id) => ids.push(id),
+                Err(e) if ids.is_empty() => return Err(e),
+                Err(_) => break,
+            }
+            index += 1;
+        }
+
+        let mut xclaim = Xclaim {
+            key: frame.get_arg_bytes(1).unwrap_or_default(),
+            group: args[2].to_string(),
+            consumer: args[3].to_string(),
+            min_idle,
+            ids,
+            idle: None,
+            time: None,
+            retry_count: None,
+            force: false,
+            justid: false,
+            last_id: None,
+        };
+
+        while index < args.len() {
+            let value = args.get(index + 1);
+            match (args[index].to_uppercase().as_str(), value) {
+                ("FORCE", _) => {
+                    xclaim.force = true;
+                    index += 1;
+                },
+                ("JUSTID", _) => {
+                    xclaim.justid = true;
+                    index += 1;
+                },
+                ("IDLE", Some(value)) => {
+                    xclaim.idle = Some(parse_integer(value).map_err(|_| Error::msg("ERR Invalid IDLE option argument for XCLAIM"))?);
+                    index += 2;
+                },
+                ("TIME", Some(value)) => {
+                    xclaim.time = Some(parse_integer(value).map_err(|_| Error::msg("ERR Invalid TIME option argument for XCLAIM"))?);
+                    index += 2;
+                },
+                ("RETRYCOUNT", Some(value)) => {
+                    xclaim.retry_count = Some(parse_integer(value).map_err(|_| Error::msg("ERR Invalid RETRYCOUNT option argument for XCLAIM"))?);
+                    index += 2;
+                },
+                ("LASTID", Some(value)) => {
+                    xclaim.last_id = Some(StreamId::parse(value, 0)?);
+                    index += 2;
+                },
+                _ => return Err(Error::msg(format!("ERR Unrecognized XCLAIM option '{}'", args[index]))),
+            }
+        }
+
+        Ok(xclaim)
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 只保留实际被认领的消息，并将 min-idle-time 改为 0，避免重放时因空闲时间不同而结果不一致
+     *
+     * @param frame 原始命令
+     * @param reply 命令的执行结果
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let claimed = match reply {
+            Frame::Array(items) => claimed_ids(items),
+            _ => return None,
+        };
+        if claimed.is_empty() {
+            return None;
+        }
+        let xclaim = Xclaim::parse_from_frame(frame.clone()).ok()?;
+        let args = frame.get_args_bytes();
+        let options = args[5 + xclaim.ids.len()..].to_vec();
+        let mut rewritten: Vec<Vec<u8>> = args[..4].to_vec();
+        rewritten.push(b"0".to_vec());
+        rewritten.extend(claimed.into_iter().map(String::into_bytes));
+        rewritten.extend(options);
+        if !xclaim.force {
+            rewritten.push(b"FORCE".to_vec());
+        }
+        Some(Frame::Array(rewritten.into_iter().map(Frame::BulkString).collect()))
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let no_group = Frame::Error(format!("NOGROUP No such key '{}' or consumer group '{}'", String::from_utf8_lossy(&self.key), self.group));
+        let stream = match db.get_mut(&self.key) {
+            Some(Structure::Stream(stream)) => stream,
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => return Ok(no_group),
+        };
+        let (group, entries) = match stream.group_with_entries(&self.group) {
+            Some(result) => result,
+            None => return Ok(no_group),
+        };
+
+        let now = memory::now_millis();
+        let delivery_time = match (self.time, self.idle) {
+            (Some(time), _) => time,
+            (None, Some(idle)) => now.saturating_sub(idle),
+            (None, None) => now,
+        };
+        if let Some(last_id) = self.last_id {
+            if last_id > group.last_delivered_id {
+                group.last_delivered_id = last_id;
+            }
+        }
+        group.consumer(&self.consumer, now);
+
+        let mut claimed = Vec::new();
+        for id in self.ids {
+            let pending = group.pending.get(&id).map(|pending| (pending.delivery_time, pending.delivery_count));
+            let delivery_count = match pending {
+                Some((last_delivery, delivery_count)) => {
+                    // 消息已从流中删除，直接从 PEL 中移除
+                    if !entries.contains_key(&id) {
+                        group.ack(&id);
+                        continue;
+                    }
+                    if now.saturating_sub(last_delivery) < self.min_idle {
+                        continue;
+                    }
+                    delivery_count
+                },
+                None if self.force && entries.contains_key(&id) => 0,
+                None => continue,
+            };
+            let delivery_count = self.retry_count.unwrap_or(if self.justid { delivery_count } else { delivery_count + 1 });
+            group.assign(id, &self.consumer, delivery_time, delivery_count);
+            if self.justid {
+                claimed.push(super::id_to_frame(id));
+            } else if let Some(fields) = entries.get(&id) {
+                claimed.push(super::entry_to_frame(id, fields));
+            }
+        }
+        Ok(Frame::Array(claimed))
+    }
+}
+
+/**
+ * 从认领结果中提取消息 ID（JUSTID 时为 ID 列表，否则为消息列表）
+ *
+ * @param items 认领结果
+ */
+pub fn claimed_ids(items: &[Frame]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Frame::BulkString(id) => Some(String::from_utf8_lossy(id).to_string()),
+            Frame::Array(entry) => entry.first().map(|id| id.to_string()),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,48 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::stream::StreamId;
+
+/// XDEL key id [id ...]
+pub struct Xdel {
+    key: Vec<u8>,
+    ids: Vec<StreamId>,
+}
+
+impl Xdel {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xdel' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap();
+        let ids = args[2..]
+            .iter()
+            .map(|id| StreamId::parse(id, 0))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Xdel { key, ids })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 消息都不存在时不修改键
+        if let Some(Structure::Stream(stream)) = db.get(&self.key) {
+            if !self.ids.iter().any(|id| stream.entry(id).is_some()) {
+                return Ok(Frame::Integer(0));
+            }
+        }
+        match db.get_mut(&self.key) {
+            Some(Structure::Stream(stream)) => {
+                let deleted = self.ids.iter().filter(|id| stream.delete(id)).count();
+                Ok(Frame::Integer(deleted as i64))
+            },
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                Ok(Frame::Error(f.to_string()))
+            },
+            None => Ok(Frame::Integer(0)),
+        }
+    }
+}
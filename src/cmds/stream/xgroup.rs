@@ -0,0 +1,179 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::memory;
+use crate::store::stream::{Stream, StreamId};
+
+/// 消费者组的起始位置
+#[derive(Clone, Copy)]
+enum GroupId {
+    // $：流当前的最后一个 ID
+    Last,
+    Id(StreamId),
+}
+
+#[derive(Clone)]
+enum Subcommand {
+    Create { group: String, id: GroupId, mkstream: bool },
+    SetId { group: String, id: GroupId },
+    Destroy { group: String },
+    CreateConsumer { group: String, consumer: String },
+    DelConsumer { group: String, consumer: String },
+}
+
+/**
+ * XGROUP 命令：管理消费者组
+ *
+ * XGROUP CREATE key group id|$ [MKSTREAM] [ENTRIESREAD entries-read]
+ * XGROUP SETID key group id|$ [ENTRIESREAD entries-read]
+ * XGROUP DESTROY key group
+ * XGROUP CREATECONSUMER key group consumer
+ * XGROUP DELCONSUMER key group consumer
+ */
+#[derive(Clone)]
+pub struct Xgroup {
+    key: Vec<u8>,
+    subcommand: Subcommand,
+}
+
+impl Xgroup {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 2 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xgroup' command"));
+        }
+
+        let name = args[1].to_uppercase();
+        let wrong_arity = || Error::msg(format!("ERR wrong number of arguments for 'xgroup|{}' command", name.to_lowercase()));
+        let parse_id = |id: &str| match id {
+            "$" => Ok(GroupId::Last),
+            id => StreamId::parse(id, 0).map(GroupId::Id),
+        };
+
+        let subcommand = match name.as_str() {
+            "CREATE" => {
+                if args.len() < 5 {
+                    return Err(wrong_arity());
+                }
+                let mut mkstream = false;
+                let mut index = 5;
+                while index < args.len() {
+                    match args[index].to_uppercase().as_str() {
+                        "MKSTREAM" => {
+                            mkstream = true;
+                            index += 1;
+                        },
+                        "ENTRIESREAD" if index + 1 < args.len() => index += 2,
+                        _ => return Err(Error::msg("ERR syntax error")),
+                    }
+                }
+                Subcommand::Create { group: args[3].clone(), id: parse_id(&args[4])?, mkstream }
+            },
+            "SETID" => {
+                if args.len() != 5 && args.len() != 7 {
+                    return Err(wrong_arity());
+                }
+                if args.len() == 7 && !args[5].eq_ignore_ascii_case("ENTRIESREAD") {
+                    return Err(Error::msg("ERR syntax error"));
+                }
+                Subcommand::SetId { group: args[3].clone(), id: parse_id(&args[4])? }
+            },
+            "DESTROY" => {
+                if args.len() != 4 {
+                    return Err(wrong_arity());
+                }
+                Subcommand::Destroy { group: args[3].clone() }
+            },
+            "CREATECONSUMER" => {
+                if args.len() != 5 {
+                    return Err(wrong_arity());
+                }
+                Subcommand::CreateConsumer { group: args[3].clone(), consumer: args[4].clone() }
+            },
+            "DELCONSUMER" => {
+                if args.len() != 5 {
+                    return Err(wrong_arity());
+                }
+                Subcommand::DelConsumer { group: args[3].clone(), consumer: args[4].clone() }
+            },
+            _ => return Err(Error::msg(format!("ERR unknown subcommand '{}'. Try XGROUP HELP.", args[1]))),
+        };
+
+        Ok(Xgroup { key: frame.get_arg_bytes(2).unwrap_or_default(), subcommand })
+    }
+
+    /// 获取键名
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        if let Subcommand::Create { mkstream: true, .. } = self.subcommand {
+            if db.get(&self.key).is_none() {
+                db.insert(self.key.clone(), Structure::Stream(Stream::new()));
+            }
+        }
+
+        let stream = match db.get_mut(&self.key) {
+            Some(Structure::Stream(stream)) => stream,
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => {
+                let f = "ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.";
+                return Ok(Frame::Error(f.to_string()));
+            }
+        };
+
+        let last_id = stream.last_id();
+        let resolve = |id: GroupId| match id {
+            GroupId::Last => last_id,
+            GroupId::Id(id) => id,
+        };
+        let no_group = |group: &str| Frame::Error(format!("NOGROUP No such consumer group '{}' for key name '{}'", group, String::from_utf8_lossy(&self.key)));
+
+        match self.subcommand {
+            Subcommand::Create { group, id, .. } => {
+                let id = resolve(id);
+                if stream.create_group(&group, id) {
+                    Ok(Frame::Ok)
+                } else {
+                    Ok(Frame::Error("BUSYGROUP Consumer Group name already exists".to_string()))
+                }
+            },
+            Subcommand::SetId { group, id } => {
+                let id = resolve(id);
+                match stream.group_mut(&group) {
+                    Some(consumer_group) => {
+                        consumer_group.last_delivered_id = id;
+                        Ok(Frame::Ok)
+                    },
+                    None => Ok(no_group(&group)),
+                }
+            },
+            Subcommand::Destroy { group } => {
+                Ok(Frame::Integer(stream.destroy_group(&group) as i64))
+            },
+            Subcommand::CreateConsumer { group, consumer } => {
+                match stream.group_mut(&group) {
+                    Some(consumer_group) => {
+                        let created = consumer_group.create_consumer(&consumer, memory::now_millis());
+                        Ok(Frame::Integer(created as i64))
+                    },
+                    None => Ok(no_group(&group)),
+                }
+            },
+            Subcommand::DelConsumer { group, consumer } => {
+                match stream.group_mut(&group) {
+                    Some(consumer_group) => {
+                        let pending = consumer_group.delete_consumer(&consumer).unwrap_or(0);
+                        Ok(Frame::Integer(pending as i64))
+                    },
+                    None => Ok(no_group(&group)),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::memory;
+use crate::store::stream::StreamId;
+
+/// 扩展形式的查询条件
+struct Filter {
+    min_idle: u64,
+    start: Option<StreamId>,
+    end: Option<StreamId>,
+    count: usize,
+    consumer: Option<String>,
+}
+
+/**
+ * XPENDING 命令：查询消费者组的待确认消息
+ *
+ * XPENDING key group：返回概要 [数量, 最小 ID, 最大 ID, [[消费者, 数量], ...]]
+ * XPENDING key group [IDLE min-idle-time] start end count [consumer]：返回 [[ID, 消费者, 空闲毫秒数, 投递次数], ...]
+ */
+pub struct Xpending {
+    key: Vec<u8>,
+    group: String,
+    filter: Option<Filter>,
+}
+
+impl Xpending {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 3 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xpending' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap();
+        let group = args[2].to_string();
+        if args.len() == 3 {
+            return Ok(Xpending { key, group, filter: None });
+        }
+
+        let mut index = 3;
+        let mut min_idle = 0;
+        if args[index].eq_ignore_ascii_case("IDLE") {
+            let idle = args.get(index + 1).ok_or_else(|| Error::msg("ERR syntax error"))?;
+            min_idle = idle.parse::<i64>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?.max(0) as u64;
+            index += 2;
+        }
+        if args.len() != index + 3 && args.len() != index + 4 {
+            return Err(Error::msg("ERR syntax error"));
+        }
+
+        let start = StreamId::parse_range_bound(&args[index], true)?;
+        let end = StreamId::parse_range_bound(&args[index + 1], false)?;
+        let count = super::parse_count(&args[index + 2])?;
+        let consumer = args.get(index + 3).cloned();
+
+        Ok(Xpending { key, group, filter: Some(Filter { min_idle, start, end, count, consumer }) })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let group = match db.get(&self.key) {
+            Some(Structure::Stream(stream)) => stream.group(&self.group),
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                return Ok(Frame::Error(f.to_string()));
+            },
+            None => None,
+        };
+        let group = match group {
+            Some(group) => group,
+            None => return Ok(Frame::Error(format!("NOGROUP No such key '{}' or consumer group '{}'", String::from_utf8_lossy(&self.key), self.group))),
+        };
+
+        let filter = match self.filter {
+            Some(filter) => filter,
+            None => {
+                // 概要形式
+                if group.pending.is_empty() {
+                    return Ok(Frame::Array(vec![Frame::Integer(0), Frame::Null, Frame::Null, Frame::NullArray]));
+                }
+                let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+                for entry in group.pending.values() {
+                    *counts.entry(entry.consumer.as_str()).or_default() += 1;
+                }
+                let consumers = counts
+                    .into_iter()
+                    .map(|(consumer, count)| Frame::Array(vec![
+                        Frame::BulkString(consumer.to_string().into()),
+                        Frame::BulkString(count.to_string().into()),
+                    ]))
+                    .collect();
+                let first = group.pending.keys().next().copied().unwrap_or_default();
+                let last = group.pending.keys().next_back().copied().unwrap_or_default();
+                return Ok(Frame::Array(vec![
+                    Frame::Integer(group.pending.len() as i64),
+                    super::id_to_frame(first),
+                    super::id_to_frame(last),
+                    Frame::Array(consumers),
+                ]));
+            }
+        };
+
+        let (start, end) = match (filter.start, filter.end) {
+            (Some(start), Some(end)) if start <= end => (start, end),
+            _ => return Ok(Frame::Array(vec![])),
+        };
+        let now = memory::now_millis();
+        let entries = group.pending
+            .range(start..=end)
+            .filter(|(_, entry)| filter.consumer.as_ref().is_none_or(|consumer| *consumer == entry.consumer))
+            .filter(|(_, entry)| now.saturating_sub(entry.delivery_time) >= filter.min_idle)
+            .take(filter.count)
+            .map(|(id, entry)| Frame::Array(vec![
+                super::id_to_frame(*id),
+                Frame::BulkString(entry.consumer.clone().into()),
+                Frame::Integer(now.saturating_sub(entry.delivery_time) as i64),
+                Frame::Integer(entry.delivery_count as i64),
+            ]))
+            .collect();
+        Ok(Frame::Array(entries))
+    }
+}
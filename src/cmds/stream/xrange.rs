@@ -0,0 +1,72 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::stream::StreamId;
+
+/// XRANGE key start end [COUNT count]
+pub struct Xrange {
+    key: Vec<u8>,
+    start: Option<StreamId>,
+    end: Option<StreamId>,
+    count: Option<usize>,
+}
+
+impl Xrange {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 4 && args.len() != 6 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xrange' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap();
+        let start = StreamId::parse_range_bound(&args[2], true)?;
+        let end = StreamId::parse_range_bound(&args[3], false)?;
+
+        let mut count = None;
+        if args.len() == 6 {
+            if !args[4].eq_ignore_ascii_case("COUNT") {
+                return Err(Error::msg("ERR syntax error"));
+            }
+            count = Some(super::parse_count(&args[5])?);
+        }
+
+        Ok(Xrange { key, start, end, count })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        range(db, &self.key, self.start, self.end, self.count, false)
+    }
+}
+
+/**
+ * 按 ID 范围查询消息（XRANGE、XREVRANGE）
+ *
+ * @param db 数据库
+ * @param key 键名
+ * @param start 起始 ID，为 None 时范围为空
+ * @param end 结束 ID，为 None 时范围为空
+ * @param count 最多返回的数量
+ * @param rev 是否逆序
+ */
+pub fn range(db: &mut Db, key: &[u8], start: Option<StreamId>, end: Option<StreamId>, count: Option<usize>, rev: bool) -> Result<Frame, Error> {
+    match db.get(key) {
+        Some(Structure::Stream(stream)) => {
+            let (start, end) = match (start, end) {
+                (Some(start), Some(end)) => (start, end),
+                _ => return Ok(Frame::Array(vec![])),
+            };
+            let entries = stream
+                .range(start, end, count, rev)
+                .into_iter()
+                .map(|(id, fields)| super::entry_to_frame(id, fields))
+                .collect();
+            Ok(Frame::Array(entries))
+        },
+        Some(_) => {
+            let f = "ERR Operation against a key holding the wrong kind of value";
+            Ok(Frame::Error(f.to_string()))
+        },
+        None => Ok(Frame::Array(vec![])),
+    }
+}
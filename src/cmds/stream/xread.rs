@@ -0,0 +1,158 @@
+use anyhow::Error;
+
+use crate::{command::Command, frame::Frame, server::Handler};
+use crate::store::db::{Db, Structure};
+use crate::store::stream::StreamId;
+
+/// 读取的起始位置
+#[derive(Clone, Copy)]
+enum ReadFrom {
+    // $：只读取之后写入的消息
+    Last,
+    // 读取大于该 ID 的消息
+    After(StreamId),
+}
+
+/// XREAD [COUNT count] [BLOCK milliseconds] STREAMS key [key ...] id [id ...]
+///
+/// 指定 BLOCK 时需要在 Handler 中处理：没有可读的消息时等待流被写入
+#[derive(Clone)]
+pub struct Xread {
+    count: Option<usize>,
+    block: Option<u64>,
+    keys: Vec<Vec<u8>>,
+    ids: Vec<ReadFrom>,
+}
+
+impl Xread {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xread' command"));
+        }
+
+        let mut count = None;
+        let mut block = None;
+        let mut index = 1;
+        while index < args.len() {
+            match args[index].to_uppercase().as_str() {
+                "COUNT" if index + 1 < args.len() => {
+                    count = Some(super::parse_count(&args[index + 1])?);
+                    index += 2;
+                },
+                "BLOCK" if index + 1 < args.len() => {
+                    block = Some(super::parse_block(&args[index + 1])?);
+                    index += 2;
+                },
+                "STREAMS" => {
+                    index += 1;
+                    break;
+                },
+                _ => return Err(Error::msg("ERR syntax error")),
+            }
+        }
+
+        let (keys, ids) = super::split_streams(&frame.get_args_bytes()[index.min(args.len())..], "xread")?;
+        let ids = ids
+            .iter()
+            .map(|id| match id.as_str() {
+                "$" => Ok(ReadFrom::Last),
+                id => StreamId::parse(id, 0).map(ReadFrom::After),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Xread { count, block, keys, ids })
+    }
+
+    /**
+     * 在 Handler 中执行 XREAD
+     *
+     * 没有 BLOCK 或在事务中执行时直接读取；否则先将 $ 解析为流当前的最后一个 ID，
+     * 再等待新消息写入或超时
+     */
+    pub async fn apply_blocking(mut self, handler: &mut Handler) -> Result<Frame, Error> {
+        let timeout = match self.block {
+            Some(timeout) if !handler.is_in_transaction() => timeout,
+            _ => return handler.apply_db_command(Command::Xread(self)).await,
+        };
+
+        for index in 0..self.keys.len() {
+            if let ReadFrom::Last = self.ids[index] {
+                let last_id = Self::last_id(handler, &self.keys[index]).await?;
+                self.ids[index] = ReadFrom::After(last_id);
+            }
+        }
+
+        let keys = self.keys.clone();
+        super::block_on_streams(handler, &keys, timeout, || Command::Xread(self.clone())).await
+    }
+
+    /**
+     * 获取流中最后一条消息的 ID，流不存在或为空时返回 0-0
+     *
+     * 新写入的消息 ID 一定大于该 ID
+     */
+    async fn last_id(handler: &mut Handler, key: &[u8]) -> Result<StreamId, Error> {
+        let frame = Frame::Array(vec![
+            Frame::BulkString("XREVRANGE".to_string().into()),
+            Frame::BulkString(key.to_vec()),
+            Frame::BulkString("+".to_string().into()),
+            Frame::BulkString("-".to_string().into()),
+            Frame::BulkString("COUNT".to_string().into()),
+            Frame::BulkString("1".to_string().into()),
+        ]);
+        let command = Command::parse_from_frame(frame)?;
+        match handler.apply_db_command(command).await? {
+            Frame::Array(entries) => match entries.first() {
+                Some(Frame::Array(entry)) => match entry.first() {
+                    Some(id) => StreamId::parse(&id.to_string(), 0),
+                    None => Ok(StreamId::MIN),
+                },
+                _ => Ok(StreamId::MIN),
+            },
+            Frame::Error(e) => Err(Error::msg(e)),
+            _ => Ok(StreamId::MIN),
+        }
+    }
+
+    /// 非阻塞读取，所有流都没有可读的消息时返回 nil
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let mut results = Vec::new();
+        for (key, from) in self.keys.iter().zip(self.ids) {
+            let start = match from {
+                ReadFrom::Last => continue,
+                ReadFrom::After(id) => match id.next() {
+                    Some(start) => start,
+                    None => continue,
+                },
+            };
+            match db.get(key) {
+                Some(Structure::Stream(stream)) => {
+                    let entries: Vec<Frame> = stream
+                        .range(start, StreamId::MAX, self.count, false)
+                        .into_iter()
+                        .map(|(id, fields)| super::entry_to_frame(id, fields))
+                        .collect();
+                    if !entries.is_empty() {
+                        results.push(Frame::Array(vec![
+                            Frame::BulkString(key.clone()),
+                            Frame::Array(entries),
+                        ]));
+                    }
+                },
+                Some(_) => {
+                    let f = "ERR Operation against a key holding the wrong kind of value";
+                    return Ok(Frame::Error(f.to_string()));
+                },
+                None => {},
+            }
+        }
+
+        if results.is_empty() {
+            Ok(Frame::NullArray)
+        } else {
+            Ok(Frame::Array(results))
+        }
+    }
+}
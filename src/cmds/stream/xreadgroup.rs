@@ -0,0 +1,160 @@
+use anyhow::Error;
+
+use crate::{command::Command, frame::Frame, server::Handler};
+use crate::store::db::{Db, Structure};
+use crate::store::memory;
+use crate::store::stream::StreamId;
+
+/// 读取的起始位置
+#[derive(Clone, Copy)]
+enum ReadFrom {
+    // >：读取从未投递给组内消费者的新消息
+    New,
+    // 读取消费者名下 ID 大于该值的待确认消息
+    Pending(StreamId),
+}
+
+/// XREADGROUP GROUP group consumer [COUNT count] [BLOCK milliseconds] [NOACK] STREAMS key [key ...] id [id ...]
+///
+/// 指定 BLOCK 且所有 ID 都为 > 时需要在 Handler 中处理：没有新消息时等待流被写入
+#[derive(Clone)]
+pub struct Xreadgroup {
+    group: String,
+    consumer: String,
+    count: Option<usize>,
+    block: Option<u64>,
+    noack: bool,
+    keys: Vec<Vec<u8>>,
+    ids: Vec<ReadFrom>,
+}
+
+impl Xreadgroup {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 7 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xreadgroup' command"));
+        }
+        if !args[1].eq_ignore_ascii_case("GROUP") {
+            return Err(Error::msg("ERR Missing GROUP option for XREADGROUP"));
+        }
+
+        let group = args[2].clone();
+        let consumer = args[3].clone();
+        let mut count = None;
+        let mut block = None;
+        let mut noack = false;
+        let mut index = 4;
+        while index < args.len() {
+            match args[index].to_uppercase().as_str() {
+                "COUNT" if index + 1 < args.len() => {
+                    count = Some(super::parse_count(&args[index + 1])?);
+                    index += 2;
+                },
+                "BLOCK" if index + 1 < args.len() => {
+                    block = Some(super::parse_block(&args[index + 1])?);
+                    index += 2;
+                },
+                "NOACK" => {
+                    noack = true;
+                    index += 1;
+                },
+                "STREAMS" => {
+                    index += 1;
+                    break;
+                },
+                _ => return Err(Error::msg("ERR syntax error")),
+            }
+        }
+
+        let (keys, ids) = super::split_streams(&frame.get_args_bytes()[index.min(args.len())..], "xreadgroup")?;
+        let ids = ids
+            .iter()
+            .map(|id| match id.as_str() {
+                ">" => Ok(ReadFrom::New),
+                "$" => Err(Error::msg("ERR The $ ID is meaningless in the context of XREADGROUP: you want to read the history of this consumer by specifying a proper ID, or use the > ID to get new messages. The $ ID would just return an empty result set.")),
+                id => StreamId::parse(id, 0).map(ReadFrom::Pending),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Xreadgroup { group, consumer, count, block, noack, keys, ids })
+    }
+
+    /// 是否可能阻塞等待新消息
+    pub fn is_blocking(&self) -> bool {
+        self.block.is_some() && self.ids.iter().all(|id| matches!(id, ReadFrom::New))
+    }
+
+    /**
+     * 在 Handler 中执行 XREADGROUP
+     *
+     * 读取历史消息（具体 ID）时从不阻塞；只读取新消息（>）且指定 BLOCK 时，
+     * 等待新消息写入或超时
+     */
+    pub async fn apply_blocking(self, handler: &mut Handler) -> Result<Frame, Error> {
+        let reads_new_only = self.ids.iter().all(|id| matches!(id, ReadFrom::New));
+        let timeout = match self.block {
+            Some(timeout) if reads_new_only && !handler.is_in_transaction() => timeout,
+            _ => return handler.apply_db_command(Command::Xreadgroup(self)).await,
+        };
+
+        let keys = self.keys.clone();
+        super::block_on_streams(handler, &keys, timeout, || Command::Xreadgroup(self.clone())).await
+    }
+
+    /// 非阻塞读取，没有任何结果时返回 nil
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        let now = memory::now_millis();
+
+        // 先检查所有的流与消费者组，避免部分读取后才发现错误
+        for key in &self.keys {
+            match db.get(key) {
+                Some(Structure::Stream(stream)) if stream.group(&self.group).is_some() => {},
+                Some(Structure::Stream(_)) | None => {
+                    return Ok(Frame::Error(format!("NOGROUP No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option", String::from_utf8_lossy(key), self.group)));
+                },
+                Some(_) => {
+                    let f = "ERR Operation against a key holding the wrong kind of value";
+                    return Ok(Frame::Error(f.to_string()));
+                }
+            }
+        }
+
+        let mut results = Vec::new();
+        for (key, from) in self.keys.iter().zip(self.ids) {
+            let stream = match db.get_mut(key) {
+                Some(Structure::Stream(stream)) => stream,
+                _ => continue,
+            };
+            let entries: Vec<Frame> = match from {
+                ReadFrom::New => {
+                    let entries = stream.read_new(&self.group, &self.consumer, self.count, self.noack, now);
+                    if entries.is_empty() {
+                        continue;
+                    }
+                    entries.iter().map(|(id, fields)| super::entry_to_frame(*id, fields)).collect()
+                },
+                ReadFrom::Pending(start) => {
+                    stream
+                        .read_pending(&self.group, &self.consumer, start, self.count, now)
+                        .into_iter()
+                        .map(|(id, fields)| match fields {
+                            Some(fields) => super::entry_to_frame(id, &fields),
+                            None => super::deleted_entry_to_frame(id),
+                        })
+                        .collect()
+                },
+            };
+            results.push(Frame::Array(vec![
+                Frame::BulkString(key.clone()),
+                Frame::Array(entries),
+            ]));
+        }
+
+        if results.is_empty() {
+            Ok(Frame::NullArray)
+        } else {
+            Ok(Frame::Array(results))
+        }
+    }
+}
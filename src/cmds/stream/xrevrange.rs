@@ -0,0 +1,40 @@
+use anyhow::Error;
+
+use crate::{store::db::Db, frame::Frame};
+use crate::store::stream::StreamId;
+
+/// XREVRANGE key end start [COUNT count]
+pub struct Xrevrange {
+    key: Vec<u8>,
+    end: Option<StreamId>,
+    start: Option<StreamId>,
+    count: Option<usize>,
+}
+
+impl Xrevrange {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() != 4 && args.len() != 6 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xrevrange' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap();
+        let end = StreamId::parse_range_bound(&args[2], false)?;
+        let start = StreamId::parse_range_bound(&args[3], true)?;
+
+        let mut count = None;
+        if args.len() == 6 {
+            if !args[4].eq_ignore_ascii_case("COUNT") {
+                return Err(Error::msg("ERR syntax error"));
+            }
+            count = Some(super::parse_count(&args[5])?);
+        }
+
+        Ok(Xrevrange { key, end, start, count })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        super::xrange::range(db, &self.key, self.start, self.end, self.count, true)
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::Error;
+
+use crate::{store::db::{Db, Structure}, frame::Frame};
+use crate::store::stream::TrimStrategy;
+
+/// XTRIM key MAXLEN|MINID [=|~] threshold [LIMIT count]
+pub struct Xtrim {
+    key: Vec<u8>,
+    strategy: TrimStrategy,
+    limit: Option<usize>,
+}
+
+impl Xtrim {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        if args.len() < 4 {
+            return Err(Error::msg("ERR wrong number of arguments for 'xtrim' command"));
+        }
+
+        let key = frame.get_arg_bytes(1).unwrap();
+        match args[2].to_uppercase().as_str() {
+            "MAXLEN" | "MINID" => {},
+            _ => return Err(Error::msg("ERR syntax error")),
+        }
+        let (strategy, limit, next) = super::parse_trim(&args, 2)?;
+        if next != args.len() {
+            return Err(Error::msg("ERR syntax error"));
+        }
+
+        Ok(Xtrim { key, strategy, limit })
+    }
+
+    pub fn apply(self, db: &mut Db) -> Result<Frame, Error> {
+        // 没有需要裁剪的消息时不修改键
+        if let Some(Structure::Stream(stream)) = db.get(&self.key) {
+            if self.limit == Some(0) || !stream.needs_trim(self.strategy) {
+                return Ok(Frame::Integer(0));
+            }
+        }
+        match db.get_mut(&self.key) {
+            Some(Structure::Stream(stream)) => {
+                let removed = stream.trim(self.strategy, self.limit);
+                Ok(Frame::Integer(removed as i64))
+            },
+            Some(_) => {
+                let f = "ERR Operation against a key holding the wrong kind of value";
+                Ok(Frame::Error(f.to_string()))
+            },
+            None => Ok(Frame::Integer(0)),
+        }
+    }
+}
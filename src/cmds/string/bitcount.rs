@@ -3,7 +3,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Bitcount {
-    key: String,
+    key: Vec<u8>,
     start: Option<isize>,
     end: Option<isize>,
 }
@@ -11,13 +11,13 @@ pub struct Bitcount {
 impl Bitcount {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'bitcount' command"));
         }
 
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
         
         let start = frame.get_arg(2).map(|s| {
             s.parse::<isize>().map_err(|_| Error::msg("ERR value is not an integer or out of range"))
@@ -41,7 +41,7 @@ impl Bitcount {
             Some(structure) => {
                 match structure {
                     Structure::String(value) => {
-                        let bytes = value.as_bytes();
+                        let bytes = value.as_slice();
                         
                         if bytes.is_empty() {
                             return Ok(Frame::Integer(0));
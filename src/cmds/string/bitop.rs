@@ -4,15 +4,15 @@ use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Bitop {
     operation: String,
-    dest_key: String,
-    keys: Vec<String>,
+    dest_key: Vec<u8>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Bitop {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
         let operation = frame.get_arg(1);
-        let dest_key = frame.get_arg(2);
+        let dest_key = frame.get_arg_bytes(2);
 
         if operation.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'bitop' command"));
@@ -23,7 +23,7 @@ impl Bitop {
         }
 
         let operation = operation.unwrap().to_uppercase();
-        let dest_key = dest_key.unwrap().to_string();
+        let dest_key = dest_key.unwrap();
 
         // validate the type of action
         if operation != "AND" && operation != "OR" && operation != "XOR" && operation != "NOT" {
@@ -31,11 +31,7 @@ impl Bitop {
         }
 
         // get all source keys
-        let mut keys = Vec::new();
-        let args = frame.get_args();
-        for i in 3..args.len() {
-            keys.push(args[i].clone());
-        }
+        let keys = frame.get_args_bytes_from_index(3);
 
         // The NOT operation accepts only one source key
         if operation == "NOT" && keys.len() != 1 {
@@ -64,7 +60,7 @@ impl Bitop {
                 Some(structure) => {
                     match structure {
                         Structure::String(value) => {
-                            source_bytes.push(value.as_bytes().to_vec());
+                            source_bytes.push(value.clone());
                         },
                         _ => {
                             return Ok(Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".to_string()));
@@ -87,7 +83,7 @@ impl Bitop {
 
         if max_len == 0 {
             // all source keys are empty and so are the results
-            db.insert(self.dest_key, Structure::String(String::new()));
+            db.insert(self.dest_key, Structure::String(Vec::new()));
             return Ok(Frame::Integer(0));
         }
 
@@ -110,13 +106,8 @@ impl Bitop {
             }
         };
 
-        // convert the result to a string
-        // note: Redis's string type can store arbitrary byte sequences, not just valid UTF-8
-        // so we use unsafe to bypass utf 8 checks
-        let result_string = unsafe { String::from_utf8_unchecked(result_bytes) };
-
-        let result_len = result_string.len() as i64;
-        db.insert(self.dest_key, Structure::String(result_string));
+        let result_len = result_bytes.len() as i64;
+        db.insert(self.dest_key, Structure::String(result_bytes));
         Ok(Frame::Integer(result_len))
     }
 
@@ -2,7 +2,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Decr {
-    key: String,
+    key: Vec<u8>,
 }
 
 impl Decr {
@@ -12,7 +12,7 @@ impl Decr {
         if args.len() != 2 {
             return Err(Error::msg("ERR wrong number of arguments for 'decr' command"));
         }
-        let key = args[1].to_string(); // 键
+        let key = frame.get_arg_bytes(1).unwrap(); // 键
         Ok(Decr { key })
     }
 
@@ -21,10 +21,10 @@ impl Decr {
             Some(structure) => {
                 match structure {
                     Structure::String(str) => {
-                        match str.parse::<i64>() {
+                        match String::from_utf8_lossy(str).parse::<i64>() {
                             Ok(mut num) => {
                                 num -= 1;
-                                *str = num.to_string();
+                                *str = num.to_string().into_bytes();
                                 Ok(Frame::Integer(num))
                             },
                             Err(_) => {
@@ -40,7 +40,7 @@ impl Decr {
                 }
             },
             None => {
-                db.insert(self.key.clone(), Structure::String("-1".to_string()));
+                db.insert(self.key.clone(), Structure::String("-1".to_string().into_bytes()));
                 Ok(Frame::Integer(-1))
             }
         }
@@ -3,26 +3,26 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Get {
-    pub key: String,
+    pub key: Vec<u8>,
 }
 
 impl Get {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'get' command"));
         }
 
-        let fianl_key = key.unwrap().to_string();
+        let fianl_key = key.unwrap();
         
         Ok(Get { 
             key: fianl_key 
         })
     }
 
-    pub fn new(key: String) -> Self {
+    pub fn new(key: Vec<u8>) -> Self {
         Get { key }
     }
 
@@ -32,7 +32,7 @@ impl Get {
             Some(structure) => {
                 match structure {
                     Structure::String(value) => {
-                        Ok(Frame::BulkString(value.to_string()))
+                        Ok(Frame::BulkString(value.clone()))
                     },
                     _ => {
                         Ok(Frame::Error("Type parsing error".to_string()))
@@ -3,14 +3,14 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Getbit {
-    key: String,
+    key: Vec<u8>,
     offset: usize,
 }
 
 impl Getbit {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         let offset = frame.get_arg(2);
 
         if key.is_none() {
@@ -21,7 +21,7 @@ impl Getbit {
             return Err(Error::msg("ERR wrong number of arguments for 'getbit' command"));
         }
 
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
         
         let offset = match offset.unwrap().parse::<usize>() {
             Ok(o) => o,
@@ -41,7 +41,7 @@ impl Getbit {
             Some(structure) => {
                 match structure {
                     Structure::String(value) => {
-                        let bytes = value.as_bytes();
+                        let bytes = value.as_slice();
                         
                         let byte_index = self.offset / 8;
                         let bit_offset = 7 - (self.offset % 8); // Redis uses a large end order, with the highest position first
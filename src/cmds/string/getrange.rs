@@ -6,14 +6,14 @@ use crate::{
 };
 
 pub struct GetRange {
-    key: String,
+    key: Vec<u8>,
     start: i64,
     end: i64,
 }
 
 impl GetRange {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         let start = frame.get_arg(2);
         let end = frame.get_arg(3);
 
@@ -23,7 +23,7 @@ impl GetRange {
             ));
         }
 
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
         let final_start = start.unwrap().to_string();
         let final_end = end.unwrap().to_string();
 
@@ -72,18 +72,12 @@ impl GetRange {
             return Ok(Frame::BulkString("".into()));
         }
 
-        // 安全截取字符串 (考虑 UTF-8 边界)
-        let substring = match value.char_indices().nth(start) {
-            Some((start_idx, _)) => {
-                let remainder = &value[start_idx..];
-                match remainder.char_indices().nth(end - start) {
-                    Some((end_idx, ch)) => &remainder[..end_idx + ch.len_utf8()],
-                    None => remainder,
-                }
-            }
-            None => "",
-        };
+        // 按字节截取，end 为闭区间
+        let end = (end + 1).min(value.len());
+        if start >= end {
+            return Ok(Frame::BulkString("".into()));
+        }
 
-        Ok(Frame::BulkString(substring.into()))
+        Ok(Frame::BulkString(value[start..end].to_vec()))
     }
 }
\ No newline at end of file
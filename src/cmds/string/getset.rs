@@ -2,8 +2,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct GetSet {
-    key: String,
-    value: String,
+    key: Vec<u8>,
+    value: Vec<u8>,
 }
 
 impl GetSet {
@@ -14,8 +14,8 @@ impl GetSet {
             return Err(Error::msg("ERR wrong number of arguments for 'getset' command"));
         }
 
-        let key = frame.get_arg(1).ok_or(Error::msg("ERR missing key"))?.to_string();
-        let value = frame.get_arg(2).ok_or(Error::msg("ERR missing value"))?.to_string();
+        let key = frame.get_arg_bytes(1).ok_or(Error::msg("ERR missing key"))?;
+        let value = frame.get_arg_bytes(2).ok_or(Error::msg("ERR missing value"))?;
 
         Ok(GetSet { key, value })
     }
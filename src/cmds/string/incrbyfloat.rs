@@ -2,7 +2,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct IncrbyFloat {
-    key: String,
+    key: Vec<u8>,
     increment: f64,
 }
 
@@ -12,7 +12,7 @@ impl IncrbyFloat {
         if args.len() != 3 {
             return Err(Error::msg("ERR wrong number of arguments for 'incrbyfloat' command"));
         }
-        let key = args[1].to_string();
+        let key = frame.get_arg_bytes(1).unwrap();
         let increment = args[2].parse::<f64>().map_err(|_| {
             Error::msg("ERR value is not a valid float")
         })?;
@@ -60,11 +60,11 @@ impl IncrbyFloat {
             Some(structure) => {
                 match structure {
                     Structure::String(str_value) => {
-                        match str_value.parse::<f64>() {
+                        match String::from_utf8_lossy(str_value).parse::<f64>() {
                             Ok(current) => {
                                 let new_value = current + self.increment;
                                 let formatted = Self::format_float(new_value);
-                                *str_value = formatted.clone();
+                                *str_value = formatted.clone().into_bytes();
                                 Ok(Frame::BulkString(formatted.into()))
                             },
                             Err(_) => {
@@ -81,7 +81,7 @@ impl IncrbyFloat {
             },
             None => {
                 let formatted = Self::format_float(self.increment);
-                db.insert(self.key.clone(), Structure::String(formatted.clone()));
+                db.insert(self.key.clone(), Structure::String(formatted.clone().into_bytes()));
                 Ok(Frame::BulkString(formatted.into()))
             }
         }
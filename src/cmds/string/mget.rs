@@ -2,12 +2,12 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Mget {
-    keys: Vec<String>,
+    keys: Vec<Vec<u8>>,
 }
 
 impl Mget {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let args = frame.get_args_from_index(1);
+        let args = frame.get_args_bytes_from_index(1);
 
         Ok(Mget { keys: args })
     }
@@ -18,7 +18,7 @@ impl Mget {
             match db.get(&key) {
                 Some(structure) => {
                     match structure {
-                        Structure::String(str) => result.push(Frame::BulkString(str.to_string())),
+                        Structure::String(str) => result.push(Frame::BulkString(str.clone())),
                         _ => result.push(Frame::Null),
                     }   
                 } 
@@ -2,14 +2,14 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Msetnx {
-    key_vals: Vec<(String, String)>,
+    key_vals: Vec<(Vec<u8>, Vec<u8>)>,
 }
 
 impl Msetnx {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let args = frame.get_args_from_index(1);
+        let args = frame.get_args_bytes_from_index(1);
 
         if args.len() % 2 != 0 {
             return Err(Error::msg("ERR wrong number of arguments for 'msetnx' command"));
@@ -18,8 +18,8 @@ impl Msetnx {
         let mut key_vals = Vec::new();
         
         for i in (0..args.len()).step_by(2) {
-            let key = args[i].to_string();
-            let val = args[i + 1].to_string();
+            let key = args[i].clone();
+            let val = args[i + 1].clone();
             key_vals.push((key, val));
         }
 
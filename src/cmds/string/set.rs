@@ -3,8 +3,8 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Set {
-    pub key: String,
-    pub val: String,
+    pub key: Vec<u8>,
+    pub val: Vec<u8>,
     pub ttl: Option<u64>
 }
 
@@ -12,8 +12,8 @@ impl Set {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error>{
 
-        let key = frame.get_arg(1);
-        let val = frame.get_arg(2);
+        let key = frame.get_arg_bytes(1);
+        let val = frame.get_arg_bytes(2);
 
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'set' command"));
@@ -23,8 +23,8 @@ impl Set {
             return Err(Error::msg("ERR wrong number of arguments for 'set' command"));
         }
 
-        let fianl_key = key.unwrap().to_string(); // 键
-        let final_val = val.unwrap().to_string(); // 值
+        let fianl_key = key.unwrap(); // 键
+        let final_val = val.unwrap(); // 值
 
         let args = frame.get_args();
 
@@ -55,7 +55,7 @@ impl Set {
         })
     }
 
-    pub fn new(key: String, val: String, ttl: Option<u64>) -> Self {
+    pub fn new(key: Vec<u8>, val: Vec<u8>, ttl: Option<u64>) -> Self {
         Set { key, val, ttl }
     }
 
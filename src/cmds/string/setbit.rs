@@ -3,7 +3,7 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Setbit {
-    key: String,
+    key: Vec<u8>,
     offset: usize,
     value: u8,
 }
@@ -11,7 +11,7 @@ pub struct Setbit {
 impl Setbit {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         let offset = frame.get_arg(2);
         let value = frame.get_arg(3);
 
@@ -27,7 +27,7 @@ impl Setbit {
             return Err(Error::msg("ERR wrong number of arguments for 'setbit' command"));
         }
 
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
         
         let offset = match offset.unwrap().parse::<usize>() {
             Ok(o) => o,
@@ -58,7 +58,7 @@ impl Setbit {
             Some(structure) => {
                 match structure {
                     Structure::String(value) => {
-                        value.as_bytes().to_vec()
+                        value.clone()
                     },
                     _ => {
                         return Ok(Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".to_string()));
@@ -93,12 +93,7 @@ impl Setbit {
             bytes[byte_index] &= !(1 << bit_offset);
         }
 
-        // convert byte arrays back to strings
-        // note: Redis's string type can store arbitrary byte sequences, not just valid UTF-8
-        // so we use unsafe to bypass utf 8 checks
-        let new_value = unsafe { String::from_utf8_unchecked(bytes) };
-
-        db.insert(self.key, Structure::String(new_value));
+        db.insert(self.key, Structure::String(bytes));
         Ok(Frame::Integer(old_bit as i64))
     }
 }
@@ -3,18 +3,18 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Setex {
-    key: String,
+    key: Vec<u8>,
     seconds: u64,
-    value: String,
+    value: Vec<u8>,
 }
 
 impl Setex {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         let seconds = frame.get_arg(2);
-        let value = frame.get_arg(3);
+        let value = frame.get_arg_bytes(3);
 
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'setex' command"));
@@ -28,8 +28,8 @@ impl Setex {
             return Err(Error::msg("ERR wrong number of arguments for 'setex' command"));
         }
 
-        let final_key = key.unwrap().to_string();
-        let final_value = value.unwrap().to_string();
+        let final_key = key.unwrap();
+        let final_value = value.unwrap();
         
         let seconds = match seconds.unwrap().parse::<u64>() {
             Ok(s) => s,
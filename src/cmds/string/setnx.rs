@@ -3,16 +3,16 @@ use anyhow::Error;
 use crate::{store::db::{Db, Structure}, frame::Frame};
 
 pub struct Setnx {
-    key: String,
-    value: String,
+    key: Vec<u8>,
+    value: Vec<u8>,
 }
 
 impl Setnx {
 
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
 
-        let key = frame.get_arg(1);
-        let value = frame.get_arg(2);
+        let key = frame.get_arg_bytes(1);
+        let value = frame.get_arg_bytes(2);
 
         if key.is_none() {
             return Err(Error::msg("ERR wrong number of arguments for 'setnx' command"));
@@ -22,8 +22,8 @@ impl Setnx {
             return Err(Error::msg("ERR wrong number of arguments for 'setnx' command"));
         }
 
-        let final_key = key.unwrap().to_string();
-        let final_value = value.unwrap().to_string();
+        let final_key = key.unwrap();
+        let final_value = value.unwrap();
 
         Ok(Setnx {
             key: final_key,
@@ -6,16 +6,16 @@ use crate::{
 };
 
 pub struct SetRange {
-    key: String,
+    key: Vec<u8>,
     offset: i64,
-    value: String,
+    value: Vec<u8>,
 }
 
 impl SetRange {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
-        let key = frame.get_arg(1);
+        let key = frame.get_arg_bytes(1);
         let offset = frame.get_arg(2);
-        let value = frame.get_arg(3);
+        let value = frame.get_arg_bytes(3);
 
         if key.is_none() || offset.is_none() || value.is_none() {
             return Err(Error::msg(
@@ -23,9 +23,9 @@ impl SetRange {
             ));
         }
 
-        let final_key = key.unwrap().to_string();
+        let final_key = key.unwrap();
         let final_offset = offset.unwrap().to_string();
-        let final_value = value.unwrap().to_string();
+        let final_value = value.unwrap();
 
         let offset_int = match final_offset.parse::<i64>() {
             Ok(n) => n,
@@ -54,13 +54,12 @@ impl SetRange {
                     "WRONGTYPE Operation against a key holding the wrong kind of value"
                 ))
             }
-            None => String::new(),
+            None => Vec::new(),
         };
 
-        // 将字符串转换为字节向量以便操作
-        let mut bytes = current_value.into_bytes();
+        let mut bytes = current_value;
         let offset = self.offset as usize;
-        let value_bytes = self.value.into_bytes();
+        let value_bytes = self.value;
 
         // 确保字节数组足够长以容纳新数据
         if bytes.len() < offset + value_bytes.len() {
@@ -72,18 +71,8 @@ impl SetRange {
             bytes[offset + i] = *byte;
         }
 
-        // 转换回字符串并保存
-        let new_value = match String::from_utf8(bytes) {
-            Ok(s) => s,
-            Err(_) => {
-                return Err(Error::msg(
-                    "ERR invalid UTF-8 sequence produced by SETRANGE operation"
-                ))
-            }
-        };
-
         // 保存到数据库
-        db.insert(self.key.clone(), Structure::String(new_value));
+        db.insert(self.key.clone(), Structure::String(bytes));
         
         // 返回修改后的字符串长度
         let length = db.get(&self.key).map_or(0, |s| {
@@ -8,11 +8,12 @@ impl Discard {
         Ok(Discard)
     }
 
-    pub fn apply(&self, handler: &mut crate::server::Handler) -> Result<Frame, Error> {
+    pub async fn apply(&self, handler: &mut crate::server::Handler) -> Result<Frame, Error> {
         if !handler.is_in_transaction() {
             return Ok(Frame::Error("ERR DISCARD without MULTI".to_string()));
         }
         handler.clear_transaction();
+        handler.unwatch_keys().await;
         Ok(Frame::Ok)
     }
 }
\ No newline at end of file
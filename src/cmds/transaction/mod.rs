@@ -1,3 +1,5 @@
 pub mod multi;
 pub mod exec;
-pub mod discard;
\ No newline at end of file
+pub mod discard;
+pub mod watch;
+pub mod unwatch;
\ No newline at end of file
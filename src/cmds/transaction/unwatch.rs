@@ -0,0 +1,19 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+/// UNWATCH
+///
+/// 取消 WATCH 命令对所有键的监视
+#[derive(Clone)]
+pub struct Unwatch;
+
+impl Unwatch {
+    pub fn parse_from_frame(_frame: Frame) -> Result<Self, Error> {
+        Ok(Unwatch)
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        handler.unwatch_keys().await;
+        Ok(Frame::Ok)
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::Error;
+use crate::{frame::Frame, server::Handler};
+
+/// WATCH key [key ...]
+///
+/// 监视一个或多个键，如果在 EXEC 执行之前这些键被其他命令修改、删除或过期，
+/// 那么事务将被放弃，EXEC 返回空回复
+#[derive(Clone)]
+pub struct Watch {
+    keys: Vec<Vec<u8>>,
+}
+
+impl Watch {
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let keys = frame.get_args_bytes_from_index(1);
+        if keys.is_empty() {
+            return Err(Error::msg("ERR wrong number of arguments for 'watch' command"));
+        }
+        Ok(Watch { keys })
+    }
+
+    pub async fn apply(self, handler: &mut Handler) -> Result<Frame, Error> {
+        if handler.is_in_transaction() {
+            return Ok(Frame::Error("ERR WATCH inside MULTI is not allowed".to_string()));
+        }
+        handler.watch_keys(self.keys).await?;
+        Ok(Frame::Ok)
+    }
+}
@@ -2,24 +2,34 @@ use anyhow::Error;
 
 use crate::{
     cmds::{
-        connect::{auth::Auth, client::Client, echo::Echo, ping::Ping, select::Select},         hash::{
+        connect::{auth::Auth, client::Client, echo::Echo, hello::Hello, ping::Ping, select::Select},         hash::{
             hdel::Hdel, hexists::Hexists, hget::Hget, hgetall::Hgetall, hincrby::Hincrby, hincrbyfloat::HincrbyFloat, hkeys::Hkeys, hlen::Hlen,
             hmget::Hmget, hmset::Hmset, hset::Hset, hsetnx::Hsetnx, hstrlen::Hstrlen, hvals::Hvals, hscan::Hscan,
         }, key::{
-            del::Del, exists::Exists, expire::Expire, expireat::ExpireAt, keys::Keys, r#move::Move, persist::Persist, pexpire::Pexpire, pexpireat::PexpireAt, pttl::Pttl, randomkey::RandomKey, rename::Rename, renamenx::Renamenx, scan::Scan, ttl::Ttl, r#type::Type
+            del::Del, exists::Exists, expire::Expire, expireat::ExpireAt, keys::Keys, r#move::Move, persist::Persist, pexpire::Pexpire, pexpireat::PexpireAt, pttl::Pttl, randomkey::RandomKey, rename::Rename, renamenx::Renamenx, scan::Scan, ttl::Ttl, r#type::Type, dump::Dump, restore::Restore
         }, listing::{
-            blpop::Blpop, brpop::Brpop, lindex::Lindex, llen::Llen, lpop::Lpop, lpush::Lpush, lpushx::Lpushx, lrange::Lrange,
-            lrem::Lrem, lset::Lset, ltrim::Ltrim, rpop::Rpop, rpush::Rpush, rpushx::Rpushx,
-        }, server::{bgsave::Bgsave, dbsize::Dbsize, flushall::Flushall, flushdb::Flushdb, info::Info, save::Save}, server_sync::{psync::Psync, replconf::Replconf}, set::{
+            blpop::{Blpop, Bpop}, brpop::Brpop, blmove::Blmove, brpoplpush::Brpoplpush, blmpop::Blmpop, lindex::Lindex, llen::Llen, lpop::Lpop, lpush::Lpush, lpushx::Lpushx, lrange::Lrange,
+            lrem::Lrem, lset::Lset, ltrim::Ltrim, rpop::Rpop, rpush::Rpush, rpushx::Rpushx, linsert::Linsert,
+            lpos::Lpos, lmove::Lmove, rpoplpush::Rpoplpush, lmpop::Lmpop,
+        }, server::{acl::Acl, bgrewriteaof::Bgrewriteaof, bgsave::Bgsave, config::Config, dbsize::Dbsize, debug::Debug, flushall::Flushall, flushdb::Flushdb, info::Info, lastsave::Lastsave, save::Save}, server_sync::{psync::Psync, replconf::Replconf, replicaof::Replicaof, wait::Wait}, set::{
             sadd::Sadd, scard::Scard, sdiff::Sdiff, sinter::Sinter, sismember::Sismember, smembers::Smembers, spop::Spop, srem::Srem, sscan::Sscan, sunion::Sunion, sunionstore::Sunionstore, srandmember::Srandmember, sdiffstore::Sdiffstore, sinterstore::Sinterstore, smove::Smove
         }, sorted_set::{
             zadd::Zadd, zcard::Zcard, zcount::Zcount, zincrby::Zincrby, zlexcount::Zlexcount, zrank::Zrank, zrem::Zrem, zscore::Zscore, zrange::Zrange,
+            zrevrange::Zrevrange, zrangebyscore::Zrangebyscore, zrevrangebyscore::Zrevrangebyscore, zrangebylex::Zrangebylex, zrevrangebylex::Zrevrangebylex, zrangestore::Zrangestore, zrevrank::Zrevrank, zremrangebyrank::Zremrangebyrank, zremrangebyscore::Zremrangebyscore, zremrangebylex::Zremrangebylex, zmscore::Zmscore, zrandmember::Zrandmember, zscan::Zscan,
+            zunion::Zunion, zunionstore::Zunionstore, zinter::Zinter, zinterstore::Zinterstore, zintercard::Zintercard, zdiff::Zdiff, zdiffstore::Zdiffstore, zpopmin::Zpopmin, zpopmax::Zpopmax, zmpop::Zmpop, bzpopmin::{Bzpop, Bzpopmin}, bzpopmax::Bzpopmax, bzmpop::Bzmpop,
         }, string::{
             append::Append, decr::Decr, decrby::Decrby, get::Get, getrange::GetRange, getset::GetSet, incr::Incr, incrby::Incrby, incrbyfloat::IncrbyFloat, mget::Mget, mset::Mset, msetnx::Msetnx, set::Set, setrange::SetRange, strlen::Strlen, setex::Setex, psetex::Psetex, setnx::Setnx, setbit::Setbit, getbit::Getbit, bitcount::Bitcount, bitop::Bitop
         }, transaction::{
-            discard::Discard, exec::Exec, multi::Multi
+            discard::Discard, exec::Exec, multi::Multi, unwatch::Unwatch, watch::Watch
         }, hyperloglog::{
             pfadd::Pfadd, pfcount::Pfcount, pfmerge::Pfmerge
+        }, pubsub::{
+            psubscribe::Psubscribe, publish::Publish, pubsub::Pubsub, punsubscribe::Punsubscribe, subscribe::Subscribe, unsubscribe::Unsubscribe
+        }, stream::{
+            xack::Xack, xadd::Xadd, xautoclaim::Xautoclaim, xclaim::Xclaim, xdel::Xdel, xgroup::Xgroup, xlen::Xlen, xpending::Xpending,
+            xrange::Xrange, xread::Xread, xreadgroup::Xreadgroup, xrevrange::Xrevrange, xtrim::Xtrim
+        }, scripting::{
+            eval::Eval, evalsha::Evalsha, script::Script
         }, unknown::Unknown
     },
     frame::Frame,
@@ -29,6 +39,7 @@ pub enum Command {
     Auth(Auth),
     Append(Append),
     Client(Client),
+    Hello(Hello),
     Dbsize(Dbsize),
     Expire(Expire),
     Del(Del),
@@ -77,6 +88,11 @@ pub enum Command {
     Persist(Persist),
     Rpop(Rpop),
     Lpop(Lpop),
+    Linsert(Linsert),
+    Lpos(Lpos),
+    Lmove(Lmove),
+    Rpoplpush(Rpoplpush),
+    Lmpop(Lmpop),
     Llen(Llen),
     Hvals(Hvals),
     Hscan(Hscan),
@@ -113,33 +129,98 @@ pub enum Command {
     Zrank(Zrank),
     Zrem(Zrem),
     Zrange(Zrange),
+    Zrevrange(Zrevrange),
+    Zrangebyscore(Zrangebyscore),
+    Zrevrangebyscore(Zrevrangebyscore),
+    Zrangebylex(Zrangebylex),
+    Zrevrangebylex(Zrevrangebylex),
+    Zrangestore(Zrangestore),
+    Zrevrank(Zrevrank),
+    Zremrangebyrank(Zremrangebyrank),
+    Zremrangebyscore(Zremrangebyscore),
+    Zremrangebylex(Zremrangebylex),
+    Zmscore(Zmscore),
+    Zrandmember(Zrandmember),
+    Zscan(Zscan),
+    Zunion(Zunion),
+    Zunionstore(Zunionstore),
+    Zinter(Zinter),
+    Zinterstore(Zinterstore),
+    Zintercard(Zintercard),
+    Zdiff(Zdiff),
+    Zdiffstore(Zdiffstore),
+    Zpopmin(Zpopmin),
+    Zpopmax(Zpopmax),
+    Zmpop(Zmpop),
+    Bzpopmin(Bzpopmin),
+    Bzpopmax(Bzpopmax),
+    Bzmpop(Bzmpop),
     Incrby(Incrby),
     Decrby(Decrby),
     Echo(Echo),
     ExpireAt(ExpireAt),
     RandomKey(RandomKey),
     PexpireAt(PexpireAt),
+    Dump(Dump),
+    Restore(Restore),
     Pexpire(Pexpire),
     Lrange(Lrange),
     Replconf(Replconf),
     Psync(Psync),
+    Replicaof(Replicaof),
+    Wait(Wait),
     Bgsave(Bgsave),
+    Bgrewriteaof(Bgrewriteaof),
     Save(Save),
+    Lastsave(Lastsave),
+    Debug(Debug),
     GetSet(GetSet),
     Info(Info),
+    Acl(Acl),
+    Config(Config),
     Move(Move),
     Sscan(Sscan),
     // 阻塞列表命令
     Blpop(Blpop),
     Brpop(Brpop),
+    Blmove(Blmove),
+    Brpoplpush(Brpoplpush),
+    Blmpop(Blmpop),
     // 事务命令
     Multi(Multi),
     Discard(Discard),
     Exec(Exec),
+    Watch(Watch),
+    Unwatch(Unwatch),
     // HyperLogLog 命令
     Pfadd(Pfadd),
     Pfcount(Pfcount),
     Pfmerge(Pfmerge),
+    // 发布订阅命令
+    Subscribe(Subscribe),
+    Unsubscribe(Unsubscribe),
+    Psubscribe(Psubscribe),
+    Punsubscribe(Punsubscribe),
+    Publish(Publish),
+    Pubsub(Pubsub),
+    // 流命令
+    Xadd(Xadd),
+    Xlen(Xlen),
+    Xrange(Xrange),
+    Xrevrange(Xrevrange),
+    Xdel(Xdel),
+    Xtrim(Xtrim),
+    Xread(Xread),
+    Xgroup(Xgroup),
+    Xreadgroup(Xreadgroup),
+    Xack(Xack),
+    Xpending(Xpending),
+    Xclaim(Xclaim),
+    Xautoclaim(Xautoclaim),
+    // 脚本命令
+    Eval(Eval),
+    Evalsha(Evalsha),
+    Script(Script),
 }
 impl Command {
     pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
@@ -191,6 +272,11 @@ impl Command {
             "LINDEX" => Command::Lindex(Lindex::parse_from_frame(frame)?),
             "RPOP" => Command::Rpop(Rpop::parse_from_frame(frame)?),
             "LPOP" => Command::Lpop(Lpop::parse_from_frame(frame)?),
+            "LINSERT" => Command::Linsert(Linsert::parse_from_frame(frame)?),
+            "LPOS" => Command::Lpos(Lpos::parse_from_frame(frame)?),
+            "LMOVE" => Command::Lmove(Lmove::parse_from_frame(frame)?),
+            "RPOPLPUSH" => Command::Rpoplpush(Rpoplpush::parse_from_frame(frame)?),
+            "LMPOP" => Command::Lmpop(Lmpop::parse_from_frame(frame)?),
             "LLEN" => Command::Llen(Llen::parse_from_frame(frame)?),
             "HVALS" => Command::Hvals(Hvals::parse_from_frame(frame)?),
             "HSCAN" => Command::Hscan(Hscan::parse_from_frame(frame)?),
@@ -230,22 +316,62 @@ impl Command {
             "ZRANK" => Command::Zrank(Zrank::parse_from_frame(frame)?),
             "ZLEXCOUNT" => Command::Zlexcount(Zlexcount::parse_from_frame(frame)?),
             "ZRANGE" => Command::Zrange(Zrange::parse_from_frame(frame)?),
+            "ZREVRANGE" => Command::Zrevrange(Zrevrange::parse_from_frame(frame)?),
+            "ZRANGEBYSCORE" => Command::Zrangebyscore(Zrangebyscore::parse_from_frame(frame)?),
+            "ZREVRANGEBYSCORE" => Command::Zrevrangebyscore(Zrevrangebyscore::parse_from_frame(frame)?),
+            "ZRANGEBYLEX" => Command::Zrangebylex(Zrangebylex::parse_from_frame(frame)?),
+            "ZREVRANGEBYLEX" => Command::Zrevrangebylex(Zrevrangebylex::parse_from_frame(frame)?),
+            "ZRANGESTORE" => Command::Zrangestore(Zrangestore::parse_from_frame(frame)?),
+            "ZREVRANK" => Command::Zrevrank(Zrevrank::parse_from_frame(frame)?),
+            "ZREMRANGEBYRANK" => Command::Zremrangebyrank(Zremrangebyrank::parse_from_frame(frame)?),
+            "ZREMRANGEBYSCORE" => Command::Zremrangebyscore(Zremrangebyscore::parse_from_frame(frame)?),
+            "ZREMRANGEBYLEX" => Command::Zremrangebylex(Zremrangebylex::parse_from_frame(frame)?),
+            "ZMSCORE" => Command::Zmscore(Zmscore::parse_from_frame(frame)?),
+            "ZRANDMEMBER" => Command::Zrandmember(Zrandmember::parse_from_frame(frame)?),
+            "ZSCAN" => Command::Zscan(Zscan::parse_from_frame(frame)?),
+            "ZUNION" => Command::Zunion(Zunion::parse_from_frame(frame)?),
+            "ZUNIONSTORE" => Command::Zunionstore(Zunionstore::parse_from_frame(frame)?),
+            "ZINTER" => Command::Zinter(Zinter::parse_from_frame(frame)?),
+            "ZINTERSTORE" => Command::Zinterstore(Zinterstore::parse_from_frame(frame)?),
+            "ZINTERCARD" => Command::Zintercard(Zintercard::parse_from_frame(frame)?),
+            "ZDIFF" => Command::Zdiff(Zdiff::parse_from_frame(frame)?),
+            "ZDIFFSTORE" => Command::Zdiffstore(Zdiffstore::parse_from_frame(frame)?),
+            "ZPOPMIN" => Command::Zpopmin(Zpopmin::parse_from_frame(frame)?),
+            "ZPOPMAX" => Command::Zpopmax(Zpopmax::parse_from_frame(frame)?),
+            "ZMPOP" => Command::Zmpop(Zmpop::parse_from_frame(frame)?),
+            "BZPOPMIN" => Command::Bzpopmin(Bzpopmin::parse_from_frame(frame)?),
+            "BZPOPMAX" => Command::Bzpopmax(Bzpopmax::parse_from_frame(frame)?),
+            "BZMPOP" => Command::Bzmpop(Bzmpop::parse_from_frame(frame)?),
             "INCRBY" => Command::Incrby(Incrby::parse_from_frame(frame)?),
             "INCRBYFLOAT" => Command::IncrbyFloat(IncrbyFloat::parse_from_frame(frame)?),
             "DECRBY" => Command::Decrby(Decrby::parse_from_frame(frame)?),
             "ECHO" => Command::Echo(Echo::parse_from_frame(frame)?),
             "PEXPIRE" => Command::Pexpire(Pexpire::parse_from_frame(frame)?),
             "PEXPIREAT" => Command::PexpireAt(PexpireAt::parse_from_frame(frame)?),
+            "DUMP" => Command::Dump(Dump::parse_from_frame(frame)?),
+            "RESTORE" => Command::Restore(Restore::parse_from_frame(frame)?),
             "REPLCONF" => Command::Replconf(Replconf::parse_from_frame(frame)?),
             "LRANGE" => Command::Lrange(Lrange::parse_from_frame(frame)?),
             "PSYNC" => Command::Psync(Psync::parse_from_frame(frame)?),
+            "REPLICAOF" | "SLAVEOF" => Command::Replicaof(Replicaof::parse_from_frame(frame)?),
+            "WAIT" => Command::Wait(Wait::parse_from_frame(frame)?),
             "GETSET" => Command::GetSet(GetSet::parse_from_frame(frame)?),
             "CLIENT" => Command::Client(Client::parse_from_frame(frame)?),
+            "HELLO" => Command::Hello(Hello::parse_from_frame(frame)?),
             "INFO" => Command::Info(Info::parse_from_frame(frame)?),
+            "ACL" => Command::Acl(Acl::parse_from_frame(frame)?),
+            "CONFIG" => Command::Config(Config::parse_from_frame(frame)?),
+            "SAVE" => Command::Save(Save::parse_from_frame(frame)?),
+            "BGSAVE" => Command::Bgsave(Bgsave::parse_from_frame(frame)?),
+            "LASTSAVE" => Command::Lastsave(Lastsave::parse_from_frame(frame)?),
+            "DEBUG" => Command::Debug(Debug::parse_from_frame(frame)?),
+            "BGREWRITEAOF" => Command::Bgrewriteaof(Bgrewriteaof::parse_from_frame(frame)?),
             "MOVE" => Command::Move(Move::parse_from_frame(frame)?),
             "MULTI" => Command::Multi(Multi::parse_from_frame(frame)?),
             "EXEC" => Command::Exec(Exec::parse_from_frame(frame)?),
             "DISCARD" => Command::Discard(Discard::parse_from_frame(frame)?),
+            "WATCH" => Command::Watch(Watch::parse_from_frame(frame)?),
+            "UNWATCH" => Command::Unwatch(Unwatch::parse_from_frame(frame)?),
             "SCAN" => Command::Scan(Scan::parse_from_frame(frame)?),
             "SSCAN" => Command::Sscan(Sscan::parse_from_frame(frame)?),
             "PFADD" => Command::Pfadd(Pfadd::parse_from_frame(frame)?),
@@ -253,10 +379,92 @@ impl Command {
             "PFMERGE" => Command::Pfmerge(Pfmerge::parse_from_frame(frame)?),
             "BLPOP" => Command::Blpop(Blpop::parse_from_frame(frame)?),
             "BRPOP" => Command::Brpop(Brpop::parse_from_frame(frame)?),
+            "BLMOVE" => Command::Blmove(Blmove::parse_from_frame(frame)?),
+            "BRPOPLPUSH" => Command::Brpoplpush(Brpoplpush::parse_from_frame(frame)?),
+            "BLMPOP" => Command::Blmpop(Blmpop::parse_from_frame(frame)?),
+            "SUBSCRIBE" => Command::Subscribe(Subscribe::parse_from_frame(frame)?),
+            "UNSUBSCRIBE" => Command::Unsubscribe(Unsubscribe::parse_from_frame(frame)?),
+            "PSUBSCRIBE" => Command::Psubscribe(Psubscribe::parse_from_frame(frame)?),
+            "PUNSUBSCRIBE" => Command::Punsubscribe(Punsubscribe::parse_from_frame(frame)?),
+            "PUBLISH" => Command::Publish(Publish::parse_from_frame(frame)?),
+            "PUBSUB" => Command::Pubsub(Pubsub::parse_from_frame(frame)?),
+            "XADD" => Command::Xadd(Xadd::parse_from_frame(frame)?),
+            "XLEN" => Command::Xlen(Xlen::parse_from_frame(frame)?),
+            "XRANGE" => Command::Xrange(Xrange::parse_from_frame(frame)?),
+            "XREVRANGE" => Command::Xrevrange(Xrevrange::parse_from_frame(frame)?),
+            "XDEL" => Command::Xdel(Xdel::parse_from_frame(frame)?),
+            "XTRIM" => Command::Xtrim(Xtrim::parse_from_frame(frame)?),
+            "XREAD" => Command::Xread(Xread::parse_from_frame(frame)?),
+            "XGROUP" => Command::Xgroup(Xgroup::parse_from_frame(frame)?),
+            "XREADGROUP" => Command::Xreadgroup(Xreadgroup::parse_from_frame(frame)?),
+            "XACK" => Command::Xack(Xack::parse_from_frame(frame)?),
+            "XPENDING" => Command::Xpending(Xpending::parse_from_frame(frame)?),
+            "XCLAIM" => Command::Xclaim(Xclaim::parse_from_frame(frame)?),
+            "XAUTOCLAIM" => Command::Xautoclaim(Xautoclaim::parse_from_frame(frame)?),
+            "EVAL" => Command::Eval(Eval::parse_from_frame(frame)?),
+            "EVALSHA" => Command::Evalsha(Evalsha::parse_from_frame(frame)?),
+            "SCRIPT" => Command::Script(Script::parse_from_frame(frame)?),
             _ => Command::Unknown(Unknown::parse_from_frame(frame)?),
         };
         Ok(command)
     }
+    /**
+     * 是否为可能增加内存用量的命令
+     *
+     * 内存超过 maxmemory 且无法淘汰足够的键时，这些命令会被拒绝并返回 OOM 错误（与 Redis 的 denyoom 标记一致）
+     */
+    pub fn is_denyoom(&self) -> bool {
+        matches!(self,
+            Command::Append(_) |
+            Command::Decr(_) |
+            Command::Decrby(_) |
+            Command::GetSet(_) |
+            Command::Incr(_) |
+            Command::Incrby(_) |
+            Command::IncrbyFloat(_) |
+            Command::Mset(_) |
+            Command::Msetnx(_) |
+            Command::Set(_) |
+            Command::SetRange(_) |
+            Command::Setex(_) |
+            Command::Psetex(_) |
+            Command::Setnx(_) |
+            Command::Setbit(_) |
+            Command::Bitop(_) |
+            Command::Hmset(_) |
+            Command::Hset(_) |
+            Command::Hincrby(_) |
+            Command::HincrbyFloat(_) |
+            Command::Hsetnx(_) |
+            Command::Linsert(_) |
+            Command::Lmove(_) |
+            Command::Rpoplpush(_) |
+            Command::Blmove(_) |
+            Command::Brpoplpush(_) |
+            Command::Lpush(_) |
+            Command::Lpushx(_) |
+            Command::Lset(_) |
+            Command::Rpush(_) |
+            Command::Rpushx(_) |
+            Command::Sadd(_) |
+            Command::Sdiffstore(_) |
+            Command::Sinterstore(_) |
+            Command::Sunionstore(_) |
+            Command::Zadd(_) |
+            Command::Zincrby(_) |
+            Command::Zrangestore(_) |
+            Command::Zunionstore(_) |
+            Command::Zinterstore(_) |
+            Command::Zdiffstore(_) |
+            Command::Pfadd(_) |
+            Command::Pfmerge(_) |
+            Command::Restore(_) |
+            Command::Xadd(_) |
+            Command::Eval(_) |
+            Command::Evalsha(_)
+        )
+    }
+
     pub fn propagate_aof_if_needed(&self) -> bool {
         match self {
             Command::Del(_) |
@@ -265,6 +473,7 @@ impl Command {
             Command::Persist(_) |
             Command::Pexpire(_) |
             Command::PexpireAt(_) |
+            Command::Restore(_) |
             Command::Rename(_) |
             Command::Renamenx(_) |
             Command::Append(_) |
@@ -292,6 +501,15 @@ impl Command {
             Command::HincrbyFloat(_) |
             Command::Hsetnx(_) |
             Command::Lpop(_) |
+            Command::Linsert(_) |
+            Command::Lmove(_) |
+            Command::Rpoplpush(_) |
+            Command::Lmpop(_) |
+            Command::Blpop(_) |
+            Command::Brpop(_) |
+            Command::Blmove(_) |
+            Command::Brpoplpush(_) |
+            Command::Blmpop(_) |
             Command::Lpush(_) |
             Command::Lpushx(_) |
             Command::Lset(_) |
@@ -312,10 +530,73 @@ impl Command {
             Command::Zadd(_) |
             Command::Zincrby(_) |
             Command::Zrem(_) |
+            Command::Zrangestore(_) |
+            Command::Zremrangebyrank(_) |
+            Command::Zremrangebyscore(_) |
+            Command::Zremrangebylex(_) |
+            Command::Zunionstore(_) |
+            Command::Zinterstore(_) |
+            Command::Zdiffstore(_) |
+            Command::Zpopmin(_) |
+            Command::Zpopmax(_) |
+            Command::Zmpop(_) |
+            Command::Bzpopmin(_) |
+            Command::Bzpopmax(_) |
+            Command::Bzmpop(_) |
             Command::Move(_) |
             Command::Pfadd(_) |
             Command::Pfmerge(_) |
+            Command::Xadd(_) |
+            Command::Xdel(_) |
+            Command::Xtrim(_) |
+            Command::Xgroup(_) |
+            Command::Xreadgroup(_) |
+            Command::Xack(_) |
+            Command::Xclaim(_) |
+            Command::Xautoclaim(_) => true,
             _ => false,
         }
     }
+
+    /**
+     * 执行期间是否需要持有写屏障
+     *
+     * 会写入 AOF 的命令（包括执行写命令的脚本与事务）需要持有；
+     * 阻塞的 XREADGROUP、BLPOP、BZPOPMIN 等命令可能长时间等待，持有屏障会使重写无法开始，
+     * 因此只在每次非阻塞尝试及其传播期间持有（Handler::apply_with_barrier）
+     */
+    pub fn holds_write_barrier(&self) -> bool {
+        match self {
+            Command::Xreadgroup(xreadgroup) => !xreadgroup.is_blocking(),
+            Command::Blpop(_) | Command::Brpop(_) | Command::Blmove(_) | Command::Brpoplpush(_) | Command::Blmpop(_) => false,
+            Command::Bzpopmin(_) | Command::Bzpopmax(_) | Command::Bzmpop(_) => false,
+            Command::Eval(_) | Command::Evalsha(_) | Command::Exec(_) => true,
+            command => command.propagate_aof_if_needed(),
+        }
+    }
+
+    /**
+     * 生成写入 AOF 与传播给从节点的命令
+     *
+     * 结果不确定的命令（如自动生成 ID 的 XADD）改写为确定的形式，保证重放后结果一致
+     *
+     * @param frame 原始命令
+     * @param reply 命令的执行结果
+     * @return 不需要传播时返回 None
+     */
+    pub fn propagation_frame(frame: Frame, reply: &Frame) -> Option<Frame> {
+        let command_name = frame.get_arg(0)?.to_uppercase();
+        match command_name.as_str() {
+            "XADD" => Xadd::propagation_frame(frame, reply),
+            "XCLAIM" => Xclaim::propagation_frame(frame, reply),
+            "XAUTOCLAIM" => Xautoclaim::propagation_frame(frame, reply),
+            "BLPOP" | "BRPOP" => Bpop::propagation_frame(frame, reply),
+            "BLMOVE" => Blmove::propagation_frame(frame, reply),
+            "BRPOPLPUSH" => Brpoplpush::propagation_frame(frame, reply),
+            "BLMPOP" => Blmpop::propagation_frame(frame, reply),
+            "BZPOPMIN" | "BZPOPMAX" => Bzpop::propagation_frame(frame, reply),
+            "BZMPOP" => Bzmpop::propagation_frame(frame, reply),
+            _ => Some(frame),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,332 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use anyhow::Error;
+use log::LevelFilter;
+use tokio::sync::watch;
+
+use crate::args::{parse_memory, parse_save_rules, Args, SaveRule};
+use crate::persistence::aof_file::SyncStrategy;
+use crate::store::memory::EvictionPolicy;
+use crate::tools::pattern;
+
+/// 运行时可以通过 CONFIG SET 修改的配置
+#[derive(Clone)]
+pub struct Values {
+    pub save: Vec<SaveRule>,
+    pub appendfsync: SyncStrategy,
+    pub aof_use_rdb_preamble: bool,
+    pub auto_aof_rewrite_percentage: u64,
+    pub auto_aof_rewrite_min_size: usize,
+    pub maxclients: usize,
+    pub requirepass: Option<String>,
+    pub loglevel: String,
+    pub maxmemory: usize,
+    pub maxmemory_policy: EvictionPolicy,
+    pub maxmemory_samples: usize,
+    pub replica_read_only: bool,
+    pub min_replicas_to_write: usize,
+    pub min_replicas_max_lag: u64,
+    pub repl_backlog_size: usize,
+    pub lua_time_limit: i64,
+}
+
+/// 复制积压缓冲区的最小大小（与 Redis 相同）
+const MIN_REPL_BACKLOG_SIZE: usize = 16 * 1024;
+
+/// CONFIG GET 支持的所有配置项
+const PARAMETERS: &[&str] = &[
+    "bind", "port", "dir", "dbfilename", "import-rdb", "databases", "hz", "appendonly", "appendfilename", "aclfile", "proto-max-bulk-len",
+    "save", "appendfsync", "aof-use-rdb-preamble", "auto-aof-rewrite-percentage", "auto-aof-rewrite-min-size", "maxclients",
+    "requirepass", "loglevel", "maxmemory", "maxmemory-policy", "maxmemory-samples", "replica-read-only",
+    "min-replicas-to-write", "min-replicas-max-lag", "repl-backlog-size", "lua-time-limit",
+];
+
+/// 可以通过 CONFIG SET 修改的配置项
+const MUTABLE_PARAMETERS: &[&str] = &[
+    "save", "appendfsync", "aof-use-rdb-preamble", "auto-aof-rewrite-percentage", "auto-aof-rewrite-min-size", "maxclients",
+    "requirepass", "loglevel", "maxmemory", "maxmemory-policy", "maxmemory-samples", "replica-read-only",
+    "min-replicas-to-write", "min-replicas-max-lag", "repl-backlog-size", "lua-time-limit",
+];
+
+/**
+ * 运行时配置
+ *
+ * 启动参数 Args 只读，CONFIG SET 修改的配置保存在 values 中，
+ * 各子系统（RDB 保存规则、AOF 同步策略、连接数限制、内存淘汰）每次使用时读取最新的值
+ */
+pub struct Config {
+    args: Arc<Args>,
+    values: RwLock<Values>,
+    appendfsync: watch::Sender<SyncStrategy>,
+}
+
+impl Config {
+
+    pub fn new(args: Arc<Args>) -> Self {
+        let values = Values {
+            save: args.save.clone(),
+            appendfsync: SyncStrategy::from_str(&args.appendfsync),
+            aof_use_rdb_preamble: args.aof_use_rdb_preamble == "yes",
+            auto_aof_rewrite_percentage: args.auto_aof_rewrite_percentage,
+            auto_aof_rewrite_min_size: args.auto_aof_rewrite_min_size,
+            maxclients: args.maxclients,
+            requirepass: args.requirepass.clone(),
+            loglevel: args.loglevel.clone(),
+            maxmemory: args.maxmemory,
+            maxmemory_policy: args.maxmemory_policy,
+            maxmemory_samples: args.maxmemory_samples,
+            replica_read_only: args.replica_read_only == "yes",
+            min_replicas_to_write: args.min_replicas_to_write,
+            min_replicas_max_lag: args.min_replicas_max_lag,
+            repl_backlog_size: args.repl_backlog_size,
+            lua_time_limit: args.lua_time_limit,
+        };
+        let (appendfsync, _) = watch::channel(values.appendfsync.clone());
+        Config {
+            args,
+            values: RwLock::new(values),
+            appendfsync,
+        }
+    }
+
+    /// 当前配置
+    pub fn values(&self) -> RwLockReadGuard<'_, Values> {
+        self.values.read().unwrap()
+    }
+
+    /// 订阅 AOF 同步策略的变化
+    pub fn subscribe_appendfsync(&self) -> watch::Receiver<SyncStrategy> {
+        self.appendfsync.subscribe()
+    }
+
+    /// 配置文件路径
+    pub fn file(&self) -> PathBuf {
+        PathBuf::from(&self.args.config)
+    }
+
+    /**
+     * 获取匹配模式的所有配置项（CONFIG GET）
+     *
+     * @param patterns glob 模式列表（不区分大小写）
+     * @return (配置名, 配置值) 列表
+     */
+    pub fn get(&self, patterns: &[String]) -> Vec<(String, String)> {
+        let patterns: Vec<String> = patterns.iter().map(|pattern| pattern.to_lowercase()).collect();
+        PARAMETERS
+            .iter()
+            .filter(|name| patterns.iter().any(|pattern| pattern::is_match(name, pattern)))
+            .filter_map(|name| self.get_value(name).map(|value| (name.to_string(), value)))
+            .collect()
+    }
+
+    fn get_value(&self, name: &str) -> Option<String> {
+        let args = &self.args;
+        let values = self.values();
+        let value = match name {
+            "bind" => args.bind.clone(),
+            "port" => args.port.clone(),
+            "dir" => args.dir.clone(),
+            "dbfilename" => args.dbfilename.clone(),
+            "import-rdb" => args.import_rdb.clone().unwrap_or_default(),
+            "databases" => args.databases.to_string(),
+            "hz" => args.hz.to_string(),
+            "appendonly" => args.appendonly.clone(),
+            "appendfilename" => args.appendfilename.clone(),
+            "aclfile" => args.aclfile.clone().unwrap_or_default(),
+            "proto-max-bulk-len" => args.proto_max_bulk_len.to_string(),
+            "save" => format_save_rules(&values.save),
+            "appendfsync" => values.appendfsync.as_str().to_string(),
+            "aof-use-rdb-preamble" => (if values.aof_use_rdb_preamble { "yes" } else { "no" }).to_string(),
+            "auto-aof-rewrite-percentage" => values.auto_aof_rewrite_percentage.to_string(),
+            "auto-aof-rewrite-min-size" => values.auto_aof_rewrite_min_size.to_string(),
+            "maxclients" => values.maxclients.to_string(),
+            "requirepass" => values.requirepass.clone().unwrap_or_default(),
+            "loglevel" => values.loglevel.clone(),
+            "maxmemory" => values.maxmemory.to_string(),
+            "maxmemory-policy" => values.maxmemory_policy.as_str().to_string(),
+            "maxmemory-samples" => values.maxmemory_samples.to_string(),
+            "replica-read-only" => (if values.replica_read_only { "yes" } else { "no" }).to_string(),
+            "min-replicas-to-write" => values.min_replicas_to_write.to_string(),
+            "min-replicas-max-lag" => values.min_replicas_max_lag.to_string(),
+            "repl-backlog-size" => values.repl_backlog_size.to_string(),
+            "lua-time-limit" => values.lua_time_limit.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /**
+     * 修改配置（CONFIG SET）
+     *
+     * 所有配置项都合法时才会生效，任意一项出错则配置保持不变
+     *
+     * @param pairs (配置名, 配置值) 列表
+     */
+    pub fn set(&self, pairs: &[(String, String)]) -> Result<(), Error> {
+        let mut values = self.values().clone();
+        for (name, value) in pairs {
+            let name = name.to_lowercase();
+            if !PARAMETERS.contains(&name.as_str()) {
+                return Err(Error::msg(format!("ERR Unknown option or number of arguments for CONFIG SET - '{}'", name)));
+            }
+            if !MUTABLE_PARAMETERS.contains(&name.as_str()) {
+                return Err(Error::msg(format!("ERR CONFIG SET failed (possibly related to argument '{}') - can't set immutable config", name)));
+            }
+            if let Err(reason) = Self::set_value(&mut values, &name, value) {
+                return Err(Error::msg(format!("ERR CONFIG SET failed (possibly related to argument '{}') - {}", name, reason)));
+            }
+        }
+
+        if let Some(level) = parse_loglevel(&values.loglevel) {
+            log::set_max_level(level);
+        }
+        self.appendfsync.send_replace(values.appendfsync.clone());
+        *self.values.write().unwrap() = values;
+        Ok(())
+    }
+
+    fn set_value(values: &mut Values, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "save" => values.save = parse_save_rules(value)?,
+            "appendfsync" => {
+                values.appendfsync = match value.to_lowercase().as_str() {
+                    "always" => SyncStrategy::Always,
+                    "everysec" => SyncStrategy::EverySec,
+                    "no" => SyncStrategy::No,
+                    _ => return Err("argument(s) must be one of the following: always, everysec, no".to_string()),
+                }
+            },
+            "aof-use-rdb-preamble" => {
+                values.aof_use_rdb_preamble = match value.to_lowercase().as_str() {
+                    "yes" => true,
+                    "no" => false,
+                    _ => return Err("argument must be 'yes' or 'no'".to_string()),
+                }
+            },
+            "auto-aof-rewrite-percentage" => {
+                values.auto_aof_rewrite_percentage = value.parse().map_err(|_| "argument couldn't be parsed into an integer".to_string())?
+            },
+            "auto-aof-rewrite-min-size" => {
+                values.auto_aof_rewrite_min_size = parse_memory(value).map_err(|_| "argument must be a memory value".to_string())?
+            },
+            "maxclients" => {
+                values.maxclients = match value.parse::<u32>() {
+                    Ok(maxclients) if maxclients > 0 => maxclients as usize,
+                    _ => return Err("argument must be between 1 and 4294967295 inclusive".to_string()),
+                }
+            },
+            "requirepass" => values.requirepass = if value.is_empty() { None } else { Some(value.to_string()) },
+            "loglevel" => {
+                if parse_loglevel(value).is_none() {
+                    return Err("argument(s) must be one of the following: debug, verbose, notice, warning, nothing".to_string());
+                }
+                values.loglevel = value.to_lowercase();
+            },
+            "maxmemory" => values.maxmemory = parse_memory(value).map_err(|_| "argument must be a memory value".to_string())?,
+            "maxmemory-policy" => values.maxmemory_policy = EvictionPolicy::from_str(value)?,
+            "maxmemory-samples" => {
+                values.maxmemory_samples = match value.parse() {
+                    Ok(samples) if samples > 0 => samples,
+                    _ => return Err("argument must be between 1 and 64 inclusive".to_string()),
+                }
+            },
+            "replica-read-only" => {
+                values.replica_read_only = match value.to_lowercase().as_str() {
+                    "yes" => true,
+                    "no" => false,
+                    _ => return Err("argument must be 'yes' or 'no'".to_string()),
+                }
+            },
+            "min-replicas-to-write" => {
+                values.min_replicas_to_write = value.parse().map_err(|_| "argument couldn't be parsed into an integer".to_string())?
+            },
+            "min-replicas-max-lag" => {
+                values.min_replicas_max_lag = value.parse().map_err(|_| "argument couldn't be parsed into an integer".to_string())?
+            },
+            "repl-backlog-size" => {
+                values.repl_backlog_size = match parse_memory(value) {
+                    Ok(size) if size >= MIN_REPL_BACKLOG_SIZE => size,
+                    Ok(_) => return Err(format!("argument must be at least {}", MIN_REPL_BACKLOG_SIZE)),
+                    Err(_) => return Err("argument must be a memory value".to_string()),
+                }
+            },
+            "lua-time-limit" => {
+                values.lua_time_limit = value.parse().map_err(|_| "argument couldn't be parsed into an integer".to_string())?
+            },
+            _ => return Err("can't set immutable config".to_string()),
+        }
+        Ok(())
+    }
+
+    /**
+     * 将当前配置写回配置文件（CONFIG REWRITE）
+     *
+     * 保留文件中的注释与未知配置，已有的配置项原地更新；
+     * 文件中没有、且与默认值不同的可修改配置追加到文件末尾
+     */
+    pub fn rewrite(&self) -> Result<(), Error> {
+        let file = self.file();
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(_) => return Err(Error::msg("ERR The server is running without a config file")),
+        };
+
+        let mut lines = Vec::new();
+        let mut written = Vec::new();
+        for line in content.lines() {
+            let name = line.split_whitespace().next().unwrap_or_default().to_lowercase();
+            if line.trim_start().starts_with('#') || !MUTABLE_PARAMETERS.contains(&name.as_str()) {
+                lines.push(line.to_string());
+                continue;
+            }
+            // 同一配置项出现多次时只保留第一处
+            if !written.contains(&name) {
+                lines.push(self.config_line(&name));
+                written.push(name);
+            }
+        }
+
+        let defaults = Config::new(Arc::new(Args::default_args()));
+        for name in MUTABLE_PARAMETERS {
+            if !written.iter().any(|written| written == name) && self.get_value(name) != defaults.get_value(name) {
+                lines.push(self.config_line(name));
+            }
+        }
+
+        let mut content = lines.join("\n");
+        content.push('\n');
+        let temp = file.with_extension("tmp");
+        std::fs::write(&temp, content)
+            .and_then(|_| std::fs::rename(&temp, &file))
+            .map_err(|e| Error::msg(format!("ERR Rewriting config file: {}", e)))
+    }
+
+    fn config_line(&self, name: &str) -> String {
+        match self.get_value(name).unwrap_or_default() {
+            value if value.is_empty() => format!("{} \"\"", name),
+            value => format!("{} {}", name, value),
+        }
+    }
+}
+
+/// 将保存规则格式化为 Redis 的格式，如 "900 1 300 10"
+fn format_save_rules(rules: &[SaveRule]) -> String {
+    rules.iter().map(|rule| format!("{} {}", rule.seconds, rule.changes)).collect::<Vec<_>>().join(" ")
+}
+
+/**
+ * 解析日志级别
+ *
+ * 同时支持 Redis 的级别名称（debug、verbose、notice、warning、nothing）与 log 的级别名称
+ */
+pub fn parse_loglevel(level: &str) -> Option<LevelFilter> {
+    match level.to_lowercase().as_str() {
+        "verbose" => Some(LevelFilter::Debug),
+        "notice" => Some(LevelFilter::Info),
+        "warning" => Some(LevelFilter::Warn),
+        "nothing" => Some(LevelFilter::Off),
+        level => LevelFilter::from_str(level).ok(),
+    }
+}
@@ -1,8 +1,70 @@
 use crate::persistence::rdb_file::RdbFile;
 use anyhow::Error;
 
+/// 批量字符串的默认最大长度（512MB，与 Redis 的 proto-max-bulk-len 默认值一致）
+pub const DEFAULT_MAX_BULK_LEN: usize = 512 * 1024 * 1024;
+
+/// 内联命令以及长度行的最大长度
+const MAX_INLINE_LEN: usize = 64 * 1024;
+
+/// 数组的最大元素数量
+const MAX_MULTIBULK_LEN: i64 = i32::MAX as i64;
+
+/// 协议错误
+///
+/// 客户端发送了无法解析的数据，服务器应回复错误并关闭连接
+#[derive(Debug)]
+pub struct ProtocolError(String);
+
+impl ProtocolError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ProtocolError(message.into())
+    }
+}
+
+impl std::fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Protocol error: {}", self.0)
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// 客户端使用的协议版本（通过 HELLO 命令协商）
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Protocol {
+    Resp2,
+    Resp3,
+}
+
+impl Protocol {
+    /**
+     * 根据 HELLO 的 protover 参数获取协议版本
+     *
+     * @param version 协议版本号
+     */
+    pub fn from_version(version: i64) -> Option<Protocol> {
+        match version {
+            2 => Some(Protocol::Resp2),
+            3 => Some(Protocol::Resp3),
+            _ => None,
+        }
+    }
+
+    pub fn version(&self) -> u8 {
+        match self {
+            Protocol::Resp2 => 2,
+            Protocol::Resp3 => 3,
+        }
+    }
+}
+
 /*
  * 命令帧枚举
+ *
+ * Map、Set、Double、Boolean、BigNumber、Verbatim、Push 为 RESP3 类型，
+ * 向 RESP2 客户端回复时会降级为对应的 RESP2 类型。
+ * RDBFile 用于主从全量同步，使用 RESP 协议未占用的 '@' 前缀
  */
 #[derive(Clone)]
 pub enum Frame {
@@ -11,9 +73,17 @@ pub enum Frame {
     RDBFile(Vec<u8>),
     SimpleString(String),
     Array(Vec<Frame>),
-    BulkString(String),
+    BulkString(Vec<u8>),
     Error(String),
-    Null
+    Null,
+    NullArray,
+    Map(Vec<(Frame, Frame)>),
+    Set(Vec<Frame>),
+    Double(f64),
+    Boolean(bool),
+    BigNumber(String),
+    Verbatim(String, Vec<u8>),
+    Push(Vec<Frame>),
 }
 
 impl Frame {
@@ -29,10 +99,15 @@ impl Frame {
             Frame::Integer(i) => i.to_string(),
             Frame::RDBFile(data) => format!("[RDBFile {} bytes]", data.len()),
             Frame::SimpleString(s) => s.clone(),
-            Frame::BulkString(s) => s.clone(),
+            Frame::BulkString(s) => String::from_utf8_lossy(s).into_owned(),
             Frame::Error(e) => e.clone(),
             Frame::Null => String::new(),
-            Frame::Array(arr) => {
+            Frame::NullArray => String::new(),
+            Frame::Double(d) => Frame::format_double(*d),
+            Frame::Boolean(b) => b.to_string(),
+            Frame::BigNumber(n) => n.clone(),
+            Frame::Verbatim(_, data) => String::from_utf8_lossy(data).into_owned(),
+            Frame::Array(arr) | Frame::Set(arr) | Frame::Push(arr) => {
                 let mut result = String::new();
                 for item in arr {
                     result.push_str(&item.to_string());
@@ -40,40 +115,115 @@ impl Frame {
                 }
                 result.trim_end().to_string()
             },
+            Frame::Map(pairs) => {
+                let mut result = String::new();
+                for (key, value) in pairs {
+                    result.push_str(&key.to_string());
+                    result.push(' ');
+                    result.push_str(&value.to_string());
+                    result.push(' ');
+                }
+                result.trim_end().to_string()
+            },
         }
     }
 
     /**
-     * 将 frame 转换为 bytes
+     * 将 frame 转换为 bytes（RESP2）
      * 
      * @param self 本身
      */
     pub fn as_bytes(&self) -> Vec<u8> {
+        self.as_bytes_with_protocol(Protocol::Resp2)
+    }
+
+    /**
+     * 按协议版本将 frame 转换为 bytes
+     *
+     * RESP2 下 RESP3 类型的降级规则（与 Redis 一致）：
+     * Map 展开为键值交替的数组，Set 与 Push 为数组，Double、BigNumber 与 Verbatim 为批量字符串，
+     * Boolean 为整数 1 或 0
+     *
+     * @param protocol 协议版本
+     */
+    pub fn as_bytes_with_protocol(&self, protocol: Protocol) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_bytes(protocol, &mut bytes);
+        bytes
+    }
+
+    fn write_bytes(&self, protocol: Protocol, bytes: &mut Vec<u8>) {
+        let resp3 = protocol == Protocol::Resp3;
         match self {
-            Frame::Ok => b"+OK\r\n".to_vec(),
-            Frame::Integer(i) => format!(":{}\r\n", i).into_bytes(),
-            Frame::SimpleString(s) => format!("+{}\r\n", s).into_bytes(),
-            Frame::Error(e) => format!("-{}\r\n", e).into_bytes(),
-            Frame::Null => b"$-1\r\n".to_vec(),
+            Frame::Ok => bytes.extend(b"+OK\r\n"),
+            Frame::Integer(i) => bytes.extend(format!(":{}\r\n", i).into_bytes()),
+            Frame::SimpleString(s) => bytes.extend(format!("+{}\r\n", s).into_bytes()),
+            Frame::Error(e) => bytes.extend(format!("-{}\r\n", e).into_bytes()),
+            Frame::Null | Frame::NullArray if resp3 => bytes.extend(b"_\r\n"),
+            Frame::Null => bytes.extend(b"$-1\r\n"),
+            Frame::NullArray => bytes.extend(b"*-1\r\n"),
             Frame::RDBFile(data) => {
-                let mut bytes = format!("~{}\r\n", data.len()).into_bytes();
+                bytes.extend(format!("@{}\r\n", data.len()).into_bytes());
                 bytes.extend(data);
                 bytes.extend(b"\r\n");
-                bytes
             },
-            Frame::Array(arr) => {
-                let mut bytes = format!("*{}\r\n", arr.len()).into_bytes();
-                for item in arr {
-                    bytes.extend(item.as_bytes());
+            Frame::Array(arr) => Frame::write_aggregate(b'*', arr, protocol, bytes),
+            Frame::Set(arr) => Frame::write_aggregate(if resp3 { b'~' } else { b'*' }, arr, protocol, bytes),
+            Frame::Push(arr) => Frame::write_aggregate(if resp3 { b'>' } else { b'*' }, arr, protocol, bytes),
+            Frame::Map(pairs) => {
+                if resp3 {
+                    bytes.extend(format!("%{}\r\n", pairs.len()).into_bytes());
+                } else {
+                    bytes.extend(format!("*{}\r\n", pairs.len() * 2).into_bytes());
+                }
+                for (key, value) in pairs {
+                    key.write_bytes(protocol, bytes);
+                    value.write_bytes(protocol, bytes);
                 }
-                bytes
             },
-            Frame::BulkString(s) => {
-                let mut bytes = format!("${}\r\n", s.len()).into_bytes();
-                bytes.extend(s.as_bytes());
-                bytes.extend(b"\r\n");
-                bytes
+            Frame::BulkString(s) => Frame::write_blob(b'$', s, bytes),
+            Frame::Double(d) if resp3 => bytes.extend(format!(",{}\r\n", Frame::format_double(*d)).into_bytes()),
+            Frame::Double(d) => Frame::write_blob(b'$', Frame::format_double(*d).as_bytes(), bytes),
+            Frame::Boolean(b) if resp3 => bytes.extend(if *b { b"#t\r\n" } else { b"#f\r\n" }),
+            Frame::Boolean(b) => bytes.extend(format!(":{}\r\n", *b as i64).into_bytes()),
+            Frame::BigNumber(n) if resp3 => bytes.extend(format!("({}\r\n", n).into_bytes()),
+            Frame::BigNumber(n) => Frame::write_blob(b'$', n.as_bytes(), bytes),
+            Frame::Verbatim(format, data) if resp3 => {
+                let mut content = format!("{}:", format).into_bytes();
+                content.extend(data);
+                Frame::write_blob(b'=', &content, bytes);
             },
+            Frame::Verbatim(_, data) => Frame::write_blob(b'$', data, bytes),
+        }
+    }
+
+    fn write_aggregate(prefix: u8, items: &[Frame], protocol: Protocol, bytes: &mut Vec<u8>) {
+        bytes.push(prefix);
+        bytes.extend(format!("{}\r\n", items.len()).into_bytes());
+        for item in items {
+            item.write_bytes(protocol, bytes);
+        }
+    }
+
+    fn write_blob(prefix: u8, data: &[u8], bytes: &mut Vec<u8>) {
+        bytes.push(prefix);
+        bytes.extend(format!("{}\r\n", data.len()).into_bytes());
+        bytes.extend(data);
+        bytes.extend(b"\r\n");
+    }
+
+    /**
+     * 浮点数的文本形式，无穷大写作 inf / -inf
+     *
+     * @param value 浮点数
+     */
+    pub fn format_double(value: f64) -> String {
+        if value.is_infinite() {
+            if value > 0.0 { "inf".to_string() } else { "-inf".to_string() }
+        } else if value.is_nan() {
+            "nan".to_string()
+        } else {
+            value.to_string()
         }
     }
     
@@ -83,12 +233,7 @@ impl Frame {
      * @param bytes 二进制
      */
     pub fn parse_from_bytes(bytes: &[u8]) -> Result<Frame, Error> {
-        match bytes[0] {
-            b'+' => Frame::parse_simple_string(bytes),
-            b'~' => Frame::parse_rdb_file(bytes),
-            b'*' => Frame::parse_array(bytes),
-            _ => Err(Error::msg("Unknown frame type")),
-        }
+        Frame::parse_frame(bytes).map(|(frame, _)| frame)
     }
 
     /**
@@ -100,206 +245,219 @@ impl Frame {
         let mut frames = Vec::new();
         let mut position = 0;
         
-        while position < bytes.len() {
-            // 查找下一个完整的命令帧
-            if let Some(frame_end) = Frame::find_frame_end(&bytes[position..]) {
-                let frame_bytes = &bytes[position..position + frame_end];
-                let frame = Frame::parse_from_bytes(frame_bytes)?;
-                frames.push(frame);
-                position += frame_end;
-            } else {
-                // 如果找不到完整的帧结束位置，将剩余的数据作为最后一个帧处理
-                let frame_bytes = &bytes[position..];
-                let frame = Frame::parse_from_bytes(frame_bytes)?;
-                frames.push(frame);
-                break;
-            }
+        while bytes[position..].iter().any(|&byte| byte != b'\r' && byte != b'\n') {
+            let (frame, used) = Frame::parse_frame(&bytes[position..])?;
+            frames.push(frame);
+            position += used;
         }
         
         Ok(frames)
     }
-    
+
     /**
-     * 查找单个命令帧的结束位置
+     * 解析 bytes 开头的一个完整命令帧
+     *
+     * 数据不完整时返回错误，适用于已经完整读取的数据（如 AOF 文件）。
+     * 数据已经全部在内存中，长度不会超过实际内容，因此不限制长度（AOF 开头的 RDB 快照可能超过 proto-max-bulk-len）
      *
      * @param bytes 二进制数据
+     * @return 命令帧与消耗的字节数
      */
-    fn find_frame_end(bytes: &[u8]) -> Option<usize> {
-       
-        if bytes.is_empty() {
-            return None;
+    pub fn parse_frame(bytes: &[u8]) -> Result<(Frame, usize), Error> {
+        match Frame::decode(bytes, usize::MAX)? {
+            Some(result) => Ok(result),
+            None => Err(Error::msg("Incomplete frame")),
         }
-        
-        match bytes[0] {
-            b'*' => {
-            
-                // 数组类型的帧, 首先找到数组长度行的结束位置
-                let mut line_end = None;
-                for i in 1..bytes.len().min(20) { // 限制搜索范围，防止过长的第一行
-                    if bytes[i] == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
-                        line_end = Some(i + 2);
-                        break;
-                    }
-                }
-                
-                let line_end = line_end?;
-                if line_end >= bytes.len() {
-                    return None;
-                }
-                
-                // 解析数组长度
-                let line = std::str::from_utf8(&bytes[1..line_end - 2]).ok()?;
-                let array_len: usize = line.parse().ok()?;
-                
-                // 计算数组元素的结束位置
-                let mut current_pos = line_end;
-                for _ in 0..array_len {
-                    if current_pos >= bytes.len() {
-                        return None;
-                    }
-                    
-                    if let Some(element_end) = Frame::find_element_end(&bytes[current_pos..]) {
-                        current_pos += element_end;
-                    } else {
-                        return None;
-                    }
-                }
-                
-                Some(current_pos)
-            },
-            b'+' | b'-' | b':' => {
-                // 简单字符串、错误、整数类型
-                for i in 1..bytes.len() {
-                    if bytes[i] == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
-                        return Some(i + 2);
-                    }
-                }
-                None
-            },
-            b'$' => {
-
-                // 批量字符串类型
-                let mut line_end = None;
-                for i in 1..bytes.len().min(20) {
-                    if bytes[i] == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
-                        line_end = Some(i + 2);
-                        break;
-                    }
-                }
-                
-                let line_end = line_end?;
-                if line_end >= bytes.len() {
-                    return None;
-                }
-                
-                // 解析字符串长度
-                let line = std::str::from_utf8(&bytes[1..line_end - 2]).ok()?;
-                if line == "-1" { // NULL批量字符串
-                    return Some(line_end);
-                }
-                
-                let str_len: usize = line.parse().ok()?;
-                
-                // 字符串内容 + \r\n
-                if line_end + str_len + 2 <= bytes.len() {
-                    Some(line_end + str_len + 2)
-                } else {
-                    None
-                }
-            },
-            b'~' => {
-
-                // RDB文件类型
-                let mut len_end = None;
-                for i in 1..bytes.len().min(20) {
-                    if bytes[i] == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
-                        len_end = Some(i + 2);
-                        break;
-                    }
-                }
-                
-                let len_end = len_end?;
-                if len_end >= bytes.len() {
-                    return None;
-                }
-                
-                let len_str = std::str::from_utf8(&bytes[1..len_end - 2]).ok()?;
-                let data_len: usize = len_str.parse().ok()?;
-                
-                if len_end + data_len + 2 <= bytes.len() {
-                    Some(len_end + data_len + 2)
-                } else {
-                    None
-                }
-            },
-            _ => None,
+    }
+
+    /**
+     * 解析 bytes 开头的一个命令帧
+     *
+     * 每次调用都从头解析，需要在多次读取之间保留进度时使用 FrameParser。
+     * 帧之前多余的换行会被跳过（兼容旧版 AOF 文件中的分隔符与空的内联命令）。
+     * 支持 RESP2 与 RESP3 的全部类型，不以类型前缀开头的行按内联命令解析（如 telnet 发送的 "PING\r\n"）。
+     * 用于复制流与 AOF 文件，可以解析 RDB 文件帧
+     *
+     * @param bytes 二进制数据
+     * @param max_bulk_len 批量字符串与 RDB 文件的最大长度（proto-max-bulk-len）
+     * @return 数据不完整时返回 None，否则返回命令帧与消耗的字节数
+     */
+    pub fn decode(bytes: &[u8], max_bulk_len: usize) -> Result<Option<(Frame, usize)>, Error> {
+        match FrameParser::with_rdb_file().parse(bytes, max_bulk_len)? {
+            (Some(frame), used) => Ok(Some((frame, used))),
+            (None, _) => Ok(None),
         }
     }
-    
+
     /**
-     * 查找元素的结束位置
+     * 读取一行（不包含 \r\n）
      *
      * @param bytes 二进制数据
+     * @return 行内容与包含 \r\n 在内的长度，数据不完整时返回 None
      */
-    fn find_element_end(bytes: &[u8]) -> Option<usize> {
-        if bytes.is_empty() {
-            return None;
+    fn read_line(bytes: &[u8]) -> Result<Option<(&[u8], usize)>, Error> {
+        match bytes.windows(2).position(|window| window == b"\r\n") {
+            Some(end) => Ok(Some((&bytes[..end], end + 2))),
+            None if bytes.len() > MAX_INLINE_LEN => Err(ProtocolError::new("too big inline request").into()),
+            None => Ok(None),
         }
-        
-        match bytes[0] {
-            b'*' => Frame::find_frame_end(bytes),
-            b'+' | b'-' | b':' => {
-                for i in 1..bytes.len() {
-                    if bytes[i] == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
-                        return Some(i + 2);
+    }
+
+    /**
+     * 解析长度或整数行
+     *
+     * @param line 行内容（不包含类型前缀）
+     */
+    fn parse_number(line: &[u8]) -> Result<i64, Error> {
+        std::str::from_utf8(line)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .ok_or_else(|| ProtocolError::new(format!("invalid length '{}'", String::from_utf8_lossy(line))).into())
+    }
+
+    /**
+     * 解析浮点数文本，支持 inf / -inf / nan
+     *
+     * @param text 文本
+     */
+    pub fn parse_double(text: &str) -> Option<f64> {
+        match text.to_lowercase().as_str() {
+            "inf" | "+inf" => Some(f64::INFINITY),
+            "-inf" => Some(f64::NEG_INFINITY),
+            "nan" => Some(f64::NAN),
+            other => other.parse::<f64>().ok().filter(|value| value.is_finite()),
+        }
+    }
+
+    /**
+     * 解析内联命令
+     *
+     * 参数以空白分隔，支持单引号、双引号以及双引号中的转义字符
+     *
+     * @param line 命令行（不包含 \n）
+     * @return 只包含空白时返回 None
+     */
+    fn decode_inline(line: &[u8]) -> Result<Option<Frame>, Error> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let args = Frame::split_inline_args(line)?;
+        if args.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Frame::Array(args.into_iter().map(Frame::BulkString).collect())))
+    }
+
+    /**
+     * 拆分内联命令参数（与 Redis 的 sdssplitargs 规则一致）
+     *
+     * @param line 命令行（不包含换行）
+     */
+    fn split_inline_args(line: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        let mut args = Vec::new();
+        let mut i = 0;
+        loop {
+            while i < line.len() && line[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i >= line.len() {
+                return Ok(args);
+            }
+            let mut current = Vec::new();
+            let mut in_double_quotes = false;
+            let mut in_single_quotes = false;
+            loop {
+                if in_double_quotes {
+                    match line.get(i) {
+                        None => return Err(ProtocolError::new("unbalanced quotes in request").into()),
+                        Some(b'\\') if i + 3 < line.len() && line[i + 1] == b'x'
+                            && line[i + 2].is_ascii_hexdigit() && line[i + 3].is_ascii_hexdigit() => {
+                            let hex = std::str::from_utf8(&line[i + 2..i + 4]).unwrap_or("0");
+                            current.push(u8::from_str_radix(hex, 16).unwrap_or(0));
+                            i += 3;
+                        },
+                        Some(b'\\') if i + 1 < line.len() => {
+                            i += 1;
+                            current.push(match line[i] {
+                                b'n' => b'\n',
+                                b'r' => b'\r',
+                                b't' => b'\t',
+                                b'b' => 0x08,
+                                b'a' => 0x07,
+                                other => other,
+                            });
+                        },
+                        Some(b'"') => {
+                            // 右引号之后必须是空白或结尾
+                            if i + 1 < line.len() && !line[i + 1].is_ascii_whitespace() {
+                                return Err(ProtocolError::new("unbalanced quotes in request").into());
+                            }
+                            in_double_quotes = false;
+                        },
+                        Some(&byte) => current.push(byte),
                     }
-                }
-                None
-            },
-            b'$' => {
-                // 找到长度行的结束
-                let mut line_end = None;
-                for i in 1..bytes.len().min(20) {
-                    if bytes[i] == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
-                        line_end = Some(i + 2);
-                        break;
+                } else if in_single_quotes {
+                    match line.get(i) {
+                        None => return Err(ProtocolError::new("unbalanced quotes in request").into()),
+                        Some(b'\\') if i + 1 < line.len() && line[i + 1] == b'\'' => {
+                            i += 1;
+                            current.push(b'\'');
+                        },
+                        Some(b'\'') => {
+                            if i + 1 < line.len() && !line[i + 1].is_ascii_whitespace() {
+                                return Err(ProtocolError::new("unbalanced quotes in request").into());
+                            }
+                            in_single_quotes = false;
+                        },
+                        Some(&byte) => current.push(byte),
                     }
-                }
-                
-                let line_end = line_end?;
-                if line_end >= bytes.len() {
-                    return None;
-                }
-                
-                // 解析字符串长度
-                let line = std::str::from_utf8(&bytes[1..line_end - 2]).ok()?;
-                if line == "-1" {
-                    // NULL批量字符串
-                    return Some(line_end);
-                }
-                
-                let str_len: usize = line.parse().ok()?;
-                
-                // 字符串内容 + \r\n
-                if line_end + str_len + 2 <= bytes.len() {
-                    Some(line_end + str_len + 2)
                 } else {
-                    None
+                    match line.get(i) {
+                        None => break,
+                        Some(byte) if byte.is_ascii_whitespace() => break,
+                        Some(b'"') => in_double_quotes = true,
+                        Some(b'\'') => in_single_quotes = true,
+                        Some(&byte) => current.push(byte),
+                    }
                 }
-            },
-            _ => None,
+                i += 1;
+            }
+            args.push(current);
         }
     }
 
     /**
-     * 简单字符串
+     * 增量解析 RDB 文件帧
      *
-     * @param bytes 二进制
+     * @param bytes 二进制数据
+     * @param max_bulk_len RDB 文件的最大长度（proto-max-bulk-len）
      */
-    fn parse_simple_string(bytes: &[u8]) -> Result<Frame, Error> {
-        let end = bytes.iter().position(|&x| x == b'\r').unwrap();
-        let content = String::from_utf8(bytes[1..end].to_vec())?;
-        Ok(Frame::SimpleString(content))
+    fn decode_rdb_file(bytes: &[u8], max_bulk_len: usize) -> Result<Option<(Frame, usize)>, Error> {
+        match Frame::find_rdb_file_end(bytes, max_bulk_len)? {
+            Some(frame_end) => Ok(Some((Frame::parse_rdb_file(&bytes[..frame_end])?, frame_end))),
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * 查找 RDB 文件帧的结束位置
+     *
+     * @param bytes 二进制数据
+     * @param max_bulk_len RDB 文件的最大长度
+     * @return 数据不完整时返回 None，长度非法或超过上限时返回协议错误
+     */
+    fn find_rdb_file_end(bytes: &[u8], max_bulk_len: usize) -> Result<Option<usize>, Error> {
+        let (line, line_len) = match Frame::read_line(bytes)? {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        let data_len = match std::str::from_utf8(&line[1..]).ok().and_then(|len| len.parse::<usize>().ok()) {
+            Some(len) if len <= max_bulk_len => len,
+            _ => return Err(ProtocolError::new("invalid RDB file length").into()),
+        };
+        match line_len.checked_add(data_len).and_then(|len| len.checked_add(2)) {
+            Some(frame_end) if frame_end <= bytes.len() => Ok(Some(frame_end)),
+            Some(_) => Ok(None),
+            None => Err(ProtocolError::new("invalid RDB file length").into()),
+        }
     }
 
     /**
@@ -344,42 +502,7 @@ impl Frame {
             return Err(Error::msg("Invalid RDB terminator"));
         }
 
-        let mut data = Vec::with_capacity(data_len);
-        for &byte in &bytes[data_start..data_end] {
-            data.push(byte);
-        }
-
-        Ok(Frame::RDBFile(data))
-    }
-
-
-    /**
-     * 数组字符串
-     *
-     * @param bytes 二进制
-     */
-    fn  parse_array(bytes: &[u8]) -> Result<Frame, Error> {
-        let mut frames = Vec::new();
-        let mut start = 0;
-
-        for (i, &item) in bytes.iter().enumerate() {
-
-            if item == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
-
-                let part = match std::str::from_utf8(&bytes[start..i]) {
-                    Ok(v) => v,
-                    Err(_) => return Err(Error::msg("Invalid UTF-8 sequence")),
-                };
-
-                if !((part.starts_with('*') && part.len()!= 1) || part.starts_with('$')) {
-                    frames.push(Frame::BulkString(part.to_string()));
-                }
-
-                start = i + 2;
-            }
-        }
-
-        Ok(Frame::Array(frames))
+        Ok(Frame::RDBFile(bytes[data_start..data_end].to_vec()))
     }
 
     /**
@@ -435,6 +558,53 @@ impl Frame {
         }
     }
 
+    /**
+     * 获取 frame 的原始字节内容
+     *
+     * 批量字符串返回未经转换的字节，其余类型返回其字符串形式
+     */
+    pub fn to_vec(&self) -> Vec<u8> {
+        match self {
+            Frame::BulkString(bytes) => bytes.clone(),
+            _ => self.to_string().into_bytes(),
+        }
+    }
+
+    /**
+     * 获取指定索引的原始字节内容（二进制安全）
+     *
+     * @param index 索引
+     */
+    pub fn get_arg_bytes(&self, index: usize) -> Option<Vec<u8>> {
+        match self {
+            Frame::Array(array) => array.get(index).map(|frame| frame.to_vec()),
+            _ => None,
+        }
+    }
+
+    /**
+     * 获取命令帧中所有参数的原始字节内容（二进制安全）
+     *
+     * 键、成员与字段等参数需要使用字节形式，get_args 只适合解析选项与数字
+     */
+    pub fn get_args_bytes(&self) -> Vec<Vec<u8>> {
+        self.get_args_bytes_from_index(0)
+    }
+
+    /**
+     * 获取从指定索引开始的原始字节内容集合（二进制安全）
+     *
+     * @param start_index 开始索引
+     */
+    pub fn get_args_bytes_from_index(&self, start_index: usize) -> Vec<Vec<u8>> {
+        match self {
+            Frame::Array(array) if start_index < array.len() => {
+                array[start_index..].iter().map(|frame| frame.to_vec()).collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
     /**
      * 将 RDBFile 帧转换为 RdbFile 对象
      * 
@@ -448,4 +618,272 @@ impl Frame {
             _ => Err(Error::msg("Frame is not an RDBFile")),
         }
     }
-}
\ No newline at end of file
+}
+/// 尚未读完的聚合类型（数组、集合、推送、映射）
+struct PendingAggregate {
+    prefix: u8,
+    remaining: usize,
+    items: Vec<Frame>,
+}
+
+/// 可恢复的 RESP 解析器
+///
+/// 数据不完整时保存解析进度：每层嵌套剩余的元素数量、已读取头部的批量字符串长度，
+/// 以及当前行已经扫描过的位置。后续数据到达后从中断处继续，每个字节只扫描一次。
+/// 只有复制流与 AOF 文件可以包含 RDB 文件帧，客户端连接中 '@' 开头的行按内联命令解析
+#[derive(Default)]
+pub struct FrameParser {
+    levels: Vec<PendingAggregate>,
+    pending_bulk: Option<(u8, usize)>,
+    scanned: usize,
+    rdb_file: bool,
+}
+
+impl FrameParser {
+    pub fn new() -> Self {
+        FrameParser::default()
+    }
+
+    /// 可以解析 RDB 文件帧的解析器（复制流与 AOF 文件）
+    pub fn with_rdb_file() -> Self {
+        FrameParser { rdb_file: true, ..FrameParser::default() }
+    }
+
+    /// 是否有解析到一半的帧
+    pub fn in_progress(&self) -> bool {
+        !self.levels.is_empty() || self.pending_bulk.is_some()
+    }
+
+    /**
+     * 继续解析命令帧
+     *
+     * bytes 必须从上一次调用消耗的位置开始，已消耗的字节不会再被读取
+     *
+     * @param bytes 尚未消耗的数据
+     * @param max_bulk_len 批量字符串的最大长度（proto-max-bulk-len）
+     * @return 解析出的完整帧（数据不完整时为 None）与本次消耗的字节数
+     */
+    pub fn parse(&mut self, bytes: &[u8], max_bulk_len: usize) -> Result<(Option<Frame>, usize), Error> {
+        let mut position = 0;
+        loop {
+            let rest = &bytes[position..];
+            let element = if let Some((prefix, len)) = self.pending_bulk {
+                if rest.len() < len + 2 {
+                    return Ok((None, position));
+                }
+                if &rest[len..len + 2] != b"\r\n" {
+                    return Err(ProtocolError::new("expected CRLF after bulk string").into());
+                }
+                self.pending_bulk = None;
+                position += len + 2;
+                FrameParser::bulk_frame(prefix, rest[..len].to_vec())?
+            } else {
+                if self.levels.is_empty() {
+                    // 帧之间多余的换行直接跳过
+                    let skipped = rest.iter().take_while(|&&byte| byte == b'\r' || byte == b'\n').count();
+                    if skipped > 0 {
+                        position += skipped;
+                        continue;
+                    }
+                    match rest.first() {
+                        None => return Ok((None, position)),
+                        Some(b'@') if self.rdb_file => {
+                            return match Frame::decode_rdb_file(rest, max_bulk_len)? {
+                                Some((frame, used)) => Ok((Some(frame), position + used)),
+                                None => Ok((None, position)),
+                            };
+                        },
+                        Some(b'*' | b'$' | b'+' | b'-' | b':' | b'_' | b',' | b'#' | b'(' | b'!' | b'=' | b'%' | b'~' | b'>') => {},
+                        Some(_) => {
+                            let start = self.scanned;
+                            let end = match rest[start..].iter().position(|&byte| byte == b'\n') {
+                                Some(end) => start + end,
+                                None if rest.len() > MAX_INLINE_LEN => return Err(ProtocolError::new("too big inline request").into()),
+                                None => {
+                                    self.scanned = rest.len();
+                                    return Ok((None, position));
+                                },
+                            };
+                            self.scanned = 0;
+                            position += end + 1;
+                            // 只包含空白的内联命令直接跳过
+                            match Frame::decode_inline(&rest[..end])? {
+                                Some(frame) => return Ok((Some(frame), position)),
+                                None => continue,
+                            }
+                        },
+                    }
+                }
+                match self.parse_header(rest, max_bulk_len)? {
+                    Some((Some(element), used)) => {
+                        position += used;
+                        element
+                    },
+                    Some((None, used)) => {
+                        position += used;
+                        continue;
+                    },
+                    None => return Ok((None, position)),
+                }
+            };
+            if let Some(frame) = self.complete(element) {
+                return Ok((Some(frame), position));
+            }
+        }
+    }
+
+    /**
+     * 读取一行（不包含 \r\n），从上一次扫描到的位置继续查找
+     *
+     * @param bytes 从行首开始的数据
+     * @return 行内容与包含 \r\n 在内的长度，数据不完整时返回 None
+     */
+    fn read_line<'a>(&mut self, bytes: &'a [u8]) -> Result<Option<(&'a [u8], usize)>, Error> {
+        // 上次扫描的最后一个字节可能是 \r，需要与新数据一起判断
+        let start = self.scanned.saturating_sub(1);
+        match bytes[start..].windows(2).position(|window| window == b"\r\n") {
+            Some(end) => {
+                self.scanned = 0;
+                Ok(Some((&bytes[..start + end], start + end + 2)))
+            },
+            None if bytes.len() > MAX_INLINE_LEN => Err(ProtocolError::new("too big inline request").into()),
+            None => {
+                self.scanned = bytes.len();
+                Ok(None)
+            },
+        }
+    }
+
+    /**
+     * 解析元素的头部行
+     *
+     * 简单类型直接返回元素；批量字符串记录长度等待数据；聚合类型压入新的一层
+     *
+     * @param bytes 从元素开头开始的数据
+     * @param max_bulk_len 批量字符串的最大长度
+     * @return 头部不完整时返回 None，否则返回已完成的元素（可能为 None）与消耗的字节数
+     */
+    fn parse_header(&mut self, bytes: &[u8], max_bulk_len: usize) -> Result<Option<(Option<Frame>, usize)>, Error> {
+        let (line, line_len) = match self.read_line(bytes)? {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        if line.is_empty() {
+            return Err(ProtocolError::new("empty line").into());
+        }
+        let content = &line[1..];
+        let text = || String::from_utf8_lossy(content).into_owned();
+        let frame = match line[0] {
+            b'+' => Frame::SimpleString(text()),
+            b'-' => Frame::Error(text()),
+            b':' => Frame::Integer(Frame::parse_number(content)?),
+            b'_' => Frame::Null,
+            b'(' => Frame::BigNumber(text()),
+            b'#' => match content {
+                b"t" => Frame::Boolean(true),
+                b"f" => Frame::Boolean(false),
+                _ => return Err(ProtocolError::new("invalid boolean").into()),
+            },
+            b',' => match Frame::parse_double(&text()) {
+                Some(value) => Frame::Double(value),
+                None => return Err(ProtocolError::new("invalid double").into()),
+            },
+            b'$' | b'!' | b'=' => {
+                let len = Frame::parse_number(content)?;
+                if len == -1 && line[0] == b'$' {
+                    return Ok(Some((Some(Frame::Null), line_len)));
+                }
+                if len < 0 || len as u64 > max_bulk_len as u64 {
+                    return Err(ProtocolError::new("invalid bulk length").into());
+                }
+                self.pending_bulk = Some((line[0], len as usize));
+                return Ok(Some((None, line_len)));
+            },
+            b'*' | b'~' | b'>' | b'%' => {
+                let len = Frame::parse_number(content)?;
+                if len == -1 && line[0] == b'*' {
+                    return Ok(Some((Some(Frame::NullArray), line_len)));
+                }
+                if !(0..=MAX_MULTIBULK_LEN).contains(&len) {
+                    return Err(ProtocolError::new("invalid multibulk length").into());
+                }
+                // Map 的每个元素包含键与值两个帧
+                let count = if line[0] == b'%' { len as usize * 2 } else { len as usize };
+                if count == 0 {
+                    return Ok(Some((Some(FrameParser::aggregate_frame(line[0], Vec::new())), line_len)));
+                }
+                self.levels.push(PendingAggregate {
+                    prefix: line[0],
+                    remaining: count,
+                    // 长度来自客户端，预分配的容量需要设置上限
+                    items: Vec::with_capacity(count.min(1024)),
+                });
+                return Ok(Some((None, line_len)));
+            },
+            other => return Err(ProtocolError::new(format!("expected '$', got '{}'", other as char)).into()),
+        };
+        Ok(Some((Some(frame), line_len)))
+    }
+
+    /**
+     * 将完成的元素加入所在的聚合类型，逐层向上合并已读完的聚合类型
+     *
+     * @param frame 完成的元素
+     * @return 最外层的帧完成时返回该帧
+     */
+    fn complete(&mut self, mut frame: Frame) -> Option<Frame> {
+        loop {
+            let level = match self.levels.last_mut() {
+                Some(level) => level,
+                None => return Some(frame),
+            };
+            level.items.push(frame);
+            level.remaining -= 1;
+            if level.remaining > 0 {
+                return None;
+            }
+            let level = self.levels.pop()?;
+            frame = FrameParser::aggregate_frame(level.prefix, level.items);
+        }
+    }
+
+    /**
+     * 根据类型前缀创建批量字符串帧，内容可以包含任意字节（包括 \r\n）
+     *
+     * @param prefix 类型前缀
+     * @param data 内容
+     */
+    fn bulk_frame(prefix: u8, data: Vec<u8>) -> Result<Frame, Error> {
+        match prefix {
+            b'$' => Ok(Frame::BulkString(data)),
+            b'!' => Ok(Frame::Error(String::from_utf8_lossy(&data).into_owned())),
+            // 文本格式固定为 3 个字符，后跟冒号
+            _ if data.len() >= 4 && data[3] == b':' => {
+                Ok(Frame::Verbatim(String::from_utf8_lossy(&data[..3]).into_owned(), data[4..].to_vec()))
+            },
+            _ => Err(ProtocolError::new("invalid verbatim string").into()),
+        }
+    }
+
+    /**
+     * 根据类型前缀创建聚合类型帧
+     *
+     * @param prefix 类型前缀
+     * @param items 元素
+     */
+    fn aggregate_frame(prefix: u8, items: Vec<Frame>) -> Frame {
+        match prefix {
+            b'~' => Frame::Set(items),
+            b'>' => Frame::Push(items),
+            b'%' => {
+                let mut pairs = Vec::with_capacity(items.len() / 2);
+                let mut iter = items.into_iter();
+                while let (Some(key), Some(value)) = (iter.next(), iter.next()) {
+                    pairs.push((key, value));
+                }
+                Frame::Map(pairs)
+            },
+            _ => Frame::Array(items),
+        }
+    }
+}
@@ -1,9 +1,12 @@
+pub mod acl;
 pub mod args;
 pub mod command;
+pub mod config;
 pub mod cmds;
 pub mod frame;
 pub mod persistence;
 pub mod replication;
+pub mod sentinel;
 pub mod network;
 pub mod server;
 pub mod store;
@@ -1,4 +1,6 @@
 use rudis_server::args::Args;
+use rudis_server::config::{self, Config};
+use rudis_server::sentinel::Sentinel;
 use rudis_server::server::Server;
 use rudis_server::web::WebServer;
 use rudis_server::store::db_manager::DatabaseManager;
@@ -9,13 +11,28 @@ use std::sync::Arc;
 async fn main() {
 
     let args = Arc::new(Args::load());
-    unsafe { std::env::set_var("RUST_LOG", &args.loglevel); };
-    env_logger::init();
+    // 日志级别由 log::set_max_level 控制，CONFIG SET loglevel 可以在运行时修改
+    env_logger::Builder::new().filter_level(log::LevelFilter::Trace).init();
+    log::set_max_level(config::parse_loglevel(&args.loglevel).unwrap_or(log::LevelFilter::Info));
+
+    // 哨兵模式只监控主从节点，不加载数据也不启动 Web 管理界面
+    if args.sentinel {
+        match Sentinel::new(args.clone()) {
+            Ok(sentinel) => Arc::new(sentinel).start().await,
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let config = Arc::new(Config::new(args.clone()));
 
     server_info(args.clone());
-    let db_manager = Arc::new(DatabaseManager::new(args.clone()));
-    let web_server = WebServer::new(args.clone(), db_manager.clone());
-    let mut server = Server::new(args.clone(), db_manager);
+    let db_manager = Arc::new(DatabaseManager::new(args.clone(), config.clone()));
+    let mut server = Server::new(args.clone(), config, db_manager.clone());
+    let web_server = WebServer::new(args.clone(), db_manager, server.state());
 
     tokio::select! {
         _ = web_server.start(args.webport) => {
@@ -1,28 +1,74 @@
 // src/network/connection.rs
 use anyhow::Error;
-use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpStream};
-use std::sync::Arc;
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::{tcp::{OwnedReadHalf, OwnedWriteHalf}, TcpStream}};
+use std::{net::SocketAddr, sync::{atomic::{AtomicU8, Ordering}, Arc}};
 use tokio::sync::Mutex;
 
+use crate::{frame::{Frame, Protocol}, network::decoder::FrameDecoder};
+
+/// 客户端连接
+///
+/// 读写两端分别加锁：Handler 阻塞在读取时，其他任务（如 PUBLISH、阻塞命令唤醒）
+/// 仍然可以向该连接推送数据。
+/// 协议版本保存在连接上，其他任务推送的消息同样按该连接协商的协议编码
 #[derive(Clone)]
 pub struct Connection {
-    stream: Arc<Mutex<TcpStream>>,
+    reader: Arc<Mutex<Reader>>,
+    writer: Arc<Mutex<OwnedWriteHalf>>,
+    protocol: Arc<AtomicU8>,
+    peer_addr: Option<SocketAddr>,
+}
+
+/// 读取端：TCP 读半部与该连接的解码缓冲区
+struct Reader {
+    stream: OwnedReadHalf,
+    decoder: FrameDecoder,
 }
 
 impl Connection {
-    pub fn new(stream: TcpStream) -> Self {
+    pub fn new(stream: TcpStream, max_bulk_len: usize) -> Self {
+        let peer_addr = stream.peer_addr().ok();
+        let (reader, writer) = stream.into_split();
         Connection {
-            stream: Arc::new(Mutex::new(stream)),
+            reader: Arc::new(Mutex::new(Reader {
+                stream: reader,
+                decoder: FrameDecoder::new(max_bulk_len),
+            })),
+            writer: Arc::new(Mutex::new(writer)),
+            protocol: Arc::new(AtomicU8::new(Protocol::Resp2.version())),
+            peer_addr,
         }
     }
 
-    pub async fn read_bytes(&self) -> Result<Vec<u8>, Error> {
-        let mut stream = self.stream.lock().await;
-        let mut bytes: Vec<u8> = Vec::new();
-        let mut temp_bytes: [u8; 1024] = [0; 1024]; 
-        
+    /// 客户端地址
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.peer_addr
+    }
+
+    pub fn set_protocol(&self, protocol: Protocol) {
+        self.protocol.store(protocol.version(), Ordering::SeqCst);
+    }
+
+    pub fn get_protocol(&self) -> Protocol {
+        match self.protocol.load(Ordering::SeqCst) {
+            3 => Protocol::Resp3,
+            _ => Protocol::Resp2,
+        }
+    }
+
+    /// 读取下一个完整的命令帧
+    ///
+    /// 对端正常关闭时返回 None；在帧的中间断开或数据不符合协议时返回错误
+    pub async fn read_frame(&self) -> Result<Option<Frame>, Error> {
+        let mut reader = self.reader.lock().await;
+        let mut temp_bytes: [u8; 16 * 1024] = [0; 16 * 1024];
+
         loop {
-            let n = match stream.read(&mut temp_bytes).await {
+            if let Some(frame) = reader.decoder.next_frame()? {
+                return Ok(Some(frame));
+            }
+
+            let n = match reader.stream.read(&mut temp_bytes).await {
                 Ok(n) => n,
                 Err(e) => {
                     return Err(Error::msg(format!("Failed to read from stream: {:?}", e)));
@@ -30,24 +76,24 @@ impl Connection {
             };
 
             if n == 0 {
-                if bytes.is_empty() {
-                    return Err(Error::msg("Connection closed by peer"));
-                } else {
-                    break;
+                if reader.decoder.has_pending() {
+                    return Err(Error::msg("Connection reset by peer"));
                 }
+                return Ok(None);
             }
-            bytes.extend_from_slice(&temp_bytes[..n]);
-            if n < temp_bytes.len() {
-                break;
-            }
+            reader.decoder.extend(&temp_bytes[..n]);
         }
-        Ok(bytes)
+    }
+
+    /// 按连接协商的协议版本写入命令帧
+    pub async fn write_frame(&self, frame: &Frame) {
+        self.write_bytes(frame.as_bytes_with_protocol(self.get_protocol())).await;
     }
 
     pub async fn write_bytes(&self, bytes: Vec<u8>) {
-        let mut stream = self.stream.lock().await;
+        let mut stream = self.writer.lock().await;
         if let Err(e) = stream.write_all(&bytes).await {
             eprintln!("Failed to write to socket; err = {:?}", e);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,51 @@
+use anyhow::Error;
+
+use crate::frame::{Frame, FrameParser};
+
+/// 增量 RESP 解码器
+///
+/// 每个连接持有一个读取缓冲区：TCP 数据可能在任意位置被拆分，
+/// 不完整的帧由 FrameParser 保存解析进度，后续数据到达后从中断处继续解析
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    position: usize,
+    max_bulk_len: usize,
+    parser: FrameParser,
+}
+
+impl FrameDecoder {
+    pub fn new(max_bulk_len: usize) -> Self {
+        FrameDecoder {
+            buffer: Vec::new(),
+            position: 0,
+            max_bulk_len,
+            parser: FrameParser::new(),
+        }
+    }
+
+    /// 追加新读取到的数据
+    pub fn extend(&mut self, bytes: &[u8]) {
+        // 已解析的数据超过一半时整理缓冲区，避免缓冲区无限增长
+        if self.position > 0 && self.position >= self.buffer.len() / 2 {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// 解析下一个完整的帧，数据不完整时返回 None
+    pub fn next_frame(&mut self) -> Result<Option<Frame>, Error> {
+        let (frame, used) = self.parser.parse(&self.buffer[self.position..], self.max_bulk_len)?;
+        self.position += used;
+        if self.position == self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+        }
+        Ok(frame)
+    }
+
+    /// 缓冲区中是否还有未解析的数据（忽略帧之间多余的换行）
+    pub fn has_pending(&self) -> bool {
+        self.parser.in_progress() || self.buffer[self.position..].iter().any(|&byte| byte != b'\r' && byte != b'\n')
+    }
+}
@@ -1,4 +1,5 @@
 pub mod connection;
+pub mod decoder;
 pub mod session_manager;
 pub mod session;
 pub mod session_role;
\ No newline at end of file
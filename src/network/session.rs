@@ -1,20 +1,50 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{sync::atomic::{AtomicUsize, Ordering}, time::Instant};
 
 use tokio::sync::mpsc::Sender;
 use crate::{frame::Frame, network::{connection::Connection, session_role::SessionRole}, store::db::DatabaseMessage};
 
 static SESSION_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+/// 从节点通过 REPLCONF 上报的信息
+///
+/// ack_offset 与 ack_time 为最近一次 REPLCONF ACK 确认的偏移量与时间，开始同步时记为确认时间
+#[derive(Debug, Clone, Default)]
+pub struct ReplicaInfo {
+    pub listening_port: Option<u16>,
+    pub ip_address: Option<String>,
+    pub capabilities: Vec<String>,
+    pub ack_offset: u64,
+    pub ack_time: Option<Instant>,
+}
+
+impl ReplicaInfo {
+
+    /// 是否声明了指定能力（REPLCONF capa）
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c.eq_ignore_ascii_case(capability))
+    }
+
+    /// 距最近一次确认的秒数，没有确认过时为 None
+    pub fn lag(&self) -> Option<u64> {
+        self.ack_time.map(|ack_time| ack_time.elapsed().as_secs())
+    }
+}
+
 #[derive(Clone)]
 pub struct Session {
     id: usize,
     certification: bool,
+    user: String,
     sender: Sender<DatabaseMessage>,
     pub connection: Connection,
     current_db: usize,
     role: SessionRole,
     in_transaction: bool,
-    transaction_frames: Vec<Frame>
+    transaction_frames: Vec<Frame>,
+    subscriptions: usize,
+    watched_keys: Vec<(usize, Vec<u8>, u64)>,
+    name: Option<String>,
+    replica_info: ReplicaInfo,
 }
 
 impl Session {
@@ -24,12 +54,17 @@ impl Session {
         Session {
             id,
             certification,
+            user: crate::acl::DEFAULT_USER.to_string(),
             sender,
             current_db,
             connection,
             role: SessionRole::Other,
             in_transaction: false,
-            transaction_frames: Vec::new()
+            transaction_frames: Vec::new(),
+            subscriptions: 0,
+            watched_keys: Vec::new(),
+            name: None,
+            replica_info: ReplicaInfo::default(),
         }
     }
     
@@ -57,10 +92,27 @@ impl Session {
         self.certification
     }
 
+    /// 设置当前登录的 ACL 用户
+    pub fn set_user(&mut self, user: String) {
+        self.user = user;
+    }
+
+    pub fn get_user(&self) -> &String {
+        &self.user
+    }
+
     pub fn get_id(&self) -> usize {
         self.id
     }
 
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    pub fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
     pub fn set_role(&mut self, role: SessionRole) {
         self.role = role;
     }
@@ -69,6 +121,14 @@ impl Session {
         &self.role
     }
 
+    pub fn get_replica_info(&self) -> &ReplicaInfo {
+        &self.replica_info
+    }
+
+    pub fn get_replica_info_mut(&mut self) -> &mut ReplicaInfo {
+        &mut self.replica_info
+    }
+
     // 事务相关方法
     pub fn start_transaction(&mut self) {
         self.in_transaction = true;
@@ -95,4 +155,28 @@ impl Session {
     pub fn get_transaction_frames_mut(&mut self) -> &mut Vec<Frame> {
         &mut self.transaction_frames
     }
-}
\ No newline at end of file
+
+    // WATCH 相关方法
+    pub fn add_watched_key(&mut self, db_index: usize, key: Vec<u8>, version: u64) {
+        self.watched_keys.push((db_index, key, version));
+    }
+
+    /// 被监视的键：(数据库索引, 键名, WATCH 时的版本号)
+    pub fn get_watched_keys(&self) -> &Vec<(usize, Vec<u8>, u64)> {
+        &self.watched_keys
+    }
+
+    pub fn take_watched_keys(&mut self) -> Vec<(usize, Vec<u8>, u64)> {
+        std::mem::take(&mut self.watched_keys)
+    }
+
+    // 发布订阅相关方法
+    pub fn set_subscriptions(&mut self, count: usize) {
+        self.subscriptions = count;
+    }
+
+    /// 是否处于订阅模式（至少订阅了一个频道或模式）
+    pub fn is_subscribed(&self) -> bool {
+        self.subscriptions > 0
+    }
+}
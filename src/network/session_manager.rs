@@ -1,10 +1,16 @@
+use std::time::Instant;
+
 use dashmap::DashMap;
+use tokio::sync::Notify;
 
 use crate::network::{session::Session, session_role::SessionRole};
 
 /// 高性能会话管理器
+///
+/// 从节点的会话同时记录其确认的复制偏移量（REPLCONF ACK），供 WAIT 与 min-replicas-to-write 使用
 pub struct SessionManager {
     sessions: DashMap<usize, Session>,
+    replica_acks: Notify,
 }
 
 impl SessionManager {
@@ -13,6 +19,7 @@ impl SessionManager {
     pub fn new() -> Self {
         Self {
             sessions: DashMap::new(),
+            replica_acks: Notify::new(),
         }
     }
 
@@ -51,4 +58,58 @@ impl SessionManager {
     pub fn get_session(&self, session_id: usize) -> Option<Session> {
         self.sessions.get(&session_id).map(|entry| entry.value().clone())
     }
-}
\ No newline at end of file
+
+    /// 记录从节点确认的复制偏移量（REPLCONF ACK），唤醒等待确认的 WAIT
+    pub fn update_replica_ack(&self, session_id: usize, offset: u64) {
+        if let Some(mut entry) = self.sessions.get_mut(&session_id) {
+            let info = entry.value_mut().get_replica_info_mut();
+            info.ack_offset = offset;
+            info.ack_time = Some(Instant::now());
+        }
+        self.replica_acks.notify_waiters();
+    }
+
+    /// 确认的偏移量不小于 offset 的从节点数量
+    pub fn count_replicas_acked(&self, offset: u64) -> usize {
+        self.sessions.iter()
+            .filter(|entry| entry.value().get_role().is_slave() && entry.value().get_replica_info().ack_offset >= offset)
+            .count()
+    }
+
+    /// 最近 max_lag 秒内确认过的从节点数量（min-replicas-max-lag）
+    pub fn count_good_replicas(&self, max_lag: u64) -> usize {
+        self.sessions.iter()
+            .filter(|entry| entry.value().get_role().is_slave())
+            .filter(|entry| entry.value().get_replica_info().lag().is_some_and(|lag| lag <= max_lag))
+            .count()
+    }
+
+    /**
+     * 等待足够多的从节点确认偏移量（WAIT）
+     *
+     * @param offset 需要确认的偏移量
+     * @param numreplicas 需要的从节点数量
+     * @param deadline 超时时间，None 表示一直等待
+     * @return 超时或满足条件时确认了偏移量的从节点数量
+     */
+    pub async fn wait_replica_acks(&self, offset: u64, numreplicas: usize, deadline: Option<tokio::time::Instant>) -> usize {
+        loop {
+            // 先注册通知再检查，避免检查之后、等待之前到达的确认被错过
+            let notified = self.replica_acks.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            let acked = self.count_replicas_acked(offset);
+            if acked >= numreplicas {
+                return acked;
+            }
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                        return self.count_replicas_acked(offset);
+                    }
+                },
+                None => notified.await,
+            }
+        }
+    }
+}
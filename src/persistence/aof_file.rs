@@ -1,9 +1,9 @@
-use std::{fs, path::PathBuf, time::Duration};
+use std::{fs, path::{Path, PathBuf}, sync::{atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering}, Arc, Mutex}, time::{Duration, Instant}};
 
-use anyhow::Result;
-use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::mpsc::{self, Receiver, Sender}, time::{interval, Interval}};
+use anyhow::{Error, Result};
+use tokio::{fs::{File, OpenOptions}, io::AsyncWriteExt, sync::{mpsc::{self, Receiver, Sender}, oneshot, watch, RwLock}, time::interval};
 
-use crate::frame::Frame;
+use crate::{config::Config, frame::Frame, persistence::{aof_rewrite, rdb_file::RdbFile}, store::{db::DatabaseMessage, db_manager::DatabaseManager}};
 
 #[derive(Debug, Clone)]
 pub enum SyncStrategy {
@@ -21,58 +21,282 @@ impl SyncStrategy {
             _ => SyncStrategy::Always, // 默认为 always
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncStrategy::Always => "always",
+            SyncStrategy::EverySec => "everysec",
+            SyncStrategy::No => "no",
+        }
+    }
+}
+
+/// AOF 写入任务接收的消息
+pub enum AofMessage {
+    /// 追加一条写命令：(数据库索引, 命令)
+    Append(usize, Frame),
+    /// 开始重写：之后的写命令在写入当前文件的同时暂存到重写缓冲区
+    RewriteStart,
+    /// 重写完成：将缓冲区追加到新文件（临时文件路径）并替换当前文件，回复新文件的大小
+    RewriteFinish(PathBuf, oneshot::Sender<Result<u64>>),
+    /// 重写失败：丢弃缓冲区
+    RewriteAbort,
+}
+
+/**
+ * AOF 重写状态（INFO persistence）
+ *
+ * @param rewrite_started 正在进行的重写的开始时间
+ * @param last_rewrite_time_sec 上次重写的耗时（秒），-1 表示没有重写过
+ * @param current_size 当前文件大小
+ * @param base_size 启动或上次重写后的文件大小，用于计算自动重写的增长比例
+ */
+pub struct AofStatus {
+    rewrite_in_progress: AtomicBool,
+    rewrite_started: Mutex<Option<Instant>>,
+    last_rewrite_time_sec: AtomicI64,
+    last_rewrite_ok: AtomicBool,
+    rewrites: AtomicU64,
+    current_size: AtomicU64,
+    base_size: AtomicU64,
+}
+
+impl AofStatus {
+
+    fn new() -> Self {
+        AofStatus {
+            rewrite_in_progress: AtomicBool::new(false),
+            rewrite_started: Mutex::new(None),
+            last_rewrite_time_sec: AtomicI64::new(-1),
+            last_rewrite_ok: AtomicBool::new(true),
+            rewrites: AtomicU64::new(0),
+            current_size: AtomicU64::new(0),
+            base_size: AtomicU64::new(0),
+        }
+    }
+
+    pub fn rewrite_in_progress(&self) -> bool {
+        self.rewrite_in_progress.load(Ordering::Relaxed)
+    }
+
+    /// 正在进行的重写已经持续的时间（秒），没有进行中的重写时为 -1
+    pub fn current_rewrite_time_sec(&self) -> i64 {
+        match *self.rewrite_started.lock().unwrap() {
+            Some(started) => started.elapsed().as_secs() as i64,
+            None => -1,
+        }
+    }
+
+    pub fn last_rewrite_time_sec(&self) -> i64 {
+        self.last_rewrite_time_sec.load(Ordering::Relaxed)
+    }
+
+    pub fn last_rewrite_ok(&self) -> bool {
+        self.last_rewrite_ok.load(Ordering::Relaxed)
+    }
+
+    pub fn rewrites(&self) -> u64 {
+        self.rewrites.load(Ordering::Relaxed)
+    }
+
+    pub fn current_size(&self) -> u64 {
+        self.current_size.load(Ordering::Relaxed)
+    }
+
+    pub fn base_size(&self) -> u64 {
+        self.base_size.load(Ordering::Relaxed)
+    }
+
+    /// 相对 base_size 的增长比例（百分比）
+    fn growth(&self) -> u64 {
+        let base = self.base_size().max(1);
+        (self.current_size() * 100 / base).saturating_sub(100)
+    }
+
+    fn finish_rewrite(&self, result: &Result<u64>) {
+        let started = self.rewrite_started.lock().unwrap().take();
+        if let Some(started) = started {
+            self.last_rewrite_time_sec.store(started.elapsed().as_secs() as i64, Ordering::Relaxed);
+        }
+        self.last_rewrite_ok.store(result.is_ok(), Ordering::Relaxed);
+        if let Ok(size) = result {
+            self.base_size.store(*size, Ordering::Relaxed);
+            self.rewrites.fetch_add(1, Ordering::Relaxed);
+        }
+        self.rewrite_in_progress.store(false, Ordering::Relaxed);
+    }
 }
 
+/**
+ * AOF 文件
+ *
+ * 写命令通过通道交给后台任务追加到文件；重写时根据数据库快照生成新文件（RDB 快照或最少的命令），
+ * 重写期间到达的写命令暂存在缓冲区，新文件生成后追加缓冲区并原子地替换旧文件
+ */
 pub struct AofFile {
-    sender: Sender<(usize, Frame)>,
-    file_path: PathBuf
+    sender: Sender<AofMessage>,
+    file_path: PathBuf,
+    status: Arc<AofStatus>,
+    barrier: Arc<RwLock<()>>,
 }
 
 impl AofFile {
     
     /// 创建 AOF 处理实例
-    pub fn new(file_path: PathBuf, sync_strategy: SyncStrategy) -> Self {
+    ///
+    /// 同步策略通过 watch 通道传入，CONFIG SET appendfsync 修改后立即生效；
+    /// barrier 为服务器的写屏障，重写开始时持有写锁，等待进行中的写命令完成
+    pub fn new(file_path: PathBuf, sync_strategy: watch::Receiver<SyncStrategy>, barrier: Arc<RwLock<()>>) -> Self {
         let (sender, receiver) = mpsc::channel(1024);
+        let status = Arc::new(AofStatus::new());
         let aof_file = AofFile {
             sender,
-            file_path: file_path.clone()
+            file_path: file_path.clone(),
+            status: status.clone(),
+            barrier,
         };
-        tokio::spawn(Self::persist_loop(file_path, receiver, sync_strategy));
+        tokio::spawn(Self::persist_loop(file_path, receiver, sync_strategy, status));
         aof_file
     }
 
     /// 获取 AOF 发送通道
-    pub fn get_sender(&self) -> Sender<(usize, Frame)> {
+    pub fn get_sender(&self) -> Sender<AofMessage> {
         self.sender.clone()
     }
 
+    /// 重写状态
+    pub fn status(&self) -> &AofStatus {
+        &self.status
+    }
+
     pub async fn read_all_frames(&self) -> Result<Vec<Frame>> {
         if !self.file_path.exists() {
             return Ok(Vec::new());
         }
 
         let content = tokio::fs::read(&self.file_path).await?;
+        Ok(Self::parse_frames(&content))
+    }
+
+    /// 解析 AOF 文件内容
+    ///
+    /// 按 RESP 长度前缀逐帧解析（值可以包含任意字节），遇到不完整或损坏的帧时停止，只加载之前的有效部分
+    pub fn parse_frames(content: &[u8]) -> Vec<Frame> {
         let mut frames = Vec::new();
-        let mut start = 0;
-        let separator = b"\r\n\r\n";
-
-        // 遍历内容查找分隔符
-        while let Some(pos) =&content[start..].windows(separator.len()).position(|window| window == separator) {
-            let end = start + pos;
-            let frame_data = &content[start..end + separator.len() / 2];
-            if !frame_data.is_empty() {
-                if let Ok(frame) = Frame::parse_from_bytes(frame_data) {
+        let mut position = 0;
+        while content[position..].iter().any(|&byte| byte != b'\r' && byte != b'\n') {
+            match Frame::parse_frame(&content[position..]) {
+                Ok((frame, used)) => {
                     frames.push(frame);
+                    position += used;
+                },
+                Err(e) => {
+                    log::warn!("AOF file is truncated or corrupted at byte {}: {}", position, e);
+                    break;
+                }
+            }
+        }
+        frames
+    }
+
+    /**
+     * 在后台重写 AOF（BGREWRITEAOF）
+     *
+     * @param senders 所有数据库的通道，用于获取快照
+     * @param use_preamble 是否以 RDB 快照作为新文件的开头（aof-use-rdb-preamble）
+     * @return 已有重写正在进行时返回错误
+     */
+    pub fn rewrite(self: &Arc<Self>, senders: Vec<Sender<DatabaseMessage>>, use_preamble: bool) -> Result<(), Error> {
+        if self.status.rewrite_in_progress.swap(true, Ordering::Relaxed) {
+            return Err(Error::msg("ERR Background append only file rewriting already in progress"));
+        }
+        *self.status.rewrite_started.lock().unwrap() = Some(Instant::now());
+
+        let aof_file = self.clone();
+        tokio::spawn(async move {
+            let result = aof_file.run_rewrite(senders, use_preamble).await;
+            match &result {
+                Ok(size) => log::info!("Background AOF rewrite finished successfully, new size {}", size),
+                Err(e) => {
+                    log::error!("Background AOF rewrite failed: {}", e);
+                    let _ = aof_file.sender.send(AofMessage::RewriteAbort).await;
                 }
             }
-            // 跳过分隔符（4字节）
-            start = end + separator.len();
+            aof_file.status.finish_rewrite(&result);
+        });
+        Ok(())
+    }
+
+    async fn run_rewrite(&self, senders: Vec<Sender<DatabaseMessage>>, use_preamble: bool) -> Result<u64> {
+        // 持有屏障期间没有进行中的写命令：先开启缓冲，再让各数据库生成快照
+        let receivers = {
+            let _barrier = self.barrier.write().await;
+            self.sender.send(AofMessage::RewriteStart).await?;
+            let mut receivers = Vec::new();
+            for sender in &senders {
+                let (tx, rx) = oneshot::channel();
+                sender.send(DatabaseMessage::Snapshot(tx)).await?;
+                receivers.push(rx);
+            }
+            receivers
+        };
+
+        let mut snapshots = Vec::new();
+        for receiver in receivers {
+            snapshots.push(receiver.await?);
         }
-        Ok(frames)
+        let content = if use_preamble {
+            // 混合格式：快照以 RDB 格式写在文件开头，之后追加增量命令
+            Frame::RDBFile(RdbFile::from_snapshots(snapshots).serialize()?).as_bytes()
+        } else {
+            snapshots
+                .iter()
+                .enumerate()
+                .flat_map(|(index, snapshot)| aof_rewrite::snapshot_commands(index, snapshot))
+                .collect()
+        };
+
+        let temp_path = self.file_path.with_file_name(format!("temp-rewriteaof-{}.aof", std::process::id()));
+        if let Err(e) = tokio::fs::write(&temp_path, content).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+        let (tx, rx) = oneshot::channel();
+        self.sender.send(AofMessage::RewriteFinish(temp_path, tx)).await?;
+        rx.await?
+    }
+
+    /**
+     * 启动自动重写检查
+     *
+     * 文件大小超过 auto-aof-rewrite-min-size，且相对启动或上次重写后的大小
+     * 增长超过 auto-aof-rewrite-percentage 时自动重写，percentage 为 0 时关闭
+     */
+    pub fn start_auto_rewrite(self: &Arc<Self>, db_manager: Arc<DatabaseManager>, config: Arc<Config>) {
+        let aof_file = self.clone();
+        tokio::spawn(async move {
+            let mut interval_timer = interval(Duration::from_secs(1));
+            loop {
+                interval_timer.tick().await;
+                let (percentage, min_size, use_preamble) = {
+                    let values = config.values();
+                    (values.auto_aof_rewrite_percentage, values.auto_aof_rewrite_min_size as u64, values.aof_use_rdb_preamble)
+                };
+                let status = aof_file.status();
+                if percentage == 0 || status.rewrite_in_progress() || status.current_size() < min_size {
+                    continue;
+                }
+                let growth = status.growth();
+                if growth >= percentage {
+                    log::info!("Starting automatic rewriting of AOF on {}% growth", growth);
+                    let _ = aof_file.rewrite(db_manager.get_senders(), use_preamble);
+                }
+            }
+        });
     }
     
     /// 后台 AOF 写入任务
-    pub async fn persist_loop(file_path: PathBuf, mut receiver: Receiver<(usize, Frame)>, sync_strategy: SyncStrategy) {
+    pub async fn persist_loop(file_path: PathBuf, mut receiver: Receiver<AofMessage>, sync_strategy: watch::Receiver<SyncStrategy>, status: Arc<AofStatus>) {
 
         // 确保目录存在
         if let Some(parent) = file_path.parent() {
@@ -82,112 +306,157 @@ impl AofFile {
             }
         }
 
-        let mut open_options = OpenOptions::new();
-        open_options.create(true);
-        open_options.append(true);
-
         // 确保文件存在
-        let mut file = match open_options.open(&file_path).await {
+        let mut file = match Self::open(&file_path).await {
             Ok(file) => file,
             Err(e) => {
                 log::error!("Failed to open AOF file: {}", e);
                 return;  // 文件打开失败时退出任务
             }
         };
+        let size = file.metadata().await.map(|metadata| metadata.len()).unwrap_or(0);
+        status.current_size.store(size, Ordering::Relaxed);
+        status.base_size.store(size, Ordering::Relaxed);
+
+        // 跟踪数据库索引，文件末尾所在的数据库未知，第一条命令之前总是写入 SELECT
+        let mut current_db_index = usize::MAX;
 
-        let mut current_db_index = 0; // 跟踪数据库索引
+        // 重写期间到达的写命令
+        let mut rewrite_buffer: Option<Vec<(usize, Frame)>> = None;
         
-        // 为 everysec 策略创建定时器
-        let mut interval_timer: Option<Interval> = match sync_strategy {
-            SyncStrategy::EverySec => {
-                let mut interval = interval(Duration::from_secs(1));
-                interval.tick().await; // 消耗第一次 tick
-                Some(interval)
-            },
-            _ => None,
-        };
+        // everysec 策略的定时器（策略可能在运行期间切换，定时器始终运行）
+        let mut interval_timer = interval(Duration::from_secs(1));
+        interval_timer.tick().await; // 消耗第一次 tick
         
         // 用于跟踪是否需要 flush
         let mut need_flush = false;
         
         loop {
-            // 对于 everysec 策略，我们既要处理消息也要处理定时器
-            if let SyncStrategy::EverySec = sync_strategy {
-                tokio::select! {
-                    // 处理接收的消息
-                    msg = receiver.recv() => {
-                        if let Some((idx, frame)) = msg {
-                            if let Err(e) = Self::write_frame(&mut file, &mut current_db_index, idx, &frame, &mut need_flush).await {
-                                log::error!("Failed to write command to AOF file: {}", e);
-                                continue;
+            tokio::select! {
+                // 处理接收的消息
+                msg = receiver.recv() => {
+                    match msg {
+                        Some(AofMessage::Append(idx, frame)) => {
+                            match Self::write_frame(&mut file, &mut current_db_index, idx, &frame, &mut need_flush).await {
+                                Ok(written) => {
+                                    status.current_size.fetch_add(written, Ordering::Relaxed);
+                                },
+                                Err(e) => {
+                                    log::error!("Failed to write command to AOF file: {}", e);
+                                    continue;
+                                }
                             }
-                        } else {
-                            // 通道关闭，退出循环
-                            break;
-                        }
-                    },
-                    // 处理定时 flush
-                    _ = interval_timer.as_mut().unwrap().tick() => {
-                        if need_flush {
-                            if let Err(e) = file.flush().await {
-                                log::error!("Failed to flush AOF file: {}", e);
-                            } else {
-                                need_flush = false;
+                            if let Some(buffer) = rewrite_buffer.as_mut() {
+                                buffer.push((idx, frame));
                             }
-                        }
-                    }
-                }
-            } else {
-                // 对于 always 和 no 策略，只需要处理消息
-                if let Some((idx, frame)) = receiver.recv().await {
-                    if let Err(e) = Self::write_frame(&mut file, &mut current_db_index, idx, &frame, &mut need_flush).await {
-                        log::error!("Failed to write command to AOF file: {}", e);
-                        continue;
-                    }
-                    
-                    // 根据策略决定是否 flush
-                    match sync_strategy {
-                        SyncStrategy::Always => {
-                            if let Err(e) = file.flush().await {
-                                log::error!("Failed to flush AOF file: {}", e);
+                            // always 策略每次写入后立即 flush
+                            let strategy = sync_strategy.borrow().clone();
+                            if let SyncStrategy::Always = strategy {
+                                Self::flush(&mut file, &mut need_flush).await;
                             }
                         },
-                        SyncStrategy::EverySec => unreachable!(), 
-                        SyncStrategy::No => {},
+                        Some(AofMessage::RewriteStart) => {
+                            rewrite_buffer = Some(Vec::new());
+                        },
+                        Some(AofMessage::RewriteFinish(temp_path, reply)) => {
+                            let buffer = rewrite_buffer.take().unwrap_or_default();
+                            Self::flush(&mut file, &mut need_flush).await;
+                            let result = match Self::install_rewrite(&file_path, &temp_path, &buffer).await {
+                                Ok((new_file, db_index, size)) => {
+                                    file = new_file;
+                                    current_db_index = db_index;
+                                    status.current_size.store(size, Ordering::Relaxed);
+                                    Ok(size)
+                                },
+                                Err(e) => {
+                                    let _ = tokio::fs::remove_file(&temp_path).await;
+                                    Err(e)
+                                }
+                            };
+                            let _ = reply.send(result);
+                        },
+                        Some(AofMessage::RewriteAbort) => {
+                            rewrite_buffer = None;
+                        },
+                        // 通道关闭，退出循环
+                        None => break,
+                    }
+                },
+                // everysec 策略每秒 flush 一次，no 策略交给操作系统
+                _ = interval_timer.tick() => {
+                    let strategy = sync_strategy.borrow().clone();
+                    if need_flush {
+                        if let SyncStrategy::EverySec = strategy {
+                            Self::flush(&mut file, &mut need_flush).await;
+                        }
                     }
-                } else {
-                    // 通道关闭，退出循环
-                    break;
                 }
             }
         }
     }
+
+    async fn open(file_path: &Path) -> Result<File> {
+        Ok(OpenOptions::new().create(true).append(true).open(file_path).await?)
+    }
+
+    /**
+     * 将重写缓冲区追加到新文件，并替换当前文件
+     *
+     * @return (新文件, 文件末尾所在的数据库索引, 新文件大小)
+     */
+    async fn install_rewrite(file_path: &Path, temp_path: &Path, buffer: &[(usize, Frame)]) -> Result<(File, usize, u64)> {
+        let mut temp = OpenOptions::new().append(true).open(temp_path).await?;
+        let mut current_db_index = usize::MAX;
+        let mut need_flush = false;
+        for (idx, frame) in buffer {
+            Self::write_frame(&mut temp, &mut current_db_index, *idx, frame, &mut need_flush).await?;
+        }
+        temp.flush().await?;
+        temp.sync_all().await?;
+        drop(temp);
+
+        tokio::fs::rename(temp_path, file_path).await?;
+        let file = Self::open(file_path).await?;
+        let size = file.metadata().await?.len();
+        Ok((file, current_db_index, size))
+    }
+
+    async fn flush(file: &mut File, need_flush: &mut bool) {
+        if let Err(e) = file.flush().await {
+            log::error!("Failed to flush AOF file: {}", e);
+        } else {
+            *need_flush = false;
+        }
+    }
     
-    /// 写入帧数据的辅助函数
+    /// 写入帧数据的辅助函数，返回写入的字节数
     async fn write_frame(
-        file: &mut tokio::fs::File,
+        file: &mut File,
         current_db_index: &mut usize,
         idx: usize,
         frame: &Frame,
         need_flush: &mut bool,
-    ) -> Result<()> {
+    ) -> Result<u64> {
+        let mut written = 0;
         if idx != *current_db_index {
             let select_frame = Frame::Array(vec![
-                Frame::BulkString("SELECT".to_string()),
-                Frame::BulkString(idx.to_string()),
+                Frame::BulkString("SELECT".to_string().into()),
+                Frame::BulkString(idx.to_string().into()),
             ]);
 
-            file.write_all(&select_frame.as_bytes()).await?;
-            file.write_all(b"\r\n").await?;
+            let bytes = select_frame.as_bytes();
+            file.write_all(&bytes).await?;
+            written += bytes.len() as u64;
 
             *current_db_index = idx;
             *need_flush = true;
         }
        
-        file.write_all(&frame.as_bytes()).await?;
-        file.write_all(b"\r\n").await?;
+        let bytes = frame.as_bytes();
+        file.write_all(&bytes).await?;
+        written += bytes.len() as u64;
         *need_flush = true;
         
-        Ok(())
+        Ok(written)
     }
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{frame::Frame, persistence::dump::dump_value, store::db::{DatabaseSnapshot, Structure}};
+
+/// 集合类数据每条命令最多携带的元素数量，避免生成过大的命令
+const ITEMS_PER_COMMAND: usize = 64;
+
+/**
+ * 根据数据库快照生成重建数据所需的最少命令
+ *
+ * 字符串、列表、哈希、集合与有序集合使用对应的写命令重建；
+ * HyperLogLog、流等包含内部状态（寄存器、消费者组）的类型使用 RESTORE 原样还原。
+ * 设置了过期时间的键追加 PEXPIREAT，已经过期的键直接跳过
+ *
+ * @param index 数据库索引
+ * @param snapshot 数据库快照
+ * @return 命令的 RESP 编码，数据库为空时返回空字节
+ */
+pub fn snapshot_commands(index: usize, snapshot: &DatabaseSnapshot) -> Vec<u8> {
+    let now = SystemTime::now();
+    let mut content = Vec::new();
+    for (key, value) in &snapshot.records {
+        let expire_at = snapshot.expire_records.get(key);
+        if expire_at.is_some_and(|expire_at| *expire_at <= now) {
+            continue;
+        }
+        if content.is_empty() {
+            content.extend(command(vec![b"SELECT".to_vec(), index.to_string().into_bytes()]).as_bytes());
+        }
+        for frame in value_commands(key, value) {
+            content.extend(frame.as_bytes());
+        }
+        if let Some(expire_at) = expire_at {
+            let millis = expire_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+            content.extend(command(vec![b"PEXPIREAT".to_vec(), key.clone(), millis.to_string().into_bytes()]).as_bytes());
+        }
+    }
+    content
+}
+
+fn value_commands(key: &[u8], value: &Structure) -> Vec<Frame> {
+    let key = key.to_vec();
+    match value {
+        Structure::String(value) => vec![command(vec![b"SET".to_vec(), key, value.clone()])],
+        Structure::List(items) => chunked("RPUSH", &key, items.iter().map(|item| vec![item.clone()])),
+        Structure::Set(members) => chunked("SADD", &key, members.iter().map(|member| vec![member.clone()])),
+        Structure::Hash(fields) => chunked("HMSET", &key, fields.iter().map(|(field, value)| vec![field.clone(), value.clone()])),
+        Structure::SortedSet(set) => chunked("ZADD", &key, set.iter().map(|(member, score)| vec![score.to_string().into_bytes(), member.clone()])),
+        _ => match dump_value(value) {
+            Ok(payload) => vec![command(vec![b"RESTORE".to_vec(), key, b"0".to_vec(), payload, b"REPLACE".to_vec()])],
+            Err(e) => {
+                log::error!("Failed to serialize key {} for AOF rewrite: {}", String::from_utf8_lossy(&key), e);
+                Vec::new()
+            }
+        },
+    }
+}
+
+/// 将元素按 ITEMS_PER_COMMAND 分批，每批生成一条 "name key item..." 命令
+fn chunked(name: &str, key: &[u8], items: impl Iterator<Item = Vec<Vec<u8>>>) -> Vec<Frame> {
+    let items: Vec<Vec<Vec<u8>>> = items.collect();
+    items
+        .chunks(ITEMS_PER_COMMAND)
+        .map(|chunk| {
+            let mut args = vec![name.as_bytes().to_vec(), key.to_vec()];
+            args.extend(chunk.iter().flatten().cloned());
+            command(args)
+        })
+        .collect()
+}
+
+fn command(args: Vec<Vec<u8>>) -> Frame {
+    Frame::Array(args.into_iter().map(Frame::BulkString).collect())
+}
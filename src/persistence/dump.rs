@@ -0,0 +1,82 @@
+use anyhow::Error;
+use bincode::{config, decode_from_slice, encode_to_vec, error::DecodeError};
+
+use crate::frame::DEFAULT_MAX_BULK_LEN;
+use crate::store::db::Structure;
+
+/// 序列化格式版本，格式变化时递增，RESTORE 拒绝无法识别的版本
+const DUMP_VERSION: u16 = 1;
+
+/// 每个编码字节最多允许预留的内存（字节），合法数据中每个元素至少占 1 字节，元素在内存中不超过该大小
+const CLAIM_FACTOR: usize = 1024;
+
+/**
+ * 将值序列化为 DUMP 格式
+ *
+ * 格式与 Redis 相同：值的序列化内容 + 2 字节版本号（小端）+ 8 字节校验和
+ *
+ * @param value 键的值
+ * @return 序列化后的字节
+ */
+pub fn dump_value(value: &Structure) -> Result<Vec<u8>, Error> {
+    let mut payload = encode_to_vec(value, config::standard())?;
+    payload.extend_from_slice(&DUMP_VERSION.to_le_bytes());
+    let checksum = checksum(&payload);
+    payload.extend_from_slice(&checksum);
+    Ok(payload)
+}
+
+/**
+ * 从 DUMP 格式反序列化值
+ *
+ * @param payload DUMP 命令生成的字节
+ * @return 版本号或校验和不匹配、内容损坏时返回错误
+ */
+pub fn restore_value(payload: &[u8]) -> Result<Structure, Error> {
+    let invalid = || Error::msg("ERR DUMP payload version or checksum are wrong");
+    if payload.len() < 10 {
+        return Err(invalid());
+    }
+    let (body, footer) = payload.split_at(payload.len() - 8);
+    if checksum(body) != footer {
+        return Err(invalid());
+    }
+    let (encoded, version) = body.split_at(body.len() - 2);
+    if u16::from_le_bytes([version[0], version[1]]) != DUMP_VERSION {
+        return Err(invalid());
+    }
+    match decode_structure(encoded) {
+        Ok((value, used)) if used == encoded.len() => Ok(value),
+        _ => Err(Error::msg("ERR Bad data format")),
+    }
+}
+
+/**
+ * 在内存上限内反序列化值
+ *
+ * 校验和可以由客户端任意构造，声明的长度不可信：bincode 按声明的长度预先分配内存，
+ * 因此按内容长度选择上限（不超过 proto-max-bulk-len 的默认值），声明的长度超出剩余内容时返回 LimitExceeded
+ *
+ * @param encoded 值的序列化内容
+ * @return (值, 使用的字节数)
+ */
+fn decode_structure(encoded: &[u8]) -> Result<(Structure, usize), DecodeError> {
+    let budget = encoded.len().saturating_mul(CLAIM_FACTOR);
+    if budget <= 1 << 16 {
+        decode_from_slice(encoded, config::standard().with_limit::<{ 1 << 16 }>())
+    } else if budget <= 1 << 20 {
+        decode_from_slice(encoded, config::standard().with_limit::<{ 1 << 20 }>())
+    } else if budget <= 1 << 24 {
+        decode_from_slice(encoded, config::standard().with_limit::<{ 1 << 24 }>())
+    } else {
+        decode_from_slice(encoded, config::standard().with_limit::<DEFAULT_MAX_BULK_LEN>())
+    }
+}
+
+/// 校验和：SHA1 摘要的前 8 字节
+fn checksum(bytes: &[u8]) -> [u8; 8] {
+    let digest = sha1_smol::Sha1::from(bytes).digest().bytes();
+    let mut checksum = [0; 8];
+    checksum.copy_from_slice(&digest[..8]);
+    checksum
+}
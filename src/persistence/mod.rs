@@ -1,2 +1,6 @@
 pub mod rdb_file;
-pub mod aof_file;
\ No newline at end of file
+pub mod rdb_save;
+pub mod aof_file;
+pub mod aof_rewrite;
+pub mod dump;
+pub mod redis_rdb;
\ No newline at end of file
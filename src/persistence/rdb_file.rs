@@ -1,14 +1,30 @@
-use std::{collections::HashMap, fs::{self, File}, io::Write, path::PathBuf, time::SystemTime};
+use std::{collections::HashMap, fs::{self, File}, io::Write, path::{Path, PathBuf}, sync::atomic::{AtomicU64, Ordering}, time::SystemTime};
 
 use anyhow::Error;
 use bincode::{config, decode_from_slice, encode_to_vec, Decode, Encode};
 
-use crate::store::db::DatabaseSnapshot;
+use crate::{persistence::redis_rdb, store::db::DatabaseSnapshot, tools::crc64::crc64};
+
+/// 文件头魔数
+const MAGIC: &[u8] = b"RUDIS";
+
+/// 文件格式版本（4 位 ASCII 数字），格式变化时递增
+const RDB_VERSION: u32 = 1;
+
+/// 文件头长度：魔数 + 版本号
+const HEADER_LEN: usize = MAGIC.len() + 4;
+
+/// 文件尾 CRC64 校验和长度
+const CHECKSUM_LEN: usize = 8;
+
+/// 临时文件序号，保证并发保存时临时文件互不覆盖
+static TEMP_FILE_ID: AtomicU64 = AtomicU64::new(0);
 
 /// Rudis 数据库快照文件 (RDB) 的表示
 ///
 /// 包含多个数据库的快照、持久化元数据和文件路径信息。
-/// 使用二进制格式 (bincode) 进行序列化和反序列化。
+/// 文件格式为 `RUDIS` + 4 位版本号 + bincode 数据 + 8 字节 CRC64（小端），
+/// 校验和覆盖之前的全部内容，用于发现截断或损坏的文件。
 #[derive(Clone, Encode, Decode)]
 pub struct RdbFile {
     pub databases: HashMap<usize, DatabaseSnapshot>,
@@ -63,11 +79,40 @@ impl RdbFile {
     ///
     /// # 返回
     /// - `Ok(RdbFile)`: 解析成功的 RDB 文件对象
-    /// - `Err(Error)`: 反序列化失败时返回错误
+    /// - `Err(Error)`: 文件头、版本号或校验和不正确，或反序列化失败时返回错误
+    ///
+    /// 以 `REDIS` 开头的数据按 Redis RDB 格式解析，不以魔数开头的数据按旧版本（无文件头的 bincode）解析
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
         let config = config::standard();
-        let (rdb_file, _) = decode_from_slice(bytes, config)?;
-        Ok(rdb_file)
+        if bytes.starts_with(b"REDIS") {
+            let mut rdb_file = RdbFile::new("virtual-dump.rdb");
+            rdb_file.databases = redis_rdb::decode(bytes)?;
+            return Ok(rdb_file);
+        }
+        if !bytes.starts_with(MAGIC) {
+            let (rdb_file, _) = decode_from_slice(bytes, config)?;
+            return Ok(rdb_file);
+        }
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(Error::msg("RDB file is truncated"));
+        }
+        let (body, footer) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        let expected = u64::from_le_bytes(footer.try_into()?);
+        if crc64(body) != expected {
+            return Err(Error::msg("RDB file checksum mismatch"));
+        }
+        let version = std::str::from_utf8(&body[MAGIC.len()..HEADER_LEN])
+            .ok()
+            .and_then(|version| version.parse::<u32>().ok())
+            .ok_or_else(|| Error::msg("RDB file has an invalid version"))?;
+        if version > RDB_VERSION {
+            return Err(Error::msg(format!("Can't handle RDB format version {}", version)));
+        }
+        let payload = &body[HEADER_LEN..];
+        match decode_from_slice::<RdbFile, _>(payload, config)? {
+            (rdb_file, used) if used == payload.len() => Ok(rdb_file),
+            _ => Err(Error::msg("RDB file has trailing data")),
+        }
     }
 
     /// 将当前对象序列化为字节向量
@@ -77,7 +122,12 @@ impl RdbFile {
     /// - `Err(Error)`: 序列化失败时返回错误
     pub fn serialize(&self) -> Result<Vec<u8>, Error> {
         let config = config::standard();
-        Ok(encode_to_vec(self, config)?)
+        let mut bytes = Vec::from(MAGIC);
+        bytes.extend_from_slice(format!("{:04}", RDB_VERSION).as_bytes());
+        bytes.extend(encode_to_vec(self, config)?);
+        let checksum = crc64(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        Ok(bytes)
     }
 
     /// 获取指定数据库的快照
@@ -105,43 +155,125 @@ impl RdbFile {
     ///
     /// 执行步骤:
     /// 1. 创建父目录（如果需要）
-    /// 2. 序列化数据
-    /// 3. 写入文件
-    /// 4. 更新保存时间和变更计数
+    /// 2. 序列化数据，将现有文件保留为备份（`<文件名>.bak`）
+    /// 3. 写入同目录下的临时文件，fsync 临时文件
+    /// 4. 原子重命名临时文件覆盖目标文件，并 fsync 所在目录
+    ///
+    /// 任何一步失败（包括进程在保存过程中被杀死）都不会破坏已有的快照
     ///
     /// # 返回
     /// - `Ok(())`: 保存成功
     /// - `Err(Error)`: 文件操作或序列化失败
     pub fn save(&mut self) -> Result<(), Error> {
-        if let Some(parent) = self.path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        let mut file = File::create(&self.path)?;
-        let config = config::standard();
-        let serialized = encode_to_vec(&*self, config)?;
-        file.write_all(&serialized)?;
-        Ok(())
+        let serialized = self.serialize()?;
+        self.backup();
+        write_atomically(&self.path, &serialized)
     }
 
     /// 从磁盘文件加载数据
     ///
     /// 执行步骤:
     /// 1. 检查文件是否存在
-    /// 2. 读取字节数据
-    /// 3. 反序列化并覆盖当前状态
+    /// 2. 读取字节数据并校验文件头与校验和
+    /// 3. 文件损坏时尝试加载备份文件
+    /// 4. 反序列化并覆盖当前状态
     ///
     /// # 返回
-    /// - `Ok(())`: 加载成功（文件不存在视为空操作）
-    /// - `Err(Error)`: 文件读取或反序列化失败
+    /// - `Ok(())`: 加载成功（文件与备份都不存在视为空操作）
+    /// - `Err(Error)`: 文件损坏且没有可用的备份
     pub fn load(&mut self) -> Result<(), Error> {
-        if self.path.exists() {
-            let data = fs::read(&self.path)?;
-            let config = config::standard();
-            let (deserialized, _) = decode_from_slice::<RdbFile, _>(&data, config)?;
-            self.last_save_changes = deserialized.last_save_changes;
-            self.last_save_time = deserialized.last_save_time;
-            self.databases = deserialized.databases;
-        }
+        let backup_path = self.backup_path();
+        let loaded = match Self::read_file(&self.path) {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) if !backup_path.exists() => return Ok(()),
+            result => {
+                let reason = match result {
+                    Err(e) => e.to_string(),
+                    _ => "file is missing".to_string(),
+                };
+                match Self::read_file(&backup_path) {
+                    Ok(Some(loaded)) => {
+                        log::warn!("Failed to load RDB file {}: {}, loaded backup {} instead", self.path.display(), reason, backup_path.display());
+                        loaded
+                    }
+                    _ => return Err(Error::msg(format!("Failed to load RDB file {}: {}", self.path.display(), reason))),
+                }
+            }
+        };
+        self.last_save_changes = loaded.last_save_changes;
+        self.last_save_time = loaded.last_save_time;
+        self.databases = loaded.databases;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// 备份文件路径：在文件名后追加 `.bak`
+    fn backup_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".bak");
+        PathBuf::from(path)
+    }
+
+    /// 将现有的快照文件保留为备份，失败只记录日志，不影响本次保存
+    fn backup(&self) {
+        if !self.path.exists() {
+            return;
+        }
+        let backup_path = self.backup_path();
+        let _ = fs::remove_file(&backup_path);
+        if fs::hard_link(&self.path, &backup_path).is_err() {
+            if let Err(e) = fs::copy(&self.path, &backup_path) {
+                log::warn!("Failed to back up RDB file {}: {}", self.path.display(), e);
+            }
+        }
+    }
+
+    /// 读取并解析文件，文件不存在时返回 None
+    fn read_file(path: &Path) -> Result<Option<RdbFile>, Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read(path)?;
+        Ok(Some(Self::from_bytes(&data)?))
+    }
+}
+
+/// 原子地写入文件：先写入同目录下的临时文件并 fsync，再重命名覆盖目标文件并 fsync 所在目录
+///
+/// # 参数
+/// - `path`: 目标文件路径，父目录不存在时自动创建
+/// - `bytes`: 文件内容
+pub fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), Error> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    fs::create_dir_all(&dir)?;
+    let temp_path = dir.join(format!("temp-{}-{}.rdb", std::process::id(), TEMP_FILE_ID.fetch_add(1, Ordering::Relaxed)));
+    let result = write_temp_file(&temp_path, bytes).and_then(|_| {
+        fs::rename(&temp_path, path)?;
+        sync_dir(&dir)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn write_temp_file(path: &Path, bytes: &[u8]) -> Result<(), Error> {
+    let mut file = File::create(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// fsync 目录，保证重命名操作落盘
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> Result<(), Error> {
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> Result<(), Error> {
+    Ok(())
+}
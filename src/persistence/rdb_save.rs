@@ -0,0 +1,157 @@
+use std::{path::PathBuf, sync::{atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering}, Arc, Mutex}, time::{Instant, SystemTime, UNIX_EPOCH}};
+
+use anyhow::{Error, Result};
+use tokio::sync::{mpsc::Sender, oneshot};
+
+use crate::{persistence::rdb_file::RdbFile, store::db::DatabaseMessage};
+
+/**
+ * RDB 保存器
+ *
+ * SAVE、BGSAVE 与自动保存共用：数据库快照由各数据库任务生成，序列化与写盘在阻塞线程中执行，
+ * 同一时间只允许一个保存任务
+ *
+ * @param last_save_time 上次保存成功的时间（Unix 秒），启动时为启动时间
+ * @param bgsave_started 正在进行的后台保存的开始时间
+ * @param last_bgsave_time_sec 上次后台保存的耗时（秒），-1 表示没有后台保存过
+ */
+pub struct RdbSaver {
+    path: PathBuf,
+    senders: Vec<Sender<DatabaseMessage>>,
+    in_progress: AtomicBool,
+    last_save_time: AtomicU64,
+    bgsave_started: Mutex<Option<Instant>>,
+    last_bgsave_time_sec: AtomicI64,
+    last_bgsave_ok: AtomicBool,
+}
+
+impl RdbSaver {
+
+    pub fn new(path: impl Into<PathBuf>, senders: Vec<Sender<DatabaseMessage>>) -> Self {
+        RdbSaver {
+            path: path.into(),
+            senders,
+            in_progress: AtomicBool::new(false),
+            last_save_time: AtomicU64::new(unix_time()),
+            bgsave_started: Mutex::new(None),
+            last_bgsave_time_sec: AtomicI64::new(-1),
+            last_bgsave_ok: AtomicBool::new(true),
+        }
+    }
+
+    pub fn bgsave_in_progress(&self) -> bool {
+        self.bgsave_started.lock().unwrap().is_some()
+    }
+
+    pub fn last_save_time(&self) -> u64 {
+        self.last_save_time.load(Ordering::Relaxed)
+    }
+
+    /// 正在进行的后台保存已经持续的时间（秒），没有进行中的后台保存时为 -1
+    pub fn current_bgsave_time_sec(&self) -> i64 {
+        match *self.bgsave_started.lock().unwrap() {
+            Some(started) => started.elapsed().as_secs() as i64,
+            None => -1,
+        }
+    }
+
+    pub fn last_bgsave_time_sec(&self) -> i64 {
+        self.last_bgsave_time_sec.load(Ordering::Relaxed)
+    }
+
+    pub fn last_bgsave_ok(&self) -> bool {
+        self.last_bgsave_ok.load(Ordering::Relaxed)
+    }
+
+    /// 所有数据库自上次保存以来的变更数
+    pub async fn changes(&self) -> u64 {
+        let mut changes = 0;
+        for sender in &self.senders {
+            let (tx, rx) = oneshot::channel();
+            if sender.send(DatabaseMessage::Changes(tx)).await.is_ok() {
+                if let Ok(count) = rx.await {
+                    changes += count;
+                }
+            }
+        }
+        changes
+    }
+
+    /**
+     * 前台保存（SAVE），等待写盘完成
+     *
+     * @return 已有保存任务或保存失败时返回错误
+     */
+    pub async fn save(&self) -> Result<()> {
+        self.begin()?;
+        let result = self.run().await;
+        self.in_progress.store(false, Ordering::Release);
+        result
+    }
+
+    /**
+     * 后台保存（BGSAVE 与自动保存），立即返回
+     *
+     * @return 已有保存任务时返回错误
+     */
+    pub fn bgsave(self: &Arc<Self>) -> Result<()> {
+        self.begin()?;
+        *self.bgsave_started.lock().unwrap() = Some(Instant::now());
+        let saver = self.clone();
+        tokio::spawn(async move {
+            let result = saver.run().await;
+            if let Err(e) = &result {
+                log::error!("Background saving error: {}", e);
+            } else {
+                log::info!("Background saving terminated with success");
+            }
+            let started = saver.bgsave_started.lock().unwrap().take();
+            if let Some(started) = started {
+                saver.last_bgsave_time_sec.store(started.elapsed().as_secs() as i64, Ordering::Relaxed);
+            }
+            saver.last_bgsave_ok.store(result.is_ok(), Ordering::Relaxed);
+            saver.in_progress.store(false, Ordering::Release);
+        });
+        Ok(())
+    }
+
+    fn begin(&self) -> Result<()> {
+        if self.in_progress.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_err() {
+            return Err(Error::msg("ERR Background save already in progress"));
+        }
+        Ok(())
+    }
+
+    /**
+     * 生成快照并写盘
+     *
+     * 每个数据库先读取变更数再生成快照，保存成功后从变更数中扣除已经写入快照的部分，
+     * 保存期间到达的写命令仍然计入下一次保存
+     */
+    async fn run(&self) -> Result<()> {
+        let mut rdb_file = RdbFile::new(self.path.clone());
+        let mut saved_changes = Vec::with_capacity(self.senders.len());
+        for (index, sender) in self.senders.iter().enumerate() {
+            let (tx, rx) = oneshot::channel();
+            sender.send(DatabaseMessage::Changes(tx)).await?;
+            saved_changes.push(rx.await?);
+            let (tx, rx) = oneshot::channel();
+            sender.send(DatabaseMessage::Snapshot(tx)).await?;
+            rdb_file.set_database(index, rx.await?);
+        }
+        rdb_file.last_save_changes = saved_changes.iter().sum();
+
+        tokio::task::spawn_blocking(move || rdb_file.save()).await??;
+
+        for (sender, changes) in self.senders.iter().zip(saved_changes) {
+            let _ = sender.send(DatabaseMessage::ResetChanges(changes)).await;
+        }
+        self.last_save_time.store(unix_time(), Ordering::Relaxed);
+        log::debug!("DB saved on disk");
+        Ok(())
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
@@ -0,0 +1,598 @@
+use std::{collections::{HashMap, HashSet}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use anyhow::{Error, Result};
+
+use crate::{store::{db::{DatabaseSnapshot, Structure}, sorted_set::SortedSet}, tools::crc64::crc64};
+
+/// 写入文件时使用的 RDB 版本
+const WRITE_VERSION: u32 = 9;
+
+/// 能够读取的最高 RDB 版本
+const MAX_VERSION: u32 = 12;
+
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_SET_LISTPACK: u8 = 20;
+
+const OPCODE_SLOT_INFO: u8 = 244;
+const OPCODE_FUNCTION2: u8 = 245;
+const OPCODE_MODULE_AUX: u8 = 247;
+const OPCODE_IDLE: u8 = 248;
+const OPCODE_FREQ: u8 = 249;
+const OPCODE_AUX: u8 = 250;
+const OPCODE_RESIZEDB: u8 = 251;
+const OPCODE_EXPIRETIME_MS: u8 = 252;
+const OPCODE_EXPIRETIME: u8 = 253;
+const OPCODE_SELECTDB: u8 = 254;
+const OPCODE_EOF: u8 = 255;
+
+/// 长度前缀中的特殊编码
+const ENC_INT8: u64 = 0;
+const ENC_INT16: u64 = 1;
+const ENC_INT32: u64 = 2;
+const ENC_LZF: u64 = 3;
+
+/// quicklist 2 节点的容器类型
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+
+/**
+ * 解析 Redis RDB 文件（版本 9 及之后，即 Redis 5.0+），用于从 Redis 迁移数据
+ *
+ * 支持字符串、列表、集合、哈希、有序集合的常见编码（ziplist、listpack、intset、quicklist、LZF 压缩），
+ * 以及过期时间与选择数据库操作码；流、模块等类型无法映射到 Structure，遇到时返回错误。
+ * 键、集合成员与哈希字段按原始字节保存，非 UTF-8 的内容不做任何转换
+ *
+ * @param bytes 文件内容
+ * @return 按数据库索引分组的快照，已经过期的键被跳过；格式错误、校验和不匹配或包含不支持的类型时返回错误
+ */
+pub fn decode(bytes: &[u8]) -> Result<HashMap<usize, DatabaseSnapshot>> {
+    if bytes.len() < 9 || &bytes[..5] != b"REDIS" {
+        return Err(Error::msg("Wrong signature trying to load DB from file"));
+    }
+    let version = std::str::from_utf8(&bytes[5..9])
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+        .ok_or_else(|| Error::msg("Wrong signature trying to load DB from file"))?;
+    if version > MAX_VERSION {
+        return Err(Error::msg(format!("Can't handle RDB format version {}", version)));
+    }
+
+    let now = SystemTime::now();
+    let mut reader = Reader { bytes, pos: 9 };
+    let mut databases: HashMap<usize, DatabaseSnapshot> = HashMap::new();
+    let mut db_index = 0;
+    let mut expire_at: Option<SystemTime> = None;
+    loop {
+        let opcode = reader.u8()?;
+        match opcode {
+            OPCODE_EOF => break,
+            OPCODE_SELECTDB => db_index = reader.length()? as usize,
+            OPCODE_RESIZEDB => {
+                reader.length()?;
+                reader.length()?;
+            }
+            OPCODE_SLOT_INFO => {
+                reader.length()?;
+                reader.length()?;
+                reader.length()?;
+            }
+            OPCODE_AUX => {
+                reader.string()?;
+                reader.string()?;
+            }
+            OPCODE_EXPIRETIME_MS => {
+                let millis = u64::from_le_bytes(reader.array()?);
+                expire_at = Some(UNIX_EPOCH + Duration::from_millis(millis));
+            }
+            OPCODE_EXPIRETIME => {
+                let secs = u32::from_le_bytes(reader.array()?);
+                expire_at = Some(UNIX_EPOCH + Duration::from_secs(secs as u64));
+            }
+            OPCODE_IDLE => {
+                reader.length()?;
+            }
+            OPCODE_FREQ => {
+                reader.u8()?;
+            }
+            OPCODE_FUNCTION2 => {
+                reader.string()?;
+                log::warn!("Skipping function library in RDB file, functions are not supported");
+            }
+            OPCODE_MODULE_AUX => return Err(Error::msg("Module data in RDB files is not supported")),
+            value_type => {
+                let key = reader.string()?;
+                let value = read_value(&mut reader, value_type)?;
+                let expire_at = expire_at.take();
+                if expire_at.is_some_and(|expire_at| expire_at <= now) {
+                    continue;
+                }
+                let snapshot = databases.entry(db_index).or_default();
+                if let Some(expire_at) = expire_at {
+                    snapshot.expire_records.insert(key.clone(), expire_at);
+                }
+                snapshot.records.insert(key, value);
+            }
+        }
+    }
+
+    // 版本 5 起文件末尾是 8 字节 CRC64，为 0 表示写入时关闭了校验
+    if version >= 5 {
+        let end = reader.pos;
+        let expected = u64::from_le_bytes(reader.array()?);
+        if expected != 0 && crc64(&bytes[..end]) != expected {
+            return Err(Error::msg("Wrong RDB checksum"));
+        }
+    }
+    Ok(databases)
+}
+
+/**
+ * 将数据库快照编码为 Redis RDB 文件
+ *
+ * 字符串、列表、集合、哈希与有序集合使用最基础的编码写入，任何版本 9 及之后的 Redis 都可以加载；
+ * JSON、HyperLogLog、流与向量在 Redis 中没有对应的类型，被跳过并记录日志
+ *
+ * @param databases 按数据库索引分组的快照
+ * @return 文件内容
+ */
+pub fn encode(databases: &HashMap<usize, DatabaseSnapshot>) -> Vec<u8> {
+    let now = SystemTime::now();
+    let mut out = format!("REDIS{:04}", WRITE_VERSION).into_bytes();
+    write_aux(&mut out, "redis-ver", env!("CARGO_PKG_VERSION"));
+    write_aux(&mut out, "redis-bits", "64");
+    write_aux(&mut out, "ctime", &now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs().to_string());
+
+    let mut indexes: Vec<&usize> = databases.keys().collect();
+    indexes.sort();
+    for index in indexes {
+        let snapshot = &databases[index];
+        let mut entries = Vec::new();
+        for (key, value) in &snapshot.records {
+            let expire_at = snapshot.expire_records.get(key);
+            if expire_at.is_some_and(|expire_at| *expire_at <= now) {
+                continue;
+            }
+            if value_type(value).is_none() {
+                log::warn!("Skipping key {} in RDB export, its type has no Redis equivalent", String::from_utf8_lossy(key));
+                continue;
+            }
+            entries.push((key, value, expire_at));
+        }
+        if entries.is_empty() {
+            continue;
+        }
+
+        out.push(OPCODE_SELECTDB);
+        write_length(&mut out, *index as u64);
+        out.push(OPCODE_RESIZEDB);
+        write_length(&mut out, entries.len() as u64);
+        write_length(&mut out, entries.iter().filter(|(_, _, expire_at)| expire_at.is_some()).count() as u64);
+        for (key, value, expire_at) in entries {
+            if let Some(expire_at) = expire_at {
+                let millis = expire_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+                out.push(OPCODE_EXPIRETIME_MS);
+                out.extend_from_slice(&millis.to_le_bytes());
+            }
+            write_value(&mut out, key, value);
+        }
+    }
+
+    out.push(OPCODE_EOF);
+    let checksum = crc64(&out);
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+fn value_type(value: &Structure) -> Option<u8> {
+    match value {
+        Structure::String(_) => Some(TYPE_STRING),
+        Structure::List(_) => Some(TYPE_LIST),
+        Structure::Set(_) => Some(TYPE_SET),
+        Structure::Hash(_) => Some(TYPE_HASH),
+        Structure::SortedSet(_) => Some(TYPE_ZSET_2),
+        _ => None,
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, key: &[u8], value: &Structure) {
+    let Some(value_type) = value_type(value) else {
+        return;
+    };
+    out.push(value_type);
+    write_string(out, key);
+    match value {
+        Structure::String(value) => write_string(out, value),
+        Structure::List(items) => {
+            write_length(out, items.len() as u64);
+            for item in items {
+                write_string(out, item);
+            }
+        }
+        Structure::Set(members) => {
+            write_length(out, members.len() as u64);
+            for member in members {
+                write_string(out, member);
+            }
+        }
+        Structure::Hash(fields) => {
+            write_length(out, fields.len() as u64);
+            for (field, value) in fields {
+                write_string(out, field);
+                write_string(out, value);
+            }
+        }
+        Structure::SortedSet(set) => {
+            write_length(out, set.len() as u64);
+            for (member, score) in set.iter() {
+                write_string(out, member);
+                out.extend_from_slice(&score.to_le_bytes());
+            }
+        }
+        _ => {}
+    }
+}
+
+fn write_aux(out: &mut Vec<u8>, name: &str, value: &str) {
+    out.push(OPCODE_AUX);
+    write_string(out, name.as_bytes());
+    write_string(out, value.as_bytes());
+}
+
+fn write_string(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_length(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn write_length(out: &mut Vec<u8>, len: u64) {
+    if len < 1 << 6 {
+        out.push(len as u8);
+    } else if len < 1 << 14 {
+        out.push(0x40 | (len >> 8) as u8);
+        out.push(len as u8);
+    } else if len <= u32::MAX as u64 {
+        out.push(0x80);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    } else {
+        out.push(0x81);
+        out.extend_from_slice(&len.to_be_bytes());
+    }
+}
+
+fn read_value(reader: &mut Reader, value_type: u8) -> Result<Structure> {
+    let value = match value_type {
+        TYPE_STRING => Structure::String(reader.string()?),
+        TYPE_LIST => {
+            let len = reader.length()?;
+            let mut items = Vec::new();
+            for _ in 0..len {
+                items.push(reader.string()?);
+            }
+            Structure::List(items)
+        }
+        TYPE_SET => {
+            let len = reader.length()?;
+            let mut members = HashSet::new();
+            for _ in 0..len {
+                members.insert(reader.string()?);
+            }
+            Structure::Set(members)
+        }
+        TYPE_ZSET | TYPE_ZSET_2 => {
+            let len = reader.length()?;
+            let mut set = SortedSet::new();
+            for _ in 0..len {
+                let member = reader.string()?;
+                let score = if value_type == TYPE_ZSET_2 { f64::from_le_bytes(reader.array()?) } else { reader.string_double()? };
+                set.add(member, score);
+            }
+            Structure::SortedSet(set)
+        }
+        TYPE_HASH => {
+            let len = reader.length()?;
+            let mut fields = HashMap::new();
+            for _ in 0..len {
+                let field = reader.string()?;
+                fields.insert(field, reader.string()?);
+            }
+            Structure::Hash(fields)
+        }
+        TYPE_LIST_ZIPLIST => Structure::List(ziplist_entries(&reader.string()?)?),
+        TYPE_LIST_QUICKLIST => {
+            let nodes = reader.length()?;
+            let mut items = Vec::new();
+            for _ in 0..nodes {
+                items.extend(ziplist_entries(&reader.string()?)?);
+            }
+            Structure::List(items)
+        }
+        TYPE_LIST_QUICKLIST_2 => {
+            let nodes = reader.length()?;
+            let mut items = Vec::new();
+            for _ in 0..nodes {
+                let container = reader.length()?;
+                let node = reader.string()?;
+                if container == QUICKLIST_NODE_PLAIN {
+                    items.push(node);
+                } else {
+                    items.extend(listpack_entries(&node)?);
+                }
+            }
+            Structure::List(items)
+        }
+        TYPE_SET_INTSET => Structure::Set(intset_entries(&reader.string()?)?.into_iter().collect()),
+        TYPE_SET_LISTPACK => Structure::Set(listpack_entries(&reader.string()?)?.into_iter().collect()),
+        TYPE_HASH_ZIPLIST | TYPE_HASH_LISTPACK => {
+            let encoded = reader.string()?;
+            let entries = if value_type == TYPE_HASH_ZIPLIST { ziplist_entries(&encoded)? } else { listpack_entries(&encoded)? };
+            Structure::Hash(pairs(entries)?.into_iter().collect())
+        }
+        TYPE_ZSET_ZIPLIST | TYPE_ZSET_LISTPACK => {
+            let encoded = reader.string()?;
+            let entries = if value_type == TYPE_ZSET_ZIPLIST { ziplist_entries(&encoded)? } else { listpack_entries(&encoded)? };
+            let mut set = SortedSet::new();
+            for (member, score) in pairs(entries)? {
+                let score = std::str::from_utf8(&score).ok().and_then(|score| score.parse::<f64>().ok())
+                    .ok_or_else(|| Error::msg("Invalid sorted set score in RDB file"))?;
+                set.add(member, score);
+            }
+            Structure::SortedSet(set)
+        }
+        value_type => return Err(Error::msg(format!("Unsupported RDB object type {}", value_type))),
+    };
+    Ok(value)
+}
+
+/// 将交替排列的 字段、值 组合成键值对
+fn pairs(entries: Vec<Vec<u8>>) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    if !entries.len().is_multiple_of(2) {
+        return Err(Error::msg("Odd number of entries in RDB encoded pairs"));
+    }
+    let mut iter = entries.into_iter();
+    let mut pairs = Vec::new();
+    while let (Some(first), Some(second)) = (iter.next(), iter.next()) {
+        pairs.push((first, second));
+    }
+    Ok(pairs)
+}
+
+/**
+ * 解析 ziplist：zlbytes(4) + zltail(4) + zllen(2) + 条目 + 0xFF
+ *
+ * 每个条目为 前一条目长度（1 或 5 字节）+ 编码 + 数据，整数条目转换为十进制字符串
+ */
+fn ziplist_entries(bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader { bytes, pos: 10 };
+    let mut entries = Vec::new();
+    loop {
+        let prevlen = reader.u8()?;
+        if prevlen == 0xff {
+            break;
+        }
+        if prevlen == 0xfe {
+            reader.take(4)?;
+        }
+        let encoding = reader.u8()?;
+        let entry = match encoding >> 6 {
+            0 => reader.take((encoding & 0x3f) as usize)?.to_vec(),
+            1 => {
+                let len = ((encoding as usize & 0x3f) << 8) | reader.u8()? as usize;
+                reader.take(len)?.to_vec()
+            }
+            2 => {
+                let len = u32::from_be_bytes(reader.array()?) as usize;
+                reader.take(len)?.to_vec()
+            }
+            _ => {
+                let value = match encoding {
+                    0xc0 => i16::from_le_bytes(reader.array()?) as i64,
+                    0xd0 => i32::from_le_bytes(reader.array()?) as i64,
+                    0xe0 => i64::from_le_bytes(reader.array()?),
+                    0xf0 => {
+                        let bytes: [u8; 3] = reader.array()?;
+                        (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as i64
+                    }
+                    0xfe => reader.u8()? as i8 as i64,
+                    0xf1..=0xfd => (encoding & 0x0f) as i64 - 1,
+                    _ => return Err(Error::msg("Invalid ziplist entry encoding")),
+                };
+                value.to_string().into_bytes()
+            }
+        };
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/**
+ * 解析 listpack：总字节数(4) + 元素数(2) + 条目 + 0xFF
+ *
+ * 每个条目为 编码 + 数据 + 反向长度（1 到 5 字节），整数条目转换为十进制字符串
+ */
+fn listpack_entries(bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader { bytes, pos: 6 };
+    let mut entries = Vec::new();
+    loop {
+        let start = reader.pos;
+        let encoding = reader.u8()?;
+        if encoding == 0xff {
+            break;
+        }
+        let entry = if encoding & 0x80 == 0 {
+            (encoding & 0x7f).to_string().into_bytes()
+        } else if encoding & 0xc0 == 0x80 {
+            reader.take((encoding & 0x3f) as usize)?.to_vec()
+        } else if encoding & 0xe0 == 0xc0 {
+            let value = ((encoding as i64 & 0x1f) << 8) | reader.u8()? as i64;
+            let value = if value >= 1 << 12 { value - (1 << 13) } else { value };
+            value.to_string().into_bytes()
+        } else if encoding & 0xf0 == 0xe0 {
+            let len = ((encoding as usize & 0x0f) << 8) | reader.u8()? as usize;
+            reader.take(len)?.to_vec()
+        } else {
+            match encoding {
+                0xf0 => {
+                    let len = u32::from_le_bytes(reader.array()?) as usize;
+                    reader.take(len)?.to_vec()
+                }
+                0xf1 => i16::from_le_bytes(reader.array()?).to_string().into_bytes(),
+                0xf2 => {
+                    let bytes: [u8; 3] = reader.array()?;
+                    (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8).to_string().into_bytes()
+                }
+                0xf3 => i32::from_le_bytes(reader.array()?).to_string().into_bytes(),
+                0xf4 => i64::from_le_bytes(reader.array()?).to_string().into_bytes(),
+                _ => return Err(Error::msg("Invalid listpack entry encoding")),
+            }
+        };
+        let backlen = match reader.pos - start {
+            len if len < 1 << 7 => 1,
+            len if len < 1 << 14 => 2,
+            len if len < 1 << 21 => 3,
+            len if len < 1 << 28 => 4,
+            _ => 5,
+        };
+        reader.take(backlen)?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// 解析 intset：编码宽度(4) + 元素数(4) + 小端整数
+fn intset_entries(bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let width = u32::from_le_bytes(reader.array()?);
+    let len = u32::from_le_bytes(reader.array()?);
+    let mut entries = Vec::new();
+    for _ in 0..len {
+        let value = match width {
+            2 => i16::from_le_bytes(reader.array()?) as i64,
+            4 => i32::from_le_bytes(reader.array()?) as i64,
+            8 => i64::from_le_bytes(reader.array()?),
+            _ => return Err(Error::msg("Invalid intset encoding")),
+        };
+        entries.push(value.to_string().into_bytes());
+    }
+    Ok(entries)
+}
+
+/// LZF 解压
+fn lzf_decompress(input: &[u8], expected_len: usize) -> Result<Vec<u8>> {
+    let invalid = || Error::msg("Invalid LZF compressed string");
+    let mut output = Vec::with_capacity(expected_len);
+    let mut pos = 0;
+    while pos < input.len() {
+        let ctrl = input[pos] as usize;
+        pos += 1;
+        if ctrl < 32 {
+            let literal = input.get(pos..pos + ctrl + 1).ok_or_else(invalid)?;
+            output.extend_from_slice(literal);
+            pos += ctrl + 1;
+        } else {
+            let mut len = ctrl >> 5;
+            if len == 7 {
+                len += *input.get(pos).ok_or_else(invalid)? as usize;
+                pos += 1;
+            }
+            let offset = ((ctrl & 0x1f) << 8) + *input.get(pos).ok_or_else(invalid)? as usize + 1;
+            pos += 1;
+            let start = output.len().checked_sub(offset).ok_or_else(invalid)?;
+            // 引用的区域可能与正在写入的区域重叠，只能逐字节复制
+            for index in start..start + len + 2 {
+                output.push(output[index]);
+            }
+        }
+    }
+    if output.len() != expected_len {
+        return Err(invalid());
+    }
+    Ok(output)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| Error::msg("Unexpected end of RDB data"))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into()?)
+    }
+
+    /// 读取长度前缀，返回 (长度或特殊编码, 是否为特殊编码)
+    fn length_or_encoding(&mut self) -> Result<(u64, bool)> {
+        let first = self.u8()?;
+        let value = match first >> 6 {
+            0 => (first as u64 & 0x3f, false),
+            1 => (((first as u64 & 0x3f) << 8) | self.u8()? as u64, false),
+            2 => match first {
+                0x80 => (u32::from_be_bytes(self.array()?) as u64, false),
+                0x81 => (u64::from_be_bytes(self.array()?), false),
+                _ => return Err(Error::msg("Unknown length encoding in RDB file")),
+            },
+            _ => (first as u64 & 0x3f, true),
+        };
+        Ok(value)
+    }
+
+    fn length(&mut self) -> Result<u64> {
+        match self.length_or_encoding()? {
+            (len, false) => Ok(len),
+            _ => Err(Error::msg("Unexpected encoded length in RDB file")),
+        }
+    }
+
+    /// 读取字符串：普通字符串、整数编码或 LZF 压缩
+    fn string(&mut self) -> Result<Vec<u8>> {
+        let bytes = match self.length_or_encoding()? {
+            (len, false) => self.take(len as usize)?.to_vec(),
+            (ENC_INT8, true) => (self.u8()? as i8).to_string().into_bytes(),
+            (ENC_INT16, true) => i16::from_le_bytes(self.array()?).to_string().into_bytes(),
+            (ENC_INT32, true) => i32::from_le_bytes(self.array()?).to_string().into_bytes(),
+            (ENC_LZF, true) => {
+                let compressed_len = self.length()? as usize;
+                let len = self.length()? as usize;
+                lzf_decompress(self.take(compressed_len)?, len)?
+            }
+            _ => return Err(Error::msg("Unknown string encoding in RDB file")),
+        };
+        Ok(bytes)
+    }
+
+    /// 旧版有序集合（类型 3）的分值：1 字节长度 + ASCII，253/254/255 分别表示 NaN、+inf、-inf
+    fn string_double(&mut self) -> Result<f64> {
+        let value = match self.u8()? {
+            253 => f64::NAN,
+            254 => f64::INFINITY,
+            255 => f64::NEG_INFINITY,
+            len => std::str::from_utf8(self.take(len as usize)?).ok()
+                .and_then(|score| score.parse::<f64>().ok())
+                .ok_or_else(|| Error::msg("Invalid sorted set score in RDB file"))?,
+        };
+        Ok(value)
+    }
+}
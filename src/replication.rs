@@ -1,12 +1,25 @@
-use std::{sync::Arc};
+pub mod master;
+
+use std::{sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex}, time::{Duration, Instant}};
 
 use anyhow::{Error, Result};
 use tokio::net::TcpStream;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use crate::store::db::{DatabaseMessage};
+use tokio::task::JoinHandle;
+use crate::command::Command;
+use crate::persistence::rdb_file::RdbFile;
+use crate::store::db::DatabaseMessage;
 use crate::store::db_manager::DatabaseManager;
+use crate::store::script_status::BUSY_ERROR;
 use crate::{args::Args, frame::Frame};
 
+/// 从节点定期上报复制偏移量的间隔
+const ACK_PERIOD: Duration = Duration::from_secs(1);
+
+/// 重连间隔：每次失败后加倍，直到上限；同步成功后恢复初始值
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(16);
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ReplicationState {    
     Connecting,    
@@ -16,52 +29,199 @@ pub enum ReplicationState {
     Connected    
 }
 
+/**
+ * 从节点与主节点之间的复制状态（INFO replication、ROLE）
+ *
+ * 复制任务更新，其他连接只读取
+ */
+pub struct ReplicaStatus {
+    master_host: String,
+    master_port: u16,
+    link_up: AtomicBool,
+    sync_in_progress: AtomicBool,
+    offset: AtomicU64,
+    master_replid: Mutex<String>,
+    last_io: Mutex<Option<Instant>>,
+    link_down_since: Mutex<Option<Instant>>,
+}
+
+impl ReplicaStatus {
+
+    fn new(master_host: String, master_port: u16) -> Self {
+        ReplicaStatus {
+            master_host,
+            master_port,
+            link_up: AtomicBool::new(false),
+            sync_in_progress: AtomicBool::new(false),
+            offset: AtomicU64::new(0),
+            master_replid: Mutex::new(String::new()),
+            last_io: Mutex::new(None),
+            link_down_since: Mutex::new(None),
+        }
+    }
+
+    pub fn master_host(&self) -> &str {
+        &self.master_host
+    }
+
+    pub fn master_port(&self) -> u16 {
+        self.master_port
+    }
+
+    pub fn link_up(&self) -> bool {
+        self.link_up.load(Ordering::Relaxed)
+    }
+
+    pub fn sync_in_progress(&self) -> bool {
+        self.sync_in_progress.load(Ordering::Relaxed)
+    }
+
+    /// 已处理的复制流偏移量
+    pub fn offset(&self) -> u64 {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    pub fn master_replid(&self) -> String {
+        self.master_replid.lock().unwrap().clone()
+    }
+
+    /// 距上次收到主节点数据的秒数，从未收到时为 -1
+    pub fn last_io_seconds_ago(&self) -> i64 {
+        self.last_io.lock().unwrap().map_or(-1, |last_io| last_io.elapsed().as_secs() as i64)
+    }
+
+    /// 连接断开的秒数，连接正常时为 None，从未连接成功时为 -1
+    pub fn link_down_seconds(&self) -> Option<i64> {
+        if self.link_up() {
+            return None;
+        }
+        Some(self.link_down_since.lock().unwrap().map_or(-1, |since| since.elapsed().as_secs() as i64))
+    }
+}
+
+/**
+ * 正在运行的复制任务
+ *
+ * 连接断开后按退避间隔自动重连，drop 时停止复制（REPLICAOF NO ONE 或切换主节点）
+ */
+pub struct ReplicaLink {
+    status: Arc<ReplicaStatus>,
+    task: JoinHandle<()>,
+}
+
+impl ReplicaLink {
+
+    pub fn start(master_host: String, master_port: u16, args: Arc<Args>, db_manager: Arc<DatabaseManager>) -> Self {
+        let status = Arc::new(ReplicaStatus::new(master_host, master_port));
+        let manager = ReplicationManager::new(args, db_manager, status.clone());
+        let task = tokio::spawn(manager.run());
+        ReplicaLink { status, task }
+    }
+
+    pub fn status(&self) -> &Arc<ReplicaStatus> {
+        &self.status
+    }
+}
+
+impl Drop for ReplicaLink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/**
+ * 从节点复制
+ *
+ * 握手流程与 Redis 相同：PING、REPLCONF listening-port、REPLCONF capa、PSYNC，
+ * 主节点回复 +FULLRESYNC 后接收 $<len> 格式的快照，之后持续接收复制流并定期上报偏移量。
+ * 快照可以是 Redis RDB 格式，也可以是原生格式（主节点为 rudis 时）。
+ * 重新连接同一主节点时携带复制 ID 与偏移量，主节点回复 +CONTINUE 时只接收缺失的复制流
+ *
+ * @param buffer 已从连接读取但尚未处理的数据
+ * @param master_replid 主节点的复制 ID，没有同步过时为空
+ * @param offset 已处理的复制流偏移量
+ * @param current_db 复制流当前选择的数据库，部分同步时沿用
+ */
 pub struct ReplicationManager {
     pub state: ReplicationState,
     pub db_manager: Arc<DatabaseManager>,
     pub stream: Option<TcpStream>,
-    pub args: Arc<Args>
+    pub args: Arc<Args>,
+    status: Arc<ReplicaStatus>,
+    buffer: Vec<u8>,
+    master_replid: String,
+    offset: u64,
+    current_db: usize,
 }
 
 impl ReplicationManager {
 
-    pub fn new(args: Arc<Args>, db_manager: Arc<DatabaseManager>) -> Self {
+    pub fn new(args: Arc<Args>, db_manager: Arc<DatabaseManager>, status: Arc<ReplicaStatus>) -> Self {
         
         Self {
             state: ReplicationState::Disconnected,
-            db_manager: db_manager,
+            db_manager,
             stream: None,
-            args
+            args,
+            status,
+            buffer: Vec::new(),
+            master_replid: String::new(),
+            offset: 0,
+            current_db: 0,
         }
     }
     
     /**
-     * 连接到主节点
+     * 持续复制：连接断开或同步失败后按退避间隔重连
      */
-    pub async fn connect(&mut self) -> Result<()> {
-        self.state = ReplicationState::Connecting;
-        match self.args.replicaof.as_ref() {
-            Some(addr) => {
-                match TcpStream::connect(addr).await {
-                    Ok(mut _stream) => {
-                        self.stream = Some(_stream);
-                        self.ping().await?;
-                        self.replconf().await?;
-                        self.psync().await?; 
-                        self.rdb_file_receiver().await?;
-                        self.cmd_receiver().await?;
-                        Ok(())
-                    },
-                    Err(_e) => {
-                        self.state = ReplicationState::Disconnected;
-                        Err(Error::msg("Connection failed"))
-                    }
-                }
-            },
-            None => {
-                Ok(())
+    pub async fn run(mut self) {
+        let mut delay = RECONNECT_INITIAL_DELAY;
+        loop {
+            log::info!("Connecting to MASTER {}:{}", self.status.master_host, self.status.master_port);
+            let result = self.connect().await;
+            let synced = self.state == ReplicationState::Connected;
+            self.set_state(ReplicationState::Disconnected);
+            self.stream = None;
+            match result {
+                Ok(()) => log::warn!("Connection with master lost"),
+                Err(e) => log::error!("Replication with master failed: {}", e),
             }
+            if synced {
+                delay = RECONNECT_INITIAL_DELAY;
+            }
+            tokio::time::sleep(delay).await;
+            if !synced {
+                delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+            }
+        }
+    }
+
+    /**
+     * 连接到主节点并完成同步，返回时连接已经断开
+     */
+    pub async fn connect(&mut self) -> Result<()> {
+        self.set_state(ReplicationState::Connecting);
+        let addr = (self.status.master_host.clone(), self.status.master_port);
+        let stream = TcpStream::connect(addr).await.map_err(|e| Error::msg(format!("Connection failed: {}", e)))?;
+        self.stream = Some(stream);
+        self.buffer.clear();
+        self.ping().await?;
+        self.replconf().await?;
+        if self.psync().await? {
+            self.rdb_file_receiver().await?;
+        }
+        self.cmd_receiver().await
+    }
+
+    /// 更新复制阶段，同步更新对外可见的连接状态
+    fn set_state(&mut self, state: ReplicationState) {
+        let link_up = state == ReplicationState::Connected;
+        if self.status.link_up.swap(link_up, Ordering::Relaxed) && !link_up {
+            *self.status.link_down_since.lock().unwrap() = Some(Instant::now());
         }
+        let syncing = matches!(state, ReplicationState::WaitPsync | ReplicationState::ReceivingRdb);
+        self.status.sync_in_progress.store(syncing, Ordering::Relaxed);
+        self.state = state;
     }
 
     /**
@@ -70,153 +230,255 @@ impl ReplicationManager {
      * @param self
      */
     async fn ping(&mut self) -> Result<()> {
-
-        let stream = self.stream.as_mut().unwrap();
-        let frame = Frame::Array(vec![Frame::BulkString("PING".to_string())]);
-        stream.write_all(&frame.as_bytes()).await?;
-        
-        // 等待 PING 响应
-        let mut buffer = [0; 1024];
-        let n = stream.read(&mut buffer).await?;
-        let response = Frame::parse_from_bytes(&buffer[..n]).unwrap();
-        if let Frame::SimpleString(s) = response {
-            if s == "PONG" {
-                return Ok(());
-            }
+        self.send_command(&["PING"]).await?;
+        let reply = self.read_line().await?;
+        if reply != "+PONG" {
+            return Err(Error::msg(format!("Master did not respond with PONG: {}", reply)));
         }
-
-        let msg_str = "Master did not respond with PONG";
-        let msg = Error::msg(msg_str);
-        Err(msg) // 响应 error 信息
+        Ok(())
     }
 
     /**
      * 发送 REPLCONF 命令
+     *
+     * 上报监听端口与支持的能力：psync2 与 Redis 相同，rudis 表示可以接收原生格式的快照。
+     * 不支持 REPLCONF capa 的主节点会回复错误，忽略即可
      * 
      * @param self
      */
     async fn replconf(&mut self) -> Result<()> {
-
-        let stream = self.stream.as_mut().unwrap();
         let port = self.args.port.to_string();
-        let bind = self.args.bind.to_string();
-        let replconf_str = String::from("REPLCONF");
-        let listening_port_str = String::from("LISTENING-PORT");
-        let ip_address_str = String::from("IP-ADDRESS");
-        
-        let replconf_frame = Frame::Array(vec![
-            Frame::BulkString(replconf_str),
-            Frame::BulkString(listening_port_str),
-            Frame::BulkString(port),
-            Frame::BulkString(ip_address_str),
-            Frame::BulkString(bind),
-        ]);
-
-        stream.write_all(&replconf_frame.as_bytes()).await?;
-        let mut buffer = [0; 1024];
-        let n = stream.read(&mut buffer).await?;
-        let response = Frame::parse_from_bytes(&buffer[..n]).unwrap();
-        if let Frame::SimpleString(s) = response {
-            if s == "OK" {
-                return Ok(());
-            }
+        self.send_command(&["REPLCONF", "listening-port", &port]).await?;
+        let reply = self.read_line().await?;
+        if reply != "+OK" {
+            return Err(Error::msg(format!("REPLCONF listening-port failed: {}", reply)));
         }
 
-        Err(Error::msg("REPLCONF failed"))
+        self.send_command(&["REPLCONF", "capa", "psync2", "capa", "rudis"]).await?;
+        let reply = self.read_line().await?;
+        if reply != "+OK" {
+            log::warn!("Master does not understand REPLCONF capa: {}", reply);
+        }
+        Ok(())
     }
 
     /**
      * 发送 PSYNC 命令
+     *
+     * 同步过的从节点请求 <replid> <offset + 1>，否则请求 ? -1
      * 
      * @param self
+     * @return 需要接收快照（+FULLRESYNC）时返回 true，部分同步（+CONTINUE）时返回 false
      */
-    async fn psync(&mut self) -> Result<()> {
-        let stream = self.stream.as_mut().unwrap();
-        let psync_frame = Frame::Array(vec![Frame::BulkString("PSYNC".to_string())]);
-        stream.write_all(&psync_frame.as_bytes()).await?;
-        self.state = ReplicationState::WaitPsync;
-        Ok(())
+    async fn psync(&mut self) -> Result<bool> {
+        if self.master_replid.is_empty() {
+            self.send_command(&["PSYNC", "?", "-1"]).await?;
+        } else {
+            let replid = self.master_replid.clone();
+            let offset = (self.offset + 1).to_string();
+            self.send_command(&["PSYNC", &replid, &offset]).await?;
+        }
+        self.set_state(ReplicationState::WaitPsync);
+        let reply = self.read_line().await?;
+        let parts: Vec<&str> = reply.split(' ').collect();
+        match parts.as_slice() {
+            ["+FULLRESYNC", replid, offset] => {
+                self.master_replid = replid.to_string();
+                self.offset = offset.parse().map_err(|_| Error::msg(format!("Invalid FULLRESYNC reply: {}", reply)))?;
+                self.current_db = 0;
+                self.publish();
+                log::info!("Full resync from master: {}:{}", self.master_replid, self.offset);
+                Ok(true)
+            },
+            ["+CONTINUE", rest @ ..] => {
+                // 主节点的复制 ID 可能变化（如切换主节点），偏移量保持连续
+                if let Some(replid) = rest.first() {
+                    self.master_replid = replid.to_string();
+                    self.publish();
+                }
+                log::info!("Successful partial resynchronization with master, offset {}", self.offset);
+                Ok(false)
+            },
+            _ => Err(Error::msg(format!("Unexpected reply to PSYNC from master: {}", reply))),
+        }
     }
 
     /**
-     * 接收 RDB_FILE 内容
+     * 接收快照（$<len>\r\n<rdb>）并替换所有数据库的内容
      * 
      * @param self
      */
     async fn rdb_file_receiver(&mut self) -> Result<()> {
-        let mut buffer = [0; 1024];
-        let stream: &mut TcpStream = self.stream.as_mut().unwrap();
-        let n = stream.read(&mut buffer).await?;
-        let frame = Frame::parse_from_bytes(&buffer[..n]).unwrap();
-        let rdb_file = frame.to_rdb_file().unwrap();
+        self.set_state(ReplicationState::ReceivingRdb);
+        let line = self.read_line().await?;
+        let len = line.strip_prefix('$')
+            .and_then(|len| len.parse::<usize>().ok())
+            .ok_or_else(|| Error::msg(format!("Bad protocol from master, expected RDB payload length: {}", line)))?;
+        let payload = self.read_exact(len).await?;
+        log::info!("MASTER <-> REPLICA sync: receiving {} bytes from master", len);
+        let rdb_file = tokio::task::spawn_blocking(move || RdbFile::from_bytes(&payload)).await??;
+
         let senders = self.db_manager.get_senders();
         for (db_index, target_sender) in senders.iter().enumerate() {
-            match target_sender.send(DatabaseMessage::Restore(rdb_file.get_database(db_index))).await {
-                Ok(()) => {}
-                Err(e) => {
-                    eprintln!("Failed to write to socket; err = {:?}", e);
-                }
-            };
+            target_sender.send(DatabaseMessage::Restore(rdb_file.get_database(db_index))).await?;
         }
+        log::info!("MASTER <-> REPLICA sync: Finished with success");
         Ok(())
     }
 
     /**
      * 接收 COMMAND 传播
+     *
+     * 偏移量按已经处理的复制流字节数累计（包括 SELECT、PING 等），
+     * 每秒通过 REPLCONF ACK 上报，收到 REPLCONF GETACK 时立即上报
      * 
      * @param self
      */
     async fn cmd_receiver(&mut self) -> Result<()> {
-        let stream = self.stream.as_mut().unwrap();
-        let mut buffer = [0; 4096];
-        let mut current_db_index = 0;
+        self.set_state(ReplicationState::Connected);
+        let mut chunk = vec![0; 16 * 1024];
+        let mut ack = tokio::time::interval(ACK_PERIOD);
         
         log::info!("Connected to master, waiting for commands...");
         
         loop {
-            let n = stream.read(&mut buffer).await?;
-            if n == 0 {
-                self.state = ReplicationState::Disconnected;
-                log::warn!("Master connection closed");
-                break;
+            // 一次读取可能包含多个命令，也可能只包含命令的一部分（剩余部分留在缓冲区中）
+            let mut position = 0;
+            while let Some((frame, used)) = Frame::decode(&self.buffer[position..], self.args.proto_max_bulk_len)? {
+                position += used;
+                self.apply_frame(frame).await?;
+                self.offset += used as u64;
+            }
+            self.buffer.drain(..position);
+            self.publish();
+
+            let stream = self.stream.as_mut().unwrap();
+            tokio::select! {
+                result = stream.read(&mut chunk) => {
+                    let n = result?;
+                    if n == 0 {
+                        return Ok(());
+                    }
+                    self.touch();
+                    self.buffer.extend_from_slice(&chunk[..n]);
+                },
+                _ = ack.tick() => {
+                    self.send_ack().await?;
+                },
             }
-            
-            match Frame::parse_from_bytes(&buffer[..n]) {
-                Ok(frame) => {
-                    match crate::command::Command::parse_from_frame(frame) {
-                        Ok(command) => {
-                            if let crate::command::Command::Select(ref select_cmd) = command {
-                                current_db_index = select_cmd.get_db_index();
-                                continue;
-                            }
-                            
-                            let db_sender = self.db_manager.get_sender(current_db_index);
-                            let (sender, receiver) = tokio::sync::oneshot::channel();
-                            let message = DatabaseMessage::Command { sender, command };
-                            match db_sender.send(message).await {
-                                Ok(()) => {
-                                    match receiver.await {
-                                        Ok(_result) => {}
-                                        Err(e) => {
-                                            log::error!("Failed to receive command result: {}", e);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    log::error!("Failed to send command to database: {}", e);
-                                }
-                            }
-                        }
+        }
+    }
+
+    /// 执行复制流中的一条命令
+    async fn apply_frame(&mut self, frame: Frame) -> Result<()> {
+        let command = match Command::parse_from_frame(frame.clone()) {
+            Ok(command) => command,
+            Err(e) => {
+                log::error!("Failed to parse master node command: {}", e);
+                return Ok(());
+            }
+        };
+        match command {
+            Command::Select(select) => {
+                self.current_db = select.get_db_index();
+            },
+            Command::Ping(_) | Command::Multi(_) | Command::Exec(_) => {},
+            // 上报的偏移量不包括 GETACK 本身
+            Command::Replconf(replconf) if replconf.is_getack() => {
+                self.send_ack().await?;
+            },
+            Command::Replconf(_) => {},
+            Command::Flushall(flushall) => {
+                flushall.apply(self.db_manager.clone()).await?;
+            },
+            mut command => {
+                let db_sender = self.db_manager.get_sender(self.current_db);
+                // 从节点上的脚本超时期间数据库回复 BUSY 且不执行命令，主节点的写命令不能丢弃，等待脚本结束后重试
+                loop {
+                    let (sender, receiver) = tokio::sync::oneshot::channel();
+                    let message = DatabaseMessage::Command { sender, command };
+                    if let Err(e) = db_sender.send(message).await {
+                        log::error!("Failed to send command to database: {}", e);
+                        break;
+                    }
+                    match receiver.await {
+                        Ok(Frame::Error(e)) if e == BUSY_ERROR => {
+                            tokio::time::sleep(Duration::from_millis(10)).await;
+                            command = Command::parse_from_frame(frame.clone())?;
+                        },
+                        Ok(_) => break,
                         Err(e) => {
-                            log::error!("Failed to parse master node command: {}", e);
-                        }
+                            log::error!("Failed to receive command result: {}", e);
+                            break;
+                        },
                     }
                 }
-                Err(e) => {
-                    log::error!("Failed to parse frame from master: {}", e);
-                }
-            }
+            },
+        }
+        Ok(())
+    }
+
+    /// 记录收到主节点数据的时间
+    fn touch(&self) {
+        *self.status.last_io.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// 对外公布复制 ID 与偏移量
+    fn publish(&self) {
+        self.status.offset.store(self.offset, Ordering::Relaxed);
+        let mut master_replid = self.status.master_replid.lock().unwrap();
+        if *master_replid != self.master_replid {
+            *master_replid = self.master_replid.clone();
+        }
+    }
+
+    async fn send_ack(&mut self) -> Result<()> {
+        let offset = self.offset.to_string();
+        self.send_command(&["REPLCONF", "ACK", &offset]).await
+    }
+
+    async fn send_command(&mut self, args: &[&str]) -> Result<()> {
+        let frame = Frame::Array(args.iter().map(|arg| Frame::BulkString(arg.to_string().into())).collect());
+        let stream = self.stream.as_mut().unwrap();
+        stream.write_all(&frame.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// 从连接读取更多数据到缓冲区
+    async fn fill_buffer(&mut self) -> Result<()> {
+        let stream = self.stream.as_mut().unwrap();
+        let mut chunk = vec![0; 16 * 1024];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(Error::msg("Master closed the connection"));
         }
+        self.touch();
+        self.buffer.extend_from_slice(&chunk[..n]);
         Ok(())
     }
-}
\ No newline at end of file
+
+    /**
+     * 读取一行回复（不含 CRLF）
+     *
+     * 主节点准备快照期间会发送单独的换行保持连接，这些换行被跳过
+     */
+    async fn read_line(&mut self) -> Result<String> {
+        loop {
+            let skipped = self.buffer.iter().take_while(|&&byte| byte == b'\r' || byte == b'\n').count();
+            self.buffer.drain(..skipped);
+            if let Some(end) = self.buffer.windows(2).position(|window| window == b"\r\n") {
+                let line = String::from_utf8_lossy(&self.buffer[..end]).to_string();
+                self.buffer.drain(..end + 2);
+                return Ok(line);
+            }
+            self.fill_buffer().await?;
+        }
+    }
+
+    async fn read_exact(&mut self, len: usize) -> Result<Vec<u8>> {
+        while self.buffer.len() < len {
+            self.fill_buffer().await?;
+        }
+        Ok(self.buffer.drain(..len).collect())
+    }
+}
@@ -0,0 +1,196 @@
+use std::{collections::VecDeque, sync::{Arc, Mutex}, time::Duration};
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::{config::Config, frame::Frame, store::memory::random_u64};
+
+/// 没有写命令时向从节点发送 PING 的间隔（repl-ping-replica-period）
+const PING_PERIOD: Duration = Duration::from_secs(10);
+
+/**
+ * 主节点复制流
+ *
+ * 写命令按执行顺序编码为 RESP 追加到复制流，发送给所有在线的从节点；
+ * 复制偏移量为复制流累计的字节数，与 Redis 一致。
+ * 第一个从节点连接后创建积压缓冲区（repl-backlog-size），保存最近的复制流，
+ * 从节点断线重连时缺失的部分仍在缓冲区内则只补发缺失部分（PSYNC 回复 +CONTINUE）
+ *
+ * @param replid 复制 ID，40 位十六进制字符串，每次启动重新生成
+ */
+pub struct ReplicationMaster {
+    replid: String,
+    config: Arc<Config>,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    offset: u64,
+    /// 复制流中最近一次 SELECT 的数据库，None 表示下一条命令前必须 SELECT
+    selected_db: Option<usize>,
+    replicas: Vec<(usize, UnboundedSender<Vec<u8>>)>,
+    /// 积压缓冲区，保存复制流最后的字节，没有从节点连接过时为 None
+    backlog: Option<VecDeque<u8>>,
+}
+
+/// 积压缓冲区的状态（INFO replication）
+pub struct BacklogInfo {
+    pub size: usize,
+    pub first_byte_offset: u64,
+    pub histlen: usize,
+}
+
+impl ReplicationMaster {
+
+    pub fn new(config: Arc<Config>) -> Self {
+        let replid = format!("{:016x}{:016x}{:08x}", random_u64(), random_u64(), random_u64() as u32);
+        ReplicationMaster {
+            replid,
+            config,
+            inner: Mutex::new(Inner { offset: 0, selected_db: None, replicas: Vec::new(), backlog: None }),
+        }
+    }
+
+    pub fn replid(&self) -> &str {
+        &self.replid
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.inner.lock().unwrap().offset
+    }
+
+    pub fn replica_count(&self) -> usize {
+        self.inner.lock().unwrap().replicas.len()
+    }
+
+    /// 积压缓冲区的状态，没有积压缓冲区时返回 None
+    pub fn backlog_info(&self) -> Option<BacklogInfo> {
+        let inner = self.inner.lock().unwrap();
+        inner.backlog.as_ref().map(|backlog| BacklogInfo {
+            size: self.config.values().repl_backlog_size,
+            first_byte_offset: inner.offset - backlog.len() as u64 + 1,
+            histlen: backlog.len(),
+        })
+    }
+
+    /**
+     * 追加一条写命令
+     *
+     * 数据库与上一条命令不同时先追加 SELECT；没有积压缓冲区（从未有从节点连接）时不生成复制流
+     *
+     * @param db 命令所在的数据库
+     * @param frame 命令
+     */
+    pub fn feed(&self, db: usize, frame: &Frame) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.backlog.is_none() {
+            return;
+        }
+        let mut bytes = Vec::new();
+        if inner.selected_db != Some(db) {
+            let select = Frame::Array(vec![
+                Frame::BulkString("SELECT".to_string().into()),
+                Frame::BulkString(db.to_string().into()),
+            ]);
+            bytes.extend(select.as_bytes());
+            inner.selected_db = Some(db);
+        }
+        bytes.extend(frame.as_bytes());
+        inner.send(bytes, self.config.values().repl_backlog_size);
+    }
+
+    /**
+     * 注册从节点（全量同步）
+     *
+     * 调用方需持有写屏障，使返回的偏移量与同时生成的快照一致
+     *
+     * @param id 从节点的会话 ID
+     * @return 快照对应的复制偏移量，以及此后的复制流
+     */
+    pub fn add_replica(&self, id: usize) -> (u64, UnboundedReceiver<Vec<u8>>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut inner = self.inner.lock().unwrap();
+        // 新的从节点不知道当前选择的数据库
+        inner.selected_db = None;
+        inner.backlog.get_or_insert_with(VecDeque::new);
+        inner.replicas.push((id, sender));
+        (inner.offset, receiver)
+    }
+
+    /**
+     * 尝试部分同步
+     *
+     * 复制 ID 相同、且从节点需要的第一个字节仍在积压缓冲区内时才能继续
+     *
+     * @param id 从节点的会话 ID
+     * @param replid 从节点已知的复制 ID
+     * @param offset 从节点需要的第一个字节的偏移量（已处理的偏移量 + 1）
+     * @return 需要补发的数据，以及此后的复制流；无法部分同步时返回 None
+     */
+    pub fn try_continue(&self, id: usize, replid: &str, offset: i64) -> Option<(Vec<u8>, UnboundedReceiver<Vec<u8>>)> {
+        if replid != self.replid || offset < 1 {
+            return None;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        let backlog = inner.backlog.as_ref()?;
+        let first_byte_offset = inner.offset - backlog.len() as u64 + 1;
+        let offset = offset as u64;
+        if offset < first_byte_offset || offset > inner.offset + 1 {
+            return None;
+        }
+        let missing = backlog.iter().skip((offset - first_byte_offset) as usize).copied().collect();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        inner.replicas.push((id, sender));
+        Some((missing, receiver))
+    }
+
+    /// 要求所有从节点立即上报偏移量（REPLCONF GETACK *），用于 WAIT
+    pub fn request_ack(&self) {
+        let backlog_size = self.config.values().repl_backlog_size;
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.replicas.is_empty() {
+            let getack = Frame::Array(vec![
+                Frame::BulkString("REPLCONF".to_string().into()),
+                Frame::BulkString("GETACK".to_string().into()),
+                Frame::BulkString("*".to_string().into()),
+            ]);
+            inner.send(getack.as_bytes(), backlog_size);
+        }
+    }
+
+    pub fn remove_replica(&self, id: usize) {
+        self.inner.lock().unwrap().replicas.retain(|(replica_id, _)| *replica_id != id);
+    }
+
+    /// 定期向从节点发送 PING，使从节点在没有写命令时也能确认连接可用
+    pub fn start_ping(self: &Arc<Self>) {
+        let master = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PING_PERIOD);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let backlog_size = master.config.values().repl_backlog_size;
+                let mut inner = master.inner.lock().unwrap();
+                if !inner.replicas.is_empty() {
+                    let ping = Frame::Array(vec![Frame::BulkString("PING".to_string().into())]);
+                    inner.send(ping.as_bytes(), backlog_size);
+                }
+            }
+        });
+    }
+}
+
+impl Inner {
+
+    /// 写入积压缓冲区并发送到所有从节点，已断开的从节点被移除
+    fn send(&mut self, bytes: Vec<u8>, backlog_size: usize) {
+        self.offset += bytes.len() as u64;
+        if let Some(backlog) = self.backlog.as_mut() {
+            backlog.extend(&bytes);
+            if backlog.len() > backlog_size {
+                backlog.drain(..backlog.len() - backlog_size);
+            }
+        }
+        self.replicas.retain(|(_, sender)| sender.send(bytes.clone()).is_ok());
+    }
+}
@@ -0,0 +1,237 @@
+pub mod client;
+pub mod command;
+pub mod instance;
+pub mod monitor;
+
+use std::{collections::{BTreeMap, HashSet}, sync::{Arc, Mutex, MutexGuard}, time::Duration};
+
+use anyhow::Error;
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{args::Args, frame::Frame, network::connection::Connection, store::memory::random_u64};
+use client::Client;
+use command::SentinelCommand;
+use instance::{Addr, MasterInstance, MonitorConfig};
+
+/// hello 消息使用的频道，哨兵通过它互相发现并传播最新的主节点配置
+pub const HELLO_CHANNEL: &str = "__sentinel__:hello";
+
+/**
+ * 哨兵模式（--sentinel）
+ *
+ * 与 Redis Sentinel 相同：定期向主从节点发送 PING 与 INFO replication，发现从节点；
+ * 通过数据节点上的 __sentinel__:hello 频道互相发现；
+ * 主节点超过 down-after-milliseconds 没有有效回复时主观下线，
+ * 通过 SENTINEL is-master-down-by-addr 询问其他哨兵，同意的数量达到 quorum 时客观下线；
+ * 之后在新的纪元中选举领头哨兵，由领头哨兵将最合适的从节点提升为主节点（REPLICAOF NO ONE），
+ * 并将其余从节点指向新的主节点。新的配置带着纪元通过 hello 消息传播给其他哨兵
+ *
+ * @param my_id 运行 ID，40 位十六进制字符串，每次启动重新生成
+ */
+pub struct Sentinel {
+    args: Arc<Args>,
+    port: u16,
+    state: Mutex<SentinelState>,
+}
+
+/**
+ * 哨兵的状态
+ *
+ * @param current_epoch 当前纪元，发起故障转移时加一，收到更大的纪元时更新
+ * @param links 已经启动监控任务的实例：(主节点名称, 实例地址)
+ */
+pub struct SentinelState {
+    pub my_id: String,
+    pub current_epoch: u64,
+    pub masters: BTreeMap<String, MasterInstance>,
+    links: HashSet<(String, Addr)>,
+}
+
+impl Sentinel {
+
+    pub fn new(args: Arc<Args>) -> Result<Self, String> {
+        let port = args.port.parse().map_err(|_| format!("Invalid port: {}", args.port))?;
+        let down_after = Duration::from_millis(args.sentinel_down_after_milliseconds);
+        let failover_timeout = Duration::from_millis(args.sentinel_failover_timeout);
+        let mut masters = BTreeMap::new();
+        for monitor in &args.sentinel_monitor {
+            let config: MonitorConfig = monitor.parse()?;
+            masters.insert(config.name.clone(), MasterInstance::new(config, down_after, failover_timeout));
+        }
+        if masters.is_empty() {
+            return Err("Sentinel mode requires at least one --sentinel-monitor".to_string());
+        }
+        let my_id = format!("{:016x}{:016x}{:08x}", random_u64(), random_u64(), random_u64() as u32);
+        Ok(Sentinel {
+            args,
+            port,
+            state: Mutex::new(SentinelState { my_id, current_epoch: 0, masters, links: HashSet::new() }),
+        })
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, SentinelState> {
+        self.state.lock().unwrap()
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub async fn start(self: Arc<Self>) {
+        match TcpListener::bind(format!("{}:{}", self.args.bind, self.args.port)).await {
+            Ok(listener) => {
+                log::info!("Sentinel ID is {}", self.state().my_id);
+                for master in self.state().masters.values() {
+                    log::info!("+monitor master {} {}:{} quorum {}", master.name, master.addr.0, master.addr.1, master.quorum);
+                }
+                monitor::start(self.clone());
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, _address)) => {
+                            let sentinel = self.clone();
+                            tokio::spawn(async move {
+                                sentinel.handle(stream).await;
+                            });
+                        }
+                        Err(e) => {
+                            log::error!("Failed to accept connection: {}", e);
+                        }
+                    }
+                }
+            }
+            Err(_e) => {
+                log::error!("Failed to bind to address {}:{}", self.args.bind, self.args.port);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// 处理客户端连接：只支持哨兵相关的命令
+    async fn handle(&self, stream: TcpStream) {
+        let connection = Connection::new(stream, self.args.proto_max_bulk_len);
+        loop {
+            let frame = match connection.read_frame().await {
+                Ok(Some(frame)) => frame,
+                Ok(None) => return,
+                Err(e) => {
+                    connection.write_frame(&Frame::Error(format!("ERR Protocol error: {}", e))).await;
+                    return;
+                }
+            };
+            let reply = match SentinelCommand::parse_from_frame(frame) {
+                Ok(command) => command.apply(self),
+                Err(e) => Frame::Error(e.to_string()),
+            };
+            connection.write_frame(&reply).await;
+        }
+    }
+
+    /**
+     * 连接被监控的主从节点，配置了 sentinel-auth-pass 时先发送 AUTH
+     *
+     * 其他哨兵不需要认证，直接使用 Client::connect
+     *
+     * @param addr 实例地址
+     */
+    pub async fn connect_instance(&self, addr: &Addr) -> anyhow::Result<Client> {
+        let mut client = Client::connect(&addr.0, addr.1).await?;
+        if let Some(pass) = &self.args.sentinel_auth_pass {
+            let reply = match &self.args.sentinel_auth_user {
+                Some(user) => client.query(&["AUTH", user, pass]).await?,
+                None => client.query(&["AUTH", pass]).await?,
+            };
+            if let Frame::Error(e) = reply {
+                return Err(Error::msg(format!("AUTH to {}:{} failed: {}", addr.0, addr.1, e)));
+            }
+        }
+        Ok(client)
+    }
+
+    /**
+     * 生成发布到数据节点的 hello 消息
+     *
+     * 格式与 Redis 相同：ip,port,runid,current_epoch,master_name,master_ip,master_port,master_config_epoch
+     *
+     * @param master 主节点名称
+     * @param ip 本哨兵的地址（连接该数据节点使用的本端地址）
+     */
+    pub fn hello(&self, master: &str, ip: &str) -> Option<String> {
+        let state = self.state();
+        let instance = state.masters.get(master)?;
+        Some(format!(
+            "{},{},{},{},{},{},{},{}",
+            ip, self.port, state.my_id, state.current_epoch,
+            instance.name, instance.addr.0, instance.addr.1, instance.config_epoch,
+        ))
+    }
+
+    /**
+     * 处理其他哨兵的 hello 消息
+     *
+     * 记录发送者；消息中的主节点配置纪元更大时采用其中的主节点地址（其他哨兵完成了故障转移）
+     *
+     * @param message hello 消息
+     */
+    pub fn process_hello(&self, message: &str) {
+        let parts: Vec<&str> = message.split(',').collect();
+        if parts.len() != 8 {
+            return;
+        }
+        let (Ok(port), Ok(current_epoch), Ok(master_port), Ok(config_epoch)) =
+            (parts[1].parse::<u16>(), parts[3].parse::<u64>(), parts[6].parse::<u16>(), parts[7].parse::<u64>()) else {
+            return;
+        };
+        let mut state = self.state();
+        if parts[2] == state.my_id {
+            return;
+        }
+        if current_epoch > state.current_epoch {
+            state.current_epoch = current_epoch;
+            log::info!("+new-epoch {}", current_epoch);
+        }
+        let Some(master) = state.masters.get_mut(parts[4]) else {
+            return;
+        };
+        master.add_sentinel(parts[2], parts[0], port);
+        if config_epoch > master.config_epoch {
+            let addr = (parts[5].to_string(), master_port);
+            if addr != master.addr {
+                log::info!("+config-update-from sentinel {} {}:{}", parts[2], parts[0], port);
+                master.switch_master(addr, config_epoch);
+            } else {
+                master.config_epoch = config_epoch;
+            }
+        }
+    }
+
+    /**
+     * 检查监控任务是否仍然需要，不再需要时（实例已不属于该主节点）注销
+     *
+     * @return 实例仍被监控时返回主节点的 INFO 刷新间隔
+     */
+    pub fn link_info_period(&self, master: &str, addr: &Addr) -> Option<Duration> {
+        let mut state = self.state();
+        match state.masters.get(master) {
+            Some(instance) if instance.contains(addr) => Some(instance.info_period()),
+            _ => {
+                state.links.remove(&(master.to_string(), addr.clone()));
+                None
+            }
+        }
+    }
+}
+
+impl SentinelState {
+
+    /// 返回尚未启动监控任务的实例，并记录为已启动
+    pub fn take_new_links(&mut self) -> Vec<(String, Addr)> {
+        let wanted: Vec<(String, Addr)> = self.masters.values()
+            .flat_map(|master| {
+                std::iter::once(master.addr.clone())
+                    .chain(master.replicas.keys().cloned())
+                    .map(|addr| (master.name.clone(), addr))
+            })
+            .collect();
+        wanted.into_iter().filter(|link| self.links.insert(link.clone())).collect()
+    }
+}
@@ -0,0 +1,82 @@
+use std::{net::IpAddr, time::Duration};
+
+use anyhow::{Error, Result};
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpStream, time::timeout};
+
+use crate::frame::Frame;
+
+/// 连接与等待回复的超时时间
+const IO_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 回复中单个批量字符串的最大长度（INFO 回复较大，这里只需要防止异常数据）
+const MAX_BULK_LEN: usize = 64 * 1024 * 1024;
+
+/**
+ * 哨兵访问数据节点与其他哨兵使用的 RESP 客户端
+ *
+ * @param buffer 已读取但尚未解析的数据
+ */
+pub struct Client {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+}
+
+impl Client {
+
+    pub async fn connect(host: &str, port: u16) -> Result<Self> {
+        let stream = timeout(IO_TIMEOUT, TcpStream::connect((host, port))).await
+            .map_err(|_| Error::msg(format!("Connect to {}:{} timed out", host, port)))??;
+        Ok(Client { stream, buffer: Vec::new() })
+    }
+
+    /// 本端地址，哨兵在 hello 消息中以此作为自己的地址
+    pub fn local_ip(&self) -> Option<IpAddr> {
+        self.stream.local_addr().ok().map(|addr| addr.ip())
+    }
+
+    /**
+     * 发送命令并等待回复
+     *
+     * @param args 命令与参数
+     * @return 回复，超时或连接断开时返回错误
+     */
+    pub async fn query(&mut self, args: &[&str]) -> Result<Frame> {
+        self.send(args).await?;
+        timeout(IO_TIMEOUT, self.read_frame()).await
+            .map_err(|_| Error::msg("Reply timed out"))?
+    }
+
+    pub async fn send(&mut self, args: &[&str]) -> Result<()> {
+        let frame = Frame::Array(args.iter().map(|arg| Frame::BulkString(arg.as_bytes().to_vec())).collect());
+        timeout(IO_TIMEOUT, self.stream.write_all(&frame.as_bytes())).await
+            .map_err(|_| Error::msg("Write timed out"))??;
+        Ok(())
+    }
+
+    /// 读取下一个帧（订阅连接上没有超时）
+    pub async fn read_frame(&mut self) -> Result<Frame> {
+        loop {
+            if let Some((frame, used)) = Frame::decode(&self.buffer, MAX_BULK_LEN)? {
+                self.buffer.drain(..used);
+                return Ok(frame);
+            }
+            let mut bytes = [0u8; 16 * 1024];
+            let n = self.stream.read(&mut bytes).await?;
+            if n == 0 {
+                return Err(Error::msg("Connection closed"));
+            }
+            self.buffer.extend_from_slice(&bytes[..n]);
+        }
+    }
+}
+
+/**
+ * 建立临时连接发送一条命令
+ *
+ * @param host 地址
+ * @param port 端口
+ * @param args 命令与参数
+ */
+pub async fn query_once(host: &str, port: u16, args: &[&str]) -> Result<Frame> {
+    Client::connect(host, port).await?.query(args).await
+}
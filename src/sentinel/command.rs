@@ -0,0 +1,249 @@
+use std::time::Instant;
+
+use anyhow::Error;
+
+use crate::frame::Frame;
+use super::{instance::{FailoverState, MasterInstance}, Sentinel};
+
+/// 哨兵支持的命令
+pub enum SentinelCommand {
+    Ping,
+    Info,
+    /// CLIENT SETINFO 等客户端库连接时发送的命令，直接回复 OK
+    Client,
+    Sentinel(SentinelSubcommand),
+}
+
+pub enum SentinelSubcommand {
+    Myid,
+    Masters,
+    Master(String),
+    Replicas(String),
+    Sentinels(String),
+    GetMasterAddrByName(String),
+    IsMasterDownByAddr { host: String, port: u16, epoch: u64, run_id: String },
+    Failover(String),
+}
+
+impl SentinelCommand {
+
+    pub fn parse_from_frame(frame: Frame) -> Result<Self, Error> {
+        let args = frame.get_args();
+        let name = args.first().map(|name| name.to_uppercase()).unwrap_or_default();
+        match name.as_str() {
+            "PING" => Ok(SentinelCommand::Ping),
+            "INFO" => Ok(SentinelCommand::Info),
+            "CLIENT" => Ok(SentinelCommand::Client),
+            "SENTINEL" => SentinelSubcommand::parse(&args[1..]).map(SentinelCommand::Sentinel),
+            _ => Err(Error::msg(format!("ERR unknown command '{}', with args beginning with: ", args.first().cloned().unwrap_or_default()))),
+        }
+    }
+
+    pub fn apply(self, sentinel: &Sentinel) -> Frame {
+        match self {
+            SentinelCommand::Ping => Frame::SimpleString("PONG".to_string()),
+            SentinelCommand::Client => Frame::Ok,
+            SentinelCommand::Info => Frame::BulkString(info(sentinel).into()),
+            SentinelCommand::Sentinel(subcommand) => subcommand.apply(sentinel),
+        }
+    }
+}
+
+impl SentinelSubcommand {
+
+    fn parse(args: &[String]) -> Result<Self, Error> {
+        let subcommand = args.first().map(|name| name.to_lowercase()).unwrap_or_default();
+        let arity = match subcommand.as_str() {
+            "myid" | "masters" => 1,
+            "master" | "replicas" | "slaves" | "sentinels" | "get-master-addr-by-name" | "failover" => 2,
+            "is-master-down-by-addr" => 5,
+            _ => return Err(Error::msg(format!("ERR Unknown sentinel subcommand '{}'", args.first().cloned().unwrap_or_default()))),
+        };
+        if args.len() != arity {
+            return Err(Error::msg(format!("ERR wrong number of arguments for 'sentinel|{}' command", subcommand)));
+        }
+        let name = || args[1].clone();
+        Ok(match subcommand.as_str() {
+            "myid" => SentinelSubcommand::Myid,
+            "masters" => SentinelSubcommand::Masters,
+            "master" => SentinelSubcommand::Master(name()),
+            "replicas" | "slaves" => SentinelSubcommand::Replicas(name()),
+            "sentinels" => SentinelSubcommand::Sentinels(name()),
+            "get-master-addr-by-name" => SentinelSubcommand::GetMasterAddrByName(name()),
+            "failover" => SentinelSubcommand::Failover(name()),
+            _ => {
+                let port = args[2].parse().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+                let epoch = args[3].parse().map_err(|_| Error::msg("ERR value is not an integer or out of range"))?;
+                SentinelSubcommand::IsMasterDownByAddr { host: args[1].clone(), port, epoch, run_id: args[4].clone() }
+            },
+        })
+    }
+
+    fn apply(self, sentinel: &Sentinel) -> Frame {
+        let mut state = sentinel.state();
+        let no_such_master = || Frame::Error("ERR No such master with that name".to_string());
+        match self {
+            SentinelSubcommand::Myid => Frame::BulkString(state.my_id.clone().into()),
+            SentinelSubcommand::Masters => Frame::Array(state.masters.values().map(master_fields).collect()),
+            SentinelSubcommand::Master(name) => match state.masters.get(&name) {
+                Some(master) => master_fields(master),
+                None => no_such_master(),
+            },
+            SentinelSubcommand::Replicas(name) => match state.masters.get(&name) {
+                Some(master) => Frame::Array(master.replicas.iter().map(|(addr, replica)| {
+                    let mut flags = vec!["slave"];
+                    if replica.link.unavailable_for() > master.down_after {
+                        flags.push("s_down");
+                    }
+                    fields(vec![
+                        ("name", format!("{}:{}", addr.0, addr.1)),
+                        ("ip", addr.0.clone()),
+                        ("port", addr.1.to_string()),
+                        ("flags", flags.join(",")),
+                        ("last-ok-ping-reply", millis_since(replica.link.last_ok_ping)),
+                        ("info-refresh", millis_since(replica.link.last_info)),
+                        ("role-reported", if replica.role_master { "master" } else { "slave" }.to_string()),
+                        ("master-link-status", if replica.master_link_up { "ok" } else { "err" }.to_string()),
+                        ("master-host", replica.master_host.clone().unwrap_or_else(|| "?".to_string())),
+                        ("master-port", replica.master_port.map_or("0".to_string(), |port| port.to_string())),
+                        ("slave-priority", replica.priority.to_string()),
+                        ("slave-repl-offset", replica.repl_offset.to_string()),
+                    ])
+                }).collect()),
+                None => no_such_master(),
+            },
+            SentinelSubcommand::Sentinels(name) => match state.masters.get(&name) {
+                Some(master) => Frame::Array(master.sentinels.iter().map(|(run_id, peer)| {
+                    fields(vec![
+                        ("name", run_id.clone()),
+                        ("ip", peer.ip.clone()),
+                        ("port", peer.port.to_string()),
+                        ("runid", run_id.clone()),
+                        ("flags", "sentinel".to_string()),
+                        ("last-hello-message", millis_since(Some(peer.last_hello))),
+                        ("voted-leader", peer.leader.clone().unwrap_or_else(|| "?".to_string())),
+                        ("voted-leader-epoch", peer.leader_epoch.to_string()),
+                    ])
+                }).collect()),
+                None => no_such_master(),
+            },
+            SentinelSubcommand::GetMasterAddrByName(name) => match state.masters.get(&name) {
+                Some(master) => Frame::Array(vec![
+                    Frame::BulkString(master.addr.0.clone().into()),
+                    Frame::BulkString(master.addr.1.to_string().into()),
+                ]),
+                None => Frame::NullArray,
+            },
+            SentinelSubcommand::IsMasterDownByAddr { host, port, epoch, run_id } => {
+                // 请求方带有运行 ID 时表示请求投票，"*" 只询问主节点状态
+                let my_id = state.my_id.clone();
+                let mut current_epoch = state.current_epoch;
+                let voting = run_id != "*";
+                if voting && epoch > current_epoch {
+                    current_epoch = epoch;
+                    state.current_epoch = epoch;
+                    log::info!("+new-epoch {}", epoch);
+                }
+                let addr = (host, port);
+                let (down, (leader, leader_epoch)) = match state.masters.values_mut().find(|master| master.addr == addr) {
+                    Some(master) => {
+                        let down = master.s_down_since.is_some();
+                        let vote = if voting { master.vote_leader(epoch, &run_id, current_epoch, &my_id) } else { (None, 0) };
+                        (down, vote)
+                    },
+                    None => (false, (None, 0)),
+                };
+                Frame::Array(vec![
+                    Frame::Integer(down as i64),
+                    Frame::BulkString(leader.unwrap_or_else(|| "*".to_string()).into()),
+                    Frame::Integer(leader_epoch as i64),
+                ])
+            },
+            SentinelSubcommand::Failover(name) => {
+                let epoch = state.current_epoch + 1;
+                let my_id = state.my_id.clone();
+                let Some(master) = state.masters.get_mut(&name) else {
+                    return no_such_master();
+                };
+                if master.in_failover() {
+                    return Frame::Error("INPROG Failover already in progress".to_string());
+                }
+                if master.select_replica().is_none() {
+                    return Frame::Error("NOGOODSLAVE No suitable replica to promote".to_string());
+                }
+                master.start_failover(epoch, &my_id, true);
+                state.current_epoch = epoch;
+                Frame::Ok
+            },
+        }
+    }
+}
+
+/// 主节点的状态（SENTINEL MASTERS / SENTINEL MASTER）
+fn master_fields(master: &MasterInstance) -> Frame {
+    let mut flags = vec!["master"];
+    if master.s_down_since.is_some() {
+        flags.push("s_down");
+    }
+    if master.o_down_since.is_some() {
+        flags.push("o_down");
+    }
+    if master.in_failover() {
+        flags.push("failover_in_progress");
+    }
+    let failover_state = match &master.failover {
+        FailoverState::None => "none",
+        FailoverState::WaitStart => "wait_start",
+        FailoverState::WaitPromotion(_, _) => "wait_promotion",
+    };
+    fields(vec![
+        ("name", master.name.clone()),
+        ("ip", master.addr.0.clone()),
+        ("port", master.addr.1.to_string()),
+        ("flags", flags.join(",")),
+        ("last-ok-ping-reply", millis_since(master.link.last_ok_ping)),
+        ("info-refresh", millis_since(master.link.last_info)),
+        ("num-slaves", master.replicas.len().to_string()),
+        ("num-other-sentinels", master.sentinels.len().to_string()),
+        ("quorum", master.quorum.to_string()),
+        ("down-after-milliseconds", master.down_after.as_millis().to_string()),
+        ("failover-timeout", master.failover_timeout.as_millis().to_string()),
+        ("config-epoch", master.config_epoch.to_string()),
+        ("failover-state", failover_state.to_string()),
+    ])
+}
+
+/// 以 [字段, 值, 字段, 值, ...] 的形式回复，与 Redis Sentinel 相同
+fn fields(pairs: Vec<(&str, String)>) -> Frame {
+    Frame::Array(pairs.into_iter()
+        .flat_map(|(key, value)| [Frame::BulkString(key.as_bytes().to_vec()), Frame::BulkString(value.into())])
+        .collect())
+}
+
+/// 距某个时刻的毫秒数，从未发生时为 -1
+fn millis_since(instant: Option<Instant>) -> String {
+    instant.map_or("-1".to_string(), |instant| instant.elapsed().as_millis().to_string())
+}
+
+/// 哨兵的 INFO：Server 与 Sentinel 两节
+fn info(sentinel: &Sentinel) -> String {
+    let state = sentinel.state();
+    let mut info = String::new();
+    info.push_str("# Server\r\n");
+    info.push_str(&format!("redis_version:{}\r\n", env!("CARGO_PKG_VERSION")));
+    info.push_str("redis_mode:sentinel\r\n");
+    info.push_str(&format!("process_id:{}\r\n", std::process::id()));
+    info.push_str(&format!("run_id:{}\r\n", state.my_id));
+    info.push_str(&format!("tcp_port:{}\r\n\r\n", sentinel.port()));
+    info.push_str("# Sentinel\r\n");
+    info.push_str(&format!("sentinel_masters:{}\r\n", state.masters.len()));
+    info.push_str(&format!("sentinel_current_epoch:{}\r\n", state.current_epoch));
+    for (index, master) in state.masters.values().enumerate() {
+        let status = if master.o_down_since.is_some() { "odown" } else if master.s_down_since.is_some() { "sdown" } else { "ok" };
+        info.push_str(&format!(
+            "master{}:name={},status={},address={}:{},slaves={},sentinels={}\r\n",
+            index, master.name, status, master.addr.0, master.addr.1, master.replicas.len(), master.sentinels.len() + 1,
+        ));
+    }
+    info
+}
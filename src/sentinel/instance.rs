@@ -0,0 +1,496 @@
+use std::{collections::{BTreeMap, HashMap}, str::FromStr, time::{Duration, Instant}};
+
+use crate::store::memory::random_u64;
+
+/// 实例地址（主机, 端口）
+pub type Addr = (String, u16);
+
+/// 主节点下线或故障转移期间，INFO 的刷新间隔
+pub const INFO_PERIOD_FAST: Duration = Duration::from_secs(1);
+
+/// 正常情况下 INFO 的刷新间隔
+pub const INFO_PERIOD: Duration = Duration::from_secs(10);
+
+/// 其他哨兵的主观下线回复的有效期
+const DOWN_REPLY_VALIDITY: Duration = Duration::from_secs(5);
+
+/// 选举的最长时间（不超过故障转移超时）
+const ELECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 配置变更后，等待该时间再纠正指向错误主节点的从节点，避免与其他哨兵的故障转移冲突
+const RECONF_GRACE: Duration = Duration::from_secs(4);
+
+/// 两次纠正同一从节点之间的最小间隔
+const RECONF_PERIOD: Duration = Duration::from_secs(10);
+
+/// 主节点进入客观下线后开始故障转移前的随机延迟上限，降低多个哨兵同时发起选举的概率
+const MAX_FAILOVER_DELAY_MS: u64 = 1000;
+
+/**
+ * 监控配置：sentinel-monitor "<name> <host> <port> <quorum>"
+ *
+ * @param quorum 判定主节点客观下线需要同意的哨兵数量
+ */
+pub struct MonitorConfig {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub quorum: usize,
+}
+
+impl FromStr for MonitorConfig {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        if parts.len() != 4 {
+            return Err(format!("Invalid sentinel monitor '{}', expected '<name> <host> <port> <quorum>'", s));
+        }
+        let port = parts[2].parse().map_err(|_| format!("Invalid sentinel monitor port: {}", parts[2]))?;
+        let quorum = match parts[3].parse() {
+            Ok(quorum) if quorum > 0 => quorum,
+            _ => return Err(format!("Invalid sentinel monitor quorum: {}", parts[3])),
+        };
+        Ok(MonitorConfig { name: parts[0].to_string(), host: parts[1].to_string(), port, quorum })
+    }
+}
+
+/**
+ * 与实例之间的连接状态
+ *
+ * @param ping_pending_since 最早一个尚未收到有效回复的 PING 的发送时间（连接失败同样计入）
+ */
+pub struct InstanceLink {
+    ping_pending_since: Option<Instant>,
+    pub last_ok_ping: Option<Instant>,
+    pub last_info: Option<Instant>,
+}
+
+impl InstanceLink {
+
+    fn new() -> Self {
+        InstanceLink { ping_pending_since: None, last_ok_ping: None, last_info: None }
+    }
+
+    pub fn ping_sent(&mut self) {
+        self.ping_pending_since.get_or_insert_with(Instant::now);
+    }
+
+    pub fn ping_ok(&mut self) {
+        self.ping_pending_since = None;
+        self.last_ok_ping = Some(Instant::now());
+    }
+
+    /// 等待有效回复的时间，超过 down-after-milliseconds 即主观下线
+    pub fn unavailable_for(&self) -> Duration {
+        self.ping_pending_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+}
+
+/// 故障转移的阶段
+#[derive(Clone, PartialEq, Eq)]
+pub enum FailoverState {
+    None,
+    /// 等待选举结果
+    WaitStart,
+    /// 已向选中的从节点发送 REPLICAOF NO ONE，等待其 INFO 报告 role:master
+    WaitPromotion(Addr, Instant),
+}
+
+/// 主节点的从节点
+pub struct ReplicaInstance {
+    pub link: InstanceLink,
+    pub role_master: bool,
+    pub master_host: Option<String>,
+    pub master_port: Option<u16>,
+    pub master_link_up: bool,
+    pub priority: u64,
+    pub repl_offset: u64,
+    last_reconf: Option<Instant>,
+}
+
+impl ReplicaInstance {
+
+    fn new() -> Self {
+        ReplicaInstance {
+            link: InstanceLink::new(),
+            role_master: false,
+            master_host: None,
+            master_port: None,
+            master_link_up: false,
+            priority: 100,
+            repl_offset: 0,
+            last_reconf: None,
+        }
+    }
+}
+
+/**
+ * 监控同一主节点的其他哨兵（通过 hello 消息发现）
+ *
+ * @param master_down 最近一次报告主节点主观下线的时间
+ * @param leader 该哨兵在 leader_epoch 纪元投票选出的领头哨兵
+ */
+pub struct SentinelPeer {
+    pub ip: String,
+    pub port: u16,
+    pub last_hello: Instant,
+    pub master_down: Option<Instant>,
+    pub leader: Option<String>,
+    pub leader_epoch: u64,
+}
+
+/**
+ * 被监控的主节点
+ *
+ * @param config_epoch 当前配置（主节点地址）对应的纪元，故障转移成功后更新为发起故障转移的纪元
+ * @param leader 本哨兵在 leader_epoch 纪元投票选出的领头哨兵
+ * @param failover_start 最近一次发起故障转移（或投票给其他哨兵）的时间
+ */
+pub struct MasterInstance {
+    pub name: String,
+    pub addr: Addr,
+    pub quorum: usize,
+    pub down_after: Duration,
+    pub failover_timeout: Duration,
+    pub config_epoch: u64,
+    pub link: InstanceLink,
+    pub s_down_since: Option<Instant>,
+    pub o_down_since: Option<Instant>,
+    failover_delay: Duration,
+    pub replicas: BTreeMap<Addr, ReplicaInstance>,
+    pub sentinels: BTreeMap<String, SentinelPeer>,
+    pub leader: Option<String>,
+    pub leader_epoch: u64,
+    pub failover: FailoverState,
+    pub failover_epoch: u64,
+    pub failover_start: Option<Instant>,
+    pub failover_forced: bool,
+    pub last_ask: Option<Instant>,
+    last_config_change: Instant,
+}
+
+impl MasterInstance {
+
+    pub fn new(config: MonitorConfig, down_after: Duration, failover_timeout: Duration) -> Self {
+        MasterInstance {
+            name: config.name,
+            addr: (config.host, config.port),
+            quorum: config.quorum,
+            down_after,
+            failover_timeout,
+            config_epoch: 0,
+            link: InstanceLink::new(),
+            s_down_since: None,
+            o_down_since: None,
+            failover_delay: Duration::ZERO,
+            replicas: BTreeMap::new(),
+            sentinels: BTreeMap::new(),
+            leader: None,
+            leader_epoch: 0,
+            failover: FailoverState::None,
+            failover_epoch: 0,
+            failover_start: None,
+            failover_forced: false,
+            last_ask: None,
+            last_config_change: Instant::now(),
+        }
+    }
+
+    /// 实例是否属于该主节点（主节点本身或其从节点）
+    pub fn contains(&self, addr: &Addr) -> bool {
+        self.addr == *addr || self.replicas.contains_key(addr)
+    }
+
+    pub fn link_mut(&mut self, addr: &Addr) -> Option<&mut InstanceLink> {
+        if self.addr == *addr {
+            return Some(&mut self.link);
+        }
+        self.replicas.get_mut(addr).map(|replica| &mut replica.link)
+    }
+
+    pub fn in_failover(&self) -> bool {
+        self.failover != FailoverState::None
+    }
+
+    /// INFO 的刷新间隔：主节点下线或故障转移期间加快刷新，以便及时选择从节点
+    pub fn info_period(&self) -> Duration {
+        if self.s_down_since.is_some() || self.in_failover() { INFO_PERIOD_FAST } else { INFO_PERIOD }
+    }
+
+    /**
+     * 更新主观下线与客观下线状态
+     *
+     * 超过 down-after-milliseconds 没有有效回复为主观下线；
+     * 主观下线且同意的哨兵数量（包括自己）达到 quorum 为客观下线
+     */
+    pub fn update_down_state(&mut self) {
+        if self.link.unavailable_for() <= self.down_after {
+            if self.s_down_since.take().is_some() {
+                log::info!("-sdown master {} {}:{}", self.name, self.addr.0, self.addr.1);
+            }
+            self.o_down_since = None;
+            for peer in self.sentinels.values_mut() {
+                peer.master_down = None;
+            }
+            return;
+        }
+        if self.s_down_since.is_none() {
+            log::warn!("+sdown master {} {}:{}", self.name, self.addr.0, self.addr.1);
+            self.s_down_since = Some(Instant::now());
+        }
+        let agreed = 1 + self.sentinels.values()
+            .filter(|peer| peer.master_down.is_some_and(|at| at.elapsed() <= DOWN_REPLY_VALIDITY))
+            .count();
+        if agreed >= self.quorum {
+            if self.o_down_since.is_none() {
+                log::warn!("+odown master {} {}:{} #quorum {}/{}", self.name, self.addr.0, self.addr.1, agreed, self.quorum);
+                self.o_down_since = Some(Instant::now());
+                self.failover_delay = Duration::from_millis(random_u64() % MAX_FAILOVER_DELAY_MS);
+            }
+        } else if self.o_down_since.take().is_some() {
+            log::info!("-odown master {} {}:{}", self.name, self.addr.0, self.addr.1);
+        }
+    }
+
+    /// 是否可以发起故障转移：客观下线一段随机时间，且距上次尝试超过两倍故障转移超时
+    pub fn should_start_failover(&self) -> bool {
+        if self.in_failover() {
+            return false;
+        }
+        let o_down = self.o_down_since.is_some_and(|since| since.elapsed() >= self.failover_delay);
+        o_down && self.failover_start.is_none_or(|start| start.elapsed() > self.failover_timeout * 2)
+    }
+
+    /**
+     * 发起故障转移：进入新的纪元并投票给自己
+     *
+     * @param epoch 新的纪元
+     * @param my_id 本哨兵的运行 ID
+     * @param forced SENTINEL FAILOVER 强制发起，不需要其他哨兵同意
+     */
+    pub fn start_failover(&mut self, epoch: u64, my_id: &str, forced: bool) {
+        log::warn!("+try-failover master {} {}:{} epoch {}", self.name, self.addr.0, self.addr.1, epoch);
+        self.failover = FailoverState::WaitStart;
+        self.failover_epoch = epoch;
+        self.failover_start = Some(Instant::now());
+        self.failover_forced = forced;
+        self.leader = Some(my_id.to_string());
+        self.leader_epoch = epoch;
+    }
+
+    pub fn abort_failover(&mut self, reason: &str) {
+        log::warn!("-failover-abort-{} master {} {}:{}", reason, self.name, self.addr.0, self.addr.1);
+        self.failover = FailoverState::None;
+        self.failover_forced = false;
+    }
+
+    /// 选举是否超时
+    pub fn election_timed_out(&self) -> bool {
+        self.failover_start.is_some_and(|start| start.elapsed() > self.failover_timeout.min(ELECTION_TIMEOUT))
+    }
+
+    /**
+     * 为请求投票的哨兵投票（SENTINEL is-master-down-by-addr）
+     *
+     * 每个纪元只投一票，投给最先请求的哨兵；投票给其他哨兵后推迟自己发起故障转移
+     *
+     * @param req_epoch 请求方的纪元
+     * @param req_id 请求方的运行 ID
+     * @param current_epoch 本哨兵的当前纪元
+     * @param my_id 本哨兵的运行 ID
+     * @return 本哨兵在该纪元投票的领头哨兵与纪元
+     */
+    pub fn vote_leader(&mut self, req_epoch: u64, req_id: &str, current_epoch: u64, my_id: &str) -> (Option<String>, u64) {
+        if self.leader_epoch < req_epoch && current_epoch <= req_epoch {
+            self.leader = Some(req_id.to_string());
+            self.leader_epoch = current_epoch;
+            log::info!("+vote-for-leader {} {}", req_id, current_epoch);
+            if req_id != my_id {
+                self.failover_start = Some(Instant::now());
+            }
+        }
+        (self.leader.clone(), self.leader_epoch)
+    }
+
+    /**
+     * 统计故障转移纪元内的投票，返回获胜的哨兵
+     *
+     * 获胜者的票数需要超过所有哨兵的半数，同时不少于 quorum
+     */
+    pub fn election_winner(&self) -> Option<String> {
+        let mut votes: HashMap<&str, usize> = HashMap::new();
+        if self.leader_epoch == self.failover_epoch {
+            if let Some(leader) = &self.leader {
+                *votes.entry(leader).or_default() += 1;
+            }
+        }
+        for peer in self.sentinels.values() {
+            if peer.leader_epoch == self.failover_epoch {
+                if let Some(leader) = &peer.leader {
+                    *votes.entry(leader).or_default() += 1;
+                }
+            }
+        }
+        let voters = self.sentinels.len() + 1;
+        let needed = self.quorum.max(voters / 2 + 1);
+        votes.into_iter()
+            .filter(|(_, count)| *count >= needed)
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(leader, _)| leader.to_string())
+    }
+
+    /**
+     * 选择提升为主节点的从节点
+     *
+     * 排除下线、INFO 过期以及优先级为 0 的从节点，
+     * 然后依次按优先级（小者优先）、复制偏移量（大者优先）、地址排序
+     */
+    pub fn select_replica(&self) -> Option<Addr> {
+        let info_validity = if self.s_down_since.is_some() { INFO_PERIOD_FAST * 5 } else { INFO_PERIOD * 3 };
+        self.replicas.iter()
+            .filter(|(_, replica)| {
+                replica.link.unavailable_for() <= self.down_after
+                    && replica.link.last_info.is_some_and(|at| at.elapsed() <= info_validity)
+                    && replica.priority != 0
+            })
+            .min_by(|(a_addr, a), (b_addr, b)| {
+                a.priority.cmp(&b.priority)
+                    .then_with(|| b.repl_offset.cmp(&a.repl_offset))
+                    .then_with(|| a_addr.cmp(b_addr))
+            })
+            .map(|(addr, _)| addr.clone())
+    }
+
+    /**
+     * 切换到新的主节点地址
+     *
+     * 原主节点与其余从节点都作为新主节点的从节点继续监控
+     *
+     * @param addr 新的主节点地址
+     * @param config_epoch 新配置的纪元
+     */
+    pub fn switch_master(&mut self, addr: Addr, config_epoch: u64) {
+        log::warn!(
+            "+switch-master {} {} {} {} {}",
+            self.name, self.addr.0, self.addr.1, addr.0, addr.1,
+        );
+        let old = std::mem::replace(&mut self.addr, addr.clone());
+        self.replicas.remove(&addr);
+        if old != addr {
+            self.replicas.insert(old, ReplicaInstance::new());
+        }
+        self.config_epoch = config_epoch;
+        self.link = InstanceLink::new();
+        self.s_down_since = None;
+        self.o_down_since = None;
+        self.failover = FailoverState::None;
+        self.failover_forced = false;
+        for peer in self.sentinels.values_mut() {
+            peer.master_down = None;
+        }
+        self.last_config_change = Instant::now();
+    }
+
+    /**
+     * 根据 INFO replication 更新实例状态
+     *
+     * 主节点的 INFO 用于发现从节点；从节点报告自己是主节点或指向其他主节点时，
+     * 在没有故障转移且主节点正常的情况下返回需要执行的 REPLICAOF
+     *
+     * @param addr 实例地址
+     * @param info INFO replication 的回复
+     * @return 需要将该实例重新指向的主节点
+     */
+    pub fn refresh_info(&mut self, addr: &Addr, info: &str) -> Option<Addr> {
+        let fields = parse_info(info);
+        if *addr == self.addr {
+            self.link.last_info = Some(Instant::now());
+            if fields.get("role").map(String::as_str) == Some("master") {
+                for (key, value) in &fields {
+                    if key.starts_with("slave") && key[5..].parse::<usize>().is_ok() {
+                        if let Some(replica) = parse_slave_line(value) {
+                            self.replicas.entry(replica).or_insert_with(ReplicaInstance::new);
+                        }
+                    }
+                }
+            }
+            return None;
+        }
+        let master_ok = self.s_down_since.is_none() && !self.in_failover()
+            && self.last_config_change.elapsed() > RECONF_GRACE;
+        let master_addr = self.addr.clone();
+        let replica = self.replicas.get_mut(addr)?;
+        replica.link.last_info = Some(Instant::now());
+        replica.role_master = fields.get("role").map(String::as_str) == Some("master");
+        replica.master_host = fields.get("master_host").cloned();
+        replica.master_port = fields.get("master_port").and_then(|port| port.parse().ok());
+        replica.master_link_up = fields.get("master_link_status").map(String::as_str) == Some("up");
+        replica.priority = fields.get("slave_priority").and_then(|priority| priority.parse().ok()).unwrap_or(100);
+        replica.repl_offset = fields.get("slave_repl_offset").and_then(|offset| offset.parse().ok()).unwrap_or(0);
+
+        let misconfigured = replica.role_master
+            || replica.master_host.as_deref() != Some(master_addr.0.as_str())
+            || replica.master_port != Some(master_addr.1);
+        if misconfigured && master_ok && replica.last_reconf.is_none_or(|at| at.elapsed() > RECONF_PERIOD) {
+            replica.last_reconf = Some(Instant::now());
+            log::warn!("+convert-to-slave {}:{} -> {}:{}", addr.0, addr.1, master_addr.0, master_addr.1);
+            return Some(master_addr);
+        }
+        None
+    }
+
+    /// 从节点是否已报告自己为主节点（等待提升完成）
+    pub fn is_promoted(&self, addr: &Addr) -> bool {
+        self.replicas.get(addr).is_some_and(|replica| replica.role_master)
+    }
+
+    /**
+     * 记录其他哨兵的 hello 消息
+     *
+     * 同一地址出现新的运行 ID 时（哨兵重启）替换原来的记录
+     */
+    pub fn add_sentinel(&mut self, run_id: &str, ip: &str, port: u16) {
+        if let Some(peer) = self.sentinels.get_mut(run_id) {
+            peer.ip = ip.to_string();
+            peer.port = port;
+            peer.last_hello = Instant::now();
+            return;
+        }
+        self.sentinels.retain(|_, peer| peer.ip != ip || peer.port != port);
+        log::info!("+sentinel {} {}:{} @ {}", run_id, ip, port, self.name);
+        self.sentinels.insert(run_id.to_string(), SentinelPeer {
+            ip: ip.to_string(),
+            port,
+            last_hello: Instant::now(),
+            master_down: None,
+            leader: None,
+            leader_epoch: 0,
+        });
+    }
+}
+
+/// 解析 INFO 回复为字段表
+pub fn parse_info(info: &str) -> HashMap<String, String> {
+    info.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// 解析主节点 INFO 中的 "slaveN:ip=...,port=...,..." 行
+fn parse_slave_line(value: &str) -> Option<Addr> {
+    let mut ip = None;
+    let mut port = None;
+    for part in value.split(',') {
+        match part.split_once('=') {
+            Some(("ip", value)) => ip = Some(value.to_string()),
+            Some(("port", value)) => port = value.parse().ok(),
+            _ => {}
+        }
+    }
+    match (ip, port) {
+        (Some(ip), Some(port)) if port != 0 => Some((ip, port)),
+        _ => None,
+    }
+}
@@ -0,0 +1,258 @@
+use std::{sync::Arc, time::{Duration, Instant}};
+
+use crate::frame::Frame;
+use super::{client::{query_once, Client}, instance::{Addr, FailoverState}, Sentinel, HELLO_CHANNEL};
+
+/// 定时检查主节点状态、推进故障转移的间隔
+const TIMER_PERIOD: Duration = Duration::from_millis(100);
+
+/// PING 的最大间隔（down-after-milliseconds 更小时以其为准）
+const PING_PERIOD: Duration = Duration::from_secs(1);
+
+/// 发布 hello 消息的间隔
+const HELLO_PERIOD: Duration = Duration::from_secs(2);
+
+/// 主节点主观下线期间询问其他哨兵的间隔
+const ASK_PERIOD: Duration = Duration::from_secs(1);
+
+/// 需要异步执行的操作（在状态锁之外进行网络请求）
+enum Action {
+    /// 询问其他哨兵主节点是否下线，run_id 不为 "*" 时同时请求投票
+    AskSentinels { master: String, addr: Addr, epoch: u64, run_id: String, peers: Vec<(String, String, u16)> },
+    /// 向实例发送 REPLICAOF，master 为 None 时提升为主节点
+    Replicaof { addr: Addr, master: Option<Addr> },
+}
+
+/// 启动定时任务
+pub fn start(sentinel: Arc<Sentinel>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TIMER_PERIOD);
+        loop {
+            interval.tick().await;
+            let (links, actions) = tick(&sentinel);
+            for (master, addr) in links {
+                tokio::spawn(monitor_instance(sentinel.clone(), master, addr));
+            }
+            for action in actions {
+                tokio::spawn(run_action(sentinel.clone(), action));
+            }
+        }
+    });
+}
+
+/**
+ * 检查所有主节点，推进故障转移
+ *
+ * @return 需要启动监控任务的实例，以及需要执行的操作
+ */
+fn tick(sentinel: &Sentinel) -> (Vec<(String, Addr)>, Vec<Action>) {
+    let mut state = sentinel.state();
+    let links = state.take_new_links();
+    let my_id = state.my_id.clone();
+    let mut current_epoch = state.current_epoch;
+    let mut actions = Vec::new();
+
+    for master in state.masters.values_mut() {
+        master.update_down_state();
+
+        // 主观下线期间定期询问其他哨兵，发起故障转移后同时请求投票
+        let asking = master.s_down_since.is_some() && matches!(master.failover, FailoverState::None | FailoverState::WaitStart);
+        if asking && master.last_ask.is_none_or(|at| at.elapsed() >= ASK_PERIOD) && !master.sentinels.is_empty() {
+            master.last_ask = Some(Instant::now());
+            let voting = master.failover == FailoverState::WaitStart;
+            actions.push(Action::AskSentinels {
+                master: master.name.clone(),
+                addr: master.addr.clone(),
+                epoch: if voting { master.failover_epoch } else { current_epoch },
+                run_id: if voting { my_id.clone() } else { "*".to_string() },
+                peers: master.sentinels.iter().map(|(id, peer)| (id.clone(), peer.ip.clone(), peer.port)).collect(),
+            });
+        }
+
+        if master.should_start_failover() {
+            current_epoch += 1;
+            master.start_failover(current_epoch, &my_id, false);
+            // 立即请求投票
+            master.last_ask = None;
+        }
+
+        match master.failover.clone() {
+            FailoverState::None => {},
+            FailoverState::WaitStart => {
+                if !master.failover_forced && master.s_down_since.is_none() {
+                    master.abort_failover("master-up");
+                    continue;
+                }
+                let winner = if master.failover_forced { Some(my_id.clone()) } else { master.election_winner() };
+                if winner.as_deref() == Some(my_id.as_str()) {
+                    log::warn!("+elected-leader master {} {}:{} epoch {}", master.name, master.addr.0, master.addr.1, master.failover_epoch);
+                    match master.select_replica() {
+                        Some(addr) => {
+                            log::warn!("+selected-slave {}:{} @ {}", addr.0, addr.1, master.name);
+                            actions.push(Action::Replicaof { addr: addr.clone(), master: None });
+                            master.failover = FailoverState::WaitPromotion(addr, Instant::now());
+                        },
+                        None => master.abort_failover("no-good-slave"),
+                    }
+                } else if master.election_timed_out() {
+                    master.abort_failover("not-elected");
+                }
+            },
+            FailoverState::WaitPromotion(addr, since) => {
+                if master.is_promoted(&addr) {
+                    log::warn!("+promoted-slave {}:{} @ {}", addr.0, addr.1, master.name);
+                    for replica in master.replicas.keys().filter(|replica| **replica != addr) {
+                        actions.push(Action::Replicaof { addr: replica.clone(), master: Some(addr.clone()) });
+                    }
+                    let epoch = master.failover_epoch;
+                    master.switch_master(addr, epoch);
+                } else if since.elapsed() > master.failover_timeout {
+                    master.abort_failover("timeout");
+                }
+            },
+        }
+    }
+    state.current_epoch = current_epoch;
+    (links, actions)
+}
+
+async fn run_action(sentinel: Arc<Sentinel>, action: Action) {
+    match action {
+        Action::AskSentinels { master, addr, epoch, run_id, peers } => {
+            let port = addr.1.to_string();
+            let epoch = epoch.to_string();
+            for (peer_id, ip, peer_port) in peers {
+                let args = ["SENTINEL", "is-master-down-by-addr", addr.0.as_str(), port.as_str(), epoch.as_str(), run_id.as_str()];
+                let reply = match query_once(&ip, peer_port, &args).await {
+                    Ok(Frame::Array(reply)) if reply.len() == 3 => reply,
+                    _ => continue,
+                };
+                let (Frame::Integer(down), Frame::Integer(leader_epoch)) = (&reply[0], &reply[2]) else {
+                    continue;
+                };
+                let leader = reply[1].to_string();
+                let mut state = sentinel.state();
+                let Some(peer) = state.masters.get_mut(&master).and_then(|master| master.sentinels.get_mut(&peer_id)) else {
+                    continue;
+                };
+                peer.master_down = (*down == 1).then(Instant::now);
+                if leader != "*" {
+                    peer.leader = Some(leader);
+                    peer.leader_epoch = *leader_epoch as u64;
+                }
+            }
+        },
+        Action::Replicaof { addr, master } => {
+            let port = master.as_ref().map(|(_, port)| port.to_string());
+            let args = match (&master, &port) {
+                (Some((host, _)), Some(port)) => ["REPLICAOF", host.as_str(), port.as_str()],
+                _ => ["REPLICAOF", "NO", "ONE"],
+            };
+            let result = match sentinel.connect_instance(&addr).await {
+                Ok(mut client) => client.query(&args).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to send REPLICAOF to {}:{}: {}", addr.0, addr.1, e);
+            }
+        },
+    }
+}
+
+/**
+ * 监控单个实例：定期 PING、刷新 INFO replication、发布 hello 消息，并订阅其他哨兵的 hello 消息
+ *
+ * 实例不再属于该主节点时结束
+ */
+async fn monitor_instance(sentinel: Arc<Sentinel>, master: String, addr: Addr) {
+    let listener = tokio::spawn(listen_hello(sentinel.clone(), addr.clone()));
+    let mut client: Option<Client> = None;
+    let mut last_info: Option<Instant> = None;
+    let mut last_hello: Option<Instant> = None;
+
+    while let Some(info_period) = sentinel.link_info_period(&master, &addr) {
+        let ping_period = {
+            let mut state = sentinel.state();
+            match state.masters.get_mut(&master) {
+                Some(instance) => {
+                    if let Some(link) = instance.link_mut(&addr) {
+                        link.ping_sent();
+                    }
+                    instance.down_after.min(PING_PERIOD)
+                },
+                None => PING_PERIOD,
+            }
+        };
+        if client.is_none() {
+            client = sentinel.connect_instance(&addr).await.ok();
+        }
+        if let Some(connection) = client.as_mut() {
+            if let Err(e) = check_instance(&sentinel, connection, &master, &addr, info_period, &mut last_info, &mut last_hello).await {
+                log::debug!("Sentinel link to {}:{} failed: {}", addr.0, addr.1, e);
+                client = None;
+            }
+        }
+        tokio::time::sleep(ping_period).await;
+    }
+    listener.abort();
+}
+
+async fn check_instance(
+    sentinel: &Sentinel,
+    client: &mut Client,
+    master: &str,
+    addr: &Addr,
+    info_period: Duration,
+    last_info: &mut Option<Instant>,
+    last_hello: &mut Option<Instant>,
+) -> anyhow::Result<()> {
+    // 正在加载数据（LOADING）或与主节点断开（MASTERDOWN）的实例同样视为可用
+    let available = match client.query(&["PING"]).await? {
+        Frame::SimpleString(_) => true,
+        Frame::Error(e) => e.starts_with("LOADING") || e.starts_with("MASTERDOWN"),
+        _ => false,
+    };
+    if available {
+        if let Some(link) = sentinel.state().masters.get_mut(master).and_then(|instance| instance.link_mut(addr)) {
+            link.ping_ok();
+        }
+    }
+
+    if last_info.is_none_or(|at| at.elapsed() >= info_period) {
+        *last_info = Some(Instant::now());
+        if let Frame::BulkString(info) = client.query(&["INFO", "replication"]).await? {
+            let info = String::from_utf8_lossy(&info);
+            let target = sentinel.state().masters.get_mut(master).and_then(|instance| instance.refresh_info(addr, &info));
+            if let Some((host, port)) = target {
+                client.query(&["REPLICAOF", &host, &port.to_string()]).await?;
+            }
+        }
+    }
+
+    if last_hello.is_none_or(|at| at.elapsed() >= HELLO_PERIOD) {
+        *last_hello = Some(Instant::now());
+        let ip = client.local_ip().map(|ip| ip.to_string()).unwrap_or_default();
+        if let Some(hello) = sentinel.hello(master, &ip) {
+            client.query(&["PUBLISH", HELLO_CHANNEL, &hello]).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 订阅实例上的 hello 频道，连接断开后重新订阅
+async fn listen_hello(sentinel: Arc<Sentinel>, addr: Addr) {
+    loop {
+        if let Ok(mut client) = sentinel.connect_instance(&addr).await {
+            if client.send(&["SUBSCRIBE", HELLO_CHANNEL]).await.is_ok() {
+                while let Ok(frame) = client.read_frame().await {
+                    if let Frame::Array(message) | Frame::Push(message) = frame {
+                        if message.len() == 3 && message[0].to_string() == "message" {
+                            sentinel.process_hello(&message[2].to_string());
+                        }
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(PING_PERIOD).await;
+    }
+}
@@ -3,34 +3,40 @@ use anyhow::Error;
 use indicatif::{ProgressBar, ProgressStyle};
 use tokio::net::TcpStream;
 
-use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::net::TcpListener;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
+use tokio::sync::{OwnedRwLockReadGuard, RwLock};
+use tokio::time::Instant;
 
+use crate::acl::command_table;
 use crate::args::Args;
-use crate::network::session::Session;
+use crate::config::Config;
+use crate::network::session::{ReplicaInfo, Session};
 use crate::network::session_manager::SessionManager;
 use crate::network::session_role::SessionRole;
-use crate::persistence::aof_file::{AofFile, SyncStrategy};
+use crate::persistence::aof_file::{AofFile, AofMessage};
+use crate::store::blocking::BlockDirection;
 use crate::store::db::DatabaseMessage;
 use crate::store::db_manager::DatabaseManager;
+use crate::store::script_status::BUSY_ERROR;
 use crate::network::connection::Connection;
-use crate::replication::ReplicationManager;
 use crate::command::Command;
-use crate::frame::Frame;
+use crate::frame::{Frame, Protocol, ProtocolError};
 
 mod async_dispatch;
 mod state;
+mod stats;
 use async_dispatch::dispatch;
-use state::ServerState;
+pub use state::ServerState;
 
 pub struct Server {
     args: Arc<Args>,
-    aof_file: Option<AofFile>,
-    aof_sender: Option<Sender<(usize, Frame)>>,
+    aof_file: Option<Arc<AofFile>>,
+    aof_sender: Option<Sender<AofMessage>>,
     session_manager: Arc<SessionManager>,
     db_manager: Arc<DatabaseManager>,
     state: Arc<ServerState>,
@@ -38,18 +44,17 @@ pub struct Server {
 
 impl Server {
 
-    pub fn new(args: Arc<Args>, db_manager: Arc<DatabaseManager>) -> Self {
+    pub fn new(args: Arc<Args>, config: Arc<Config>, db_manager: Arc<DatabaseManager>) -> Self {
         let session_manager = Arc::new(SessionManager::new());
-        let state = Arc::new(ServerState::new());
+        let write_barrier = Arc::new(RwLock::new(()));
         let (aof_file, aof_sender) = if args.appendonly == "yes" {
-            let file_path = PathBuf::from(&args.dir).join(&args.appendfilename);
-            let sync_strategy = SyncStrategy::from_str(&args.appendfsync);
-            let file = AofFile::new(file_path, sync_strategy);
+            let file = Arc::new(AofFile::new(args.aof_path(), config.subscribe_appendfsync(), write_barrier.clone()));
             let sender = file.get_sender();
             (Some(file), Some(sender))
         } else {
             (None, None)
         };
+        let state = Arc::new(ServerState::new(&args, config, aof_file.clone(), write_barrier));
 
         Server { 
             args, 
@@ -61,27 +66,37 @@ impl Server {
         }
     }
 
+    /// 全局状态（Web 管理界面执行命令时同样需要权限检查与命令传播）
+    pub fn state(&self) -> Arc<ServerState> {
+        self.state.clone()
+    }
+
     pub async fn start(&mut self) {
 
-        if let Some(af) = &mut self.aof_file {
-            if let Err(_) = Self::replay_aof_file(af, self.db_manager.clone()).await {
-                log::info!("Failed to load AOF file");
+        if let Some(af) = &self.aof_file {
+            match Self::replay_aof_file(af, self.db_manager.clone()).await {
+                // AOF 为空（如首次开启 AOF）而 RDB 中有数据时，立即重写，使 AOF 包含这些数据
+                Ok(false) if self.db_manager.get_memory().used_memory() > 0 => {
+                    let use_preamble = self.state.config.values().aof_use_rdb_preamble;
+                    let _ = af.rewrite(self.db_manager.get_senders(), use_preamble);
+                },
+                Ok(_) => {},
+                Err(e) => {
+                    log::error!("Bad file format reading the append only file: {}", e);
+                    std::process::exit(1);
+                }
             }
+            af.start_auto_rewrite(self.db_manager.clone(), self.state.config.clone());
         }
 
-        if self.args.is_slave() {
-            let args = self.args.clone();
-            let db_manager = self.db_manager.clone();
-            std::thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async {
-                    let mut rm = ReplicationManager::new(args,  db_manager);
-                    if let Err(e) = rm.connect().await {
-                        log::error!("Failed to connect to master: {}", e);
-                    }
-                });
-            });
-        } 
+        match self.args.master_addr() {
+            Ok(Some((host, port))) => self.state.replicaof(Some((host, port)), self.args.clone(), self.db_manager.clone()),
+            Ok(None) => {},
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(1);
+            }
+        }
 
         match TcpListener::bind(format!("{}:{}", self.args.bind, self.args.port)).await {
             Ok(listener) => {
@@ -92,15 +107,18 @@ impl Server {
                         Ok((stream, _address)) => {
                             
                             // 检查 maxclients 限制
-                            if self.session_manager.is_over_max_clients(self.args.maxclients) {
-                                let connection = crate::network::connection::Connection::new(stream);
+                            let maxclients = self.state.config.values().maxclients;
+                            if self.session_manager.is_over_max_clients(maxclients) {
+                                self.state.stats.connection_rejected();
+                                let connection = crate::network::connection::Connection::new(stream, self.args.proto_max_bulk_len);
                                 let error_frame = crate::frame::Frame::Error("ERR max number of clients reached".to_string());
                                 tokio::spawn(async move {
-                                    connection.write_bytes(error_frame.as_bytes()).await;
+                                    connection.write_frame(&error_frame).await;
                                 });
                                 continue;
                             }
                             
+                            self.state.stats.connection_received();
                             let aof_sender = self.aof_sender.clone(); 
                             let session_manager_clone = self.session_manager.clone();
                             let db_manager_clone = self.db_manager.clone();
@@ -123,8 +141,20 @@ impl Server {
         }
     }
 
-    async fn replay_aof_file(aof_file: &mut AofFile, db_manager: Arc<DatabaseManager>) -> Result<(), Error>  {
-        let frames = aof_file.read_all_frames().await.unwrap();
+    /// 加载 AOF 文件，返回文件中是否有数据
+    async fn replay_aof_file(aof_file: &AofFile, db_manager: Arc<DatabaseManager>) -> Result<bool, Error>  {
+        let mut frames = aof_file.read_all_frames().await?;
+        if frames.is_empty() {
+            return Ok(false);
+        }
+
+        // 混合格式：文件以 RDB 快照开头，先整体恢复快照，再重放之后的增量命令
+        if let Some(Frame::RDBFile(_)) = frames.first() {
+            let rdb_file = frames.remove(0).to_rdb_file()?;
+            for (index, sender) in db_manager.get_senders().iter().enumerate() {
+                sender.send(DatabaseMessage::Restore(rdb_file.get_database(index))).await?;
+            }
+        }
         let pb = ProgressBar::new(frames.len() as u64);
         pb.set_style(ProgressStyle::default_bar()
             .template("{spinner:.green} [{bar:40.green/gray}] {pos}/{len} ({percent}%) {msg}")
@@ -162,17 +192,23 @@ impl Server {
         pb.set_message("Status: Completed");
         pb.finish();
         println!();
-        Ok(())
+        Ok(true)
     }
 }
 
+/**
+ * 客户端连接的处理器
+ *
+ * @param pop_barrier 阻塞命令弹出成功后持有的写屏障，命令写入 AOF、进入复制流之后释放
+ */
 pub struct Handler {
     session: Session,
-    aof_sender: Option<Sender<(usize, Frame)>>,
+    aof_sender: Option<Sender<AofMessage>>,
     session_manager: Arc<SessionManager>,
     db_manager: Arc<DatabaseManager>,
     args: Arc<Args>,
     state: Arc<ServerState>,
+    pop_barrier: Option<OwnedRwLockReadGuard<()>>,
 }
 
 impl Handler {
@@ -204,11 +240,11 @@ impl Handler {
 
 impl Handler {
 
-    pub fn new(db_manager: Arc<DatabaseManager>, session_manager: Arc<SessionManager>, stream: TcpStream, args: Arc<Args>, aof_sender: Option<Sender<(usize,Frame)>>, state: Arc<ServerState>) -> Self {
+    pub fn new(db_manager: Arc<DatabaseManager>, session_manager: Arc<SessionManager>, stream: TcpStream, args: Arc<Args>, aof_sender: Option<Sender<AofMessage>>, state: Arc<ServerState>) -> Self {
         let args_ref = args.as_ref();
-        let certification = args_ref.requirepass.is_none();
+        let certification = state.acl.read().unwrap().default_user_nopass();
         let sender = db_manager.as_ref().get_sender(0);
-        let connection = Connection::new(stream);
+        let connection = Connection::new(stream, args_ref.proto_max_bulk_len);
         let session = Session::new(certification, sender, connection);
         session_manager.create_session(session.clone());
 
@@ -219,26 +255,46 @@ impl Handler {
             db_manager,
             args,
             state,
+            pop_barrier: None,
         }
     }
 
     /**
      * 客户端登录认证
      * 
-     * 如果 "密码" 不匹配，响应 ERR invalid password 错误
+     * 如果 "用户名" 与 "密码" 不匹配或用户被禁用，响应 WRONGPASS 错误
      * 
-     * @param input_requirepass 输入密码【只读】
+     * @param username 用户名【只读】
+     * @param password 输入密码【只读】
      */
-    pub fn login(&mut self, input_requirepass: &String) -> Result<(), Error> {
-        if let Some(ref requirepass) = self.args.requirepass {
-            if requirepass == input_requirepass {
-                self.session.set_certification(true);
-                return Ok(())
-            } 
-            return Err(Error::msg("ERR invalid password"));
-        } else {
-            Ok(())
+    pub fn login(&mut self, username: &str, password: &str) -> Result<(), Error> {
+        if !self.state.acl.read().unwrap().authenticate(username, password) {
+            return Err(Error::msg("WRONGPASS invalid username-password pair or user is disabled."));
         }
+        self.session.set_user(username.to_string());
+        self.session.set_certification(true);
+        self.session_manager.create_session(self.session.clone());
+        Ok(())
+    }
+
+    /**
+     * 检查当前用户是否有权限执行命令（命令、键与频道）
+     *
+     * @param args 完整的命令参数（含命令名）
+     * @return 没有权限时返回 NOPERM 错误
+     */
+    pub fn check_permission(&self, args: &[Vec<u8>]) -> Option<Frame> {
+        let acl = self.state.acl.read().unwrap();
+        let username = self.session.get_user();
+        match acl.get_user(username) {
+            Some(user) => user.check(args).err().map(|denied| Frame::Error(denied.to_error(username))),
+            None => Some(Frame::Error(format!("NOPERM User {} has been deleted", username))),
+        }
+    }
+
+    /// 当前登录用户的快照（用于脚本内的权限检查），用户已被删除时为 None
+    pub fn current_user(&self) -> Option<crate::acl::User> {
+        self.state.acl.read().unwrap().get_user(self.session.get_user()).cloned()
     }
 
     /**
@@ -267,91 +323,222 @@ impl Handler {
         self.session_manager.create_session(self.session.clone());
     }
 
+    pub fn set_replica_info(&mut self, info: ReplicaInfo) {
+        *self.session.get_replica_info_mut() = info;
+        self.session_manager.create_session(self.session.clone());
+    }
+
+    /**
+     * 设置连接名称并同步到 SessionManager（CLIENT SETNAME、HELLO SETNAME）
+     *
+     * 名称为空字符串时清除连接名称
+     *
+     * @param name 连接名称
+     */
+    pub fn set_client_name(&mut self, name: String) -> Result<(), Error> {
+        if name.chars().any(|c| !('!'..='~').contains(&c)) {
+            return Err(Error::msg("ERR Client names cannot contain spaces, newlines or special characters."));
+        }
+        self.session.set_name(if name.is_empty() { None } else { Some(name) });
+        self.session_manager.create_session(self.session.clone());
+        Ok(())
+    }
+
+    /**
+     * 更新会话的订阅数量（进入或退出订阅模式）
+     * 
+     * @param count 订阅总数（频道 + 模式）
+     */
+    pub fn set_subscriptions(&mut self, count: usize) {
+        self.session.set_subscriptions(count);
+    }
+
     /// Handling client connections
     pub async fn handle(&mut self) {
         loop {
 
-            log::debug!("Waiting for bytes");
-            let bytes = match self.session.connection.read_bytes().await {
-                Ok(bytes) => bytes,
-                Err(_e) => {
-                    // 清理会话相关的所有资源（阻塞请求、订阅等）
-                    self.state.cleanup_session(self.session.get_id()).await;
-                    self.session_manager.remove_session(self.session.get_id());
+            log::debug!("Waiting for frame");
+            let frame = match self.session.connection.read_frame().await {
+                Ok(Some(frame)) => frame,
+                Ok(None) => {
+                    self.close().await;
+                    return;
+                },
+                Err(e) => {
+                    // 协议错误：回复错误后关闭连接（与 Redis 行为一致）
+                    if let Some(protocol_error) = e.downcast_ref::<ProtocolError>() {
+                        let frame = Frame::Error(format!("ERR {}", protocol_error));
+                        self.session.connection.write_frame(&frame).await;
+                    }
+                    log::debug!("Closing connection: {}", e);
+                    self.close().await;
                     return;
                 }
             };
             
-            // 解析可能的多个粘连命令帧
-            let frames = match Frame::parse_multiple_frames(bytes.as_slice()) {
-                Ok(frames) => frames,
+            log::debug!("Received frame: {}", frame.to_string());
+            let frame_copy = frame.clone();
+            let args = frame.get_args_bytes();
+            if self.session.is_in_transaction() {
+                let command_name = frame.get_arg(0).unwrap_or_default().to_uppercase();
+                if command_name != "EXEC" && command_name != "DISCARD" && command_name != "WATCH" {
+                    self.session.add_transaction_frame(frame_copy);
+                    self.session.connection.write_frame(&Frame::SimpleString("QUEUED".to_string())).await;
+                    continue;
+                }
+            }
+            
+            let command = match Command::parse_from_frame(frame) {
+                Ok(cmd) => cmd,
                 Err(e) => {
-                    log::error!("Failed to parse multiple frames: {:?}", e);
-                    let frame = Frame::Error(format!("Failed to parse frames: {:?}", e));
-                    self.session.connection.write_bytes(frame.as_bytes()).await;
+                    let frame = Frame::Error(e.to_string());
+                    self.session.connection.write_frame(&frame).await;
                     continue;
                 }
             };
             
-            log::debug!("Received bytes: {:?}", String::from_utf8_lossy(bytes.as_slice()));
-            
-            for frame in frames {
-                log::debug!("Received frame: {}", frame.to_string());
-                let frame_copy = frame.clone();
-                if self.session.is_in_transaction() {
-                    let command_name = frame.get_arg(0).unwrap_or_default().to_uppercase();
-                    if command_name != "EXEC" && command_name != "DISCARD" {
-                        self.session.add_transaction_frame(frame_copy);
-                        self.session.connection.write_bytes(Frame::SimpleString("QUEUED".to_string()).as_bytes()).await;
+            match command {
+                Command::Auth(_) | Command::Hello(_) => {},
+                _ => { 
+                    if !self.session.get_certification() {
+                        let frame = Frame::Error("NOAUTH Authentication required.".to_string());
+                        self.session.connection.write_frame(&frame).await;
                         continue;
                     }
-                }
-                
-                let command = match Command::parse_from_frame(frame) {
-                    Ok(cmd) => cmd,
-                    Err(e) => {
-                        let frame = Frame::Error(e.to_string());
-                        self.session.connection.write_bytes(frame.as_bytes()).await;
+                },
+            };
+
+            // ACL 权限检查（命令、键与频道），在执行命令之前进行
+            match command {
+                Command::Auth(_) | Command::Hello(_) | Command::Unknown(_) => {},
+                _ => {
+                    if let Some(frame) = self.check_permission(&args) {
+                        self.session.connection.write_frame(&frame).await;
                         continue;
                     }
-                };
-                
+                },
+            };
+
+            // RESP2 订阅模式下只允许执行订阅相关命令与 PING（RESP3 的消息以推送类型发送，不受限制）
+            if self.session.is_subscribed() && self.session.connection.get_protocol() == Protocol::Resp2 {
                 match command {
-                    Command::Auth(_) => {},
-                    _ => { 
-                        if self.args.requirepass.is_some() {
-                            if self.session.get_certification() == false {
-                                let frame = Frame::Error("NOAUTH Authentication required.".to_string());
-                                self.session.connection.write_bytes(frame.as_bytes()).await;
-                                continue;
-                            }
-                        } 
+                    Command::Subscribe(_) | Command::Unsubscribe(_) | Command::Psubscribe(_) | Command::Punsubscribe(_) => {},
+                    Command::Ping(_) => {
+                        let frame = Frame::Array(vec![
+                            Frame::BulkString("pong".to_string().into()),
+                            Frame::BulkString(String::new().into()),
+                        ]);
+                        self.session.connection.write_frame(&frame).await;
+                        continue;
                     },
-                };
+                    _ => {
+                        let command_name = frame_copy.get_arg(0).unwrap_or_default();
+                        let frame = Frame::Error(format!("ERR Can't execute '{}': only (P)SUBSCRIBE / (P)UNSUBSCRIBE / PING are allowed in this context", command_name.to_lowercase()));
+                        self.session.connection.write_frame(&frame).await;
+                        continue;
+                    }
+                }
+            }
 
-                let is_psync_command = matches!(command, Command::Psync(_));
-                let should_propagate = command.propagate_aof_if_needed();
-                let result = self.apply_command(command).await;
+            if let Some(frame) = self.check_busy_script(&command) {
+                self.session.connection.write_frame(&frame).await;
+                continue;
+            }
 
-                match result {
-                    Ok(frame) => {
-                        if should_propagate {
-                            if let Some(ref aof_sender) = self.aof_sender {
-                                let _ = aof_sender.send((self.session.get_current_db(), frame_copy.clone())).await;
-                            }
-                            self.propagate_to_slaves(frame_copy.clone()).await;
-                        }
-                        self.session.connection.write_bytes(frame.as_bytes()).await;
-                        if is_psync_command {
-                            return;
+            if let Some(frame) = self.check_read_only(&command) {
+                self.session.connection.write_frame(&frame).await;
+                continue;
+            }
+
+            if let Some(frame) = self.check_min_replicas(&command) {
+                self.session.connection.write_frame(&frame).await;
+                continue;
+            }
+
+            if let Some(frame) = self.check_memory(&command).await {
+                self.session.connection.write_frame(&frame).await;
+                continue;
+            }
+
+            self.state.stats.command_processed();
+
+            // 全量同步之后连接转为复制流，不再按普通客户端处理
+            if let Command::Psync(psync) = command {
+                match psync.apply(self).await {
+                    Ok(()) => {
+                        self.serve_replica().await;
+                        return;
+                    },
+                    Err(e) => {
+                        log::error!("Full synchronization failed: {}", e);
+                        self.session.connection.write_frame(&Frame::Error(format!("ERR {}", e))).await;
+                        continue;
+                    }
+                }
+            }
+            let should_propagate = command.propagate_aof_if_needed();
+            let written_dbs = self.written_dbs(&command);
+
+            // 写命令从执行到写入 AOF、进入复制流期间持有写屏障
+            let write_barrier = self.state.write_barrier.clone();
+            let barrier = if command.holds_write_barrier() {
+                Some(write_barrier.read().await)
+            } else {
+                None
+            };
+            let result = self.apply_command(command).await;
+
+            match result {
+                Ok(frame) => {
+                    if should_propagate {
+                        if let Some(propagated) = Command::propagation_frame(frame_copy, &frame) {
+                            self.propagate(propagated).await;
                         }
                     }
-                    Err(e) => {
-                        println!("Failed to receive; err = {:?}", e);
+                    drop(barrier);
+                    self.pop_barrier = None;
+                    for db_index in written_dbs {
+                        self.signal_keys_ready(db_index, &args).await;
                     }
+                    self.session.connection.write_frame(&frame).await;
+                }
+                Err(e) => {
+                    self.pop_barrier = None;
+                    println!("Failed to receive; err = {:?}", e);
+                }
+            }
+        }
+    }
+
+    /**
+     * 处理从节点连接
+     *
+     * 复制流由 PSYNC 启动的任务发送，这里只读取从节点上报的 REPLCONF ACK 并记录偏移量，不回复任何内容；
+     * 连接断开后从复制流中移除
+     */
+    async fn serve_replica(&mut self) {
+        let id = self.session.get_id();
+        loop {
+            let frame = match self.session.connection.read_frame().await {
+                Ok(Some(frame)) => frame,
+                _ => break,
+            };
+            if let Ok(Command::Replconf(replconf)) = Command::parse_from_frame(frame) {
+                if let Some(offset) = replconf.ack_offset() {
+                    self.session_manager.update_replica_ack(id, offset);
                 }
             }
         }
+        log::info!("Connection with replica {} lost", id);
+        self.state.replication.remove_replica(id);
+        self.close().await;
+    }
+
+    /// 清理会话相关的所有资源（阻塞请求、订阅、WATCH 等）
+    async fn close(&mut self) {
+        self.unwatch_keys().await;
+        self.state.cleanup_session(self.session.get_id()).await;
+        self.session_manager.remove_session(self.session.get_id());
     }
     
     /// 执行服务器命令
@@ -363,34 +550,125 @@ impl Handler {
 
         match command {
             Command::Auth(auth) => auth.apply(self),
-            Command::Client(client) => client.apply(),
+            Command::Hello(hello) => hello.apply(self),
+            Command::Client(client) => client.apply(self),
             Command::Replconf(replconf) => replconf.apply(self),
-            Command::Save(save) => save.apply(self.db_manager.clone(), self.args.clone()).await,
-            Command::Bgsave(bgsave) => bgsave.apply(self.db_manager.clone(), self.args.clone()).await,
-            Command::Psync(psync) => psync.apply(self.db_manager.clone(), self.args.clone()).await,
+            Command::Replicaof(replicaof) => replicaof.apply(self),
+            Command::Wait(wait) => wait.apply(self).await,
+            Command::Save(save) => save.apply(self.db_manager.clone()).await,
+            Command::Bgsave(bgsave) => bgsave.apply(self.db_manager.clone()),
+            Command::Lastsave(lastsave) => lastsave.apply(self.db_manager.clone()),
+            Command::Bgrewriteaof(bgrewriteaof) => bgrewriteaof.apply(self),
             Command::Flushall(flushall) => flushall.apply(self.db_manager.clone()).await,
             Command::Move(r#move) => r#move.apply(self).await,
             Command::Exec(_) => Box::pin(self.execute_transaction()).await,
             Command::Multi(multi) => multi.apply(self),
-            Command::Discard(discard) => discard.apply(self),
+            Command::Discard(discard) => discard.apply(self).await,
             Command::Select(select) => select.apply(self),
             Command::Unknown(unknown) => unknown.apply(),
             Command::Ping(ping) => ping.apply(),
             Command::Echo(echo) => echo.apply(),
+            Command::Info(info) => info.apply(self).await,
+            Command::Acl(acl) => acl.apply(self),
+            Command::Config(config) => config.apply(self),
+            Command::Debug(debug) => debug.apply(self).await,
             _ => self.apply_db_command(command).await,
         }
     }
 
-    /// 执行事务中的所有命令
+    /**
+     * 内存超过 maxmemory 时先按淘汰策略删除键
+     *
+     * 仍然无法回到上限以内时，拒绝可能增加内存用量的命令
+     *
+     * @param command 即将执行的命令
+     * @return 需要拒绝命令时返回 OOM 错误
+     */
+    async fn check_memory(&self, command: &Command) -> Option<Frame> {
+        let (maxmemory, policy, samples) = {
+            let values = self.state.config.values();
+            (values.maxmemory, values.maxmemory_policy, values.maxmemory_samples)
+        };
+        // 从节点不主动淘汰，由主节点传播的 DEL 删除键（replica-ignore-maxmemory）
+        if maxmemory == 0 || self.state.is_replica() {
+            return None;
+        }
+        let (within_limit, evicted) = self.db_manager
+            .perform_evictions(maxmemory, policy, samples)
+            .await;
+        for (db_index, key) in evicted {
+            let del = Frame::Array(vec![
+                Frame::BulkString("DEL".to_string().into()),
+                Frame::BulkString(key),
+            ]);
+            self.propagate_in(db_index, del).await;
+        }
+        if !within_limit && command.is_denyoom() {
+            return Some(Frame::Error("OOM command not allowed when used memory > 'maxmemory'.".to_string()));
+        }
+        None
+    }
+
+    /// 脚本执行超过 lua-time-limit 时，只允许 SCRIPT KILL 与认证命令
+    fn check_busy_script(&self, command: &Command) -> Option<Frame> {
+        match command {
+            Command::Script(script) if script.is_kill() => None,
+            Command::Auth(_) | Command::Hello(_) => None,
+            _ if self.db_manager.script_busy() => Some(Frame::Error(BUSY_ERROR.to_string())),
+            _ => None,
+        }
+    }
+
+    /// 只读从节点拒绝客户端的写命令（replica-read-only）
+    fn check_read_only(&self, command: &Command) -> Option<Frame> {
+        if command.propagate_aof_if_needed() && self.state.config.values().replica_read_only && self.state.is_replica() {
+            return Some(Frame::Error("READONLY You can't write against a read only replica.".to_string()));
+        }
+        None
+    }
+
+    /// 正常的从节点不足 min-replicas-to-write 时，主节点拒绝写命令
+    fn check_min_replicas(&self, command: &Command) -> Option<Frame> {
+        let (to_write, max_lag) = {
+            let values = self.state.config.values();
+            (values.min_replicas_to_write, values.min_replicas_max_lag)
+        };
+        if to_write == 0 || !command.propagate_aof_if_needed() || self.state.is_replica() {
+            return None;
+        }
+        if self.session_manager.count_good_replicas(max_lag) < to_write {
+            return Some(Frame::Error("NOREPLICAS Not enough good replicas to write.".to_string()));
+        }
+        None
+    }
+
+    /**
+     * 执行事务中的所有命令
+     *
+     * 连续的数据库命令作为一条消息交给数据库执行，当前数据库中被监视的键随第一批命令一起检查，
+     * 检查与执行之间不会穿插其他客户端的写入；SELECT、脚本等需要 Handler 的命令在两批之间依次执行。
+     * 执行成功的写命令逐条写入 AOF 并传播给从节点
+     */
     async fn execute_transaction(&mut self) -> Result<Frame, Error> {
 
         if !self.session.is_in_transaction() {
             return Ok(Frame::Error("ERR EXEC without MULTI".to_string()));
         }
 
+        // 其他数据库中被监视的键无法随命令一起检查，提前检查
+        let mut watched = Self::group_by_db(self.session.get_watched_keys().clone());
+        let mut pending_watch = watched.remove(&self.session.get_current_db());
+        if self.watched_modified(watched).await {
+            return Ok(self.abort_transaction().await);
+        }
+
         let transaction_frames = self.session.get_transaction_frames().clone();
-        let mut results = Vec::new();
+        let mut results = Vec::with_capacity(transaction_frames.len());
+        let mut batch = Vec::new();
+        let mut executed = Vec::new();
         for frame in transaction_frames {
+            let frame_copy = frame.clone();
+            let args = frame.get_args_bytes();
             let command = match Command::parse_from_frame(frame) {
                 Ok(cmd) => cmd,
                 Err(e) => {
@@ -398,52 +676,177 @@ impl Handler {
                     continue;
                 }
             };
-            
-            // 应用命令并收集结果
-            // 注意：这里我们不处理EXEC、MULTI、DISCARD命令，避免递归
+            if let Some(frame) = self.check_queued_command(&command, &args).await {
+                results.push(frame);
+                continue;
+            }
+            executed.push((self.written_dbs(&command), args));
+
             match command {
-                Command::Exec(_) | Command::Multi(_) | Command::Discard(_) => {
-                    results.push(Frame::Error("ERR nested transaction commands not allowed".to_string()));
+                Command::Unknown(_) | Command::Ping(_) | Command::Echo(_) => {
+                    // 不访问数据，不需要打断当前批次
+                    results.push(self.apply_queued_command(command).await);
                 },
-                _ => {
-                    // 优先尝试通过 dispatch 执行命令
-                    // 这对于 LPUSH/RPUSH 等命令至关重要，因为它们需要检查并唤醒阻塞的客户端（BLPOP/BRPOP）
-                    // 如果在这里绕过 dispatch，事务中的 LPUSH 将直接写入数据库，而不会唤醒等待者
-                    if let Some(res) = dispatch(self, &command).await {
-                        match res {
-                            Ok(frame) => results.push(frame),
-                            Err(e) => results.push(Frame::Error(e.to_string())),
-                        }
-                        continue;
+                command if Self::needs_handler(&command) => {
+                    if !self.flush_batch(&mut batch, &mut pending_watch, &mut results).await {
+                        return Ok(self.abort_transaction().await);
                     }
-
-                    // 为了避免递归（实际不会有, 解决 Rust 编译问题）
-                    let result = match command {
-                        Command::Auth(auth) => auth.apply(self),
-                        Command::Client(client) => client.apply(),
-                        Command::Replconf(replconf) => replconf.apply(self),
-                        Command::Save(save) => save.apply(self.db_manager.clone(), self.args.clone()).await,
-                        Command::Bgsave(bgsave) => bgsave.apply(self.db_manager.clone(), self.args.clone()).await,
-                        Command::Psync(psync) => psync.apply(self.db_manager.clone(), self.args.clone()).await,
-                        Command::Flushall(flushall) => flushall.apply(self.db_manager.clone()).await,
-                        Command::Move(r#move) => r#move.apply(self).await,
-                        Command::Select(select) => select.apply(self),
-                        Command::Unknown(unknown) => unknown.apply(),
-                        Command::Ping(ping) => ping.apply(),
-                        Command::Echo(echo) => echo.apply(),
-                        _ => self.apply_db_command(command).await,
-                    };
-                    match result {
-                        Ok(frame) => results.push(frame),
-                        Err(e) => results.push(Frame::Error(e.to_string())),
+                    let should_propagate = command.propagate_aof_if_needed();
+                    let reply = self.apply_queued_command(command).await;
+                    if should_propagate {
+                        self.propagate_reply(frame_copy, &reply).await;
                     }
+                    results.push(reply);
+                },
+                command => {
+                    batch.push((results.len(), frame_copy, command));
+                    results.push(Frame::Null);
                 }
             }
         }
+        if !self.flush_batch(&mut batch, &mut pending_watch, &mut results).await {
+            return Ok(self.abort_transaction().await);
+        }
+
+        self.unwatch_keys().await;
         self.session.clear_transaction();
+        // 事务执行完毕后再唤醒阻塞的客户端，等待者看到的是事务的完整结果
+        for (written_dbs, args) in executed {
+            for db_index in written_dbs {
+                self.signal_keys_ready(db_index, &args).await;
+            }
+        }
         Ok(Frame::Array(results))
     }
 
+    /**
+     * 把一批数据库命令作为一条消息交给当前数据库执行，并传播其中执行成功的写命令
+     *
+     * @param batch 命令在结果中的位置、原始命令帧与命令
+     * @param watched 尚未检查的被监视的键，随这批命令一起检查（批次为空时只检查）
+     * @param results 事务的结果，按位置填入执行结果
+     * @return 被监视的键已经被修改时返回 false，此时不执行任何命令
+     */
+    async fn flush_batch(&mut self, batch: &mut Vec<(usize, Frame, Command)>, watched: &mut Option<Vec<(Vec<u8>, u64)>>, results: &mut [Frame]) -> bool {
+        if batch.is_empty() && watched.is_none() {
+            return true;
+        }
+        let mut positions = Vec::with_capacity(batch.len());
+        let mut commands = Vec::with_capacity(batch.len());
+        for (index, frame, command) in batch.drain(..) {
+            positions.push((index, frame, command.propagate_aof_if_needed()));
+            commands.push(command);
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        let message = DatabaseMessage::Exec { watched: watched.take().unwrap_or_default(), commands, sender };
+        if let Err(e) = self.session.get_sender().send(message).await {
+            let error = Frame::Error(format!("Channel closed: {:?}", e));
+            for (index, _, _) in positions {
+                results[index] = error.clone();
+            }
+            return true;
+        }
+        let replies = match receiver.await {
+            Ok(Some(replies)) => replies,
+            Ok(None) => return false,
+            Err(e) => vec![Frame::Error(format!("{:?}", e)); positions.len()],
+        };
+
+        for ((index, frame, should_propagate), reply) in positions.into_iter().zip(replies) {
+            if should_propagate {
+                self.propagate_reply(frame, &reply).await;
+            }
+            results[index] = reply;
+        }
+        true
+    }
+
+    /// 被监视的键被修改，放弃执行事务
+    async fn abort_transaction(&mut self) -> Frame {
+        self.unwatch_keys().await;
+        self.session.clear_transaction();
+        Frame::NullArray
+    }
+
+    /**
+     * 检查事务中的命令能否执行
+     *
+     * @param command 排队的命令
+     * @param args 命令的完整参数
+     * @return 不能执行时返回错误回复
+     */
+    async fn check_queued_command(&self, command: &Command, args: &[Vec<u8>]) -> Option<Frame> {
+        if matches!(command, Command::Exec(_) | Command::Multi(_) | Command::Discard(_)) {
+            return Some(Frame::Error("ERR nested transaction commands not allowed".to_string()));
+        }
+        if !matches!(command, Command::Unknown(_)) {
+            if let Some(frame) = self.check_permission(args) {
+                return Some(frame);
+            }
+        }
+        if let Some(frame) = self.check_read_only(command) {
+            return Some(frame);
+        }
+        if let Some(frame) = self.check_min_replicas(command) {
+            return Some(frame);
+        }
+        self.check_memory(command).await
+    }
+
+    /// 命令是否需要 Handler 上下文，不能交给数据库批量执行
+    fn needs_handler(command: &Command) -> bool {
+        async_dispatch::handles(command) || matches!(command,
+            Command::Auth(_) | Command::Hello(_) | Command::Client(_) | Command::Replconf(_) | Command::Replicaof(_) |
+            Command::Wait(_) | Command::Save(_) | Command::Bgsave(_) | Command::Lastsave(_) | Command::Bgrewriteaof(_) |
+            Command::Flushall(_) | Command::Move(_) | Command::Select(_) | Command::Info(_) | Command::Acl(_) |
+            Command::Config(_) | Command::Debug(_))
+    }
+
+    /// 执行事务中不交给数据库批量执行的命令
+    async fn apply_queued_command(&mut self, command: Command) -> Frame {
+        // 优先尝试通过 dispatch 执行需要 Handler 上下文的命令（如脚本、XADD）
+        if let Some(res) = dispatch(self, &command).await {
+            return res.unwrap_or_else(|e| Frame::Error(e.to_string()));
+        }
+
+        // 为了避免递归（实际不会有, 解决 Rust 编译问题）
+        let result = match command {
+            Command::Auth(auth) => auth.apply(self),
+            Command::Hello(hello) => hello.apply(self),
+            Command::Client(client) => client.apply(self),
+            Command::Replconf(replconf) => replconf.apply(self),
+            Command::Replicaof(replicaof) => replicaof.apply(self),
+            Command::Wait(wait) => wait.apply(self).await,
+            Command::Save(save) => save.apply(self.db_manager.clone()).await,
+            Command::Bgsave(bgsave) => bgsave.apply(self.db_manager.clone()),
+            Command::Lastsave(lastsave) => lastsave.apply(self.db_manager.clone()),
+            Command::Bgrewriteaof(bgrewriteaof) => bgrewriteaof.apply(self),
+            Command::Flushall(flushall) => flushall.apply(self.db_manager.clone()).await,
+            Command::Move(r#move) => r#move.apply(self).await,
+            Command::Select(select) => select.apply(self),
+            Command::Unknown(unknown) => unknown.apply(),
+            Command::Ping(ping) => ping.apply(),
+            Command::Echo(echo) => echo.apply(),
+            Command::Info(info) => info.apply(self).await,
+            Command::Acl(acl) => acl.apply(self),
+            Command::Config(config) => config.apply(self),
+            Command::Debug(debug) => debug.apply(self).await,
+            _ => self.apply_db_command(command).await,
+        };
+        result.unwrap_or_else(|e| Frame::Error(e.to_string()))
+    }
+
+    /// 传播执行成功的写命令
+    async fn propagate_reply(&self, frame: Frame, reply: &Frame) {
+        if matches!(reply, Frame::Error(_)) {
+            return;
+        }
+        if let Some(propagated) = Command::propagation_frame(frame, reply) {
+            self.propagate(propagated).await;
+        }
+    }
+
     /// 执行数据库命令
     pub async fn apply_db_command(&self, command: Command) -> Result<Frame, Error> {
         let (sender, receiver) = oneshot::channel();
@@ -456,21 +859,17 @@ impl Handler {
         Ok(result)
     }
 
-    /// 传播主节点命令
-    async fn propagate_to_slaves(&self, frame: Frame) {
-        let slave_sessions = self.session_manager.get_slave_sessions();
-        let current_db = self.session.get_current_db();
-        if slave_sessions.is_empty() {
-            return;
-        }
-        
-        for slave_session in slave_sessions {
-            let frame_clone = frame.clone();
-            let db = current_db;
-            
-            slave_session.connection.write_bytes(Frame::Array(vec![Frame::BulkString("SELECT".to_string()),Frame::BulkString(db.to_string())]).as_bytes()).await;
-            slave_session.connection.write_bytes(frame_clone.as_bytes()).await;
+    /// 将写命令追加到 AOF 并传播给从节点
+    pub async fn propagate(&self, frame: Frame) {
+        self.propagate_in(self.session.get_current_db(), frame).await;
+    }
+
+    /// 将在指定数据库中执行的写命令追加到 AOF 并传播给从节点
+    async fn propagate_in(&self, db_index: usize, frame: Frame) {
+        if let Some(ref aof_sender) = self.aof_sender {
+            let _ = aof_sender.send(AofMessage::Append(db_index, frame.clone())).await;
         }
+        self.state.replication.feed(db_index, &frame);
     }
 
     // 事务相关方法
@@ -494,5 +893,194 @@ impl Handler {
         self.session.clear_transaction();
     }
 
+    /**
+     * 阻塞执行弹出命令（BLPOP、BLMOVE、BLMPOP、BZPOPMIN 等）
+     *
+     * 先注册等待请求再尝试弹出，避免在两者之间写入的元素被遗漏；
+     * 弹出成功、出错或超时后返回，否则等待键被写入后重新弹出，重新等待时保持原来的排队位置。
+     * 事务中不阻塞，与 Redis 相同
+     *
+     * @param keys 等待的键
+     * @param timeout 阻塞的时间，None 表示永久阻塞
+     * @param direction 弹出的方向
+     * @param pop 构建一次非阻塞弹出的命令，所有键都为空时回复 nil
+     */
+    pub async fn block_on_keys<F>(&mut self, keys: &[Vec<u8>], timeout: Option<Duration>, direction: BlockDirection, pop: F) -> Result<Frame, Error>
+    where
+        F: Fn() -> Command,
+    {
+        if self.is_in_transaction() {
+            return self.apply_db_command(pop()).await;
+        }
+
+        let session_id = self.session.get_id();
+        let db_index = self.session.get_current_db();
+        let started = Instant::now();
+        let deadline = timeout.map(|timeout| started + timeout);
+        let manager = self.state.blocking_list.clone();
+
+        loop {
+            let receiver = manager.lock().await.register_blocking_request(keys.to_vec(), session_id, db_index, direction, timeout, started.into_std());
+
+            let result = self.apply_with_barrier(pop()).await?;
+            if !matches!(result, Frame::Null | Frame::NullArray) {
+                manager.lock().await.cleanup_session(session_id);
+                return Ok(result);
+            }
+
+            // 被写入唤醒时收到 OK，超时或连接断开时收到 nil
+            let woken = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, receiver).await.ok().and_then(|result| result.ok()),
+                None => receiver.await.ok(),
+            };
+            if !matches!(woken, Some(Frame::Ok)) {
+                manager.lock().await.cleanup_session(session_id);
+                return Ok(result);
+            }
+        }
+    }
+
+    /**
+     * 持有写屏障执行阻塞命令的一次非阻塞尝试（弹出、XREADGROUP 读取新消息）
+     *
+     * 取到数据时屏障保留到命令传播之后，由主循环释放，重写与全量同步不会在两者之间开始；
+     * 没有数据时立即释放，等待期间不持有
+     *
+     * @param command 非阻塞的命令
+     */
+    pub async fn apply_with_barrier(&mut self, command: Command) -> Result<Frame, Error> {
+        let barrier = self.state.write_barrier.clone().read_owned().await;
+        let result = self.apply_db_command(command).await?;
+        if !matches!(result, Frame::Null | Frame::NullArray) {
+            self.pop_barrier = Some(barrier);
+        }
+        Ok(result)
+    }
+
+    /**
+     * 唤醒等待命令所涉及键的阻塞客户端（每条命令与整个事务执行后调用）
+     *
+     * LPUSH、LMOVE、LINSERT、RESTORE、脚本等都可能使列表或有序集合变为非空，
+     * 因此按命令表提取键，只通知有客户端等待且当前非空的键；
+     * 阻塞命令弹出后键仍非空时，也由这里唤醒下一个等待者
+     *
+     * @param db_index 命令写入的数据库
+     * @param args 已执行命令的完整参数
+     */
+    async fn signal_keys_ready(&self, db_index: usize, args: &[Vec<u8>]) {
+        let Some(name) = args.first() else {
+            return;
+        };
+        let subcommand = args.get(1).map(|arg| String::from_utf8_lossy(arg).to_lowercase());
+        let Some(spec) = command_table::lookup(&String::from_utf8_lossy(name).to_lowercase(), subcommand.as_deref()) else {
+            return;
+        };
+        let mut keys = command_table::extract_keys(spec.keys, args);
+        keys.sort();
+        keys.dedup();
+
+        for key in keys {
+            let (list, sorted_set) = self.state.blocking_list.lock().await.waiting_on(db_index, key);
+            if list && self.key_len(db_index, "LLEN", key).await > 0 {
+                self.state.blocking_list.lock().await.notify(db_index, key, false);
+            }
+            if sorted_set && self.key_len(db_index, "ZCARD", key).await > 0 {
+                self.state.blocking_list.lock().await.notify(db_index, key, true);
+            }
+        }
+    }
+
+    /**
+     * 命令执行后需要唤醒阻塞客户端的数据库：当前数据库，MOVE 还包括目标数据库
+     *
+     * 在命令执行前调用，事务中的 SELECT 会改变之后命令所在的数据库
+     */
+    fn written_dbs(&self, command: &Command) -> Vec<usize> {
+        let current_db = self.session.get_current_db();
+        match command {
+            Command::Move(r#move) => vec![current_db, r#move.get_db_index()],
+            _ => vec![current_db],
+        }
+    }
+
+    /// 列表或有序集合的长度，类型不符时为 0
+    async fn key_len(&self, db_index: usize, command: &str, key: &[u8]) -> i64 {
+        let frame = Frame::Array(vec![
+            Frame::BulkString(command.to_string().into()),
+            Frame::BulkString(key.to_vec()),
+        ]);
+        let Ok(command) = Command::parse_from_frame(frame) else {
+            return 0;
+        };
+        let (sender, receiver) = oneshot::channel();
+        let message = DatabaseMessage::Command { sender, command };
+        if self.db_manager.get_sender(db_index).send(message).await.is_err() {
+            return 0;
+        }
+        match receiver.await {
+            Ok(Frame::Integer(len)) => len,
+            _ => 0,
+        }
+    }
+
+    /**
+     * 监视当前数据库中的键
+     * 
+     * 记录每个键在 WATCH 时的版本号，EXEC 时据此判断键是否被修改
+     * 
+     * @param keys 键名列表
+     */
+    pub async fn watch_keys(&mut self, keys: Vec<Vec<u8>>) -> Result<(), Error> {
+        let (sender, receiver) = oneshot::channel();
+        let message = DatabaseMessage::Watch { keys: keys.clone(), sender };
+        if let Err(e) = self.session.get_sender().send(message).await {
+            return Err(Error::msg(format!("Channel closed: {:?}", e)));
+        }
+        let versions = receiver.await?;
+        let db_index = self.session.get_current_db();
+        for (key, version) in keys.into_iter().zip(versions) {
+            self.session.add_watched_key(db_index, key, version);
+        }
+        Ok(())
+    }
+
+    /**
+     * 取消监视所有键（UNWATCH、EXEC、DISCARD 以及断开连接时调用）
+     */
+    pub async fn unwatch_keys(&mut self) {
+        let watched_keys = self.session.take_watched_keys();
+        for (db_index, keys) in Self::group_by_db(watched_keys) {
+            let keys = keys.into_iter().map(|(key, _)| key).collect();
+            let _ = self.db_manager.get_sender(db_index).send(DatabaseMessage::Unwatch(keys)).await;
+        }
+    }
+
+    /**
+     * 检查被监视的键是否被修改
+     *
+     * @param watched_keys 按数据库分组的被监视的键及其版本号
+     */
+    async fn watched_modified(&self, watched_keys: std::collections::BTreeMap<usize, Vec<(Vec<u8>, u64)>>) -> bool {
+        for (db_index, keys) in watched_keys {
+            let (sender, receiver) = oneshot::channel();
+            let message = DatabaseMessage::CheckWatched { keys, sender };
+            if self.db_manager.get_sender(db_index).send(message).await.is_err() {
+                return true;
+            }
+            if receiver.await.unwrap_or(true) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn group_by_db(watched_keys: Vec<(usize, Vec<u8>, u64)>) -> std::collections::BTreeMap<usize, Vec<(Vec<u8>, u64)>> {
+        let mut groups: std::collections::BTreeMap<usize, Vec<(Vec<u8>, u64)>> = std::collections::BTreeMap::new();
+        for (db_index, key, version) in watched_keys {
+            groups.entry(db_index).or_default().push((key, version));
+        }
+        groups
+    }
+
 
 }
\ No newline at end of file
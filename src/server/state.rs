@@ -1,13 +1,31 @@
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::{Arc, Mutex as SyncMutex, RwLock};
+use tokio::sync::{Mutex, RwLock as AsyncRwLock};
+use crate::acl::Acl;
+use crate::args::Args;
+use crate::config::Config;
+use crate::persistence::aof_file::AofFile;
+use crate::replication::master::ReplicationMaster;
+use crate::replication::{ReplicaLink, ReplicaStatus};
+use crate::store::db_manager::DatabaseManager;
 use crate::store::blocking::BlockingQueueManager;
+use crate::store::pubsub::PubSubManager;
+use crate::store::script_cache::ScriptCache;
+use crate::store::stream_blocking::StreamManager;
+use super::stats::ServerStats;
 
 /// 全局状态容器
 /// 
 /// 用于持有和管理服务器的所有全局/异步状态资源，如：
 /// - BlockingQueueManager (List BLPOP/BRPOP)
-/// - PubSubManager (SUBSCRIBE/PUBLISH) - 未来扩展
-/// - StreamManager (XREAD BLOCK) - 未来扩展
+/// - PubSubManager (SUBSCRIBE/PUBLISH)
+/// - StreamManager (XREAD BLOCK / XREADGROUP BLOCK)
+/// - ScriptCache (EVAL/EVALSHA/SCRIPT)
+/// - Acl (AUTH/ACL)
+/// - Config (CONFIG GET/SET)
+/// - ServerStats (INFO stats)
+/// - AofFile (BGREWRITEAOF / INFO persistence)
+/// - ReplicationMaster (PSYNC / INFO replication)
+/// - ReplicaLink (REPLICAOF / INFO replication)
 /// 
 /// 好处：
 /// 1. 解耦：Server 和 Handler 不需要直接持有具体的 Manager
@@ -16,14 +34,41 @@ use crate::store::blocking::BlockingQueueManager;
 pub struct ServerState {
     /// 列表阻塞管理器 (List BLPOP/BRPOP)
     pub blocking_list: Arc<Mutex<BlockingQueueManager>>,
-    
-    // 未来扩展：
-    // pub pubsub: Arc<Mutex<PubSubManager>>,
-    // pub blocking_stream: Arc<Mutex<StreamManager>>,
+
+    /// 发布订阅管理器 (SUBSCRIBE/PUBLISH)
+    pub pubsub: Arc<Mutex<PubSubManager>>,
+
+    /// 流阻塞管理器 (XREAD BLOCK / XREADGROUP BLOCK)
+    pub blocking_stream: Arc<Mutex<StreamManager>>,
+
+    /// Lua 脚本缓存 (EVAL/EVALSHA/SCRIPT)
+    pub scripts: Arc<Mutex<ScriptCache>>,
+
+    /// ACL 用户 (AUTH/ACL)，权限检查在每条命令执行前进行，使用同步读写锁避免跨 await 持有
+    pub acl: Arc<RwLock<Acl>>,
+
+    /// 运行时配置 (CONFIG GET/SET/REWRITE)
+    pub config: Arc<Config>,
+
+    /// 服务器统计 (INFO stats / CONFIG RESETSTAT)
+    pub stats: Arc<ServerStats>,
+
+    /// AOF 文件 (BGREWRITEAOF / INFO persistence)，未开启 AOF 时为 None
+    pub aof: Option<Arc<AofFile>>,
+
+    /// 写屏障：写命令从执行到写入 AOF、进入复制流期间持有读锁，
+    /// AOF 重写与全量同步生成快照时持有写锁，保证每条写命令要么包含在快照中，要么在快照之后传播
+    pub write_barrier: Arc<AsyncRwLock<()>>,
+
+    /// 主节点复制流 (PSYNC / INFO replication)
+    pub replication: Arc<ReplicationMaster>,
+
+    /// 作为从节点时到主节点的复制任务 (REPLICAOF / INFO replication)，为 None 时是主节点
+    pub replica: Arc<SyncMutex<Option<ReplicaLink>>>,
 }
 
 impl ServerState {
-    pub fn new() -> Self {
+    pub fn new(args: &Args, config: Arc<Config>, aof: Option<Arc<AofFile>>, write_barrier: Arc<AsyncRwLock<()>>) -> Self {
         let blocking_list = Arc::new(Mutex::new(BlockingQueueManager::new()));
         
         // 启动超时清理任务
@@ -37,17 +82,64 @@ impl ServerState {
             }
         });
 
+        // 加载 ACL 文件：文件不存在时使用默认用户，文件内容有误时拒绝启动
+        let mut acl = Acl::new(args);
+        if let Some(file) = acl.file().cloned() {
+            if file.exists() {
+                if let Err(e) = acl.load() {
+                    log::error!("Failed to load ACL file: {}", e);
+                    std::process::exit(1);
+                }
+            } else {
+                log::warn!("ACL file {} not found, starting with the default user", file.display());
+            }
+        }
+
+        let replication = Arc::new(ReplicationMaster::new(config.clone()));
+        replication.start_ping();
+
         ServerState {
             blocking_list,
+            pubsub: Arc::new(Mutex::new(PubSubManager::new())),
+            blocking_stream: Arc::new(Mutex::new(StreamManager::new())),
+            scripts: Arc::new(Mutex::new(ScriptCache::new())),
+            acl: Arc::new(RwLock::new(acl)),
+            config,
+            stats: Arc::new(ServerStats::default()),
+            aof,
+            write_barrier,
+            replication,
+            replica: Arc::new(SyncMutex::new(None)),
         }
     }
 
+    /// 作为从节点时的复制状态，主节点返回 None
+    pub fn replica_status(&self) -> Option<Arc<ReplicaStatus>> {
+        self.replica.lock().unwrap().as_ref().map(|link| link.status().clone())
+    }
+
+    pub fn is_replica(&self) -> bool {
+        self.replica.lock().unwrap().is_some()
+    }
+
+    /**
+     * 切换复制角色
+     *
+     * 停止当前的复制任务（如果有），指定主节点时开始复制新的主节点
+     *
+     * @param master 主节点地址，None 表示成为主节点
+     */
+    pub fn replicaof(&self, master: Option<(String, u16)>, args: Arc<Args>, db_manager: Arc<DatabaseManager>) {
+        let link = master.map(|(host, port)| ReplicaLink::start(host, port, args, db_manager));
+        *self.replica.lock().unwrap() = link;
+    }
+
     /// 清理会话相关的所有资源
     /// 
     /// 当客户端断开连接时调用，负责清理该会话在各个子系统中的状态
     /// - BlockingQueueManager: 清理未完成的阻塞请求
-    /// - (未来) PubSubManager: 取消订阅
-    /// - (未来) StreamManager: 清理消费者状态
+    /// - PubSubManager: 取消订阅
+    /// - StreamManager: 清理未完成的 XREAD / XREADGROUP 阻塞请求
     pub async fn cleanup_session(&self, session_id: usize) {
         // 1. 清理 List 阻塞请求
         {
@@ -55,9 +147,16 @@ impl ServerState {
             blocking_manager.cleanup_session(session_id);
         }
         
-        // 2. 未来：清理 Pub/Sub
-        // if let Some(pubsub) = &self.pubsub {
-        //     pubsub.lock().await.unsubscribe_all(session_id);
-        // }
+        // 2. 清理 Pub/Sub 订阅
+        {
+            let mut pubsub = self.pubsub.lock().await;
+            pubsub.cleanup_session(session_id);
+        }
+
+        // 3. 清理 Stream 阻塞请求
+        {
+            let mut blocking_stream = self.blocking_stream.lock().await;
+            blocking_stream.cleanup_session(session_id);
+        }
     }
 }
@@ -0,0 +1,47 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/**
+ * 服务器统计（INFO stats）
+ *
+ * 可以通过 CONFIG RESETSTAT 清零
+ */
+#[derive(Default)]
+pub struct ServerStats {
+    total_connections_received: AtomicU64,
+    total_commands_processed: AtomicU64,
+    rejected_connections: AtomicU64,
+}
+
+impl ServerStats {
+
+    pub fn connection_received(&self) {
+        self.total_connections_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn command_processed(&self) {
+        self.total_commands_processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn connection_rejected(&self) {
+        self.rejected_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn total_connections_received(&self) -> u64 {
+        self.total_connections_received.load(Ordering::Relaxed)
+    }
+
+    pub fn total_commands_processed(&self) -> u64 {
+        self.total_commands_processed.load(Ordering::Relaxed)
+    }
+
+    pub fn rejected_connections(&self) -> u64 {
+        self.rejected_connections.load(Ordering::Relaxed)
+    }
+
+    /// 清零所有统计
+    pub fn reset(&self) {
+        self.total_connections_received.store(0, Ordering::Relaxed);
+        self.total_commands_processed.store(0, Ordering::Relaxed);
+        self.rejected_connections.store(0, Ordering::Relaxed);
+    }
+}
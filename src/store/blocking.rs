@@ -1,14 +1,24 @@
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 use std::sync::{Arc, Mutex};
+use anyhow::Error;
 use tokio::sync::oneshot;
 use crate::frame::Frame;
 
 /// 阻塞方向
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BlockDirection {
-    Left,   // BLPOP
-    Right,  // BRPOP
+    Left,   // BLPOP、BLMOVE LEFT、BLMPOP LEFT
+    Right,  // BRPOP、BRPOPLPUSH、BLMOVE RIGHT、BLMPOP RIGHT
+    Min,    // BZPOPMIN、BZMPOP MIN
+    Max,    // BZPOPMAX、BZMPOP MAX
+}
+
+impl BlockDirection {
+    /// 是否为等待有序集合的请求
+    pub fn is_sorted_set(&self) -> bool {
+        matches!(self, BlockDirection::Min | BlockDirection::Max)
+    }
 }
 
 /// 共享的发送端，用于多键阻塞
@@ -18,7 +28,8 @@ type SharedSender = Arc<Mutex<Option<oneshot::Sender<Frame>>>>;
 /// 阻塞请求信息
 pub struct BlockingRequest {
     pub session_id: usize,
-    pub key: String,
+    pub db_index: usize,
+    pub key: Vec<u8>,
     pub direction: BlockDirection,
     pub timeout: Option<Duration>,
     pub response_sender: SharedSender,
@@ -27,12 +38,14 @@ pub struct BlockingRequest {
 
 /// 阻塞队列管理器
 /// 
-/// 管理所有等待 BLPOP/BRPOP 的客户端请求
+/// 管理所有等待列表（BLPOP、BLMOVE、BLMPOP 等）与有序集合（BZPOPMIN、BZMPOP 等）的客户端请求
+/// 
+/// 键被写入后只通知等待者重新执行弹出，不转交数据；同一个键的等待者按开始阻塞的时间排队
 pub struct BlockingQueueManager {
     // key -> 等待该键的请求队列（FIFO）
-    waiting_requests: HashMap<String, VecDeque<BlockingRequest>>,
+    waiting_requests: HashMap<Vec<u8>, VecDeque<BlockingRequest>>,
     // session_id -> keys 映射（用于客户端断开时快速清理）
-    session_to_keys: HashMap<usize, Vec<String>>,
+    session_to_keys: HashMap<usize, Vec<Vec<u8>>>,
 }
 
 impl BlockingQueueManager {
@@ -45,19 +58,21 @@ impl BlockingQueueManager {
     
     /// 注册阻塞请求
     /// 
+    /// 按 created_at 插入等待队列，被唤醒后没有弹出成功而重新注册的请求仍保持原来的位置
     /// 返回一个 receiver，用于等待结果
     pub fn register_blocking_request(
         &mut self,
-        keys: Vec<String>,
+        keys: Vec<Vec<u8>>,
         session_id: usize,
+        db_index: usize,
         direction: BlockDirection,
         timeout: Option<Duration>,
+        created_at: Instant,
     ) -> oneshot::Receiver<Frame> {
         let (sender, receiver) = oneshot::channel();
         
         // 创建共享发送端
         let shared_sender = Arc::new(Mutex::new(Some(sender)));
-        let created_at = Instant::now();
         
         // 记录该会话监听的所有 key
         let mut session_keys = Vec::new();
@@ -66,6 +81,7 @@ impl BlockingQueueManager {
         for key in keys {
             let request = BlockingRequest {
                 session_id,
+                db_index,
                 key: key.clone(),
                 direction,
                 timeout,
@@ -74,10 +90,11 @@ impl BlockingQueueManager {
             };
             
             // 添加到等待队列（FIFO）
-            self.waiting_requests
+            let requests = self.waiting_requests
                 .entry(key.clone())
-                .or_insert_with(VecDeque::new)
-                .push_back(request);
+                .or_default();
+            let index = requests.partition_point(|req| req.created_at <= created_at);
+            requests.insert(index, request);
                 
             session_keys.push(key);
         }
@@ -88,84 +105,49 @@ impl BlockingQueueManager {
         receiver
     }
     
-    /// 尝试唤醒等待的客户端
+    /// 唤醒等待该键的第一个客户端（键被写入且非空时调用）
     /// 
-    /// 当 LPUSH/RPUSH 执行时调用此方法
-    /// 返回：
-    /// - Some(Frame): 成功唤醒一个客户端，返回要发送给该客户端的结果
-    /// - None: 没有等待的客户端
-    pub fn try_wakeup(
-        &mut self,
-        key: &str,
-        direction: BlockDirection,
-        value: String,
-    ) -> Option<(usize, Frame)> {
-        // 需要在一个循环中尝试唤醒，因为可能遇到"已经失效"的请求（被其他key唤醒了）
-        loop {
-            let mut found_session_id = None;
-            
-            if let Some(requests) = self.waiting_requests.get_mut(key) {
-                // 查找匹配方向的第一个请求（FIFO）
-                let mut found_index = None;
-                for (i, req) in requests.iter().enumerate() {
-                    if req.direction == direction {
-                        found_index = Some(i);
-                        break;
-                    }
-                }
-                
-                if let Some(index) = found_index {
-                    let request = requests.remove(index).unwrap();
-                    
-                    // 尝试获取发送锁
-                    // 如果能 take 到 sender，说明我们是第一个唤醒它的
-                    let mut sender_guard = request.response_sender.lock().unwrap();
-                    if let Some(sender) = sender_guard.take() {
-                        // 构建响应：Array[key, value]
-                        let response = Frame::Array(vec![
-                            Frame::BulkString(key.to_string()),
-                            Frame::BulkString(value),
-                        ]);
-                        
-                        // 发送结果给等待的客户端
-                        let _ = sender.send(response.clone());
-                        
-                        found_session_id = Some((request.session_id, response));
-                        
-                        // 如果队列为空，移除该键
-                        if requests.is_empty() {
-                            self.waiting_requests.remove(key);
-                        }
-                    } else {
-                        // 这个请求已经被其他 key 唤醒了（sender 为 None）
-                        // 它是无效的，我们已经把它移除了，继续循环尝试下一个
-                        if requests.is_empty() {
-                            self.waiting_requests.remove(key);
-                        }
-                        continue;
-                    }
-                }
+    /// 弹出的是整个列表或集合一端的元素，因此不转交数据，只通知等待者重新执行弹出；
+    /// 等待者弹出后键仍非空时，再唤醒下一个等待者
+    /// 
+    /// @param sorted_set 为 true 时唤醒等待有序集合的客户端，否则唤醒等待列表的客户端
+    /// @return 是否唤醒了客户端
+    pub fn notify(&mut self, db_index: usize, key: &[u8], sorted_set: bool) -> bool {
+        let Some(requests) = self.waiting_requests.get_mut(key) else {
+            return false;
+        };
+        let mut woken = None;
+        while let Some(index) = requests.iter().position(|req| req.db_index == db_index && req.direction.is_sorted_set() == sorted_set) {
+            let request = requests.remove(index).unwrap();
+            // 已经被其他 key 唤醒的请求直接丢弃
+            let sender = request.response_sender.lock().unwrap().take();
+            if let Some(sender) = sender {
+                let _ = sender.send(Frame::Ok);
+                woken = Some(request.session_id);
+                break;
             }
-            
-            // 如果成功唤醒了一个客户端
-            if let Some((session_id, response)) = found_session_id {
-                // 清理该会话在其他 key 上的等待记录
+        }
+        if requests.is_empty() {
+            self.waiting_requests.remove(key);
+        }
+        match woken {
+            Some(session_id) => {
                 self.cleanup_session(session_id);
-                return Some((session_id, response));
-            } else {
-                // 没有找到任何有效请求
-                return None;
-            }
+                true
+            },
+            None => false,
         }
     }
-    
-    /// 检查是否有等待该键的客户端
-    pub fn has_waiting(&self, key: &str, direction: BlockDirection) -> bool {
-        if let Some(requests) = self.waiting_requests.get(key) {
-            requests.iter().any(|req| req.direction == direction)
-        } else {
-            false
-        }
+
+    /// 等待该键的客户端类型：(是否有等待列表的客户端, 是否有等待有序集合的客户端)
+    pub fn waiting_on(&self, db_index: usize, key: &[u8]) -> (bool, bool) {
+        let Some(requests) = self.waiting_requests.get(key) else {
+            return (false, false);
+        };
+        let mut requests = requests.iter().filter(|req| req.db_index == db_index);
+        let list = requests.clone().any(|req| !req.direction.is_sorted_set());
+        let sorted_set = requests.any(|req| req.direction.is_sorted_set());
+        (list, sorted_set)
     }
     
     /// 清理客户端的所有阻塞请求（客户端断开时调用，或被唤醒后调用）
@@ -230,3 +212,26 @@ impl BlockingQueueManager {
         }
     }
 }
+
+/**
+ * 解析阻塞命令的超时时间（秒，可以是小数）
+ *
+ * 与 Redis 相同，超时时间换算为毫秒后不能超过 i64 的范围，保证计算截止时间时不会溢出
+ *
+ * @return 0 表示永久阻塞，返回 None
+ */
+pub fn parse_timeout(text: &str) -> Result<Option<Duration>, Error> {
+    let timeout = text.parse::<f64>().ok()
+        .filter(|timeout| timeout.is_finite())
+        .ok_or_else(|| Error::msg("ERR timeout is not a float or out of range"))?;
+    if timeout < 0.0 {
+        return Err(Error::msg("ERR timeout is negative"));
+    }
+    if timeout == 0.0 {
+        return Ok(None);
+    }
+    match Duration::try_from_secs_f64(timeout) {
+        Ok(timeout) if timeout.as_millis() <= i64::MAX as u128 => Ok(Some(timeout)),
+        _ => Err(Error::msg("ERR timeout is out of range")),
+    }
+}
@@ -1,24 +1,28 @@
 use std::{
-    collections::{HashMap, HashSet}, sync::{atomic::{AtomicU64, Ordering}}
+    collections::{HashMap, HashSet, VecDeque}, sync::{atomic::{AtomicU64, Ordering}, Arc}
 };
 
 use anyhow::Error;
 use bincode::{Decode, Encode};
 use std::time::{SystemTime, UNIX_EPOCH};
+use mlua::Lua;
 use tokio::sync::{
     mpsc::{channel, Receiver, Sender},
-    oneshot,
+    oneshot, Mutex,
 };
 
-use crate::{command::Command, frame::Frame, tools::pattern};
+use crate::{acl::User, cmds::scripting::{self, eval::Eval}, command::Command, config::Config, frame::Frame, tools::pattern};
 use crate::store::hyperloglog::HyperLogLog;
+use crate::store::memory::{self, EvictionPolicy, KeyMeta, MemoryStats};
+use crate::store::script_status::{ScriptStatus, BUSY_ERROR};
 use crate::store::sorted_set::SortedSet;
+use crate::store::stream::Stream;
 
 // 数据库快照数据结构
 #[derive(Clone, Encode, Decode)]
 pub struct DatabaseSnapshot {
-    pub expire_records: HashMap<String, SystemTime>,
-    pub records: HashMap<String, Structure>,
+    pub expire_records: HashMap<Vec<u8>, SystemTime>,
+    pub records: HashMap<Vec<u8>, Structure>,
 }
 
 /**
@@ -33,7 +37,76 @@ pub enum DatabaseMessage {
     Snapshot(oneshot::Sender<DatabaseSnapshot>),
     Restore(DatabaseSnapshot),
     CleanExpired, 
-    ResetChanges,
+    /// 保存成功后扣除已经写入快照的变更数
+    ResetChanges(u64),
+    Watch { keys: Vec<Vec<u8>>, sender: oneshot::Sender<Vec<u64>> },
+    Unwatch(Vec<Vec<u8>>),
+    CheckWatched { keys: Vec<(Vec<u8>, u64)>, sender: oneshot::Sender<bool> },
+    Keyspace(oneshot::Sender<(usize, usize)>),
+    EvictionCandidate { policy: EvictionPolicy, samples: usize, sender: oneshot::Sender<Option<(Vec<u8>, u64)>> },
+    Evict { key: Vec<u8>, sender: oneshot::Sender<bool> },
+    /// 执行脚本，user 为执行脚本的用户（脚本中的命令同样需要检查权限）
+    Eval { eval: Eval, user: Option<User>, sender: oneshot::Sender<(Frame, Vec<Frame>)> },
+    /// 执行事务中的一批命令，watched 中的键被修改过时不执行任何命令并回复 None
+    Exec { watched: Vec<(Vec<u8>, u64)>, commands: Vec<Command>, sender: oneshot::Sender<Option<Vec<Frame>>> },
+}
+
+/**
+ * 数据库的消息队列
+ *
+ * 脚本超时后 Db 线程仍在执行脚本，由 Lua 指令钩子通过 reject_busy 取出排队的消息：
+ * 命令与脚本直接回复 BUSY 错误，其余内部消息暂存，脚本结束后按原顺序处理
+ *
+ * @param receiver 消息接收端
+ * @param deferred 脚本执行期间暂存的内部消息
+ */
+#[derive(Clone)]
+pub struct Mailbox {
+    receiver: Arc<Mutex<Receiver<DatabaseMessage>>>,
+    deferred: Arc<std::sync::Mutex<VecDeque<DatabaseMessage>>>,
+}
+
+impl Mailbox {
+
+    fn new(receiver: Receiver<DatabaseMessage>) -> Self {
+        Mailbox {
+            receiver: Arc::new(Mutex::new(receiver)),
+            deferred: Arc::new(std::sync::Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// 接收下一条消息，优先处理暂存的消息
+    async fn recv(&self) -> Option<DatabaseMessage> {
+        let deferred = self.deferred.lock().unwrap().pop_front();
+        if deferred.is_some() {
+            return deferred;
+        }
+        self.receiver.lock().await.recv().await
+    }
+
+    /**
+     * 脚本超时期间，对排队的命令与脚本回复 BUSY 错误
+     */
+    pub fn reject_busy(&self) {
+        let mut receiver = match self.receiver.try_lock() {
+            Ok(receiver) => receiver,
+            Err(_) => return,
+        };
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DatabaseMessage::Command { sender, .. } => {
+                    let _ = sender.send(Frame::Error(BUSY_ERROR.to_string()));
+                },
+                DatabaseMessage::Eval { sender, .. } => {
+                    let _ = sender.send((Frame::Error(BUSY_ERROR.to_string()), Vec::new()));
+                },
+                DatabaseMessage::Exec { commands, sender, .. } => {
+                    let _ = sender.send(Some(vec![Frame::Error(BUSY_ERROR.to_string()); commands.len()]));
+                },
+                message => self.deferred.lock().unwrap().push_back(message),
+            }
+        }
+    }
 }
 
 impl Default for DatabaseSnapshot {
@@ -47,16 +120,22 @@ impl Default for DatabaseSnapshot {
 
 
 
+/**
+ * 数据结构
+ *
+ * 键、字符串值、列表元素、集合与有序集合成员、哈希字段及其值均以字节存储，保证二进制安全
+ */
 #[derive(Clone, Encode, Decode)]
 pub enum Structure {
-    String(String),
-    Hash(HashMap<String, String>),
+    String(Vec<u8>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
     SortedSet(SortedSet),
     VectorCollection(Vector),
-    Set(HashSet<String>),
-    List(Vec<String>),
+    Set(HashSet<Vec<u8>>),
+    List(Vec<Vec<u8>>),
     Json(String),  // 使用字符串存储JSON数据
     HyperLogLog(HyperLogLog),
+    Stream(Stream),
 }
 
 #[derive(Clone, Encode, Decode)]
@@ -66,21 +145,48 @@ pub struct Vector {
     pub norms: HashMap<String, f32>,
 }
 
+/**
+ * 被 WATCH 的键
+ *
+ * @param version 键的版本号，每次修改（写入、删除、过期）递增
+ * @param watchers 正在 WATCH 该键的客户端数量，归零时移除
+ */
+struct WatchedKey {
+    version: u64,
+    watchers: usize,
+}
+
 /**
  * 数据库
  * 
- * @param receiver
+ * @param mailbox 消息队列
  * @param sender
  * @param expire_records
  * @param records
  * @param modify_count
+ * @param watched_keys 被 WATCH 的键及其版本号（只跟踪被监视的键）
+ * @param memory 全局内存统计
+ * @param key_meta 键的元数据（估算的内存用量、访问时间与访问频率）
+ * @param sample_keys 所有键的数组，用于淘汰时随机采样
+ * @param dirty_keys 当前命令通过 get_mut 修改的键，命令执行后重新估算内存
+ * @param script 正在执行的脚本的状态（SCRIPT KILL 与超时检查）
+ * @param lua 执行脚本的 Lua 虚拟机，第一次执行脚本时创建，之后重复使用
+ * @param config 运行时配置（lua-time-limit）
  */
 pub struct Db {
-    receiver: Receiver<DatabaseMessage>,
+    mailbox: Mailbox,
     pub sender: Sender<DatabaseMessage>,
-    pub expire_records: HashMap<String, SystemTime>,
-    pub records: HashMap<String, Structure>,
+    pub expire_records: HashMap<Vec<u8>, SystemTime>,
+    pub records: HashMap<Vec<u8>, Structure>,
     pub changes: AtomicU64,
+    watched_keys: HashMap<Vec<u8>, WatchedKey>,
+    memory: Arc<MemoryStats>,
+    key_meta: HashMap<Vec<u8>, KeyMeta>,
+    sample_keys: Vec<Vec<u8>>,
+    dirty_keys: Vec<Vec<u8>>,
+    pub script: Arc<ScriptStatus>,
+    lua: Option<Lua>,
+    config: Arc<Config>,
 }
 
 impl Db {
@@ -88,21 +194,33 @@ impl Db {
     /**
      * 创建数据库
      * 
-     * @param args 数据库配置
+     * @param snapshot 初始数据
+     * @param memory 全局内存统计
+     * @param config 运行时配置
      */
-    pub fn new(snapshot: DatabaseSnapshot) -> Self {
+    pub fn new(snapshot: DatabaseSnapshot, memory: Arc<MemoryStats>, config: Arc<Config>) -> Self {
 
         let (sender, receiver) = channel(1024);
         let expire_records = snapshot.expire_records;
         let records = snapshot.records;
 
-        Db {
+        let mut db = Db {
             records,
             expire_records,
             changes: AtomicU64::new(0),
-            receiver,
+            mailbox: Mailbox::new(receiver),
             sender,
-        }
+            watched_keys: HashMap::new(),
+            memory,
+            key_meta: HashMap::new(),
+            sample_keys: Vec::new(),
+            dirty_keys: Vec::new(),
+            script: Arc::new(ScriptStatus::default()),
+            lua: None,
+            config,
+        };
+        db.rebuild_key_meta();
+        db
     }
 
     /**
@@ -114,15 +232,48 @@ impl Db {
      */
     pub async fn run(&mut self) {
         loop {
-            match self.receiver.recv().await {
+            match self.mailbox.recv().await {
                 Some(DatabaseMessage::Command { sender, command }) => {
-                    match self.handle_command(command) {
+                    let result = self.handle_command(command);
+                    self.refresh_dirty_keys();
+                    match result {
                         Ok(f) => {
                             let _ = sender.send(f);
                         },
                         Err(e) => eprintln!("Error applying command: {:?}", e),
                     }
                 },
+                Some(DatabaseMessage::Eval { eval, user, sender }) => {
+                    let lua = match self.lua.take() {
+                        Some(lua) => Ok(lua),
+                        None => scripting::create_vm(self.script.clone(), self.config.clone(), self.mailbox.clone()),
+                    };
+                    let result = match lua {
+                        Ok(lua) => {
+                            // 脚本可能长时间占用当前线程，交出工作线程，其他连接仍然可以收到 BUSY 回复或执行 SCRIPT KILL
+                            let result = tokio::task::block_in_place(|| eval.execute(self, &lua, user.as_ref()));
+                            self.lua = Some(lua);
+                            result
+                        },
+                        Err(e) => (Frame::Error(format!("ERR {}", e)), Vec::new()),
+                    };
+                    self.refresh_dirty_keys();
+                    let _ = sender.send(result);
+                },
+                Some(DatabaseMessage::Exec { watched, commands, sender }) => {
+                    // 检查与执行在同一条消息中完成，期间其他客户端的命令不会穿插执行
+                    if watched.iter().any(|(key, version)| self.is_modified(key, *version)) {
+                        let _ = sender.send(None);
+                        continue;
+                    }
+                    let mut results = Vec::with_capacity(commands.len());
+                    for command in commands {
+                        let result = self.handle_command(command);
+                        self.refresh_dirty_keys();
+                        results.push(result.unwrap_or_else(|e| Frame::Error(e.to_string())));
+                    }
+                    let _ = sender.send(Some(results));
+                },
                 Some(DatabaseMessage::CleanExpired) => {
                     self.clean_expired_keys();
                 },
@@ -131,6 +282,7 @@ impl Db {
                     let _ = sender.send(count);
                 },
                 Some(DatabaseMessage::Restore(snapshot)) => {
+                    self.touch_all();
                     self.records = snapshot.records;
                     self.expire_records = snapshot.expire_records;
                     // 重置所有 HyperLogLog 的缓存
@@ -139,9 +291,10 @@ impl Db {
                             hll.reset_cache();
                         }
                     }
+                    self.rebuild_key_meta();
                 },
-                Some(DatabaseMessage::ResetChanges) => {
-                    self.changes.store(0, Ordering::Relaxed);
+                Some(DatabaseMessage::ResetChanges(saved)) => {
+                    let _ = self.changes.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| Some(count.saturating_sub(saved)));
                 },
                 Some(DatabaseMessage::Snapshot(sender)) => {
                     let snapshot = DatabaseSnapshot {
@@ -150,12 +303,38 @@ impl Db {
                     };
                     let _ = sender.send(snapshot);
                 },
+                Some(DatabaseMessage::Watch { keys, sender }) => {
+                    let versions = keys.iter().map(|key| self.watch(key)).collect();
+                    let _ = sender.send(versions);
+                },
+                Some(DatabaseMessage::Unwatch(keys)) => {
+                    for key in keys {
+                        self.unwatch(&key);
+                    }
+                },
+                Some(DatabaseMessage::CheckWatched { keys, sender }) => {
+                    let modified = keys.iter().any(|(key, version)| self.is_modified(key, *version));
+                    let _ = sender.send(modified);
+                },
+                Some(DatabaseMessage::Keyspace(sender)) => {
+                    let _ = sender.send((self.records.len(), self.expire_records.len()));
+                },
+                Some(DatabaseMessage::EvictionCandidate { policy, samples, sender }) => {
+                    let _ = sender.send(self.eviction_candidate(policy, samples));
+                },
+                Some(DatabaseMessage::Evict { key, sender }) => {
+                    let evicted = self.remove(&key).is_some();
+                    if evicted {
+                        self.memory.record_eviction();
+                    }
+                    let _ = sender.send(evicted);
+                },
                 None => {}
             }
         }
     }
 
-    fn handle_command(&mut self, command: Command) -> Result<Frame, Error> {
+    pub fn handle_command(&mut self, command: Command) -> Result<Frame, Error> {
         match command {
             Command::Set(set) => set.apply(self),
             Command::Get(get) => get.apply(self),
@@ -205,6 +384,16 @@ impl Db {
             Command::Lindex(lindex) => lindex.apply(self),
             Command::Lpop(lpop) => lpop.apply(self),
             Command::Rpop(rpop) => rpop.apply(self),
+            Command::Linsert(linsert) => linsert.apply(self),
+            Command::Lpos(lpos) => lpos.apply(self),
+            Command::Lmove(lmove) => lmove.apply(self),
+            Command::Rpoplpush(rpoplpush) => rpoplpush.apply(self),
+            Command::Lmpop(lmpop) => lmpop.apply(self),
+            Command::Blpop(blpop) => blpop.apply(self),
+            Command::Brpop(brpop) => brpop.apply(self),
+            Command::Blmove(blmove) => blmove.apply(self),
+            Command::Brpoplpush(brpoplpush) => brpoplpush.apply(self),
+            Command::Blmpop(blmpop) => blmpop.apply(self),
             Command::Llen(llen) => llen.apply(self),
             Command::Sadd(sadd) => sadd.apply(self),
             Command::Scard(scard) => scard.apply(self),
@@ -240,17 +429,57 @@ impl Db {
             Command::Decrby(decrby) => decrby.apply(self),
             Command::ExpireAt(expireat) => expireat.apply(self),
             Command::PexpireAt(pexpireat) => pexpireat.apply(self),
+            Command::Dump(dump) => dump.apply(self),
+            Command::Restore(restore) => restore.apply(self),
             Command::Pexpire(pexpire) => pexpire.apply(self),
             Command::Lrange(lrange) => lrange.apply(self),
             Command::GetSet(getset) => getset.apply(self),
-            Command::Info(info) => info.apply(self),
             Command::Scan(scan) => scan.apply(self),
             Command::Sscan(sscan) => sscan.apply(self),
             Command::Msetnx(msetnx) => msetnx.apply(self),
             Command::Zrange(zrange) => zrange.apply(self),
+            Command::Zrevrange(zrevrange) => zrevrange.apply(self),
+            Command::Zrangebyscore(zrangebyscore) => zrangebyscore.apply(self),
+            Command::Zrevrangebyscore(zrevrangebyscore) => zrevrangebyscore.apply(self),
+            Command::Zrangebylex(zrangebylex) => zrangebylex.apply(self),
+            Command::Zrevrangebylex(zrevrangebylex) => zrevrangebylex.apply(self),
+            Command::Zrangestore(zrangestore) => zrangestore.apply(self),
+            Command::Zrevrank(zrevrank) => zrevrank.apply(self),
+            Command::Zremrangebyrank(zremrangebyrank) => zremrangebyrank.apply(self),
+            Command::Zremrangebyscore(zremrangebyscore) => zremrangebyscore.apply(self),
+            Command::Zremrangebylex(zremrangebylex) => zremrangebylex.apply(self),
+            Command::Zmscore(zmscore) => zmscore.apply(self),
+            Command::Zrandmember(zrandmember) => zrandmember.apply(self),
+            Command::Zscan(zscan) => zscan.apply(self),
+            Command::Zunion(zunion) => zunion.apply(self),
+            Command::Zunionstore(zunionstore) => zunionstore.apply(self),
+            Command::Zinter(zinter) => zinter.apply(self),
+            Command::Zinterstore(zinterstore) => zinterstore.apply(self),
+            Command::Zintercard(zintercard) => zintercard.apply(self),
+            Command::Zdiff(zdiff) => zdiff.apply(self),
+            Command::Zdiffstore(zdiffstore) => zdiffstore.apply(self),
+            Command::Zpopmin(zpopmin) => zpopmin.apply(self),
+            Command::Zpopmax(zpopmax) => zpopmax.apply(self),
+            Command::Zmpop(zmpop) => zmpop.apply(self),
+            Command::Bzpopmin(bzpopmin) => bzpopmin.apply(self),
+            Command::Bzpopmax(bzpopmax) => bzpopmax.apply(self),
+            Command::Bzmpop(bzmpop) => bzmpop.apply(self),
             Command::Pfadd(pfadd) => pfadd.apply(self),
             Command::Pfcount(pfcount) => pfcount.apply(self),
             Command::Pfmerge(pfmerge) => pfmerge.apply(self),
+            Command::Xadd(xadd) => xadd.apply(self),
+            Command::Xlen(xlen) => xlen.apply(self),
+            Command::Xrange(xrange) => xrange.apply(self),
+            Command::Xrevrange(xrevrange) => xrevrange.apply(self),
+            Command::Xdel(xdel) => xdel.apply(self),
+            Command::Xtrim(xtrim) => xtrim.apply(self),
+            Command::Xread(xread) => xread.apply(self),
+            Command::Xgroup(xgroup) => xgroup.apply(self),
+            Command::Xreadgroup(xreadgroup) => xreadgroup.apply(self),
+            Command::Xack(xack) => xack.apply(self),
+            Command::Xpending(xpending) => xpending.apply(self),
+            Command::Xclaim(xclaim) => xclaim.apply(self),
+            Command::Xautoclaim(xautoclaim) => xautoclaim.apply(self),
             _ => Err(Error::msg("Unknown command")),
         }
     }
@@ -260,9 +489,12 @@ impl Db {
      * @param key 键名
      * @param value 值
      */
-    pub fn insert(&mut self, key: String, value: Structure) {
+    pub fn insert(&mut self, key: Vec<u8>, value: Structure) {
         self.changes.fetch_add(1, Ordering::Relaxed);
-        self.records.insert(key, value);
+        self.touch(&key);
+        let size = memory::estimate_entry(&key, &value);
+        self.records.insert(key.clone(), value);
+        self.track_size(key, size);
     }
 
     /**
@@ -270,18 +502,30 @@ impl Db {
      *
      * @param key 键名
      */
-    pub fn get(&mut self, key: &str) -> Option<&Structure> {
+    pub fn get(&mut self, key: &[u8]) -> Option<&Structure> {
         self.expire_if_needed(key);
+        if let Some(meta) = self.key_meta.get_mut(key) {
+            meta.touch();
+        }
         self.records.get(key)
     }
 
     /**
      * 获取键值【引用】
      *
+     * 键被视为已修改，被 WATCH 的键版本号递增；可能不修改数据的命令先用 get 检查，确实需要修改时再调用
+     *
      * @param key 键名
      */
-    pub fn get_mut(&mut self, key: &str) -> Option<&mut Structure> {
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut Structure> {
         self.expire_if_needed(key);
+        if self.records.contains_key(key) {
+            self.touch(key);
+            if let Some(meta) = self.key_meta.get_mut(key) {
+                meta.touch();
+            }
+            self.dirty_keys.push(key.to_vec());
+        }
         self.records.get_mut(key)
     }
 
@@ -291,8 +535,9 @@ impl Db {
      * @param key 键名
      * @param ttl 距离现在多少【毫秒】后过期
      */
-    pub fn expire(&mut self, key: String, ttl: u64) {
+    pub fn expire(&mut self, key: Vec<u8>, ttl: u64) {
         let expire_time = SystemTime::now() + std::time::Duration::from_millis(ttl);
+        self.touch(&key);
         self.expire_records.insert(key, expire_time);
     }
 
@@ -302,9 +547,11 @@ impl Db {
      * @param key 键名
      * @return 如果删除成功，返回被删除的值；如果删除失败，返回 None
      */
-    pub fn remove(&mut self, key: &str) -> Option<Structure> {
+    pub fn remove(&mut self, key: &[u8]) -> Option<Structure> {
         if self.records.contains_key(key) {
             self.changes.fetch_add(1, Ordering::Relaxed);
+            self.touch(key);
+            self.untrack(key);
             self.expire_records.remove(key);
             self.records.remove(key)
         } else {
@@ -312,6 +559,227 @@ impl Db {
         }
     }
 
+    /**
+     * 清空数据库
+     */
+    pub fn clear(&mut self) {
+        if !self.records.is_empty() {
+            self.changes.fetch_add(1, Ordering::Relaxed);
+        }
+        self.touch_all();
+        self.expire_records.clear();
+        self.records.clear();
+        self.clear_key_meta();
+    }
+
+    /**
+     * 记录键的内存用量，新键同时加入采样数组
+     *
+     * @param key 键名
+     * @param size 估算的内存用量
+     */
+    fn track_size(&mut self, key: Vec<u8>, size: usize) {
+        match self.key_meta.get_mut(&key) {
+            Some(meta) => {
+                self.memory.decrease(meta.size);
+                self.memory.increase(size);
+                meta.size = size;
+                meta.touch();
+            },
+            None => {
+                self.memory.increase(size);
+                self.key_meta.insert(key.clone(), KeyMeta::new(size, self.sample_keys.len()));
+                self.sample_keys.push(key);
+            },
+        }
+    }
+
+    /**
+     * 移除键的元数据并释放其内存用量
+     *
+     * @param key 键名
+     */
+    fn untrack(&mut self, key: &[u8]) {
+        if let Some(meta) = self.key_meta.remove(key) {
+            self.memory.decrease(meta.size);
+            self.sample_keys.swap_remove(meta.slot);
+            // 原来的最后一个键被移动到了空出的位置
+            if let Some(moved) = self.sample_keys.get(meta.slot) {
+                if let Some(moved_meta) = self.key_meta.get_mut(moved) {
+                    moved_meta.slot = meta.slot;
+                }
+            }
+        }
+    }
+
+    /**
+     * 重新估算本次命令修改过的键的内存用量
+     */
+    fn refresh_dirty_keys(&mut self) {
+        let mut dirty_keys = std::mem::take(&mut self.dirty_keys);
+        dirty_keys.sort_unstable();
+        dirty_keys.dedup();
+        for key in dirty_keys {
+            if let Some(value) = self.records.get(&key) {
+                let size = memory::estimate_entry(&key, value);
+                self.track_size(key, size);
+            }
+        }
+    }
+
+    fn clear_key_meta(&mut self) {
+        let total: usize = self.key_meta.values().map(|meta| meta.size).sum();
+        self.memory.decrease(total);
+        self.key_meta.clear();
+        self.sample_keys.clear();
+        self.dirty_keys.clear();
+    }
+
+    /**
+     * 根据当前数据重建所有键的元数据（启动加载与快照恢复）
+     */
+    fn rebuild_key_meta(&mut self) {
+        self.clear_key_meta();
+        let sizes: Vec<(Vec<u8>, usize)> = self.records
+            .iter()
+            .map(|(key, value)| (key.clone(), memory::estimate_entry(key, value)))
+            .collect();
+        for (key, size) in sizes {
+            self.track_size(key, size);
+        }
+    }
+
+    /**
+     * 按淘汰策略采样，返回最适合淘汰的键
+     *
+     * 得分越高越优先淘汰：LRU 为空闲时间，LFU 为访问频率的反向值，
+     * TTL 为剩余生存时间的反向值，随机策略为随机数
+     *
+     * @param policy 淘汰策略
+     * @param samples 采样数量（maxmemory-samples）
+     * @return 候选键与得分，没有可淘汰的键时返回 None
+     */
+    fn eviction_candidate(&mut self, policy: EvictionPolicy, samples: usize) -> Option<(Vec<u8>, u64)> {
+        if policy == EvictionPolicy::NoEviction || self.sample_keys.is_empty() {
+            return None;
+        }
+        if policy.is_volatile() && self.expire_records.is_empty() {
+            return None;
+        }
+
+        let now = memory::now_millis();
+        let mut best: Option<(Vec<u8>, u64)> = None;
+        // 设置了过期时间的键较少时，从所有键中随机采样可能很难命中，需要多尝试几次
+        let attempts = if policy.is_volatile() { samples * 16 } else { samples };
+        let mut sampled = 0;
+        for _ in 0..attempts {
+            if sampled >= samples {
+                break;
+            }
+            let index = (memory::random_u64() % self.sample_keys.len() as u64) as usize;
+            let key = &self.sample_keys[index];
+            let expire_time = self.expire_records.get(key);
+            if policy.is_volatile() && expire_time.is_none() {
+                continue;
+            }
+            sampled += 1;
+            let meta = match self.key_meta.get(key) {
+                Some(meta) => meta,
+                None => continue,
+            };
+            let score = match policy {
+                EvictionPolicy::AllKeysLru | EvictionPolicy::VolatileLru => meta.idle_time(now),
+                EvictionPolicy::AllKeysLfu | EvictionPolicy::VolatileLfu => (u8::MAX - meta.decayed_lfu_counter(now)) as u64,
+                EvictionPolicy::VolatileTtl => {
+                    let expire_at = expire_time
+                        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                        .map_or(0, |duration| duration.as_millis() as u64);
+                    u64::MAX - expire_at
+                },
+                _ => memory::random_u64(),
+            };
+            if best.as_ref().is_none_or(|(_, best_score)| score > *best_score) {
+                best = Some((key.clone(), score));
+            }
+        }
+
+        // 采样没有命中设置了过期时间的键时，直接从过期字典中选择
+        if best.is_none() && policy.is_volatile() {
+            best = self.expire_records
+                .iter()
+                .take(samples)
+                .min_by_key(|(_, time)| **time)
+                .map(|(key, _)| (key.clone(), 0));
+        }
+        best
+    }
+
+    /**
+     * 标记键已被修改
+     *
+     * 递增被 WATCH 的键的版本号，使监视该键的事务在 EXEC 时放弃执行
+     *
+     * @param key 键名
+     */
+    pub fn touch(&mut self, key: &[u8]) {
+        if let Some(watched) = self.watched_keys.get_mut(key) {
+            watched.version += 1;
+        }
+    }
+
+    /**
+     * 标记所有被 WATCH 的键已被修改（FLUSHDB、快照恢复等）
+     */
+    pub fn touch_all(&mut self) {
+        for watched in self.watched_keys.values_mut() {
+            watched.version += 1;
+        }
+    }
+
+    /**
+     * 监视键
+     *
+     * @param key 键名
+     * @return 键的当前版本号
+     */
+    fn watch(&mut self, key: &[u8]) -> u64 {
+        // 已过期的键先删除，避免在 EXEC 时才过期导致误判
+        self.expire_if_needed(key);
+        let watched = self.watched_keys
+            .entry(key.to_vec())
+            .or_insert(WatchedKey { version: 0, watchers: 0 });
+        watched.watchers += 1;
+        watched.version
+    }
+
+    /**
+     * 取消监视键
+     *
+     * @param key 键名
+     */
+    fn unwatch(&mut self, key: &[u8]) {
+        if let Some(watched) = self.watched_keys.get_mut(key) {
+            watched.watchers = watched.watchers.saturating_sub(1);
+            if watched.watchers == 0 {
+                self.watched_keys.remove(key);
+            }
+        }
+    }
+
+    /**
+     * 检查被监视的键自 WATCH 以来是否被修改、删除或过期
+     *
+     * @param key 键名
+     * @param version WATCH 时的版本号
+     */
+    fn is_modified(&mut self, key: &[u8], version: u64) -> bool {
+        self.expire_if_needed(key);
+        match self.watched_keys.get(key) {
+            Some(watched) => watched.version != version,
+            None => true,
+        }
+    }
+
     /**
      * 清理过期键
      */
@@ -338,7 +806,7 @@ impl Db {
      *
      * @param key 键名
      */
-    pub fn expire_if_needed(&mut self, key: &str) {
+    pub fn expire_if_needed(&mut self, key: &[u8]) {
         if let Some(expire_time) = self.expire_records.get(key) {
             if SystemTime::now() > *expire_time {
                 self.remove(key);
@@ -352,7 +820,7 @@ impl Db {
      * @param key 键名
      * @return 过期毫秒数，如果键不存在则返回 -2，如果键存在但没有设置过期时间则返回 -1
      */
-    pub fn ttl_millis(&mut self, key: &str) -> i64 {
+    pub fn ttl_millis(&mut self, key: &[u8]) -> i64 {
         if let Some(expire_time) = self.expire_records.get(key) {
             let now = SystemTime::now();
             if now >= *expire_time {
@@ -379,7 +847,7 @@ impl Db {
      * @param key 键名
      * @return 如果键存在返回 true，否则返回 false
      */
-    pub fn exists(&self, key: &str) -> bool {
+    pub fn exists(&self, key: &[u8]) -> bool {
         self.records.contains_key(key)
     }
 
@@ -389,9 +857,9 @@ impl Db {
      * @param pattern 模式
      * @return 符合模式的所有键的列表
      */
-    pub fn keys(&self, pattern: &str) -> Vec<String> {
+    pub fn keys(&self, pattern: &[u8]) -> Vec<Vec<u8>> {
         // 特殊情况优化：如果模式是 "*"，直接返回所有键
-        if pattern == "*" {
+        if pattern == b"*" {
             return self.records.keys().cloned().collect();
         }
         
@@ -404,13 +872,11 @@ impl Db {
      * 
      * @param 
      */
-    pub fn random_key(&self) -> Option<String> {
-        let keys: Vec<String> = self.records.keys().cloned().collect();
-        if keys.is_empty() {
+    pub fn random_key(&self) -> Option<Vec<u8>> {
+        if self.sample_keys.is_empty() {
             return None;
         }
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let random_index = (now as usize) % keys.len();
-        Some(keys[random_index].clone())
+        let random_index = (memory::random_u64() % self.sample_keys.len() as u64) as usize;
+        Some(self.sample_keys[random_index].clone())
     }
 }
\ No newline at end of file
@@ -1,14 +1,17 @@
-use std::{sync::Arc, time::{Duration, SystemTime}};
+use std::{sync::Arc, time::{Duration, SystemTime, UNIX_EPOCH}};
 
 use tokio::sync::{mpsc::Sender, oneshot};
 
-use crate::{args::Args, store::db::{DatabaseMessage, Db}, persistence::rdb_file::RdbFile};
+use crate::{args::Args, config::Config, store::{db::{DatabaseMessage, Db}, memory::{EvictionPolicy, MemoryStats}, script_status::{KillResult, ScriptStatus}}, persistence::{rdb_file::RdbFile, rdb_save::RdbSaver}};
 
 /**
  * DB 管理器
  */
 pub struct DatabaseManager {
-    senders: Vec<Sender<DatabaseMessage>>
+    senders: Vec<Sender<DatabaseMessage>>,
+    scripts: Vec<Arc<ScriptStatus>>,
+    memory: Arc<MemoryStats>,
+    rdb: Arc<RdbSaver>,
 }
 
 impl DatabaseManager {
@@ -16,18 +19,28 @@ impl DatabaseManager {
     /**
      * 创建 DB 管理器
      *
-     * @param config 参数
+     * @param args 参数
+     * @param config 运行时配置（保存规则可以通过 CONFIG SET save 修改）
      */
-    pub fn new(args: Arc<Args>) -> Self {
+    pub fn new(args: Arc<Args>, config: Arc<Config>) -> Self {
 
         let mut dbs = Vec::new();
         let mut senders = Vec::new();
-        let mut rdb_file = RdbFile::new(args.dbfilename.clone());
-        let _ = rdb_file.load();
+        let mut scripts = Vec::new();
+        let memory = Arc::new(MemoryStats::new());
+        // 开启 AOF 且 AOF 文件存在时，数据完全由 AOF 恢复，不再加载 RDB；配置了 import-rdb 时从 Redis RDB 文件加载
+        let mut rdb_file = RdbFile::new(args.import_rdb.clone().unwrap_or_else(|| args.dbfilename.clone()));
+        if args.appendonly != "yes" || !args.aof_path().exists() {
+            if let Err(e) = rdb_file.load() {
+                log::error!("{}", e);
+                std::process::exit(1);
+            }
+        }
 
         for id in 0..args.databases {
-            let db = Db::new(rdb_file.get_database(id));
+            let db = Db::new(rdb_file.get_database(id), memory.clone(), config.clone());
             senders.push(db.sender.clone());
+            scripts.push(db.script.clone());
             dbs.push(db);
         }
 
@@ -37,11 +50,12 @@ impl DatabaseManager {
             });
         }
 
-        let args_clone = args.clone();
+        let rdb = Arc::new(RdbSaver::new(args.dbfilename.clone(), senders.clone()));
+        let rdb_clone = rdb.clone();
         let senders_clone = senders.clone();
 
         tokio::spawn(async move {
-            let period = Duration::from_secs_f64(1.0 / args_clone.hz);
+            let period = Duration::from_secs_f64(1.0 / args.hz);
             let mut interval = tokio::time::interval(period);
             loop {
 
@@ -50,59 +64,86 @@ impl DatabaseManager {
                     let _ = sender.send(DatabaseMessage::CleanExpired).await;
                 }
 
-                let mut changes = 0;
-                for sender in &senders_clone {
-                    let (tx, rx) = oneshot::channel();
-                    if sender.send(DatabaseMessage::Changes(tx)).await.is_ok() {
-                        if let Ok(count) = rx.await {
-                            changes += count;
-                        }
-                    }
+                if rdb_clone.bgsave_in_progress() {
+                    continue;
                 }
-
+                let changes = rdb_clone.changes().await;
                 let should_save = {
-                    let now = SystemTime::now();
                     // 处理系统时间向后调整的情况，如果时间倒退则视为 0
-                    let elapsed = now.duration_since(rdb_file.last_save_time)
-                        .unwrap_or(Duration::from_secs(0))
-                        .as_secs();
-                    args_clone.save.iter().any(|rule| {
-                        elapsed >= rule.seconds && changes.saturating_sub(rdb_file.last_save_changes) >= rule.changes
+                    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                    let elapsed = now.saturating_sub(rdb_clone.last_save_time());
+                    config.values().save.iter().any(|rule| {
+                        elapsed >= rule.seconds && changes >= rule.changes
                     })
                 };
 
                 if should_save {
-                    let mut snapshots = Vec::new();
-                    for sender in &senders_clone {
-                        let (tx, rx) = oneshot::channel();
-                        if sender.send(DatabaseMessage::Snapshot(tx)).await.is_ok() {
-                            if let Ok(snapshot) = rx.await {
-                                snapshots.push(snapshot);
-                            }
-                        }
-                    }
-
-                    for (index, snapshot) in snapshots.into_iter().enumerate() {
-                        rdb_file.set_database(index, snapshot); // 更新快照
-                    }
-
-                    rdb_file.last_save_time = SystemTime::now();
-                    rdb_file.last_save_changes = changes;
-                    match rdb_file.save() {
-                        Ok(()) => {
-                            log::debug!("Successfully persisted dump.RDB");
-                            for sender in &senders_clone {
-                                let _ = sender.send(DatabaseMessage::ResetChanges).await;
-                            }
-                        },
-                        Err(_) => log::error!("Failed to dump.RDB")
-                    };
+                    // SAVE 正在进行时跳过本轮
+                    let _ = rdb_clone.bgsave();
                 }
             }
         });
         DatabaseManager { 
-            senders 
+            senders,
+            scripts,
+            memory,
+            rdb,
+        }
+    }
+
+    /**
+     * 获取 RDB 保存器
+     */
+    pub fn get_rdb(&self) -> &Arc<RdbSaver> {
+        &self.rdb
+    }
+
+    /**
+     * 获取全局内存统计
+     */
+    pub fn get_memory(&self) -> &Arc<MemoryStats> {
+        &self.memory
+    }
+
+    /**
+     * 内存用量超过 maxmemory 时，按淘汰策略逐个删除键
+     *
+     * 每一轮向所有数据库请求采样得到的候选键，淘汰其中得分最高的一个
+     *
+     * @param maxmemory 内存上限（字节）
+     * @param policy 淘汰策略
+     * @param samples 每个数据库的采样数量
+     * @return 内存用量是否已回到上限以内，以及被淘汰的键（数据库索引与键名），调用者需要将其作为 DEL 传播
+     */
+    pub async fn perform_evictions(&self, maxmemory: usize, policy: EvictionPolicy, samples: usize) -> (bool, Vec<(usize, Vec<u8>)>) {
+        let mut evicted = Vec::new();
+        while self.memory.used_memory() > maxmemory {
+            let mut best: Option<(usize, Vec<u8>, u64)> = None;
+            for (index, sender) in self.senders.iter().enumerate() {
+                let (tx, rx) = oneshot::channel();
+                if sender.send(DatabaseMessage::EvictionCandidate { policy, samples, sender: tx }).await.is_err() {
+                    continue;
+                }
+                if let Ok(Some((key, score))) = rx.await {
+                    if best.as_ref().is_none_or(|(_, _, best_score)| score > *best_score) {
+                        best = Some((index, key, score));
+                    }
+                }
+            }
+
+            let (index, key) = match best {
+                Some((index, key, _)) => (index, key),
+                None => return (false, evicted),
+            };
+            let (tx, rx) = oneshot::channel();
+            if self.senders[index].send(DatabaseMessage::Evict { key: key.clone(), sender: tx }).await.is_err() {
+                return (false, evicted);
+            }
+            if let Ok(true) = rx.await {
+                evicted.push((index, key));
+            }
         }
+        (true, evicted)
     }
 
     /**
@@ -124,4 +165,25 @@ impl DatabaseManager {
     pub fn get_senders(&self) -> Vec<Sender<DatabaseMessage>> {
         self.senders.clone()
     }
-}
\ No newline at end of file
+
+    /**
+     * 是否有数据库正在执行超时的脚本
+     */
+    pub fn script_busy(&self) -> bool {
+        self.scripts.iter().any(|script| script.is_busy())
+    }
+
+    /**
+     * 终止所有超时且没有执行过写命令的脚本（SCRIPT KILL）
+     */
+    pub fn kill_script(&self) -> KillResult {
+        let results: Vec<KillResult> = self.scripts.iter().map(|script| script.kill()).collect();
+        if results.contains(&KillResult::Killed) {
+            KillResult::Killed
+        } else if results.contains(&KillResult::Unkillable) {
+            KillResult::Unkillable
+        } else {
+            KillResult::NotBusy
+        }
+    }
+}
@@ -53,7 +53,35 @@ impl HyperLogLog {
      * @param value 要添加的元素
      * @return 如果至少有一个寄存器被更新，返回 true，否则返回 false
      */
-    pub fn add(&mut self, value: &str) -> bool {
+    pub fn add(&mut self, value: &[u8]) -> bool {
+        let (j, r) = self.register(value);
+        // 如果新的前导零数大于当前寄存器值，更新寄存器
+        if r > self.registers[j] {
+            self.registers[j] = r;
+            self.cache_valid = false; // 标记缓存失效
+            true
+        } else {
+            false
+        }
+    }
+
+    /**
+     * 添加元素是否会更新寄存器（不修改 HyperLogLog）
+     *
+     * @param value 要添加的元素
+     */
+    pub fn would_update(&self, value: &[u8]) -> bool {
+        let (j, r) = self.register(value);
+        r > self.registers[j]
+    }
+
+    /**
+     * 计算元素对应的寄存器下标与 ρ 值
+     *
+     * @param value 元素
+     * @return (寄存器下标, ρ 值)
+     */
+    fn register(&self, value: &[u8]) -> (usize, u8) {
         let hash = self.hash(value);
         
         // 使用低 14 位作为寄存器索引 (0-16383)
@@ -78,15 +106,7 @@ impl HyperLogLog {
             // 然后加 1 得到 ρ 值（1-51）
             (leading_zeros.min(50) + 1) as u8
         };
-        
-        // 如果新的前导零数大于当前寄存器值，更新寄存器
-        if r > self.registers[j] {
-            self.registers[j] = r;
-            self.cache_valid = false; // 标记缓存失效
-            true
-        } else {
-            false
-        }
+        (j, r)
     }
 
     /**
@@ -160,13 +180,13 @@ impl HyperLogLog {
      * @param value 要哈希的值
      * @return 64 位哈希值
      */
-    fn hash(&self, value: &str) -> u64 {
+    fn hash(&self, value: &[u8]) -> u64 {
         // FNV-1a 哈希算法的常量
         const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
         const FNV_PRIME: u64 = 0x100000001b3;
         
         let mut hash = FNV_OFFSET_BASIS;
-        for byte in value.bytes() {
+        for &byte in value {
             hash ^= byte as u64;
             hash = hash.wrapping_mul(FNV_PRIME);
         }
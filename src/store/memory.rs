@@ -0,0 +1,329 @@
+use std::{
+    str::FromStr,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::store::db::Structure;
+
+/// 每个键的固定开销（哈希表节点、键对象与过期信息等）
+const KEY_OVERHEAD: usize = 64;
+
+/// 集合类型中每个元素的固定开销
+const ELEMENT_OVERHEAD: usize = 24;
+
+/// 集合类型估算内存时最多采样的元素数量
+const SIZE_SAMPLES: usize = 32;
+
+/// HyperLogLog 使用稠密表示，固定 16384 个寄存器
+const HYPERLOGLOG_SIZE: usize = 16384;
+
+/// 新键的 LFU 计数初始值（与 Redis 的 LFU_INIT_VAL 一致），避免新键被立即淘汰
+const LFU_INIT_VAL: u8 = 5;
+
+/// LFU 对数计数因子（lfu-log-factor）
+const LFU_LOG_FACTOR: f64 = 10.0;
+
+/// LFU 计数衰减周期，单位分钟（lfu-decay-time）
+const LFU_DECAY_TIME: u64 = 1;
+
+/// 内存淘汰策略（maxmemory-policy）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    NoEviction,
+    AllKeysLru,
+    AllKeysLfu,
+    AllKeysRandom,
+    VolatileLru,
+    VolatileLfu,
+    VolatileRandom,
+    VolatileTtl,
+}
+
+impl EvictionPolicy {
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EvictionPolicy::NoEviction => "noeviction",
+            EvictionPolicy::AllKeysLru => "allkeys-lru",
+            EvictionPolicy::AllKeysLfu => "allkeys-lfu",
+            EvictionPolicy::AllKeysRandom => "allkeys-random",
+            EvictionPolicy::VolatileLru => "volatile-lru",
+            EvictionPolicy::VolatileLfu => "volatile-lfu",
+            EvictionPolicy::VolatileRandom => "volatile-random",
+            EvictionPolicy::VolatileTtl => "volatile-ttl",
+        }
+    }
+
+    /// 是否只淘汰设置了过期时间的键
+    pub fn is_volatile(&self) -> bool {
+        matches!(self, EvictionPolicy::VolatileLru | EvictionPolicy::VolatileLfu | EvictionPolicy::VolatileRandom | EvictionPolicy::VolatileTtl)
+    }
+}
+
+impl FromStr for EvictionPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "noeviction" => Ok(EvictionPolicy::NoEviction),
+            "allkeys-lru" => Ok(EvictionPolicy::AllKeysLru),
+            "allkeys-lfu" => Ok(EvictionPolicy::AllKeysLfu),
+            "allkeys-random" => Ok(EvictionPolicy::AllKeysRandom),
+            "volatile-lru" => Ok(EvictionPolicy::VolatileLru),
+            "volatile-lfu" => Ok(EvictionPolicy::VolatileLfu),
+            "volatile-random" => Ok(EvictionPolicy::VolatileRandom),
+            "volatile-ttl" => Ok(EvictionPolicy::VolatileTtl),
+            _ => Err(format!("Invalid maxmemory policy: {}", s)),
+        }
+    }
+}
+
+/**
+ * 全局内存统计
+ *
+ * 所有数据库共享，每个数据库在键写入、修改与删除时更新估算的内存用量
+ */
+#[derive(Default)]
+pub struct MemoryStats {
+    used_memory: AtomicUsize,
+    peak_memory: AtomicUsize,
+    evicted_keys: AtomicU64,
+}
+
+impl MemoryStats {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 当前估算的内存用量（字节）
+    pub fn used_memory(&self) -> usize {
+        self.used_memory.load(Ordering::Relaxed)
+    }
+
+    /// 内存用量峰值（字节）
+    pub fn peak_memory(&self) -> usize {
+        self.peak_memory.load(Ordering::Relaxed)
+    }
+
+    /// 因内存淘汰被删除的键数量
+    pub fn evicted_keys(&self) -> u64 {
+        self.evicted_keys.load(Ordering::Relaxed)
+    }
+
+    pub fn increase(&self, size: usize) {
+        let used = self.used_memory.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak_memory.fetch_max(used, Ordering::Relaxed);
+    }
+
+    pub fn decrease(&self, size: usize) {
+        let _ = self.used_memory.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| Some(used.saturating_sub(size)));
+    }
+
+    pub fn record_eviction(&self) {
+        self.evicted_keys.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 清零淘汰计数，并将峰值重置为当前用量（CONFIG RESETSTAT）
+    pub fn reset_stats(&self) {
+        self.evicted_keys.store(0, Ordering::Relaxed);
+        self.peak_memory.store(self.used_memory(), Ordering::Relaxed);
+    }
+}
+
+/**
+ * 键的元数据
+ *
+ * @param size 估算的内存用量（包含键本身）
+ * @param slot 键在采样数组中的位置，用于随机采样
+ * @param access_time 最近一次访问的时间（毫秒），用于 LRU
+ * @param lfu_counter 对数访问计数，用于 LFU
+ * @param lfu_decay_time 最近一次衰减 LFU 计数的时间（分钟）
+ */
+pub struct KeyMeta {
+    pub size: usize,
+    pub slot: usize,
+    access_time: u64,
+    lfu_counter: u8,
+    lfu_decay_time: u64,
+}
+
+impl KeyMeta {
+
+    pub fn new(size: usize, slot: usize) -> Self {
+        let now = now_millis();
+        KeyMeta {
+            size,
+            slot,
+            access_time: now,
+            lfu_counter: LFU_INIT_VAL,
+            lfu_decay_time: now / 60_000,
+        }
+    }
+
+    /**
+     * 记录一次访问：更新 LRU 时间并按对数概率递增 LFU 计数
+     */
+    pub fn touch(&mut self) {
+        let now = now_millis();
+        let counter = self.decayed_lfu_counter(now);
+        self.lfu_counter = lfu_log_incr(counter);
+        self.lfu_decay_time = now / 60_000;
+        self.access_time = now;
+    }
+
+    /// 空闲时间（毫秒）
+    pub fn idle_time(&self, now: u64) -> u64 {
+        now.saturating_sub(self.access_time)
+    }
+
+    /// 按经过的时间衰减后的 LFU 计数
+    pub fn decayed_lfu_counter(&self, now: u64) -> u8 {
+        let periods = (now / 60_000).saturating_sub(self.lfu_decay_time) / LFU_DECAY_TIME;
+        self.lfu_counter.saturating_sub(periods.min(u8::MAX as u64) as u8)
+    }
+}
+
+/**
+ * 按对数概率递增 LFU 计数（与 Redis 的 LFULogIncr 一致）
+ *
+ * 计数越大，递增的概率越小，8 位计数可以表示百万级的访问次数
+ */
+fn lfu_log_incr(counter: u8) -> u8 {
+    if counter == u8::MAX {
+        return counter;
+    }
+    let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+    let probability = 1.0 / (base * LFU_LOG_FACTOR + 1.0);
+    if random_f64() < probability {
+        counter + 1
+    } else {
+        counter
+    }
+}
+
+impl Structure {
+
+    /**
+     * 估算值占用的内存（字节）
+     *
+     * 元素较多的集合类型只采样部分元素，按平均大小推算总量（与 MEMORY USAGE 的采样方式类似）
+     */
+    pub fn estimate_memory(&self) -> usize {
+        match self {
+            Structure::String(value) => value.len() + 16,
+            Structure::Json(value) => value.len() + 16,
+            Structure::HyperLogLog(_) => HYPERLOGLOG_SIZE + 64,
+            Structure::List(list) => {
+                // 列表支持随机访问，均匀地采样
+                let step = (list.len() / SIZE_SAMPLES).max(1);
+                let samples = list.iter().step_by(step).take(SIZE_SAMPLES).map(|item| item.len() + ELEMENT_OVERHEAD);
+                estimate_collection(list.len(), samples)
+            },
+            Structure::Hash(hash) => {
+                let samples = hash.iter().take(SIZE_SAMPLES).map(|(field, value)| field.len() + value.len() + ELEMENT_OVERHEAD * 2);
+                estimate_collection(hash.len(), samples)
+            },
+            Structure::Set(set) => {
+                let samples = set.iter().take(SIZE_SAMPLES).map(|member| member.len() + ELEMENT_OVERHEAD);
+                estimate_collection(set.len(), samples)
+            },
+            Structure::SortedSet(sorted_set) => {
+                // 成员同时保存在哈希表与跳表中
+                let samples = sorted_set.iter().take(SIZE_SAMPLES).map(|(member, _)| member.len() * 2 + ELEMENT_OVERHEAD * 3);
+                estimate_collection(sorted_set.len(), samples)
+            },
+            Structure::VectorCollection(vector) => {
+                let samples = vector.vectors.iter().take(SIZE_SAMPLES).map(|(id, values)| id.len() * 2 + values.len() * 4 + ELEMENT_OVERHEAD * 2);
+                estimate_collection(vector.vectors.len(), samples)
+            },
+            Structure::Stream(stream) => {
+                // 消息 ID 占 16 字节，消费者组的待确认消息按元素开销计算
+                let samples = stream.entries().take(SIZE_SAMPLES).map(|(_, fields)| {
+                    16 + ELEMENT_OVERHEAD + fields.iter().map(|(field, value)| field.len() + value.len() + ELEMENT_OVERHEAD).sum::<usize>()
+                });
+                let pending: usize = stream.groups().map(|(name, group)| name.len() + group.pending.len() * ELEMENT_OVERHEAD * 2).sum();
+                estimate_collection(stream.len(), samples) + pending
+            },
+        }
+    }
+}
+
+/**
+ * 根据采样元素的平均大小推算集合的总大小
+ *
+ * @param len 元素总数
+ * @param samples 采样元素的大小
+ */
+fn estimate_collection(len: usize, samples: impl Iterator<Item = usize>) -> usize {
+    let mut count = 0;
+    let mut total = 0;
+    for size in samples {
+        count += 1;
+        total += size;
+    }
+    if count == 0 {
+        return 64;
+    }
+    64 + total * len / count
+}
+
+/**
+ * 估算键值对占用的内存（字节）
+ *
+ * @param key 键名
+ * @param value 值
+ */
+pub fn estimate_entry(key: &[u8], value: &Structure) -> usize {
+    KEY_OVERHEAD + key.len() + value.estimate_memory()
+}
+
+/// 当前时间（毫秒）
+pub fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/**
+ * 伪随机数（xorshift），用于淘汰采样与 LFU 计数
+ */
+pub fn random_u64() -> u64 {
+    static STATE: AtomicU64 = AtomicU64::new(0);
+    let mut x = STATE.load(Ordering::Relaxed);
+    if x == 0 {
+        x = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(1) | 1;
+    }
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    STATE.store(x, Ordering::Relaxed);
+    x
+}
+
+fn random_f64() -> f64 {
+    (random_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/**
+ * 进程常驻内存（RSS，字节），无法获取时返回 0
+ */
+pub fn process_rss() -> usize {
+    std::fs::read_to_string("/proc/self/statm")
+        .ok()
+        .and_then(|statm| statm.split_whitespace().nth(1).and_then(|pages| pages.parse::<usize>().ok()))
+        .map_or(0, |pages| pages * 4096)
+}
+
+/**
+ * 格式化内存大小（如 1.50M）
+ *
+ * @param bytes 字节数
+ */
+pub fn format_human(bytes: usize) -> String {
+    let units = [("G", 1024 * 1024 * 1024), ("M", 1024 * 1024), ("K", 1024)];
+    for (unit, size) in units {
+        if bytes >= size {
+            return format!("{:.2}{}", bytes as f64 / size as f64, unit);
+        }
+    }
+    format!("{}B", bytes)
+}
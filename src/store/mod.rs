@@ -1,6 +1,12 @@
 pub mod db;
 pub mod db_manager;
 pub mod hyperloglog;
+pub mod memory;
 pub mod sorted_set;
+pub mod stream;
+pub mod stream_blocking;
+pub mod pubsub;
+pub mod script_cache;
+pub mod script_status;
 
 pub mod blocking;
\ No newline at end of file
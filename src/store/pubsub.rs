@@ -0,0 +1,175 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use crate::frame::Frame;
+use crate::tools::pattern;
+
+/// 发布订阅管理器
+///
+/// 管理频道订阅（SUBSCRIBE）与模式订阅（PSUBSCRIBE）：
+/// - channels / patterns: 频道（模式）-> 订阅者会话集合
+/// - session_channels / session_patterns: 会话 -> 已订阅的频道（模式），用于退订与断线清理
+pub struct PubSubManager {
+    channels: HashMap<String, HashSet<usize>>,
+    patterns: HashMap<String, HashSet<usize>>,
+    session_channels: HashMap<usize, BTreeSet<String>>,
+    session_patterns: HashMap<usize, BTreeSet<String>>,
+}
+
+impl Default for PubSubManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PubSubManager {
+    pub fn new() -> Self {
+        Self {
+            channels: HashMap::new(),
+            patterns: HashMap::new(),
+            session_channels: HashMap::new(),
+            session_patterns: HashMap::new(),
+        }
+    }
+
+    /// 订阅频道，返回该会话当前的订阅总数（频道 + 模式）
+    pub fn subscribe(&mut self, session_id: usize, channel: &str) -> usize {
+        self.channels.entry(channel.to_string()).or_default().insert(session_id);
+        self.session_channels.entry(session_id).or_default().insert(channel.to_string());
+        self.subscription_count(session_id)
+    }
+
+    /// 退订频道，返回该会话剩余的订阅总数
+    pub fn unsubscribe(&mut self, session_id: usize, channel: &str) -> usize {
+        if let Some(sessions) = self.channels.get_mut(channel) {
+            sessions.remove(&session_id);
+            if sessions.is_empty() {
+                self.channels.remove(channel);
+            }
+        }
+        if let Some(channels) = self.session_channels.get_mut(&session_id) {
+            channels.remove(channel);
+            if channels.is_empty() {
+                self.session_channels.remove(&session_id);
+            }
+        }
+        self.subscription_count(session_id)
+    }
+
+    /// 订阅模式，返回该会话当前的订阅总数
+    pub fn psubscribe(&mut self, session_id: usize, pattern: &str) -> usize {
+        self.patterns.entry(pattern.to_string()).or_default().insert(session_id);
+        self.session_patterns.entry(session_id).or_default().insert(pattern.to_string());
+        self.subscription_count(session_id)
+    }
+
+    /// 退订模式，返回该会话剩余的订阅总数
+    pub fn punsubscribe(&mut self, session_id: usize, pattern: &str) -> usize {
+        if let Some(sessions) = self.patterns.get_mut(pattern) {
+            sessions.remove(&session_id);
+            if sessions.is_empty() {
+                self.patterns.remove(pattern);
+            }
+        }
+        if let Some(patterns) = self.session_patterns.get_mut(&session_id) {
+            patterns.remove(pattern);
+            if patterns.is_empty() {
+                self.session_patterns.remove(&session_id);
+            }
+        }
+        self.subscription_count(session_id)
+    }
+
+    /// 会话已订阅的频道（按字典序）
+    pub fn session_channels(&self, session_id: usize) -> Vec<String> {
+        self.session_channels
+            .get(&session_id)
+            .map(|channels| channels.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 会话已订阅的模式（按字典序）
+    pub fn session_patterns(&self, session_id: usize) -> Vec<String> {
+        self.session_patterns
+            .get(&session_id)
+            .map(|patterns| patterns.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 会话的订阅总数（频道 + 模式）
+    pub fn subscription_count(&self, session_id: usize) -> usize {
+        let channels = self.session_channels.get(&session_id).map_or(0, |c| c.len());
+        let patterns = self.session_patterns.get(&session_id).map_or(0, |p| p.len());
+        channels + patterns
+    }
+
+    /// 计算一条消息需要投递的目标
+    ///
+    /// 返回 (session_id, 消息帧) 列表：
+    /// - 频道订阅者收到 ["message", channel, payload]
+    /// - 模式订阅者收到 ["pmessage", pattern, channel, payload]
+    ///
+    /// 消息为推送类型，RESP2 连接按普通数组接收
+    ///
+    /// 调用者应在释放锁之后再写入连接，避免持锁等待网络 IO
+    pub fn messages_for(&self, channel: &str, payload: &[u8]) -> Vec<(usize, Frame)> {
+        let mut messages = Vec::new();
+
+        if let Some(sessions) = self.channels.get(channel) {
+            for session_id in sessions {
+                let frame = Frame::Push(vec![
+                    Frame::BulkString("message".to_string().into()),
+                    Frame::BulkString(channel.to_string().into()),
+                    Frame::BulkString(payload.to_vec()),
+                ]);
+                messages.push((*session_id, frame));
+            }
+        }
+
+        for (pattern, sessions) in &self.patterns {
+            if !pattern::is_match(channel, pattern) {
+                continue;
+            }
+            for session_id in sessions {
+                let frame = Frame::Push(vec![
+                    Frame::BulkString("pmessage".to_string().into()),
+                    Frame::BulkString(pattern.clone().into()),
+                    Frame::BulkString(channel.to_string().into()),
+                    Frame::BulkString(payload.to_vec()),
+                ]);
+                messages.push((*session_id, frame));
+            }
+        }
+
+        messages
+    }
+
+    /// 活跃频道（至少有一个订阅者），可按模式过滤（PUBSUB CHANNELS）
+    pub fn active_channels(&self, filter: Option<&str>) -> Vec<String> {
+        let mut channels: Vec<String> = self.channels
+            .keys()
+            .filter(|channel| filter.is_none_or(|p| pattern::is_match(channel, p)))
+            .cloned()
+            .collect();
+        channels.sort();
+        channels
+    }
+
+    /// 频道的订阅者数量，不包含模式订阅者（PUBSUB NUMSUB）
+    pub fn numsub(&self, channel: &str) -> usize {
+        self.channels.get(channel).map_or(0, |sessions| sessions.len())
+    }
+
+    /// 被订阅的模式数量（PUBSUB NUMPAT）
+    pub fn numpat(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// 清理会话的所有订阅（客户端断开时调用）
+    pub fn cleanup_session(&mut self, session_id: usize) {
+        for channel in self.session_channels(session_id) {
+            self.unsubscribe(session_id, &channel);
+        }
+        for pattern in self.session_patterns(session_id) {
+            self.punsubscribe(session_id, &pattern);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+/// Lua 脚本缓存
+///
+/// 以脚本内容的 SHA1 摘要（小写十六进制）为键，供 EVALSHA 按摘要执行已加载的脚本
+pub struct ScriptCache {
+    scripts: HashMap<String, String>,
+}
+
+impl Default for ScriptCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptCache {
+    pub fn new() -> Self {
+        Self {
+            scripts: HashMap::new(),
+        }
+    }
+
+    /// 计算内容的 SHA1 摘要
+    pub fn sha1hex(data: &[u8]) -> String {
+        sha1_smol::Sha1::from(data).digest().to_string()
+    }
+
+    /// 缓存脚本，返回脚本的 SHA1 摘要
+    pub fn load(&mut self, script: &str) -> String {
+        let sha = Self::sha1hex(script.as_bytes());
+        self.scripts.entry(sha.clone()).or_insert_with(|| script.to_string());
+        sha
+    }
+
+    /// 按摘要获取脚本（不区分大小写）
+    pub fn get(&self, sha: &str) -> Option<String> {
+        self.scripts.get(&sha.to_lowercase()).cloned()
+    }
+
+    /// 脚本是否已缓存（不区分大小写）
+    pub fn exists(&self, sha: &str) -> bool {
+        self.scripts.contains_key(&sha.to_lowercase())
+    }
+
+    /// 清空缓存
+    pub fn flush(&mut self) {
+        self.scripts.clear();
+    }
+}
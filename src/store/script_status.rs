@@ -0,0 +1,124 @@
+use std::{
+    sync::{atomic::{AtomicBool, AtomicU8, Ordering}, Mutex},
+    time::Instant,
+};
+
+/// 脚本超时后其他命令收到的错误
+pub const BUSY_ERROR: &str = "BUSY Redis is busy running a script. You can only call SCRIPT KILL or SHUTDOWN NOSAVE.";
+
+/// SCRIPT KILL 终止脚本后，执行脚本的客户端收到的错误信息
+pub const KILLED_MESSAGE: &str = "Script killed by user with SCRIPT KILL...";
+
+/// 脚本正在执行，尚未写入数据
+const RUNNING: u8 = 0;
+/// 脚本已经执行过写命令
+const WROTE: u8 = 1;
+/// 脚本已经被 SCRIPT KILL 终止
+const KILLED: u8 = 2;
+
+/// SCRIPT KILL 的结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KillResult {
+    /// 已经请求终止脚本
+    Killed,
+    /// 没有超时的脚本
+    NotBusy,
+    /// 脚本已经执行过写命令，终止会破坏原子性
+    Unkillable,
+}
+
+/**
+ * 数据库中正在执行的脚本的状态
+ *
+ * Db 线程执行脚本时更新，Lua 指令钩子与 SCRIPT KILL 从其他线程读取
+ *
+ * @param started 脚本开始执行的时间，没有脚本执行时为 None
+ * @param busy 脚本执行时间已经超过 lua-time-limit
+ * @param state 脚本是否写入过数据或已被终止，两者互斥，保证被终止的脚本不会再写入
+ */
+#[derive(Default)]
+pub struct ScriptStatus {
+    started: Mutex<Option<Instant>>,
+    busy: AtomicBool,
+    state: AtomicU8,
+}
+
+impl ScriptStatus {
+
+    /// 开始执行脚本
+    pub fn start(&self) {
+        self.state.store(RUNNING, Ordering::SeqCst);
+        *self.started.lock().unwrap() = Some(Instant::now());
+    }
+
+    /**
+     * 脚本执行结束
+     *
+     * @return 脚本是否被 SCRIPT KILL 终止
+     */
+    pub fn finish(&self) -> bool {
+        *self.started.lock().unwrap() = None;
+        self.busy.store(false, Ordering::SeqCst);
+        self.state.swap(RUNNING, Ordering::SeqCst) == KILLED
+    }
+
+    /**
+     * 检查脚本是否超时，超时后标记为 busy
+     *
+     * @param limit 最长执行时间（毫秒），不大于 0 时不限制
+     * @return 脚本是否超时
+     */
+    pub fn check_time(&self, limit: i64) -> bool {
+        if self.busy.load(Ordering::SeqCst) {
+            return true;
+        }
+        if limit <= 0 {
+            return false;
+        }
+        let elapsed = match *self.started.lock().unwrap() {
+            Some(started) => started.elapsed().as_millis(),
+            None => return false,
+        };
+        if elapsed < limit as u128 {
+            return false;
+        }
+        log::warn!("Slow script detected: still in execution after {} milliseconds. You can try killing the script using the SCRIPT KILL command.", elapsed);
+        self.busy.store(true, Ordering::SeqCst);
+        true
+    }
+
+    /**
+     * 记录脚本将要执行写命令，此后脚本不能再被终止
+     *
+     * @return 脚本已经被终止时返回 false，写命令不能执行
+     */
+    pub fn record_write(&self) -> bool {
+        match self.state.compare_exchange(RUNNING, WROTE, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => true,
+            Err(state) => state == WROTE,
+        }
+    }
+
+    /// 是否有超时的脚本正在执行
+    pub fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::SeqCst)
+    }
+
+    /// 脚本是否已经被终止
+    pub fn is_killed(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == KILLED
+    }
+
+    /**
+     * 终止超时的脚本（SCRIPT KILL），只有没有执行过写命令的脚本可以终止
+     */
+    pub fn kill(&self) -> KillResult {
+        if !self.is_busy() {
+            return KillResult::NotBusy;
+        }
+        match self.state.compare_exchange(RUNNING, KILLED, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) | Err(KILLED) => KillResult::Killed,
+            Err(_) => KillResult::Unkillable,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::ops::Bound;
 use bincode::{BorrowDecode, Decode, Encode};
 use skiplist::OrderedSkipList;
 
@@ -10,9 +11,9 @@ use skiplist::OrderedSkipList;
 #[derive(Debug)]
 pub struct SortedSet {
     // 哈希表：member -> score，O(1) 查找
-    member_map: HashMap<String, f64>,
+    member_map: HashMap<Vec<u8>, f64>,
     // 跳表：按 (score, member) 排序，O(log n) 范围查询
-    score_list: OrderedSkipList<(f64, String)>,
+    score_list: OrderedSkipList<(f64, Vec<u8>)>,
 }
 
 // 手动实现 Clone
@@ -34,7 +35,7 @@ impl Encode for SortedSet {
         encoder: &mut E,
     ) -> Result<(), bincode::error::EncodeError> {
         // 将跳表转换为 Vec 进行序列化
-        let items: Vec<(f64, String)> = self.score_list.iter().cloned().collect();
+        let items: Vec<(f64, Vec<u8>)> = self.score_list.iter().cloned().collect();
         items.encode(encoder)
     }
 }
@@ -44,8 +45,8 @@ impl<Context> Decode<Context> for SortedSet {
     fn decode<D: bincode::de::Decoder<Context = Context>>(
         decoder: &mut D,
     ) -> Result<Self, bincode::error::DecodeError> {
-        // 从 Vec<(f64, String)> 反序列化，然后重建跳表
-        let items: Vec<(f64, String)> = Vec::decode(decoder)?;
+        // 从 Vec<(f64, Vec<u8>)> 反序列化，然后重建跳表
+        let items: Vec<(f64, Vec<u8>)> = Vec::decode(decoder)?;
         let mut set = SortedSet::new();
         for (score, member) in items {
             set.add(member, score);
@@ -60,7 +61,7 @@ impl<'de, Context> BorrowDecode<'de, Context> for SortedSet {
         decoder: &mut D,
     ) -> Result<Self, bincode::error::DecodeError> {
         // 从 Vec 反序列化，然后重建跳表
-        let items: Vec<(f64, String)> = Vec::borrow_decode(decoder)?;
+        let items: Vec<(f64, Vec<u8>)> = Vec::borrow_decode(decoder)?;
         let mut set = SortedSet::new();
         for (score, member) in items {
             set.add(member, score);
@@ -87,7 +88,7 @@ impl SortedSet {
     /// # 返回
     /// - `true`: 成员是新增的
     /// - `false`: 成员已存在，只是更新了分数
-    pub fn add(&mut self, member: String, score: f64) -> bool {
+    pub fn add(&mut self, member: Vec<u8>, score: f64) -> bool {
         let is_new = if let Some(old_score) = self.member_map.get(&member) {
             // 成员已存在，需要先删除旧的 (old_score, member)
             self.score_list.remove(&(*old_score, member.clone()));
@@ -110,7 +111,7 @@ impl SortedSet {
     /// # 返回
     /// - `Some(score)`: 成员存在
     /// - `None`: 成员不存在
-    pub fn get_score(&self, member: &str) -> Option<f64> {
+    pub fn get_score(&self, member: &[u8]) -> Option<f64> {
         self.member_map.get(member).copied()
     }
 
@@ -119,10 +120,10 @@ impl SortedSet {
     /// # 返回
     /// - `true`: 成员存在并被删除
     /// - `false`: 成员不存在
-    pub fn remove(&mut self, member: &str) -> bool {
+    pub fn remove(&mut self, member: &[u8]) -> bool {
         if let Some(score) = self.member_map.remove(member) {
             // 同时从跳表中删除
-            self.score_list.remove(&(score, member.to_string()));
+            self.score_list.remove(&(score, member.to_vec()));
             true
         } else {
             false
@@ -144,21 +145,10 @@ impl SortedSet {
     /// # 返回
     /// - `Some(rank)`: 成员存在，返回排名
     /// - `None`: 成员不存在
-    pub fn rank(&self, member: &str) -> Option<usize> {
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
         let score = self.member_map.get(member)?;
-        
-        // 计算比当前 (score, member) 小的元素数量
-        // 跳表已经按 (score, member) 排序，直接遍历计算
-        let key = (*score, member.to_string());
-        let mut rank = 0;
-        for item in self.score_list.iter() {
-            if *item < key {
-                rank += 1;
-            } else {
-                break;
-            }
-        }
-        Some(rank)
+        // 排名即跳表中小于 (score, member) 的元素数量，O(log n)
+        Some(self.start_index(Bound::Included(&(*score, member.to_vec()))))
     }
 
     /// 获取指定范围的成员（按分数排序）
@@ -169,36 +159,124 @@ impl SortedSet {
     /// 
     /// # 返回
     /// 成员和分数的元组列表
-    pub fn range(&self, start: usize, stop: usize) -> Vec<(String, f64)> {
-        self.score_list
-            .iter()
-            .skip(start)
-            .take(stop.saturating_sub(start).saturating_add(1))
-            .map(|(score, member)| (member.clone(), *score))
-            .collect()
+    pub fn range(&self, start: usize, stop: usize) -> Vec<(Vec<u8>, f64)> {
+        self.slice(start, stop.saturating_add(1), false)
     }
 
-    /// 计算分数范围内的成员数量
+    /// 获取索引区间 [start, end) 内的成员，O(log n + m)
     /// 
     /// # 参数
-    /// - `min`: 最小分数（包含）
-    /// - `max`: 最大分数（包含）
-    pub fn count_by_score(&self, min: f64, max: f64) -> usize {
-        self.score_list
-            .iter()
-            .filter(|(score, _)| *score >= min && *score <= max)
-            .count()
+    /// - `rev`: 为 true 时按分数从大到小返回
+    pub fn slice(&self, start: usize, end: usize, rev: bool) -> Vec<(Vec<u8>, f64)> {
+        let end = end.min(self.len());
+        if start >= end {
+            return Vec::new();
+        }
+        let first = &self.score_list[start];
+        let last = &self.score_list[end - 1];
+        let items = self.score_list
+            .range(Bound::Included(first), Bound::Included(last))
+            .map(|(score, member)| (member.clone(), *score));
+        if rev {
+            items.rev().collect()
+        } else {
+            items.collect()
+        }
+    }
+
+    /// 删除索引区间 [start, end) 内的成员，返回删除的数量
+    pub fn remove_slice(&mut self, start: usize, end: usize) -> usize {
+        let removed = self.slice(start, end, false);
+        for (member, _) in &removed {
+            self.remove(member);
+        }
+        removed.len()
+    }
+
+    /// 弹出分数最小（`max` 为 false）或最大的 count 个成员，按弹出顺序返回
+    pub fn pop(&mut self, count: usize, max: bool) -> Vec<(Vec<u8>, f64)> {
+        let len = self.len();
+        let count = count.min(len);
+        let popped = if max {
+            self.slice(len - count, len, true)
+        } else {
+            self.slice(0, count, false)
+        };
+        for (member, _) in &popped {
+            self.remove(member);
+        }
+        popped
+    }
+
+    /// 获取指定索引的成员，O(log n)
+    pub fn get_by_index(&self, index: usize) -> Option<(&Vec<u8>, f64)> {
+        self.score_list.get(index).map(|(score, member)| (member, *score))
+    }
+
+    /// 分数区间对应的索引区间 [start, end)，O(log n)
+    pub fn score_range(&self, min: ScoreBound, max: ScoreBound) -> (usize, usize) {
+        // 同一分数的成员中空字节串最小，(score, []) 即该分数的下界
+        let start = match min {
+            ScoreBound::Inclusive(score) => self.start_index(Bound::Included(&(score, Vec::new()))),
+            ScoreBound::Exclusive(score) if score == f64::INFINITY => self.len(),
+            ScoreBound::Exclusive(score) => self.start_index(Bound::Included(&(score.next_up(), Vec::new()))),
+        };
+        let end = match max {
+            ScoreBound::Inclusive(score) if score == f64::INFINITY => self.len(),
+            ScoreBound::Inclusive(score) => self.end_index(Bound::Excluded(&(score.next_up(), Vec::new()))),
+            ScoreBound::Exclusive(score) => self.end_index(Bound::Excluded(&(score, Vec::new()))),
+        };
+        (start, end.max(start))
+    }
+
+    /// 字典序区间对应的索引区间 [start, end)，O(log n)
+    /// 
+    /// 与 Redis 相同，只有所有成员的分数相同时结果才有意义
+    pub fn lex_range(&self, min: &LexBound, max: &LexBound) -> (usize, usize) {
+        let (Some(first), Some(last)) = (self.score_list.front(), self.score_list.back()) else {
+            return (0, 0);
+        };
+        let start = match min {
+            LexBound::NegInf => 0,
+            LexBound::PosInf => self.len(),
+            LexBound::Inclusive(member) => self.start_index(Bound::Included(&(first.0, member.clone()))),
+            LexBound::Exclusive(member) => self.start_index(Bound::Excluded(&(first.0, member.clone()))),
+        };
+        let end = match max {
+            LexBound::NegInf => 0,
+            LexBound::PosInf => self.len(),
+            LexBound::Inclusive(member) => self.end_index(Bound::Included(&(last.0, member.clone()))),
+            LexBound::Exclusive(member) => self.end_index(Bound::Excluded(&(last.0, member.clone()))),
+        };
+        (start, end.max(start))
+    }
+
+    /// 不满足下界的元素数量，即第一个满足下界的元素的索引
+    fn start_index(&self, min: Bound<&(f64, Vec<u8>)>) -> usize {
+        match min {
+            Bound::Unbounded => 0,
+            Bound::Included(key) => self.score_list.range(Bound::Unbounded, Bound::Excluded(key)).size_hint().0,
+            Bound::Excluded(key) => self.score_list.range(Bound::Unbounded, Bound::Included(key)).size_hint().0,
+        }
+    }
+
+    /// 满足上界的元素数量，即最后一个满足上界的元素的索引加一
+    fn end_index(&self, max: Bound<&(f64, Vec<u8>)>) -> usize {
+        match max {
+            Bound::Unbounded => self.len(),
+            bound => self.score_list.range(Bound::Unbounded, bound).size_hint().0,
+        }
     }
 
     /// 获取所有成员（按分数排序）
-    pub fn iter(&self) -> impl Iterator<Item = (&String, &f64)> {
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<u8>, &f64)> {
         self.score_list
             .iter()
             .map(|(score, member)| (member, score))
     }
 
     /// 获取所有成员名称（按分数排序）
-    pub fn members(&self) -> Vec<String> {
+    pub fn members(&self) -> Vec<Vec<u8>> {
         self.score_list
             .iter()
             .map(|(_, member)| member.clone())
@@ -206,7 +284,7 @@ impl SortedSet {
     }
 
     /// 获取所有成员和分数（按分数排序）
-    pub fn members_with_scores(&self) -> Vec<(String, f64)> {
+    pub fn members_with_scores(&self) -> Vec<(Vec<u8>, f64)> {
         self.score_list
             .iter()
             .map(|(score, member)| (member.clone(), *score))
@@ -214,16 +292,9 @@ impl SortedSet {
     }
 
     /// 检查成员是否存在
-    pub fn contains(&self, member: &str) -> bool {
+    pub fn contains(&self, member: &[u8]) -> bool {
         self.member_map.contains_key(member)
     }
-
-    /// 获取所有成员名称（用于 ZLEXCOUNT 等命令，按字典序）
-    pub fn members_lex(&self) -> Vec<String> {
-        let mut members: Vec<String> = self.member_map.keys().cloned().collect();
-        members.sort();
-        members
-    }
 }
 
 impl Default for SortedSet {
@@ -232,3 +303,52 @@ impl Default for SortedSet {
     }
 }
 
+
+/// 分数区间的边界（ZRANGEBYSCORE 等命令的 min / max），"(" 开头表示开区间
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreBound {
+    Inclusive(f64),
+    Exclusive(f64),
+}
+
+impl ScoreBound {
+    /// 解析边界，支持 -inf / +inf，格式错误时返回 None
+    pub fn parse(value: &str) -> Option<Self> {
+        let (exclusive, number) = match value.strip_prefix('(') {
+            Some(number) => (true, number),
+            None => (false, value),
+        };
+        let score = number.parse::<f64>().ok().filter(|score| !score.is_nan())?;
+        Some(if exclusive { ScoreBound::Exclusive(score) } else { ScoreBound::Inclusive(score) })
+    }
+}
+
+/// 字典序区间的边界（ZRANGEBYLEX 等命令的 min / max）
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexBound {
+    /// 负无穷 (-)
+    NegInf,
+    /// 正无穷 (+)
+    PosInf,
+    /// 闭区间 [value
+    Inclusive(Vec<u8>),
+    /// 开区间 (value
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    /// 解析边界（成员按字节比较），格式错误时返回 None
+    pub fn parse(value: &[u8]) -> Option<Self> {
+        match value {
+            b"-" => Some(LexBound::NegInf),
+            b"+" => Some(LexBound::PosInf),
+            _ => {
+                if let Some(member) = value.strip_prefix(b"[") {
+                    Some(LexBound::Inclusive(member.to_vec()))
+                } else {
+                    value.strip_prefix(b"(").map(|member| LexBound::Exclusive(member.to_vec()))
+                }
+            },
+        }
+    }
+}
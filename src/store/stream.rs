@@ -0,0 +1,455 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use anyhow::Error;
+use bincode::{Decode, Encode};
+
+/// 消息的字段与值，保持写入时的顺序
+pub type StreamFields = Vec<(Vec<u8>, Vec<u8>)>;
+
+/**
+ * 消息 ID（毫秒时间戳-序号）
+ *
+ * 按 (ms, seq) 排序，与 Redis 基数树中的键顺序一致
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl StreamId {
+
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    pub const MAX: StreamId = StreamId { ms: u64::MAX, seq: u64::MAX };
+
+    pub fn new(ms: u64, seq: u64) -> Self {
+        StreamId { ms, seq }
+    }
+
+    /**
+     * 解析消息 ID
+     *
+     * @param text ID 文本，格式为 ms-seq 或 ms
+     * @param default_seq 省略序号时使用的序号
+     */
+    pub fn parse(text: &str, default_seq: u64) -> Result<StreamId, Error> {
+        let invalid = || Error::msg("ERR Invalid stream ID specified as stream command argument");
+        match text.split_once('-') {
+            Some((ms, seq)) => {
+                let ms = ms.parse::<u64>().map_err(|_| invalid())?;
+                let seq = seq.parse::<u64>().map_err(|_| invalid())?;
+                Ok(StreamId::new(ms, seq))
+            },
+            None => {
+                let ms = text.parse::<u64>().map_err(|_| invalid())?;
+                Ok(StreamId::new(ms, default_seq))
+            }
+        }
+    }
+
+    /**
+     * 解析范围的边界（XRANGE、XPENDING 等）
+     *
+     * 支持 - 与 + 表示最小与最大 ID，( 前缀表示不包含该 ID
+     *
+     * @param text 边界文本
+     * @param is_start 是否为范围起点
+     * @return 范围为空（如 (18446744073709551615-18446744073709551615）时返回 None
+     */
+    pub fn parse_range_bound(text: &str, is_start: bool) -> Result<Option<StreamId>, Error> {
+        match text {
+            "-" => return Ok(Some(StreamId::MIN)),
+            "+" => return Ok(Some(StreamId::MAX)),
+            _ => {}
+        }
+        let default_seq = if is_start { 0 } else { u64::MAX };
+        match text.strip_prefix('(') {
+            Some(exclusive) => {
+                let id = StreamId::parse(exclusive, default_seq)?;
+                Ok(if is_start { id.next() } else { id.prev() })
+            },
+            None => StreamId::parse(text, default_seq).map(Some),
+        }
+    }
+
+    /// 下一个 ID，已是最大 ID 时返回 None
+    pub fn next(&self) -> Option<StreamId> {
+        if self.seq < u64::MAX {
+            Some(StreamId::new(self.ms, self.seq + 1))
+        } else if self.ms < u64::MAX {
+            Some(StreamId::new(self.ms + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    /// 上一个 ID，已是最小 ID 时返回 None
+    pub fn prev(&self) -> Option<StreamId> {
+        if self.seq > 0 {
+            Some(StreamId::new(self.ms, self.seq - 1))
+        } else if self.ms > 0 {
+            Some(StreamId::new(self.ms - 1, u64::MAX))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+/// 裁剪策略（MAXLEN 或 MINID）
+#[derive(Clone, Copy, Debug)]
+pub enum TrimStrategy {
+    MaxLen(usize),
+    MinId(StreamId),
+}
+
+/**
+ * 待确认消息（PEL 中的一项）
+ *
+ * @param consumer 消息当前所属的消费者
+ * @param delivery_time 最近一次投递的时间（毫秒）
+ * @param delivery_count 投递次数
+ */
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct PendingEntry {
+    pub consumer: String,
+    pub delivery_time: u64,
+    pub delivery_count: u64,
+}
+
+/**
+ * 消费者
+ *
+ * @param seen_time 最近一次活跃的时间（毫秒）
+ * @param pending 该消费者名下的待确认消息
+ */
+#[derive(Clone, Debug, Default, Encode, Decode)]
+pub struct Consumer {
+    pub seen_time: u64,
+    pub pending: BTreeSet<StreamId>,
+}
+
+/**
+ * 消费者组
+ *
+ * @param last_delivered_id 最后投递给组内消费者的消息 ID
+ * @param pending 组内所有已投递但未确认的消息（PEL）
+ * @param consumers 组内的消费者
+ */
+#[derive(Clone, Debug, Default, Encode, Decode)]
+pub struct ConsumerGroup {
+    pub last_delivered_id: StreamId,
+    pub pending: BTreeMap<StreamId, PendingEntry>,
+    pub consumers: BTreeMap<String, Consumer>,
+}
+
+impl ConsumerGroup {
+
+    pub fn new(last_delivered_id: StreamId) -> Self {
+        ConsumerGroup {
+            last_delivered_id,
+            ..Default::default()
+        }
+    }
+
+    /**
+     * 获取消费者，不存在时自动创建
+     *
+     * @param name 消费者名称
+     * @param now 当前时间（毫秒）
+     */
+    pub fn consumer(&mut self, name: &str, now: u64) -> &mut Consumer {
+        let consumer = self.consumers.entry(name.to_string()).or_default();
+        consumer.seen_time = now;
+        consumer
+    }
+
+    /**
+     * 创建消费者
+     *
+     * @return 消费者已存在时返回 false
+     */
+    pub fn create_consumer(&mut self, name: &str, now: u64) -> bool {
+        if self.consumers.contains_key(name) {
+            return false;
+        }
+        self.consumer(name, now);
+        true
+    }
+
+    /**
+     * 删除消费者及其名下的待确认消息
+     *
+     * @return 被删除的待确认消息数量，消费者不存在时返回 None
+     */
+    pub fn delete_consumer(&mut self, name: &str) -> Option<usize> {
+        let consumer = self.consumers.remove(name)?;
+        for id in &consumer.pending {
+            self.pending.remove(id);
+        }
+        Some(consumer.pending.len())
+    }
+
+    /**
+     * 将消息分配给消费者（投递或认领）
+     *
+     * @param id 消息 ID
+     * @param consumer 消费者名称
+     * @param delivery_time 投递时间（毫秒）
+     * @param delivery_count 投递次数
+     */
+    pub fn assign(&mut self, id: StreamId, consumer: &str, delivery_time: u64, delivery_count: u64) {
+        if let Some(previous) = self.pending.get(&id) {
+            if let Some(owner) = self.consumers.get_mut(&previous.consumer) {
+                owner.pending.remove(&id);
+            }
+        }
+        self.consumers.entry(consumer.to_string()).or_default().pending.insert(id);
+        self.pending.insert(id, PendingEntry {
+            consumer: consumer.to_string(),
+            delivery_time,
+            delivery_count,
+        });
+    }
+
+    /**
+     * 确认消息，从 PEL 中移除
+     *
+     * @return 消息不在 PEL 中时返回 false
+     */
+    pub fn ack(&mut self, id: &StreamId) -> bool {
+        match self.pending.remove(id) {
+            Some(entry) => {
+                if let Some(consumer) = self.consumers.get_mut(&entry.consumer) {
+                    consumer.pending.remove(id);
+                }
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+/**
+ * 流（Stream）
+ *
+ * @param entries 消息，按 ID 有序存储
+ * @param last_id 最后生成的消息 ID（消息被删除后仍然保留，保证 ID 单调递增）
+ * @param groups 消费者组
+ */
+#[derive(Clone, Debug, Default, Encode, Decode)]
+pub struct Stream {
+    entries: BTreeMap<StreamId, StreamFields>,
+    last_id: StreamId,
+    groups: BTreeMap<String, ConsumerGroup>,
+}
+
+impl Stream {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&StreamId, &StreamFields)> {
+        self.entries.iter()
+    }
+
+    pub fn entry(&self, id: &StreamId) -> Option<&StreamFields> {
+        self.entries.get(id)
+    }
+
+    /**
+     * 追加消息，调用者需保证 ID 大于 last_id
+     *
+     * @param id 消息 ID
+     * @param fields 字段与值
+     */
+    pub fn add(&mut self, id: StreamId, fields: StreamFields) {
+        self.entries.insert(id, fields);
+        self.last_id = id;
+    }
+
+    /**
+     * 按 ID 范围查询消息
+     *
+     * @param start 起始 ID（包含）
+     * @param end 结束 ID（包含）
+     * @param count 最多返回的数量
+     * @param rev 是否逆序
+     */
+    pub fn range(&self, start: StreamId, end: StreamId, count: Option<usize>, rev: bool) -> Vec<(StreamId, &StreamFields)> {
+        if start > end {
+            return Vec::new();
+        }
+        let limit = count.unwrap_or(usize::MAX);
+        let range = self.entries.range(start..=end);
+        if rev {
+            range.rev().take(limit).map(|(id, fields)| (*id, fields)).collect()
+        } else {
+            range.take(limit).map(|(id, fields)| (*id, fields)).collect()
+        }
+    }
+
+    /**
+     * 删除消息
+     *
+     * @return 消息不存在时返回 false
+     */
+    pub fn delete(&mut self, id: &StreamId) -> bool {
+        self.entries.remove(id).is_some()
+    }
+
+    /// 按裁剪策略是否有消息需要删除
+    pub fn needs_trim(&self, strategy: TrimStrategy) -> bool {
+        match (strategy, self.entries.keys().next()) {
+            (_, None) => false,
+            (TrimStrategy::MaxLen(max_len), Some(_)) => self.entries.len() > max_len,
+            (TrimStrategy::MinId(min_id), Some(oldest)) => *oldest < min_id,
+        }
+    }
+
+    /**
+     * 裁剪最旧的消息
+     *
+     * @param strategy 裁剪策略
+     * @param limit 最多删除的数量（LIMIT）
+     * @return 被删除的消息数量
+     */
+    pub fn trim(&mut self, strategy: TrimStrategy, limit: Option<usize>) -> usize {
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut removed = 0;
+        while removed < limit {
+            let oldest = match self.entries.keys().next() {
+                Some(id) => *id,
+                None => break,
+            };
+            let should_remove = match strategy {
+                TrimStrategy::MaxLen(max_len) => self.entries.len() > max_len,
+                TrimStrategy::MinId(min_id) => oldest < min_id,
+            };
+            if !should_remove {
+                break;
+            }
+            self.delete(&oldest);
+            removed += 1;
+        }
+        removed
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    pub fn group_mut(&mut self, name: &str) -> Option<&mut ConsumerGroup> {
+        self.groups.get_mut(name)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (&String, &ConsumerGroup)> {
+        self.groups.iter()
+    }
+
+    /**
+     * 同时获取消费者组（可变）与消息（只读），用于认领消息时检查消息是否仍然存在
+     *
+     * @param name 消费者组名称
+     */
+    pub fn group_with_entries(&mut self, name: &str) -> Option<(&mut ConsumerGroup, &BTreeMap<StreamId, StreamFields>)> {
+        let group = self.groups.get_mut(name)?;
+        Some((group, &self.entries))
+    }
+
+    /**
+     * 创建消费者组
+     *
+     * @return 消费者组已存在时返回 false
+     */
+    pub fn create_group(&mut self, name: &str, last_delivered_id: StreamId) -> bool {
+        if self.groups.contains_key(name) {
+            return false;
+        }
+        self.groups.insert(name.to_string(), ConsumerGroup::new(last_delivered_id));
+        true
+    }
+
+    pub fn destroy_group(&mut self, name: &str) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    /**
+     * 为消费者读取新消息（XREADGROUP 使用 > 作为 ID）
+     *
+     * 读取 last_delivered_id 之后的消息，推进 last_delivered_id，并将消息加入 PEL（NOACK 时除外）
+     *
+     * @param group 消费者组名称（调用者需保证存在）
+     * @param consumer 消费者名称
+     * @param count 最多读取的数量
+     * @param noack 是否无需确认
+     * @param now 当前时间（毫秒）
+     */
+    pub fn read_new(&mut self, group: &str, consumer: &str, count: Option<usize>, noack: bool, now: u64) -> Vec<(StreamId, StreamFields)> {
+        let group = match self.groups.get_mut(group) {
+            Some(group) => group,
+            None => return Vec::new(),
+        };
+        group.consumer(consumer, now);
+        let start = match group.last_delivered_id.next() {
+            Some(start) => start,
+            None => return Vec::new(),
+        };
+        let entries: Vec<(StreamId, StreamFields)> = self.entries
+            .range(start..)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| (*id, fields.clone()))
+            .collect();
+        for (id, _) in &entries {
+            group.last_delivered_id = *id;
+            if !noack {
+                group.assign(*id, consumer, now, 1);
+            }
+        }
+        entries
+    }
+
+    /**
+     * 读取消费者名下的待确认消息（XREADGROUP 使用具体 ID）
+     *
+     * 已被删除的消息返回 None
+     *
+     * @param group 消费者组名称（调用者需保证存在）
+     * @param consumer 消费者名称
+     * @param start 起始 ID（不包含）
+     * @param count 最多读取的数量
+     * @param now 当前时间（毫秒）
+     */
+    pub fn read_pending(&mut self, group: &str, consumer: &str, start: StreamId, count: Option<usize>, now: u64) -> Vec<(StreamId, Option<StreamFields>)> {
+        let group = match self.groups.get_mut(group) {
+            Some(group) => group,
+            None => return Vec::new(),
+        };
+        let consumer = group.consumer(consumer, now);
+        consumer.pending
+            .iter()
+            .filter(|id| **id > start)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|id| (*id, self.entries.get(id).cloned()))
+            .collect()
+    }
+}
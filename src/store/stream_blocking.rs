@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// 共享的发送端，客户端可能同时等待多个流，但只需要被唤醒一次
+type SharedSender = Arc<Mutex<Option<oneshot::Sender<()>>>>;
+
+/// 等待流写入的客户端
+struct StreamWaiter {
+    session_id: usize,
+    sender: SharedSender,
+}
+
+/// 流阻塞管理器
+///
+/// 管理所有等待 XREAD BLOCK / XREADGROUP BLOCK 的客户端
+///
+/// 与列表不同，流的读取不会消费消息，因此写入时唤醒该键上的所有等待者，
+/// 由它们重新执行读取：读到消息则返回，否则继续等待
+pub struct StreamManager {
+    // (数据库索引, key) -> 等待该键的客户端（FIFO）
+    waiting: HashMap<(usize, Vec<u8>), Vec<StreamWaiter>>,
+    // session_id -> (数据库索引, key) 映射（用于唤醒或断开时快速清理）
+    session_to_keys: HashMap<usize, Vec<(usize, Vec<u8>)>>,
+}
+
+impl Default for StreamManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamManager {
+    pub fn new() -> Self {
+        Self {
+            waiting: HashMap::new(),
+            session_to_keys: HashMap::new(),
+        }
+    }
+
+    /// 注册等待请求
+    ///
+    /// 返回一个 receiver，任意一个键有新的写入时被唤醒
+    pub fn register(&mut self, db_index: usize, keys: &[Vec<u8>], session_id: usize) -> oneshot::Receiver<()> {
+        let (sender, receiver) = oneshot::channel();
+        let shared_sender = Arc::new(Mutex::new(Some(sender)));
+
+        let mut session_keys = Vec::new();
+        for key in keys {
+            let entry = (db_index, key.clone());
+            self.waiting
+                .entry(entry.clone())
+                .or_default()
+                .push(StreamWaiter { session_id, sender: shared_sender.clone() });
+            session_keys.push(entry);
+        }
+        self.session_to_keys.insert(session_id, session_keys);
+
+        receiver
+    }
+
+    /// 唤醒等待该键的所有客户端（XADD 等写入后调用）
+    ///
+    /// 返回被唤醒的客户端数量
+    pub fn notify(&mut self, db_index: usize, key: &[u8]) -> usize {
+        let waiters = match self.waiting.remove(&(db_index, key.to_vec())) {
+            Some(waiters) => waiters,
+            None => return 0,
+        };
+
+        let mut woken = 0;
+        for waiter in waiters {
+            let sender = waiter.sender.lock().unwrap().take();
+            if let Some(sender) = sender {
+                let _ = sender.send(());
+                woken += 1;
+            }
+            // 清理该会话在其他键上的等待记录
+            self.cleanup_session(waiter.session_id);
+        }
+        woken
+    }
+
+    /// 检查是否有等待该键的客户端
+    pub fn has_waiting(&self, db_index: usize, key: &[u8]) -> bool {
+        self.waiting.contains_key(&(db_index, key.to_vec()))
+    }
+
+    /// 清理客户端的所有等待请求（客户端断开、超时或读取成功后调用）
+    pub fn cleanup_session(&mut self, session_id: usize) {
+        if let Some(keys) = self.session_to_keys.remove(&session_id) {
+            for entry in keys {
+                if let Some(waiters) = self.waiting.get_mut(&entry) {
+                    waiters.retain(|waiter| waiter.session_id != session_id);
+                    if waiters.is_empty() {
+                        self.waiting.remove(&entry);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+/**
+ * CRC64（Jones 多项式，与 Redis 的 RDB 校验和相同）
+ *
+ * 反射输入输出，初始值为 0，不做最终异或；"123456789" 的校验值为 0xe9c6d914c4b8d9ca
+ */
+const POLY: u64 = 0x95ac9329ac4bc9b5;
+
+const TABLE: [u64; 256] = build_table();
+
+const fn build_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ POLY } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// 在已有校验值的基础上继续计算
+pub fn update(mut crc: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        crc = TABLE[((crc ^ byte as u64) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+/// 计算字节的校验值
+pub fn crc64(bytes: &[u8]) -> u64 {
+    update(0, bytes)
+}
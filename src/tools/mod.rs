@@ -1 +1,2 @@
+pub mod crc64;
 pub mod pattern;
\ No newline at end of file
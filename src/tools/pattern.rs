@@ -1,43 +1,66 @@
 use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
-use regex::Regex;
+use regex::bytes::Regex;
 
 // 使用 Mutex 保护的 HashMap 来缓存编译后的正则表达式
-fn regex_cache() -> &'static Mutex<HashMap<String, Regex>> {
-    static CACHE: OnceLock<Mutex<HashMap<String, Regex>>> = OnceLock::new();
+fn regex_cache() -> &'static Mutex<HashMap<Vec<u8>, Regex>> {
+    static CACHE: OnceLock<Mutex<HashMap<Vec<u8>, Regex>>> = OnceLock::new();
     CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-pub fn is_match(key: &str, pattern: &str) -> bool {
-    fn convert_pattern(pattern: &str) -> String {
-        let mut regex_pattern = String::new();
-        let mut chars = pattern.chars().peekable();
-        while let Some(p) = chars.next() {
+/**
+ * glob 模式匹配（二进制安全，键与模式都可以包含任意字节）
+ *
+ * @param key 被匹配的内容
+ * @param pattern 模式
+ */
+pub fn is_match(key: impl AsRef<[u8]>, pattern: impl AsRef<[u8]>) -> bool {
+    fn push_literal(regex_pattern: &mut String, byte: u8) {
+        if byte.is_ascii_alphanumeric() {
+            regex_pattern.push(byte as char);
+        } else {
+            regex_pattern.push_str(&format!("\\x{:02X}", byte));
+        }
+    }
+
+    fn convert_pattern(pattern: &[u8]) -> String {
+        // 整体匹配：glob 语义要求匹配整个字符串，而不是其中的一段；关闭 Unicode 后按字节匹配
+        let mut regex_pattern = String::from("(?s-u)^");
+        let mut bytes = pattern.iter().copied().peekable();
+        while let Some(p) = bytes.next() {
             match p {
-                '*' => regex_pattern.push_str(".*"), 
-                '?' => regex_pattern.push('.'),    
-                '[' => {
+                b'*' => regex_pattern.push_str(".*"),
+                b'?' => regex_pattern.push('.'),
+                b'\\' => {
+                    // 转义字符：下一个字符按字面量匹配
+                    if let Some(byte) = bytes.next() {
+                        push_literal(&mut regex_pattern, byte);
+                    }
+                }
+                b'[' => {
                     regex_pattern.push('[');
-                    if let Some(next) = chars.peek() {
-                        if *next == '^' {
-                            regex_pattern.push('^');
-                            chars.next(); // 跳过 '^'
-                        }
+                    if bytes.peek() == Some(&b'^') {
+                        regex_pattern.push('^');
+                        bytes.next(); // 跳过 '^'
                     }
-                    while let Some(ch) = chars.next() {
-                        if ch == ']' {
-                            break;
+                    for byte in bytes.by_ref() {
+                        match byte {
+                            b']' => break,
+                            b'-' => regex_pattern.push('-'),
+                            _ => push_literal(&mut regex_pattern, byte),
                         }
-                        regex_pattern.push(ch);
                     }
                     regex_pattern.push(']');
                 }
-                _ => regex_pattern.push(p)
+                // 其余字符均为字面量，需要转义正则元字符（如 '.'、'+'）
+                _ => push_literal(&mut regex_pattern, p),
             }
         }
+        regex_pattern.push('$');
         regex_pattern
     }
-    
+
+    let pattern = pattern.as_ref();
     // 尝试从缓存中获取已编译的正则表达式
     let regex = {
         let cache = regex_cache().lock().unwrap();
@@ -46,12 +69,15 @@ pub fn is_match(key: &str, pattern: &str) -> bool {
         } else {
             drop(cache); // 释放读锁后再进行写操作
             let regex_pattern = convert_pattern(pattern);
-            let regex = Regex::new(&regex_pattern).unwrap();
+            let regex = match Regex::new(&regex_pattern) {
+                Ok(regex) => regex,
+                Err(_) => return false, // 非法模式不匹配任何内容
+            };
             let mut cache = regex_cache().lock().unwrap();
-            cache.insert(pattern.to_string(), regex.clone());
+            cache.insert(pattern.to_vec(), regex.clone());
             regex
         }
     };
-    
-    regex.is_match(key)
-}
\ No newline at end of file
+
+    regex.is_match(key.as_ref())
+}
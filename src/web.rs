@@ -15,9 +15,12 @@ use tower_http::{
     services::ServeDir,
 };
 
+use crate::acl::DEFAULT_USER;
 use crate::args::Args;
 use crate::command::Command;
 use crate::frame::Frame;
+use crate::persistence::aof_file::{AofFile, AofMessage};
+use crate::server::ServerState;
 use crate::store::db::DatabaseMessage;
 use crate::store::db_manager::DatabaseManager;
 
@@ -25,14 +28,16 @@ use crate::store::db_manager::DatabaseManager;
 pub struct WebServer {
     args: Arc<Args>,
     db_manager: Arc<DatabaseManager>,
+    server_state: Arc<ServerState>,
 }
 
 impl WebServer {
     
-    pub fn new(args: Arc<Args>, db_manager: Arc<DatabaseManager>) -> Self {
+    pub fn new(args: Arc<Args>, db_manager: Arc<DatabaseManager>, server_state: Arc<ServerState>) -> Self {
         WebServer {
             args,
             db_manager,
+            server_state,
         }
     }
 
@@ -42,6 +47,7 @@ impl WebServer {
         let aof_path = PathBuf::from(&self.args.appendfilename);
         let web_state = Arc::new(WebState {
             db_manager: self.db_manager,
+            server_state: self.server_state,
             max_databases,
             webuser: self.args.webuser.clone(),
             webpass: self.args.webpass.clone(),
@@ -56,6 +62,7 @@ impl WebServer {
 /// Web服务状态
 pub struct WebState {
     pub db_manager: Arc<DatabaseManager>,
+    pub server_state: Arc<ServerState>,
     pub max_databases: usize,
     pub webuser: String,
     pub webpass: String,
@@ -159,12 +166,23 @@ fn create_router(state: Arc<WebState>) -> Router {
         .with_state(state)
 }
 
-/// 登录验证
+/// 配置了 ACL 用户（包括为默认用户设置密码）时，Web 管理界面不再以默认用户的身份免密访问
+fn acl_configured(state: &WebState) -> bool {
+    let acl = state.server_state.acl.read().unwrap();
+    !acl.default_user_nopass() || acl.users().count() > 1
+}
+
+/// 登录验证，配置了 ACL 用户时使用 ACL 用户名与密码，否则使用 webuser / webpass
 async fn login(
     State(state): State<Arc<WebState>>,
     Json(req): Json<LoginRequest>,
 ) -> impl IntoResponse {
-    if req.username == state.webuser && req.password == state.webpass {
+    let authenticated = if acl_configured(&state) {
+        state.server_state.acl.read().unwrap().authenticate(&req.username, &req.password)
+    } else {
+        req.username == state.webuser && req.password == state.webpass
+    };
+    if authenticated {
         Json(json!({
             "success": true,
             "message": "登录成功"
@@ -177,7 +195,12 @@ async fn login(
     }
 }
 
-/// 执行CLI命令
+/**
+ * 执行CLI命令
+ *
+ * 控制台以默认用户的身份执行命令：配置了 ACL 用户时禁用，否则按默认用户的权限检查；
+ * 写命令与客户端连接一样持有写屏障，并追加到 AOF、传播给从节点
+ */
 async fn execute_cli(
     State(state): State<Arc<WebState>>,
     Json(req): Json<CliRequest>,
@@ -199,11 +222,27 @@ async fn execute_cli(
         }));
     }
 
+    if acl_configured(&state) {
+        return Json(json!({
+            "success": false,
+            "result": "(error) 配置了 ACL 用户时 Web 控制台不可用，请使用客户端认证后执行命令"
+        }));
+    }
+    let denied = state.server_state.acl.read().unwrap()
+        .get_user(DEFAULT_USER)
+        .and_then(|user| user.check(&parts).err());
+    if let Some(denied) = denied {
+        return Json(json!({
+            "success": false,
+            "result": format!("(error) {}", denied.to_error(DEFAULT_USER))
+        }));
+    }
+
     let frame = Frame::Array(
-        parts.iter().map(|s| Frame::BulkString(s.to_string())).collect()
+        parts.iter().map(|s| Frame::BulkString(s.to_string().into())).collect()
     );
 
-    let command = match Command::parse_from_frame(frame) {
+    let command = match Command::parse_from_frame(frame.clone()) {
         Ok(cmd) => cmd,
         Err(e) => {
             return Json(json!({
@@ -213,6 +252,20 @@ async fn execute_cli(
         }
     };
 
+    let server_state = &state.server_state;
+    let should_propagate = command.propagate_aof_if_needed();
+    if should_propagate && server_state.config.values().replica_read_only && server_state.is_replica() {
+        return Json(json!({
+            "success": false,
+            "result": "(error) READONLY You can't write against a read only replica."
+        }));
+    }
+    let barrier = if should_propagate {
+        Some(server_state.write_barrier.read().await)
+    } else {
+        None
+    };
+
     let sender = state.db_manager.get_sender(db_id);
     let (tx, rx) = oneshot::channel();
     let message = DatabaseMessage::Command { sender: tx, command };
@@ -226,6 +279,15 @@ async fn execute_cli(
 
     match rx.await {
         Ok(result_frame) => {
+            if should_propagate && !matches!(result_frame, Frame::Error(_)) {
+                if let Some(propagated) = Command::propagation_frame(frame, &result_frame) {
+                    if let Some(aof) = &server_state.aof {
+                        let _ = aof.get_sender().send(AofMessage::Append(db_id, propagated.clone())).await;
+                    }
+                    server_state.replication.feed(db_id, &propagated);
+                }
+            }
+            drop(barrier);
             let result = format_frame_result(&result_frame);
             Json(json!({
                 "success": true,
@@ -243,13 +305,17 @@ async fn execute_cli(
 fn format_frame_result(frame: &Frame) -> String {
     match frame {
         Frame::SimpleString(s) => s.clone(),
-        Frame::BulkString(s) => format!("\"{}\"", s),
+        Frame::BulkString(s) => format!("\"{}\"", String::from_utf8_lossy(s)),
         Frame::Integer(i) => format!("(integer) {}", i),
-        Frame::Null => "(nil)".to_string(),
+        Frame::Null | Frame::NullArray => "(nil)".to_string(),
         Frame::Error(e) => format!("(error) {}", e),
         Frame::Ok => "OK".to_string(),
         Frame::RDBFile(_) => "(rdb file)".to_string(),
-        Frame::Array(arr) => {
+        Frame::Double(d) => format!("(double) {}", Frame::format_double(*d)),
+        Frame::Boolean(b) => format!("({})", b),
+        Frame::BigNumber(n) => format!("(big number) {}", n),
+        Frame::Verbatim(_, data) => format!("\"{}\"", String::from_utf8_lossy(data)),
+        Frame::Array(arr) | Frame::Set(arr) | Frame::Push(arr) => {
             if arr.is_empty() {
                 "(empty array)".to_string()
             } else {
@@ -260,6 +326,17 @@ fn format_frame_result(frame: &Frame) -> String {
                     .join("\n")
             }
         }
+        Frame::Map(pairs) => {
+            if pairs.is_empty() {
+                "(empty hash)".to_string()
+            } else {
+                pairs.iter()
+                    .enumerate()
+                    .map(|(i, (k, v))| format!("{}# {} => {}", i + 1, format_frame_result(k), format_frame_result(v)))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
     }
 }
 
@@ -291,20 +368,10 @@ async fn get_aof_logs(
 
     match tokio::fs::read(&state.aof_path).await {
         Ok(content) => {
-            let mut commands = Vec::new();
-            let separator = b"\r\n\r\n";
-            let mut start = 0;
-            
-            while let Some(pos) = content[start..].windows(separator.len()).position(|w| w == separator) {
-                let end = start + pos;
-                let frame_data = &content[start..end + 2];
-                if !frame_data.is_empty() {
-                    if let Ok(frame) = Frame::parse_from_bytes(frame_data) {
-                        commands.push(format_frame_as_command(&frame));
-                    }
-                }
-                start = end + separator.len();
-            }
+            let commands: Vec<String> = AofFile::parse_frames(&content)
+                .iter()
+                .map(format_frame_as_command)
+                .collect();
             
             let total = commands.len();
             // 从后往前取，返回最新的命令
@@ -335,7 +402,7 @@ fn format_frame_as_command(frame: &Frame) -> String {
         Frame::Array(arr) => {
             arr.iter()
                 .map(|f| match f {
-                    Frame::BulkString(s) => s.clone(),
+                    Frame::BulkString(s) => String::from_utf8_lossy(s).into_owned(),
                     Frame::SimpleString(s) => s.clone(),
                     Frame::Integer(i) => i.to_string(),
                     _ => String::new(),
@@ -357,7 +424,7 @@ async fn get_stats(State(state): State<Arc<WebState>>) -> impl IntoResponse {
         let sender = state.db_manager.get_sender(db_id);
         
         // 获取DBSIZE
-        let frame = Frame::Array(vec![Frame::BulkString("DBSIZE".to_string())]);
+        let frame = Frame::Array(vec![Frame::BulkString("DBSIZE".to_string().into())]);
         if let Ok(command) = Command::parse_from_frame(frame) {
             let (tx, rx) = oneshot::channel();
             let message = DatabaseMessage::Command { sender: tx, command };
@@ -403,7 +470,7 @@ async fn list_databases(State(state): State<Arc<WebState>>) -> impl IntoResponse
         let sender = state.db_manager.get_sender(db_id);
         
         // 获取DBSIZE
-        let frame = Frame::Array(vec![Frame::BulkString("DBSIZE".to_string())]);
+        let frame = Frame::Array(vec![Frame::BulkString("DBSIZE".to_string().into())]);
         let command = match Command::parse_from_frame(frame) {
             Ok(cmd) => cmd,
             Err(_) => continue,
@@ -456,8 +523,8 @@ async fn list_keys(
     
     // 执行KEYS命令
     let frame = Frame::Array(vec![
-        Frame::BulkString("KEYS".to_string()),
-        Frame::BulkString(pattern),
+        Frame::BulkString("KEYS".to_string().into()),
+        Frame::BulkString(pattern.into()),
     ]);
     
     let command = match Command::parse_from_frame(frame) {
@@ -492,7 +559,7 @@ async fn list_keys(
                 Frame::Array(frames) => {
                     frames.into_iter().filter_map(|f| {
                         if let Frame::BulkString(key) = f {
-                            Some(key)
+                            Some(String::from_utf8_lossy(&key).into_owned())
                         } else {
                             None
                         }
@@ -533,8 +600,8 @@ async fn get_key_info(state: &Arc<WebState>, db_id: usize, key: &str) -> Option<
     
     // 获取键类型
     let type_frame = Frame::Array(vec![
-        Frame::BulkString("TYPE".to_string()),
-        Frame::BulkString(key.to_string()),
+        Frame::BulkString("TYPE".to_string().into()),
+        Frame::BulkString(key.to_string().into()),
     ]);
     
     let type_command = Command::parse_from_frame(type_frame).ok()?;
@@ -548,8 +615,8 @@ async fn get_key_info(state: &Arc<WebState>, db_id: usize, key: &str) -> Option<
     
     // 获取TTL
     let ttl_frame = Frame::Array(vec![
-        Frame::BulkString("TTL".to_string()),
-        Frame::BulkString(key.to_string()),
+        Frame::BulkString("TTL".to_string().into()),
+        Frame::BulkString(key.to_string().into()),
     ]);
     
     let ttl_command = Command::parse_from_frame(ttl_frame).ok()?;
@@ -590,8 +657,8 @@ async fn get_key_value(
     
     // 先获取键类型
     let type_frame = Frame::Array(vec![
-        Frame::BulkString("TYPE".to_string()),
-        Frame::BulkString(key.clone()),
+        Frame::BulkString("TYPE".to_string().into()),
+        Frame::BulkString(key.clone().into()),
     ]);
     
     let type_command = match Command::parse_from_frame(type_frame) {
@@ -653,8 +720,8 @@ async fn get_key_value(
     
     // 获取TTL
     let ttl_frame = Frame::Array(vec![
-        Frame::BulkString("TTL".to_string()),
-        Frame::BulkString(key.clone()),
+        Frame::BulkString("TTL".to_string().into()),
+        Frame::BulkString(key.clone().into()),
     ]);
     
     let ttl_command = Command::parse_from_frame(ttl_frame).ok().unwrap();
@@ -684,8 +751,8 @@ async fn get_key_value(
 async fn get_string_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Option<serde_json::Value> {
     let sender = state.db_manager.get_sender(db_id);
     let frame = Frame::Array(vec![
-        Frame::BulkString("GET".to_string()),
-        Frame::BulkString(key.to_string()),
+        Frame::BulkString("GET".to_string().into()),
+        Frame::BulkString(key.to_string().into()),
     ]);
     
     let command = Command::parse_from_frame(frame).ok()?;
@@ -693,7 +760,7 @@ async fn get_string_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Opt
     sender.send(DatabaseMessage::Command { sender: tx, command }).await.ok()?;
     
     match rx.await.ok()? {
-        Frame::BulkString(s) => Some(json!(s)),
+        Frame::BulkString(s) => Some(json!(String::from_utf8_lossy(&s))),
         Frame::Null => None,
         _ => None,
     }
@@ -703,8 +770,8 @@ async fn get_string_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Opt
 async fn get_hash_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Option<serde_json::Value> {
     let sender = state.db_manager.get_sender(db_id);
     let frame = Frame::Array(vec![
-        Frame::BulkString("HGETALL".to_string()),
-        Frame::BulkString(key.to_string()),
+        Frame::BulkString("HGETALL".to_string().into()),
+        Frame::BulkString(key.to_string().into()),
     ]);
     
     let command = Command::parse_from_frame(frame).ok()?;
@@ -712,11 +779,12 @@ async fn get_hash_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Optio
     sender.send(DatabaseMessage::Command { sender: tx, command }).await.ok()?;
     
     match rx.await.ok()? {
-        Frame::Array(frames) => {
+        Frame::Map(pairs) => {
             let mut map = serde_json::Map::new();
-            let mut iter = frames.into_iter();
-            while let (Some(Frame::BulkString(k)), Some(Frame::BulkString(v))) = (iter.next(), iter.next()) {
-                map.insert(k, json!(v));
+            for pair in pairs {
+                if let (Frame::BulkString(k), Frame::BulkString(v)) = pair {
+                    map.insert(String::from_utf8_lossy(&k).into_owned(), json!(String::from_utf8_lossy(&v)));
+                }
             }
             Some(json!(map))
         }
@@ -728,10 +796,10 @@ async fn get_hash_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Optio
 async fn get_list_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Option<serde_json::Value> {
     let sender = state.db_manager.get_sender(db_id);
     let frame = Frame::Array(vec![
-        Frame::BulkString("LRANGE".to_string()),
-        Frame::BulkString(key.to_string()),
-        Frame::BulkString("0".to_string()),
-        Frame::BulkString("-1".to_string()),
+        Frame::BulkString("LRANGE".to_string().into()),
+        Frame::BulkString(key.to_string().into()),
+        Frame::BulkString("0".to_string().into()),
+        Frame::BulkString("-1".to_string().into()),
     ]);
     
     let command = Command::parse_from_frame(frame).ok()?;
@@ -742,7 +810,7 @@ async fn get_list_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Optio
         Frame::Array(frames) => {
             let values: Vec<String> = frames.into_iter().filter_map(|f| {
                 if let Frame::BulkString(s) = f {
-                    Some(s)
+                    Some(String::from_utf8_lossy(&s).into_owned())
                 } else {
                     None
                 }
@@ -757,8 +825,8 @@ async fn get_list_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Optio
 async fn get_set_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Option<serde_json::Value> {
     let sender = state.db_manager.get_sender(db_id);
     let frame = Frame::Array(vec![
-        Frame::BulkString("SMEMBERS".to_string()),
-        Frame::BulkString(key.to_string()),
+        Frame::BulkString("SMEMBERS".to_string().into()),
+        Frame::BulkString(key.to_string().into()),
     ]);
     
     let command = Command::parse_from_frame(frame).ok()?;
@@ -766,10 +834,10 @@ async fn get_set_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Option
     sender.send(DatabaseMessage::Command { sender: tx, command }).await.ok()?;
     
     match rx.await.ok()? {
-        Frame::Array(frames) => {
+        Frame::Set(frames) => {
             let values: Vec<String> = frames.into_iter().filter_map(|f| {
                 if let Frame::BulkString(s) = f {
-                    Some(s)
+                    Some(String::from_utf8_lossy(&s).into_owned())
                 } else {
                     None
                 }
@@ -785,10 +853,10 @@ async fn get_zset_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Optio
     let sender = state.db_manager.get_sender(db_id);
     // 使用ZRANGE获取所有成员和分数
     let frame = Frame::Array(vec![
-        Frame::BulkString("ZRANGE".to_string()),
-        Frame::BulkString(key.to_string()),
-        Frame::BulkString("0".to_string()),
-        Frame::BulkString("-1".to_string()),
+        Frame::BulkString("ZRANGE".to_string().into()),
+        Frame::BulkString(key.to_string().into()),
+        Frame::BulkString("0".to_string().into()),
+        Frame::BulkString("-1".to_string().into()),
     ]);
     
     let command = Command::parse_from_frame(frame).ok()?;
@@ -799,7 +867,7 @@ async fn get_zset_value(state: &Arc<WebState>, db_id: usize, key: &str) -> Optio
         Frame::Array(frames) => {
             let values: Vec<String> = frames.into_iter().filter_map(|f| {
                 if let Frame::BulkString(s) = f {
-                    Some(s)
+                    Some(String::from_utf8_lossy(&s).into_owned())
                 } else {
                     None
                 }
@@ -834,19 +902,19 @@ async fn set_key_value(
     // 根据payload内容决定使用哪种命令
     let command_result = if !payload.fields.is_empty() {
         // 创建hash类型
-        let mut args = vec![Frame::BulkString("HMSET".to_string()), Frame::BulkString(key.clone())];
+        let mut args = vec![Frame::BulkString("HMSET".to_string().into()), Frame::BulkString(key.clone().into())];
         for (field, value) in payload.fields {
-            args.push(Frame::BulkString(field));
-            args.push(Frame::BulkString(value));
+            args.push(Frame::BulkString(field.into()));
+            args.push(Frame::BulkString(value.into()));
         }
         let frame = Frame::Array(args);
         Command::parse_from_frame(frame)
     } else if !payload.members_with_scores.is_empty() {
         // 创建zset类型
-        let mut args = vec![Frame::BulkString("ZADD".to_string()), Frame::BulkString(key.clone())];
+        let mut args = vec![Frame::BulkString("ZADD".to_string().into()), Frame::BulkString(key.clone().into())];
         for (score, member) in payload.members_with_scores {
-            args.push(Frame::BulkString(score.to_string()));
-            args.push(Frame::BulkString(member));
+            args.push(Frame::BulkString(score.to_string().into()));
+            args.push(Frame::BulkString(member.into()));
         }
         let frame = Frame::Array(args);
         Command::parse_from_frame(frame)
@@ -858,18 +926,18 @@ async fn set_key_value(
             "list" | _ => ("LPUSH", "列表"),
         };
         
-        let mut args = vec![Frame::BulkString(cmd_name.to_string()), Frame::BulkString(key.clone())];
+        let mut args = vec![Frame::BulkString(cmd_name.to_string().into()), Frame::BulkString(key.clone().into())];
         for value in payload.values {
-            args.push(Frame::BulkString(value));
+            args.push(Frame::BulkString(value.into()));
         }
         let frame = Frame::Array(args);
         Command::parse_from_frame(frame)
     } else {
         // 创建string类型
         let frame = Frame::Array(vec![
-            Frame::BulkString("SET".to_string()),
-            Frame::BulkString(key.clone()),
-            Frame::BulkString(payload.value),
+            Frame::BulkString("SET".to_string().into()),
+            Frame::BulkString(key.clone().into()),
+            Frame::BulkString(payload.value.into()),
         ]);
         Command::parse_from_frame(frame)
     };
@@ -911,9 +979,9 @@ async fn set_key_value(
     // 如果指定了TTL，设置过期时间
     if let Some(ttl) = payload.ttl {
         let expire_frame = Frame::Array(vec![
-            Frame::BulkString("EXPIRE".to_string()),
-            Frame::BulkString(key.clone()),
-            Frame::BulkString(ttl.to_string()),
+            Frame::BulkString("EXPIRE".to_string().into()),
+            Frame::BulkString(key.clone().into()),
+            Frame::BulkString(ttl.to_string().into()),
         ]);
         
         if let Ok(expire_command) = Command::parse_from_frame(expire_frame) {
@@ -953,8 +1021,8 @@ async fn delete_key(
     
     // 执行DEL命令
     let frame = Frame::Array(vec![
-        Frame::BulkString("DEL".to_string()),
-        Frame::BulkString(key),
+        Frame::BulkString("DEL".to_string().into()),
+        Frame::BulkString(key.into()),
     ]);
     
     let command = match Command::parse_from_frame(frame) {
@@ -1022,9 +1090,9 @@ async fn update_key_ttl(
     
     // 执行EXPIRE命令
     let frame = Frame::Array(vec![
-        Frame::BulkString("EXPIRE".to_string()),
-        Frame::BulkString(key),
-        Frame::BulkString(payload.ttl.to_string()),
+        Frame::BulkString("EXPIRE".to_string().into()),
+        Frame::BulkString(key.into()),
+        Frame::BulkString(payload.ttl.to_string().into()),
     ]);
     
     let command = match Command::parse_from_frame(frame) {
@@ -0,0 +1,364 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use redis::{cmd, Client, Connection, RedisResult, Value};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// 创建用户并返回以该用户登录的连接
+    fn login_as(username: &str, rules: &[&str]) -> Connection {
+        let mut admin = setup();
+        let _: () = cmd("ACL").arg("DELUSER").arg(username).query(&mut admin).unwrap();
+        let _: () = cmd("ACL").arg("SETUSER").arg(username).arg(rules).query(&mut admin).unwrap();
+        let mut con = setup();
+        let _: () = cmd("AUTH").arg(username).arg("secret").query(&mut con).unwrap();
+        con
+    }
+
+    fn error_of(result: RedisResult<Value>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    /// NOPERM 错误（redis 客户端将错误码显示为 NoPerm）
+    fn is_noperm(error: &str) -> bool {
+        error.starts_with("NoPerm")
+    }
+
+    #[test]
+    fn test_acl_setuser_getuser_deluser() {
+        let mut con = setup();
+        let _: () = cmd("ACL").arg("DELUSER").arg("acl_basic").query(&mut con).unwrap();
+        let _: () = cmd("ACL").arg("SETUSER").arg("acl_basic")
+            .arg("on").arg(">secret").arg("~cache:*").arg("&news").arg("+@read").arg("-keys")
+            .query(&mut con).unwrap();
+
+        let user: Vec<Value> = cmd("ACL").arg("GETUSER").arg("acl_basic").query(&mut con).unwrap();
+        let fields: Vec<String> = user.iter().step_by(2).map(|field| redis::from_redis_value(field.clone()).unwrap()).collect();
+        assert_eq!(fields, vec!["flags", "passwords", "commands", "keys", "channels", "selectors"]);
+        let flags: Vec<String> = redis::from_redis_value(user[1].clone()).unwrap();
+        assert_eq!(flags, vec!["on"]);
+        let passwords: Vec<String> = redis::from_redis_value(user[3].clone()).unwrap();
+        assert_eq!(passwords.len(), 1);
+        assert_eq!(passwords[0].len(), 64);
+        let commands: String = redis::from_redis_value(user[5].clone()).unwrap();
+        assert_eq!(commands, "+@read -keys");
+        let keys: String = redis::from_redis_value(user[7].clone()).unwrap();
+        assert_eq!(keys, "~cache:*");
+        let channels: String = redis::from_redis_value(user[9].clone()).unwrap();
+        assert_eq!(channels, "&news");
+
+        let users: Vec<String> = cmd("ACL").arg("USERS").query(&mut con).unwrap();
+        assert!(users.contains(&"default".to_string()));
+        assert!(users.contains(&"acl_basic".to_string()));
+        let list: Vec<String> = cmd("ACL").arg("LIST").query(&mut con).unwrap();
+        assert!(list.iter().any(|line| line.starts_with("user acl_basic on #") && line.ends_with("~cache:* &news +@read -keys")));
+
+        let deleted: i64 = cmd("ACL").arg("DELUSER").arg("acl_basic").arg("acl_missing").query(&mut con).unwrap();
+        assert_eq!(deleted, 1);
+        let user: Value = cmd("ACL").arg("GETUSER").arg("acl_basic").query(&mut con).unwrap();
+        assert_eq!(user, Value::Nil);
+        assert!(error_of(cmd("ACL").arg("DELUSER").arg("default").query(&mut con)).contains("cannot be removed"));
+    }
+
+    #[test]
+    fn test_acl_setuser_invalid_rule_is_atomic() {
+        let mut con = setup();
+        let _: () = cmd("ACL").arg("DELUSER").arg("acl_atomic").query(&mut con).unwrap();
+        let _: () = cmd("ACL").arg("SETUSER").arg("acl_atomic").arg("on").arg("+get").query(&mut con).unwrap();
+
+        let error = error_of(cmd("ACL").arg("SETUSER").arg("acl_atomic").arg("+set").arg("+notacommand").query(&mut con));
+        assert!(error.contains("Error in ACL SETUSER modifier '+notacommand'"));
+        let error = error_of(cmd("ACL").arg("SETUSER").arg("acl_atomic").arg("+@notacategory").query(&mut con));
+        assert!(error.contains("Unknown command or category name in ACL"));
+        let error = error_of(cmd("ACL").arg("SETUSER").arg("acl_atomic").arg("<missing").query(&mut con));
+        assert!(error.contains("no such password"));
+
+        // 出错的 SETUSER 不会应用其中任何一条规则
+        let list: Vec<String> = cmd("ACL").arg("LIST").query(&mut con).unwrap();
+        assert!(list.iter().any(|line| line == "user acl_atomic on resetchannels +get"));
+    }
+
+    #[test]
+    fn test_auth_with_username() {
+        let mut admin = setup();
+        let _: () = cmd("ACL").arg("DELUSER").arg("acl_auth").query(&mut admin).unwrap();
+        let _: () = cmd("ACL").arg("SETUSER").arg("acl_auth").arg("off").arg(">secret").arg("+@all").arg("~*").query(&mut admin).unwrap();
+
+        let mut con = setup();
+        // 禁用的用户无法登录
+        let error = error_of(cmd("AUTH").arg("acl_auth").arg("secret").query(&mut con));
+        assert!(error.contains("WRONGPASS"));
+
+        let _: () = cmd("ACL").arg("SETUSER").arg("acl_auth").arg("on").query(&mut admin).unwrap();
+        let error = error_of(cmd("AUTH").arg("acl_auth").arg("wrong").query(&mut con));
+        assert!(error.contains("WRONGPASS"));
+        let error = error_of(cmd("AUTH").arg("acl_missing").arg("secret").query(&mut con));
+        assert!(error.contains("WRONGPASS"));
+
+        let whoami: String = cmd("ACL").arg("WHOAMI").query(&mut con).unwrap();
+        assert_eq!(whoami, "default");
+        let _: () = cmd("AUTH").arg("acl_auth").arg("secret").query(&mut con).unwrap();
+        let whoami: String = cmd("ACL").arg("WHOAMI").query(&mut con).unwrap();
+        assert_eq!(whoami, "acl_auth");
+
+        // 默认用户没有密码时，只提供密码的 AUTH 返回错误
+        let error = error_of(cmd("AUTH").arg("anything").query(&mut con));
+        assert!(error.contains("without any password configured"));
+    }
+
+    #[test]
+    fn test_command_categories() {
+        let mut con = login_as("acl_reader", &["on", ">secret", "~*", "+@read"]);
+        let _: Option<String> = cmd("GET").arg("acl_reader_key").query(&mut con).unwrap();
+        let error = error_of(cmd("SET").arg("acl_reader_key").arg("value").query(&mut con));
+        assert!(is_noperm(&error));
+        assert!(error.contains("'set'"));
+
+        // 服务之间不能互相清空数据
+        let mut con = login_as("acl_service", &["on", ">secret", "~*", "+@all", "-@dangerous"]);
+        let _: () = cmd("SET").arg("acl_service_key").arg("value").query(&mut con).unwrap();
+        let error = error_of(cmd("FLUSHALL").query(&mut con));
+        assert!(is_noperm(&error));
+        let error = error_of(cmd("FLUSHDB").query(&mut con));
+        assert!(is_noperm(&error));
+
+        // 最后一条匹配的规则生效
+        let mut con = login_as("acl_order", &["on", ">secret", "~*", "-@all", "+@string", "-set"]);
+        let _: Option<String> = cmd("GET").arg("acl_order_key").query(&mut con).unwrap();
+        assert!(is_noperm(&error_of(cmd("SET").arg("acl_order_key").arg("value").query(&mut con))));
+        assert!(is_noperm(&error_of(cmd("HGET").arg("acl_order_key").arg("field").query(&mut con))));
+    }
+
+    #[test]
+    fn test_subcommand_rules() {
+        let mut con = login_as("acl_subcommand", &["on", ">secret", "-@all", "+acl|whoami"]);
+        let whoami: String = cmd("ACL").arg("WHOAMI").query(&mut con).unwrap();
+        assert_eq!(whoami, "acl_subcommand");
+        let error = error_of(cmd("ACL").arg("LIST").query(&mut con));
+        assert!(is_noperm(&error));
+    }
+
+    #[test]
+    fn test_key_patterns() {
+        let mut con = login_as("acl_keys", &["on", ">secret", "+@all", "~app1:*"]);
+        let _: () = cmd("SET").arg("app1:name").arg("value").query(&mut con).unwrap();
+        let error = error_of(cmd("SET").arg("app2:name").arg("value").query(&mut con));
+        assert!(is_noperm(&error) && error.contains("No permissions to access a key"));
+
+        // 多个键中任意一个不匹配即拒绝
+        let error = error_of(cmd("MGET").arg("app1:name").arg("app2:name").query(&mut con));
+        assert!(is_noperm(&error));
+        let error = error_of(cmd("MSET").arg("app1:a").arg("1").arg("app2:b").arg("2").query(&mut con));
+        assert!(is_noperm(&error));
+        let values: Vec<Option<String>> = cmd("MGET").arg("app1:name").arg("app1:missing").query(&mut con).unwrap();
+        assert_eq!(values, vec![Some("value".to_string()), None]);
+    }
+
+    #[test]
+    fn test_channel_patterns() {
+        let mut con = login_as("acl_channels", &["on", ">secret", "+@all", "resetchannels", "&news.*"]);
+        let _: i64 = cmd("PUBLISH").arg("news.tech").arg("hello").query(&mut con).unwrap();
+        let error = error_of(cmd("PUBLISH").arg("sports").arg("hello").query(&mut con));
+        assert!(is_noperm(&error) && error.contains("No permissions to access a channel"));
+        let error = error_of(cmd("SUBSCRIBE").arg("news.tech").arg("sports").query(&mut con));
+        assert!(is_noperm(&error));
+        // 模式订阅必须与授权的模式完全相同
+        let error = error_of(cmd("PSUBSCRIBE").arg("*").query(&mut con));
+        assert!(is_noperm(&error));
+    }
+
+    #[test]
+    fn test_permissions_in_transaction_and_script() {
+        let mut con = login_as("acl_tx", &["on", ">secret", "+@all", "~tx:*"]);
+        let _: () = cmd("MULTI").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("tx:a").arg("1").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("other:a").arg("1").query(&mut con).unwrap();
+        // redis 客户端会将 EXEC 结果中的错误作为整体的错误返回，其余命令仍然执行
+        assert!(is_noperm(&error_of(cmd("EXEC").query(&mut con))));
+        let value: String = cmd("GET").arg("tx:a").query(&mut con).unwrap();
+        assert_eq!(value, "1");
+
+        // EVAL 的键与脚本中访问的键都需要检查权限
+        let error = error_of(cmd("EVAL").arg("return 1").arg(1).arg("other:a").query(&mut con));
+        assert!(is_noperm(&error));
+        let error = error_of(cmd("EVAL").arg("return redis.call('GET', 'other:a')").arg(0).query(&mut con));
+        assert!(is_noperm(&error));
+        let value: String = cmd("EVAL").arg("return redis.call('GET', KEYS[1])").arg(1).arg("tx:a").query(&mut con).unwrap();
+        assert_eq!(value, "1");
+    }
+
+    #[test]
+    fn test_acl_cat_and_dryrun() {
+        let mut con = setup();
+        let categories: Vec<String> = cmd("ACL").arg("CAT").query(&mut con).unwrap();
+        assert!(categories.contains(&"dangerous".to_string()));
+        let commands: Vec<String> = cmd("ACL").arg("CAT").arg("dangerous").query(&mut con).unwrap();
+        assert!(commands.contains(&"flushall".to_string()));
+        assert!(!commands.contains(&"get".to_string()));
+        assert!(error_of(cmd("ACL").arg("CAT").arg("nope").query(&mut con)).contains("Unknown category"));
+
+        let _: () = cmd("ACL").arg("DELUSER").arg("acl_dryrun").query(&mut con).unwrap();
+        let _: () = cmd("ACL").arg("SETUSER").arg("acl_dryrun").arg("on").arg("+get").arg("~dry:*").query(&mut con).unwrap();
+        let _: () = cmd("ACL").arg("DRYRUN").arg("acl_dryrun").arg("GET").arg("dry:key").query(&mut con).unwrap();
+        let result: String = cmd("ACL").arg("DRYRUN").arg("acl_dryrun").arg("SET").arg("dry:key").arg("1").query(&mut con).unwrap();
+        assert!(result.contains("no permissions to run the 'set' command"));
+        let result: String = cmd("ACL").arg("DRYRUN").arg("acl_dryrun").arg("GET").arg("wet:key").query(&mut con).unwrap();
+        assert!(result.contains("No permissions to access a key"));
+        assert!(error_of(cmd("ACL").arg("DRYRUN").arg("acl_missing").arg("GET").arg("k").query(&mut con)).contains("not found"));
+
+        // 没有配置 ACL 文件时无法保存
+        assert!(error_of(cmd("ACL").arg("SAVE").query(&mut con)).contains("not configured to use an ACL file"));
+    }
+
+    /// 使用独立数据目录启动的服务器，用于验证认证与 ACL 文件
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16, args: &[&str]) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    #[test]
+    fn test_requirepass_and_aclfile_persistence() {
+        let dir = std::env::temp_dir().join("rudis-acl-file");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let args = ["--requirepass", "rootpass", "--aclfile", "users.acl"];
+
+        {
+            let server = TestServer::start(&dir, 16400, &args);
+            let mut con = server.connect();
+            let error = error_of(cmd("GET").arg("key").query(&mut con));
+            assert!(error.contains("NOAUTH"));
+            assert!(error_of(cmd("AUTH").arg("wrong").query(&mut con)).contains("WRONGPASS"));
+            let _: () = cmd("AUTH").arg("rootpass").query(&mut con).unwrap();
+            let _: () = cmd("ACL").arg("SETUSER").arg("service").arg("on").arg(">secret").arg("~svc:*").arg("+@all").arg("-@dangerous").query(&mut con).unwrap();
+            let _: () = cmd("ACL").arg("SAVE").query(&mut con).unwrap();
+        }
+
+        let content = std::fs::read_to_string(dir.join("users.acl")).unwrap();
+        assert!(content.contains("user service on #"));
+        assert!(!content.contains("secret"));
+
+        let server = TestServer::start(&dir, 16400, &args);
+        let mut con = server.connect();
+        let _: () = cmd("AUTH").arg("service").arg("secret").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("svc:key").arg("value").query(&mut con).unwrap();
+        assert!(is_noperm(&error_of(cmd("FLUSHALL").query(&mut con))));
+
+        // 修改文件后通过 ACL LOAD 重新加载
+        let mut admin = server.connect();
+        let _: () = cmd("AUTH").arg("default").arg("rootpass").query(&mut admin).unwrap();
+        std::fs::write(dir.join("users.acl"), format!("{}user other on nopass ~* +@all\n", content)).unwrap();
+        let _: () = cmd("ACL").arg("LOAD").query(&mut admin).unwrap();
+        let users: Vec<String> = cmd("ACL").arg("USERS").query(&mut admin).unwrap();
+        assert_eq!(users, vec!["default", "other", "service"]);
+
+        // 文件内容有误时 ACL LOAD 失败且保持原有用户
+        std::fs::write(dir.join("users.acl"), "user broken on +notacommand\n").unwrap();
+        assert!(error_of(cmd("ACL").arg("LOAD").query(&mut admin)).contains("Unknown command or category name in ACL"));
+        let users: Vec<String> = cmd("ACL").arg("USERS").query(&mut admin).unwrap();
+        assert_eq!(users, vec!["default", "other", "service"]);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// 向 Web 管理界面发送 POST 请求，返回响应正文
+    fn web_post(port: u16, path: &str, body: &str) -> String {
+        let mut stream = None;
+        for _ in 0..100 {
+            if let Ok(connected) = std::net::TcpStream::connect(("127.0.0.1", port)) {
+                stream = Some(connected);
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        let mut stream = stream.expect("web server is not listening");
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            path, body.len(), body
+        );
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response.split_once("\r\n\r\n").map(|(_, body)| body.to_string()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_web_cli_follows_acl_and_propagates_writes() {
+        let dir = std::env::temp_dir().join("rudis-acl-web-cli");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let args = ["--appendonly", "yes"];
+
+        {
+            let _server = TestServer::start(&dir, 16444, &args);
+            let reply = web_post(17444, "/api/cli", r#"{"command": "SET web value"}"#);
+            assert!(reply.contains(r#""success":true"#), "{}", reply);
+            assert!(web_post(17444, "/api/login", r#"{"username": "admin", "password": "admin"}"#).contains(r#""success":true"#));
+            thread::sleep(Duration::from_millis(1500));
+        }
+
+        // 控制台写入的数据追加到了 AOF
+        let server = TestServer::start(&dir, 16444, &args);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("web").query(&mut con).unwrap();
+        assert_eq!(value, "value");
+
+        // 配置 ACL 用户后控制台不可用，登录改用 ACL 用户
+        let _: () = cmd("ACL").arg("SETUSER").arg("service").arg("on").arg(">secret").arg("~svc:*").arg("+@all").query(&mut con).unwrap();
+        let reply = web_post(17444, "/api/cli", r#"{"command": "FLUSHALL"}"#);
+        assert!(reply.contains(r#""success":false"#), "{}", reply);
+        let dbsize: i64 = cmd("DBSIZE").query(&mut con).unwrap();
+        assert_eq!(dbsize, 1);
+        assert!(web_post(17444, "/api/login", r#"{"username": "admin", "password": "admin"}"#).contains(r#""success":false"#));
+        assert!(web_post(17444, "/api/login", r#"{"username": "service", "password": "secret"}"#).contains(r#""success":true"#));
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,447 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use redis::{cmd, Client, Connection, RedisResult, Value};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// 使用独立数据目录启动的服务器
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        /// 开启 AOF 启动
+        fn start(dir: &PathBuf, port: u16) -> Self {
+            Self::start_with(dir, port, &["--appendonly", "yes"])
+        }
+
+        fn start_with(dir: &PathBuf, port: u16, args: &[&str]) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn persistence_info(con: &mut Connection, field: &str) -> String {
+        let info: String = cmd("INFO").arg("persistence").query(con).unwrap();
+        info.lines()
+            .find_map(|line| line.strip_prefix(&format!("{}:", field)))
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// 等待完成指定次数的重写
+    fn wait_for_rewrites(con: &mut Connection, rewrites: u64) {
+        for _ in 0..100 {
+            let done: u64 = persistence_info(con, "aof_rewrites").parse().unwrap();
+            if done >= rewrites && persistence_info(con, "aof_rewrite_in_progress") == "0" {
+                return;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        panic!("AOF rewrite did not finish");
+    }
+
+    #[test]
+    fn test_bgrewriteaof_compacts_and_reloads() {
+        let dir = temp_dir("rudis-aof-rewrite");
+        let aof = dir.join("data/dump.aof");
+        {
+            let server = TestServer::start_with(&dir, 16403, &["--appendonly", "yes", "--aof-use-rdb-preamble", "no"]);
+            let mut con = server.connect();
+            for _ in 0..1000 {
+                let _: i64 = cmd("INCR").arg("counter").query(&mut con).unwrap();
+            }
+            let _: () = cmd("SET").arg("binary").arg(b"\x00\r\n\xff".as_slice()).query(&mut con).unwrap();
+            let _: () = cmd("SET").arg("temporary").arg("value").arg("EX").arg(1000).query(&mut con).unwrap();
+            let _: i64 = cmd("RPUSH").arg("list").arg(&["a", "b", "c"]).query(&mut con).unwrap();
+            let _: String = cmd("LPOP").arg("list").query(&mut con).unwrap();
+            let _: i64 = cmd("HSET").arg("hash").arg("field").arg("value").query(&mut con).unwrap();
+            let _: i64 = cmd("SADD").arg("set").arg(&["x", "y"]).query(&mut con).unwrap();
+            let _: i64 = cmd("ZADD").arg("zset").arg(1.5).arg("one").arg(-2).arg("two").query(&mut con).unwrap();
+            let _: i64 = cmd("PFADD").arg("hll").arg(&["a", "b", "c", "d"]).query(&mut con).unwrap();
+            let _: String = cmd("XADD").arg("stream").arg("1-1").arg("field").arg("value").query(&mut con).unwrap();
+            let _: () = cmd("XGROUP").arg("CREATE").arg("stream").arg("group").arg("0").query(&mut con).unwrap();
+            let _: Value = cmd("XREADGROUP").arg("GROUP").arg("group").arg("consumer").arg("STREAMS").arg("stream").arg(">").query(&mut con).unwrap();
+            let _: () = cmd("SELECT").arg(2).query(&mut con).unwrap();
+            let _: () = cmd("SET").arg("other").arg("db2").query(&mut con).unwrap();
+            let _: () = cmd("SELECT").arg(0).query(&mut con).unwrap();
+
+            thread::sleep(Duration::from_millis(200));
+            let size_before = std::fs::metadata(&aof).unwrap().len();
+            let reply: String = cmd("BGREWRITEAOF").query(&mut con).unwrap();
+            assert_eq!(reply, "Background append only file rewriting started");
+            wait_for_rewrites(&mut con, 1);
+            assert_eq!(persistence_info(&mut con, "aof_last_bgrewrite_status"), "ok");
+
+            // 重写后的文件不再包含 1000 条 INCR
+            let content = std::fs::read(&aof).unwrap();
+            assert!((content.len() as u64) < size_before);
+            assert!(!String::from_utf8_lossy(&content).contains("INCR"));
+            assert_eq!(persistence_info(&mut con, "aof_base_size"), content.len().to_string());
+
+            // 重写后的写命令继续追加到新文件
+            let _: () = cmd("SET").arg("after").arg("rewrite").query(&mut con).unwrap();
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        let server = TestServer::start(&dir, 16403);
+        let mut con = server.connect();
+        let counter: i64 = cmd("GET").arg("counter").query(&mut con).unwrap();
+        assert_eq!(counter, 1000);
+        let binary: Vec<u8> = cmd("GET").arg("binary").query(&mut con).unwrap();
+        assert_eq!(binary, b"\x00\r\n\xff");
+        let ttl: i64 = cmd("TTL").arg("temporary").query(&mut con).unwrap();
+        assert!(ttl > 900 && ttl <= 1000);
+        let list: Vec<String> = cmd("LRANGE").arg("list").arg(0).arg(-1).query(&mut con).unwrap();
+        assert_eq!(list, vec!["b", "c"]);
+        let value: String = cmd("HGET").arg("hash").arg("field").query(&mut con).unwrap();
+        assert_eq!(value, "value");
+        let count: i64 = cmd("SCARD").arg("set").query(&mut con).unwrap();
+        assert_eq!(count, 2);
+        let score: f64 = cmd("ZSCORE").arg("zset").arg("one").query(&mut con).unwrap();
+        assert_eq!(score, 1.5);
+        let count: i64 = cmd("PFCOUNT").arg("hll").query(&mut con).unwrap();
+        assert_eq!(count, 4);
+        let pending: Vec<Value> = cmd("XPENDING").arg("stream").arg("group").query(&mut con).unwrap();
+        assert_eq!(pending[0], Value::Int(1));
+        let after: String = cmd("GET").arg("after").query(&mut con).unwrap();
+        assert_eq!(after, "rewrite");
+        let _: () = cmd("SELECT").arg(2).query(&mut con).unwrap();
+        let other: String = cmd("GET").arg("other").query(&mut con).unwrap();
+        assert_eq!(other, "db2");
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rdb_preamble() {
+        let dir = temp_dir("rudis-aof-preamble");
+        let aof = dir.join("data/dump.aof");
+        {
+            let server = TestServer::start(&dir, 16406);
+            let mut con = server.connect();
+            for i in 0..100 {
+                let _: () = cmd("SET").arg(format!("key:{}", i)).arg(i).query(&mut con).unwrap();
+            }
+            let _: i64 = cmd("ZADD").arg("zset").arg(1).arg("one").query(&mut con).unwrap();
+            let _: () = cmd("SELECT").arg(3).query(&mut con).unwrap();
+            let _: () = cmd("SET").arg("other").arg("db3").query(&mut con).unwrap();
+
+            let _: String = cmd("BGREWRITEAOF").query(&mut con).unwrap();
+            wait_for_rewrites(&mut con, 1);
+            // 重写之后的增量命令追加在快照之后
+            let _: () = cmd("SET").arg("after").arg("rewrite").query(&mut con).unwrap();
+            thread::sleep(Duration::from_millis(200));
+
+            let content = std::fs::read(&aof).unwrap();
+            assert_eq!(content[0], b'@');
+            assert!(String::from_utf8_lossy(&content).ends_with("*2\r\n$6\r\nSELECT\r\n$1\r\n3\r\n*3\r\n$3\r\nSET\r\n$5\r\nafter\r\n$7\r\nrewrite\r\n"));
+        }
+
+        let server = TestServer::start(&dir, 16406);
+        let mut con = server.connect();
+        let size: i64 = cmd("DBSIZE").query(&mut con).unwrap();
+        assert_eq!(size, 101);
+        let score: f64 = cmd("ZSCORE").arg("zset").arg("one").query(&mut con).unwrap();
+        assert_eq!(score, 1.0);
+        let _: () = cmd("SELECT").arg(3).query(&mut con).unwrap();
+        let values: Vec<String> = cmd("MGET").arg(&["other", "after"]).query(&mut con).unwrap();
+        assert_eq!(values, vec!["db3", "rewrite"]);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_plain_aof_is_still_loaded() {
+        let dir = temp_dir("rudis-aof-plain");
+        std::fs::create_dir_all(dir.join("data")).unwrap();
+        std::fs::write(
+            dir.join("data/dump.aof"),
+            "*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n*2\r\n$4\r\nINCR\r\n$7\r\ncounter\r\n*2\r\n$4\r\nINCR\r\n$7\r\ncounter\r\n",
+        ).unwrap();
+
+        let server = TestServer::start(&dir, 16407);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "value");
+        let counter: i64 = cmd("GET").arg("counter").query(&mut con).unwrap();
+        assert_eq!(counter, 2);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_transaction_is_persisted() {
+        let dir = temp_dir("rudis-aof-transaction");
+        {
+            let server = TestServer::start(&dir, 16440);
+            let mut con = server.connect();
+            let _: () = cmd("MULTI").query(&mut con).unwrap();
+            let _: () = cmd("SET").arg("key").arg("value").query(&mut con).unwrap();
+            let _: () = cmd("SELECT").arg(1).query(&mut con).unwrap();
+            let _: () = cmd("INCR").arg("counter").query(&mut con).unwrap();
+            let _: () = cmd("EXEC").query(&mut con).unwrap();
+            thread::sleep(Duration::from_millis(1500));
+        }
+
+        // 事务中的写命令同样写入 AOF，重启后按各自的数据库恢复
+        let server = TestServer::start(&dir, 16440);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "value");
+        let exists: i64 = cmd("EXISTS").arg("counter").query(&mut con).unwrap();
+        assert_eq!(exists, 0);
+        let _: () = cmd("SELECT").arg(1).query(&mut con).unwrap();
+        let counter: i64 = cmd("GET").arg("counter").query(&mut con).unwrap();
+        assert_eq!(counter, 1);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_aof_is_seeded_from_rdb() {
+        let dir = temp_dir("rudis-aof-seed");
+        {
+            // 未开启 AOF 时数据保存在 RDB 中
+            let server = TestServer::start_with(&dir, 16408, &["--save", "1,1"]);
+            let mut con = server.connect();
+            let _: () = cmd("SET").arg("key").arg("value").query(&mut con).unwrap();
+            thread::sleep(Duration::from_millis(2500));
+            assert!(dir.join("data/dump.rdb").exists());
+        }
+        {
+            // 首次开启 AOF：从 RDB 加载数据后立即重写 AOF
+            let server = TestServer::start(&dir, 16408);
+            let mut con = server.connect();
+            wait_for_rewrites(&mut con, 1);
+            let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+            assert_eq!(value, "value");
+        }
+
+        // AOF 存在时只从 AOF 加载
+        std::fs::remove_file(dir.join("data/dump.rdb")).unwrap();
+        let server = TestServer::start(&dir, 16408);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "value");
+        assert_eq!(persistence_info(&mut con, "aof_rewrites"), "0");
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bgrewriteaof_keeps_concurrent_writes() {
+        let dir = temp_dir("rudis-aof-rewrite-concurrent");
+        {
+            let server = TestServer::start(&dir, 16404);
+            let mut con = server.connect();
+            for i in 0..2000 {
+                let _: () = cmd("SET").arg(format!("key:{}", i)).arg(i).query(&mut con).unwrap();
+            }
+
+            // 重写期间持续写入，重写前后的写入都不能丢失或重复
+            let mut writer = server.connect();
+            let handle = thread::spawn(move || {
+                for _ in 0..500 {
+                    let _: i64 = cmd("INCR").arg("counter").query(&mut writer).unwrap();
+                }
+            });
+            for rewrites in 1..=3 {
+                let _: String = cmd("BGREWRITEAOF").query(&mut con).unwrap();
+                wait_for_rewrites(&mut con, rewrites);
+            }
+            handle.join().unwrap();
+
+            let result: RedisResult<Value> = cmd("BGREWRITEAOF").arg("extra").query(&mut con);
+            assert!(result.is_err());
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        let server = TestServer::start(&dir, 16404);
+        let mut con = server.connect();
+        let counter: i64 = cmd("GET").arg("counter").query(&mut con).unwrap();
+        assert_eq!(counter, 500);
+        let size: i64 = cmd("DBSIZE").query(&mut con).unwrap();
+        assert_eq!(size, 2001);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bgrewriteaof_with_blocking_pops() {
+        let dir = temp_dir("rudis-aof-rewrite-blocking");
+        {
+            let server = TestServer::start(&dir, 16441);
+            let mut con = server.connect();
+
+            // 等待中的 BLPOP 不持有写屏障，不影响重写
+            let mut idle = server.connect();
+            let idle_waiter = thread::spawn(move || {
+                let reply: Option<(String, String)> = cmd("BLPOP").arg("idle").arg(3).query(&mut idle).unwrap();
+                reply
+            });
+            thread::sleep(Duration::from_millis(100));
+            let _: String = cmd("BGREWRITEAOF").query(&mut con).unwrap();
+            wait_for_rewrites(&mut con, 1);
+
+            // 重写期间弹出的元素只被删除一次
+            let values: Vec<i64> = (0..3000).collect();
+            let _: i64 = cmd("RPUSH").arg("queue").arg(&values).query(&mut con).unwrap();
+            let consumers: Vec<_> = (0..4)
+                .map(|_| {
+                    let mut popper = server.connect();
+                    thread::spawn(move || {
+                        for _ in 0..500 {
+                            let reply: Option<(String, String)> = cmd("BLPOP").arg("queue").arg(5).query(&mut popper).unwrap();
+                            assert!(reply.is_some());
+                        }
+                    })
+                })
+                .collect();
+            let mut rewrites = 1;
+            while consumers.iter().any(|consumer| !consumer.is_finished()) {
+                rewrites += 1;
+                let _: String = cmd("BGREWRITEAOF").query(&mut con).unwrap();
+                wait_for_rewrites(&mut con, rewrites);
+            }
+            for consumer in consumers {
+                consumer.join().unwrap();
+            }
+            assert_eq!(idle_waiter.join().unwrap(), None);
+            thread::sleep(Duration::from_millis(1500));
+        }
+
+        let server = TestServer::start(&dir, 16441);
+        let mut con = server.connect();
+        let items: Vec<i64> = cmd("LRANGE").arg("queue").arg(0).arg(-1).query(&mut con).unwrap();
+        assert_eq!(items, (2000..3000).collect::<Vec<i64>>());
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_auto_aof_rewrite() {
+        let dir = temp_dir("rudis-aof-rewrite-auto");
+        let server = TestServer::start(&dir, 16405);
+        let mut con = server.connect();
+        let _: () = cmd("CONFIG").arg("SET").arg("auto-aof-rewrite-percentage").arg("0").query(&mut con).unwrap();
+        let _: () = cmd("CONFIG").arg("SET").arg("auto-aof-rewrite-min-size").arg("4kb").query(&mut con).unwrap();
+        let config: std::collections::HashMap<String, String> = cmd("CONFIG").arg("GET").arg("auto-aof-*").query(&mut con).unwrap();
+        assert_eq!(config["auto-aof-rewrite-min-size"], "4096");
+
+        for _ in 0..500 {
+            let _: i64 = cmd("INCR").arg("counter").query(&mut con).unwrap();
+        }
+        thread::sleep(Duration::from_millis(1500));
+        assert_eq!(persistence_info(&mut con, "aof_rewrites"), "0");
+
+        // 文件超过最小大小且增长超过比例后自动重写
+        let _: () = cmd("CONFIG").arg("SET").arg("auto-aof-rewrite-percentage").arg("100").query(&mut con).unwrap();
+        wait_for_rewrites(&mut con, 1);
+        let size: u64 = persistence_info(&mut con, "aof_current_size").parse().unwrap();
+        assert!(size < 4096);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bgrewriteaof_without_aof() {
+        let mut con = setup();
+        let result: RedisResult<Value> = cmd("BGREWRITEAOF").query(&mut con);
+        assert!(result.is_err());
+        assert_eq!(persistence_info(&mut con, "aof_enabled"), "0");
+    }
+
+    #[test]
+    fn test_dump_restore() {
+        let mut con = setup();
+        let _: () = cmd("DEL").arg(&["dump:source", "dump:target"]).query(&mut con).unwrap();
+        let _: i64 = cmd("RPUSH").arg("dump:source").arg(&["a", "b"]).query(&mut con).unwrap();
+
+        let payload: Vec<u8> = cmd("DUMP").arg("dump:source").query(&mut con).unwrap();
+        let _: () = cmd("RESTORE").arg("dump:target").arg(0).arg(payload.as_slice()).query(&mut con).unwrap();
+        let list: Vec<String> = cmd("LRANGE").arg("dump:target").arg(0).arg(-1).query(&mut con).unwrap();
+        assert_eq!(list, vec!["a", "b"]);
+
+        // 目标键已存在时需要 REPLACE
+        let result: RedisResult<Value> = cmd("RESTORE").arg("dump:target").arg(0).arg(payload.as_slice()).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Target key name already exists"));
+        let _: () = cmd("RESTORE").arg("dump:target").arg(5000).arg(payload.as_slice()).arg("REPLACE").query(&mut con).unwrap();
+        let ttl: i64 = cmd("PTTL").arg("dump:target").query(&mut con).unwrap();
+        assert!(ttl > 0 && ttl <= 5000);
+
+        // 损坏的数据
+        let mut corrupted = payload.clone();
+        corrupted[0] ^= 0xff;
+        let result: RedisResult<Value> = cmd("RESTORE").arg("dump:other").arg(0).arg(corrupted.as_slice()).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("checksum"));
+
+        let missing: Option<Vec<u8>> = cmd("DUMP").arg("dump:missing").query(&mut con).unwrap();
+        assert!(missing.is_none());
+        let _: () = cmd("DEL").arg(&["dump:source", "dump:target"]).query(&mut con).unwrap();
+    }
+
+    #[test]
+    fn test_restore_rejects_oversized_length() {
+        let mut con = setup();
+        // 字符串值声明 2^62 字节的长度，校验和由客户端自行计算
+        let mut payload = vec![0x00, 0xfd, 0, 0, 0, 0, 0, 0, 0, 0x40, 0x01, 0x00];
+        let digest = sha1_smol::Sha1::from(&payload).digest().bytes();
+        payload.extend_from_slice(&digest[..8]);
+        let result: RedisResult<Value> = cmd("RESTORE").arg("dump:oversized").arg(0).arg(payload.as_slice()).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Bad data format"));
+
+        let pong: String = cmd("PING").query(&mut con).unwrap();
+        assert_eq!(pong, "PONG");
+        let exists: i64 = cmd("EXISTS").arg("dump:oversized").query(&mut con).unwrap();
+        assert_eq!(exists, 0);
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use redis::{cmd, Client, Connection, RedisResult};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// 使用独立数据目录启动的服务器
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn persistence_info(con: &mut Connection, field: &str) -> String {
+        let info: String = cmd("INFO").arg("persistence").query(con).unwrap();
+        info.lines()
+            .find_map(|line| line.strip_prefix(&format!("{}:", field)))
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn wait_for_bgsave(con: &mut Connection) {
+        for _ in 0..200 {
+            if persistence_info(con, "rdb_bgsave_in_progress") == "0" {
+                return;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        panic!("Background save did not finish");
+    }
+
+    fn unix_time() -> i64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64
+    }
+
+    #[test]
+    fn test_save_updates_lastsave() {
+        let mut con = setup();
+        let before = unix_time();
+        let _: () = cmd("SAVE").query(&mut con).unwrap();
+        let lastsave: i64 = cmd("LASTSAVE").query(&mut con).unwrap();
+        assert!(lastsave >= before);
+
+        let result: RedisResult<()> = cmd("LASTSAVE").arg("extra").query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bgsave_runs_in_background() {
+        let dir = temp_dir("rudis-bgsave");
+        let server = TestServer::start(&dir, 16412);
+        let mut con = server.connect();
+        let lastsave: i64 = cmd("LASTSAVE").query(&mut con).unwrap();
+
+        // 足够大的值，让保存持续一段时间
+        let value = vec![b'x'; 64 * 1024 * 1024];
+        let _: () = cmd("SET").arg("big").arg(value).query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("key").arg("value").query(&mut con).unwrap();
+        assert_eq!(persistence_info(&mut con, "rdb_changes_since_last_save"), "2");
+        thread::sleep(Duration::from_millis(1100));
+
+        let reply: String = cmd("BGSAVE").query(&mut con).unwrap();
+        assert_eq!(reply, "Background saving started");
+
+        // 保存期间仍然可以执行命令，并拒绝新的保存
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "value");
+        assert_eq!(persistence_info(&mut con, "rdb_bgsave_in_progress"), "1");
+        let result: RedisResult<String> = cmd("BGSAVE").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Background save already in progress"));
+        let result: RedisResult<()> = cmd("SAVE").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Background save already in progress"));
+
+        wait_for_bgsave(&mut con);
+        assert_eq!(persistence_info(&mut con, "rdb_last_bgsave_status"), "ok");
+        assert_eq!(persistence_info(&mut con, "rdb_changes_since_last_save"), "0");
+        assert_eq!(persistence_info(&mut con, "rdb_current_bgsave_time_sec"), "-1");
+        assert_ne!(persistence_info(&mut con, "rdb_last_bgsave_time_sec"), "-1");
+        let saved: i64 = cmd("LASTSAVE").query(&mut con).unwrap();
+        assert!(saved > lastsave);
+        assert!(dir.join("data/dump.rdb").exists());
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bgsave_failure_is_reported() {
+        let dir = temp_dir("rudis-bgsave-failure");
+        // 数据目录被同名文件占用，保存必然失败
+        std::fs::write(dir.join("data"), b"").unwrap();
+        let server = TestServer::start(&dir, 16413);
+        let mut con = server.connect();
+        let lastsave: i64 = cmd("LASTSAVE").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("key").arg("value").query(&mut con).unwrap();
+
+        let reply: String = cmd("BGSAVE").query(&mut con).unwrap();
+        assert_eq!(reply, "Background saving started");
+        wait_for_bgsave(&mut con);
+        assert_eq!(persistence_info(&mut con, "rdb_last_bgsave_status"), "err");
+        assert_eq!(persistence_info(&mut con, "rdb_changes_since_last_save"), "1");
+
+        let result: RedisResult<()> = cmd("SAVE").query(&mut con);
+        assert!(result.is_err());
+        let saved: i64 = cmd("LASTSAVE").query(&mut con).unwrap();
+        assert_eq!(saved, lastsave);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
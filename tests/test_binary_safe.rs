@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use redis::{Client, Commands, Connection};
+    use rudis_server::frame::Frame;
+    use rudis_server::persistence::redis_rdb::{decode, encode};
+    use rudis_server::store::db::{DatabaseSnapshot, Structure};
+    use rudis_server::store::sorted_set::SortedSet;
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// 包含 \r\n、\0 与非法 UTF-8 的字节序列
+    fn binary_value() -> Vec<u8> {
+        let mut value = vec![0x00, 0xff, 0xfe, b'\r', b'\n', 0x80, b'$', b'3', b'\r', b'\n'];
+        value.extend((0..=255u8).rev());
+        value
+    }
+
+    #[test]
+    fn test_set_get_binary_value() {
+        let mut con = setup();
+        let value = binary_value();
+
+        let _: () = con.set("binary-safe-string", value.as_slice()).unwrap();
+        let result: Vec<u8> = con.get("binary-safe-string").unwrap();
+        assert_eq!(result, value);
+
+        let len: usize = con.strlen("binary-safe-string").unwrap();
+        assert_eq!(len, value.len());
+
+        let _: () = con.append("binary-safe-string", &[0xc3u8, 0x28][..]).unwrap();
+        let result: Vec<u8> = con.getrange("binary-safe-string", -2, -1).unwrap();
+        assert_eq!(result, vec![0xc3, 0x28]);
+    }
+
+    #[test]
+    fn test_list_and_hash_binary_values() {
+        let mut con = setup();
+        let value = binary_value();
+
+        let _: () = con.del("binary-safe-list").unwrap();
+        let _: () = con.rpush("binary-safe-list", value.as_slice()).unwrap();
+        let _: () = con.rpush("binary-safe-list", &[0xffu8][..]).unwrap();
+        let result: Vec<Vec<u8>> = con.lrange("binary-safe-list", 0, -1).unwrap();
+        assert_eq!(result, vec![value.clone(), vec![0xff]]);
+
+        let _: () = con.hset("binary-safe-hash", "field", value.as_slice()).unwrap();
+        let result: Vec<u8> = con.hget("binary-safe-hash", "field").unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn test_non_utf8_keys() {
+        let mut con = setup();
+        let first = b"binary-safe-key-\xff".to_vec();
+        let second = b"binary-safe-key-\xfe".to_vec();
+
+        let _: () = con.set(first.as_slice(), "first").unwrap();
+        let _: () = con.set(second.as_slice(), "second").unwrap();
+        let result: String = con.get(first.as_slice()).unwrap();
+        assert_eq!(result, "first");
+        let result: String = con.get(second.as_slice()).unwrap();
+        assert_eq!(result, "second");
+
+        let mut keys: Vec<Vec<u8>> = con.keys(b"binary-safe-key-[\xfe\xff]".as_slice()).unwrap();
+        keys.sort();
+        assert_eq!(keys, vec![second.clone(), first.clone()]);
+
+        let _: () = con.rename(first.as_slice(), b"binary-safe-key-\x80".as_slice()).unwrap();
+        let exists: i64 = con.exists(first.as_slice()).unwrap();
+        assert_eq!(exists, 0);
+        let removed: i64 = con.del(&[second.as_slice(), b"binary-safe-key-\x80".as_slice()]).unwrap();
+        assert_eq!(removed, 2);
+    }
+
+    #[test]
+    fn test_non_utf8_members_and_fields() {
+        let mut con = setup();
+        let first = vec![0xffu8, 0x01];
+        let second = vec![0xfeu8, 0x01];
+
+        let _: () = con.del(&["binary-safe-set", "binary-safe-fields", "binary-safe-zset"]).unwrap();
+        let added: i64 = con.sadd("binary-safe-set", &[first.as_slice(), second.as_slice()]).unwrap();
+        assert_eq!(added, 2);
+        let mut members: Vec<Vec<u8>> = con.smembers("binary-safe-set").unwrap();
+        members.sort();
+        assert_eq!(members, vec![second.clone(), first.clone()]);
+        let is_member: bool = con.sismember("binary-safe-set", &[0xfdu8, 0x01][..]).unwrap();
+        assert!(!is_member);
+
+        let _: () = con.hset("binary-safe-fields", first.as_slice(), "a").unwrap();
+        let _: () = con.hset("binary-safe-fields", second.as_slice(), "b").unwrap();
+        let len: i64 = con.hlen("binary-safe-fields").unwrap();
+        assert_eq!(len, 2);
+        let value: String = con.hget("binary-safe-fields", first.as_slice()).unwrap();
+        assert_eq!(value, "a");
+        let mut fields: Vec<Vec<u8>> = con.hkeys("binary-safe-fields").unwrap();
+        fields.sort();
+        assert_eq!(fields, vec![second.clone(), first.clone()]);
+
+        let added: i64 = con.zadd_multiple("binary-safe-zset", &[(1, first.as_slice()), (2, second.as_slice())]).unwrap();
+        assert_eq!(added, 2);
+        let members: Vec<Vec<u8>> = con.zrange("binary-safe-zset", 0, -1).unwrap();
+        assert_eq!(members, vec![first.clone(), second.clone()]);
+        let score: f64 = con.zscore("binary-safe-zset", second.as_slice()).unwrap();
+        assert_eq!(score, 2.0);
+        let members: Vec<Vec<u8>> = redis::cmd("ZRANGEBYLEX").arg("binary-safe-zset").arg(b"[\xfe".as_slice()).arg("+").query(&mut con).unwrap();
+        assert_eq!(members, vec![first.clone(), second.clone()]);
+    }
+
+    #[test]
+    fn test_rdb_keeps_non_utf8_keys_and_members() {
+        let key = b"binary-safe-\xff".to_vec();
+        let member = vec![0xfeu8, 0x00, 0xff];
+        let mut zset = SortedSet::new();
+        zset.add(member.clone(), 1.5);
+        let mut snapshot = DatabaseSnapshot::default();
+        snapshot.records.insert(key.clone(), Structure::Set(HashSet::from([member.clone()])));
+        snapshot.records.insert(b"hash-\xfe".to_vec(), Structure::Hash(HashMap::from([(member.clone(), b"v".to_vec())])));
+        snapshot.records.insert(b"zset-\xfd".to_vec(), Structure::SortedSet(zset));
+
+        let databases = decode(&encode(&HashMap::from([(0, snapshot)]))).unwrap();
+        let records = &databases[&0].records;
+        assert!(matches!(records.get(&key), Some(Structure::Set(members)) if members.contains(&member)));
+        assert!(matches!(records.get(b"hash-\xfe".as_slice()), Some(Structure::Hash(fields)) if fields.contains_key(&member)));
+        assert!(matches!(records.get(b"zset-\xfd".as_slice()), Some(Structure::SortedSet(zset)) if zset.get_score(&member) == Some(1.5)));
+    }
+
+    #[test]
+    fn test_parse_frame_with_crlf_in_bulk_string() {
+        let bytes = b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$4\r\na\r\nb\r\n*2\r\n$3\r\nGET\r\n$1\r\nk\r\n";
+        let frames = Frame::parse_multiple_frames(bytes).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].get_arg_bytes(2).unwrap(), b"a\r\nb".to_vec());
+        assert_eq!(frames[1].get_arg(0).unwrap(), "GET");
+        assert_eq!(frames[0].as_bytes(), bytes[..frames[0].as_bytes().len()].to_vec());
+    }
+}
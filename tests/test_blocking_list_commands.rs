@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use redis::{cmd, Client, Commands, Connection, RedisResult};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    fn items(con: &mut Connection, key: &str) -> Vec<String> {
+        con.lrange(key, 0, -1).unwrap()
+    }
+
+    type BlpopReply = Option<(String, String)>;
+
+    #[test]
+    fn test_blmove_immediate_and_timeout() {
+        let mut con = setup();
+        let _: () = con.del(&["blmove-src", "blmove-dst"]).unwrap();
+        let _: i64 = con.rpush("blmove-src", &["a", "b"]).unwrap();
+
+        let value: Option<String> = cmd("BLMOVE").arg("blmove-src").arg("blmove-dst").arg("RIGHT").arg("LEFT").arg(1).query(&mut con).unwrap();
+        assert_eq!(value, Some("b".to_string()));
+        let value: Option<String> = cmd("BRPOPLPUSH").arg("blmove-src").arg("blmove-dst").arg(1).query(&mut con).unwrap();
+        assert_eq!(value, Some("a".to_string()));
+        assert_eq!(items(&mut con, "blmove-dst"), vec!["a", "b"]);
+
+        // 源列表为空时等待到超时，支持小数秒
+        let start = Instant::now();
+        let value: Option<String> = cmd("BLMOVE").arg("blmove-src").arg("blmove-dst").arg("LEFT").arg("LEFT").arg(0.2).query(&mut con).unwrap();
+        assert_eq!(value, None);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let result: RedisResult<Option<String>> = cmd("BLMOVE").arg("blmove-src").arg("blmove-dst").arg("UP").arg("LEFT").arg(1).query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_blmove_wakeup() {
+        let mut con = setup();
+        let _: () = con.del(&["blmove-wake-src", "blmove-wake-dst"]).unwrap();
+
+        let writer = thread::spawn(|| {
+            thread::sleep(Duration::from_millis(200));
+            let mut con = setup();
+            let len: i64 = con.rpush("blmove-wake-src", &["a", "b", "c"]).unwrap();
+            // 唤醒等待者不影响 RPUSH 的返回值
+            assert_eq!(len, 3);
+        });
+
+        let value: Option<String> = cmd("BLMOVE").arg("blmove-wake-src").arg("blmove-wake-dst").arg("LEFT").arg("RIGHT").arg(5).query(&mut con).unwrap();
+        writer.join().unwrap();
+        assert_eq!(value, Some("a".to_string()));
+        assert_eq!(items(&mut con, "blmove-wake-src"), vec!["b", "c"]);
+        assert_eq!(items(&mut con, "blmove-wake-dst"), vec!["a"]);
+    }
+
+    #[test]
+    fn test_blmpop() {
+        let mut con = setup();
+        let _: () = con.del(&["blmpop-a", "blmpop-b"]).unwrap();
+
+        let reply: Option<(String, Vec<String>)> = cmd("BLMPOP").arg(0.1).arg(2).arg("blmpop-a").arg("blmpop-b").arg("LEFT").query(&mut con).unwrap();
+        assert_eq!(reply, None);
+
+        let writer = thread::spawn(|| {
+            thread::sleep(Duration::from_millis(200));
+            let mut con = setup();
+            let _: i64 = con.rpush("blmpop-b", &["1", "2", "3"]).unwrap();
+        });
+
+        let reply: Option<(String, Vec<String>)> = cmd("BLMPOP").arg(5).arg(2).arg("blmpop-a").arg("blmpop-b").arg("RIGHT").arg("COUNT").arg(2).query(&mut con).unwrap();
+        writer.join().unwrap();
+        assert_eq!(reply, Some(("blmpop-b".to_string(), vec!["3".to_string(), "2".to_string()])));
+        assert_eq!(items(&mut con, "blmpop-b"), vec!["1"]);
+    }
+
+    #[test]
+    fn test_blocking_waiters_served_in_order_across_keys() {
+        let mut con = setup();
+        let _: () = con.del(&["bfifo-a", "bfifo-b"]).unwrap();
+
+        // 第一个客户端等待两个键，第二个客户端随后只等待 bfifo-b
+        let first = thread::spawn(|| {
+            let mut con = setup();
+            let reply: BlpopReply = cmd("BLPOP").arg("bfifo-a").arg("bfifo-b").arg(5).query(&mut con).unwrap();
+            reply
+        });
+        thread::sleep(Duration::from_millis(100));
+        let second = thread::spawn(|| {
+            let mut con = setup();
+            let reply: Option<(String, Vec<String>)> = cmd("BLMPOP").arg(5).arg(1).arg("bfifo-b").arg("RIGHT").query(&mut con).unwrap();
+            reply
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        // 一次写入两个元素，先阻塞的客户端先被服务，剩余的元素交给下一个等待者
+        let len: i64 = con.rpush("bfifo-b", &["x", "y"]).unwrap();
+        assert_eq!(len, 2);
+
+        assert_eq!(first.join().unwrap(), Some(("bfifo-b".to_string(), "x".to_string())));
+        assert_eq!(second.join().unwrap(), Some(("bfifo-b".to_string(), vec!["y".to_string()])));
+        let exists: bool = con.exists("bfifo-b").unwrap();
+        assert!(!exists);
+    }
+
+    #[test]
+    fn test_blocking_wakeup_by_lmove_and_transaction() {
+        let mut con = setup();
+        let _: () = con.del(&["bwake-src", "bwake-dst", "bwake-multi"]).unwrap();
+        let _: i64 = con.rpush("bwake-src", "moved").unwrap();
+
+        // LMOVE 推入目标列表后唤醒等待目标列表的客户端
+        let waiter = thread::spawn(|| {
+            let mut con = setup();
+            let reply: BlpopReply = cmd("BRPOP").arg("bwake-dst").arg(5).query(&mut con).unwrap();
+            reply
+        });
+        thread::sleep(Duration::from_millis(100));
+        let _: Option<String> = cmd("LMOVE").arg("bwake-src").arg("bwake-dst").arg("LEFT").arg("LEFT").query(&mut con).unwrap();
+        assert_eq!(waiter.join().unwrap(), Some(("bwake-dst".to_string(), "moved".to_string())));
+
+        // 事务执行完毕后唤醒，等待者看到事务写入的全部元素
+        let waiter = thread::spawn(|| {
+            let mut con = setup();
+            let reply: Option<(String, Vec<String>)> = cmd("BLMPOP").arg(5).arg(1).arg("bwake-multi").arg("LEFT").arg("COUNT").arg(10).query(&mut con).unwrap();
+            reply
+        });
+        thread::sleep(Duration::from_millis(100));
+        let (first, second): (i64, i64) = redis::pipe().atomic()
+            .rpush("bwake-multi", "a")
+            .rpush("bwake-multi", "b")
+            .query(&mut con)
+            .unwrap();
+        assert_eq!((first, second), (1, 2));
+        assert_eq!(waiter.join().unwrap(), Some(("bwake-multi".to_string(), vec!["a".to_string(), "b".to_string()])));
+    }
+
+    #[test]
+    fn test_blocking_wakeup_by_restore() {
+        let mut con = setup();
+        let _: () = con.del(&["brestore-source", "brestore-target"]).unwrap();
+        let _: i64 = con.rpush("brestore-source", &["r1", "r2"]).unwrap();
+        let dump: Vec<u8> = cmd("DUMP").arg("brestore-source").query(&mut con).unwrap();
+
+        let waiter = thread::spawn(|| {
+            let mut con = setup();
+            let reply: BlpopReply = cmd("BLPOP").arg("brestore-target").arg(5).query(&mut con).unwrap();
+            reply
+        });
+        thread::sleep(Duration::from_millis(100));
+        let _: () = cmd("RESTORE").arg("brestore-target").arg(0).arg(dump).query(&mut con).unwrap();
+
+        assert_eq!(waiter.join().unwrap(), Some(("brestore-target".to_string(), "r1".to_string())));
+        assert_eq!(items(&mut con, "brestore-target"), vec!["r2"]);
+    }
+
+    #[test]
+    fn test_blocking_wakeup_in_other_db() {
+        let mut con = setup();
+        let _: () = con.del("bwake-db-move").unwrap();
+        let _: () = cmd("SELECT").arg(1).query(&mut con).unwrap();
+        let _: () = con.del(&["bwake-db-move", "bwake-db-multi"]).unwrap();
+        let _: () = cmd("SELECT").arg(0).query(&mut con).unwrap();
+
+        let blpop_db1 = |key: &'static str| thread::spawn(move || {
+            let mut con = Client::open("redis://127.0.0.1:6379/1").unwrap().get_connection().unwrap();
+            let reply: BlpopReply = cmd("BLPOP").arg(key).arg(5).query(&mut con).unwrap();
+            reply
+        });
+
+        // MOVE 把列表移到数据库 1 后唤醒数据库 1 中的等待者
+        let waiter = blpop_db1("bwake-db-move");
+        thread::sleep(Duration::from_millis(100));
+        let _: i64 = con.rpush("bwake-db-move", "moved").unwrap();
+        let moved: i64 = cmd("MOVE").arg("bwake-db-move").arg(1).query(&mut con).unwrap();
+        assert_eq!(moved, 1);
+        assert_eq!(waiter.join().unwrap(), Some(("bwake-db-move".to_string(), "moved".to_string())));
+
+        // 事务中 SELECT 之后的写入唤醒对应数据库中的等待者
+        let waiter = blpop_db1("bwake-db-multi");
+        thread::sleep(Duration::from_millis(100));
+        let _: () = cmd("MULTI").query(&mut con).unwrap();
+        let _: () = cmd("SELECT").arg(1).query(&mut con).unwrap();
+        let _: () = cmd("RPUSH").arg("bwake-db-multi").arg("queued").query(&mut con).unwrap();
+        let _: () = cmd("SELECT").arg(0).query(&mut con).unwrap();
+        let _: () = cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(waiter.join().unwrap(), Some(("bwake-db-multi".to_string(), "queued".to_string())));
+    }
+}
@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use redis::{cmd, Client, Connection, RedisResult, Value};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    fn config_get(con: &mut Connection, pattern: &str) -> HashMap<String, String> {
+        cmd("CONFIG").arg("GET").arg(pattern).query(con).unwrap()
+    }
+
+    fn error_of(result: RedisResult<Value>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_config_get_patterns() {
+        let mut con = setup();
+        let config = config_get(&mut con, "maxmemory*");
+        let mut names: Vec<&String> = config.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["maxmemory", "maxmemory-policy", "maxmemory-samples"]);
+
+        let config = config_get(&mut con, "PORT");
+        assert_eq!(config.get("port").map(String::as_str), Some("6379"));
+
+        let config: HashMap<String, String> = cmd("CONFIG").arg("GET").arg("databases").arg("appendfsync").query(&mut con).unwrap();
+        assert_eq!(config.len(), 2);
+        assert!(config_get(&mut con, "no-such-*").is_empty());
+    }
+
+    #[test]
+    fn test_config_set_errors() {
+        let mut con = setup();
+        let error = error_of(cmd("CONFIG").arg("SET").arg("no-such-option").arg("1").query(&mut con));
+        assert!(error.contains("Unknown option"));
+        let error = error_of(cmd("CONFIG").arg("SET").arg("port").arg("7000").query(&mut con));
+        assert!(error.contains("can't set immutable config"));
+        let error = error_of(cmd("CONFIG").arg("SET").arg("appendfsync").arg("sometimes").query(&mut con));
+        assert!(error.contains("appendfsync"));
+        let error = error_of(cmd("CONFIG").arg("SET").arg("maxmemory").query(&mut con));
+        assert!(error.contains("wrong number of arguments"));
+    }
+
+    #[test]
+    fn test_config_set_is_atomic() {
+        let mut con = setup();
+        let samples = config_get(&mut con, "maxmemory-samples")["maxmemory-samples"].clone();
+
+        // 任意一项出错时所有修改都不生效
+        let result: RedisResult<Value> = cmd("CONFIG").arg("SET")
+            .arg("maxmemory-samples").arg("7")
+            .arg("maxmemory-policy").arg("not-a-policy")
+            .query(&mut con);
+        assert!(result.is_err());
+        assert_eq!(config_get(&mut con, "maxmemory-samples")["maxmemory-samples"], samples);
+
+        let _: () = cmd("CONFIG").arg("SET").arg("maxmemory-samples").arg("7").arg("loglevel").arg("info").query(&mut con).unwrap();
+        assert_eq!(config_get(&mut con, "maxmemory-samples")["maxmemory-samples"], "7");
+        let _: () = cmd("CONFIG").arg("SET").arg("maxmemory-samples").arg(&samples).query(&mut con).unwrap();
+    }
+
+    /// 使用独立数据目录启动的服务器，用于验证配置在运行时生效
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> RedisResult<Connection> {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_config_set_takes_effect_live() {
+        let dir = temp_dir("rudis-config-live");
+        let server = TestServer::start(&dir, 16402);
+        let mut con = server.connect().unwrap();
+
+        // 保存规则：修改后后台保存任务立即使用新规则
+        let _: () = cmd("CONFIG").arg("SET").arg("save").arg("1 1").query(&mut con).unwrap();
+        assert_eq!(config_get(&mut con, "save")["save"], "1 1");
+        let _: () = cmd("SET").arg("key").arg("value").query(&mut con).unwrap();
+        thread::sleep(Duration::from_millis(2500));
+        assert!(dir.join("data/dump.rdb").exists());
+
+        // 连接数限制
+        let _: () = cmd("CONFIG").arg("SET").arg("maxclients").arg("1").query(&mut con).unwrap();
+        let rejected = server.connect().and_then(|mut other| cmd("PING").query::<String>(&mut other));
+        assert!(rejected.is_err());
+        assert!(error_of(cmd("CONFIG").arg("SET").arg("maxclients").arg("0").query(&mut con)).contains("argument must be between 1"));
+        assert_eq!(config_get(&mut con, "maxclients")["maxclients"], "1");
+        let _: () = cmd("CONFIG").arg("SET").arg("maxclients").arg("10000").query(&mut con).unwrap();
+
+        // 认证密码：新连接需要认证，已认证的连接不受影响
+        let _: () = cmd("CONFIG").arg("SET").arg("requirepass").arg("newpass").query(&mut con).unwrap();
+        let mut other = server.connect().unwrap();
+        assert!(error_of(cmd("GET").arg("key").query(&mut other)).contains("NOAUTH"));
+        let _: () = cmd("AUTH").arg("newpass").query(&mut other).unwrap();
+        let value: String = cmd("GET").arg("key").query(&mut other).unwrap();
+        assert_eq!(value, "value");
+        let _: () = cmd("CONFIG").arg("SET").arg("requirepass").arg("").query(&mut con).unwrap();
+        let mut other = server.connect().unwrap();
+        let _: String = cmd("PING").query(&mut other).unwrap();
+
+        // 统计信息清零
+        let _: () = cmd("CONFIG").arg("RESETSTAT").query(&mut con).unwrap();
+        let info: String = cmd("INFO").arg("stats").query(&mut con).unwrap();
+        assert!(info.contains("total_commands_processed:1\r\n"));
+        assert!(info.contains("rejected_connections:0\r\n"));
+
+        // 没有配置文件时无法重写
+        assert!(error_of(cmd("CONFIG").arg("REWRITE").query(&mut con)).contains("without a config file"));
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_config_rewrite() {
+        let dir = temp_dir("rudis-config-rewrite");
+        std::fs::write(dir.join("rudis.conf"), "# 最大客户端连接数\nmaxclients 100\nmaxclients 200\nhz 10\n").unwrap();
+
+        {
+            let server = TestServer::start(&dir, 16401);
+            let mut con = server.connect().unwrap();
+            assert_eq!(config_get(&mut con, "maxclients")["maxclients"], "200");
+            let _: () = cmd("CONFIG").arg("SET")
+                .arg("maxclients").arg("50")
+                .arg("maxmemory-policy").arg("allkeys-lru")
+                .arg("save").arg("60 100")
+                .query(&mut con).unwrap();
+            let _: () = cmd("CONFIG").arg("REWRITE").query(&mut con).unwrap();
+        }
+
+        // 注释与其他配置保留，已有配置项原地更新，新配置项追加到末尾
+        let content = std::fs::read_to_string(dir.join("rudis.conf")).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[..3], ["# 最大客户端连接数", "maxclients 50", "hz 10"]);
+        assert!(lines.contains(&"maxmemory-policy allkeys-lru"));
+        assert!(lines.contains(&"save 60 100"));
+        assert!(!lines.iter().any(|line| line.starts_with("maxmemory ")));
+
+        let server = TestServer::start(&dir, 16401);
+        let mut con = server.connect().unwrap();
+        assert_eq!(config_get(&mut con, "maxclients")["maxclients"], "50");
+        assert_eq!(config_get(&mut con, "maxmemory-policy")["maxmemory-policy"], "allkeys-lru");
+        assert_eq!(config_get(&mut con, "save")["save"], "60 100");
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,259 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    use rudis_server::frame::{Frame, ProtocolError, DEFAULT_MAX_BULK_LEN};
+    use rudis_server::network::decoder::FrameDecoder;
+
+    /// 流水线样本：包含二进制值、嵌套数组、空数组、空字符串、内联命令与多余换行
+    fn sample_stream() -> Vec<u8> {
+        let mut big_value = Vec::new();
+        for i in 0..5000u32 {
+            big_value.push((i % 251) as u8);
+        }
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n");
+        bytes.extend_from_slice(format!("${}\r\n", big_value.len()).as_bytes());
+        bytes.extend_from_slice(&big_value);
+        bytes.extend_from_slice(b"\r\n");
+        bytes.extend_from_slice(b"*2\r\n$3\r\nGET\r\n$4\r\na\r\nb\r\n");
+        bytes.extend_from_slice(b"PING\r\n");
+        bytes.extend_from_slice(b"\r\n");
+        bytes.extend_from_slice(b"SET \"quoted \\x41\\n\" 'single'\n");
+        bytes.extend_from_slice(b"*0\r\n");
+        bytes.extend_from_slice(b"*2\r\n$4\r\nECHO\r\n$0\r\n\r\n");
+        bytes.extend_from_slice(b"*2\r\n*1\r\n:42\r\n+OK\r\n");
+        bytes
+    }
+
+    fn decode_in_chunks(bytes: &[u8], split_points: &[usize]) -> Vec<Vec<u8>> {
+        let mut decoder = FrameDecoder::new(DEFAULT_MAX_BULK_LEN);
+        let mut frames = Vec::new();
+        let mut start = 0;
+        for &end in split_points.iter().chain(std::iter::once(&bytes.len())) {
+            decoder.extend(&bytes[start..end]);
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame.as_bytes());
+            }
+            start = end;
+        }
+        assert!(!decoder.has_pending());
+        frames
+    }
+
+    /// 简单的线性同余随机数，保证测试可重复
+    fn next_random(seed: &mut u64) -> u64 {
+        *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        *seed >> 33
+    }
+
+    #[test]
+    fn test_decode_whole_stream() {
+        let frames = decode_in_chunks(&sample_stream(), &[]);
+        assert_eq!(frames.len(), 7);
+        assert_eq!(frames[2], Frame::Array(vec![Frame::BulkString(b"PING".to_vec())]).as_bytes());
+        assert_eq!(frames[3], Frame::Array(vec![
+            Frame::BulkString(b"SET".to_vec()),
+            Frame::BulkString(b"quoted A\n".to_vec()),
+            Frame::BulkString(b"single".to_vec()),
+        ]).as_bytes());
+    }
+
+    #[test]
+    fn test_decode_every_single_split_point() {
+        let bytes = sample_stream();
+        let expected = decode_in_chunks(&bytes, &[]);
+        for split in 0..=bytes.len() {
+            assert_eq!(decode_in_chunks(&bytes, &[split]), expected, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn test_decode_random_split_points() {
+        let bytes = sample_stream();
+        let expected = decode_in_chunks(&bytes, &[]);
+        let mut seed = 7;
+        for _ in 0..500 {
+            let count = (next_random(&mut seed) % 20) as usize;
+            let mut split_points: Vec<usize> = (0..count)
+                .map(|_| (next_random(&mut seed) as usize) % (bytes.len() + 1))
+                .collect();
+            split_points.sort();
+            assert_eq!(decode_in_chunks(&bytes, &split_points), expected, "splits {:?}", split_points);
+        }
+    }
+
+    #[test]
+    fn test_decode_byte_by_byte() {
+        let bytes = sample_stream();
+        let expected = decode_in_chunks(&bytes, &[]);
+        let split_points: Vec<usize> = (1..bytes.len()).collect();
+        assert_eq!(decode_in_chunks(&bytes, &split_points), expected);
+    }
+
+    #[test]
+    fn test_decode_large_array_byte_by_byte() {
+        // 已接收的部分不会被重复扫描，逐字节到达的大数组也能在线性时间内解析完成
+        let mut items = Vec::new();
+        for i in 0..50_000 {
+            let nested = Frame::Array(vec![Frame::Integer(i), Frame::BulkString(format!("v{}", i).into_bytes())]);
+            items.push(if i % 2 == 0 { Frame::BulkString(format!("item:{}", i).into_bytes()) } else { nested });
+        }
+        let bytes = Frame::Array(items).as_bytes();
+
+        let start = std::time::Instant::now();
+        let mut decoder = FrameDecoder::new(DEFAULT_MAX_BULK_LEN);
+        let mut frames = Vec::new();
+        for &byte in &bytes {
+            decoder.extend(&[byte]);
+            if let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame.as_bytes());
+            } else {
+                assert!(decoder.has_pending());
+            }
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "{:?}", start.elapsed());
+        assert_eq!(frames, vec![bytes]);
+        assert!(!decoder.has_pending());
+    }
+
+    #[test]
+    fn test_max_bulk_len() {
+        let mut decoder = FrameDecoder::new(10);
+        decoder.extend(b"*2\r\n$3\r\nGET\r\n$11\r\n");
+        let err = decoder.next_frame().err().unwrap();
+        assert!(err.downcast_ref::<ProtocolError>().is_some());
+
+        let mut decoder = FrameDecoder::new(10);
+        decoder.extend(b"*2\r\n$3\r\nGET\r\n$10\r\n0123456789\r\n");
+        assert!(decoder.next_frame().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        for bytes in [&b"*1\r\n$3\r\nGETX\r\n"[..], b"*x\r\n", b"SET \"unbalanced\r\n", b"*1\r\n?3\r\n"] {
+            let mut decoder = FrameDecoder::new(DEFAULT_MAX_BULK_LEN);
+            decoder.extend(bytes);
+            assert!(decoder.next_frame().is_err(), "{:?}", String::from_utf8_lossy(bytes));
+        }
+    }
+
+    #[test]
+    fn test_rdb_file_frames() {
+        let frame = Frame::RDBFile(b"REDIS0011".to_vec()).as_bytes();
+        let (decoded, used) = Frame::decode(&frame, DEFAULT_MAX_BULK_LEN).unwrap().unwrap();
+        assert_eq!(used, frame.len());
+        assert_eq!(decoded.as_bytes(), frame);
+
+        // 长度溢出或超过 proto-max-bulk-len 时返回协议错误
+        let err = Frame::decode(b"@18446744073709551615\r\n", DEFAULT_MAX_BULK_LEN).err().unwrap();
+        assert!(err.downcast_ref::<ProtocolError>().is_some());
+        let err = Frame::decode(b"@11\r\n", 10).err().unwrap();
+        assert!(err.downcast_ref::<ProtocolError>().is_some());
+        assert!(Frame::decode(b"@10\r\n0123", 10).unwrap().is_none());
+
+        // 客户端连接不接受 RDB 文件帧，'@' 开头的行按内联命令解析
+        let mut decoder = FrameDecoder::new(DEFAULT_MAX_BULK_LEN);
+        decoder.extend(&frame);
+        let inline = decoder.next_frame().unwrap().unwrap();
+        assert!(matches!(inline, Frame::Array(_)));
+        assert_eq!(inline.get_arg(0).unwrap(), "@9");
+    }
+
+    fn connect() -> TcpStream {
+        let stream = TcpStream::connect("127.0.0.1:6379").unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        stream
+    }
+
+    fn read_reply(stream: &mut TcpStream, expected_len: usize) -> Vec<u8> {
+        let mut reply = Vec::new();
+        let mut buffer = [0u8; 64 * 1024];
+        while reply.len() < expected_len {
+            let n = stream.read(&mut buffer).unwrap();
+            if n == 0 {
+                break;
+            }
+            reply.extend_from_slice(&buffer[..n]);
+        }
+        reply
+    }
+
+    /// 大值分成多个 TCP 段缓慢发送，服务器需要等待完整的帧
+    #[test]
+    fn test_server_large_value_split_across_segments() {
+        let mut stream = connect();
+        let value: Vec<u8> = (0..200_000u32).map(|i| (i % 256) as u8).collect();
+        let mut request = Vec::new();
+        request.extend_from_slice(b"*3\r\n$3\r\nSET\r\n$23\r\ndecoder-split-large-key\r\n");
+        request.extend_from_slice(format!("${}\r\n", value.len()).as_bytes());
+        request.extend_from_slice(&value);
+        request.extend_from_slice(b"\r\n");
+        request.extend_from_slice(b"*2\r\n$3\r\nGET\r\n$23\r\ndecoder-split-large-key\r\n");
+
+        for chunk in request.chunks(7919) {
+            stream.write_all(chunk).unwrap();
+            stream.flush().unwrap();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let mut expected = b"+OK\r\n".to_vec();
+        expected.extend_from_slice(format!("${}\r\n", value.len()).as_bytes());
+        expected.extend_from_slice(&value);
+        expected.extend_from_slice(b"\r\n");
+        assert_eq!(read_reply(&mut stream, expected.len()), expected);
+    }
+
+    /// 一次发送大量流水线命令
+    #[test]
+    fn test_server_big_pipeline() {
+        let mut stream = connect();
+        let mut request = Vec::new();
+        for i in 0..2000 {
+            let value = format!("value-{}", i);
+            request.extend_from_slice(format!("*3\r\n$3\r\nSET\r\n$19\r\ndecoder-pipeline-ke\r\n${}\r\n{}\r\n", value.len(), value).as_bytes());
+        }
+        stream.write_all(&request).unwrap();
+        let expected = b"+OK\r\n".repeat(2000);
+        assert_eq!(read_reply(&mut stream, expected.len()), expected);
+    }
+
+    #[test]
+    fn test_server_inline_commands() {
+        let mut stream = connect();
+        stream.write_all(b"PING\r\nSET decoder-inline-key \"hello world\"\r\nGET decoder-inline-key\n").unwrap();
+        let expected = b"+PONG\r\n+OK\r\n$11\r\nhello world\r\n".to_vec();
+        assert_eq!(read_reply(&mut stream, expected.len()), expected);
+    }
+
+    #[test]
+    fn test_server_ignores_rdb_file_frames() {
+        let mut stream = connect();
+        stream.write_all(b"@18446744073709551615\r\nPING\r\n").unwrap();
+        let mut reply = Vec::new();
+        let mut buffer = [0u8; 1024];
+        while !reply.ends_with(b"+PONG\r\n") {
+            let n = stream.read(&mut buffer).unwrap();
+            if n == 0 {
+                break;
+            }
+            reply.extend_from_slice(&buffer[..n]);
+        }
+        let reply = String::from_utf8_lossy(&reply);
+        assert!(reply.starts_with("-ERR unknown command"), "{}", reply);
+        assert!(reply.ends_with("+PONG\r\n"), "{}", reply);
+    }
+
+    #[test]
+    fn test_server_rejects_too_big_bulk() {
+        let mut stream = connect();
+        stream.write_all(b"*2\r\n$3\r\nGET\r\n$1073741824\r\n").unwrap();
+        let reply = read_reply(&mut stream, 1);
+        assert!(String::from_utf8_lossy(&reply).starts_with("-ERR Protocol error"));
+        // 服务器在协议错误后关闭连接
+        let mut buffer = [0u8; 16];
+        assert_eq!(stream.read(&mut buffer).unwrap_or(0), 0);
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use redis::{cmd, Client, Commands, Connection, RedisResult};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    fn reset(con: &mut Connection, key: &str, values: &[&str]) {
+        let _: () = con.del(key).unwrap();
+        if !values.is_empty() {
+            let _: i64 = con.rpush(key, values).unwrap();
+        }
+    }
+
+    fn items(con: &mut Connection, key: &str) -> Vec<String> {
+        con.lrange(key, 0, -1).unwrap()
+    }
+
+    #[test]
+    fn test_pop_with_count() {
+        let mut con = setup();
+        reset(&mut con, "list-pop-count", &["a", "b", "c", "d"]);
+
+        let popped: Vec<String> = cmd("LPOP").arg("list-pop-count").arg(2).query(&mut con).unwrap();
+        assert_eq!(popped, vec!["a", "b"]);
+        let popped: Vec<String> = cmd("RPOP").arg("list-pop-count").arg(5).query(&mut con).unwrap();
+        assert_eq!(popped, vec!["d", "c"]);
+
+        // 列表弹空后键被删除
+        let exists: bool = con.exists("list-pop-count").unwrap();
+        assert!(!exists);
+
+        // 键不存在时带 count 返回 nil
+        let popped: Option<Vec<String>> = cmd("LPOP").arg("list-pop-count").arg(1).query(&mut con).unwrap();
+        assert_eq!(popped, None);
+
+        let result: RedisResult<Vec<String>> = cmd("LPOP").arg("list-pop-count").arg(-1).query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_linsert() {
+        let mut con = setup();
+        reset(&mut con, "list-linsert", &["a", "c"]);
+
+        let len: i64 = cmd("LINSERT").arg("list-linsert").arg("BEFORE").arg("c").arg("b").query(&mut con).unwrap();
+        assert_eq!(len, 3);
+        let len: i64 = cmd("LINSERT").arg("list-linsert").arg("AFTER").arg("c").arg("d").query(&mut con).unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(items(&mut con, "list-linsert"), vec!["a", "b", "c", "d"]);
+
+        // 找不到基准元素返回 -1，键不存在返回 0
+        let len: i64 = cmd("LINSERT").arg("list-linsert").arg("BEFORE").arg("x").arg("y").query(&mut con).unwrap();
+        assert_eq!(len, -1);
+        let len: i64 = cmd("LINSERT").arg("list-linsert-missing").arg("BEFORE").arg("x").arg("y").query(&mut con).unwrap();
+        assert_eq!(len, 0);
+    }
+
+    #[test]
+    fn test_lpos() {
+        let mut con = setup();
+        reset(&mut con, "list-lpos", &["a", "b", "c", "1", "2", "3", "c", "c"]);
+
+        let pos: Option<i64> = cmd("LPOS").arg("list-lpos").arg("c").query(&mut con).unwrap();
+        assert_eq!(pos, Some(2));
+        let pos: Option<i64> = cmd("LPOS").arg("list-lpos").arg("c").arg("RANK").arg(2).query(&mut con).unwrap();
+        assert_eq!(pos, Some(6));
+        let pos: Option<i64> = cmd("LPOS").arg("list-lpos").arg("c").arg("RANK").arg(-1).query(&mut con).unwrap();
+        assert_eq!(pos, Some(7));
+        let pos: Vec<i64> = cmd("LPOS").arg("list-lpos").arg("c").arg("COUNT").arg(0).query(&mut con).unwrap();
+        assert_eq!(pos, vec![2, 6, 7]);
+        let pos: Vec<i64> = cmd("LPOS").arg("list-lpos").arg("c").arg("RANK").arg(-1).arg("COUNT").arg(2).query(&mut con).unwrap();
+        assert_eq!(pos, vec![7, 6]);
+        let pos: Vec<i64> = cmd("LPOS").arg("list-lpos").arg("c").arg("COUNT").arg(0).arg("MAXLEN").arg(3).query(&mut con).unwrap();
+        assert_eq!(pos, vec![2]);
+        let pos: Option<i64> = cmd("LPOS").arg("list-lpos").arg("x").query(&mut con).unwrap();
+        assert_eq!(pos, None);
+
+        let result: RedisResult<Option<i64>> = cmd("LPOS").arg("list-lpos").arg("c").arg("RANK").arg(0).query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_lmove_and_rpoplpush() {
+        let mut con = setup();
+        reset(&mut con, "list-lmove-src", &["a", "b", "c"]);
+        reset(&mut con, "list-lmove-dst", &[]);
+
+        // 可靠队列：取出任务的同时放入处理中列表
+        let value: String = cmd("RPOPLPUSH").arg("list-lmove-src").arg("list-lmove-dst").query(&mut con).unwrap();
+        assert_eq!(value, "c");
+        let value: String = cmd("LMOVE").arg("list-lmove-src").arg("list-lmove-dst").arg("LEFT").arg("RIGHT").query(&mut con).unwrap();
+        assert_eq!(value, "a");
+        assert_eq!(items(&mut con, "list-lmove-src"), vec!["b"]);
+        assert_eq!(items(&mut con, "list-lmove-dst"), vec!["c", "a"]);
+
+        // 源列表与目标列表相同时旋转列表
+        let value: String = cmd("LMOVE").arg("list-lmove-dst").arg("list-lmove-dst").arg("LEFT").arg("RIGHT").query(&mut con).unwrap();
+        assert_eq!(value, "c");
+        assert_eq!(items(&mut con, "list-lmove-dst"), vec!["a", "c"]);
+
+        // 源列表不存在返回 nil
+        let value: Option<String> = cmd("RPOPLPUSH").arg("list-lmove-missing").arg("list-lmove-dst").query(&mut con).unwrap();
+        assert_eq!(value, None);
+
+        // 目标键类型错误时不弹出源列表
+        let _: () = con.set("list-lmove-string", "x").unwrap();
+        let result: RedisResult<String> = cmd("LMOVE").arg("list-lmove-src").arg("list-lmove-string").arg("LEFT").arg("LEFT").query(&mut con);
+        assert!(result.is_err());
+        assert_eq!(items(&mut con, "list-lmove-src"), vec!["b"]);
+    }
+
+    #[test]
+    fn test_lmpop() {
+        let mut con = setup();
+        reset(&mut con, "list-lmpop-a", &[]);
+        reset(&mut con, "list-lmpop-b", &["1", "2", "3"]);
+
+        let popped: Option<(String, Vec<String>)> = cmd("LMPOP").arg(2).arg("list-lmpop-a").arg("list-lmpop-b").arg("RIGHT").arg("COUNT").arg(2).query(&mut con).unwrap();
+        assert_eq!(popped, Some(("list-lmpop-b".to_string(), vec!["3".to_string(), "2".to_string()])));
+        let popped: Option<(String, Vec<String>)> = cmd("LMPOP").arg(2).arg("list-lmpop-a").arg("list-lmpop-b").arg("LEFT").query(&mut con).unwrap();
+        assert_eq!(popped, Some(("list-lmpop-b".to_string(), vec!["1".to_string()])));
+        let popped: Option<(String, Vec<String>)> = cmd("LMPOP").arg(2).arg("list-lmpop-a").arg("list-lmpop-b").arg("LEFT").query(&mut con).unwrap();
+        assert_eq!(popped, None);
+
+        let result: RedisResult<Option<(String, Vec<String>)>> = cmd("LMPOP").arg(0).arg("list-lmpop-a").arg("LEFT").query(&mut con);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::time::Duration;
+
+    use redis::{Client, Commands, Connection, RedisResult};
+
+    /// 使用指定内存配置启动的独立服务器，测试结束时关闭
+    struct TestServer {
+        child: Child,
+        dir: PathBuf,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(port: u16, maxmemory: &str, policy: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("rudis-maxmemory-{}", port));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(&dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(["--maxmemory", maxmemory, "--maxmemory-policy", policy])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, dir, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn info_field(con: &mut Connection, field: &str) -> String {
+        let info: String = redis::cmd("INFO").arg("all").query(con).unwrap();
+        info.lines()
+            .find_map(|line| line.strip_prefix(&format!("{}:", field)))
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn value() -> Vec<u8> {
+        vec![b'x'; 10 * 1024]
+    }
+
+    #[test]
+    fn test_noeviction_rejects_writes() {
+        let server = TestServer::start(16391, "200kb", "noeviction");
+        let mut con = server.connect();
+
+        let mut rejected = None;
+        for i in 0..100 {
+            let result: RedisResult<()> = con.set(format!("noeviction-{}", i), value());
+            if let Err(e) = result {
+                rejected = Some((i, e));
+                break;
+            }
+        }
+        let (written, error) = rejected.expect("writes should be rejected once maxmemory is reached");
+        assert!(written > 5);
+        assert!(error.to_string().contains("command not allowed when used memory"), "{}", error);
+
+        // 读取与删除不受影响，删除后可以继续写入
+        let result: Vec<u8> = con.get("noeviction-0").unwrap();
+        assert_eq!(result.len(), 10 * 1024);
+        let _: () = con.del("noeviction-0").unwrap();
+        let _: () = con.del("noeviction-1").unwrap();
+        let _: () = con.set("noeviction-after-del", "small").unwrap();
+        assert_eq!(info_field(&mut con, "evicted_keys"), "0");
+    }
+
+    #[test]
+    fn test_allkeys_lru_keeps_recently_used_keys() {
+        let server = TestServer::start(16392, "300kb", "allkeys-lru");
+        let mut con = server.connect();
+
+        let _: () = con.set("lru-hot", value()).unwrap();
+        for i in 0..100 {
+            let _: () = con.set(format!("lru-{}", i), value()).unwrap();
+            let _: Vec<u8> = con.get("lru-hot").unwrap();
+        }
+
+        let exists: bool = con.exists("lru-hot").unwrap();
+        assert!(exists);
+        let dbsize: usize = redis::cmd("DBSIZE").query(&mut con).unwrap();
+        assert!(dbsize < 40, "dbsize = {}", dbsize);
+
+        let used: usize = info_field(&mut con, "used_memory").parse().unwrap();
+        assert!(used <= 300 * 1024, "used_memory = {}", used);
+        let evicted: usize = info_field(&mut con, "evicted_keys").parse().unwrap();
+        assert!(evicted > 60);
+        assert_eq!(info_field(&mut con, "maxmemory_policy"), "allkeys-lru");
+    }
+
+    #[test]
+    fn test_allkeys_lfu_keeps_frequently_used_keys() {
+        let server = TestServer::start(16393, "300kb", "allkeys-lfu");
+        let mut con = server.connect();
+
+        let _: () = con.set("lfu-hot", value()).unwrap();
+        for _ in 0..100 {
+            let _: Vec<u8> = con.get("lfu-hot").unwrap();
+        }
+        for i in 0..100 {
+            let _: () = con.set(format!("lfu-{}", i), value()).unwrap();
+        }
+
+        let exists: bool = con.exists("lfu-hot").unwrap();
+        assert!(exists);
+        let dbsize: usize = redis::cmd("DBSIZE").query(&mut con).unwrap();
+        assert!(dbsize < 40, "dbsize = {}", dbsize);
+    }
+
+    #[test]
+    fn test_volatile_ttl_only_evicts_keys_with_expire() {
+        let server = TestServer::start(16394, "300kb", "volatile-ttl");
+        let mut con = server.connect();
+
+        for i in 0..10 {
+            let _: () = con.set(format!("persistent-{}", i), value()).unwrap();
+        }
+        for i in 0..40 {
+            let _: () = con.set_ex(format!("volatile-{}", i), value(), 1000 + i).unwrap();
+        }
+
+        for i in 0..10 {
+            let exists: bool = con.exists(format!("persistent-{}", i)).unwrap();
+            assert!(exists);
+        }
+        // 剩余生存时间最长的键最后被淘汰
+        let exists: bool = con.exists("volatile-39").unwrap();
+        assert!(exists);
+        let exists: bool = con.exists("volatile-0").unwrap();
+        assert!(!exists);
+
+        // 设置了过期时间的键不足以释放内存时，写入被拒绝
+        let mut rejected = false;
+        for i in 0..50 {
+            let result: RedisResult<()> = con.set(format!("persistent-extra-{}", i), value());
+            if result.is_err() {
+                rejected = true;
+                break;
+            }
+        }
+        assert!(rejected);
+        for i in 0..10 {
+            let exists: bool = con.exists(format!("persistent-{}", i)).unwrap();
+            assert!(exists);
+        }
+    }
+
+    #[test]
+    fn test_allkeys_random_across_databases() {
+        let server = TestServer::start(16395, "300kb", "allkeys-random");
+        let mut con = server.connect();
+
+        for i in 0..30 {
+            let _: () = con.set(format!("random-db0-{}", i), value()).unwrap();
+        }
+        let _: () = redis::cmd("SELECT").arg(1).query(&mut con).unwrap();
+        for i in 0..30 {
+            let _: () = con.set(format!("random-db1-{}", i), value()).unwrap();
+        }
+
+        let used: usize = info_field(&mut con, "used_memory").parse().unwrap();
+        assert!(used <= 300 * 1024, "used_memory = {}", used);
+        let evicted: usize = info_field(&mut con, "evicted_keys").parse().unwrap();
+        assert!(evicted >= 30);
+    }
+
+    /// INFO memory 按数据集的实际大小统计，删除后回落
+    #[test]
+    fn test_info_memory_tracks_dataset() {
+        let server = TestServer::start(16396, "0", "noeviction");
+        let mut con = server.connect();
+
+        let before: usize = info_field(&mut con, "used_memory").parse().unwrap();
+        for i in 0..10 {
+            let _: () = con.rpush("memory-list", vec![b'y'; 1024 * (i + 1)]).unwrap();
+        }
+        let after: usize = info_field(&mut con, "used_memory").parse().unwrap();
+        assert!(after >= before + 50 * 1024, "before = {}, after = {}", before, after);
+
+        let _: () = con.del("memory-list").unwrap();
+        let deleted: usize = info_field(&mut con, "used_memory").parse().unwrap();
+        assert_eq!(deleted, before);
+        let peak: usize = info_field(&mut con, "used_memory_peak").parse().unwrap();
+        assert!(peak >= after);
+        assert_eq!(info_field(&mut con, "maxmemory"), "0");
+    }
+}
@@ -159,4 +159,31 @@ mod tests {
         // 清理
         let _: () = con.del("invalid-db-key").unwrap();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_move_list_with_ttl() {
+        let mut con = setup();
+        let _: () = select_db(&mut con, 1).unwrap();
+        let _: () = con.del("move-list-key").unwrap();
+        let _: () = select_db(&mut con, 0).unwrap();
+        let _: () = con.del("move-list-key").unwrap();
+
+        // 列表等非字符串类型连同过期时间一起移动
+        let _: () = con.rpush("move-list-key", &["a", "b"]).unwrap();
+        let _: () = con.expire("move-list-key", 100).unwrap();
+        let moved: i32 = move_key(&mut con, "move-list-key", 1).unwrap();
+        assert_eq!(moved, 1);
+        let exists: bool = con.exists("move-list-key").unwrap();
+        assert_eq!(exists, false);
+
+        let _: () = select_db(&mut con, 1).unwrap();
+        let items: Vec<String> = con.lrange("move-list-key", 0, -1).unwrap();
+        assert_eq!(items, vec!["a", "b"]);
+        let ttl: i64 = con.ttl("move-list-key").unwrap();
+        assert!(ttl > 0 && ttl <= 100);
+
+        // 清理
+        let _: () = con.del("move-list-key").unwrap();
+        let _: () = select_db(&mut con, 0).unwrap();
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use redis::{cmd, Client, Connection};
+
+fn setup() -> Connection {
+    let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+    client.get_connection().unwrap()
+}
+
+#[test]
+fn test_subscribe_and_publish() {
+    let mut subscriber = setup();
+    let mut publisher = setup();
+
+    let mut pubsub = subscriber.as_pubsub();
+    pubsub.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    pubsub.subscribe("test_pubsub_channel").unwrap();
+
+    let receivers: i64 = cmd("PUBLISH")
+        .arg("test_pubsub_channel")
+        .arg("hello")
+        .query(&mut publisher)
+        .unwrap();
+    assert_eq!(receivers, 1);
+
+    let msg = pubsub.get_message().unwrap();
+    assert_eq!(msg.get_channel_name(), "test_pubsub_channel");
+    let payload: String = msg.get_payload().unwrap();
+    assert_eq!(payload, "hello");
+
+    pubsub.unsubscribe("test_pubsub_channel").unwrap();
+
+    // 退订后不再有接收者
+    let receivers: i64 = cmd("PUBLISH")
+        .arg("test_pubsub_channel")
+        .arg("ignored")
+        .query(&mut publisher)
+        .unwrap();
+    assert_eq!(receivers, 0);
+}
+
+#[test]
+fn test_psubscribe_pattern_matching() {
+    let mut subscriber = setup();
+    let mut publisher = setup();
+
+    let mut pubsub = subscriber.as_pubsub();
+    pubsub.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    pubsub.psubscribe("test_pubsub.news.*").unwrap();
+
+    // '.' 必须按字面量匹配
+    let receivers: i64 = cmd("PUBLISH")
+        .arg("test_pubsub_news_sport")
+        .arg("ignored")
+        .query(&mut publisher)
+        .unwrap();
+    assert_eq!(receivers, 0);
+
+    let receivers: i64 = cmd("PUBLISH")
+        .arg("test_pubsub.news.sport")
+        .arg("goal")
+        .query(&mut publisher)
+        .unwrap();
+    assert_eq!(receivers, 1);
+
+    let msg = pubsub.get_message().unwrap();
+    assert_eq!(msg.get_channel_name(), "test_pubsub.news.sport");
+    let pattern: String = msg.get_pattern().unwrap();
+    assert_eq!(pattern, "test_pubsub.news.*");
+    let payload: String = msg.get_payload().unwrap();
+    assert_eq!(payload, "goal");
+}
+
+#[test]
+fn test_pubsub_introspection() {
+    let mut subscriber = setup();
+    let mut con = setup();
+
+    let mut pubsub = subscriber.as_pubsub();
+    pubsub.subscribe("test_pubsub_numsub_a").unwrap();
+    pubsub.psubscribe("test_pubsub_numpat_*").unwrap();
+
+    let channels: Vec<String> = cmd("PUBSUB")
+        .arg("CHANNELS")
+        .arg("test_pubsub_numsub_*")
+        .query(&mut con)
+        .unwrap();
+    assert_eq!(channels, vec!["test_pubsub_numsub_a".to_string()]);
+
+    let numsub: Vec<(String, i64)> = cmd("PUBSUB")
+        .arg("NUMSUB")
+        .arg("test_pubsub_numsub_a")
+        .arg("test_pubsub_numsub_b")
+        .query(&mut con)
+        .unwrap();
+    assert_eq!(numsub, vec![
+        ("test_pubsub_numsub_a".to_string(), 1),
+        ("test_pubsub_numsub_b".to_string(), 0),
+    ]);
+
+    let numpat: i64 = cmd("PUBSUB").arg("NUMPAT").query(&mut con).unwrap();
+    assert!(numpat >= 1);
+}
+
+#[test]
+fn test_subscribed_mode_rejects_regular_commands() {
+    let mut con = setup();
+
+    let _: () = cmd("SUBSCRIBE").arg("test_pubsub_restricted").query(&mut con).unwrap_or(());
+    let result: redis::RedisResult<String> = cmd("GET").arg("test_pubsub_restricted_key").query(&mut con);
+    assert!(result.is_err());
+}
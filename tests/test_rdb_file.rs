@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use redis::{cmd, Client, Connection};
+    use rudis_server::persistence::rdb_file::RdbFile;
+    use rudis_server::tools::crc64::crc64;
+
+    /// 使用独立数据目录启动的服务器
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16) -> Self {
+            let child = spawn(dir, port);
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn spawn(dir: &PathBuf, port: u16) -> Child {
+        Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+            .current_dir(dir)
+            .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string(), "--save", "1,1"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 启动服务器写入一个键，等待自动保存生成 RDB 文件
+    fn write_snapshot(dir: &PathBuf, port: u16, value: &str) {
+        let server = TestServer::start(dir, port);
+        let mut con = server.connect();
+        let _: () = cmd("SET").arg("key").arg(value).query(&mut con).unwrap();
+        thread::sleep(Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn test_crc64_check_value() {
+        assert_eq!(crc64(b"123456789"), 0xe9c6d914c4b8d9ca);
+        assert_eq!(crc64(b""), 0);
+    }
+
+    #[test]
+    fn test_rdb_bytes_detect_corruption() {
+        let bytes = RdbFile::new("dump.rdb").serialize().unwrap();
+        assert!(bytes.starts_with(b"RUDIS0001"));
+        assert!(RdbFile::from_bytes(&bytes).is_ok());
+
+        // 截断
+        assert!(RdbFile::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(RdbFile::from_bytes(&bytes[..6]).is_err());
+
+        // 任意一个字节被修改
+        for index in 0..bytes.len() {
+            let mut corrupted = bytes.clone();
+            corrupted[index] ^= 0x01;
+            assert!(RdbFile::from_bytes(&corrupted).is_err(), "byte {} flipped", index);
+        }
+    }
+
+    #[test]
+    fn test_save_keeps_backup_and_no_temp_files() {
+        let dir = temp_dir("rudis-rdb-backup");
+        write_snapshot(&dir, 16409, "first");
+        write_snapshot(&dir, 16409, "second");
+
+        let data = dir.join("data");
+        assert!(data.join("dump.rdb").exists());
+        assert!(data.join("dump.rdb.bak").exists());
+        let leftovers: Vec<_> = std::fs::read_dir(&data)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("temp-"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        let server = TestServer::start(&dir, 16409);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "second");
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_corrupted_rdb_falls_back_to_backup() {
+        let dir = temp_dir("rudis-rdb-fallback");
+        write_snapshot(&dir, 16410, "first");
+        write_snapshot(&dir, 16410, "second");
+
+        // 模拟保存过程中被杀死留下的截断文件
+        let path = dir.join("data/dump.rdb");
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+        let server = TestServer::start(&dir, 16410);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "first");
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_corrupted_rdb_without_backup_refuses_to_start() {
+        let dir = temp_dir("rudis-rdb-corrupted");
+        write_snapshot(&dir, 16411, "value");
+
+        let path = dir.join("data/dump.rdb");
+        let mut bytes = std::fs::read(&path).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+
+        let mut child = spawn(&dir, 16411);
+        let status = child.wait().unwrap();
+        assert!(!status.success());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
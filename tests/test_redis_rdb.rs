@@ -0,0 +1,295 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use redis::{cmd, Client, Connection, RedisResult};
+    use rudis_server::persistence::redis_rdb::{decode, encode};
+    use rudis_server::store::db::{DatabaseSnapshot, Structure};
+    use rudis_server::store::sorted_set::SortedSet;
+    use rudis_server::tools::crc64::crc64;
+
+    /// 使用独立数据目录启动的服务器
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16, args: &[&str]) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 按 Redis 的格式拼装文件：头部 + 内容 + EOF + CRC64
+    fn redis_file(body: &[u8]) -> Vec<u8> {
+        let mut bytes = b"REDIS0011".to_vec();
+        bytes.extend_from_slice(body);
+        bytes.push(0xff);
+        let checksum = crc64(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    fn string(bytes: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![bytes.len() as u8];
+        encoded.extend_from_slice(bytes);
+        encoded
+    }
+
+    fn entry(key: &str, value_type: u8, value: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![value_type];
+        encoded.extend(string(key.as_bytes()));
+        encoded.extend_from_slice(value);
+        encoded
+    }
+
+    fn sample_snapshot() -> DatabaseSnapshot {
+        let mut snapshot = DatabaseSnapshot::default();
+        snapshot.records.insert(b"string".to_vec(), Structure::String(b"value".to_vec()));
+        snapshot.records.insert(b"list".to_vec(), Structure::List(vec![b"a".to_vec(), vec![0, 255], b"c".repeat(300)]));
+        snapshot.records.insert(b"set".to_vec(), Structure::Set(HashSet::from([b"x".to_vec(), b"y".to_vec()])));
+        snapshot.records.insert(b"hash".to_vec(), Structure::Hash(HashMap::from([(b"field".to_vec(), b"1".to_vec())])));
+        let mut zset = SortedSet::new();
+        zset.add(b"one".to_vec(), 1.0);
+        zset.add(b"inf".to_vec(), f64::INFINITY);
+        zset.add(b"neg".to_vec(), -2.5);
+        snapshot.records.insert(b"zset".to_vec(), Structure::SortedSet(zset));
+        snapshot.records.insert(b"json".to_vec(), Structure::Json("{}".to_string()));
+        snapshot.records.insert(b"expires".to_vec(), Structure::String(b"soon".to_vec()));
+        snapshot.expire_records.insert(b"expires".to_vec(), SystemTime::now() + Duration::from_secs(3600));
+        snapshot.records.insert(b"expired".to_vec(), Structure::String(b"gone".to_vec()));
+        snapshot.expire_records.insert(b"expired".to_vec(), SystemTime::now() - Duration::from_secs(1));
+        snapshot
+    }
+
+    fn assert_sample(snapshot: &DatabaseSnapshot) {
+        assert_eq!(snapshot.records.len(), 6);
+        assert!(matches!(snapshot.records.get(b"string".as_slice()), Some(Structure::String(value)) if value == b"value"));
+        assert!(matches!(snapshot.records.get(b"list".as_slice()), Some(Structure::List(items)) if items.len() == 3 && items[1] == vec![0, 255] && items[2].len() == 300));
+        assert!(matches!(snapshot.records.get(b"set".as_slice()), Some(Structure::Set(members)) if members.len() == 2 && members.contains(b"y".as_slice())));
+        assert!(matches!(snapshot.records.get(b"hash".as_slice()), Some(Structure::Hash(fields)) if fields.get(b"field".as_slice()) == Some(&b"1".to_vec())));
+        match snapshot.records.get(b"zset".as_slice()) {
+            Some(Structure::SortedSet(zset)) => {
+                assert_eq!(zset.get_score(b"inf"), Some(f64::INFINITY));
+                assert_eq!(zset.get_score(b"neg"), Some(-2.5));
+            }
+            _ => panic!("zset is missing"),
+        }
+        assert!(!snapshot.records.contains_key(b"json".as_slice()));
+        assert!(!snapshot.records.contains_key(b"expired".as_slice()));
+        let expire_at = snapshot.expire_records.get(b"expires".as_slice()).unwrap();
+        assert!(*expire_at > SystemTime::now() + Duration::from_secs(3500));
+        assert_eq!(snapshot.expire_records.len(), 1);
+    }
+
+    #[test]
+    fn test_encode_and_decode() {
+        let databases = HashMap::from([(0, sample_snapshot()), (3, sample_snapshot())]);
+        let bytes = encode(&databases);
+        assert!(bytes.starts_with(b"REDIS0009"));
+
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_sample(&decoded[&0]);
+        assert_sample(&decoded[&3]);
+
+        let mut corrupted = bytes.clone();
+        corrupted[20] ^= 0x01;
+        assert!(decode(&corrupted).is_err());
+        assert!(decode(&bytes[..bytes.len() - 9]).is_err());
+    }
+
+    #[test]
+    fn test_decode_redis_encodings() {
+        let mut body = Vec::new();
+        // AUX 字段
+        body.push(0xfa);
+        body.extend(string(b"redis-ver"));
+        body.extend(string(b"7.2.4"));
+        body.extend([0xfe, 0x00, 0xfb, 0x09, 0x01]);
+
+        // 整数编码的字符串
+        body.extend(entry("int", 0, &[0xc1, 0x39, 0x30]));
+        // LZF 压缩的字符串：20 个 'a'
+        body.extend(entry("lzf", 0, &[0xc3, 0x05, 0x14, 0x00, b'a', 0xe0, 0x0a, 0x00]));
+        // intset：1, -2, 300
+        let intset = [2, 0, 0, 0, 3, 0, 0, 0, 1, 0, 0xfe, 0xff, 0x2c, 0x01];
+        body.extend(entry("intset", 11, &string(&intset)));
+        // listpack 哈希 {a: 1}
+        let listpack = [0x0c, 0, 0, 0, 2, 0, 0x81, b'a', 0x02, 0x01, 0x01, 0xff];
+        body.extend(entry("lphash", 16, &string(&listpack)));
+        // quicklist 2：一个 listpack 节点（"x"、-100、5000）与一个普通节点
+        let listpack = [0x11, 0, 0, 0, 3, 0, 0x81, b'x', 0x02, 0xdf, 0x9c, 0x02, 0xf1, 0x88, 0x13, 0x03, 0xff];
+        let mut quicklist = vec![0x02, 0x02];
+        quicklist.extend(string(&listpack));
+        quicklist.push(0x01);
+        quicklist.extend(string(b"plain"));
+        body.extend(entry("list", 18, &quicklist));
+        // ziplist 有序集合 {m: 1.5, n: 7}
+        let ziplist = [0x11, 0, 0, 0, 0x0c, 0, 0, 0, 4, 0, 0x00, 0x01, b'm', 0x03, 0x03, b'1', b'.', b'5', 0x05, 0x01, b'n', 0x03, 0xf8, 0xff];
+        body.extend(entry("zset", 12, &string(&ziplist)));
+        // listpack 有序集合 {p: 2}
+        let listpack = [0x0c, 0, 0, 0, 2, 0, 0x81, b'p', 0x02, 0x02, 0x01, 0xff];
+        body.extend(entry("lpzset", 17, &string(&listpack)));
+        // 带过期时间的键：已过期与未过期
+        body.push(0xfc);
+        body.extend_from_slice(&1000u64.to_le_bytes());
+        body.extend(entry("expired", 0, &string(b"v")));
+        let future = (SystemTime::now() + Duration::from_secs(3600)).duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        body.push(0xfc);
+        body.extend_from_slice(&future.to_le_bytes());
+        body.extend(entry("volatile", 0, &string(b"v")));
+        // 另一个数据库，使用 14 位长度编码的字符串
+        body.extend([0xfe, 0x02]);
+        let mut long = vec![0x41, 0x2c];
+        long.extend(b"z".repeat(300));
+        body.extend(entry("long", 0, &long));
+
+        let databases = decode(&redis_file(&body)).unwrap();
+        let db = &databases[&0];
+        assert!(matches!(db.records.get(b"int".as_slice()), Some(Structure::String(value)) if value == b"12345"));
+        assert!(matches!(db.records.get(b"lzf".as_slice()), Some(Structure::String(value)) if *value == b"a".repeat(20)));
+        let expected: HashSet<Vec<u8>> = ["1", "-2", "300"].iter().map(|value| value.as_bytes().to_vec()).collect();
+        assert!(matches!(db.records.get(b"intset".as_slice()), Some(Structure::Set(members)) if *members == expected));
+        assert!(matches!(db.records.get(b"lphash".as_slice()), Some(Structure::Hash(fields)) if fields.get(b"a".as_slice()) == Some(&b"1".to_vec())));
+        let expected = vec![b"x".to_vec(), b"-100".to_vec(), b"5000".to_vec(), b"plain".to_vec()];
+        assert!(matches!(db.records.get(b"list".as_slice()), Some(Structure::List(items)) if *items == expected));
+        match db.records.get(b"zset".as_slice()) {
+            Some(Structure::SortedSet(zset)) => {
+                assert_eq!(zset.get_score(b"m"), Some(1.5));
+                assert_eq!(zset.get_score(b"n"), Some(7.0));
+            }
+            _ => panic!("zset is missing"),
+        }
+        assert!(matches!(db.records.get(b"lpzset".as_slice()), Some(Structure::SortedSet(zset)) if zset.get_score(b"p") == Some(2.0)));
+        assert!(!db.records.contains_key(b"expired".as_slice()));
+        assert!(db.expire_records.contains_key(b"volatile".as_slice()));
+        assert!(matches!(databases[&2].records.get(b"long".as_slice()), Some(Structure::String(value)) if value.len() == 300));
+
+        // 校验和为 0 表示未开启校验
+        let mut unchecked = redis_file(&body);
+        let len = unchecked.len();
+        unchecked[len - 8..].fill(0);
+        assert!(decode(&unchecked).is_ok());
+
+        // 不支持的类型（流）
+        let stream = redis_file(&entry("stream", 15, &[0x00]));
+        match decode(&stream) {
+            Err(e) => assert!(e.to_string().contains("Unsupported RDB object type 15")),
+            Ok(_) => panic!("stream should not be decoded"),
+        }
+        assert!(decode(b"REDIS0013\xff").is_err());
+    }
+
+    #[test]
+    fn test_import_and_export_commands() {
+        let dir = temp_dir("rudis-redis-rdb");
+        let mut snapshot = DatabaseSnapshot::default();
+        snapshot.records.insert(b"migrated".to_vec(), Structure::String(b"from-redis".to_vec()));
+        snapshot.records.insert(b"numbers".to_vec(), Structure::List(vec![b"1".to_vec(), b"2".to_vec()]));
+        std::fs::write(dir.join("redis.rdb"), encode(&HashMap::from([(1, snapshot)]))).unwrap();
+
+        // 启动时加载
+        let server = TestServer::start(&dir, 16414, &["--import-rdb", "redis.rdb"]);
+        let mut con = server.connect();
+        let _: () = cmd("SELECT").arg(1).query(&mut con).unwrap();
+        let value: String = cmd("GET").arg("migrated").query(&mut con).unwrap();
+        assert_eq!(value, "from-redis");
+        let len: i64 = cmd("LLEN").arg("numbers").query(&mut con).unwrap();
+        assert_eq!(len, 2);
+
+        // 导出后重新解析
+        let _: () = cmd("SADD").arg("members").arg("a").arg("b").query(&mut con).unwrap();
+        let _: () = cmd("DEBUG").arg("RDB-EXPORT").arg("export/out.rdb").query(&mut con).unwrap();
+        let databases = decode(&std::fs::read(dir.join("export/out.rdb")).unwrap()).unwrap();
+        assert_eq!(databases[&1].records.len(), 3);
+        assert!(matches!(databases[&1].records.get(b"members".as_slice()), Some(Structure::Set(members)) if members.len() == 2));
+
+        // 运行时导入，替换所有数据
+        let mut snapshot = DatabaseSnapshot::default();
+        snapshot.records.insert(b"imported".to_vec(), Structure::String(b"yes".to_vec()));
+        std::fs::write(dir.join("second.rdb"), encode(&HashMap::from([(0, snapshot)]))).unwrap();
+        let _: () = cmd("DEBUG").arg("RDB-IMPORT").arg("second.rdb").query(&mut con).unwrap();
+        let exists: i64 = cmd("EXISTS").arg("migrated").query(&mut con).unwrap();
+        assert_eq!(exists, 0);
+        let _: () = cmd("SELECT").arg(0).query(&mut con).unwrap();
+        let value: String = cmd("GET").arg("imported").query(&mut con).unwrap();
+        assert_eq!(value, "yes");
+
+        let result: RedisResult<()> = cmd("DEBUG").arg("RDB-IMPORT").arg("missing.rdb").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Error trying to load the RDB dump"));
+        let result: RedisResult<()> = cmd("DEBUG").arg("NOPE").query(&mut con);
+        assert!(result.is_err());
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_import_rewrites_aof() {
+        let dir = temp_dir("rudis-redis-rdb-aof");
+        let mut snapshot = DatabaseSnapshot::default();
+        snapshot.records.insert(b"key".to_vec(), Structure::String(b"value".to_vec()));
+        std::fs::write(dir.join("redis.rdb"), encode(&HashMap::from([(0, snapshot)]))).unwrap();
+        {
+            let server = TestServer::start(&dir, 16415, &["--appendonly", "yes"]);
+            let mut con = server.connect();
+            let _: () = cmd("SET").arg("old").arg("1").query(&mut con).unwrap();
+            let _: () = cmd("DEBUG").arg("RDB-IMPORT").arg("redis.rdb").query(&mut con).unwrap();
+            for _ in 0..100 {
+                let info: String = cmd("INFO").arg("persistence").query(&mut con).unwrap();
+                if info.contains("aof_rewrites:1") {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+
+        // 重启后从 AOF 恢复导入的数据
+        let server = TestServer::start(&dir, 16415, &["--appendonly", "yes"]);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "value");
+        let exists: i64 = cmd("EXISTS").arg("old").query(&mut con).unwrap();
+        assert_eq!(exists, 0);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,507 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use redis::{cmd, Client, Connection};
+    use rudis_server::persistence::redis_rdb::{decode, encode};
+    use rudis_server::store::db::{DatabaseSnapshot, Structure};
+
+    /// 使用独立数据目录启动的服务器
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16, args: &[&str]) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 在超时之前反复检查条件
+    fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    fn resp(args: &[&str]) -> Vec<u8> {
+        let mut bytes = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            bytes.extend(format!("${}\r\n{}\r\n", arg.len(), arg).into_bytes());
+        }
+        bytes
+    }
+
+    fn read_line(reader: &mut BufReader<TcpStream>) -> String {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        line.trim_end_matches("\r\n").to_string()
+    }
+
+    fn read_bytes(reader: &mut BufReader<TcpStream>, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0; len];
+        reader.read_exact(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// 读取一条 RESP 数组形式的命令
+    fn read_command(reader: &mut BufReader<TcpStream>) -> Vec<String> {
+        let header = read_line(reader);
+        let count: usize = header.strip_prefix('*').unwrap().parse().unwrap();
+        (0..count)
+            .map(|_| {
+                let len: usize = read_line(reader).strip_prefix('$').unwrap().parse().unwrap();
+                let value = String::from_utf8(read_bytes(reader, len)).unwrap();
+                read_bytes(reader, 2);
+                value
+            })
+            .collect()
+    }
+
+    fn info_field(con: &mut Connection, field: &str) -> String {
+        let info: String = cmd("INFO").arg("replication").query(con).unwrap();
+        info.lines()
+            .find_map(|line| line.strip_prefix(&format!("{}:", field)))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_full_resync_handshake() {
+        let dir = temp_dir("rudis-test-psync-handshake");
+        let master = TestServer::start(&dir, 16416, &[]);
+        let mut con = master.connect();
+        let _: () = cmd("SET").arg("before").arg("sync").query(&mut con).unwrap();
+
+        // 按 Redis 从节点的方式握手
+        let stream = TcpStream::connect(("127.0.0.1", 16416)).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        writer.write_all(&resp(&["PING"])).unwrap();
+        assert_eq!(read_line(&mut reader), "+PONG");
+        writer.write_all(&resp(&["REPLCONF", "listening-port", "6380"])).unwrap();
+        assert_eq!(read_line(&mut reader), "+OK");
+        writer.write_all(&resp(&["REPLCONF", "capa", "eof", "capa", "psync2"])).unwrap();
+        assert_eq!(read_line(&mut reader), "+OK");
+        writer.write_all(&resp(&["PSYNC", "?", "-1"])).unwrap();
+
+        let reply = read_line(&mut reader);
+        let parts: Vec<&str> = reply.split(' ').collect();
+        assert_eq!(parts[0], "+FULLRESYNC");
+        assert_eq!(parts[1].len(), 40);
+        assert!(parts[1].chars().all(|c| c.is_ascii_hexdigit()));
+        let offset: u64 = parts[2].parse().unwrap();
+
+        // 快照为 Redis RDB 格式，以 $<len> 发送，末尾没有 CRLF
+        let len: usize = read_line(&mut reader).strip_prefix('$').unwrap().parse().unwrap();
+        let payload = read_bytes(&mut reader, len);
+        assert!(payload.starts_with(b"REDIS"));
+        let databases = decode(&payload).unwrap();
+        assert!(matches!(databases[&0].records.get(b"before".as_slice()), Some(Structure::String(value)) if value == b"sync"));
+
+        // 之后的写命令进入复制流，数据库不变时只发送一次 SELECT
+        let _: () = cmd("SET").arg("after").arg("1").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("after").arg("2").query(&mut con).unwrap();
+        let mut expected = resp(&["SELECT", "0"]);
+        expected.extend(resp(&["SET", "after", "1"]));
+        expected.extend(resp(&["SET", "after", "2"]));
+        assert_eq!(read_bytes(&mut reader, expected.len()), expected);
+
+        // REPLCONF ACK 不回复
+        writer.write_all(&resp(&["REPLCONF", "ACK", &(offset + expected.len() as u64).to_string()])).unwrap();
+        assert_eq!(info_field(&mut con, "connected_slaves"), "1");
+        assert_eq!(info_field(&mut con, "master_replid"), parts[1]);
+        assert_eq!(info_field(&mut con, "master_repl_offset"), (offset + expected.len() as u64).to_string());
+
+        let _: () = cmd("SELECT").arg(2).query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("other").arg("db").query(&mut con).unwrap();
+        let mut expected = resp(&["SELECT", "2"]);
+        expected.extend(resp(&["SET", "other", "db"]));
+        assert_eq!(read_bytes(&mut reader, expected.len()), expected);
+
+        // 断开后不再计入从节点
+        drop(reader);
+        drop(writer);
+        let _: () = cmd("SET").arg("other").arg("again").query(&mut con).unwrap();
+        assert!(wait_until(|| info_field(&mut con, "connected_slaves") == "0"));
+    }
+
+    /// 以 Redis 从节点的方式握手并发送 PSYNC，返回连接与 PSYNC 的回复
+    fn psync(port: u16, replid: &str, offset: &str) -> (BufReader<TcpStream>, TcpStream, String) {
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        writer.write_all(&resp(&["PING"])).unwrap();
+        assert_eq!(read_line(&mut reader), "+PONG");
+        writer.write_all(&resp(&["REPLCONF", "capa", "eof", "capa", "psync2"])).unwrap();
+        assert_eq!(read_line(&mut reader), "+OK");
+        writer.write_all(&resp(&["PSYNC", replid, offset])).unwrap();
+        let reply = read_line(&mut reader);
+        (reader, writer, reply)
+    }
+
+    /// 读取全量同步的快照，返回复制 ID 与偏移量
+    fn read_full_resync(reader: &mut BufReader<TcpStream>, reply: &str) -> (String, u64) {
+        let parts: Vec<&str> = reply.split(' ').collect();
+        assert_eq!(parts[0], "+FULLRESYNC");
+        let len: usize = read_line(reader).strip_prefix('$').unwrap().parse().unwrap();
+        read_bytes(reader, len);
+        (parts[1].to_string(), parts[2].parse().unwrap())
+    }
+
+    #[test]
+    fn test_partial_resync_from_backlog() {
+        let dir = temp_dir("rudis-test-psync-backlog");
+        let master = TestServer::start(&dir, 16421, &[]);
+        let mut con = master.connect();
+        assert_eq!(info_field(&mut con, "repl_backlog_active"), "0");
+
+        let (mut reader, writer, reply) = psync(16421, "?", "-1");
+        let (replid, mut offset) = read_full_resync(&mut reader, &reply);
+        let _: () = cmd("SET").arg("a").arg("1").query(&mut con).unwrap();
+        let mut expected = resp(&["SELECT", "0"]);
+        expected.extend(resp(&["SET", "a", "1"]));
+        assert_eq!(read_bytes(&mut reader, expected.len()), expected);
+        offset += expected.len() as u64;
+        drop(reader);
+        drop(writer);
+
+        // 断线期间的写命令保存在积压缓冲区中
+        let _: () = cmd("SET").arg("b").arg("2").query(&mut con).unwrap();
+        assert!(wait_until(|| info_field(&mut con, "connected_slaves") == "0"));
+        assert_eq!(info_field(&mut con, "repl_backlog_active"), "1");
+        assert_eq!(info_field(&mut con, "repl_backlog_first_byte_offset"), "1");
+
+        let (mut reader, _writer, reply) = psync(16421, &replid, &(offset + 1).to_string());
+        assert_eq!(reply, format!("+CONTINUE {}", replid));
+        let missing = resp(&["SET", "b", "2"]);
+        assert_eq!(read_bytes(&mut reader, missing.len()), missing);
+        let _: () = cmd("SET").arg("c").arg("3").query(&mut con).unwrap();
+        let streamed = resp(&["SET", "c", "3"]);
+        assert_eq!(read_bytes(&mut reader, streamed.len()), streamed);
+        offset += (missing.len() + streamed.len()) as u64;
+        assert_eq!(info_field(&mut con, "master_repl_offset"), offset.to_string());
+
+        // 复制 ID 不同或偏移量超出积压缓冲区时全量同步
+        let (mut reader, _writer, reply) = psync(16421, &"0".repeat(40), &(offset + 1).to_string());
+        read_full_resync(&mut reader, &reply);
+        let (mut reader, _writer, reply) = psync(16421, &replid, &(offset + 2).to_string());
+        read_full_resync(&mut reader, &reply);
+
+        // 缓冲区只保留最后 repl-backlog-size 字节
+        let _: () = cmd("CONFIG").arg("SET").arg("repl-backlog-size").arg("16kb").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("big").arg("x".repeat(20000)).query(&mut con).unwrap();
+        assert_eq!(info_field(&mut con, "repl_backlog_histlen"), "16384");
+        let (mut reader, _writer, reply) = psync(16421, &replid, &(offset + 1).to_string());
+        read_full_resync(&mut reader, &reply);
+
+        let result: redis::RedisResult<()> = cmd("CONFIG").arg("SET").arg("repl-backlog-size").arg("1kb").query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_replica_of_rudis_master() {
+        let master_dir = temp_dir("rudis-test-replication-master");
+        let replica_dir = temp_dir("rudis-test-replication-replica");
+        let master = TestServer::start(&master_dir, 16417, &[]);
+        let mut con = master.connect();
+        let _: () = cmd("SET").arg("string").arg("value").query(&mut con).unwrap();
+        let _: () = cmd("PFADD").arg("hll").arg("a").arg("b").arg("c").query(&mut con).unwrap();
+        let _: () = cmd("SELECT").arg(3).query(&mut con).unwrap();
+        let _: () = cmd("RPUSH").arg("list").arg("x").arg("y").query(&mut con).unwrap();
+
+        let replica = TestServer::start(&replica_dir, 16418, &["--replicaof", "127.0.0.1:16417"]);
+        let mut replica_con = replica.connect();
+        assert!(wait_until(|| cmd("GET").arg("string").query::<Option<String>>(&mut replica_con).unwrap().is_some()));
+
+        // 原生格式的快照保留 HyperLogLog 等 Redis RDB 不支持的类型
+        let count: i64 = cmd("PFCOUNT").arg("hll").query(&mut replica_con).unwrap();
+        assert_eq!(count, 3);
+        let _: () = cmd("SELECT").arg(3).query(&mut replica_con).unwrap();
+        let list: Vec<String> = cmd("LRANGE").arg("list").arg(0).arg(-1).query(&mut replica_con).unwrap();
+        assert_eq!(list, vec!["x", "y"]);
+
+        // 快照之后的写命令
+        let _: () = cmd("RPUSH").arg("list").arg("z").query(&mut con).unwrap();
+        let _: () = cmd("SELECT").arg(0).query(&mut con).unwrap();
+        let _: () = cmd("DEL").arg("string").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("streamed").arg("yes").query(&mut con).unwrap();
+        assert!(wait_until(|| cmd("LLEN").arg("list").query::<i64>(&mut replica_con).unwrap() == 3));
+        let _: () = cmd("SELECT").arg(0).query(&mut replica_con).unwrap();
+        assert!(wait_until(|| cmd("GET").arg("streamed").query::<Option<String>>(&mut replica_con).unwrap().is_some()));
+        let exists: i64 = cmd("EXISTS").arg("string").query(&mut replica_con).unwrap();
+        assert_eq!(exists, 0);
+
+        assert_eq!(info_field(&mut con, "connected_slaves"), "1");
+        assert_eq!(info_field(&mut replica_con, "role"), "slave");
+    }
+
+    #[test]
+    fn test_replica_of_redis_master() {
+        // 模拟 Redis 主节点：检查握手，发送 Redis RDB 快照与复制流
+        let listener = TcpListener::bind(("127.0.0.1", 16419)).unwrap();
+        let replica_dir = temp_dir("rudis-test-replication-redis-master");
+        let replica = TestServer::start(&replica_dir, 16420, &["--replicaof", "127.0.0.1:16419"]);
+
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        assert_eq!(read_command(&mut reader), vec!["PING"]);
+        writer.write_all(b"+PONG\r\n").unwrap();
+        assert_eq!(read_command(&mut reader), vec!["REPLCONF", "listening-port", "16420"]);
+        writer.write_all(b"+OK\r\n").unwrap();
+        let capa = read_command(&mut reader);
+        assert_eq!(capa[..3], ["REPLCONF", "capa", "psync2"]);
+        writer.write_all(b"+OK\r\n").unwrap();
+        assert_eq!(read_command(&mut reader), vec!["PSYNC", "?", "-1"]);
+
+        let mut snapshot = DatabaseSnapshot::default();
+        snapshot.records.insert(b"imported".to_vec(), Structure::String(b"yes".to_vec()));
+        let payload = encode(&HashMap::from([(0, snapshot)]));
+        let mut bytes = format!("+FULLRESYNC {} 100\r\n\n${}\r\n", "a".repeat(40), payload.len()).into_bytes();
+        bytes.extend(payload);
+        writer.write_all(&bytes).unwrap();
+
+        let mut stream = resp(&["SELECT", "1"]);
+        stream.extend(resp(&["SET", "streamed", "yes"]));
+        stream.extend(resp(&["PING"]));
+        writer.write_all(&stream).unwrap();
+        writer.write_all(&resp(&["REPLCONF", "GETACK", "*"])).unwrap();
+
+        // GETACK 的回复为收到 GETACK 之前的偏移量，期间可能夹杂定期上报
+        let expected = (100 + stream.len()).to_string();
+        let mut acked = false;
+        for _ in 0..10 {
+            let command = read_command(&mut reader);
+            assert_eq!(command[..2], ["REPLCONF", "ACK"]);
+            if command[2] == expected {
+                acked = true;
+                break;
+            }
+        }
+        assert!(acked);
+
+        let mut con = replica.connect();
+        let imported: Option<String> = cmd("GET").arg("imported").query(&mut con).unwrap();
+        assert_eq!(imported.as_deref(), Some("yes"));
+        let _: () = cmd("SELECT").arg(1).query(&mut con).unwrap();
+        let streamed: Option<String> = cmd("GET").arg("streamed").query(&mut con).unwrap();
+        assert_eq!(streamed.as_deref(), Some("yes"));
+    }
+
+    #[test]
+    fn test_replica_reconnects_and_is_read_only() {
+        let master_dir = temp_dir("rudis-test-reconnect-master");
+        let replica_dir = temp_dir("rudis-test-reconnect-replica");
+        let master = TestServer::start(&master_dir, 16422, &[]);
+        // 与配置文件相同的 "host port" 写法
+        let replica = TestServer::start(&replica_dir, 16423, &["--replicaof", "127.0.0.1 16422"]);
+        let mut replica_con = replica.connect();
+        assert!(wait_until(|| info_field(&mut replica_con, "master_link_status") == "up"));
+        assert_eq!(info_field(&mut replica_con, "master_host"), "127.0.0.1");
+        assert_eq!(info_field(&mut replica_con, "master_port"), "16422");
+
+        // 只读从节点拒绝写命令，关闭 replica-read-only 后允许
+        let result: redis::RedisResult<()> = cmd("SET").arg("local").arg("1").query(&mut replica_con);
+        assert_eq!(result.unwrap_err().code(), Some("READONLY"));
+        let _: () = cmd("CONFIG").arg("SET").arg("replica-read-only").arg("no").query(&mut replica_con).unwrap();
+        let _: () = cmd("SET").arg("local").arg("1").query(&mut replica_con).unwrap();
+        let _: () = cmd("CONFIG").arg("SET").arg("replica-read-only").arg("yes").query(&mut replica_con).unwrap();
+
+        // 偏移量与主节点一致
+        let mut con = master.connect();
+        let _: () = cmd("SET").arg("first").arg("1").query(&mut con).unwrap();
+        let master_offset = info_field(&mut con, "master_repl_offset");
+        assert!(wait_until(|| info_field(&mut replica_con, "slave_repl_offset") == master_offset));
+        assert_ne!(info_field(&mut replica_con, "master_last_io_seconds_ago"), "-1");
+
+        // 主节点重启后自动重连并重新同步
+        drop(con);
+        drop(master);
+        assert!(wait_until(|| info_field(&mut replica_con, "master_link_status") == "down"));
+        assert!(info_field(&mut replica_con, "master_link_down_since_seconds").parse::<i64>().unwrap() >= 0);
+        let master = TestServer::start(&master_dir, 16422, &[]);
+        let mut con = master.connect();
+        let _: () = cmd("SET").arg("second").arg("2").query(&mut con).unwrap();
+        assert!(wait_until(|| cmd("GET").arg("second").query::<Option<String>>(&mut replica_con).unwrap().is_some()));
+        assert_eq!(info_field(&mut replica_con, "master_link_status"), "up");
+    }
+
+    #[test]
+    fn test_replicaof_command() {
+        let master_dir = temp_dir("rudis-test-replicaof-master");
+        let node_dir = temp_dir("rudis-test-replicaof-node");
+        let master = TestServer::start(&master_dir, 16424, &[]);
+        let node = TestServer::start(&node_dir, 16425, &[]);
+        let mut con = master.connect();
+        let mut node_con = node.connect();
+        let _: () = cmd("SET").arg("from-master").arg("1").query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("local").arg("1").query(&mut node_con).unwrap();
+
+        let reply: String = cmd("REPLICAOF").arg("127.0.0.1").arg(16424).query(&mut node_con).unwrap();
+        assert_eq!(reply, "OK");
+        assert!(wait_until(|| info_field(&mut node_con, "master_link_status") == "up"));
+        assert_eq!(info_field(&mut node_con, "role"), "slave");
+        assert_eq!(info_field(&mut con, "connected_slaves"), "1");
+        let value: Option<String> = cmd("GET").arg("from-master").query(&mut node_con).unwrap();
+        assert_eq!(value.as_deref(), Some("1"));
+        // 全量同步替换本地数据
+        let exists: i64 = cmd("EXISTS").arg("local").query(&mut node_con).unwrap();
+        assert_eq!(exists, 0);
+
+        let reply: String = cmd("SLAVEOF").arg("127.0.0.1").arg(16424).query(&mut node_con).unwrap();
+        assert_eq!(reply, "OK Already connected to specified master");
+        let result: redis::RedisResult<()> = cmd("REPLICAOF").arg("127.0.0.1").arg("port").query(&mut node_con);
+        assert!(result.is_err());
+
+        // 提升为主节点：保留数据，可以写入，不再接收原主节点的写命令
+        let _: () = cmd("REPLICAOF").arg("NO").arg("ONE").query(&mut node_con).unwrap();
+        assert_eq!(info_field(&mut node_con, "role"), "master");
+        assert!(wait_until(|| info_field(&mut con, "connected_slaves") == "0"));
+        let _: () = cmd("SET").arg("promoted").arg("yes").query(&mut node_con).unwrap();
+        let _: () = cmd("SET").arg("from-master").arg("2").query(&mut con).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        let value: Option<String> = cmd("GET").arg("from-master").query(&mut node_con).unwrap();
+        assert_eq!(value.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn test_wait_and_min_replicas() {
+        let master_dir = temp_dir("rudis-test-wait-master");
+        let replica_dir = temp_dir("rudis-test-wait-replica");
+        let master = TestServer::start(&master_dir, 16426, &[]);
+        let replica = TestServer::start(&replica_dir, 16427, &["--replicaof", "127.0.0.1:16426"]);
+        let mut con = master.connect();
+        let mut replica_con = replica.connect();
+        assert!(wait_until(|| info_field(&mut replica_con, "master_link_status") == "up"));
+
+        let _: () = cmd("SET").arg("key").arg("value").query(&mut con).unwrap();
+        let acked: i64 = cmd("WAIT").arg(1).arg(5000).query(&mut con).unwrap();
+        assert_eq!(acked, 1);
+        assert!(info_field(&mut con, "slave0").contains("port=16427,state=online"));
+
+        // 从节点数量不足时等到超时，返回已确认的数量
+        let started = Instant::now();
+        let acked: i64 = cmd("WAIT").arg(2).arg(300).query(&mut con).unwrap();
+        assert_eq!(acked, 1);
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        let result: redis::RedisResult<i64> = cmd("WAIT").arg(1).arg(100).query(&mut replica_con);
+        assert!(result.is_err());
+
+        // 正常的从节点不足时拒绝写命令，读命令不受影响
+        let _: () = cmd("CONFIG").arg("SET").arg("min-replicas-to-write").arg(1).query(&mut con).unwrap();
+        let _: () = cmd("SET").arg("key").arg("1").query(&mut con).unwrap();
+        assert_eq!(info_field(&mut con, "min_slaves_good_slaves"), "1");
+        let _: () = cmd("CONFIG").arg("SET").arg("min-replicas-to-write").arg(2).query(&mut con).unwrap();
+        let result: redis::RedisResult<()> = cmd("SET").arg("key").arg("2").query(&mut con);
+        assert_eq!(result.unwrap_err().code(), Some("NOREPLICAS"));
+        let value: String = cmd("GET").arg("key").query(&mut con).unwrap();
+        assert_eq!(value, "1");
+
+        let _: () = cmd("CONFIG").arg("SET").arg("min-replicas-to-write").arg(1).query(&mut con).unwrap();
+        drop(replica_con);
+        drop(replica);
+        assert!(wait_until(|| info_field(&mut con, "connected_slaves") == "0"));
+        let result: redis::RedisResult<()> = cmd("SET").arg("key").arg("3").query(&mut con);
+        assert_eq!(result.unwrap_err().code(), Some("NOREPLICAS"));
+    }
+
+    #[test]
+    fn test_wait_requests_ack() {
+        let dir = temp_dir("rudis-test-wait-getack");
+        let master = TestServer::start(&dir, 16428, &[]);
+        let (mut reader, mut writer, reply) = psync(16428, "?", "-1");
+        let (_, offset) = read_full_resync(&mut reader, &reply);
+
+        let mut con = master.connect();
+        let _: () = cmd("SET").arg("key").arg("value").query(&mut con).unwrap();
+        let mut expected = resp(&["SELECT", "0"]);
+        expected.extend(resp(&["SET", "key", "value"]));
+        assert_eq!(read_bytes(&mut reader, expected.len()), expected);
+
+        // WAIT 要求从节点立即确认，确认的偏移量达到 WAIT 之前的写命令即可
+        let waiter = thread::spawn(move || {
+            let acked: i64 = cmd("WAIT").arg(1).arg(0).query(&mut con).unwrap();
+            acked
+        });
+        assert_eq!(read_command(&mut reader), vec!["REPLCONF", "GETACK", "*"]);
+        writer.write_all(&resp(&["REPLCONF", "ACK", &(offset + expected.len() as u64).to_string()])).unwrap();
+        assert_eq!(waiter.join().unwrap(), 1);
+    }
+
+    /// 主节点淘汰的键以 DEL 传播，从节点自身不淘汰
+    #[test]
+    fn test_evictions_propagate_to_replica() {
+        let master_dir = temp_dir("rudis-test-eviction-master");
+        let replica_dir = temp_dir("rudis-test-eviction-replica");
+        let master = TestServer::start(&master_dir, 16442, &["--maxmemory", "300kb", "--maxmemory-policy", "allkeys-lru"]);
+        let replica = TestServer::start(&replica_dir, 16443, &[
+            "--replicaof", "127.0.0.1:16442", "--maxmemory", "100kb", "--maxmemory-policy", "allkeys-lru",
+        ]);
+        let mut con = master.connect();
+        let mut replica_con = replica.connect();
+        assert!(wait_until(|| info_field(&mut con, "connected_slaves") == "1"));
+
+        for i in 0..60 {
+            let _: () = cmd("SET").arg(format!("key-{}", i)).arg(vec![b'x'; 10 * 1024]).query(&mut con).unwrap();
+        }
+        let dbsize: usize = cmd("DBSIZE").query(&mut con).unwrap();
+        assert!(dbsize < 40, "dbsize = {}", dbsize);
+        assert!(wait_until(|| cmd("DBSIZE").query::<usize>(&mut replica_con).unwrap() == dbsize));
+        for i in 0..60 {
+            let key = format!("key-{}", i);
+            let on_master: bool = cmd("EXISTS").arg(&key).query(&mut con).unwrap();
+            let on_replica: bool = cmd("EXISTS").arg(&key).query(&mut replica_con).unwrap();
+            assert_eq!(on_master, on_replica, "{}", key);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    use redis::{Client, Commands, Connection};
+    use rudis_server::frame::{Frame, Protocol, DEFAULT_MAX_BULK_LEN};
+    use rudis_server::network::decoder::FrameDecoder;
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// 使用原始 TCP 连接发送命令并按 RESP3 解析回复
+    struct RawClient {
+        stream: TcpStream,
+        decoder: FrameDecoder,
+    }
+
+    impl RawClient {
+        fn connect() -> Self {
+            let stream = TcpStream::connect("127.0.0.1:6379").unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            RawClient { stream, decoder: FrameDecoder::new(DEFAULT_MAX_BULK_LEN) }
+        }
+
+        fn send(&mut self, args: &[&str]) {
+            let frame = Frame::Array(args.iter().map(|arg| Frame::BulkString(arg.as_bytes().to_vec())).collect());
+            self.stream.write_all(&frame.as_bytes()).unwrap();
+        }
+
+        fn read(&mut self) -> Frame {
+            let mut buffer = [0u8; 4096];
+            loop {
+                if let Some(frame) = self.decoder.next_frame().unwrap() {
+                    return frame;
+                }
+                let n = self.stream.read(&mut buffer).unwrap();
+                assert!(n > 0, "connection closed");
+                self.decoder.extend(&buffer[..n]);
+            }
+        }
+
+        /// 发送命令并返回回复的 RESP3 编码
+        fn query(&mut self, args: &[&str]) -> Vec<u8> {
+            self.send(args);
+            self.read().as_bytes_with_protocol(Protocol::Resp3)
+        }
+    }
+
+    #[test]
+    fn test_resp3_types_downgrade_to_resp2() {
+        let map = Frame::Map(vec![(Frame::BulkString(b"f".to_vec()), Frame::Double(1.5))]);
+        assert_eq!(map.as_bytes_with_protocol(Protocol::Resp3), b"%1\r\n$1\r\nf\r\n,1.5\r\n".to_vec());
+        assert_eq!(map.as_bytes(), b"*2\r\n$1\r\nf\r\n$3\r\n1.5\r\n".to_vec());
+
+        let cases: Vec<(Frame, &[u8], &[u8])> = vec![
+            (Frame::Set(vec![Frame::Integer(1)]), b"~1\r\n:1\r\n", b"*1\r\n:1\r\n"),
+            (Frame::Push(vec![Frame::Integer(1)]), b">1\r\n:1\r\n", b"*1\r\n:1\r\n"),
+            (Frame::Boolean(true), b"#t\r\n", b":1\r\n"),
+            (Frame::Boolean(false), b"#f\r\n", b":0\r\n"),
+            (Frame::Null, b"_\r\n", b"$-1\r\n"),
+            (Frame::NullArray, b"_\r\n", b"*-1\r\n"),
+            (Frame::Double(f64::INFINITY), b",inf\r\n", b"$3\r\ninf\r\n"),
+            (Frame::BigNumber("12345678901234567890".to_string()), b"(12345678901234567890\r\n", b"$20\r\n12345678901234567890\r\n"),
+            (Frame::Verbatim("txt".to_string(), b"hi".to_vec()), b"=6\r\ntxt:hi\r\n", b"$2\r\nhi\r\n"),
+        ];
+        for (frame, resp3, resp2) in cases {
+            assert_eq!(frame.as_bytes_with_protocol(Protocol::Resp3), resp3.to_vec());
+            assert_eq!(frame.as_bytes(), resp2.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_decode_resp3_types() {
+        let bytes = b"%2\r\n+a\r\n,-2.5\r\n#t\r\n~2\r\n_\r\n(123\r\n>1\r\n=7\r\nmkd:abc\r\n!3\r\nERR\r\n";
+        let mut decoder = FrameDecoder::new(DEFAULT_MAX_BULK_LEN);
+        decoder.extend(bytes);
+        let mut encoded = Vec::new();
+        while let Some(frame) = decoder.next_frame().unwrap() {
+            encoded.extend(frame.as_bytes_with_protocol(Protocol::Resp3));
+        }
+        // 二进制错误按简单错误重新编码，其余类型保持不变
+        assert_eq!(encoded, b"%2\r\n+a\r\n,-2.5\r\n#t\r\n~2\r\n_\r\n(123\r\n>1\r\n=7\r\nmkd:abc\r\n-ERR\r\n".to_vec());
+    }
+
+    #[test]
+    fn test_hello_negotiates_protocol() {
+        let mut client = RawClient::connect();
+
+        client.send(&["HELLO", "3"]);
+        match client.read() {
+            Frame::Map(pairs) => {
+                let fields: HashMap<String, String> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+                assert_eq!(fields["server"], "redis");
+                assert_eq!(fields["proto"], "3");
+                assert!(fields.contains_key("id"));
+                assert!(fields.contains_key("role"));
+            },
+            _ => panic!("HELLO should reply with a map"),
+        }
+
+        client.query(&["DEL", "resp3_hash", "resp3_zset", "resp3_missing"]);
+        client.query(&["HSET", "resp3_hash", "field", "value"]);
+        assert_eq!(client.query(&["HGETALL", "resp3_hash"]), b"%1\r\n$5\r\nfield\r\n$5\r\nvalue\r\n".to_vec());
+        assert_eq!(client.query(&["HGETALL", "resp3_missing"]), b"%0\r\n".to_vec());
+
+        client.query(&["ZADD", "resp3_zset", "1.5", "member"]);
+        assert_eq!(client.query(&["ZSCORE", "resp3_zset", "member"]), b",1.5\r\n".to_vec());
+        assert_eq!(client.query(&["GET", "resp3_missing"]), b"_\r\n".to_vec());
+
+        // 切换回 RESP2 后恢复普通数组
+        client.send(&["HELLO", "2"]);
+        match client.read() {
+            Frame::Array(items) => assert_eq!(items.len(), 14),
+            _ => panic!("HELLO 2 should reply with a flat array"),
+        }
+        client.send(&["ZSCORE", "resp3_zset", "member"]);
+        assert_eq!(client.read().as_bytes(), b"$3\r\n1.5\r\n".to_vec());
+    }
+
+    #[test]
+    fn test_hello_errors() {
+        let mut client = RawClient::connect();
+        client.send(&["HELLO", "4"]);
+        assert!(client.read().to_string().starts_with("NOPROTO"));
+        client.send(&["HELLO", "three"]);
+        assert!(client.read().to_string().starts_with("ERR Protocol version"));
+        client.send(&["HELLO", "3", "SETNAME"]);
+        assert!(client.read().to_string().starts_with("ERR Syntax error"));
+
+        // 协商失败时保持原协议
+        client.send(&["GET", "resp3_hello_errors_missing"]);
+        assert_eq!(client.read().as_bytes(), b"$-1\r\n".to_vec());
+    }
+
+    #[test]
+    fn test_hello_setname() {
+        let mut client = RawClient::connect();
+        client.send(&["HELLO", "3", "AUTH", "default", "ignored", "SETNAME", "resp3-client"]);
+        assert!(matches!(client.read(), Frame::Map(_)));
+        assert_eq!(client.query(&["CLIENT", "GETNAME"]), b"$12\r\nresp3-client\r\n".to_vec());
+    }
+
+    /// RESP3 连接订阅后以推送类型接收消息，并且可以继续执行普通命令
+    #[test]
+    fn test_resp3_pubsub_push_messages() {
+        let mut subscriber = RawClient::connect();
+        let mut publisher = setup();
+
+        subscriber.send(&["HELLO", "3"]);
+        subscriber.read();
+        assert_eq!(
+            subscriber.query(&["SUBSCRIBE", "resp3_channel"]),
+            b">3\r\n$9\r\nsubscribe\r\n$13\r\nresp3_channel\r\n:1\r\n".to_vec()
+        );
+        assert_eq!(subscriber.query(&["PING"]), b"+PONG\r\n".to_vec());
+
+        let receivers: i64 = redis::cmd("PUBLISH").arg("resp3_channel").arg("hi").query(&mut publisher).unwrap();
+        assert_eq!(receivers, 1);
+        assert_eq!(subscriber.read().as_bytes_with_protocol(Protocol::Resp3), b">3\r\n$7\r\nmessage\r\n$13\r\nresp3_channel\r\n$2\r\nhi\r\n".to_vec());
+    }
+
+    /// RESP2 客户端的回复格式保持不变
+    #[test]
+    fn test_resp2_clients_unchanged() {
+        let mut con = setup();
+        let _: () = con.del("resp2_hash").unwrap();
+        let _: () = con.hset("resp2_hash", "a", "1").unwrap();
+        let _: () = con.hset("resp2_hash", "b", "2").unwrap();
+        let result: HashMap<String, String> = con.hgetall("resp2_hash").unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result["b"], "2");
+
+        let _: () = con.zadd("resp2_zset", "m", 2.25).unwrap();
+        let score: String = con.zscore("resp2_zset", "m").unwrap();
+        assert_eq!(score, "2.25");
+    }
+}
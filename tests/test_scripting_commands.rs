@@ -0,0 +1,339 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use redis::{cmd, Client, Connection, RedisResult, Value};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_eval_keys_and_argv() {
+        let mut con = setup();
+        let result: Vec<String> = cmd("EVAL")
+            .arg("return {KEYS[1], KEYS[2], ARGV[1], ARGV[2]}")
+            .arg(2).arg("key1").arg("key2").arg("first").arg("second")
+            .query(&mut con).unwrap();
+        assert_eq!(result, vec!["key1", "key2", "first", "second"]);
+
+        let result: RedisResult<Value> = cmd("EVAL").arg("return 1").arg(2).arg("key1").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("greater than number of args"));
+        let result: RedisResult<Value> = cmd("EVAL").arg("return 1").arg(-1).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("negative"));
+    }
+
+    #[test]
+    fn test_eval_reply_conversion() {
+        let mut con = setup();
+        let value: i64 = cmd("EVAL").arg("return 3.99").arg(0).query(&mut con).unwrap();
+        assert_eq!(value, 3);
+        let value: i64 = cmd("EVAL").arg("return true").arg(0).query(&mut con).unwrap();
+        assert_eq!(value, 1);
+        let value: Option<String> = cmd("EVAL").arg("return false").arg(0).query(&mut con).unwrap();
+        assert_eq!(value, None);
+        // 数组在第一个 nil 处截断
+        let value: Vec<i64> = cmd("EVAL").arg("return {1, 2, nil, 4}").arg(0).query(&mut con).unwrap();
+        assert_eq!(value, vec![1, 2]);
+        let value: String = cmd("EVAL").arg("return redis.status_reply('FINE')").arg(0).query(&mut con).unwrap();
+        assert_eq!(value, "FINE");
+        let result: RedisResult<Value> = cmd("EVAL").arg("return redis.error_reply('MYERR custom failure')").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("custom failure"));
+        let value: String = cmd("EVAL").arg("return redis.sha1hex('')").arg(0).query(&mut con).unwrap();
+        assert_eq!(value, "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    }
+
+    #[test]
+    fn test_redis_call() {
+        let mut con = setup();
+        let _: () = cmd("DEL").arg("script_call").arg("script_call_list").query(&mut con).unwrap();
+
+        let value: String = cmd("EVAL")
+            .arg("redis.call('SET', KEYS[1], ARGV[1]); return redis.call('GET', KEYS[1])")
+            .arg(1).arg("script_call").arg("hello")
+            .query(&mut con).unwrap();
+        assert_eq!(value, "hello");
+
+        // 状态回复转换为 {ok = ...}，空回复转换为 false
+        let value: String = cmd("EVAL").arg("return redis.call('SET', KEYS[1], 'x')['ok']").arg(1).arg("script_call").query(&mut con).unwrap();
+        assert_eq!(value, "OK");
+        let value: i64 = cmd("EVAL").arg("if redis.call('GET', 'script_call_missing') == false then return 1 end return 0").arg(0).query(&mut con).unwrap();
+        assert_eq!(value, 1);
+
+        let value: Vec<String> = cmd("EVAL")
+            .arg("redis.call('RPUSH', KEYS[1], 'a', 'b', 'c'); return redis.call('LRANGE', KEYS[1], 0, -1)")
+            .arg(1).arg("script_call_list")
+            .query(&mut con).unwrap();
+        assert_eq!(value, vec!["a", "b", "c"]);
+
+        // 命令错误中断脚本并原样返回
+        let result: RedisResult<Value> = cmd("EVAL").arg("redis.call('INCR', KEYS[1]); return 1").arg(1).arg("script_call").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("not an integer"));
+
+        // redis.pcall 以 {err = ...} 返回错误，脚本继续执行
+        let value: String = cmd("EVAL")
+            .arg("local reply = redis.pcall('INCR', KEYS[1]); return type(reply['err'])")
+            .arg(1).arg("script_call")
+            .query(&mut con).unwrap();
+        assert_eq!(value, "string");
+
+        let result: RedisResult<Value> = cmd("EVAL").arg("return redis.call('NOSUCHCOMMAND')").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Unknown Redis command"));
+        let result: RedisResult<Value> = cmd("EVAL").arg("return redis.call('SUBSCRIBE', 'channel')").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("not allowed from script"));
+        let result: RedisResult<Value> = cmd("EVAL").arg("return redis.call('GET', {})").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("strings or integers"));
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let mut con = setup();
+        let result: RedisResult<Value> = cmd("EVAL").arg("return (").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Error compiling script"));
+
+        let result: RedisResult<Value> = cmd("EVAL").arg("error('boom')").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("boom"));
+
+        // 脚本不能读写全局变量，也不能访问文件系统
+        let result: RedisResult<Value> = cmd("EVAL").arg("counter = 1").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("create global variable"));
+        let result: RedisResult<Value> = cmd("EVAL").arg("return undefined_variable").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("nonexistent global variable"));
+        let result: RedisResult<Value> = cmd("EVAL").arg("return os.time()").arg(0).query(&mut con);
+        assert!(result.is_err());
+        let result: RedisResult<Value> = cmd("EVAL").arg("return dofile('/etc/passwd')").arg(0).query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_evalsha_and_script_cache() {
+        let mut con = setup();
+        let script = "return 'cached:' .. ARGV[1]";
+        let sha: String = cmd("SCRIPT").arg("LOAD").arg(script).query(&mut con).unwrap();
+        assert_eq!(sha.len(), 40);
+
+        let value: String = cmd("EVALSHA").arg(&sha).arg(0).arg("x").query(&mut con).unwrap();
+        assert_eq!(value, "cached:x");
+        // 摘要不区分大小写
+        let value: String = cmd("EVALSHA").arg(sha.to_uppercase()).arg(0).arg("y").query(&mut con).unwrap();
+        assert_eq!(value, "cached:y");
+
+        // EVAL 同样会缓存脚本
+        let eval_script = "return 'from eval'";
+        let _: String = cmd("EVAL").arg(eval_script).arg(0).query(&mut con).unwrap();
+        let eval_sha: String = cmd("EVAL").arg("return redis.sha1hex(ARGV[1])").arg(0).arg(eval_script).query(&mut con).unwrap();
+
+        let exists: Vec<i64> = cmd("SCRIPT").arg("EXISTS").arg(&sha).arg(&eval_sha).arg("ffffffffffffffffffffffffffffffffffffffff").query(&mut con).unwrap();
+        assert_eq!(exists, vec![1, 1, 0]);
+
+        let result: RedisResult<Value> = cmd("SCRIPT").arg("LOAD").arg("return (").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Error compiling script"));
+
+        let _: () = cmd("SCRIPT").arg("FLUSH").query(&mut con).unwrap();
+        let exists: Vec<i64> = cmd("SCRIPT").arg("EXISTS").arg(&sha).query(&mut con).unwrap();
+        assert_eq!(exists, vec![0]);
+        let result: RedisResult<Value> = cmd("EVALSHA").arg(&sha).arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("No matching script"));
+    }
+
+    #[test]
+    fn test_script_is_atomic() {
+        // 固定窗口限流：多个客户端并发执行，计数不会丢失
+        let script = "local current = redis.call('INCR', KEYS[1])
+            if current == 1 then redis.call('EXPIRE', KEYS[1], 60) end
+            if current > tonumber(ARGV[1]) then return 0 end
+            return 1";
+        let mut con = setup();
+        let _: () = cmd("DEL").arg("script_rate_limit").query(&mut con).unwrap();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                thread::spawn(move || {
+                    let mut con = setup();
+                    (0..25)
+                        .map(|_| cmd("EVAL").arg(script).arg(1).arg("script_rate_limit").arg(50).query::<i64>(&mut con).unwrap())
+                        .sum::<i64>()
+                })
+            })
+            .collect();
+        let allowed: i64 = workers.into_iter().map(|worker| worker.join().unwrap()).sum();
+
+        assert_eq!(allowed, 50);
+        let count: i64 = cmd("GET").arg("script_rate_limit").query(&mut con).unwrap();
+        assert_eq!(count, 100);
+        let ttl: i64 = cmd("TTL").arg("script_rate_limit").query(&mut con).unwrap();
+        assert!(ttl > 0);
+    }
+
+    #[test]
+    fn test_script_in_transaction() {
+        let mut con = setup();
+        let _: () = cmd("DEL").arg("script_multi").query(&mut con).unwrap();
+        let _: () = cmd("MULTI").query(&mut con).unwrap();
+        let _: () = cmd("EVAL").arg("return redis.call('INCRBY', KEYS[1], 5)").arg(1).arg("script_multi").query(&mut con).unwrap();
+        let _: () = cmd("INCR").arg("script_multi").query(&mut con).unwrap();
+        let results: Vec<i64> = cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(results, vec![5, 6]);
+    }
+
+    /// 使用独立数据目录与启动参数的服务器，用于验证持久化与脚本超时
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16, args: &[&str]) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    #[test]
+    fn test_script_effects_persist_in_aof() {
+        let dir = std::env::temp_dir().join("rudis-script-aof");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // 脚本写入的内容（包括自动生成的流 ID）在重放后保持一致，且不依赖脚本缓存
+        let script = "redis.call('SET', KEYS[1], ARGV[1])
+            redis.call('HSET', KEYS[2], 'field', ARGV[1])
+            return redis.call('XADD', KEYS[3], '*', 'value', ARGV[1])";
+        let id = {
+            let server = TestServer::start(&dir, 16399, &["--appendonly", "yes"]);
+            let mut con = server.connect();
+            let sha: String = cmd("SCRIPT").arg("LOAD").arg(script).query(&mut con).unwrap();
+            let id: String = cmd("EVALSHA").arg(&sha).arg(3).arg("string").arg("hash").arg("stream").arg("persisted").query(&mut con).unwrap();
+            let _: Option<String> = cmd("EVAL").arg("return redis.call('GET', KEYS[1])").arg(1).arg("string").query(&mut con).unwrap();
+            thread::sleep(Duration::from_millis(200));
+            id
+        };
+
+        let server = TestServer::start(&dir, 16399, &["--appendonly", "yes"]);
+        let mut con = server.connect();
+        let value: String = cmd("GET").arg("string").query(&mut con).unwrap();
+        assert_eq!(value, "persisted");
+        let value: String = cmd("HGET").arg("hash").arg("field").query(&mut con).unwrap();
+        assert_eq!(value, "persisted");
+        let entries: Vec<(String, Vec<String>)> = cmd("XRANGE").arg("stream").arg("-").arg("+").query(&mut con).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, id);
+
+        // 只读脚本不会写入 AOF
+        let aof = std::fs::read(dir.join("data/dump.aof")).unwrap();
+        let aof = String::from_utf8_lossy(&aof);
+        assert!(!aof.to_uppercase().contains("EVAL"));
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 在后台连接中执行脚本，并等待脚本超时
+    fn eval_in_background(server: &TestServer, script: &'static str) -> thread::JoinHandle<RedisResult<Value>> {
+        let mut con = server.connect();
+        let handle = thread::spawn(move || cmd("EVAL").arg(script).arg(0).query(&mut con));
+        thread::sleep(Duration::from_millis(500));
+        handle
+    }
+
+    #[test]
+    fn test_script_kill() {
+        let dir = temp_dir("rudis-script-kill");
+        let server = TestServer::start(&dir, 16435, &["--lua-time-limit", "100"]);
+        let mut con = server.connect();
+        let limit: Vec<String> = cmd("CONFIG").arg("GET").arg("lua-time-limit").query(&mut con).unwrap();
+        assert_eq!(limit, vec!["lua-time-limit", "100"]);
+
+        let result: RedisResult<String> = cmd("SCRIPT").arg("KILL").query(&mut con);
+        assert_eq!(result.unwrap_err().code(), Some("NOTBUSY"));
+
+        // 超时的脚本执行期间，其他客户端收到 BUSY 错误
+        let handle = eval_in_background(&server, "local i = 0 while true do i = i + 1 end");
+        let result: RedisResult<String> = cmd("PING").query(&mut con);
+        assert_eq!(result.unwrap_err().code(), Some("BUSY"));
+        let result: RedisResult<Value> = cmd("GET").arg("key").query(&mut con);
+        assert_eq!(result.unwrap_err().code(), Some("BUSY"));
+
+        let _: () = cmd("SCRIPT").arg("KILL").query(&mut con).unwrap();
+        let result = handle.join().unwrap();
+        assert!(result.unwrap_err().to_string().contains("Script killed by user with SCRIPT KILL"));
+        let pong: String = cmd("PING").query(&mut con).unwrap();
+        assert_eq!(pong, "PONG");
+
+        // 脚本中的 pcall 不能捕获终止脚本的错误
+        let handle = eval_in_background(&server, "while true do pcall(function() while true do end end) end");
+        let _: () = cmd("SCRIPT").arg("KILL").query(&mut con).unwrap();
+        let result = handle.join().unwrap();
+        assert!(result.unwrap_err().to_string().contains("Script killed by user with SCRIPT KILL"));
+
+        // 虚拟机被重复使用，KEYS 与 ARGV 每次重新设置，仍然不能创建全局变量
+        let result: Vec<String> = cmd("EVAL").arg("return {KEYS[1], ARGV[1]}").arg(1).arg("key").arg("arg").query(&mut con).unwrap();
+        assert_eq!(result, vec!["key", "arg"]);
+        let result: Vec<String> = cmd("EVAL").arg("return KEYS").arg(0).query(&mut con).unwrap();
+        assert!(result.is_empty());
+        let result: RedisResult<Value> = cmd("EVAL").arg("leaked = 1").arg(0).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Script attempted to create global variable"));
+
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_script_kill_after_write() {
+        let dir = temp_dir("rudis-script-unkillable");
+        let server = TestServer::start(&dir, 16436, &["--lua-time-limit", "100"]);
+        let mut con = server.connect();
+
+        // 执行过写命令的脚本不能被终止，只能等待脚本结束
+        let _handle = eval_in_background(&server, "redis.call('SET', 'written', 'yes') while true do end");
+        let result: RedisResult<String> = cmd("SCRIPT").arg("KILL").query(&mut con);
+        assert_eq!(result.unwrap_err().code(), Some("UNKILLABLE"));
+        let result: RedisResult<Value> = cmd("GET").arg("written").query(&mut con);
+        assert_eq!(result.unwrap_err().code(), Some("BUSY"));
+
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
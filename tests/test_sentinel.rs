@@ -0,0 +1,243 @@
+#[cfg(test)]
+mod tests {
+    use std::net::TcpStream;
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use redis::{cmd, Client, Connection, Value};
+
+    /// 使用独立数据目录启动的服务器（数据节点或哨兵）
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16, args: &[&str]) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn sentinel(dir: &PathBuf, port: u16, monitor: &str) -> Self {
+            TestServer::start(dir, port, &[
+                "--sentinel",
+                "--sentinel-monitor", monitor,
+                "--sentinel-down-after-milliseconds", "1000",
+                "--sentinel-failover-timeout", "5000",
+            ])
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 在超时之前反复检查条件
+    fn wait_for(seconds: u64, mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(seconds);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        false
+    }
+
+    fn info_field(con: &mut Connection, field: &str) -> Option<String> {
+        let info: String = cmd("INFO").arg("replication").query(con).ok()?;
+        info.lines()
+            .find_map(|line| line.strip_prefix(&format!("{}:", field)))
+            .map(|value| value.to_string())
+    }
+
+    fn master_addr(con: &mut Connection) -> Option<Vec<String>> {
+        cmd("SENTINEL").arg("get-master-addr-by-name").arg("mymaster").query(con).unwrap()
+    }
+
+    fn count(con: &mut Connection, subcommand: &str) -> usize {
+        let list: Vec<Value> = cmd("SENTINEL").arg(subcommand).arg("mymaster").query(con).unwrap();
+        list.len()
+    }
+
+    #[test]
+    fn test_sentinel_commands() {
+        let dir = temp_dir("rudis-test-sentinel-commands");
+        let sentinel = TestServer::sentinel(&dir, 16429, "mymaster 127.0.0.1 16430 1");
+        let mut con = sentinel.connect();
+
+        let pong: String = cmd("PING").query(&mut con).unwrap();
+        assert_eq!(pong, "PONG");
+        let info: String = cmd("INFO").query(&mut con).unwrap();
+        assert!(info.contains("redis_mode:sentinel"));
+        assert!(info.contains("master0:name=mymaster"));
+
+        assert_eq!(master_addr(&mut con), Some(vec!["127.0.0.1".to_string(), "16430".to_string()]));
+        let unknown: Option<Vec<String>> = cmd("SENTINEL").arg("get-master-addr-by-name").arg("other").query(&mut con).unwrap();
+        assert_eq!(unknown, None);
+        let err = cmd("SENTINEL").arg("master").arg("other").query::<Value>(&mut con).unwrap_err();
+        assert!(err.to_string().contains("No such master"));
+
+        // 没有任何实例可达：超过 down-after-milliseconds 后主观下线，quorum 为 1 时同时客观下线
+        let myid: String = cmd("SENTINEL").arg("myid").query(&mut con).unwrap();
+        assert_eq!(myid.len(), 40);
+        assert!(wait_for(10, || {
+            let reply: (i64, String, i64) = cmd("SENTINEL").arg("is-master-down-by-addr")
+                .arg("127.0.0.1").arg(16430).arg(0).arg("*").query(&mut con).unwrap();
+            reply == (1, "*".to_string(), 0)
+        }));
+        let fields: Vec<String> = cmd("SENTINEL").arg("master").arg("mymaster").query(&mut con).unwrap();
+        assert!(fields[7].contains("o_down"));
+
+        // 投票：每个纪元只投给最先请求的哨兵
+        let vote: (i64, String, i64) = cmd("SENTINEL").arg("is-master-down-by-addr")
+            .arg("127.0.0.1").arg(16430).arg(100).arg("a".repeat(40)).query(&mut con).unwrap();
+        assert_eq!(vote, (1, "a".repeat(40), 100));
+        let vote: (i64, String, i64) = cmd("SENTINEL").arg("is-master-down-by-addr")
+            .arg("127.0.0.1").arg(16430).arg(100).arg("b".repeat(40)).query(&mut con).unwrap();
+        assert_eq!(vote, (1, "a".repeat(40), 100));
+    }
+
+    #[test]
+    fn test_sentinel_failover() {
+        let master = TestServer::start(&temp_dir("rudis-test-sentinel-master"), 16431, &[]);
+        let replica1 = TestServer::start(&temp_dir("rudis-test-sentinel-replica1"), 16432, &["--replicaof", "127.0.0.1:16431"]);
+        let replica2 = TestServer::start(&temp_dir("rudis-test-sentinel-replica2"), 16433, &["--replicaof", "127.0.0.1:16431"]);
+        let mut master_con = master.connect();
+        assert!(wait_for(10, || info_field(&mut master_con, "connected_slaves").as_deref() == Some("2")));
+        let _: () = cmd("SET").arg("before").arg("failover").query(&mut master_con).unwrap();
+
+        let sentinel_dir = temp_dir("rudis-test-sentinel");
+        let monitor = "mymaster 127.0.0.1 16431 2";
+        let sentinels: Vec<TestServer> = (16434..16437).map(|port| TestServer::sentinel(&sentinel_dir, port, monitor)).collect();
+        let mut sentinel_cons: Vec<Connection> = sentinels.iter().map(|sentinel| sentinel.connect()).collect();
+
+        // 通过主节点的 INFO 发现从节点，通过 hello 消息互相发现
+        for con in sentinel_cons.iter_mut() {
+            assert_eq!(master_addr(con), Some(vec!["127.0.0.1".to_string(), "16431".to_string()]));
+            assert!(wait_for(10, || count(con, "replicas") == 2 && count(con, "sentinels") == 2));
+        }
+
+        drop(master);
+
+        // 所有哨兵就新的主节点达成一致
+        let mut promoted = None;
+        assert!(wait_for(30, || {
+            let addrs: Vec<Option<Vec<String>>> = sentinel_cons.iter_mut().map(master_addr).collect();
+            match &addrs[0] {
+                Some(addr) if addr[1] != "16431" && addrs.iter().all(|other| other.as_ref() == Some(addr)) => {
+                    promoted = Some(addr[1].parse::<u16>().unwrap());
+                    true
+                },
+                _ => false,
+            }
+        }));
+        let (promoted, other) = match promoted.unwrap() {
+            16432 => (replica1, replica2),
+            16433 => (replica2, replica1),
+            port => panic!("Unexpected master port {}", port),
+        };
+
+        let mut promoted_con = promoted.connect();
+        let mut other_con = other.connect();
+        assert_eq!(info_field(&mut promoted_con, "role").as_deref(), Some("master"));
+        let promoted_port = promoted.port.to_string();
+        assert!(wait_for(10, || {
+            info_field(&mut other_con, "master_port").as_deref() == Some(promoted_port.as_str())
+                && info_field(&mut other_con, "master_link_status").as_deref() == Some("up")
+        }));
+
+        // 新的主节点可写，写入同步到其余从节点
+        let value: Option<String> = cmd("GET").arg("before").query(&mut promoted_con).unwrap();
+        assert_eq!(value.as_deref(), Some("failover"));
+        let _: () = cmd("SET").arg("after").arg("failover").query(&mut promoted_con).unwrap();
+        assert!(wait_for(10, || cmd("GET").arg("after").query::<Option<String>>(&mut other_con).unwrap().is_some()));
+    }
+
+    #[test]
+    fn test_sentinel_manual_failover() {
+        let master = TestServer::start(&temp_dir("rudis-test-sentinel-manual-master"), 16437, &[]);
+        let replica = TestServer::start(&temp_dir("rudis-test-sentinel-manual-replica"), 16438, &["--replicaof", "127.0.0.1:16437"]);
+        let mut master_con = master.connect();
+        assert!(wait_for(10, || info_field(&mut master_con, "connected_slaves").as_deref() == Some("1")));
+
+        let sentinel = TestServer::sentinel(&temp_dir("rudis-test-sentinel-manual"), 16439, "mymaster 127.0.0.1 16437 1");
+        let mut con = sentinel.connect();
+
+        // 发现从节点并取得其 INFO 后才能选出提升的从节点
+        assert!(wait_for(10, || cmd("SENTINEL").arg("failover").arg("mymaster").query::<()>(&mut con).is_ok()));
+        assert!(wait_for(10, || master_addr(&mut con) == Some(vec!["127.0.0.1".to_string(), "16438".to_string()])));
+        let mut replica_con = replica.connect();
+        assert_eq!(info_field(&mut replica_con, "role").as_deref(), Some("master"));
+
+        // 原主节点仍在运行，哨兵将其改为新主节点的从节点
+        assert!(wait_for(20, || {
+            info_field(&mut master_con, "role").as_deref() == Some("slave")
+                && info_field(&mut master_con, "master_port").as_deref() == Some("16438")
+        }));
+        let fields: Vec<String> = cmd("SENTINEL").arg("master").arg("mymaster").query(&mut con).unwrap();
+        assert_eq!(fields[fields.iter().position(|field| field == "config-epoch").unwrap() + 1], "1");
+    }
+
+    /// 主从节点需要认证时，哨兵通过 sentinel-auth-user / sentinel-auth-pass 认证后监控
+    #[test]
+    fn test_sentinel_auth() {
+        let master = TestServer::start(&temp_dir("rudis-test-sentinel-auth-master"), 16445, &["--requirepass", "rootpass"]);
+        let mut master_con = master.connect();
+        let _: () = cmd("AUTH").arg("rootpass").query(&mut master_con).unwrap();
+        let _: () = cmd("ACL").arg("SETUSER").arg("sentinel").arg("on").arg(">sentinelpass").arg("allchannels").arg("+@all").query(&mut master_con).unwrap();
+
+        let monitor = "mymaster 127.0.0.1 16445 1";
+        let sentinel_dir = temp_dir("rudis-test-sentinel-auth");
+        let args = |auth: &'static [&'static str]| {
+            let mut args = vec!["--sentinel", "--sentinel-monitor", monitor, "--sentinel-down-after-milliseconds", "1000"];
+            args.extend_from_slice(auth);
+            args
+        };
+        let with_auth = TestServer::start(&sentinel_dir, 16446, &args(&["--sentinel-auth-user", "sentinel", "--sentinel-auth-pass", "sentinelpass"]));
+        let without_auth = TestServer::start(&sentinel_dir, 16447, &args(&[]));
+        let mut with_auth_con = with_auth.connect();
+        let mut without_auth_con = without_auth.connect();
+
+        // 未认证的 PING 收到 NOAUTH，超过 down-after-milliseconds 后主观下线
+        let flags = |con: &mut Connection| {
+            let fields: Vec<String> = cmd("SENTINEL").arg("master").arg("mymaster").query(con).unwrap();
+            fields[7].clone()
+        };
+        assert!(wait_for(10, || flags(&mut without_auth_con).contains("s_down")));
+        assert_eq!(flags(&mut with_auth_con), "master");
+        let info: String = cmd("INFO").query(&mut with_auth_con).unwrap();
+        assert!(info.contains("status=ok"));
+    }
+}
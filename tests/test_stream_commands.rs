@@ -0,0 +1,440 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use redis::{cmd, Client, Connection, RedisResult};
+
+    type Entry = (String, Vec<String>);
+    type ReadReply = Option<Vec<(String, Vec<Entry>)>>;
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    fn reset(con: &mut Connection, key: &str) {
+        let _: () = cmd("DEL").arg(key).query(con).unwrap();
+    }
+
+    fn xadd(con: &mut Connection, key: &str, id: &str, field: &str, value: &str) -> String {
+        cmd("XADD").arg(key).arg(id).arg(field).arg(value).query(con).unwrap()
+    }
+
+    #[test]
+    fn test_xadd_xlen_xrange() {
+        let mut con = setup();
+        let key = "stream_basic";
+        reset(&mut con, key);
+
+        assert_eq!(xadd(&mut con, key, "1-1", "a", "1"), "1-1");
+        assert_eq!(xadd(&mut con, key, "1-*", "b", "2"), "1-2");
+        assert_eq!(xadd(&mut con, key, "5", "c", "3"), "5-0");
+        let auto = xadd(&mut con, key, "*", "d", "4");
+        assert!(auto.split('-').next().unwrap().parse::<u64>().unwrap() > 5);
+
+        let result: RedisResult<String> = cmd("XADD").arg(key).arg("5-0").arg("e").arg("5").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("equal or smaller"));
+        let result: RedisResult<String> = cmd("XADD").arg("stream_basic_zero").arg("0-0").arg("e").arg("5").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("greater than 0-0"));
+
+        let len: i64 = cmd("XLEN").arg(key).query(&mut con).unwrap();
+        assert_eq!(len, 4);
+
+        let entries: Vec<Entry> = cmd("XRANGE").arg(key).arg("-").arg("+").query(&mut con).unwrap();
+        assert_eq!(entries[0], ("1-1".to_string(), vec!["a".to_string(), "1".to_string()]));
+        assert_eq!(entries.len(), 4);
+
+        let entries: Vec<Entry> = cmd("XRANGE").arg(key).arg("1").arg("5").arg("COUNT").arg(2).query(&mut con).unwrap();
+        let ids: Vec<&str> = entries.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["1-1", "1-2"]);
+
+        let entries: Vec<Entry> = cmd("XRANGE").arg(key).arg("(1-1").arg("5").query(&mut con).unwrap();
+        let ids: Vec<&str> = entries.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["1-2", "5-0"]);
+
+        let entries: Vec<Entry> = cmd("XREVRANGE").arg(key).arg("+").arg("-").arg("COUNT").arg(2).query(&mut con).unwrap();
+        let ids: Vec<&str> = entries.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec![auto.as_str(), "5-0"]);
+
+        let key_type: String = cmd("TYPE").arg(key).query(&mut con).unwrap();
+        assert_eq!(key_type, "stream");
+    }
+
+    #[test]
+    fn test_stream_trim_and_delete() {
+        let mut con = setup();
+        let key = "stream_trim";
+        reset(&mut con, key);
+
+        for i in 1..=10 {
+            xadd(&mut con, key, &format!("{}-0", i), "n", &i.to_string());
+        }
+        let id: String = cmd("XADD").arg(key).arg("MAXLEN").arg("=").arg(5).arg("11-0").arg("n").arg("11").query(&mut con).unwrap();
+        assert_eq!(id, "11-0");
+        let len: i64 = cmd("XLEN").arg(key).query(&mut con).unwrap();
+        assert_eq!(len, 5);
+
+        let removed: i64 = cmd("XTRIM").arg(key).arg("MINID").arg("9").query(&mut con).unwrap();
+        assert_eq!(removed, 2);
+        let removed: i64 = cmd("XTRIM").arg(key).arg("MAXLEN").arg("~").arg(0).arg("LIMIT").arg(1).query(&mut con).unwrap();
+        assert_eq!(removed, 1);
+
+        let deleted: i64 = cmd("XDEL").arg(key).arg("10-0").arg("100-0").query(&mut con).unwrap();
+        assert_eq!(deleted, 1);
+        let entries: Vec<Entry> = cmd("XRANGE").arg(key).arg("-").arg("+").query(&mut con).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "11-0");
+
+        // 删除消息后 ID 仍然单调递增
+        let result: RedisResult<String> = cmd("XADD").arg(key).arg("10-5").arg("n").arg("x").query(&mut con);
+        assert!(result.is_err());
+
+        // NOMKSTREAM 不创建新的流
+        reset(&mut con, "stream_trim_missing");
+        let id: Option<String> = cmd("XADD").arg("stream_trim_missing").arg("NOMKSTREAM").arg("*").arg("a").arg("b").query(&mut con).unwrap();
+        assert_eq!(id, None);
+
+        let result: RedisResult<i64> = cmd("XTRIM").arg(key).arg("MAXLEN").arg(1).arg("LIMIT").arg(1).query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_xread() {
+        let mut con = setup();
+        reset(&mut con, "stream_read_a");
+        reset(&mut con, "stream_read_b");
+
+        xadd(&mut con, "stream_read_a", "1-0", "a", "1");
+        xadd(&mut con, "stream_read_a", "2-0", "a", "2");
+        xadd(&mut con, "stream_read_b", "3-0", "b", "3");
+
+        let reply: ReadReply = cmd("XREAD").arg("COUNT").arg(1).arg("STREAMS").arg("stream_read_a").arg("stream_read_b").arg("0").arg("0").query(&mut con).unwrap();
+        let reply = reply.unwrap();
+        assert_eq!(reply.len(), 2);
+        assert_eq!(reply[0].0, "stream_read_a");
+        assert_eq!(reply[0].1, vec![("1-0".to_string(), vec!["a".to_string(), "1".to_string()])]);
+        assert_eq!(reply[1].1[0].0, "3-0");
+
+        let reply: ReadReply = cmd("XREAD").arg("STREAMS").arg("stream_read_a").arg("2-0").query(&mut con).unwrap();
+        assert_eq!(reply, None);
+        let reply: ReadReply = cmd("XREAD").arg("STREAMS").arg("stream_read_a").arg("$").query(&mut con).unwrap();
+        assert_eq!(reply, None);
+
+        let result: RedisResult<ReadReply> = cmd("XREAD").arg("STREAMS").arg("stream_read_a").arg("stream_read_b").arg("0").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("Unbalanced"));
+    }
+
+    #[test]
+    fn test_xread_block() {
+        let mut con = setup();
+        let key = "stream_read_block";
+        reset(&mut con, key);
+        xadd(&mut con, key, "1-0", "old", "1");
+
+        let writer = thread::spawn(|| {
+            thread::sleep(Duration::from_millis(200));
+            let mut con = setup();
+            xadd(&mut con, "stream_read_block", "*", "new", "2");
+        });
+
+        // $ 只返回阻塞之后写入的消息
+        let start = Instant::now();
+        let reply: ReadReply = cmd("XREAD").arg("BLOCK").arg(5000).arg("STREAMS").arg(key).arg("$").query(&mut con).unwrap();
+        writer.join().unwrap();
+        let reply = reply.unwrap();
+        assert_eq!(reply[0].1.len(), 1);
+        assert_eq!(reply[0].1[0].1, vec!["new".to_string(), "2".to_string()]);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // 超时返回 nil
+        let start = Instant::now();
+        let reply: ReadReply = cmd("XREAD").arg("BLOCK").arg(100).arg("STREAMS").arg(key).arg("$").query(&mut con).unwrap();
+        assert_eq!(reply, None);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // 已有可读消息时不阻塞
+        let reply: ReadReply = cmd("XREAD").arg("BLOCK").arg(0).arg("STREAMS").arg(key).arg("0").query(&mut con).unwrap();
+        assert_eq!(reply.unwrap()[0].1.len(), 2);
+    }
+
+    #[test]
+    fn test_consumer_group_read_and_ack() {
+        let mut con = setup();
+        let key = "stream_group";
+        reset(&mut con, key);
+
+        let result: RedisResult<String> = cmd("XGROUP").arg("CREATE").arg(key).arg("workers").arg("$").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("MKSTREAM"));
+        let _: () = cmd("XGROUP").arg("CREATE").arg(key).arg("workers").arg("$").arg("MKSTREAM").query(&mut con).unwrap();
+        let result: RedisResult<String> = cmd("XGROUP").arg("CREATE").arg(key).arg("workers").arg("0").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("BUSYGROUP"));
+
+        for i in 1..=3 {
+            xadd(&mut con, key, &format!("{}-0", i), "job", &i.to_string());
+        }
+
+        // 每条消息只投递给组内的一个消费者
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("workers").arg("alice").arg("COUNT").arg(2).arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        let ids: Vec<String> = reply.unwrap()[0].1.iter().map(|(id, _)| id.clone()).collect();
+        assert_eq!(ids, vec!["1-0", "2-0"]);
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("workers").arg("bob").arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        let ids: Vec<String> = reply.unwrap()[0].1.iter().map(|(id, _)| id.clone()).collect();
+        assert_eq!(ids, vec!["3-0"]);
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("workers").arg("bob").arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        assert_eq!(reply, None);
+
+        // 概要形式：[数量, 最小 ID, 最大 ID, [[消费者, 数量], ...]]
+        let (count, first, last, consumers): (i64, String, String, Vec<(String, String)>) =
+            cmd("XPENDING").arg(key).arg("workers").query(&mut con).unwrap();
+        assert_eq!((count, first.as_str(), last.as_str()), (3, "1-0", "3-0"));
+        assert_eq!(consumers, vec![("alice".to_string(), "2".to_string()), ("bob".to_string(), "1".to_string())]);
+
+        // 扩展形式：[[ID, 消费者, 空闲毫秒数, 投递次数], ...]
+        let pending: Vec<(String, String, i64, i64)> = cmd("XPENDING").arg(key).arg("workers").arg("-").arg("+").arg(10).arg("alice").query(&mut con).unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].0, "1-0");
+        assert_eq!(pending[0].3, 1);
+
+        // 读取自己名下的历史消息
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("workers").arg("alice").arg("STREAMS").arg(key).arg("0").query(&mut con).unwrap();
+        assert_eq!(reply.unwrap()[0].1.len(), 2);
+
+        let acked: i64 = cmd("XACK").arg(key).arg("workers").arg("1-0").arg("3-0").arg("9-0").query(&mut con).unwrap();
+        assert_eq!(acked, 2);
+        let (count, _, _, _): (i64, Option<String>, Option<String>, Option<Vec<(String, String)>>) =
+            cmd("XPENDING").arg(key).arg("workers").query(&mut con).unwrap();
+        assert_eq!(count, 1);
+
+        // NOACK 读取的消息不进入 PEL
+        xadd(&mut con, key, "4-0", "job", "4");
+        let _: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("workers").arg("bob").arg("NOACK").arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        let (count, _, _, _): (i64, Option<String>, Option<String>, Option<Vec<(String, String)>>) =
+            cmd("XPENDING").arg(key).arg("workers").query(&mut con).unwrap();
+        assert_eq!(count, 1);
+
+        let result: RedisResult<ReadReply> = cmd("XREADGROUP").arg("GROUP").arg("missing").arg("bob").arg("STREAMS").arg(key).arg(">").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("NOGROUP"));
+    }
+
+    #[test]
+    fn test_xgroup_management() {
+        let mut con = setup();
+        let key = "stream_group_admin";
+        reset(&mut con, key);
+        xadd(&mut con, key, "1-0", "a", "1");
+        xadd(&mut con, key, "2-0", "a", "2");
+
+        let _: () = cmd("XGROUP").arg("CREATE").arg(key).arg("g").arg("0").query(&mut con).unwrap();
+        let created: i64 = cmd("XGROUP").arg("CREATECONSUMER").arg(key).arg("g").arg("c1").query(&mut con).unwrap();
+        assert_eq!(created, 1);
+        let created: i64 = cmd("XGROUP").arg("CREATECONSUMER").arg(key).arg("g").arg("c1").query(&mut con).unwrap();
+        assert_eq!(created, 0);
+
+        let _: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("g").arg("c1").arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        let pending: i64 = cmd("XGROUP").arg("DELCONSUMER").arg(key).arg("g").arg("c1").query(&mut con).unwrap();
+        assert_eq!(pending, 2);
+
+        // SETID 重新投递
+        let _: () = cmd("XGROUP").arg("SETID").arg(key).arg("g").arg("1-0").query(&mut con).unwrap();
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("g").arg("c2").arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        assert_eq!(reply.unwrap()[0].1[0].0, "2-0");
+
+        let destroyed: i64 = cmd("XGROUP").arg("DESTROY").arg(key).arg("g").query(&mut con).unwrap();
+        assert_eq!(destroyed, 1);
+        let destroyed: i64 = cmd("XGROUP").arg("DESTROY").arg(key).arg("g").query(&mut con).unwrap();
+        assert_eq!(destroyed, 0);
+    }
+
+    #[test]
+    fn test_xclaim_and_xautoclaim() {
+        let mut con = setup();
+        let key = "stream_claim";
+        reset(&mut con, key);
+        let _: () = cmd("XGROUP").arg("CREATE").arg(key).arg("g").arg("$").arg("MKSTREAM").query(&mut con).unwrap();
+        for i in 1..=4 {
+            xadd(&mut con, key, &format!("{}-0", i), "job", &i.to_string());
+        }
+        let _: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("g").arg("crashed").arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+
+        // 空闲时间不足时不认领
+        let claimed: Vec<Entry> = cmd("XCLAIM").arg(key).arg("g").arg("alice").arg(60000).arg("1-0").query(&mut con).unwrap();
+        assert!(claimed.is_empty());
+
+        thread::sleep(Duration::from_millis(50));
+        let claimed: Vec<Entry> = cmd("XCLAIM").arg(key).arg("g").arg("alice").arg(20).arg("1-0").arg("2-0").query(&mut con).unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert_eq!(claimed[0].1, vec!["job".to_string(), "1".to_string()]);
+        let pending: Vec<(String, String, i64, i64)> = cmd("XPENDING").arg(key).arg("g").arg("-").arg("+").arg(10).query(&mut con).unwrap();
+        assert_eq!(pending[0].1, "alice");
+        assert_eq!(pending[0].3, 2);
+
+        let claimed: Vec<String> = cmd("XCLAIM").arg(key).arg("g").arg("bob").arg(0).arg("1-0").arg("JUSTID").query(&mut con).unwrap();
+        assert_eq!(claimed, vec!["1-0"]);
+
+        // 已删除的消息在 XAUTOCLAIM 时从 PEL 中移除
+        let _: i64 = cmd("XDEL").arg(key).arg("4-0").query(&mut con).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        let (cursor, claimed, deleted): (String, Vec<Entry>, Vec<String>) =
+            cmd("XAUTOCLAIM").arg(key).arg("g").arg("carol").arg(20).arg("0-0").arg("COUNT").arg(1).query(&mut con).unwrap();
+        assert_eq!(cursor, "2-0");
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].0, "1-0");
+        assert!(deleted.is_empty());
+
+        let (cursor, claimed, deleted): (String, Vec<String>, Vec<String>) =
+            cmd("XAUTOCLAIM").arg(key).arg("g").arg("carol").arg(20).arg(&cursor).arg("JUSTID").query(&mut con).unwrap();
+        assert_eq!(cursor, "0-0");
+        assert_eq!(claimed, vec!["2-0", "3-0"]);
+        assert_eq!(deleted, vec!["4-0"]);
+
+        let (count, _, _, consumers): (i64, String, String, Vec<(String, String)>) =
+            cmd("XPENDING").arg(key).arg("g").query(&mut con).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(consumers, vec![("carol".to_string(), "3".to_string())]);
+    }
+
+    #[test]
+    fn test_xreadgroup_block() {
+        let mut con = setup();
+        let key = "stream_group_block";
+        reset(&mut con, key);
+        let _: () = cmd("XGROUP").arg("CREATE").arg(key).arg("g").arg("$").arg("MKSTREAM").query(&mut con).unwrap();
+
+        let writer = thread::spawn(|| {
+            thread::sleep(Duration::from_millis(200));
+            let mut con = setup();
+            xadd(&mut con, "stream_group_block", "*", "job", "1");
+        });
+
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("g").arg("worker").arg("BLOCK").arg(5000).arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        writer.join().unwrap();
+        assert_eq!(reply.unwrap()[0].1[0].1, vec!["job".to_string(), "1".to_string()]);
+
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("g").arg("worker").arg("BLOCK").arg(100).arg("STREAMS").arg(key).arg(">").query(&mut con).unwrap();
+        assert_eq!(reply, None);
+    }
+
+    #[test]
+    fn test_stream_wrong_type() {
+        let mut con = setup();
+        let _: () = cmd("SET").arg("stream_wrong_type").arg("value").query(&mut con).unwrap();
+        let result: RedisResult<String> = cmd("XADD").arg("stream_wrong_type").arg("*").arg("a").arg("b").query(&mut con);
+        assert!(result.is_err());
+        let result: RedisResult<i64> = cmd("XLEN").arg("stream_wrong_type").query(&mut con);
+        assert!(result.is_err());
+    }
+
+    /// 使用独立数据目录启动的服务器，用于验证持久化
+    struct TestServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl TestServer {
+        fn start(dir: &PathBuf, port: u16, extra_args: &[&str]) -> Self {
+            let child = Command::new(env!("CARGO_BIN_EXE_rudis-server"))
+                .current_dir(dir)
+                .args(["--port", &port.to_string(), "--webport", &(port + 1000).to_string()])
+                .args(extra_args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+            let server = TestServer { child, port };
+            for _ in 0..100 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return server;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            panic!("Failed to start server on port {}", port);
+        }
+
+        fn connect(&self) -> Connection {
+            let client = Client::open(format!("redis://127.0.0.1:{}/", self.port)).unwrap();
+            client.get_connection().unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 写入消息并创建消费者组，返回自动生成的 ID
+    fn populate(con: &mut Connection) -> Vec<String> {
+        let _: () = cmd("XGROUP").arg("CREATE").arg("jobs").arg("g").arg("$").arg("MKSTREAM").query(con).unwrap();
+        let ids: Vec<String> = (0..3).map(|i| xadd(con, "jobs", "*", "job", &i.to_string())).collect();
+        let _: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("g").arg("worker").arg("STREAMS").arg("jobs").arg(">").query(con).unwrap();
+        let _: i64 = cmd("XACK").arg("jobs").arg("g").arg(&ids[0]).query(con).unwrap();
+        let _: Vec<String> = cmd("XCLAIM").arg("jobs").arg("g").arg("other").arg(0).arg(&ids[2]).arg("JUSTID").query(con).unwrap();
+        ids
+    }
+
+    fn verify(con: &mut Connection, ids: &[String]) {
+        let entries: Vec<Entry> = cmd("XRANGE").arg("jobs").arg("-").arg("+").query(con).unwrap();
+        let stored: Vec<String> = entries.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(stored, ids);
+        let pending: Vec<(String, String, i64, i64)> = cmd("XPENDING").arg("jobs").arg("g").arg("-").arg("+").arg(10).query(con).unwrap();
+        let pending: Vec<(String, String)> = pending.into_iter().map(|(id, consumer, _, _)| (id, consumer)).collect();
+        assert_eq!(pending, vec![(ids[1].clone(), "worker".to_string()), (ids[2].clone(), "other".to_string())]);
+        // 消费者组的读取位置同样被恢复
+        let reply: ReadReply = cmd("XREADGROUP").arg("GROUP").arg("g").arg("worker").arg("STREAMS").arg("jobs").arg(">").query(con).unwrap();
+        assert_eq!(reply, None);
+    }
+
+    #[test]
+    fn test_stream_persists_in_aof() {
+        let dir = temp_dir("rudis-stream-aof");
+        let ids = {
+            let server = TestServer::start(&dir, 16397, &["--appendonly", "yes"]);
+            let mut con = server.connect();
+            let ids = populate(&mut con);
+            thread::sleep(Duration::from_millis(200));
+            ids
+        };
+
+        let server = TestServer::start(&dir, 16397, &["--appendonly", "yes"]);
+        let mut con = server.connect();
+        verify(&mut con, &ids);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_stream_persists_in_rdb() {
+        let dir = temp_dir("rudis-stream-rdb");
+        let ids = {
+            let server = TestServer::start(&dir, 16398, &["--save", "1,1"]);
+            let mut con = server.connect();
+            let ids = populate(&mut con);
+            // 等待按保存策略生成快照
+            thread::sleep(Duration::from_millis(2500));
+            ids
+        };
+
+        let server = TestServer::start(&dir, 16398, &[]);
+        let mut con = server.connect();
+        verify(&mut con, &ids);
+        drop(server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
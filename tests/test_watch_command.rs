@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod tests {
+    use redis::{Client, Commands, Connection, RedisResult, Value};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// 被监视的键未被修改时，事务正常执行
+    #[test]
+    fn test_watch_unmodified_key() {
+        let mut con = setup();
+        let _: () = con.set("watch_unmodified_key", "1").unwrap();
+
+        let _: () = redis::cmd("WATCH").arg("watch_unmodified_key").query(&mut con).unwrap();
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("SET").arg("watch_unmodified_key").arg("2").query(&mut con).unwrap();
+        let result: Vec<String> = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, vec!["OK".to_string()]);
+
+        let value: String = con.get("watch_unmodified_key").unwrap();
+        assert_eq!(value, "2");
+    }
+
+    /// 被监视的键被其他客户端修改后，EXEC 返回空回复且事务不执行
+    #[test]
+    fn test_watch_modified_key_aborts_exec() {
+        let mut con = setup();
+        let mut other = setup();
+        let _: () = con.set("watch_modified_key", "1").unwrap();
+
+        let _: () = redis::cmd("WATCH").arg("watch_modified_key").query(&mut con).unwrap();
+        let _: () = other.set("watch_modified_key", "changed").unwrap();
+
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("SET").arg("watch_modified_key").arg("2").query(&mut con).unwrap();
+        let result: Value = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, Value::Nil);
+
+        let value: String = con.get("watch_modified_key").unwrap();
+        assert_eq!(value, "changed");
+    }
+
+    /// 被监视的键被删除、过期同样会使事务失败
+    #[test]
+    fn test_watch_deleted_and_expired_key() {
+        let mut con = setup();
+        let mut other = setup();
+
+        let _: () = con.set("watch_deleted_key", "1").unwrap();
+        let _: () = redis::cmd("WATCH").arg("watch_deleted_key").query(&mut con).unwrap();
+        let _: () = other.del("watch_deleted_key").unwrap();
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("GET").arg("watch_deleted_key").query(&mut con).unwrap();
+        let result: Value = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, Value::Nil);
+
+        let _: () = redis::cmd("PSETEX").arg("watch_expired_key").arg(50).arg("1").query(&mut con).unwrap();
+        let _: () = redis::cmd("WATCH").arg("watch_expired_key").query(&mut con).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("GET").arg("watch_expired_key").query(&mut con).unwrap();
+        let result: Value = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, Value::Nil);
+    }
+
+    /// UNWATCH 之后的修改不影响事务
+    #[test]
+    fn test_unwatch() {
+        let mut con = setup();
+        let mut other = setup();
+        let _: () = con.set("unwatch_key", "1").unwrap();
+
+        let _: () = redis::cmd("WATCH").arg("unwatch_key").query(&mut con).unwrap();
+        let result: String = redis::cmd("UNWATCH").query(&mut con).unwrap();
+        assert_eq!(result, "OK");
+        let _: () = other.set("unwatch_key", "changed").unwrap();
+
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("SET").arg("unwatch_key").arg("2").query(&mut con).unwrap();
+        let result: Vec<String> = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, vec!["OK".to_string()]);
+    }
+
+    /// EXEC 之后自动取消监视，后续事务不受之前 WATCH 的影响
+    #[test]
+    fn test_exec_clears_watch() {
+        let mut con = setup();
+        let mut other = setup();
+        let _: () = con.set("watch_exec_clear_key", "1").unwrap();
+
+        let _: () = redis::cmd("WATCH").arg("watch_exec_clear_key").query(&mut con).unwrap();
+        let _: () = other.set("watch_exec_clear_key", "changed").unwrap();
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let result: Value = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, Value::Nil);
+
+        let _: () = other.set("watch_exec_clear_key", "again").unwrap();
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("SET").arg("watch_exec_clear_key").arg("2").query(&mut con).unwrap();
+        let result: Vec<String> = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, vec!["OK".to_string()]);
+    }
+
+    /// 事务中不允许执行 WATCH
+    #[test]
+    fn test_watch_inside_multi() {
+        let mut con = setup();
+
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let result: RedisResult<()> = redis::cmd("WATCH").arg("watch_inside_multi_key").query(&mut con);
+        assert!(result.is_err());
+        let err_msg = format!("{:?}", result.unwrap_err());
+        assert!(err_msg.contains("WATCH inside MULTI is not allowed"));
+        let _: () = redis::cmd("DISCARD").query(&mut con).unwrap();
+    }
+
+    /// 多个客户端用 WATCH 实现乐观锁并发自增，检查与执行之间不会穿插其他客户端的写入
+    #[test]
+    fn test_watch_concurrent_increments() {
+        let mut con = setup();
+        let _: () = con.set("watch_concurrent_counter", 0).unwrap();
+
+        let handles: Vec<_> = (0..4).map(|_| {
+            std::thread::spawn(|| {
+                let mut con = setup();
+                for _ in 0..50 {
+                    loop {
+                        let _: () = redis::cmd("WATCH").arg("watch_concurrent_counter").query(&mut con).unwrap();
+                        let value: i64 = con.get("watch_concurrent_counter").unwrap();
+                        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+                        let _: () = redis::cmd("SET").arg("watch_concurrent_counter").arg(value + 1).query(&mut con).unwrap();
+                        let result: Value = redis::cmd("EXEC").query(&mut con).unwrap();
+                        if result != Value::Nil {
+                            break;
+                        }
+                    }
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let value: i64 = con.get("watch_concurrent_counter").unwrap();
+        assert_eq!(value, 200);
+    }
+
+    /// 没有修改数据的写命令（SREM 不存在的成员等）不会使事务失败
+    #[test]
+    fn test_watch_noop_writes() {
+        let mut con = setup();
+        let mut other = setup();
+        let keys = ["watch_noop_set", "watch_noop_list", "watch_noop_hash", "watch_noop_zset", "watch_noop_hll", "watch_noop_stream"];
+        let _: () = con.del(&keys[..]).unwrap();
+        let _: () = redis::cmd("SADD").arg(keys[0]).arg("a").query(&mut con).unwrap();
+        let _: () = redis::cmd("RPUSH").arg(keys[1]).arg("a").query(&mut con).unwrap();
+        let _: () = redis::cmd("HSET").arg(keys[2]).arg("f").arg("v").query(&mut con).unwrap();
+        let _: () = redis::cmd("ZADD").arg(keys[3]).arg(1).arg("a").query(&mut con).unwrap();
+        let _: () = redis::cmd("PFADD").arg(keys[4]).arg("a").query(&mut con).unwrap();
+        let _: () = redis::cmd("XADD").arg(keys[5]).arg("1-1").arg("f").arg("v").query(&mut con).unwrap();
+
+        let _: () = redis::cmd("WATCH").arg(&keys[..]).query(&mut con).unwrap();
+        let noops: Vec<(redis::Cmd, Value)> = vec![
+            (redis::cmd("SREM").arg(keys[0]).arg("missing").clone(), Value::Int(0)),
+            (redis::cmd("SADD").arg(keys[0]).arg("a").clone(), Value::Int(0)),
+            (redis::cmd("LREM").arg(keys[1]).arg(0).arg("missing").clone(), Value::Int(0)),
+            (redis::cmd("LINSERT").arg(keys[1]).arg("BEFORE").arg("missing").arg("x").clone(), Value::Int(-1)),
+            (redis::cmd("HDEL").arg(keys[2]).arg("missing").clone(), Value::Int(0)),
+            (redis::cmd("HSETNX").arg(keys[2]).arg("f").arg("other").clone(), Value::Int(0)),
+            (redis::cmd("ZREM").arg(keys[3]).arg("missing").clone(), Value::Int(0)),
+            (redis::cmd("ZREMRANGEBYSCORE").arg(keys[3]).arg(5).arg(10).clone(), Value::Int(0)),
+            (redis::cmd("ZREMRANGEBYRANK").arg(keys[3]).arg(5).arg(10).clone(), Value::Int(0)),
+            (redis::cmd("PFADD").arg(keys[4]).arg("a").clone(), Value::Int(0)),
+            (redis::cmd("XDEL").arg(keys[5]).arg("9-9").clone(), Value::Int(0)),
+            (redis::cmd("XTRIM").arg(keys[5]).arg("MAXLEN").arg(10).clone(), Value::Int(0)),
+        ];
+        for (command, expected) in noops {
+            let reply: Value = command.query(&mut other).unwrap();
+            assert_eq!(reply, expected);
+        }
+        let result: RedisResult<()> = redis::cmd("LSET").arg(keys[1]).arg(5).arg("x").query(&mut other);
+        assert!(result.is_err());
+
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("SCARD").arg(keys[0]).query(&mut con).unwrap();
+        let result: Value = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(1)]));
+
+        // 真正删除了成员时事务失败
+        let _: () = redis::cmd("WATCH").arg(keys[0]).query(&mut con).unwrap();
+        let _: () = redis::cmd("SREM").arg(keys[0]).arg("a").arg("missing").query(&mut other).unwrap();
+        let _: () = redis::cmd("MULTI").query(&mut con).unwrap();
+        let _: () = redis::cmd("SCARD").arg(keys[0]).query(&mut con).unwrap();
+        let result: Value = redis::cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, Value::Nil);
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+    use redis::{cmd, Client, Commands, Connection, RedisResult, Value};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    fn reset(con: &mut Connection, key: &str) {
+        let _: () = con.del(key).unwrap();
+        let _: i64 = con.zadd_multiple(key, &[(1.0, "a"), (2.0, "b")]).unwrap();
+    }
+
+    fn scores(con: &mut Connection, key: &str) -> Vec<(String, f64)> {
+        con.zrange_withscores(key, 0, -1).unwrap()
+    }
+
+    #[test]
+    fn test_zadd_nx_xx() {
+        let mut con = setup();
+        reset(&mut con, "zadd-nx-xx");
+
+        // NX 只添加新成员
+        let added: i64 = cmd("ZADD").arg("zadd-nx-xx").arg("NX").arg(10).arg("a").arg(3).arg("c").query(&mut con).unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(scores(&mut con, "zadd-nx-xx"), vec![("a".to_string(), 1.0), ("b".to_string(), 2.0), ("c".to_string(), 3.0)]);
+
+        // XX 只更新已有成员
+        let added: i64 = cmd("ZADD").arg("zadd-nx-xx").arg("XX").arg(10).arg("a").arg(4).arg("d").query(&mut con).unwrap();
+        assert_eq!(added, 0);
+        assert_eq!(scores(&mut con, "zadd-nx-xx"), vec![("b".to_string(), 2.0), ("c".to_string(), 3.0), ("a".to_string(), 10.0)]);
+
+        // XX 不会创建键
+        let _: () = con.del("zadd-xx-missing").unwrap();
+        let added: i64 = cmd("ZADD").arg("zadd-xx-missing").arg("XX").arg(1).arg("a").query(&mut con).unwrap();
+        assert_eq!(added, 0);
+        let exists: bool = con.exists("zadd-xx-missing").unwrap();
+        assert!(!exists);
+    }
+
+    #[test]
+    fn test_zadd_gt_lt_ch() {
+        let mut con = setup();
+        reset(&mut con, "zadd-gt-lt");
+
+        // GT 只在分数增大时更新，但仍会添加新成员；CH 计入分数被修改的成员
+        let changed: i64 = cmd("ZADD").arg("zadd-gt-lt").arg("GT").arg("CH").arg(5).arg("a").arg(0).arg("b").arg(3).arg("c").query(&mut con).unwrap();
+        assert_eq!(changed, 2);
+        assert_eq!(scores(&mut con, "zadd-gt-lt"), vec![("b".to_string(), 2.0), ("c".to_string(), 3.0), ("a".to_string(), 5.0)]);
+
+        let changed: i64 = cmd("ZADD").arg("zadd-gt-lt").arg("LT").arg("CH").arg(6).arg("a").arg(1).arg("b").query(&mut con).unwrap();
+        assert_eq!(changed, 1);
+        let score: f64 = con.zscore("zadd-gt-lt", "b").unwrap();
+        assert_eq!(score, 1.0);
+
+        // 分数未变化时不计入 CH
+        let changed: i64 = cmd("ZADD").arg("zadd-gt-lt").arg("CH").arg(1).arg("b").query(&mut con).unwrap();
+        assert_eq!(changed, 0);
+    }
+
+    #[test]
+    fn test_zadd_incr() {
+        let mut con = setup();
+        reset(&mut con, "zadd-incr");
+
+        let score: f64 = cmd("ZADD").arg("zadd-incr").arg("INCR").arg(2.5).arg("a").query(&mut con).unwrap();
+        assert_eq!(score, 3.5);
+        let score: f64 = cmd("ZADD").arg("zadd-incr").arg("INCR").arg(4).arg("new").query(&mut con).unwrap();
+        assert_eq!(score, 4.0);
+
+        // 条件不满足时返回 nil
+        let score: Option<f64> = cmd("ZADD").arg("zadd-incr").arg("NX").arg("INCR").arg(1).arg("a").query(&mut con).unwrap();
+        assert_eq!(score, None);
+        let score: Option<f64> = cmd("ZADD").arg("zadd-incr").arg("GT").arg("INCR").arg(-1).arg("a").query(&mut con).unwrap();
+        assert_eq!(score, None);
+        let score: f64 = con.zscore("zadd-incr", "a").unwrap();
+        assert_eq!(score, 3.5);
+
+        let _: i64 = cmd("ZADD").arg("zadd-incr").arg("inf").arg("inf").query(&mut con).unwrap();
+        let result: RedisResult<f64> = cmd("ZADD").arg("zadd-incr").arg("INCR").arg("-inf").arg("inf").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("NaN"));
+    }
+
+    #[test]
+    fn test_zadd_errors() {
+        let mut con = setup();
+        reset(&mut con, "zadd-errors");
+
+        let result: RedisResult<i64> = cmd("ZADD").arg("zadd-errors").arg("NX").arg("XX").arg(1).arg("a").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("not compatible"));
+        let result: RedisResult<i64> = cmd("ZADD").arg("zadd-errors").arg("GT").arg("LT").arg(1).arg("a").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("not compatible"));
+        let result: RedisResult<i64> = cmd("ZADD").arg("zadd-errors").arg("NX").arg("GT").arg(1).arg("a").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("not compatible"));
+        let result: RedisResult<i64> = cmd("ZADD").arg("zadd-errors").arg("INCR").arg(1).arg("a").arg(2).arg("b").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("single increment-element pair"));
+        let result: RedisResult<i64> = cmd("ZADD").arg("zadd-errors").arg(1).arg("a").arg(2).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("syntax error"));
+        let result: RedisResult<i64> = cmd("ZADD").arg("zadd-errors").arg("abc").arg("a").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("not a valid float"));
+        let result: RedisResult<i64> = cmd("ZADD").arg("zadd-errors").arg("nan").arg("a").query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_lex_queries_use_score_order() {
+        let mut con = setup();
+        let _: () = con.del("zadd-lex").unwrap();
+        let members: Vec<(f64, String)> = (0..1000).map(|i| (0.0, format!("m{:04}", i))).collect();
+        let _: i64 = con.zadd_multiple("zadd-lex", &members).unwrap();
+
+        let count: i64 = cmd("ZLEXCOUNT").arg("zadd-lex").arg("[m0100").arg("(m0200").query(&mut con).unwrap();
+        assert_eq!(count, 100);
+        let result: Vec<String> = cmd("ZRANGEBYLEX").arg("zadd-lex").arg("(m0997").arg("+").query(&mut con).unwrap();
+        assert_eq!(result, vec!["m0998", "m0999"]);
+        let removed: i64 = cmd("ZREMRANGEBYLEX").arg("zadd-lex").arg("-").arg("(m0500").query(&mut con).unwrap();
+        assert_eq!(removed, 500);
+    }
+
+    #[test]
+    fn test_zadd_without_changes_keeps_watch() {
+        let mut con = setup();
+        let mut other = setup();
+        reset(&mut con, "zadd-watch");
+
+        // 被 NX / XX / GT 过滤掉所有成员、或分数不变的 ZADD 不算修改
+        let _: () = cmd("WATCH").arg("zadd-watch").query(&mut con).unwrap();
+        let _: i64 = cmd("ZADD").arg("zadd-watch").arg("NX").arg(5).arg("a").query(&mut other).unwrap();
+        let _: i64 = cmd("ZADD").arg("zadd-watch").arg("GT").arg(0).arg("b").query(&mut other).unwrap();
+        let _: i64 = cmd("ZADD").arg("zadd-watch").arg(1).arg("a").query(&mut other).unwrap();
+        let _: Option<f64> = cmd("ZADD").arg("zadd-watch").arg("XX").arg("INCR").arg(1).arg("missing").query(&mut other).unwrap();
+        let _: () = cmd("MULTI").query(&mut con).unwrap();
+        let _: () = cmd("ZCARD").arg("zadd-watch").query(&mut con).unwrap();
+        let result: Vec<i64> = cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, vec![2]);
+
+        // 真正写入后事务被放弃
+        let _: () = cmd("WATCH").arg("zadd-watch").query(&mut con).unwrap();
+        let _: i64 = cmd("ZADD").arg("zadd-watch").arg("GT").arg(3).arg("b").query(&mut other).unwrap();
+        let _: () = cmd("MULTI").query(&mut con).unwrap();
+        let _: () = cmd("ZCARD").arg("zadd-watch").query(&mut con).unwrap();
+        let result: Value = cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, Value::Nil);
+
+        // XX 不会创建键，被监视的不存在的键同样不受影响
+        let _: () = con.del("zadd-watch-missing").unwrap();
+        let _: () = cmd("WATCH").arg("zadd-watch-missing").query(&mut con).unwrap();
+        let _: i64 = cmd("ZADD").arg("zadd-watch-missing").arg("XX").arg(1).arg("a").query(&mut other).unwrap();
+        let _: () = cmd("MULTI").query(&mut con).unwrap();
+        let _: () = cmd("EXISTS").arg("zadd-watch-missing").query(&mut con).unwrap();
+        let result: Vec<i64> = cmd("EXEC").query(&mut con).unwrap();
+        assert_eq!(result, vec![0]);
+    }
+
+    #[test]
+    fn test_zadd_duplicate_members() {
+        let mut con = setup();
+        let _: () = con.del("zadd-duplicates").unwrap();
+
+        let added: i64 = cmd("ZADD").arg("zadd-duplicates").arg(1).arg("a").arg(2).arg("a").query(&mut con).unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(scores(&mut con, "zadd-duplicates"), vec![("a".to_string(), 2.0)]);
+        let changed: i64 = cmd("ZADD").arg("zadd-duplicates").arg("CH").arg(3).arg("a").arg(2).arg("a").query(&mut con).unwrap();
+        assert_eq!(changed, 2);
+        assert_eq!(scores(&mut con, "zadd-duplicates"), vec![("a".to_string(), 2.0)]);
+    }
+}
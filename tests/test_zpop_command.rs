@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use redis::{cmd, Client, Commands, Connection, RedisResult};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    fn fill(con: &mut Connection, key: &str) {
+        let _: () = con.del(key).unwrap();
+        let _: i64 = con.zadd_multiple(key, &[(1.0, "a"), (2.0, "b"), (3.0, "c")]).unwrap();
+    }
+
+    type MpopReply = Option<(String, Vec<(String, f64)>)>;
+
+    #[test]
+    fn test_zpopmin_zpopmax() {
+        let mut con = setup();
+        fill(&mut con, "zpop-key");
+
+        let result: Vec<(String, f64)> = cmd("ZPOPMIN").arg("zpop-key").query(&mut con).unwrap();
+        assert_eq!(result, vec![("a".to_string(), 1.0)]);
+        let result: Vec<(String, f64)> = cmd("ZPOPMAX").arg("zpop-key").arg(5).query(&mut con).unwrap();
+        assert_eq!(result, vec![("c".to_string(), 3.0), ("b".to_string(), 2.0)]);
+
+        // 弹出全部成员后键不再存在
+        let exists: bool = con.exists("zpop-key").unwrap();
+        assert!(!exists);
+        let result: Vec<(String, f64)> = cmd("ZPOPMIN").arg("zpop-key").query(&mut con).unwrap();
+        assert!(result.is_empty());
+
+        let result: RedisResult<Vec<String>> = cmd("ZPOPMIN").arg("zpop-key").arg(-1).query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_zmpop() {
+        let mut con = setup();
+        fill(&mut con, "zmpop-key");
+        let _: () = con.del("zmpop-empty").unwrap();
+
+        let result: MpopReply = cmd("ZMPOP").arg(2).arg("zmpop-empty").arg("zmpop-key").arg("MAX").arg("COUNT").arg(2).query(&mut con).unwrap();
+        assert_eq!(result, Some(("zmpop-key".to_string(), vec![("c".to_string(), 3.0), ("b".to_string(), 2.0)])));
+        let result: MpopReply = cmd("ZMPOP").arg(1).arg("zmpop-empty").arg("MIN").query(&mut con).unwrap();
+        assert_eq!(result, None);
+
+        let result: RedisResult<MpopReply> = cmd("ZMPOP").arg(0).arg("zmpop-key").arg("MIN").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("numkeys"));
+        let result: RedisResult<MpopReply> = cmd("ZMPOP").arg(1).arg("zmpop-key").arg("MIDDLE").query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bzpopmin_immediate_and_timeout() {
+        let mut con = setup();
+        fill(&mut con, "bzpop-ready");
+        let _: () = con.del("bzpop-empty").unwrap();
+
+        let result: Option<(String, String, f64)> = cmd("BZPOPMIN").arg("bzpop-empty").arg("bzpop-ready").arg(1).query(&mut con).unwrap();
+        assert_eq!(result, Some(("bzpop-ready".to_string(), "a".to_string(), 1.0)));
+        let result: Option<(String, String, f64)> = cmd("BZPOPMAX").arg("bzpop-ready").arg(1).query(&mut con).unwrap();
+        assert_eq!(result, Some(("bzpop-ready".to_string(), "c".to_string(), 3.0)));
+
+        let start = Instant::now();
+        let result: Option<(String, String, f64)> = cmd("BZPOPMIN").arg("bzpop-empty").arg(0.2).query(&mut con).unwrap();
+        assert_eq!(result, None);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let result: RedisResult<Option<(String, String, f64)>> = cmd("BZPOPMIN").arg("bzpop-empty").arg(-1).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("timeout is negative"));
+
+        // 超时时间过大时返回错误，连接仍然可用
+        let result: RedisResult<Option<(String, String, f64)>> = cmd("BZPOPMIN").arg("bzpop-empty").arg("1e300").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("timeout is out of range"));
+        let result: RedisResult<Option<(String, String)>> = cmd("BLPOP").arg("bzpop-empty").arg("1e300").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("timeout is out of range"));
+        let result: RedisResult<Option<(String, String)>> = cmd("BLPOP").arg("bzpop-empty").arg("inf").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("timeout is not a float or out of range"));
+        let pong: String = cmd("PING").query(&mut con).unwrap();
+        assert_eq!(pong, "PONG");
+    }
+
+    #[test]
+    fn test_bzpopmin_wakeup() {
+        let mut con = setup();
+        let _: () = con.del("bzpop-wakeup").unwrap();
+
+        let writer = thread::spawn(|| {
+            thread::sleep(Duration::from_millis(200));
+            let mut con = setup();
+            let _: i64 = con.zadd_multiple("bzpop-wakeup", &[(5.0, "five"), (1.0, "one")]).unwrap();
+        });
+
+        // 返回写入后分数最小的成员
+        let result: Option<(String, String, f64)> = cmd("BZPOPMIN").arg("bzpop-wakeup").arg(5).query(&mut con).unwrap();
+        writer.join().unwrap();
+        assert_eq!(result, Some(("bzpop-wakeup".to_string(), "one".to_string(), 1.0)));
+        let remaining: Vec<String> = con.zrange("bzpop-wakeup", 0, -1).unwrap();
+        assert_eq!(remaining, vec!["five"]);
+    }
+
+    #[test]
+    fn test_bzpop_serves_waiters_in_order() {
+        let mut con = setup();
+        let _: () = con.del("bzpop-fifo").unwrap();
+
+        // 两个客户端先后阻塞，一次写入两个成员后各弹出一个
+        let first = thread::spawn(|| {
+            let mut con = setup();
+            let result: Option<(String, String, f64)> = cmd("BZPOPMIN").arg("bzpop-fifo").arg(5).query(&mut con).unwrap();
+            result
+        });
+        thread::sleep(Duration::from_millis(100));
+        let second = thread::spawn(|| {
+            let mut con = setup();
+            let result: MpopReply = cmd("BZMPOP").arg(5).arg(1).arg("bzpop-fifo").arg("MAX").arg("COUNT").arg(10).query(&mut con).unwrap();
+            result
+        });
+        thread::sleep(Duration::from_millis(100));
+        let _: i64 = con.zadd_multiple("bzpop-fifo", &[(1.0, "one"), (2.0, "two"), (3.0, "three")]).unwrap();
+
+        assert_eq!(first.join().unwrap(), Some(("bzpop-fifo".to_string(), "one".to_string(), 1.0)));
+        assert_eq!(second.join().unwrap(), Some(("bzpop-fifo".to_string(), vec![("three".to_string(), 3.0), ("two".to_string(), 2.0)])));
+        let exists: bool = con.exists("bzpop-fifo").unwrap();
+        assert!(!exists);
+    }
+}
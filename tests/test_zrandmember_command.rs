@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use redis::{Client, Commands, Connection};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zrandmember() {
+        let mut con = setup();
+        let _: () = con.del("zrandmember-key").unwrap();
+        let _: i64 = con.zadd_multiple("zrandmember-key", &[(1.0, "a"), (2.0, "b"), (3.0, "c"), (4.0, "d")]).unwrap();
+        let all: HashSet<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+
+        let member: String = redis::cmd("ZRANDMEMBER").arg("zrandmember-key").query(&mut con).unwrap();
+        assert!(all.contains(&member));
+
+        // 正数返回不重复的成员
+        let members: Vec<String> = redis::cmd("ZRANDMEMBER").arg("zrandmember-key").arg(3).query(&mut con).unwrap();
+        assert_eq!(members.iter().collect::<HashSet<_>>().len(), 3);
+        let members: Vec<String> = redis::cmd("ZRANDMEMBER").arg("zrandmember-key").arg(10).query(&mut con).unwrap();
+        assert_eq!(members.into_iter().collect::<HashSet<_>>(), all);
+
+        // 负数允许重复
+        let members: Vec<String> = redis::cmd("ZRANDMEMBER").arg("zrandmember-key").arg(-10).query(&mut con).unwrap();
+        assert_eq!(members.len(), 10);
+        assert!(members.iter().all(|member| all.contains(member)));
+
+        let pairs: Vec<(String, f64)> = redis::cmd("ZRANDMEMBER").arg("zrandmember-key").arg(2).arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(pairs.len(), 2);
+        for (member, score) in pairs {
+            let expected: f64 = con.zscore("zrandmember-key", &member).unwrap();
+            assert_eq!(score, expected);
+        }
+
+        let member: Option<String> = redis::cmd("ZRANDMEMBER").arg("zrandmember-missing").query(&mut con).unwrap();
+        assert_eq!(member, None);
+    }
+
+    #[test]
+    fn test_zscan() {
+        let mut con = setup();
+        let _: () = con.del("zscan-key").unwrap();
+        let members: Vec<(f64, String)> = (0..25).map(|i| (i as f64, format!("member{}", i))).collect();
+        let _: i64 = con.zadd_multiple("zscan-key", &members).unwrap();
+
+        // 遍历所有成员
+        let mut cursor = 0;
+        let mut seen = Vec::new();
+        loop {
+            let (next, items): (u64, Vec<(String, f64)>) = redis::cmd("ZSCAN").arg("zscan-key").arg(cursor).arg("COUNT").arg(10).query(&mut con).unwrap();
+            seen.extend(items);
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        assert_eq!(seen.len(), 25);
+        assert_eq!(seen[3], ("member3".to_string(), 3.0));
+
+        let (cursor, items): (u64, Vec<(String, f64)>) = redis::cmd("ZSCAN").arg("zscan-key").arg(0).arg("MATCH").arg("member2*").arg("COUNT").arg(100).query(&mut con).unwrap();
+        assert_eq!(cursor, 0);
+        assert_eq!(items.len(), 6);
+    }
+}
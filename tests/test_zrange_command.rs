@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use redis::{Client, Commands, Connection};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// 排行榜：player1 ~ player10，分数为 10 ~ 100
+    fn leaderboard(con: &mut Connection, key: &str) {
+        let _: () = con.del(key).unwrap();
+        let members: Vec<(f64, String)> = (1..=10).map(|i| ((i * 10) as f64, format!("player{}", i))).collect();
+        let _: i64 = con.zadd_multiple(key, &members).unwrap();
+    }
+
+    #[test]
+    fn test_zrange_by_index() {
+        let mut con = setup();
+        leaderboard(&mut con, "zrange-index");
+
+        let result: Vec<String> = redis::cmd("ZRANGE").arg("zrange-index").arg(0).arg(2).query(&mut con).unwrap();
+        assert_eq!(result, vec!["player1", "player2", "player3"]);
+        let result: Vec<String> = redis::cmd("ZRANGE").arg("zrange-index").arg(-2).arg(-1).query(&mut con).unwrap();
+        assert_eq!(result, vec!["player9", "player10"]);
+        let result: Vec<String> = redis::cmd("ZRANGE").arg("zrange-index").arg(5).arg(1).query(&mut con).unwrap();
+        assert!(result.is_empty());
+
+        // 排行榜前三名
+        let result: Vec<(String, f64)> = redis::cmd("ZRANGE").arg("zrange-index").arg(0).arg(2).arg("REV").arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(result, vec![("player10".to_string(), 100.0), ("player9".to_string(), 90.0), ("player8".to_string(), 80.0)]);
+        let result: Vec<String> = redis::cmd("ZREVRANGE").arg("zrange-index").arg(0).arg(2).query(&mut con).unwrap();
+        assert_eq!(result, vec!["player10", "player9", "player8"]);
+
+        let result: Vec<String> = redis::cmd("ZRANGE").arg("zrange-missing").arg(0).arg(-1).query(&mut con).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_zrange_by_score() {
+        let mut con = setup();
+        leaderboard(&mut con, "zrange-score");
+
+        let result: Vec<String> = redis::cmd("ZRANGE").arg("zrange-score").arg(30).arg("(60").arg("BYSCORE").query(&mut con).unwrap();
+        assert_eq!(result, vec!["player3", "player4", "player5"]);
+        let result: Vec<String> = redis::cmd("ZRANGEBYSCORE").arg("zrange-score").arg("-inf").arg("+inf").arg("LIMIT").arg(8).arg(5).query(&mut con).unwrap();
+        assert_eq!(result, vec!["player9", "player10"]);
+
+        // 分页：每页 3 名，按分数从高到低
+        let page = |con: &mut Connection, offset: i64| -> Vec<String> {
+            redis::cmd("ZRANGE").arg("zrange-score").arg("+inf").arg("-inf").arg("BYSCORE").arg("REV")
+                .arg("LIMIT").arg(offset).arg(3).query(con).unwrap()
+        };
+        assert_eq!(page(&mut con, 0), vec!["player10", "player9", "player8"]);
+        assert_eq!(page(&mut con, 3), vec!["player7", "player6", "player5"]);
+        assert_eq!(page(&mut con, 9), vec!["player1"]);
+        assert!(page(&mut con, 10).is_empty());
+
+        let result: Vec<(String, f64)> = redis::cmd("ZREVRANGEBYSCORE").arg("zrange-score").arg(50).arg("(30").arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(result, vec![("player5".to_string(), 50.0), ("player4".to_string(), 40.0)]);
+
+        let result: redis::RedisResult<Vec<String>> = redis::cmd("ZRANGEBYSCORE").arg("zrange-score").arg("abc").arg(10).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("not a float"));
+        let result: redis::RedisResult<Vec<String>> = redis::cmd("ZRANGE").arg("zrange-score").arg(0).arg(1).arg("LIMIT").arg(0).arg(1).query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_zrange_by_lex() {
+        let mut con = setup();
+        let _: () = con.del("zrange-lex").unwrap();
+        let _: i64 = con.zadd_multiple("zrange-lex", &[(0.0, "a"), (0.0, "b"), (0.0, "c"), (0.0, "d"), (0.0, "e")]).unwrap();
+
+        let result: Vec<String> = redis::cmd("ZRANGE").arg("zrange-lex").arg("[b").arg("(e").arg("BYLEX").query(&mut con).unwrap();
+        assert_eq!(result, vec!["b", "c", "d"]);
+        let result: Vec<String> = redis::cmd("ZRANGEBYLEX").arg("zrange-lex").arg("-").arg("+").arg("LIMIT").arg(1).arg(2).query(&mut con).unwrap();
+        assert_eq!(result, vec!["b", "c"]);
+        let result: Vec<String> = redis::cmd("ZREVRANGEBYLEX").arg("zrange-lex").arg("+").arg("(c").query(&mut con).unwrap();
+        assert_eq!(result, vec!["e", "d"]);
+
+        let result: redis::RedisResult<Vec<String>> = redis::cmd("ZRANGEBYLEX").arg("zrange-lex").arg("b").arg("+").query(&mut con);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_zrevrank() {
+        let mut con = setup();
+        leaderboard(&mut con, "zrange-rank");
+
+        let rank: i64 = redis::cmd("ZREVRANK").arg("zrange-rank").arg("player10").query(&mut con).unwrap();
+        assert_eq!(rank, 0);
+        let rank: (i64, f64) = redis::cmd("ZREVRANK").arg("zrange-rank").arg("player3").arg("WITHSCORE").query(&mut con).unwrap();
+        assert_eq!(rank, (7, 30.0));
+        let rank: (i64, f64) = redis::cmd("ZRANK").arg("zrange-rank").arg("player3").arg("WITHSCORE").query(&mut con).unwrap();
+        assert_eq!(rank, (2, 30.0));
+        let rank: Option<i64> = redis::cmd("ZREVRANK").arg("zrange-rank").arg("nobody").query(&mut con).unwrap();
+        assert_eq!(rank, None);
+    }
+
+    #[test]
+    fn test_zrangestore() {
+        let mut con = setup();
+        leaderboard(&mut con, "zrange-store-src");
+        let _: () = con.del("zrange-store-dst").unwrap();
+
+        let count: i64 = redis::cmd("ZRANGESTORE").arg("zrange-store-dst").arg("zrange-store-src").arg(100).arg(80).arg("BYSCORE").arg("REV").query(&mut con).unwrap();
+        assert_eq!(count, 3);
+        let result: Vec<(String, f64)> = con.zrange_withscores("zrange-store-dst", 0, -1).unwrap();
+        assert_eq!(result, vec![("player8".to_string(), 80.0), ("player9".to_string(), 90.0), ("player10".to_string(), 100.0)]);
+
+        // 结果为空时删除目标键
+        let count: i64 = redis::cmd("ZRANGESTORE").arg("zrange-store-dst").arg("zrange-store-src").arg(500).arg(600).arg("BYSCORE").query(&mut con).unwrap();
+        assert_eq!(count, 0);
+        let exists: bool = con.exists("zrange-store-dst").unwrap();
+        assert!(!exists);
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use redis::{Client, Commands, Connection};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    fn fill(con: &mut Connection, key: &str) {
+        let _: () = con.del(key).unwrap();
+        let _: i64 = con.zadd_multiple(key, &[(1.0, "a"), (2.0, "b"), (3.0, "c"), (4.0, "d"), (5.0, "e")]).unwrap();
+    }
+
+    #[test]
+    fn test_zremrangebyscore() {
+        let mut con = setup();
+        fill(&mut con, "zremrange-score");
+
+        let removed: i64 = redis::cmd("ZREMRANGEBYSCORE").arg("zremrange-score").arg("(1").arg(3).query(&mut con).unwrap();
+        assert_eq!(removed, 2);
+        let members: Vec<String> = con.zrange("zremrange-score", 0, -1).unwrap();
+        assert_eq!(members, vec!["a", "d", "e"]);
+
+        // 删除全部成员后键不再存在
+        let removed: i64 = redis::cmd("ZREMRANGEBYSCORE").arg("zremrange-score").arg("-inf").arg("+inf").query(&mut con).unwrap();
+        assert_eq!(removed, 3);
+        let exists: bool = con.exists("zremrange-score").unwrap();
+        assert!(!exists);
+    }
+
+    #[test]
+    fn test_zremrangebyrank() {
+        let mut con = setup();
+        fill(&mut con, "zremrange-rank");
+
+        // 只保留分数最高的两名
+        let removed: i64 = redis::cmd("ZREMRANGEBYRANK").arg("zremrange-rank").arg(0).arg(-3).query(&mut con).unwrap();
+        assert_eq!(removed, 3);
+        let members: Vec<String> = con.zrange("zremrange-rank", 0, -1).unwrap();
+        assert_eq!(members, vec!["d", "e"]);
+
+        let removed: i64 = redis::cmd("ZREMRANGEBYRANK").arg("zremrange-rank").arg(5).arg(10).query(&mut con).unwrap();
+        assert_eq!(removed, 0);
+    }
+
+    #[test]
+    fn test_zremrangebylex() {
+        let mut con = setup();
+        let _: () = con.del("zremrange-lex").unwrap();
+        let _: i64 = con.zadd_multiple("zremrange-lex", &[(0.0, "a"), (0.0, "b"), (0.0, "c"), (0.0, "d")]).unwrap();
+
+        let removed: i64 = redis::cmd("ZREMRANGEBYLEX").arg("zremrange-lex").arg("[b").arg("+").query(&mut con).unwrap();
+        assert_eq!(removed, 3);
+        let members: Vec<String> = con.zrange("zremrange-lex", 0, -1).unwrap();
+        assert_eq!(members, vec!["a"]);
+    }
+
+    #[test]
+    fn test_zmscore() {
+        let mut con = setup();
+        fill(&mut con, "zremrange-mscore");
+
+        let scores: Vec<Option<f64>> = redis::cmd("ZMSCORE").arg("zremrange-mscore").arg("a").arg("x").arg("e").query(&mut con).unwrap();
+        assert_eq!(scores, vec![Some(1.0), None, Some(5.0)]);
+        let scores: Vec<Option<f64>> = redis::cmd("ZMSCORE").arg("zremrange-missing").arg("a").query(&mut con).unwrap();
+        assert_eq!(scores, vec![None]);
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use redis::{cmd, Client, Commands, Connection, RedisResult};
+
+    fn setup() -> Connection {
+        let client = Client::open("redis://127.0.0.1:6379/").unwrap();
+        match client.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to get connection: {}", e);
+                panic!("Failed to get connection: {}", e);
+            }
+        }
+    }
+
+    /// zalgebra-a: a=1 b=2 c=3，zalgebra-b: b=10 c=20 d=30
+    fn fill(con: &mut Connection, prefix: &str) -> (String, String) {
+        let (a, b) = (format!("{}-a", prefix), format!("{}-b", prefix));
+        let _: () = con.del(&[&a, &b]).unwrap();
+        let _: i64 = con.zadd_multiple(&a, &[(1.0, "a"), (2.0, "b"), (3.0, "c")]).unwrap();
+        let _: i64 = con.zadd_multiple(&b, &[(10.0, "b"), (20.0, "c"), (30.0, "d")]).unwrap();
+        (a, b)
+    }
+
+    #[test]
+    fn test_zunion() {
+        let mut con = setup();
+        let (a, b) = fill(&mut con, "zunion");
+
+        let result: Vec<(String, f64)> = cmd("ZUNION").arg(2).arg(&a).arg(&b).arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(result, vec![("a".to_string(), 1.0), ("b".to_string(), 12.0), ("c".to_string(), 23.0), ("d".to_string(), 30.0)]);
+
+        let result: Vec<(String, f64)> = cmd("ZUNION").arg(2).arg(&a).arg(&b).arg("WEIGHTS").arg(10).arg(1).arg("AGGREGATE").arg("MAX").arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(result, vec![("a".to_string(), 10.0), ("b".to_string(), 20.0), ("c".to_string(), 30.0), ("d".to_string(), 30.0)]);
+
+        let _: () = con.del("zunion-dst").unwrap();
+        let count: i64 = cmd("ZUNIONSTORE").arg("zunion-dst").arg(3).arg(&a).arg(&b).arg("zunion-missing").arg("AGGREGATE").arg("MIN").query(&mut con).unwrap();
+        assert_eq!(count, 4);
+        let result: Vec<(String, f64)> = con.zrange_withscores("zunion-dst", 0, -1).unwrap();
+        assert_eq!(result, vec![("a".to_string(), 1.0), ("b".to_string(), 2.0), ("c".to_string(), 3.0), ("d".to_string(), 30.0)]);
+    }
+
+    #[test]
+    fn test_zinter() {
+        let mut con = setup();
+        let (a, b) = fill(&mut con, "zinter");
+
+        let result: Vec<(String, f64)> = cmd("ZINTER").arg(2).arg(&a).arg(&b).arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(result, vec![("b".to_string(), 12.0), ("c".to_string(), 23.0)]);
+
+        let _: () = con.del("zinter-dst").unwrap();
+        let count: i64 = cmd("ZINTERSTORE").arg("zinter-dst").arg(2).arg(&a).arg(&b).arg("WEIGHTS").arg(2).arg(0.5).query(&mut con).unwrap();
+        assert_eq!(count, 2);
+        let score: f64 = con.zscore("zinter-dst", "c").unwrap();
+        assert_eq!(score, 16.0);
+
+        // 普通集合的成员分数视为 1
+        let _: () = con.del("zinter-set").unwrap();
+        let _: i64 = con.sadd("zinter-set", &["a", "c"]).unwrap();
+        let result: Vec<(String, f64)> = cmd("ZINTER").arg(2).arg(&a).arg("zinter-set").arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(result, vec![("a".to_string(), 2.0), ("c".to_string(), 4.0)]);
+
+        let count: i64 = cmd("ZINTERCARD").arg(2).arg(&a).arg(&b).query(&mut con).unwrap();
+        assert_eq!(count, 2);
+        let count: i64 = cmd("ZINTERCARD").arg(2).arg(&a).arg(&b).arg("LIMIT").arg(1).query(&mut con).unwrap();
+        assert_eq!(count, 1);
+
+        // 结果为空时删除目标键
+        let count: i64 = cmd("ZINTERSTORE").arg("zinter-dst").arg(2).arg(&a).arg("zinter-missing").query(&mut con).unwrap();
+        assert_eq!(count, 0);
+        let exists: bool = con.exists("zinter-dst").unwrap();
+        assert!(!exists);
+    }
+
+    #[test]
+    fn test_zdiff() {
+        let mut con = setup();
+        let (a, b) = fill(&mut con, "zdiff");
+
+        let result: Vec<(String, f64)> = cmd("ZDIFF").arg(2).arg(&a).arg(&b).arg("WITHSCORES").query(&mut con).unwrap();
+        assert_eq!(result, vec![("a".to_string(), 1.0)]);
+        let result: Vec<String> = cmd("ZDIFF").arg(2).arg(&b).arg(&a).query(&mut con).unwrap();
+        assert_eq!(result, vec!["d"]);
+
+        let count: i64 = cmd("ZDIFFSTORE").arg("zdiff-dst").arg(1).arg(&a).query(&mut con).unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_zunion_errors() {
+        let mut con = setup();
+        let (a, b) = fill(&mut con, "zunion-errors");
+        let _: () = con.set("zunion-errors-string", "value").unwrap();
+
+        let result: RedisResult<Vec<String>> = cmd("ZUNION").arg(0).arg(&a).query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("at least 1 input key"));
+        let result: RedisResult<Vec<String>> = cmd("ZUNION").arg(3).arg(&a).arg(&b).query(&mut con);
+        assert!(result.is_err());
+        let result: RedisResult<Vec<String>> = cmd("ZINTER").arg(2).arg(&a).arg(&b).arg("WEIGHTS").arg(1).arg("x").query(&mut con);
+        assert!(result.unwrap_err().to_string().contains("weight value is not a float"));
+        let result: RedisResult<Vec<String>> = cmd("ZDIFF").arg(2).arg(&a).arg(&b).arg("AGGREGATE").arg("SUM").query(&mut con);
+        assert!(result.is_err());
+        let result: RedisResult<Vec<String>> = cmd("ZUNION").arg(2).arg(&a).arg("zunion-errors-string").query(&mut con);
+        assert!(result.is_err());
+    }
+}